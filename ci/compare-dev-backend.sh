@@ -0,0 +1,47 @@
+#!/usr/bin/env bash
+
+# Compares the code two roc compilers generate with `--dev` for the benchmarks: the size of
+# every executable, and its run time with hyperfine. Use it to measure changes to the dev
+# backend, for example register allocation, by building roc before and after the change:
+#
+#   ci/compare-dev-backend.sh path/to/old/roc path/to/new/roc
+#
+# The inputs are the ones `crates/cli_utils/src/bench_utils.rs` uses.
+
+set -euo pipefail
+
+if [ $# -ne 2 ]; then
+    echo "usage: $0 OLD_ROC NEW_ROC" >&2
+    exit 1
+fi
+
+old_roc=$(realpath "$1")
+new_roc=$(realpath "$2")
+out_dir=$(mktemp -d)
+
+# roc file, executable, input
+benchmarks=(
+    "NQueens nqueens 11"
+    "CFold cfold 17"
+    "Deriv deriv 8"
+    "RBTreeCk rbtree-ck 80000"
+    "QuicksortApp quicksortapp 1"
+)
+
+for benchmark in "${benchmarks[@]}"; do
+    read -r roc_file executable input <<< "$benchmark"
+
+    "$old_roc" build --dev "examples/benchmarks/$roc_file.roc"
+    mv "examples/benchmarks/$executable" "$out_dir/$executable-old"
+
+    "$new_roc" build --dev "examples/benchmarks/$roc_file.roc"
+    mv "examples/benchmarks/$executable" "$out_dir/$executable-new"
+
+    size "$out_dir/$executable-old" "$out_dir/$executable-new"
+
+    hyperfine --warmup 3 \
+        --command-name "$executable (old)" "echo $input | $out_dir/$executable-old" \
+        --command-name "$executable (new)" "echo $input | $out_dir/$executable-new"
+done
+
+rm -r "$out_dir"
@@ -307,6 +307,11 @@ pub fn expr_to_expr2<'a>(
             Output::default(),
         ),
 
+        Tuple(..) | TupleAccess(..) | TupleAccessorFunction(..) => {
+            // the editor does not support tuples yet
+            (Expr2::RuntimeError(), Output::default())
+        }
+
         If(branches, final_else) => {
             let mut new_branches = Vec::with_capacity(branches.len());
             let mut output = Output::default();
@@ -274,6 +274,10 @@ pub fn to_pattern2<'a>(
 
         OpaqueRef(..) => internal_error!("opaques not implemented"),
 
+        Tuple(..) => unsupported_pattern(env, pattern_type, region),
+
+        List(..) | ListRest(..) => internal_error!("list patterns not implemented"),
+
         Apply(tag, patterns) => {
             let can_patterns = PoolVec::with_capacity(patterns.len() as u32, env.pool);
             for (loc_pattern, node_id) in (*patterns).iter().zip(can_patterns.iter_node_ids()) {
@@ -458,6 +458,10 @@ pub fn to_type2<'a>(
 
             Type2::TagUnion(tag_types, ext_type)
         }
+        Tuple { .. } => {
+            // the editor does not support tuples yet
+            Type2::Erroneous(Problem2::CanonicalizationProblem)
+        }
         As(
             loc_inner,
             _spaces,
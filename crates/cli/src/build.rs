@@ -1,17 +1,20 @@
 use bumpalo::Bump;
 use roc_build::{
     link::{link, preprocess_host_wasm32, rebuild_host, LinkType, LinkingStrategy},
-    program::{self, Problems},
+    program::{self, EmitArtifacts, EmitKind, Problems},
 };
 use roc_builtins::bitcode;
 use roc_collections::VecMap;
-use roc_load::{EntryPoint, ExecutionMode, Expectations, LoadConfig, LoadingProblem, Threading};
+use roc_load::{
+    EntryPoint, ExecutionMode, Expectations, LoadConfig, LoadingProblem, MonoPasses, Threading,
+};
 use roc_module::symbol::{Interns, ModuleId};
 use roc_mono::ir::OptLevel;
 use roc_reporting::report::RenderTarget;
 use roc_target::TargetInfo;
+use std::path::{Path, PathBuf};
+use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
-use std::{path::PathBuf, thread::JoinHandle};
 use target_lexicon::Triple;
 use tempfile::Builder;
 
@@ -42,12 +45,15 @@ pub fn build_file<'a>(
     app_module_path: PathBuf,
     opt_level: OptLevel,
     emit_debug_info: bool,
+    emit_llvm_ir_debug_info: bool,
     emit_timings: bool,
     link_type: LinkType,
     linking_strategy: LinkingStrategy,
     precompiled: bool,
     threading: Threading,
     wasm_dev_stack_bytes: Option<u32>,
+    wasm_dev_proposals: bool,
+    emit: Vec<EmitKind>,
 ) -> Result<BuiltFile, LoadingProblem<'a>> {
     let compilation_start = Instant::now();
     let target_info = TargetInfo::from(target);
@@ -61,6 +67,8 @@ pub fn build_file<'a>(
         render: RenderTarget::ColorTerminal,
         threading,
         exec_mode: ExecutionMode::Executable,
+        type_cache_dir: roc_load::default_type_cache_dir(),
+        mono_passes: MonoPasses::for_opt_level(opt_level),
     };
     let loaded = roc_load::load_and_monomorphize(
         arena,
@@ -101,6 +109,21 @@ pub fn build_file<'a>(
         binary_path.set_extension(extension);
     }
 
+    let emit = {
+        let (available, unavailable): (Vec<_>, Vec<_>) = emit
+            .into_iter()
+            .partition(|kind| kind.is_available(opt_level, target));
+
+        for kind in unavailable {
+            eprintln!(
+                "Warning: --emit={} is not available for this build, so I'm skipping it.",
+                kind.name()
+            );
+        }
+
+        EmitArtifacts::new(available, binary_path.clone())
+    };
+
     let host_input_path = if let EntryPoint::Executable { platform_path, .. } = &loaded.entry_point
     {
         cwd.join(platform_path)
@@ -244,8 +267,11 @@ pub fn build_file<'a>(
         app_o_file,
         opt_level,
         emit_debug_info,
+        emit_llvm_ir_debug_info,
+        &emit,
         &preprocessed_host_path,
         wasm_dev_stack_bytes,
+        wasm_dev_proposals,
     );
 
     buf.push('\n');
@@ -271,6 +297,17 @@ pub fn build_file<'a>(
         })
         .len();
 
+    // The app file is a temporary file, so keep a copy of it
+    if emit.contains(EmitKind::Obj) {
+        let obj_path = emit.path(app_extension);
+        std::fs::copy(app_o_file, &obj_path).unwrap_or_else(|err| {
+            panic!(
+                "Could not copy {:?} to {:?}: {:?}",
+                app_o_file, obj_path, err
+            );
+        });
+    }
+
     if emit_timings {
         println!(
             "\n\nCompilation finished!\n\nHere's how long each module took to compile:\n\n{}",
@@ -305,6 +342,7 @@ pub fn build_file<'a>(
             // Just copy the object file to the output folder.
             binary_path.set_extension(app_extension);
             std::fs::copy(app_o_file, &binary_path).unwrap();
+            copy_wasm_source_map(app_o_file, &binary_path);
             problems
         }
         (LinkingStrategy::Legacy, _) => {
@@ -364,6 +402,17 @@ pub fn build_file<'a>(
     })
 }
 
+/// With --debug, the Wasm dev backend writes a source map next to the app file.
+/// Move it next to the final binary, which is where the binary says to find it.
+fn copy_wasm_source_map(app_o_file: &Path, binary_path: &Path) {
+    let source_map_path = app_o_file.with_extension("wasm.map");
+
+    if source_map_path.exists() {
+        std::fs::copy(&source_map_path, binary_path.with_extension("wasm.map")).unwrap();
+        std::fs::remove_file(&source_map_path).unwrap();
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn spawn_rebuild_thread(
     opt_level: OptLevel,
@@ -448,6 +497,8 @@ pub fn check_file(
         render: RenderTarget::ColorTerminal,
         threading,
         exec_mode: ExecutionMode::Check,
+        type_cache_dir: roc_load::default_type_cache_dir(),
+        mono_passes: MonoPasses::default(),
     };
     let mut loaded =
         roc_load::load_and_typecheck(arena, roc_file_path, subs_by_module, load_config)?;
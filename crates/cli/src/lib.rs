@@ -5,15 +5,16 @@ use build::BuiltFile;
 use bumpalo::Bump;
 use clap::{Arg, ArgMatches, Command, ValueSource};
 use roc_build::link::{LinkType, LinkingStrategy};
+use roc_build::program::EmitKind;
 use roc_collections::VecMap;
 use roc_error_macros::{internal_error, user_error};
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
 use roc_gen_llvm::run_roc::RocCallResult;
 use roc_gen_llvm::run_roc_dylib;
-use roc_load::{ExecutionMode, Expectations, LoadConfig, LoadingProblem, Threading};
+use roc_load::{ExecutionMode, Expectations, LoadConfig, LoadingProblem, MonoPasses, Threading};
 use roc_module::symbol::{Interns, ModuleId};
 use roc_mono::ir::OptLevel;
-use roc_repl_expect::run::{expect_mono_module_to_dylib, roc_dev_expect};
+use roc_repl_expect::run::{expect_mono_module_to_dylib, init_expect_buffer, roc_dev_expect};
 use roc_target::TargetInfo;
 use std::env;
 use std::ffi::{CString, OsStr};
@@ -45,8 +46,10 @@ pub const CMD_VERSION: &str = "version";
 pub const CMD_FORMAT: &str = "format";
 pub const CMD_TEST: &str = "test";
 pub const CMD_GLUE: &str = "glue";
+pub const CMD_LSP: &str = "lsp";
 
 pub const FLAG_DEBUG: &str = "debug";
+pub const FLAG_DEBUG_LLVM_IR: &str = "debug-llvm-ir";
 pub const FLAG_DEV: &str = "dev";
 pub const FLAG_OPTIMIZE: &str = "optimize";
 pub const FLAG_MAX_THREADS: &str = "max-threads";
@@ -59,6 +62,8 @@ pub const FLAG_LINKER: &str = "linker";
 pub const FLAG_PRECOMPILED: &str = "precompiled-host";
 pub const FLAG_CHECK: &str = "check";
 pub const FLAG_WASM_STACK_SIZE_KB: &str = "wasm-stack-size-kb";
+pub const FLAG_WASM_PROPOSALS: &str = "wasm-proposals";
+pub const FLAG_EMIT: &str = "emit";
 pub const ROC_FILE: &str = "ROC_FILE";
 pub const ROC_DIR: &str = "ROC_DIR";
 pub const GLUE_FILE: &str = "GLUE_FILE";
@@ -92,7 +97,12 @@ pub fn build_app<'a>() -> Command<'a> {
 
     let flag_debug = Arg::new(FLAG_DEBUG)
         .long(FLAG_DEBUG)
-        .help("Store LLVM debug information in the generated program.")
+        .help("Store debug information that points at the Roc source in the generated program, so it can be stepped through in gdb. For Wasm dev builds, write a source map for browser devtools.")
+        .required(false);
+
+    let flag_debug_llvm_ir = Arg::new(FLAG_DEBUG_LLVM_IR)
+        .long(FLAG_DEBUG_LLVM_IR)
+        .help("Store debug information that points at the generated LLVM IR instead of the Roc source. Needs the `debugir`, `llvm-as` and `llc` tools.")
         .required(false);
 
     let flag_time = Arg::new(FLAG_TIME)
@@ -119,6 +129,21 @@ pub fn build_app<'a>() -> Command<'a> {
         .validator(|s| s.parse::<u32>())
         .required(false);
 
+    let flag_wasm_proposals = Arg::new(FLAG_WASM_PROPOSALS)
+        .long(FLAG_WASM_PROPOSALS)
+        .help("Use the WebAssembly tail-call and multi-value proposals for wasm32 target. Only applies when --dev also provided.")
+        .required(false);
+
+    let flag_emit = Arg::new(FLAG_EMIT)
+        .long(FLAG_EMIT)
+        .help("Also write these intermediate artifacts next to the output, e.g. `--emit=mono-ir,llvm-ir`. `llvm-ir` and `asm` need an LLVM build (without --dev). `wat` needs --dev and --target=wasm32.")
+        .takes_value(true)
+        .multiple_values(true)
+        .value_delimiter(',')
+        .require_equals(true)
+        .possible_values(EmitKind::NAMES)
+        .required(false);
+
     let roc_file_to_run = Arg::new(ROC_FILE)
         .help("The .roc file of an app to run")
         .allow_invalid_utf8(true)
@@ -143,10 +168,13 @@ pub fn build_app<'a>() -> Command<'a> {
             .arg(flag_opt_size.clone())
             .arg(flag_dev.clone())
             .arg(flag_debug.clone())
+            .arg(flag_debug_llvm_ir.clone())
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_precompiled.clone())
             .arg(flag_wasm_stack_size_kb.clone())
+            .arg(flag_wasm_proposals.clone())
+            .arg(flag_emit)
             .arg(
                 Arg::new(FLAG_TARGET)
                     .long(FLAG_TARGET)
@@ -182,6 +210,7 @@ pub fn build_app<'a>() -> Command<'a> {
             .arg(flag_opt_size.clone())
             .arg(flag_dev.clone())
             .arg(flag_debug.clone())
+            .arg(flag_debug_llvm_ir.clone())
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_precompiled.clone())
@@ -204,6 +233,7 @@ pub fn build_app<'a>() -> Command<'a> {
             .arg(flag_opt_size.clone())
             .arg(flag_dev.clone())
             .arg(flag_debug.clone())
+            .arg(flag_debug_llvm_ir.clone())
             .arg(flag_time.clone())
             .arg(flag_linker.clone())
             .arg(flag_precompiled.clone())
@@ -264,12 +294,16 @@ pub fn build_app<'a>() -> Command<'a> {
                     .required(true)
             )
         )
+        .subcommand(Command::new(CMD_LSP)
+            .about("Start a language server that editors can talk to over stdin and stdout")
+        )
         .trailing_var_arg(true)
         .arg(flag_optimize)
             .arg(flag_max_threads.clone())
         .arg(flag_opt_size)
         .arg(flag_dev)
         .arg(flag_debug)
+        .arg(flag_debug_llvm_ir)
         .arg(flag_time)
         .arg(flag_linker)
         .arg(flag_precompiled)
@@ -366,6 +400,8 @@ pub fn test(matches: &ArgMatches, triple: Triple) -> io::Result<i32> {
         render: roc_reporting::report::RenderTarget::ColorTerminal,
         threading,
         exec_mode: ExecutionMode::Test,
+        type_cache_dir: None,
+        mono_passes: MonoPasses::for_opt_level(opt_level),
     };
     let loaded =
         roc_load::load_and_monomorphize(arena, path.to_path_buf(), subs_by_module, load_config)
@@ -461,6 +497,7 @@ pub fn build(
         _ => user_error!("build can be only one of `--dev`, `--optimize`, or `--opt-size`"),
     };
     let emit_debug_info = matches.is_present(FLAG_DEBUG);
+    let emit_llvm_ir_debug_info = matches.is_present(FLAG_DEBUG_LLVM_IR);
     let emit_timings = matches.is_present(FLAG_TIME);
 
     let threading = match matches
@@ -521,18 +558,35 @@ pub fn build(
         .and_then(|s| s.parse::<u32>().ok())
         .map(|x| x * 1024);
 
+    let wasm_dev_proposals = matches
+        .try_contains_id(FLAG_WASM_PROPOSALS)
+        .unwrap_or(false);
+
+    // --emit is only available on `roc build`
+    let emit: Vec<EmitKind> = match matches.try_contains_id(FLAG_EMIT) {
+        Ok(true) => matches
+            .values_of(FLAG_EMIT)
+            .unwrap()
+            .filter_map(EmitKind::from_name)
+            .collect(),
+        Ok(false) | Err(_) => Vec::new(),
+    };
+
     let res_binary_path = build_file(
         &arena,
         &triple,
         path.to_path_buf(),
         opt_level,
         emit_debug_info,
+        emit_llvm_ir_debug_info,
         emit_timings,
         link_type,
         linking_strategy,
         precompiled,
         threading,
         wasm_dev_stack_bytes,
+        wasm_dev_proposals,
+        emit,
     );
 
     match res_binary_path {
@@ -932,6 +986,28 @@ unsafe fn roc_run_native_debug(
 
     let mut signals = Signals::new(&[SIGCHLD, SIGUSR1]).unwrap();
 
+    // the app looks for this buffer by our pid, so it must exist before the app starts
+    // IMPORTANT: shared memory object names must begin with / and contain no other slashes!
+    let name = format!("/roc_expect_buffer_{}", process::id());
+    let cstring = CString::new(name).unwrap();
+
+    let shared_fd = libc::shm_open(cstring.as_ptr().cast(), libc::O_RDWR | libc::O_CREAT, 0o666);
+
+    libc::ftruncate(shared_fd, SHM_SIZE);
+
+    let shared_ptr = libc::mmap(
+        std::ptr::null_mut(),
+        SHM_SIZE as usize,
+        libc::PROT_READ | libc::PROT_WRITE,
+        libc::MAP_SHARED,
+        shared_fd,
+        0,
+    );
+
+    let shared_memory_ptr: *mut u8 = shared_ptr.cast();
+
+    init_expect_buffer(shared_memory_ptr);
+
     match libc::fork() {
         0 => {
             // we are the child
@@ -961,10 +1037,7 @@ unsafe fn roc_run_native_debug(
 
             process::exit(1)
         }
-        1.. => {
-            let name = "/roc_expect_buffer"; // IMPORTANT: shared memory object names must begin with / and contain no other slashes!
-            let cstring = CString::new(name).unwrap();
-
+        pid @ 1.. => {
             let arena = &bumpalo::Bump::new();
             let interns = arena.alloc(interns);
 
@@ -978,29 +1051,15 @@ unsafe fn roc_run_native_debug(
                         process::exit(0);
                     }
                     SIGUSR1 => {
-                        // this is the signal we use for an expect failure. Let's see what the child told us
-                        let shared_fd =
-                            libc::shm_open(cstring.as_ptr().cast(), libc::O_RDONLY, 0o666);
-
-                        libc::ftruncate(shared_fd, SHM_SIZE);
-
-                        let shared_ptr = libc::mmap(
-                            std::ptr::null_mut(),
-                            SHM_SIZE as usize,
-                            libc::PROT_READ,
-                            libc::MAP_SHARED,
-                            shared_fd,
-                            0,
-                        );
-
-                        let shared_memory_ptr: *mut u8 = shared_ptr.cast();
-
+                        // this is the signal we use for a dbg. Signals that arrive together are
+                        // merged, so render every frame the child wrote since the last one
                         roc_dev_expect(
                             &mut std::io::stdout(),
                             arena,
                             &mut expectations,
                             interns,
                             shared_memory_ptr,
+                            pid as u32,
                         )
                         .unwrap();
                     }
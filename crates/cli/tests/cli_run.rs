@@ -30,6 +30,11 @@ mod cli_run {
     const PRECOMPILED_HOST: &str = concatcp!("--", roc_cli::FLAG_PRECOMPILED, "=true");
     #[allow(dead_code)]
     const TARGET_FLAG: &str = concatcp!("--", roc_cli::FLAG_TARGET);
+    const EMIT_FLAG: &str = concatcp!("--", roc_cli::FLAG_EMIT);
+    #[allow(dead_code)]
+    const DEV_FLAG: &str = concatcp!("--", roc_cli::FLAG_DEV);
+    #[allow(dead_code)]
+    const DEBUG_FLAG: &str = concatcp!("--", roc_cli::FLAG_DEBUG);
 
     use std::sync::Once;
     static BENCHMARKS_BUILD_PLATFORM: Once = Once::new();
@@ -948,6 +953,344 @@ mod cli_run {
         );
     }
 
+    /// Build the multi-dep-str fixture with `--emit=<kind>`, and check that the artifact was
+    /// written next to the executable and contains `expected`
+    fn check_emit(kind: &str, flags: &[&str], extension: &str, expected: &[u8]) {
+        let file = fixture_file("multi-dep-str", "Main.roc");
+        let emit_arg = format!("{}={}", EMIT_FLAG, kind);
+        let build_args = [CMD_BUILD, emit_arg.as_str()];
+        let args = build_args.iter().chain(flags).copied();
+        run_roc_on(&file, args, &[], None);
+
+        let artifact_path = file
+            .with_file_name("multi-dep-str")
+            .with_extension(extension);
+        let artifact = std::fs::read(&artifact_path).unwrap_or_else(|e| {
+            panic!(
+                "--emit={} did not write {}: {}",
+                kind,
+                artifact_path.display(),
+                e
+            )
+        });
+        std::fs::remove_file(&artifact_path).unwrap();
+
+        assert!(
+            artifact.windows(expected.len()).any(|w| w == expected),
+            "--emit={} output does not contain {:?}",
+            kind,
+            String::from_utf8_lossy(expected)
+        );
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    fn emit_mono_ir() {
+        check_emit("mono-ir", &[], "mono", b"procedure ");
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    fn emit_llvm_ir() {
+        check_emit("llvm-ir", &[], "ll", b"define ");
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    #[cfg(any(target_arch = "x86_64", target_arch = "x86", target_arch = "aarch64"))]
+    fn emit_asm() {
+        check_emit("asm", &[], "s", b"roc__mainForHost");
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    #[cfg(target_os = "linux")]
+    fn emit_obj() {
+        check_emit("obj", &[], "o", b"\x7fELF");
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    #[cfg(feature = "wasm32-cli-run")]
+    fn emit_wat() {
+        check_emit(
+            "wat",
+            &[DEV_FLAG, concatcp!(TARGET_FLAG, "=wasm32")],
+            "wat",
+            b"(module",
+        );
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    #[cfg(target_os = "linux")]
+    fn dbg_in_dev_mode() {
+        let out = run_roc_on(
+            &fixture_file("dbg", "Main.roc"),
+            [CMD_RUN, DEV_FLAG],
+            &[],
+            None,
+        );
+
+        assert!(out.status.success(), "{}", out.stderr);
+
+        let stdout = strip_colors(&out.stdout);
+        let position = |needle: &str| {
+            stdout
+                .find(needle)
+                .unwrap_or_else(|| panic!("{:?} is not in the output:\n{}", needle, stdout))
+        };
+
+        // each dbg is rendered once, in the order the program runs them
+        assert!(position("21 : I64") < position("\"Hello, dbg!\" : Str"));
+        assert_eq!(stdout.matches("21 : I64").count(), 1);
+        assert!(stdout.contains("Hello, dbg!21"));
+    }
+
+    #[test]
+    #[serial(multi_dep_str)]
+    #[cfg(feature = "wasm32-cli-run")]
+    fn wasm_source_map() {
+        // Where the body of `greet` starts in Main.roc. Source maps count lines and columns from 0.
+        const GREET_LINE: i64 = 10;
+        const GREET_COLUMN: i64 = 4;
+
+        let file = fixture_file("wasm-source-map", "Main.roc");
+        let out = run_roc_on(
+            &file,
+            [
+                CMD_BUILD,
+                DEV_FLAG,
+                DEBUG_FLAG,
+                concatcp!(TARGET_FLAG, "=wasm32"),
+            ],
+            &[],
+            None,
+        );
+        assert!(out.status.success(), "{}", out.stderr);
+
+        let wasm_path = file.with_file_name("wasm-source-map.wasm");
+        let map_path = file.with_file_name("wasm-source-map.wasm.map");
+        let wasm_bytes = std::fs::read(&wasm_path).unwrap();
+        let source_map = std::fs::read_to_string(&map_path).unwrap();
+        std::fs::remove_file(&wasm_path).unwrap();
+        std::fs::remove_file(&map_path).unwrap();
+
+        let wasm = wasm_debug::parse(&wasm_bytes);
+        assert_eq!(
+            wasm.source_mapping_url.as_deref(),
+            Some("wasm-source-map.wasm.map")
+        );
+
+        // The name section lists `greet`
+        let greet_index = wasm
+            .function_names
+            .iter()
+            .find(|(_, name)| name.starts_with("UserApp.greet#"))
+            .map(|(index, _)| *index)
+            .unwrap_or_else(|| {
+                panic!(
+                    "greet is not in the name section: {:?}",
+                    wasm.function_names
+                )
+            });
+        let greet_body = &wasm.fn_bodies[(greet_index - wasm.imported_fns) as usize];
+
+        let sources = wasm_debug::json_string_list(&source_map, "sources");
+        let main_source = sources
+            .iter()
+            .position(|path| path.ends_with("Main.roc"))
+            .unwrap_or_else(|| panic!("Main.roc is not in the sources: {:?}", sources))
+            as i64;
+
+        // The first mapping in greet's code is the start of its body
+        let mappings =
+            wasm_debug::decode_mappings(&wasm_debug::json_string(&source_map, "mappings"));
+        let [_, source, line, column] = *mappings
+            .iter()
+            .find(|[offset, ..]| greet_body.contains(&(*offset as usize)))
+            .unwrap_or_else(|| panic!("no mappings in greet's code at {:?}", greet_body));
+
+        assert_eq!(
+            (source, line, column),
+            (main_source, GREET_LINE, GREET_COLUMN)
+        );
+    }
+
+    /// Just enough of a Wasm binary and source map reader to check the debug info from `--debug`
+    #[cfg(feature = "wasm32-cli-run")]
+    mod wasm_debug {
+        use std::ops::Range;
+
+        const BASE64_CHARS: &[u8; 64] =
+            b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+        #[derive(Default)]
+        pub struct WasmDebugInfo {
+            /// Imported functions come first in the function index space
+            pub imported_fns: u32,
+            /// Byte range of each function in the Code section, including its size prefix
+            pub fn_bodies: Vec<Range<usize>>,
+            pub function_names: Vec<(u32, String)>,
+            pub source_mapping_url: Option<String>,
+        }
+
+        fn read_u32(bytes: &[u8], cursor: &mut usize) -> u32 {
+            let mut value = 0;
+            let mut shift = 0;
+            loop {
+                let byte = bytes[*cursor];
+                *cursor += 1;
+                value |= ((byte & 0x7f) as u32) << shift;
+                if byte & 0x80 == 0 {
+                    return value;
+                }
+                shift += 7;
+            }
+        }
+
+        fn read_name(bytes: &[u8], cursor: &mut usize) -> String {
+            let len = read_u32(bytes, cursor) as usize;
+            let name = String::from_utf8(bytes[*cursor..][..len].to_vec()).unwrap();
+            *cursor += len;
+            name
+        }
+
+        fn skip_limits(bytes: &[u8], cursor: &mut usize) {
+            let has_max = bytes[*cursor] & 1 == 1;
+            *cursor += 1;
+            read_u32(bytes, cursor);
+            if has_max {
+                read_u32(bytes, cursor);
+            }
+        }
+
+        pub fn parse(bytes: &[u8]) -> WasmDebugInfo {
+            const CUSTOM: u8 = 0;
+            const IMPORT: u8 = 2;
+            const CODE: u8 = 10;
+            const FUNCTION_NAMES: u8 = 1;
+
+            let mut info = WasmDebugInfo::default();
+
+            // Skip the magic number and version
+            let mut cursor = 8;
+            while cursor < bytes.len() {
+                let id = bytes[cursor];
+                cursor += 1;
+                let size = read_u32(bytes, &mut cursor) as usize;
+                let end = cursor + size;
+
+                match id {
+                    IMPORT => {
+                        for _ in 0..read_u32(bytes, &mut cursor) {
+                            read_name(bytes, &mut cursor);
+                            read_name(bytes, &mut cursor);
+                            let kind = bytes[cursor];
+                            cursor += 1;
+                            match kind {
+                                0 => {
+                                    info.imported_fns += 1;
+                                    read_u32(bytes, &mut cursor);
+                                }
+                                1 => {
+                                    cursor += 1;
+                                    skip_limits(bytes, &mut cursor);
+                                }
+                                2 => skip_limits(bytes, &mut cursor),
+                                3 => cursor += 2,
+                                _ => panic!("unknown import kind {}", kind),
+                            }
+                        }
+                    }
+                    CODE => {
+                        for _ in 0..read_u32(bytes, &mut cursor) {
+                            let start = cursor;
+                            let body_size = read_u32(bytes, &mut cursor) as usize;
+                            cursor += body_size;
+                            info.fn_bodies.push(start..cursor);
+                        }
+                    }
+                    CUSTOM => match read_name(bytes, &mut cursor).as_str() {
+                        "name" => {
+                            while cursor < end {
+                                let subsection_id = bytes[cursor];
+                                cursor += 1;
+                                let subsection_size = read_u32(bytes, &mut cursor) as usize;
+                                let subsection_end = cursor + subsection_size;
+                                if subsection_id == FUNCTION_NAMES {
+                                    for _ in 0..read_u32(bytes, &mut cursor) {
+                                        let index = read_u32(bytes, &mut cursor);
+                                        let name = read_name(bytes, &mut cursor);
+                                        info.function_names.push((index, name));
+                                    }
+                                }
+                                cursor = subsection_end;
+                            }
+                        }
+                        "sourceMappingURL" => {
+                            info.source_mapping_url = Some(read_name(bytes, &mut cursor));
+                        }
+                        _ => {}
+                    },
+                    _ => {}
+                }
+
+                cursor = end;
+            }
+
+            info
+        }
+
+        /// The value of a string field in the source map JSON. Our paths and mappings have no escapes.
+        pub fn json_string(json: &str, key: &str) -> String {
+            let pattern = format!("\"{}\":\"", key);
+            let start = json.find(&pattern).unwrap() + pattern.len();
+            let len = json[start..].find('"').unwrap();
+            json[start..][..len].to_string()
+        }
+
+        pub fn json_string_list(json: &str, key: &str) -> Vec<String> {
+            let pattern = format!("\"{}\":[", key);
+            let start = json.find(&pattern).unwrap() + pattern.len();
+            let len = json[start..].find(']').unwrap();
+            json[start..][..len]
+                .split(',')
+                .map(|s| s.trim_matches('"').to_string())
+                .collect()
+        }
+
+        /// Decode the mappings into absolute [wasm offset, source index, line, column]
+        pub fn decode_mappings(mappings: &str) -> Vec<[i64; 4]> {
+            let mut fields = [0; 4];
+            let mut chars = mappings.bytes().peekable();
+            let mut decoded = Vec::new();
+
+            while chars.peek().is_some() {
+                for field in fields.iter_mut() {
+                    let mut vlq = 0;
+                    let mut shift = 0;
+                    loop {
+                        let c = chars.next().unwrap();
+                        let digit = BASE64_CHARS.iter().position(|b| *b == c).unwrap() as i64;
+                        vlq |= (digit & 0x1f) << shift;
+                        shift += 5;
+                        if digit & 0x20 == 0 {
+                            break;
+                        }
+                    }
+                    *field += if vlq & 1 == 1 { -(vlq >> 1) } else { vlq >> 1 };
+                }
+                decoded.push(fields);
+
+                // Segments are separated by commas
+                chars.next();
+            }
+
+            decoded
+        }
+    }
+
     #[test]
     fn known_type_error() {
         check_compile_error(
@@ -0,0 +1,13 @@
+app "dbg"
+    packages { pf: "../multi-dep-str/platform/main.roc" }
+    imports []
+    provides [main] to pf
+
+main : Str
+main =
+    answer : I64
+    answer = dbg (20 + 1)
+
+    greeting = dbg "Hello, dbg!"
+
+    Str.concat greeting (Num.toStr answer)
@@ -0,0 +1,11 @@
+app "wasm-source-map"
+    packages { pf: "../multi-dep-str/platform/main.roc" }
+    imports []
+    provides [main] to pf
+
+main : Str
+main = greet "World"
+
+greet : Str -> Str
+greet = \name ->
+    Str.concat "Hello, " name
@@ -462,7 +462,9 @@ fn stmt_spec<'a>(
 
             builder.add_choice(block, &cases)
         }
-        Expect { remainder, .. } => stmt_spec(builder, env, block, layout, remainder),
+        Expect { remainder, .. } | Dbg { remainder, .. } => {
+            stmt_spec(builder, env, block, layout, remainder)
+        }
         Ret(symbol) => Ok(env.symbols[symbol]),
         Refcounting(modify_rc, continuation) => match modify_rc {
             ModifyRc::Inc(symbol, _) => {
@@ -552,7 +554,7 @@ fn stmt_spec<'a>(
             let jpid = env.join_points[id];
             builder.add_jump(block, jpid, argument, ret_type_id)
         }
-        RuntimeError(_) => {
+        Crash(_) | RuntimeError(_) => {
             let type_id = layout_spec(builder, layout, &WhenRecursive::Unreachable)?;
 
             builder.add_terminate(block, type_id)
@@ -1344,6 +1346,17 @@ fn expr_spec<'a>(
 
             builder.add_unknown_with(block, &[value_id], type_id)
         }
+        SetUnionAtIndex {
+            structure, value, ..
+        } => {
+            // morphic values are immutable, so we can't express that the structure now contains
+            // the value. Instead, we give up on everything that is reachable from either of them
+            let type_id = layout_spec(builder, layout, &WhenRecursive::Unreachable)?;
+            let structure_id = env.symbols[structure];
+            let value_id = env.symbols[value];
+
+            builder.add_unknown_with(block, &[structure_id, value_id], type_id)
+        }
         RuntimeErrorFunction(_) => {
             let type_id = layout_spec(builder, layout, &WhenRecursive::Unreachable)?;
 
@@ -1,5 +1,6 @@
 pub use roc_gen_llvm::llvm::build::FunctionIterator;
 use roc_gen_llvm::llvm::build::{module_from_builtins, LlvmBackendMode};
+use roc_gen_llvm::llvm::debug_info::DebugSources;
 use roc_gen_llvm::llvm::externs::add_default_roc_externs;
 use roc_load::{EntryPoint, LoadedModule, MonomorphizedModule};
 use roc_module::symbol::{Interns, ModuleId};
@@ -13,6 +14,121 @@ use roc_collections::all::MutMap;
 #[cfg(feature = "target-wasm32")]
 use roc_collections::all::MutSet;
 
+/// An intermediate artifact that `roc build --emit` writes next to the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmitKind {
+    MonoIr,
+    LlvmIr,
+    Asm,
+    Wat,
+    Obj,
+}
+
+impl EmitKind {
+    pub const NAMES: &'static [&'static str] = &["mono-ir", "llvm-ir", "asm", "wat", "obj"];
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "mono-ir" => Some(EmitKind::MonoIr),
+            "llvm-ir" => Some(EmitKind::LlvmIr),
+            "asm" => Some(EmitKind::Asm),
+            "wat" => Some(EmitKind::Wat),
+            "obj" => Some(EmitKind::Obj),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            EmitKind::MonoIr => "mono-ir",
+            EmitKind::LlvmIr => "llvm-ir",
+            EmitKind::Asm => "asm",
+            EmitKind::Wat => "wat",
+            EmitKind::Obj => "obj",
+        }
+    }
+
+    /// The object file is named after the app file instead, whose extension depends on the target
+    fn extension(self) -> &'static str {
+        match self {
+            EmitKind::MonoIr => "mono",
+            EmitKind::LlvmIr => "ll",
+            EmitKind::Asm => "s",
+            EmitKind::Wat => "wat",
+            EmitKind::Obj => "o",
+        }
+    }
+
+    /// Whether the backend that we use for this build produces this kind of artifact
+    pub fn is_available(self, opt_level: OptLevel, target: &target_lexicon::Triple) -> bool {
+        use target_lexicon::Architecture;
+
+        let is_dev = matches!(opt_level, OptLevel::Development);
+        match self {
+            EmitKind::MonoIr | EmitKind::Obj => true,
+            EmitKind::LlvmIr => !is_dev,
+            EmitKind::Asm => {
+                !is_dev
+                    && matches!(
+                        target.architecture,
+                        Architecture::X86_64 | Architecture::X86_32(_) | Architecture::Aarch64(_)
+                    )
+            }
+            EmitKind::Wat => is_dev && matches!(target.architecture, Architecture::Wasm32),
+        }
+    }
+}
+
+/// The artifacts requested with `roc build --emit`, and where to write them
+#[derive(Debug, Clone, Default)]
+pub struct EmitArtifacts {
+    kinds: Vec<EmitKind>,
+    output_path: PathBuf,
+}
+
+impl EmitArtifacts {
+    pub fn new(kinds: Vec<EmitKind>, output_path: PathBuf) -> Self {
+        EmitArtifacts { kinds, output_path }
+    }
+
+    pub fn contains(&self, kind: EmitKind) -> bool {
+        self.kinds.contains(&kind)
+    }
+
+    /// An artifact goes next to the output, with the same name but a different extension
+    pub fn path(&self, extension: &str) -> PathBuf {
+        self.output_path.with_extension(extension)
+    }
+
+    fn path_for(&self, kind: EmitKind) -> PathBuf {
+        self.path(kind.extension())
+    }
+
+    fn write(&self, kind: EmitKind, contents: impl AsRef<[u8]>) {
+        let path = self.path_for(kind);
+        std::fs::write(&path, contents).unwrap_or_else(|e| {
+            panic!(
+                "I wasn't able to write the {} output {}\n{}",
+                kind.name(),
+                path.display(),
+                e
+            )
+        });
+    }
+}
+
+/// Every specialized procedure, in a stable order so that the output of two builds can be diffed
+fn mono_ir_to_pretty(loaded: &MonomorphizedModule) -> String {
+    let mut procs: Vec<String> = loaded
+        .procedures
+        .values()
+        .map(|proc| proc.to_pretty(200))
+        .collect();
+    procs.sort();
+
+    procs.join("\n")
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct CodeGenTiming {
     pub code_gen: Duration,
@@ -165,9 +281,16 @@ pub fn gen_from_mono_module(
     app_o_file: &Path,
     opt_level: OptLevel,
     emit_debug_info: bool,
+    emit_llvm_ir_debug_info: bool,
+    emit: &EmitArtifacts,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
+    wasm_dev_proposals: bool,
 ) -> CodeGenTiming {
+    if emit.contains(EmitKind::MonoIr) {
+        emit.write(EmitKind::MonoIr, mono_ir_to_pretty(&loaded));
+    }
+
     match opt_level {
         OptLevel::Normal | OptLevel::Size | OptLevel::Optimize => gen_from_mono_module_llvm(
             arena,
@@ -177,14 +300,19 @@ pub fn gen_from_mono_module(
             app_o_file,
             opt_level,
             emit_debug_info,
+            emit_llvm_ir_debug_info,
+            emit,
         ),
         OptLevel::Development => gen_from_mono_module_dev(
             arena,
             loaded,
             target,
             app_o_file,
+            emit_debug_info,
+            emit,
             preprocessed_host_path,
             wasm_dev_stack_bytes,
+            wasm_dev_proposals,
         ),
     }
 }
@@ -200,6 +328,8 @@ pub fn gen_from_mono_module_llvm(
     app_o_file: &Path,
     opt_level: OptLevel,
     emit_debug_info: bool,
+    emit_llvm_ir_debug_info: bool,
+    emit: &EmitArtifacts,
 ) -> CodeGenTiming {
     use crate::target::{self, convert_opt_level};
     use inkwell::attributes::{Attribute, AttributeLoc};
@@ -253,6 +383,17 @@ pub fn gen_from_mono_module_llvm(
     let (dibuilder, compile_unit) = roc_gen_llvm::llvm::build::Env::new_debug_info(module);
     let (mpm, _fpm) = roc_gen_llvm::llvm::build::construct_optimization_passes(module, opt_level);
 
+    // point the debug info at the Roc source, so the program can be stepped through in a debugger
+    let debug_sources = if emit_debug_info {
+        Some(DebugSources::new(
+            &dibuilder,
+            &loaded.sources,
+            loaded.procedures.values(),
+        ))
+    } else {
+        None
+    };
+
     // Compile and add all the Procs before adding main
     let env = roc_gen_llvm::llvm::build::Env {
         arena,
@@ -265,6 +406,7 @@ pub fn gen_from_mono_module_llvm(
         target_info,
         mode: LlvmBackendMode::Binary,
         exposed_to_host: loaded.exposed_to_host.values.keys().copied().collect(),
+        debug_sources,
     };
 
     // does not add any externs for this mode (we have a host) but cleans up some functions around
@@ -288,8 +430,10 @@ pub fn gen_from_mono_module_llvm(
 
     env.dibuilder.finalize();
 
-    // we don't use the debug info, and it causes weird errors.
-    module.strip_debug_info();
+    if !emit_debug_info {
+        // we don't use the debug info, and it causes weird errors.
+        module.strip_debug_info();
+    }
 
     // Uncomment this to see the module's optimized LLVM instruction output:
     // env.module.print_to_stderr();
@@ -311,12 +455,23 @@ pub fn gen_from_mono_module_llvm(
     // Uncomment this to see the module's optimized LLVM instruction output:
     // env.module.print_to_stderr();
 
+    if emit.contains(EmitKind::LlvmIr) {
+        let ll_path = emit.path_for(EmitKind::LlvmIr);
+        env.module.print_to_file(&ll_path).unwrap_or_else(|e| {
+            panic!(
+                "I wasn't able to write the LLVM IR to {}\n{}",
+                ll_path.display(),
+                e
+            )
+        });
+    }
+
     let code_gen = code_gen_start.elapsed();
     let emit_o_file_start = Instant::now();
 
     // annotate the LLVM IR output with debug info
     // so errors are reported with the line number of the LLVM source
-    if emit_debug_info {
+    if emit_llvm_ir_debug_info {
         module.strip_debug_info();
 
         let mut app_ll_dbg_file = PathBuf::from(roc_file_path);
@@ -395,6 +550,16 @@ pub fn gen_from_mono_module_llvm(
                 target_machine
                     .write_to_file(env.module, FileType::Object, app_o_file)
                     .expect("Writing .o file failed");
+
+                if emit.contains(EmitKind::Asm) {
+                    target_machine
+                        .write_to_file(
+                            env.module,
+                            FileType::Assembly,
+                            &emit.path_for(EmitKind::Asm),
+                        )
+                        .expect("Writing .s file failed");
+                }
             }
             Architecture::Wasm32 => {
                 // Useful for debugging
@@ -421,8 +586,11 @@ pub fn gen_from_mono_module_dev(
     loaded: MonomorphizedModule,
     target: &target_lexicon::Triple,
     app_o_file: &Path,
+    emit_debug_info: bool,
+    emit: &EmitArtifacts,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
+    wasm_dev_proposals: bool,
 ) -> CodeGenTiming {
     use target_lexicon::Architecture;
 
@@ -431,8 +599,11 @@ pub fn gen_from_mono_module_dev(
             arena,
             loaded,
             app_o_file,
+            emit_debug_info,
+            emit,
             preprocessed_host_path,
             wasm_dev_stack_bytes,
+            wasm_dev_proposals,
         ),
         Architecture::X86_64 | Architecture::Aarch64(_) => {
             gen_from_mono_module_dev_assembly(arena, loaded, target, app_o_file)
@@ -447,8 +618,11 @@ pub fn gen_from_mono_module_dev(
     loaded: MonomorphizedModule,
     target: &target_lexicon::Triple,
     app_o_file: &Path,
+    _emit_debug_info: bool,
+    _emit: &EmitArtifacts,
     _host_input_path: &Path,
     _wasm_dev_stack_bytes: Option<u32>,
+    _wasm_dev_proposals: bool,
 ) -> CodeGenTiming {
     use target_lexicon::Architecture;
 
@@ -465,14 +639,19 @@ fn gen_from_mono_module_dev_wasm32(
     arena: &bumpalo::Bump,
     loaded: MonomorphizedModule,
     app_o_file: &Path,
+    emit_debug_info: bool,
+    emit: &EmitArtifacts,
     preprocessed_host_path: &Path,
     wasm_dev_stack_bytes: Option<u32>,
+    wasm_dev_proposals: bool,
 ) -> CodeGenTiming {
     let code_gen_start = Instant::now();
     let MonomorphizedModule {
         module_id,
         procedures,
         mut interns,
+        sources,
+        output_path,
         ..
     } = loaded;
 
@@ -488,6 +667,11 @@ fn gen_from_mono_module_dev_wasm32(
         module_id,
         exposed_to_host,
         stack_bytes: wasm_dev_stack_bytes.unwrap_or(roc_gen_wasm::Env::DEFAULT_STACK_BYTES),
+        features: if wasm_dev_proposals {
+            roc_gen_wasm::WasmFeatures::ALL
+        } else {
+            roc_gen_wasm::WasmFeatures::default()
+        },
     };
 
     let host_bytes = std::fs::read(preprocessed_host_path).unwrap_or_else(|_| {
@@ -506,8 +690,24 @@ fn gen_from_mono_module_dev_wasm32(
         )
     });
 
-    let final_binary_bytes =
-        roc_gen_wasm::build_app_binary(&env, &mut interns, host_module, procedures);
+    let (final_binary_bytes, source_map) = if emit_debug_info {
+        // The source map is found relative to the final binary, which the CLI names after the output path
+        let wasm_path = output_path.with_extension("wasm");
+        let wasm_file_name = wasm_path.file_name().unwrap().to_string_lossy();
+
+        let (bytes, source_map) = roc_gen_wasm::build_app_binary_with_source_map(
+            &env,
+            &mut interns,
+            host_module,
+            procedures,
+            &sources,
+            &wasm_file_name,
+        );
+        (bytes, Some(source_map))
+    } else {
+        let bytes = roc_gen_wasm::build_app_binary(&env, &mut interns, host_module, procedures);
+        (bytes, None)
+    };
 
     let code_gen = code_gen_start.elapsed();
     let emit_o_file_start = Instant::now();
@@ -521,6 +721,29 @@ fn gen_from_mono_module_dev_wasm32(
         )
     });
 
+    if emit.contains(EmitKind::Wat) {
+        // Print from the final binary rather than our own IR, so that the host functions are included
+        let wat = roc_gen_wasm::wasm_module::wat::print_wat(arena, &final_binary_bytes)
+            .unwrap_or_else(|e| {
+                panic!(
+                    "I couldn't print the Wasm module as text, {} at offset 0x{:x}",
+                    e.message, e.offset
+                )
+            });
+        emit.write(EmitKind::Wat, wat);
+    }
+
+    if let Some(source_map) = source_map {
+        let source_map_path = app_o_file.with_extension("wasm.map");
+        std::fs::write(&source_map_path, source_map).unwrap_or_else(|e| {
+            panic!(
+                "I wasn't able to write the source map {}\n{}",
+                source_map_path.display(),
+                e
+            )
+        });
+    }
+
     let emit_o_file = emit_o_file_start.elapsed();
 
     CodeGenTiming {
@@ -545,6 +768,7 @@ fn gen_from_mono_module_dev_assembly(
         procedures,
         mut interns,
         exposed_to_host,
+        sources,
         ..
     } = loaded;
 
@@ -554,6 +778,7 @@ fn gen_from_mono_module_dev_assembly(
         exposed_to_host: exposed_to_host.values.keys().copied().collect(),
         lazy_literals,
         generate_allocators,
+        sources: &sources,
     };
 
     let module_object = roc_gen_dev::build_module(&env, &mut interns, target, procedures);
@@ -217,11 +217,20 @@ pub const RocDec = extern struct {
         return self.num != other.num;
     }
 
+    pub fn toF64(self: RocDec) f64 {
+        return @intToFloat(f64, self.num) / comptime @intToFloat(f64, one_point_zero_i128);
+    }
+
     pub fn negate(self: RocDec) ?RocDec {
         var negated = math.negate(self.num) catch null;
         return if (negated) |n| .{ .num = n } else null;
     }
 
+    pub fn abs(self: RocDec) ?RocDec {
+        var absolute = math.absInt(self.num) catch null;
+        return if (absolute) |n| .{ .num = n } else null;
+    }
+
     pub fn addWithOverflow(self: RocDec, other: RocDec) WithOverflow(RocDec) {
         var answer: i128 = undefined;
         const overflowed = @addWithOverflow(i128, self.num, other.num, &answer);
@@ -1055,6 +1064,22 @@ test "div: 10 / 3" {
     try expectEqual(res, numer.div(denom));
 }
 
+test "toF64: 1.5" {
+    var dec: RocDec = RocDec.fromStr(RocStr.init("1.5", 3)).?;
+
+    try expectEqual(@as(f64, 1.5), dec.toF64());
+}
+
+test "abs: negative" {
+    var dec: RocDec = RocDec.fromU64(15).negate().?;
+
+    try expectEqual(RocDec.fromU64(15), dec.abs().?);
+}
+
+test "abs: min" {
+    try expectEqual(@as(?RocDec, null), RocDec.min.abs());
+}
+
 // exports
 
 pub fn fromStr(arg: RocStr) callconv(.C) num_.NumParseResult(i128) {
@@ -1081,10 +1106,46 @@ pub fn neqC(arg1: RocDec, arg2: RocDec) callconv(.C) bool {
     return @call(.{ .modifier = always_inline }, RocDec.neq, .{ arg1, arg2 });
 }
 
+pub fn toF64C(arg: RocDec) callconv(.C) f64 {
+    return @call(.{ .modifier = always_inline }, RocDec.toF64, .{arg});
+}
+
+pub fn exportFromInt(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T) callconv(.C) i128 {
+            if (comptime T == u128) {
+                if (self > math.maxInt(i128)) {
+                    roc_panic("integer is too big to convert to a decimal", 1);
+                    unreachable;
+                }
+            }
+
+            var answer: i128 = undefined;
+            const overflowed = @mulWithOverflow(i128, @intCast(i128, self), RocDec.one_point_zero_i128, &answer);
+            if (overflowed) {
+                roc_panic("integer is too big to convert to a decimal", 1);
+                unreachable;
+            }
+
+            return answer;
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
 pub fn negateC(arg: RocDec) callconv(.C) i128 {
     return if (@call(.{ .modifier = always_inline }, RocDec.negate, .{arg})) |dec| dec.num else @panic("TODO overflow for negating RocDec");
 }
 
+pub fn absC(arg: RocDec) callconv(.C) i128 {
+    if (@call(.{ .modifier = always_inline }, RocDec.abs, .{arg})) |dec| {
+        return dec.num;
+    } else {
+        roc_panic("Decimal absolute overflowed because its argument is the minimum value", 1);
+        unreachable;
+    }
+}
+
 pub fn addC(arg1: RocDec, arg2: RocDec) callconv(.C) WithOverflow(RocDec) {
     return @call(.{ .modifier = always_inline }, RocDec.addWithOverflow, .{ arg1, arg2 });
 }
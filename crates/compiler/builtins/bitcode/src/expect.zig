@@ -4,15 +4,22 @@ extern fn shm_open(name: *const i8, oflag: c_int, mode: c_uint) c_int;
 extern fn mmap(addr: ?*anyopaque, length: c_uint, prot: c_int, flags: c_int, fd: c_int, offset: c_uint) *anyopaque;
 extern fn kill(pid: c_int, sig: c_int) c_int;
 extern fn getppid() c_int;
+extern fn sched_yield() c_int;
 
 const SIGUSR1: c_int = 10;
 
 const O_RDWR: c_int = 2;
 const O_CREAT: c_int = 64;
 
+pub const PROT_READ: c_int = 1;
 pub const PROT_WRITE: c_int = 2;
 pub const MAP_SHARED: c_int = 0x0001;
 
+// must match `SHM_SIZE` in the cli and the header of `ExpectSequence` in roc_repl_expect
+const SHM_SIZE: usize = 1024;
+const COUNT_INDEX: usize = 0;
+const OFFSET_INDEX: usize = 1;
+
 // IMPORTANT: shared memory object names must begin with / and contain no other slashes!
 var SHARED_BUFFER: []u8 = undefined;
 
@@ -32,3 +39,61 @@ pub fn expectFailedFinalize() callconv(.C) void {
 
     _ = kill(parent_pid, SIGUSR1);
 }
+
+var DEV_BUFFER: ?[*]u8 = null;
+var DEV_BUFFER_OPENED: bool = false;
+
+/// `roc dev` creates this buffer before it starts the app, and names it after its own pid.
+/// A binary that was started some other way finds no buffer, and its `dbg`s report nothing.
+fn devBuffer() ?[*]u8 {
+    if (!DEV_BUFFER_OPENED) {
+        DEV_BUFFER_OPENED = true;
+
+        var name_buffer: [64]u8 = undefined;
+        const name = std.fmt.bufPrintZ(&name_buffer, "/roc_expect_buffer_{d}", .{getppid()}) catch unreachable;
+
+        const fd = shm_open(@ptrCast(*const i8, name.ptr), O_RDWR, 0o666);
+
+        if (fd >= 0) {
+            const ptr = mmap(null, SHM_SIZE, PROT_READ | PROT_WRITE, MAP_SHARED, fd, 0);
+
+            // MAP_FAILED is -1
+            if (@ptrToInt(ptr) != std.math.maxInt(usize)) {
+                DEV_BUFFER = @ptrCast([*]u8, ptr);
+            }
+        }
+    }
+
+    return DEV_BUFFER;
+}
+
+/// The dev backend's `roc_dbg` in a `roc dev` app. The frame holds the address of the value
+/// rather than a copy, so the app waits until `roc dev` has read it and emptied the buffer.
+pub fn dbgToParent(module_id: u32, region_start: u32, region_end: u32, value: *const anyopaque) callconv(.C) void {
+    const buffer = devBuffer() orelse return;
+    const header = @ptrCast([*]usize, @alignCast(@alignOf(usize), buffer));
+
+    const start = @atomicLoad(usize, &header[OFFSET_INDEX], .SeqCst);
+
+    // 8 bytes for the region, 4 for the module id, 8 for the end offset and 8 for the address
+    const end = start + 28;
+
+    if (end > SHM_SIZE) {
+        return;
+    }
+
+    std.mem.writeIntNative(u32, buffer[start..][0..4], region_start);
+    std.mem.writeIntNative(u32, buffer[start + 4 ..][0..4], region_end);
+    std.mem.writeIntNative(u32, buffer[start + 8 ..][0..4], module_id);
+    std.mem.writeIntNative(usize, buffer[start + 12 ..][0..8], end);
+    std.mem.writeIntNative(usize, buffer[start + 20 ..][0..8], @ptrToInt(value));
+
+    @atomicStore(usize, &header[OFFSET_INDEX], end, .SeqCst);
+    _ = @atomicRmw(usize, &header[COUNT_INDEX], .Add, 1, .SeqCst);
+
+    _ = kill(getppid(), SIGUSR1);
+
+    while (@atomicLoad(usize, &header[COUNT_INDEX], .SeqCst) != 0) {
+        _ = sched_yield();
+    }
+}
@@ -5,6 +5,7 @@
 // and substantial portions of the software.
 const std = @import("std");
 const str = @import("str.zig");
+const RocList = @import("list.zig").RocList;
 const mem = std.mem;
 
 pub fn wyhash(seed: u64, bytes: ?[*]const u8, length: usize) callconv(.C) u64 {
@@ -20,6 +21,17 @@ pub fn wyhash_rocstr(seed: u64, input: str.RocStr) callconv(.C) u64 {
     return wyhash_hash(seed, input.asSlice());
 }
 
+// Hashes the bytes of a `List U8`, used by the hasher that backs `Dict`.
+pub fn wyhashBytes(input: RocList, seed: u64) callconv(.C) u64 {
+    return wyhash(seed, input.bytes, input.len());
+}
+
+// Mixes two 64-bit values, used by the hasher that backs `Dict` to fold
+// integers into its state.
+pub fn wyhashMix(a: u64, b: u64) callconv(.C) u64 {
+    return mum(a ^ primes[0], b ^ primes[1]);
+}
+
 const primes = [_]u64{
     0xa0761d6478bd642f,
     0xe7037ed1a0b428db,
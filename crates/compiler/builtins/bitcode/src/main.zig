@@ -15,9 +15,11 @@ comptime {
     exportDecFn(dec.fromStr, "from_str");
     exportDecFn(dec.toStr, "to_str");
     exportDecFn(dec.fromF64C, "from_f64");
+    exportDecFn(dec.toF64C, "to_f64");
     exportDecFn(dec.eqC, "eq");
     exportDecFn(dec.neqC, "neq");
     exportDecFn(dec.negateC, "negate");
+    exportDecFn(dec.absC, "abs");
     exportDecFn(dec.divC, "div");
 
     exportDecFn(dec.addC, "add_with_overflow");
@@ -31,6 +33,10 @@ comptime {
     exportDecFn(dec.mulC, "mul_with_overflow");
     exportDecFn(dec.mulOrPanicC, "mul_or_panic");
     exportDecFn(dec.mulSaturatedC, "mul_saturated");
+
+    inline for (INTEGERS) |T| {
+        dec.exportFromInt(T, ROC_BUILTINS ++ ".dec.from_int.");
+    }
 }
 
 // List Module
@@ -55,6 +61,14 @@ comptime {
     exportListFn(list.listIsUnique, "is_unique");
 }
 
+// Dict Module
+const hash = @import("hash.zig");
+
+comptime {
+    exportDictFn(hash.wyhashBytes, "hash_bytes");
+    exportDictFn(hash.wyhashMix, "hash_mix");
+}
+
 // Num Module
 const num = @import("num.zig");
 
@@ -70,6 +84,23 @@ comptime {
     inline for (INTEGERS) |T, i| {
         num.exportPow(T, ROC_BUILTINS ++ "." ++ NUM ++ ".pow_int.");
         num.exportDivCeil(T, ROC_BUILTINS ++ "." ++ NUM ++ ".div_ceil.");
+        num.exportDivTrunc(T, ROC_BUILTINS ++ "." ++ NUM ++ ".div_trunc.");
+        num.exportRem(T, ROC_BUILTINS ++ "." ++ NUM ++ ".rem.");
+        num.exportIsMultipleOf(T, ROC_BUILTINS ++ "." ++ NUM ++ ".is_multiple_of.");
+
+        num.exportCompare(T, ROC_BUILTINS ++ "." ++ NUM ++ ".compare.");
+        num.exportNeg(T, ROC_BUILTINS ++ "." ++ NUM ++ ".neg.");
+        num.exportAbs(T, ROC_BUILTINS ++ "." ++ NUM ++ ".abs.");
+
+        num.exportBitwiseAnd(T, ROC_BUILTINS ++ "." ++ NUM ++ ".bitwise_and.");
+        num.exportBitwiseOr(T, ROC_BUILTINS ++ "." ++ NUM ++ ".bitwise_or.");
+        num.exportBitwiseXor(T, ROC_BUILTINS ++ "." ++ NUM ++ ".bitwise_xor.");
+        num.exportShiftLeftBy(T, ROC_BUILTINS ++ "." ++ NUM ++ ".shift_left_by.");
+        num.exportShiftRightBy(T, ROC_BUILTINS ++ "." ++ NUM ++ ".shift_right_by.");
+        num.exportShiftRightZfBy(T, ROC_BUILTINS ++ "." ++ NUM ++ ".shift_right_zf_by.");
+
+        num.exportToFloat(T, f32, ROC_BUILTINS ++ "." ++ NUM ++ ".int_to_f32.");
+        num.exportToFloat(T, f64, ROC_BUILTINS ++ "." ++ NUM ++ ".int_to_f64.");
 
         num.exportRoundF32(T, ROC_BUILTINS ++ "." ++ NUM ++ ".round_f32.");
         num.exportRoundF64(T, ROC_BUILTINS ++ "." ++ NUM ++ ".round_f64.");
@@ -144,6 +175,7 @@ comptime {
     exportStrFn(str.strTrimLeft, "trim_left");
     exportStrFn(str.strTrimRight, "trim_right");
     exportStrFn(str.strCloneTo, "clone_to");
+    exportStrFn(str.crash, "crash");
 
     inline for (INTEGERS) |T| {
         str.exportFromInt(T, ROC_BUILTINS ++ "." ++ STR ++ ".from_int.");
@@ -159,6 +191,7 @@ comptime {
 // Utils
 comptime {
     exportUtilsFn(utils.test_panic, "test_panic");
+    exportUtilsFn(utils.test_dbg, "test_dbg");
     exportUtilsFn(utils.increfC, "incref");
     exportUtilsFn(utils.decrefC, "decref");
     exportUtilsFn(utils.decrefCheckNullC, "decref_check_null");
@@ -169,6 +202,7 @@ comptime {
     if (builtin.target.cpu.arch != .wasm32) {
         exportUtilsFn(expect.expectFailedStart, "expect_failed_start");
         exportUtilsFn(expect.expectFailedFinalize, "expect_failed_finalize");
+        exportUtilsFn(expect.dbgToParent, "dbg_to_parent");
 
         // sets the buffer used for expect failures
         @export(expect.setSharedBuffer, .{ .name = "set_shared_buffer", .linkage = .Weak });
@@ -185,6 +185,160 @@ pub fn exportDivCeil(comptime T: type, comptime name: []const u8) void {
     @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
 }
 
+pub fn exportDivTrunc(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(a: T, b: T) callconv(.C) T {
+            return @divTrunc(a, b);
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportRem(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(a: T, b: T) callconv(.C) T {
+            return @rem(a, b);
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportIsMultipleOf(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T, other: T) callconv(.C) bool {
+            if (other == 0) {
+                return self == 0;
+            }
+
+            if (comptime std.meta.trait.isSignedInt(T)) {
+                // every number is a multiple of -1, and `minInt(T) % -1` would overflow
+                if (other == -1) {
+                    return true;
+                }
+            }
+
+            return @rem(self, other) == 0;
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportCompare(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T, other: T) callconv(.C) u8 {
+            // The tag ids of Roc's `[EQ, GT, LT]`
+            if (self == other) {
+                return 0;
+            } else if (self > other) {
+                return 1;
+            } else {
+                return 2;
+            }
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportNeg(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T) callconv(.C) T {
+            if (comptime std.meta.trait.isSignedInt(T)) {
+                if (self == math.minInt(T)) {
+                    roc_panic("integer negation overflowed because its argument is the minimum value", 1);
+                    unreachable;
+                }
+            }
+
+            return 0 -% self;
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportAbs(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T) callconv(.C) T {
+            if (comptime std.meta.trait.isSignedInt(T)) {
+                if (self == math.minInt(T)) {
+                    roc_panic("integer absolute overflowed because its argument is the minimum value", 1);
+                    unreachable;
+                }
+
+                return if (self < 0) -self else self;
+            } else {
+                return self;
+            }
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportBitwiseAnd(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T, other: T) callconv(.C) T {
+            return self & other;
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportBitwiseOr(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T, other: T) callconv(.C) T {
+            return self | other;
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportBitwiseXor(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T, other: T) callconv(.C) T {
+            return self ^ other;
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportShiftLeftBy(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T, bits: T) callconv(.C) T {
+            return math.shl(T, self, bits);
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportShiftRightBy(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T, bits: T) callconv(.C) T {
+            // Roc defines the arithmetic shift on all integers, so shift unsigned integers as if they were signed
+            const S = std.meta.Int(.signed, @bitSizeOf(T));
+            return @bitCast(T, math.shr(S, @bitCast(S, self), bits));
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportShiftRightZfBy(comptime T: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T, bits: T) callconv(.C) T {
+            // Roc defines the logical shift on all integers, so shift signed integers as if they were unsigned
+            const U = std.meta.Int(.unsigned, @bitSizeOf(T));
+            return @bitCast(T, math.shr(U, @bitCast(U, self), bits));
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
+
+pub fn exportToFloat(comptime T: type, comptime F: type, comptime name: []const u8) void {
+    comptime var f = struct {
+        fn func(self: T) callconv(.C) F {
+            return @intToFloat(F, self);
+        }
+    }.func;
+    @export(f, .{ .name = name ++ @typeName(T), .linkage = .Strong });
+}
 pub fn ToIntCheckedResult(comptime T: type) type {
     // On the Roc side we sort by alignment; putting the errorcode last
     // always works out (no number with smaller alignment than 1).
@@ -2639,3 +2639,15 @@ pub fn strCloneTo(
         return extra_offset + slice.len;
     }
 }
+
+// `crash "message"`
+// roc_panic expects a null-terminated string, so copy the message into one and hand it over
+pub fn crash(message: RocStr) callconv(.C) void {
+    const length = message.len();
+    const c_ptr = utils.alloc(length + 1, RocStr.alignment).?;
+
+    @memcpy(c_ptr, message.asU8ptr(), length);
+    c_ptr[length] = 0;
+
+    utils.panic(c_ptr, 0);
+}
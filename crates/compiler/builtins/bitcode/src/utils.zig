@@ -1,4 +1,5 @@
 const std = @import("std");
+const builtin = @import("builtin");
 const always_inline = std.builtin.CallOptions.Modifier.always_inline;
 const Monotonic = std.builtin.AtomicOrder.Monotonic;
 
@@ -89,15 +90,25 @@ pub fn memcpy(dst: [*]u8, src: [*]u8, size: usize) void {
 
 // indirection because otherwise zig creates an alias to the panic function which our LLVM code
 // does not know how to deal with
+// the dev backend's tests have no host, so the message goes straight to stderr.
+// This returns, and the trap after the call stops the program.
 pub fn test_panic(c_ptr: *anyopaque, alignment: u32) callconv(.C) void {
-    _ = c_ptr;
     _ = alignment;
-    // const cstr = @ptrCast([*:0]u8, c_ptr);
 
-    // const stderr = std.io.getStdErr().writer();
-    // stderr.print("Roc panicked: {s}!\n", .{cstr}) catch unreachable;
+    if (builtin.target.cpu.arch != .wasm32) {
+        const cstr = @ptrCast([*:0]u8, c_ptr);
+
+        const stderr = std.io.getStdErr().writer();
+        stderr.print("Roc failed with message: \"{s}\"\n", .{std.mem.span(cstr)}) catch unreachable;
+    }
+}
 
-    // std.c.exit(1);
+// the dev backend's tests have no host, so `dbg` output is dropped
+pub fn test_dbg(module_id: u32, region_start: u32, region_end: u32, value: *const anyopaque) callconv(.C) void {
+    _ = module_id;
+    _ = region_start;
+    _ = region_end;
+    _ = value;
 }
 
 pub const Inc = fn (?[*]u8) callconv(.C) void;
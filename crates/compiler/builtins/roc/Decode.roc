@@ -23,6 +23,7 @@ interface Decode
         string,
         list,
         record,
+        tag,
         custom,
         decodeWith,
         fromBytesPartial,
@@ -60,6 +61,11 @@ DecoderFormatting has
     list : Decoder elem fmt -> Decoder (List elem) fmt | fmt has DecoderFormatting
     record : state, (state, Str -> [Keep (Decoder state fmt), Skip]), (state -> Result val DecodeError) -> Decoder val fmt | fmt has DecoderFormatting
 
+    ## Decodes a tag with its payload. The first function picks the initial state
+    ## for a tag name, the second is asked for a decoder for each payload value by
+    ## its index, and the last builds the decoded value from the final state.
+    tag : (Str -> [Keep state, Skip]), (state, Nat -> [Next (Decoder state fmt), TooLong]), (state -> Result val DecodeError) -> Decoder val fmt | fmt has DecoderFormatting
+
 custom : (List U8, fmt -> DecodeResult val) -> Decoder val fmt | fmt has DecoderFormatting
 custom = \decode -> @Decoder decode
 
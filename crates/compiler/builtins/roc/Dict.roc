@@ -20,6 +20,7 @@ interface Dict
         Bool.{ Bool },
         Result.{ Result },
         List,
+        Hash.{ Hash, Hasher },
     ]
 
 ## A [dictionary](https://en.wikipedia.org/wiki/Associative_array) that lets you can associate keys with values.
@@ -63,118 +64,360 @@ interface Dict
 ## [Dict.remove] does: it removes an element and moves the most recent insertion into the vacated spot.
 ##
 ## This move is done as a performance optimization, and it lets [remove] have
-## [constant time complexity](https://en.wikipedia.org/wiki/Time_complexity#Constant_time). ##
+## [constant time complexity](https://en.wikipedia.org/wiki/Time_complexity#Constant_time) on average.
+##
+## ### Performance
+##
+## A dictionary is an [open-addressing](https://en.wikipedia.org/wiki/Open_addressing) hash table. Its keys
+## must have the [Hash] ability, and [get], [insert], [contains] and [remove] take constant time on average.
+## The entries themselves are kept in a separate list in insertion order, which is what [walk], [keys]
+## and [values] iterate over.
 ##
 ## ### Equality
 ##
 ## When comparing two dictionaries for equality, they are `==` only if their both their contents and their
 ## orderings match. This preserves the property that if `dict1 == dict2`, you should be able to rely on
 ## `fn dict1 == fn dict2` also being `True`, even if `fn` relies on the dictionary's ordering.
-Dict k v := List [Pair k v]
+Dict k v := {
+    # A bucket holds 0 if it is empty, and otherwise 1 + the index of its entry in `data`.
+    # The buckets only depend on `data` (see `place`), so that `==` on two dictionaries
+    # only compares their entries.
+    buckets : List Nat,
+    data : List [Pair k v],
+}
 
 ## An empty dictionary.
 empty : Dict k v
-empty = @Dict []
+empty = @Dict { buckets: [], data: [] }
 
 withCapacity : Nat -> Dict k v
-withCapacity = \n -> @Dict (List.withCapacity n)
+withCapacity = \n ->
+    @Dict { buckets: [], data: List.withCapacity n }
 
-get : Dict k v, k -> Result v [KeyNotFound]*
-get = \@Dict list, needle ->
-    when List.findFirst list (\Pair key _ -> key == needle) is
-        Ok (Pair _ v) ->
-            Ok v
+get : Dict k v, k -> Result v [KeyNotFound]* | k has Hash
+get = \@Dict { buckets, data }, needle ->
+    when findSlot buckets data needle is
+        Found _ dataIndex ->
+            when List.get data dataIndex is
+                Ok (Pair _ v) ->
+                    Ok v
 
-        Err NotFound ->
+                Err OutOfBounds ->
+                    Err KeyNotFound
+
+        Vacant _ ->
             Err KeyNotFound
 
 walk : Dict k v, state, (state, k, v -> state) -> state
-walk = \@Dict list, initialState, transform ->
-    List.walk list initialState (\state, Pair k v -> transform state k v)
+walk = \@Dict { data }, initialState, transform ->
+    List.walk data initialState (\state, Pair k v -> transform state k v)
 
-insert : Dict k v, k, v -> Dict k v
-insert = \@Dict list, k, v ->
-    when List.findFirstIndex list (\Pair key _ -> key == k) is
-        Err NotFound ->
-            insertFresh (@Dict list) k v
+insert : Dict k v, k, v -> Dict k v | k has Hash
+insert = \@Dict { buckets, data }, k, v ->
+    when findSlot buckets data k is
+        Found _ dataIndex ->
+            @Dict { buckets, data: List.set data dataIndex (Pair k v) }
 
-        Ok index ->
-            list
-            |> List.set index (Pair k v)
-            |> @Dict
+        Vacant _ ->
+            insertFresh (@Dict { buckets, data }) k v
 
 len : Dict k v -> Nat
-len = \@Dict list ->
-    List.len list
-
-remove : Dict k v, k -> Dict k v
-remove = \@Dict list, key ->
-    when List.findFirstIndex list (\Pair k _ -> k == key) is
-        Err NotFound ->
-            @Dict list
-
-        Ok index ->
-            lastIndex = List.len list - 1
-
-            list
-            |> List.swap index lastIndex
-            |> List.dropLast
-            |> @Dict
-
-contains : Dict k v, k -> Bool
-contains = \@Dict list, needle ->
-    step = \_, Pair key _val ->
-        if key == needle then
-            Break {}
-        else
-            Continue {}
+len = \@Dict { data } ->
+    List.len data
+
+remove : Dict k v, k -> Dict k v | k has Hash
+remove = \@Dict { buckets, data }, key ->
+    when findSlot buckets data key is
+        Vacant _ ->
+            @Dict { buckets, data }
+
+        Found bucketIndex dataIndex ->
+            lastIndex = List.len data - 1
+            newData = data |> List.swap dataIndex lastIndex |> List.dropLast
+            newCount = bucketCount (List.len newData)
+
+            if newCount != List.len buckets then
+                @Dict { buckets: rehash newData newCount, data: newData }
+            else
+                cleared =
+                    buckets
+                    |> List.set bucketIndex 0
+                    |> shiftBack data bucketIndex (nextBucket buckets bucketIndex)
 
-    when List.iterate list {} step is
-        Continue _ -> False
-        Break _ -> True
+                when List.last data is
+                    Ok (Pair lastKey _) if dataIndex != lastIndex ->
+                        # The last entry is swapped into the removed entry's spot. That changes
+                        # its order among the entries that share its home bucket, so it is
+                        # taken out and placed again.
+                        home = homeBucket cleared lastKey
+                        movedBucket = findBucketOf cleared (lastIndex + 1) home
 
-single : k, v -> Dict k v
+                        withoutMoved =
+                            cleared
+                            |> List.set movedBucket 0
+                            |> shiftBack data movedBucket (nextBucket cleared movedBucket)
+
+                        @Dict {
+                            buckets: place withoutMoved newData (dataIndex + 1) home home,
+                            data: newData,
+                        }
+
+                    _ ->
+                        @Dict { buckets: cleared, data: newData }
+
+contains : Dict k v, k -> Bool | k has Hash
+contains = \@Dict { buckets, data }, needle ->
+    when findSlot buckets data needle is
+        Found _ _ -> True
+        Vacant _ -> False
+
+single : k, v -> Dict k v | k has Hash
 single = \key, value ->
-    @Dict [Pair key value]
+    insert empty key value
 
 ## Returns a [List] of the dictionary's keys.
 keys : Dict k v -> List k
-keys = \@Dict list ->
-    List.map list (\Pair k _ -> k)
+keys = \@Dict { data } ->
+    List.map data (\Pair k _ -> k)
 
 ## Returns a [List] of the Dict's values
 values : Dict k v -> List v
-values = \@Dict list ->
-    List.map list (\Pair _ v -> v)
+values = \@Dict { data } ->
+    List.map data (\Pair _ v -> v)
 
 # union : Dict k v, Dict k v -> Dict k v
-insertAll : Dict k v, Dict k v -> Dict k v
-insertAll = \xs, @Dict ys ->
-    List.walk ys xs (\state, Pair k v -> Dict.insertIfVacant state k v)
+insertAll : Dict k v, Dict k v -> Dict k v | k has Hash
+insertAll = \xs, @Dict { data } ->
+    List.walk data xs (\state, Pair k v -> insertIfVacant state k v)
 
 # intersection : Dict k v, Dict k v -> Dict k v
-keepShared : Dict k v, Dict k v -> Dict k v
-keepShared = \@Dict xs, ys ->
-    List.keepIf xs (\Pair k _ -> Dict.contains ys k)
-    |> @Dict
+keepShared : Dict k v, Dict k v -> Dict k v | k has Hash
+keepShared = \@Dict { data }, ys ->
+    List.walk data empty \state, Pair k v ->
+        if contains ys k then
+            insertFresh state k v
+        else
+            state
 
 # difference : Dict k v, Dict k v -> Dict k v
-removeAll : Dict k v, Dict k v -> Dict k v
-removeAll = \xs, @Dict ys ->
-    List.walk ys xs (\state, Pair k _ -> Dict.remove state k)
+removeAll : Dict k v, Dict k v -> Dict k v | k has Hash
+removeAll = \xs, @Dict { data } ->
+    List.walk data xs (\state, Pair k _ -> remove state k)
 
 ## Internal helper function to insert a new association
 ##
 ## Precondition: `k` should not exist in the Dict yet.
-insertFresh : Dict k v, k, v -> Dict k v
-insertFresh = \@Dict list, k, v ->
-    list
-    |> List.append (Pair k v)
-    |> @Dict
+insertFresh : Dict k v, k, v -> Dict k v | k has Hash
+insertFresh = \@Dict { buckets, data }, k, v ->
+    dataIndex = List.len data
+    newCount = bucketCount (dataIndex + 1)
+    grownBuckets =
+        if newCount != List.len buckets then
+            rehash data newCount
+        else
+            buckets
+
+    newData = List.append data (Pair k v)
+    home = homeBucket grownBuckets k
 
-insertIfVacant : Dict k v, k, v -> Dict k v
+    @Dict {
+        buckets: place grownBuckets newData (dataIndex + 1) home home,
+        data: newData,
+    }
+
+insertIfVacant : Dict k v, k, v -> Dict k v | k has Hash
 insertIfVacant = \dict, key, value ->
-    if Dict.contains dict key then
+    if contains dict key then
         dict
     else
-        Dict.insert dict key value
+        insert dict key value
+
+## Internal helper: the number of buckets for `n` entries. It only depends on `n`, and keeps the
+## load factor at or below 7/8, so that probing always finds an empty bucket.
+bucketCount : Nat -> Nat
+bucketCount = \n ->
+    if n == 0 then
+        0
+    else
+        bucketCountFor n 8
+
+## Internal helper: the smallest power-of-two bucket count, starting from `count`, that
+## holds `n` entries at a load factor of at most 7/8.
+bucketCountFor : Nat, Nat -> Nat
+bucketCountFor = \n, count ->
+    if count >= 8 && n * 8 <= count * 7 then
+        count
+    else if count < 8 then
+        bucketCountFor n 8
+    else
+        bucketCountFor n (count * 2)
+
+## Internal helper: builds the buckets for `data` from scratch.
+rehash : List [Pair k v], Nat -> List Nat | k has Hash
+rehash = \data, count ->
+    rehashHelp data (List.repeat 0 count) 0
+
+rehashHelp : List [Pair k v], List Nat, Nat -> List Nat | k has Hash
+rehashHelp = \data, buckets, dataIndex ->
+    when List.get data dataIndex is
+        Ok (Pair k _) ->
+            home = homeBucket buckets k
+
+            rehashHelp data (place buckets data (dataIndex + 1) home home) (dataIndex + 1)
+
+        Err OutOfBounds ->
+            buckets
+
+## Internal helper: puts `slot` (1 + an index in `data`), whose key's home bucket is `home`, into
+## the buckets, starting the search at `bucketIndex`. A run of full buckets is kept sorted by home
+## bucket, and then by index in `data` (Robin Hood hashing). So the buckets only depend on `data`,
+## no matter in which order the entries were placed or removed.
+place : List Nat, List [Pair k v], Nat, Nat, Nat -> List Nat | k has Hash
+place = \buckets, data, slot, home, bucketIndex ->
+    when List.get buckets bucketIndex is
+        Ok 0 | Err OutOfBounds ->
+            List.set buckets bucketIndex slot
+
+        Ok current ->
+            currentHome = slotHome buckets data current
+            next = nextBucket buckets bucketIndex
+
+            # the entry already here stays if its home bucket is further back, or if it
+            # has the same home bucket and comes earlier in `data`
+            currentIsFurther = probeDistance buckets currentHome bucketIndex > probeDistance buckets home bucketIndex
+            currentIsEarlier = currentHome == home && current < slot
+
+            if currentIsFurther || currentIsEarlier then
+                place buckets data slot home next
+            else
+                # `slot` goes here, and the entry it displaces moves further along
+                place (List.set buckets bucketIndex slot) data current currentHome next
+
+## Internal helper: after the bucket `hole` has been emptied, moves the rest of its run back by one,
+## up to the first entry that is in its home bucket. This leaves the buckets exactly as if the
+## removed entry had never been placed.
+shiftBack : List Nat, List [Pair k v], Nat, Nat -> List Nat | k has Hash
+shiftBack = \buckets, data, hole, bucketIndex ->
+    when List.get buckets bucketIndex is
+        Ok 0 | Err OutOfBounds ->
+            buckets
+
+        Ok slot ->
+            if slotHome buckets data slot == bucketIndex then
+                buckets
+            else
+                buckets
+                |> List.set hole slot
+                |> List.set bucketIndex 0
+                |> shiftBack data bucketIndex (nextBucket buckets bucketIndex)
+
+findBucketOf : List Nat, Nat, Nat -> Nat
+findBucketOf = \buckets, slot, bucketIndex ->
+    when List.get buckets bucketIndex is
+        Ok current ->
+            if current == slot || current == 0 then
+                bucketIndex
+            else
+                findBucketOf buckets slot (nextBucket buckets bucketIndex)
+
+        Err OutOfBounds ->
+            bucketIndex
+
+slotHome : List Nat, List [Pair k v], Nat -> Nat | k has Hash
+slotHome = \buckets, data, slot ->
+    when List.get data (slot - 1) is
+        Ok (Pair k _) -> homeBucket buckets k
+        Err OutOfBounds -> 0
+
+## Internal helper: looks for `key` along its probe sequence. Returns the bucket holding the key
+## and the key's index in `data`, or the first empty bucket encountered if the key is absent.
+findSlot : List Nat, List [Pair k v], k -> [Found Nat Nat, Vacant Nat] | k has Hash
+findSlot = \buckets, data, key ->
+    if List.isEmpty buckets then
+        Vacant 0
+    else
+        findSlotHelp buckets data key (homeBucket buckets key)
+
+findSlotHelp : List Nat, List [Pair k v], k, Nat -> [Found Nat Nat, Vacant Nat]
+findSlotHelp = \buckets, data, key, bucketIndex ->
+    when List.get buckets bucketIndex is
+        Ok 0 | Err OutOfBounds ->
+            Vacant bucketIndex
+
+        Ok slot ->
+            when List.get data (slot - 1) is
+                Ok (Pair k _) ->
+                    if k == key then
+                        Found bucketIndex (slot - 1)
+                    else
+                        findSlotHelp buckets data key (nextBucket buckets bucketIndex)
+
+                Err OutOfBounds ->
+                    Vacant bucketIndex
+
+homeBucket : List Nat, k -> Nat | k has Hash
+homeBucket = \buckets, key ->
+    mask = Num.toU64 (List.len buckets - 1)
+
+    Num.toNat (Num.bitwiseAnd (hashKey key) mask)
+
+nextBucket : List Nat, Nat -> Nat
+nextBucket = \buckets, bucketIndex ->
+    Num.bitwiseAnd (bucketIndex + 1) (List.len buckets - 1)
+
+probeDistance : List Nat, Nat, Nat -> Nat
+probeDistance = \buckets, from, to ->
+    Num.bitwiseAnd (to + List.len buckets - from) (List.len buckets - 1)
+
+hashKey : k -> U64 | k has Hash
+hashKey = \key ->
+    @LowLevelHasher 0x526F_6352_616E_643F
+    |> Hash.hash key
+    |> Hash.complete
+
+# The hasher used for the keys of every [Dict]. It folds its input into a 64-bit state
+# with the wyhash functions from the zig builtins.
+LowLevelHasher := U64 has [
+        Hasher {
+            addBytes: hasherAddBytes,
+            addU8: hasherAddU8,
+            addU16: hasherAddU16,
+            addU32: hasherAddU32,
+            addU64: hasherAddU64,
+            addU128: hasherAddU128,
+            complete: hasherComplete,
+        },
+    ]
+
+hashBytesLowLevel : List U8, U64 -> U64
+hashMixLowLevel : U64, U64 -> U64
+
+hasherAddBytes : LowLevelHasher, List U8 -> LowLevelHasher
+hasherAddBytes = \@LowLevelHasher state, bytes ->
+    @LowLevelHasher (hashBytesLowLevel bytes state)
+
+hasherAddU8 : LowLevelHasher, U8 -> LowLevelHasher
+hasherAddU8 = \@LowLevelHasher state, n ->
+    @LowLevelHasher (hashMixLowLevel state (Num.toU64 n))
+
+hasherAddU16 : LowLevelHasher, U16 -> LowLevelHasher
+hasherAddU16 = \@LowLevelHasher state, n ->
+    @LowLevelHasher (hashMixLowLevel state (Num.toU64 n))
+
+hasherAddU32 : LowLevelHasher, U32 -> LowLevelHasher
+hasherAddU32 = \@LowLevelHasher state, n ->
+    @LowLevelHasher (hashMixLowLevel state (Num.toU64 n))
+
+hasherAddU64 : LowLevelHasher, U64 -> LowLevelHasher
+hasherAddU64 = \@LowLevelHasher state, n ->
+    @LowLevelHasher (hashMixLowLevel state n)
+
+hasherAddU128 : LowLevelHasher, U128 -> LowLevelHasher
+hasherAddU128 = \@LowLevelHasher state, n ->
+    lower = Num.toU64 n
+    upper = Num.toU64 (Num.shiftRightZfBy 64 n)
+
+    @LowLevelHasher (hashMixLowLevel (hashMixLowLevel state lower) upper)
+
+hasherComplete : LowLevelHasher -> U64
+hasherComplete = \@LowLevelHasher state -> state
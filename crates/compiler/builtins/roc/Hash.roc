@@ -0,0 +1,97 @@
+interface Hash
+    exposes [
+        Hash,
+        Hasher,
+        hash,
+        addBytes,
+        addU8,
+        addU16,
+        addU32,
+        addU64,
+        addU128,
+        addI8,
+        addI16,
+        addI32,
+        addI64,
+        addI128,
+        addNat,
+        complete,
+        hashStrBytes,
+        hashList,
+    ]
+    imports [
+        List,
+        Str,
+    ]
+
+## A value that can be hashed.
+Hash has
+    ## Hashes a value into a [Hasher].
+    ## Note that [hash] does not produce a hash value itself; the hasher must be
+    ## [complete]d in order to extract the hash value.
+    hash : hasher, a -> hasher | a has Hash, hasher has Hasher
+
+## Describes a hashing algorithm that is fed bytes and produces an integer hash.
+##
+## The [Hasher] ability is not derivable; you must define a custom implementation for the
+## opaque type that represents your hashing algorithm.
+Hasher has
+    ## Adds a list of bytes to the hasher.
+    addBytes : a, List U8 -> a | a has Hasher
+
+    ## Adds a single U8 to the hasher.
+    addU8 : a, U8 -> a | a has Hasher
+
+    ## Adds a single U16 to the hasher.
+    addU16 : a, U16 -> a | a has Hasher
+
+    ## Adds a single U32 to the hasher.
+    addU32 : a, U32 -> a | a has Hasher
+
+    ## Adds a single U64 to the hasher.
+    addU64 : a, U64 -> a | a has Hasher
+
+    ## Adds a single U128 to the hasher.
+    addU128 : a, U128 -> a | a has Hasher
+
+    ## Completes the hasher, extracting a hash value from its
+    ## accumulated hash state.
+    complete : a -> U64 | a has Hasher
+
+## Adds a single I8 to a hasher.
+addI8 : a, I8 -> a | a has Hasher
+addI8 = \hasher, i8 -> addU8 hasher (Num.toU8 i8)
+
+## Adds a single I16 to a hasher.
+addI16 : a, I16 -> a | a has Hasher
+addI16 = \hasher, i16 -> addU16 hasher (Num.toU16 i16)
+
+## Adds a single I32 to a hasher.
+addI32 : a, I32 -> a | a has Hasher
+addI32 = \hasher, i32 -> addU32 hasher (Num.toU32 i32)
+
+## Adds a single I64 to a hasher.
+addI64 : a, I64 -> a | a has Hasher
+addI64 = \hasher, i64 -> addU64 hasher (Num.toU64 i64)
+
+## Adds a single I128 to a hasher.
+addI128 : a, I128 -> a | a has Hasher
+addI128 = \hasher, i128 -> addU128 hasher (Num.toU128 i128)
+
+## Adds a single Nat to a hasher.
+##
+## The Nat is widened to a U64 first, so that hashes agree between 32-bit and
+## 64-bit targets.
+addNat : a, Nat -> a | a has Hasher
+addNat = \hasher, n -> addU64 hasher (Num.toU64 n)
+
+## Adds a string into a [Hasher] by hashing its UTF-8 bytes.
+hashStrBytes : a, Str -> a | a has Hasher
+hashStrBytes = \hasher, s ->
+    addBytes hasher (Str.toUtf8 s)
+
+## Adds a list of [Hash]able elements to a [Hasher] by hashing each element.
+hashList : hasher, List a -> hasher | a has Hash, hasher has Hasher
+hashList = \hasher, lst ->
+    List.walk lst hasher \accumHasher, elem ->
+        hash accumHasher elem
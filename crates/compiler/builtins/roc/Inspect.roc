@@ -0,0 +1,229 @@
+interface Inspect
+    exposes [
+        Inspect,
+        Inspector,
+        InspectFormatter,
+        toInspector,
+        init,
+        u8,
+        u16,
+        u32,
+        u64,
+        u128,
+        i8,
+        i16,
+        i32,
+        i64,
+        i128,
+        nat,
+        f32,
+        f64,
+        dec,
+        str,
+        list,
+        record,
+        tag,
+        opaque,
+        custom,
+        apply,
+        inspect,
+        DbgFormatter,
+        toDbgStr,
+    ]
+    imports [
+        List,
+        Num,
+        Str,
+    ]
+
+## Renders a value into a formatter of type `f`.
+Inspector f := f -> f | f has InspectFormatter
+
+## A value that can be rendered into a human-readable form, e.g. for logging.
+##
+## [Inspect] is derivable for records, tag unions, lists, strings and numbers.
+## Opaque types can implement [Inspect] themselves to control how they are
+## rendered, for example to hide secrets.
+Inspect has
+    toInspector : val -> Inspector f | val has Inspect, f has InspectFormatter
+
+## Describes how inspected values are rendered.
+##
+## [DbgFormatter] is the default formatter; implement this ability for your own
+## opaque type to render values in a different way.
+InspectFormatter has
+    ## Creates an empty formatter that values can be rendered into.
+    init : {} -> f | f has InspectFormatter
+    u8 : U8 -> Inspector f | f has InspectFormatter
+    u16 : U16 -> Inspector f | f has InspectFormatter
+    u32 : U32 -> Inspector f | f has InspectFormatter
+    u64 : U64 -> Inspector f | f has InspectFormatter
+    u128 : U128 -> Inspector f | f has InspectFormatter
+    i8 : I8 -> Inspector f | f has InspectFormatter
+    i16 : I16 -> Inspector f | f has InspectFormatter
+    i32 : I32 -> Inspector f | f has InspectFormatter
+    i64 : I64 -> Inspector f | f has InspectFormatter
+    i128 : I128 -> Inspector f | f has InspectFormatter
+    nat : Nat -> Inspector f | f has InspectFormatter
+    f32 : F32 -> Inspector f | f has InspectFormatter
+    f64 : F64 -> Inspector f | f has InspectFormatter
+    dec : Dec -> Inspector f | f has InspectFormatter
+    str : Str -> Inspector f | f has InspectFormatter
+    list : List elem, (elem -> Inspector f) -> Inspector f | f has InspectFormatter
+    record : List { key : Str, value : Inspector f } -> Inspector f | f has InspectFormatter
+    tag : Str, List (Inspector f) -> Inspector f | f has InspectFormatter
+    ## Renders the contents of an opaque type, given the opaque type's name.
+    opaque : Str, Inspector f -> Inspector f | f has InspectFormatter
+
+custom : (f -> f) -> Inspector f | f has InspectFormatter
+custom = \inspector -> @Inspector inspector
+
+apply : Inspector f, f -> f | f has InspectFormatter
+apply = \@Inspector inspector, fmt -> inspector fmt
+
+## Renders a value into a fresh formatter.
+inspect : val -> f | val has Inspect, f has InspectFormatter
+inspect = \val -> apply (toInspector val) (init {})
+
+## The default [InspectFormatter], which renders values the same way the REPL
+## prints them.
+##
+## >>> Inspect.toDbgStr { name: "Rex", tags: [Good, Dog] }
+DbgFormatter := { data : Str, needsParens : Bool } has [
+         InspectFormatter {
+             init: dbgInit,
+             u8: dbgU8,
+             u16: dbgU16,
+             u32: dbgU32,
+             u64: dbgU64,
+             u128: dbgU128,
+             i8: dbgI8,
+             i16: dbgI16,
+             i32: dbgI32,
+             i64: dbgI64,
+             i128: dbgI128,
+             nat: dbgNat,
+             f32: dbgF32,
+             f64: dbgF64,
+             dec: dbgDec,
+             str: dbgStr,
+             list: dbgList,
+             record: dbgRecord,
+             tag: dbgTag,
+             opaque: dbgOpaque,
+         },
+     ]
+
+## Renders a value into a [Str] using the [DbgFormatter].
+toDbgStr : val -> Str | val has Inspect
+toDbgStr = \val ->
+    fmt : DbgFormatter
+    fmt = inspect val
+
+    dbgData fmt
+
+dbgInit = \{} -> @DbgFormatter { data: "", needsParens: False }
+
+dbgData = \@DbgFormatter { data } -> data
+
+dbgNeedsParens = \@DbgFormatter { needsParens } -> needsParens
+
+dbgWrite = \@DbgFormatter { data }, added ->
+    @DbgFormatter { data: Str.concat data added, needsParens: False }
+
+# Marks the most recently rendered value as one that must be wrapped in parens
+# when it appears as a tag or opaque payload, like `Cons 1 Nil` or `@Id 1`.
+dbgCompound = \@DbgFormatter { data } ->
+    @DbgFormatter { data, needsParens: True }
+
+# Renders a payload on its own, so that it can be parenthesized if needed.
+dbgWritePayload = \fmt, inspector ->
+    payload = apply inspector (dbgInit {})
+
+    if dbgNeedsParens payload then
+        dbgWrite fmt " (\(dbgData payload))"
+    else
+        dbgWrite fmt " \(dbgData payload)"
+
+dbgU8 = \n -> custom \fmt -> dbgWrite fmt (Num.toStr n)
+
+dbgU16 = \n -> custom \fmt -> dbgWrite fmt (Num.toStr n)
+
+dbgU32 = \n -> custom \fmt -> dbgWrite fmt (Num.toStr n)
+
+dbgU64 = \n -> custom \fmt -> dbgWrite fmt (Num.toStr n)
+
+dbgU128 = \n -> custom \fmt -> dbgWrite fmt (Num.toStr n)
+
+dbgI8 = \n -> custom \fmt -> dbgWrite fmt (Num.toStr n)
+
+dbgI16 = \n -> custom \fmt -> dbgWrite fmt (Num.toStr n)
+
+dbgI32 = \n -> custom \fmt -> dbgWrite fmt (Num.toStr n)
+
+dbgI64 = \n -> custom \fmt -> dbgWrite fmt (Num.toStr n)
+
+dbgI128 = \n -> custom \fmt -> dbgWrite fmt (Num.toStr n)
+
+dbgNat = \n -> custom \fmt -> dbgWrite fmt (Num.toStr n)
+
+dbgF32 = \n -> custom \fmt -> dbgWrite fmt (Num.toStr n)
+
+dbgF64 = \n -> custom \fmt -> dbgWrite fmt (Num.toStr n)
+
+dbgDec = \n -> custom \fmt -> dbgWrite fmt (Num.toStr n)
+
+dbgStr = \s -> custom \fmt -> dbgWrite fmt "\"\(s)\""
+
+dbgList = \content, toElemInspector ->
+    custom \fmt ->
+        writeList = \{ buffer, elemsLeft }, elem ->
+            bufferWithElem = apply (toElemInspector elem) buffer
+            bufferWithSuffix =
+                if elemsLeft > 1 then
+                    dbgWrite bufferWithElem ", "
+                else
+                    bufferWithElem
+
+            { buffer: bufferWithSuffix, elemsLeft: elemsLeft - 1 }
+
+        head = dbgWrite fmt "["
+        { buffer: withList } = List.walk content { buffer: head, elemsLeft: List.len content } writeList
+
+        dbgWrite withList "]"
+
+dbgRecord = \fields ->
+    custom \fmt ->
+        if List.isEmpty fields then
+            dbgWrite fmt "{}"
+        else
+            writeRecord = \{ buffer, fieldsLeft }, { key, value } ->
+                bufferWithKeyValue = apply value (dbgWrite buffer "\(key): ")
+                bufferWithSuffix =
+                    if fieldsLeft > 1 then
+                        dbgWrite bufferWithKeyValue ", "
+                    else
+                        bufferWithKeyValue
+
+                { buffer: bufferWithSuffix, fieldsLeft: fieldsLeft - 1 }
+
+            head = dbgWrite fmt "{ "
+            { buffer: withRecord } = List.walk fields { buffer: head, fieldsLeft: List.len fields } writeRecord
+
+            dbgWrite withRecord " }"
+
+dbgTag = \name, payload ->
+    custom \fmt ->
+        withName = dbgWrite fmt name
+
+        if List.isEmpty payload then
+            withName
+        else
+            List.walk payload withName dbgWritePayload
+            |> dbgCompound
+
+dbgOpaque = \name, inspector ->
+    custom \fmt ->
+        dbgWrite fmt "@\(name)"
+        |> dbgWritePayload inspector
+        |> dbgCompound
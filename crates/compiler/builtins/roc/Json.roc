@@ -59,6 +59,7 @@ Json := {} has [
              string: decodeString,
              list: decodeList,
              record: decodeRecord,
+             tag: decodeTag,
          },
      ]
 
@@ -404,6 +405,12 @@ recordKey = \bytes -> jsonString bytes
 anything : List U8 -> DecodeResult {}
 anything = \bytes -> { result: Err TooShort, rest: bytes }
 
+openBracket : List U8 -> DecodeResult {}
+openBracket = \bytes -> parseExactChar bytes (asciiByte '[')
+
+closingBracket : List U8 -> DecodeResult {}
+closingBracket = \bytes -> parseExactChar bytes (asciiByte ']')
+
 colon : List U8 -> DecodeResult {}
 colon = \bytes -> parseExactChar bytes (asciiByte ':')
 
@@ -447,3 +454,49 @@ decodeRecord = \initialState, stepField, finalizer -> Decode.custom \bytes, @Jso
         when finalizer endStateResult is
             Ok val -> { result: Ok val, rest: afterRecordBytes }
             Err e -> { result: Err e, rest: afterRecordBytes }
+
+decodeTag = \initialState, stepPayload, finalizer -> Decode.custom \bytes, @Json {} ->
+        # Mirrors `encodeTag`: decode `A v1 v2` from `{"A":[v1,v2]}`
+        # NB: the stepper function must be passed explicitly until #2894 is resolved.
+        decodePayload = \stepper, state, index, payloadBytes ->
+            when stepper state index is
+                TooLong ->
+                    { result: Err TooShort, rest: payloadBytes }
+
+                Next decoder ->
+                    { val: newState, rest: afterValueBytes } <- Decode.decodeWith payloadBytes decoder (@Json {}) |> tryDecode
+
+                    { result: commaResult, rest: nextBytes } = comma afterValueBytes
+
+                    when commaResult is
+                        Ok {} -> decodePayload stepper newState (index + 1) nextBytes
+                        Err _ -> { result: Ok newState, rest: nextBytes }
+
+        { rest: afterBraceBytes } <- bytes |> openBrace |> tryDecode
+
+        { val: name, rest: afterNameBytes } <- afterBraceBytes |> recordKey |> tryDecode
+
+        { rest: afterColonBytes } <- afterNameBytes |> colon |> tryDecode
+
+        { rest: afterBracketBytes } <- afterColonBytes |> openBracket |> tryDecode
+
+        when initialState name is
+            Skip ->
+                { result: Err TooShort, rest: afterBracketBytes }
+
+            Keep state ->
+                payloadResult =
+                    if List.first afterBracketBytes == Ok (asciiByte ']') then
+                        { result: Ok state, rest: afterBracketBytes }
+                    else
+                        decodePayload stepPayload state 0 afterBracketBytes
+
+                { val: endState, rest: beforeClosingBracketBytes } <- payloadResult |> tryDecode
+
+                { rest: afterPayloadBytes } <- beforeClosingBracketBytes |> closingBracket |> tryDecode
+
+                { rest: afterTagBytes } <- afterPayloadBytes |> closingBrace |> tryDecode
+
+                when finalizer endState is
+                    Ok val -> { result: Ok val, rest: afterTagBytes }
+                    Err e -> { result: Err e, rest: afterTagBytes }
@@ -295,6 +295,21 @@ pub const NUM_POW: IntrinsicName = float_intrinsic!("roc_builtins.num.pow");
 
 pub const NUM_POW_INT: IntrinsicName = int_intrinsic!("roc_builtins.num.pow_int");
 pub const NUM_DIV_CEIL: IntrinsicName = int_intrinsic!("roc_builtins.num.div_ceil");
+pub const NUM_DIV_TRUNC: IntrinsicName = int_intrinsic!("roc_builtins.num.div_trunc");
+pub const NUM_REM: IntrinsicName = int_intrinsic!("roc_builtins.num.rem");
+pub const NUM_IS_MULTIPLE_OF: IntrinsicName = int_intrinsic!("roc_builtins.num.is_multiple_of");
+pub const NUM_COMPARE: IntrinsicName = int_intrinsic!("roc_builtins.num.compare");
+pub const NUM_NEG: IntrinsicName = int_intrinsic!("roc_builtins.num.neg");
+pub const NUM_ABS: IntrinsicName = int_intrinsic!("roc_builtins.num.abs");
+pub const NUM_BITWISE_AND: IntrinsicName = int_intrinsic!("roc_builtins.num.bitwise_and");
+pub const NUM_BITWISE_OR: IntrinsicName = int_intrinsic!("roc_builtins.num.bitwise_or");
+pub const NUM_BITWISE_XOR: IntrinsicName = int_intrinsic!("roc_builtins.num.bitwise_xor");
+pub const NUM_SHIFT_LEFT_BY: IntrinsicName = int_intrinsic!("roc_builtins.num.shift_left_by");
+pub const NUM_SHIFT_RIGHT_BY: IntrinsicName = int_intrinsic!("roc_builtins.num.shift_right_by");
+pub const NUM_SHIFT_RIGHT_ZF_BY: IntrinsicName =
+    int_intrinsic!("roc_builtins.num.shift_right_zf_by");
+pub const NUM_INT_TO_F32: IntrinsicName = int_intrinsic!("roc_builtins.num.int_to_f32");
+pub const NUM_INT_TO_F64: IntrinsicName = int_intrinsic!("roc_builtins.num.int_to_f64");
 pub const NUM_ROUND_F32: IntrinsicName = int_intrinsic!("roc_builtins.num.round_f32");
 pub const NUM_ROUND_F64: IntrinsicName = int_intrinsic!("roc_builtins.num.round_f64");
 
@@ -350,6 +365,7 @@ pub const STR_RESERVE: &str = "roc_builtins.str.reserve";
 pub const STR_APPEND_SCALAR: &str = "roc_builtins.str.append_scalar";
 pub const STR_GET_SCALAR_UNSAFE: &str = "roc_builtins.str.get_scalar_unsafe";
 pub const STR_CLONE_TO: &str = "roc_builtins.str.clone_to";
+pub const STR_CRASH: &str = "roc_builtins.str.crash";
 
 pub const LIST_MAP: &str = "roc_builtins.list.map";
 pub const LIST_MAP2: &str = "roc_builtins.list.map2";
@@ -368,12 +384,18 @@ pub const LIST_PREPEND: &str = "roc_builtins.list.prepend";
 pub const LIST_APPEND_UNSAFE: &str = "roc_builtins.list.append_unsafe";
 pub const LIST_RESERVE: &str = "roc_builtins.list.reserve";
 
+pub const DICT_HASH_BYTES: &str = "roc_builtins.dict.hash_bytes";
+pub const DICT_HASH_MIX: &str = "roc_builtins.dict.hash_mix";
+
 pub const DEC_FROM_STR: &str = "roc_builtins.dec.from_str";
 pub const DEC_TO_STR: &str = "roc_builtins.dec.to_str";
 pub const DEC_FROM_F64: &str = "roc_builtins.dec.from_f64";
+pub const DEC_TO_F64: &str = "roc_builtins.dec.to_f64";
+pub const DEC_FROM_INT: IntrinsicName = int_intrinsic!("roc_builtins.dec.from_int");
 pub const DEC_EQ: &str = "roc_builtins.dec.eq";
 pub const DEC_NEQ: &str = "roc_builtins.dec.neq";
 pub const DEC_NEGATE: &str = "roc_builtins.dec.negate";
+pub const DEC_ABS: &str = "roc_builtins.dec.abs";
 pub const DEC_MUL_WITH_OVERFLOW: &str = "roc_builtins.dec.mul_with_overflow";
 pub const DEC_DIV: &str = "roc_builtins.dec.div";
 pub const DEC_ADD_WITH_OVERFLOW: &str = "roc_builtins.dec.add_with_overflow";
@@ -660,3 +660,340 @@ impl IAbilitiesStore<Pending> {
         }
     }
 }
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+struct AbilitiesStoreHeader {
+    members_of_ability: u64,
+    ability_member_symbols: u64,
+    specialization_to_root: u64,
+    ability_members: u64,
+    declared_implementations: u64,
+    specializations: u64,
+    specialization_lambda_sets: u64,
+    next_specialization_id: u64,
+    resolved_specializations: u64,
+}
+
+impl AbilitiesStoreHeader {
+    fn to_array(self) -> [u8; std::mem::size_of::<Self>()] {
+        // Safety: With repr(c) all fields are in order and properly aligned without padding.
+        unsafe { std::mem::transmute(self) }
+    }
+
+    fn from_array(array: [u8; std::mem::size_of::<Self>()]) -> Self {
+        // Safety: With repr(c) all fields are in order and properly aligned without padding.
+        unsafe { std::mem::transmute(array) }
+    }
+}
+
+// The serialized forms below only consist of 4-byte fields, so they have no padding bytes.
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SerializedAbility {
+    ability: Symbol,
+    members_start: u32,
+    members_len: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SerializedMemberData {
+    member: Symbol,
+    parent_ability: Symbol,
+    region: Region,
+    signature_var: Variable,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SerializedImpl {
+    impl_key: ImplKey,
+    /// 0 for [MemberImpl::Impl], 1 for [MemberImpl::Derived], 2 for [MemberImpl::Error].
+    tag: u32,
+    symbol: Symbol,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SerializedSpecialization {
+    symbol: Symbol,
+    lambda_sets_start: u32,
+    lambda_sets_len: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SerializedLambdaSet {
+    region: u32,
+    var: Variable,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct SerializedResolved {
+    id: u32,
+    specialization: Symbol,
+}
+
+fn round_to_multiple_of(value: usize, base: usize) -> usize {
+    (value + (base - 1)) / base * base
+}
+
+/// Writes the raw bytes of `slice`, after padding up to the alignment of `T`.
+fn serialize_slice<T: Copy>(
+    slice: &[T],
+    writer: &mut impl std::io::Write,
+    written: usize,
+) -> std::io::Result<usize> {
+    let padding_bytes = round_to_multiple_of(written, std::mem::align_of::<T>()) - written;
+    writer.write_all(&[0; 16][..padding_bytes])?;
+
+    // Safety: the serialized types are repr(C) and have no padding bytes.
+    let bytes = unsafe {
+        std::slice::from_raw_parts(slice.as_ptr() as *const u8, std::mem::size_of_val(slice))
+    };
+    writer.write_all(bytes)?;
+
+    Ok(written + padding_bytes + bytes.len())
+}
+
+/// Reads back `length` values written by [serialize_slice], or `None` if `bytes` are too short.
+fn deserialize_slice<T: Copy>(bytes: &[u8], length: usize, offset: usize) -> Option<(&[T], usize)> {
+    let offset = round_to_multiple_of(offset, std::mem::align_of::<T>());
+    let byte_length = length.checked_mul(std::mem::size_of::<T>())?;
+    let byte_slice = bytes.get(offset..)?.get(..byte_length)?;
+
+    // Safety: the bytes were written by [serialize_slice] for the same type.
+    let slice = unsafe { std::slice::from_raw_parts(byte_slice.as_ptr() as *const T, length) };
+
+    Some((slice, offset + byte_length))
+}
+
+impl IAbilitiesStore<Resolved> {
+    /// Writes a solved store in the same flat format [Subs](roc_types::subs::Subs) uses, so that
+    /// it can be cached on disk alongside the subs it refers to.
+    pub fn serialize(
+        &self,
+        writer: &mut impl std::io::Write,
+        written: usize,
+    ) -> std::io::Result<usize> {
+        let mut abilities = Vec::with_capacity(self.members_of_ability.len());
+        let mut ability_member_symbols = Vec::new();
+        for (ability, members) in self.members_of_ability.iter() {
+            abilities.push(SerializedAbility {
+                ability: *ability,
+                members_start: ability_member_symbols.len() as u32,
+                members_len: members.len() as u32,
+            });
+            ability_member_symbols.extend(members.iter().copied());
+        }
+
+        let specialization_to_root: Vec<(Symbol, ImplKey)> = self
+            .specialization_to_root
+            .iter()
+            .map(|(symbol, impl_key)| (*symbol, *impl_key))
+            .collect();
+
+        let ability_members: Vec<_> = self
+            .ability_members
+            .iter()
+            .map(|(member, data)| SerializedMemberData {
+                member: *member,
+                parent_ability: data.parent_ability,
+                region: data.region,
+                signature_var: data.signature_var(),
+            })
+            .collect();
+
+        let declared_implementations: Vec<_> = self
+            .declared_implementations
+            .iter()
+            .map(|(impl_key, member_impl)| {
+                let (tag, symbol) = match member_impl {
+                    MemberImpl::Impl(symbol) => (0, *symbol),
+                    MemberImpl::Derived => (1, impl_key.ability_member),
+                    MemberImpl::Error => (2, impl_key.ability_member),
+                };
+                SerializedImpl {
+                    impl_key: *impl_key,
+                    tag,
+                    symbol,
+                }
+            })
+            .collect();
+
+        let mut specializations = Vec::with_capacity(self.specializations.len());
+        let mut lambda_sets = Vec::new();
+        for (symbol, info) in self.specializations.iter() {
+            specializations.push(SerializedSpecialization {
+                symbol: *symbol,
+                lambda_sets_start: lambda_sets.len() as u32,
+                lambda_sets_len: info.specialization_lambda_sets.len() as u32,
+            });
+            lambda_sets.extend(info.specialization_lambda_sets.iter().map(|(region, var)| {
+                SerializedLambdaSet {
+                    region: *region as u32,
+                    var: *var,
+                }
+            }));
+        }
+
+        let resolved_specializations: Vec<_> = self
+            .resolved_specializations
+            .iter()
+            .map(|(id, specialization)| SerializedResolved {
+                id: id.0.get(),
+                specialization: *specialization,
+            })
+            .collect();
+
+        let header = AbilitiesStoreHeader {
+            members_of_ability: abilities.len() as u64,
+            ability_member_symbols: ability_member_symbols.len() as u64,
+            specialization_to_root: specialization_to_root.len() as u64,
+            ability_members: ability_members.len() as u64,
+            declared_implementations: declared_implementations.len() as u64,
+            specializations: specializations.len() as u64,
+            specialization_lambda_sets: lambda_sets.len() as u64,
+            next_specialization_id: self.next_specialization_id.get() as u64,
+            resolved_specializations: resolved_specializations.len() as u64,
+        };
+
+        let mut written = serialize_slice(&header.to_array(), writer, written)?;
+
+        written = serialize_slice(&abilities, writer, written)?;
+        written = serialize_slice(&ability_member_symbols, writer, written)?;
+        written = serialize_slice(&specialization_to_root, writer, written)?;
+        written = serialize_slice(&ability_members, writer, written)?;
+        written = serialize_slice(&declared_implementations, writer, written)?;
+        written = serialize_slice(&specializations, writer, written)?;
+        written = serialize_slice(&lambda_sets, writer, written)?;
+        written = serialize_slice(&resolved_specializations, writer, written)?;
+
+        Ok(written)
+    }
+
+    /// Reads back a store written by [`Self::serialize`], starting at `offset` in `bytes`.
+    /// Returns the store and the offset just past its last byte, or `None` if `bytes` do not
+    /// hold a well-formed store.
+    pub fn deserialize(bytes: &[u8], offset: usize) -> Option<(Self, usize)> {
+        const HEADER_SIZE: usize = std::mem::size_of::<AbilitiesStoreHeader>();
+
+        let (header_slice, offset) = deserialize_slice::<u8>(bytes, HEADER_SIZE, offset)?;
+        let header = AbilitiesStoreHeader::from_array(header_slice.try_into().unwrap());
+
+        let (abilities, offset) = deserialize_slice::<SerializedAbility>(
+            bytes,
+            header.members_of_ability as usize,
+            offset,
+        )?;
+        let (ability_member_symbols, offset) =
+            deserialize_slice::<Symbol>(bytes, header.ability_member_symbols as usize, offset)?;
+        let (specialization_to_root, offset) = deserialize_slice::<(Symbol, ImplKey)>(
+            bytes,
+            header.specialization_to_root as usize,
+            offset,
+        )?;
+        let (ability_members, offset) = deserialize_slice::<SerializedMemberData>(
+            bytes,
+            header.ability_members as usize,
+            offset,
+        )?;
+        let (declared_implementations, offset) = deserialize_slice::<SerializedImpl>(
+            bytes,
+            header.declared_implementations as usize,
+            offset,
+        )?;
+        let (specializations, offset) = deserialize_slice::<SerializedSpecialization>(
+            bytes,
+            header.specializations as usize,
+            offset,
+        )?;
+        let (lambda_sets, offset) = deserialize_slice::<SerializedLambdaSet>(
+            bytes,
+            header.specialization_lambda_sets as usize,
+            offset,
+        )?;
+        let (resolved_specializations, offset) = deserialize_slice::<SerializedResolved>(
+            bytes,
+            header.resolved_specializations as usize,
+            offset,
+        )?;
+
+        let members_of_ability = abilities
+            .iter()
+            .map(|ability| {
+                let start = ability.members_start as usize;
+                let members = ability_member_symbols
+                    .get(start..)?
+                    .get(..ability.members_len as usize)?;
+                Some((ability.ability, members.to_vec()))
+            })
+            .collect::<Option<_>>()?;
+
+        let ability_members = ability_members
+            .iter()
+            .map(|data| {
+                let member_data = AbilityMemberData {
+                    parent_ability: data.parent_ability,
+                    region: data.region,
+                    typ: ResolvedMemberType(data.signature_var),
+                };
+                (data.member, member_data)
+            })
+            .collect();
+
+        let declared_implementations = declared_implementations
+            .iter()
+            .map(|serialized| {
+                let member_impl = match serialized.tag {
+                    0 => MemberImpl::Impl(serialized.symbol),
+                    1 => MemberImpl::Derived,
+                    2 => MemberImpl::Error,
+                    _ => return None,
+                };
+                Some((serialized.impl_key, member_impl))
+            })
+            .collect::<Option<_>>()?;
+
+        let specializations = specializations
+            .iter()
+            .map(|specialization| {
+                let start = specialization.lambda_sets_start as usize;
+                let specialization_lambda_sets = lambda_sets
+                    .get(start..)?
+                    .get(..specialization.lambda_sets_len as usize)?
+                    .iter()
+                    .map(|lambda_set| (lambda_set.region as u8, lambda_set.var))
+                    .collect();
+                let info = MemberSpecializationInfo::new(
+                    specialization.symbol,
+                    specialization_lambda_sets,
+                );
+                Some((specialization.symbol, info))
+            })
+            .collect::<Option<_>>()?;
+
+        let resolved_specializations = resolved_specializations
+            .iter()
+            .map(|resolved| {
+                let id = SpecializationId(NonZeroU32::new(resolved.id)?);
+                Some((id, resolved.specialization))
+            })
+            .collect::<Option<_>>()?;
+
+        let store = Self {
+            members_of_ability,
+            specialization_to_root: specialization_to_root.iter().copied().collect(),
+            ability_members,
+            declared_implementations,
+            specializations,
+            next_specialization_id: NonZeroU32::new(header.next_specialization_id as u32)?,
+            resolved_specializations,
+        };
+
+        Some((store, offset))
+    }
+}
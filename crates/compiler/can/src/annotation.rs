@@ -443,6 +443,15 @@ pub fn find_type_def_symbols(
                     stack.push(&t.value);
                 }
             }
+            Tuple { elems, ext } => {
+                for t in elems.iter() {
+                    stack.push(&t.value);
+                }
+
+                for t in ext.iter() {
+                    stack.push(&t.value);
+                }
+            }
             SpaceBefore(inner, _) | SpaceAfter(inner, _) => {
                 stack.push(inner);
             }
@@ -855,6 +864,39 @@ fn can_annotation_help(
                 Type::TagUnion(tag_types, TypeExtension::from_type(ext_type))
             }
         }
+        Tuple { elems, ext } => {
+            let ext_type = can_extension_type(
+                env,
+                scope,
+                var_store,
+                introduced_variables,
+                local_aliases,
+                references,
+                ext,
+                roc_problem::can::ExtensionTypeKind::Tuple,
+            );
+
+            let elem_types = elems
+                .iter()
+                .enumerate()
+                .map(|(index, loc_elem)| {
+                    let elem_type = can_annotation_help(
+                        env,
+                        &loc_elem.value,
+                        loc_elem.region,
+                        scope,
+                        var_store,
+                        introduced_variables,
+                        local_aliases,
+                        references,
+                    );
+
+                    (index, elem_type)
+                })
+                .collect();
+
+            Type::Tuple(elem_types, TypeExtension::from_type(ext_type))
+        }
         SpaceBefore(nested, _) | SpaceAfter(nested, _) => can_annotation_help(
             env,
             nested,
@@ -994,12 +1036,22 @@ fn can_extension_type<'a>(
             Type::EmptyTagUnion | Type::TagUnion(..) | Type::Variable(..) | Type::Erroneous(..)
         )
     }
+    fn valid_tuple_ext_type(typ: &Type) -> bool {
+        matches!(
+            typ,
+            Type::Tuple(..) | Type::Variable(..) | Type::Erroneous(..)
+        )
+    }
 
     use roc_problem::can::ExtensionTypeKind;
 
     let (empty_ext_type, valid_extension_type): (_, fn(&Type) -> bool) = match ext_problem_kind {
         ExtensionTypeKind::Record => (Type::EmptyRec, valid_record_ext_type),
         ExtensionTypeKind::TagUnion => (Type::EmptyTagUnion, valid_tag_ext_type),
+        ExtensionTypeKind::Tuple => (
+            Type::Tuple(Default::default(), TypeExtension::Closed),
+            valid_tuple_ext_type,
+        ),
     };
 
     match opt_ext {
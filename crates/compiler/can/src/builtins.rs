@@ -143,6 +143,9 @@ map_symbol_to_lowlevel_and_arity! {
     ListSublist; LIST_SUBLIST_LOWLEVEL; 3,
     ListDropAt; LIST_DROP_AT; 2,
     ListSwap; LIST_SWAP; 3,
+
+    DictHashBytes; DICT_HASH_BYTES_LOWLEVEL; 2,
+    DictHashMix; DICT_HASH_MIX_LOWLEVEL; 2,
     ListGetCapacity; LIST_CAPACITY; 1,
 
     NumAdd; NUM_ADD; 2,
@@ -13,7 +13,8 @@ use crate::expr::AnnotatedMark;
 use crate::expr::ClosureData;
 use crate::expr::Declarations;
 use crate::expr::Expr::{self, *};
-use crate::expr::{canonicalize_expr, Output, Recursive};
+use crate::expr::TupleAccessorData;
+use crate::expr::{canonicalize_expr, tuple_index, Output, Recursive};
 use crate::pattern::{canonicalize_def_header_pattern, BindingsFromPattern, Pattern};
 use crate::procedure::References;
 use crate::scope::create_alias;
@@ -1811,6 +1812,26 @@ fn pattern_to_vars_by_symbol(
             }
         }
 
+        TupleDestructure { destructs, .. } => {
+            for destruct in destructs {
+                let (var, nested) = &destruct.value.typ;
+                pattern_to_vars_by_symbol(vars_by_symbol, &nested.value, *var);
+            }
+        }
+
+        List {
+            patterns, elem_var, ..
+        } => {
+            for pat in patterns.patterns.iter() {
+                pattern_to_vars_by_symbol(vars_by_symbol, &pat.value, *elem_var);
+            }
+
+            // the rest of a list has the same type as the whole list
+            if let Some((_, Some(rest_sym))) = &patterns.opt_rest {
+                vars_by_symbol.insert(rest_sym.value, expr_var);
+            }
+        }
+
         NumLiteral(..)
         | IntLiteral(..)
         | FloatLiteral(..)
@@ -2139,6 +2160,36 @@ fn canonicalize_pending_body<'a>(
                 (loc_can_expr, def_references)
             }
 
+            // Turn f = .1 into f = \tup -[f]-> tup.1
+            (
+                Pattern::Identifier(defined_symbol)
+                | Pattern::AbilityMemberSpecialization {
+                    ident: defined_symbol,
+                    ..
+                },
+                ast::Expr::TupleAccessorFunction(index),
+            ) => {
+                let (loc_can_expr, can_output) = (
+                    Loc::at(
+                        loc_expr.region,
+                        TupleAccessor(TupleAccessorData {
+                            name: *defined_symbol,
+                            function_var: var_store.fresh(),
+                            tuple_var: var_store.fresh(),
+                            ext_var: var_store.fresh(),
+                            closure_var: var_store.fresh(),
+                            elem_var: var_store.fresh(),
+                            index: tuple_index(index),
+                        }),
+                    ),
+                    Output::default(),
+                );
+                let def_references = DefReferences::Value(can_output.references.clone());
+                output.union(can_output);
+
+                (loc_can_expr, def_references)
+            }
+
             _ => {
                 let (loc_can_expr, can_output) =
                     canonicalize_expr(env, var_store, scope, loc_expr.region, &loc_expr.value);
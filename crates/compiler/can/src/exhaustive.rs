@@ -3,7 +3,7 @@ use crate::pattern::DestructType;
 use roc_collections::all::HumanIndex;
 use roc_error_macros::internal_error;
 use roc_exhaustive::{
-    is_useful, Ctor, CtorName, Error, Guard, Literal, Pattern, RenderAs, TagId, Union,
+    is_useful, Ctor, CtorName, Error, Guard, ListArity, Literal, Pattern, RenderAs, TagId, Union,
 };
 use roc_module::ident::{TagIdIntType, TagName};
 use roc_region::all::{Loc, Region};
@@ -57,6 +57,7 @@ enum SketchedPattern {
     Literal(Literal),
     Ctor(Variable, TagName, Vec<SketchedPattern>),
     KnownCtor(Union, TagId, Vec<SketchedPattern>),
+    List(ListArity, Vec<SketchedPattern>),
 }
 
 impl SketchedPattern {
@@ -77,6 +78,10 @@ impl SketchedPattern {
                     patterns.into_iter().map(|pat| pat.reify(subs)).collect(),
                 )
             }
+            Self::List(arity, patterns) => Pattern::List(
+                arity,
+                patterns.into_iter().map(|pat| pat.reify(subs)).collect(),
+            ),
         }
     }
 }
@@ -149,6 +154,45 @@ fn sketch_pattern(var: Variable, pattern: &crate::pattern::Pattern) -> SketchedP
             SP::KnownCtor(union, tag_id, patterns)
         }
 
+        TupleDestructure { destructs, .. } => {
+            let tag_id = TagId(0);
+            let mut patterns = std::vec::Vec::with_capacity(destructs.len());
+
+            for Loc {
+                value: destruct,
+                region: _,
+            } in destructs
+            {
+                let (var, guard) = &destruct.typ;
+                patterns.push(sketch_pattern(*var, &guard.value));
+            }
+
+            let union = Union {
+                render_as: RenderAs::Tuple,
+                alternatives: vec![Ctor {
+                    name: CtorName::Tag(TagName("#Tuple".into())),
+                    tag_id,
+                    arity: destructs.len(),
+                }],
+            };
+
+            SP::KnownCtor(union, tag_id, patterns)
+        }
+
+        List {
+            patterns, elem_var, ..
+        } => {
+            let arity = patterns.arity();
+
+            let sketched_elem_patterns = patterns
+                .patterns
+                .iter()
+                .map(|p| sketch_pattern(*elem_var, &p.value))
+                .collect();
+
+            SP::List(arity, sketched_elem_patterns)
+        }
+
         AppliedTag {
             tag_name,
             arguments,
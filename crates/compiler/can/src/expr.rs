@@ -175,6 +175,22 @@ pub enum Expr {
     /// field accessor as a function, e.g. (.foo) expr
     Accessor(AccessorData),
 
+    Tuple {
+        tuple_var: Variable,
+        elems: Vec<(Variable, Box<Loc<Expr>>)>,
+    },
+
+    /// Look up exactly one element on a tuple, e.g. (expr).1.
+    TupleAccess {
+        tuple_var: Variable,
+        ext_var: Variable,
+        elem_var: Variable,
+        loc_expr: Box<Loc<Expr>>,
+        index: usize,
+    },
+    /// tuple element accessor as a function, e.g. (.1) expr
+    TupleAccessor(TupleAccessorData),
+
     Update {
         record_var: Variable,
         ext_var: Variable,
@@ -231,6 +247,18 @@ pub enum Expr {
         lookups_in_cond: Vec<(Symbol, Variable)>,
     },
 
+    /// Reports the value of an expression to the host, then evaluates to it
+    Dbg {
+        loc_expr: Box<Loc<Expr>>,
+        variable: Variable,
+    },
+
+    /// Aborts the program with a message; it diverges, so it may stand in for any type
+    Crash {
+        msg: Box<Loc<Expr>>,
+        ret_var: Variable,
+    },
+
     /// Rendered as empty box in editor
     TypedHole(Variable),
 
@@ -261,6 +289,9 @@ impl Expr {
             Self::EmptyRecord => Category::Record,
             Self::Access { field, .. } => Category::Access(field.clone()),
             Self::Accessor(data) => Category::Accessor(data.field.clone()),
+            Self::Tuple { .. } => Category::Tuple,
+            Self::TupleAccess { index, .. } => Category::TupleAccess(*index),
+            Self::TupleAccessor(data) => Category::TupleAccessor(data.index),
             Self::Update { .. } => Category::Record,
             Self::Tag {
                 name, arguments, ..
@@ -277,9 +308,10 @@ impl Expr {
                 Category::OpaqueWrap(opaque_name)
             }
             Self::Expect { .. } => Category::Expect,
+            Self::Dbg { loc_expr, .. } => loc_expr.value.category(),
 
             // these nodes place no constraints on the expression's type
-            Self::TypedHole(_) | Self::RuntimeError(..) => Category::Unknown,
+            Self::TypedHole(_) | Self::RuntimeError(..) | Self::Crash { .. } => Category::Unknown,
         }
     }
 }
@@ -387,6 +419,71 @@ impl AccessorData {
     }
 }
 
+/// A tuple element accessor like `.1`, which is equivalent to `\t -> t.1`
+/// Tuple accessors are desugared to closures; they need to have a name
+/// so the closure can have a correct lambda set.
+///
+/// We distinguish them from closures so we can have better error messages
+/// during constraint generation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TupleAccessorData {
+    pub name: Symbol,
+    pub function_var: Variable,
+    pub tuple_var: Variable,
+    pub closure_var: Variable,
+    pub ext_var: Variable,
+    pub elem_var: Variable,
+    pub index: usize,
+}
+
+impl TupleAccessorData {
+    pub fn to_closure_data(self, tuple_symbol: Symbol) -> ClosureData {
+        let TupleAccessorData {
+            name,
+            function_var,
+            tuple_var,
+            closure_var,
+            ext_var,
+            elem_var,
+            index,
+        } = self;
+
+        // IDEA: convert accessor from
+        //
+        // .1
+        //
+        // into
+        //
+        // (\t -> t.1)
+        let body = Expr::TupleAccess {
+            tuple_var,
+            ext_var,
+            elem_var,
+            loc_expr: Box::new(Loc::at_zero(Expr::Var(tuple_symbol))),
+            index,
+        };
+
+        let loc_body = Loc::at_zero(body);
+
+        let arguments = vec![(
+            tuple_var,
+            AnnotatedMark::known_exhaustive(),
+            Loc::at_zero(Pattern::Identifier(tuple_symbol)),
+        )];
+
+        ClosureData {
+            function_type: function_var,
+            closure_type: closure_var,
+            return_type: elem_var,
+            name,
+            captured_symbols: vec![],
+            recursive: Recursive::NotRecursive,
+            arguments,
+            loc_body: Box::new(loc_body),
+        }
+    }
+}
+
 /// An opaque wrapper like `@Foo`, which is equivalent to `\p -> @Foo p`
 /// These are desugared to closures, but we distinguish them so we can have
 /// better error messages during constraint generation.
@@ -683,6 +780,33 @@ pub fn canonicalize_expr<'a>(
                 )
             }
         }
+        ast::Expr::Tuple(loc_elems) => {
+            let mut can_elems = Vec::with_capacity(loc_elems.len());
+            let mut references = References::new();
+
+            for loc_elem in loc_elems.iter() {
+                let (can_expr, elem_out) =
+                    canonicalize_expr(env, var_store, scope, loc_elem.region, &loc_elem.value);
+
+                references.union_mut(&elem_out.references);
+
+                can_elems.push((var_store.fresh(), Box::new(can_expr)));
+            }
+
+            let output = Output {
+                references,
+                tail_call: None,
+                ..Default::default()
+            };
+
+            (
+                Tuple {
+                    tuple_var: var_store.fresh(),
+                    elems: can_elems,
+                },
+                output,
+            )
+        }
         ast::Expr::Apply(loc_fn, loc_args, application_style) => {
             // The expression that evaluates to the function being called, e.g. `foo` in
             // (foo) bar baz
@@ -916,6 +1040,32 @@ pub fn canonicalize_expr<'a>(
             }),
             Output::default(),
         ),
+        ast::Expr::TupleAccess(tuple_expr, index) => {
+            let (loc_expr, output) = canonicalize_expr(env, var_store, scope, region, tuple_expr);
+
+            (
+                TupleAccess {
+                    tuple_var: var_store.fresh(),
+                    elem_var: var_store.fresh(),
+                    ext_var: var_store.fresh(),
+                    loc_expr: Box::new(loc_expr),
+                    index: tuple_index(index),
+                },
+                output,
+            )
+        }
+        ast::Expr::TupleAccessorFunction(index) => (
+            TupleAccessor(TupleAccessorData {
+                name: scope.gen_unique_symbol(),
+                function_var: var_store.fresh(),
+                tuple_var: var_store.fresh(),
+                ext_var: var_store.fresh(),
+                closure_var: var_store.fresh(),
+                elem_var: var_store.fresh(),
+                index: tuple_index(index),
+            }),
+            Output::default(),
+        ),
         ast::Expr::Tag(tag) => {
             let variant_var = var_store.fresh();
             let ext_var = var_store.fresh();
@@ -998,6 +1148,30 @@ pub fn canonicalize_expr<'a>(
                 output,
             )
         }
+        ast::Expr::Dbg(message) => {
+            let (loc_expr, output) =
+                canonicalize_expr(env, var_store, scope, message.region, &message.value);
+
+            (
+                Dbg {
+                    loc_expr: Box::new(loc_expr),
+                    variable: var_store.fresh(),
+                },
+                output,
+            )
+        }
+        ast::Expr::Crash(message) => {
+            let (loc_msg, output) =
+                canonicalize_expr(env, var_store, scope, message.region, &message.value);
+
+            (
+                Crash {
+                    msg: Box::new(loc_msg),
+                    ret_var: var_store.fresh(),
+                },
+                output,
+            )
+        }
         ast::Expr::If(if_thens, final_else_branch) => {
             let mut branches = Vec::with_capacity(if_thens.len());
             let mut output = Output::default();
@@ -1638,6 +1812,7 @@ pub fn inline_calls(var_store: &mut VarStore, scope: &mut Scope, expr: Expr) ->
         | other @ RuntimeError(_)
         | other @ EmptyRecord
         | other @ Accessor { .. }
+        | other @ TupleAccessor { .. }
         | other @ Update { .. }
         | other @ Var(_)
         | other @ AbilityMember(..)
@@ -1772,6 +1947,30 @@ pub fn inline_calls(var_store: &mut VarStore, scope: &mut Scope, expr: Expr) ->
             }
         }
 
+        Dbg { loc_expr, variable } => {
+            let loc_expr = Loc {
+                region: loc_expr.region,
+                value: inline_calls(var_store, scope, loc_expr.value),
+            };
+
+            Dbg {
+                loc_expr: Box::new(loc_expr),
+                variable,
+            }
+        }
+
+        Crash { msg, ret_var } => {
+            let msg = Loc {
+                region: msg.region,
+                value: inline_calls(var_store, scope, msg.value),
+            };
+
+            Crash {
+                msg: Box::new(msg),
+                ret_var,
+            }
+        }
+
         LetRec(defs, loc_expr, mark) => {
             let mut new_defs = Vec::with_capacity(defs.len());
 
@@ -1862,6 +2061,37 @@ pub fn inline_calls(var_store: &mut VarStore, scope: &mut Scope, expr: Expr) ->
             todo!("Inlining for Access with record_var {:?}, ext_var {:?}, field_var {:?}, loc_expr {:?}, field {:?}", record_var, ext_var, field_var, loc_expr, field);
         }
 
+        Tuple { tuple_var, elems } => {
+            let elems = elems
+                .into_iter()
+                .map(|(var, loc_elem)| {
+                    let value = inline_calls(var_store, scope, loc_elem.value);
+
+                    (var, Box::new(Loc::at(loc_elem.region, value)))
+                })
+                .collect();
+
+            Tuple { tuple_var, elems }
+        }
+
+        TupleAccess {
+            tuple_var,
+            ext_var,
+            elem_var,
+            loc_expr,
+            index,
+        } => {
+            let value = inline_calls(var_store, scope, loc_expr.value);
+
+            TupleAccess {
+                tuple_var,
+                ext_var,
+                elem_var,
+                loc_expr: Box::new(Loc::at(loc_expr.region, value)),
+                index,
+            }
+        }
+
         Tag {
             tag_union_var: variant_var,
             ext_var,
@@ -2017,6 +2247,12 @@ pub fn is_valid_interpolation(expr: &ast::Expr<'_>) -> bool {
     }
 }
 
+/// The parser only accepts ASCII digits as tuple indices; an index too large to fit in a
+/// `usize` can never be in bounds, so it saturates and is reported as a type mismatch.
+pub(crate) fn tuple_index(index: &str) -> usize {
+    index.parse().unwrap_or(usize::MAX)
+}
+
 enum StrSegment {
     Interpolation(Loc<Expr>),
     Plaintext(Box<str>),
@@ -2491,9 +2727,10 @@ impl Declarations {
             })
     }
 
-    pub fn expects(&self) -> VecMap<Region, Vec<(Symbol, Variable)>> {
+    pub fn expects(&self) -> ExpectCollector {
         let mut collector = ExpectCollector {
             expects: VecMap::default(),
+            dbgs: VecMap::default(),
         };
 
         let var = Variable::EMPTY_RECORD;
@@ -2526,7 +2763,7 @@ impl Declarations {
             }
         }
 
-        collector.expects
+        collector
     }
 }
 
@@ -2648,6 +2885,9 @@ fn get_lookup_symbols(expr: &Expr, var_store: &mut VarStore) -> Vec<(Symbol, Var
                 stack.push(&argument.1.value);
             }
             Expr::Access { loc_expr, .. }
+            | Expr::TupleAccess { loc_expr, .. }
+            | Expr::Dbg { loc_expr, .. }
+            | Expr::Crash { msg: loc_expr, .. }
             | Expr::Closure(ClosureData {
                 loc_body: loc_expr, ..
             }) => {
@@ -2656,6 +2896,9 @@ fn get_lookup_symbols(expr: &Expr, var_store: &mut VarStore) -> Vec<(Symbol, Var
             Expr::Record { fields, .. } => {
                 stack.extend(fields.iter().map(|(_, field)| &field.loc_expr.value));
             }
+            Expr::Tuple { elems, .. } => {
+                stack.extend(elems.iter().map(|(_, loc_elem)| &loc_elem.value));
+            }
             Expr::Expect {
                 loc_continuation, ..
             } => {
@@ -2670,6 +2913,7 @@ fn get_lookup_symbols(expr: &Expr, var_store: &mut VarStore) -> Vec<(Symbol, Var
             | Expr::Str(_)
             | Expr::ZeroArgumentTag { .. }
             | Expr::Accessor(_)
+            | Expr::TupleAccessor(_)
             | Expr::SingleQuote(_)
             | Expr::EmptyRecord
             | Expr::TypedHole(_)
@@ -2758,20 +3002,27 @@ pub fn toplevel_expect_to_inline_expect(mut loc_expr: Loc<Expr>) -> Loc<Expr> {
     loc_expr
 }
 
-struct ExpectCollector {
-    expects: VecMap<Region, Vec<(Symbol, Variable)>>,
+/// The `expect`s and `dbg`s of a module, keyed by the region that is reported when they run
+pub struct ExpectCollector {
+    pub expects: VecMap<Region, Vec<(Symbol, Variable)>>,
+    pub dbgs: VecMap<Region, Variable>,
 }
 
 impl crate::traverse::Visitor for ExpectCollector {
     fn visit_expr(&mut self, expr: &Expr, _region: Region, var: Variable) {
-        if let Expr::Expect {
-            lookups_in_cond,
-            loc_condition,
-            ..
-        } = expr
-        {
-            self.expects
-                .insert(loc_condition.region, lookups_in_cond.to_vec());
+        match expr {
+            Expr::Expect {
+                lookups_in_cond,
+                loc_condition,
+                ..
+            } => {
+                self.expects
+                    .insert(loc_condition.region, lookups_in_cond.to_vec());
+            }
+            Expr::Dbg { loc_expr, variable } => {
+                self.dbgs.insert(loc_expr.region, *variable);
+            }
+            _ => {}
         }
 
         walk_expr(self, expr, var)
@@ -131,6 +131,7 @@ pub struct Module {
     pub rigid_variables: RigidVariables,
     pub abilities_store: PendingAbilitiesStore,
     pub loc_expects: VecMap<Region, Vec<(Symbol, Variable)>>,
+    pub loc_dbgs: VecMap<Region, Variable>,
 }
 
 #[derive(Debug, Default)]
@@ -154,6 +155,7 @@ pub struct ModuleOutput {
     pub pending_derives: PendingDerives,
     pub scope: Scope,
     pub loc_expects: VecMap<Region, Vec<(Symbol, Variable)>>,
+    pub loc_dbgs: VecMap<Region, Variable>,
 }
 
 fn validate_generate_with<'a>(
@@ -782,7 +784,7 @@ pub fn canonicalize_module_defs<'a>(
         }
     }
 
-    let loc_expects = declarations.expects();
+    let collected = declarations.expects();
 
     ModuleOutput {
         scope,
@@ -796,7 +798,8 @@ pub fn canonicalize_module_defs<'a>(
         symbols_from_requires,
         pending_derives,
         lookups,
-        loc_expects,
+        loc_expects: collected.expects,
+        loc_dbgs: collected.dbgs,
     }
 }
 
@@ -905,6 +908,24 @@ fn fix_values_captured_in_closure_pattern(
                 }
             }
         }
+        TupleDestructure { destructs, .. } => {
+            for loc_destruct in destructs.iter_mut() {
+                fix_values_captured_in_closure_pattern(
+                    &mut loc_destruct.value.typ.1.value,
+                    no_capture_symbols,
+                    closure_captures,
+                )
+            }
+        }
+        List { patterns, .. } => {
+            for loc_pat in patterns.patterns.iter_mut() {
+                fix_values_captured_in_closure_pattern(
+                    &mut loc_pat.value,
+                    no_capture_symbols,
+                    closure_captures,
+                );
+            }
+        }
         Identifier(_)
         | NumLiteral(..)
         | IntLiteral(..)
@@ -964,6 +985,14 @@ fn fix_values_captured_in_closure_expr(
             );
         }
 
+        Dbg { loc_expr, .. } | Crash { msg: loc_expr, .. } => {
+            fix_values_captured_in_closure_expr(
+                &mut loc_expr.value,
+                no_capture_symbols,
+                closure_captures,
+            );
+        }
+
         Closure(ClosureData {
             captured_symbols,
             name,
@@ -1039,7 +1068,8 @@ fn fix_values_captured_in_closure_expr(
         | TypedHole { .. }
         | RuntimeError(_)
         | ZeroArgumentTag { .. }
-        | Accessor { .. } => {}
+        | Accessor { .. }
+        | TupleAccessor { .. } => {}
 
         List { loc_elems, .. } => {
             for elem in loc_elems.iter_mut() {
@@ -1145,7 +1175,7 @@ fn fix_values_captured_in_closure_expr(
             }
         }
 
-        Access { loc_expr, .. } => {
+        Access { loc_expr, .. } | TupleAccess { loc_expr, .. } => {
             fix_values_captured_in_closure_expr(
                 &mut loc_expr.value,
                 no_capture_symbols,
@@ -1153,6 +1183,16 @@ fn fix_values_captured_in_closure_expr(
             );
         }
 
+        Tuple { elems, .. } => {
+            for (_, loc_elem) in elems.iter_mut() {
+                fix_values_captured_in_closure_expr(
+                    &mut loc_elem.value,
+                    no_capture_symbols,
+                    closure_captures,
+                );
+            }
+        }
+
         Tag { arguments, .. } => {
             for (_, loc_arg) in arguments.iter_mut() {
                 fix_values_captured_in_closure_expr(
@@ -121,6 +121,7 @@ pub fn desugar_expr<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc
         | Str(_)
         | SingleQuote(_)
         | AccessorFunction(_)
+        | TupleAccessorFunction(_)
         | Var { .. }
         | Underscore { .. }
         | MalformedIdent(_, _)
@@ -139,6 +140,16 @@ pub fn desugar_expr<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc
 
             arena.alloc(Loc { region, value })
         }
+        TupleAccess(sub_expr, index) => {
+            let region = loc_expr.region;
+            let loc_sub_expr = Loc {
+                region,
+                value: **sub_expr,
+            };
+            let value = TupleAccess(&desugar_expr(arena, arena.alloc(loc_sub_expr)).value, index);
+
+            arena.alloc(Loc { region, value })
+        }
         List(items) => {
             let mut new_items = Vec::with_capacity_in(items.len(), arena);
 
@@ -153,6 +164,20 @@ pub fn desugar_expr<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc
                 value,
             })
         }
+        Tuple(items) => {
+            let mut new_items = Vec::with_capacity_in(items.len(), arena);
+
+            for item in items.iter() {
+                new_items.push(desugar_expr(arena, item));
+            }
+            let new_items = new_items.into_bump_slice();
+            let value: Expr<'a> = Tuple(items.replace_items(new_items));
+
+            arena.alloc(Loc {
+                region: loc_expr.region,
+                value,
+            })
+        }
         Record(fields) => arena.alloc(Loc {
             region: loc_expr.region,
             value: Record(fields.map_items(arena, |field| {
@@ -344,6 +369,20 @@ pub fn desugar_expr<'a>(arena: &'a Bump, loc_expr: &'a Loc<Expr<'a>>) -> &'a Loc
                 region: loc_expr.region,
             })
         }
+        Dbg(message) => {
+            let desugared_message = &*arena.alloc(desugar_expr(arena, message));
+            arena.alloc(Loc {
+                value: Dbg(desugared_message),
+                region: loc_expr.region,
+            })
+        }
+        Crash(message) => {
+            let desugared_message = &*arena.alloc(desugar_expr(arena, message));
+            arena.alloc(Loc {
+                value: Crash(desugared_message),
+                region: loc_expr.region,
+            })
+        }
     }
 }
 
@@ -6,9 +6,10 @@ use crate::num::{
     ParsedNumResult,
 };
 use crate::scope::{PendingAbilitiesInScope, Scope};
+use roc_exhaustive::ListArity;
 use roc_module::ident::{Ident, Lowercase, TagName};
 use roc_module::symbol::Symbol;
-use roc_parse::ast::{self, StrLiteral, StrSegment};
+use roc_parse::ast::{self, ExtractSpaces, StrLiteral, StrSegment};
 use roc_parse::pattern::PatternType;
 use roc_problem::can::{MalformedPatternProblem, Problem, RuntimeError, ShadowKind};
 use roc_region::all::{Loc, Region};
@@ -55,6 +56,16 @@ pub enum Pattern {
         ext_var: Variable,
         destructs: Vec<Loc<RecordDestruct>>,
     },
+    TupleDestructure {
+        whole_var: Variable,
+        ext_var: Variable,
+        destructs: Vec<Loc<TupleDestruct>>,
+    },
+    List {
+        list_var: Variable,
+        elem_var: Variable,
+        patterns: ListPatterns,
+    },
     NumLiteral(Variable, Box<str>, IntValue, NumBound),
     IntLiteral(Variable, Variable, Box<str>, IntValue, IntBound),
     FloatLiteral(Variable, Variable, Box<str>, f64, FloatBound),
@@ -91,6 +102,8 @@ impl Pattern {
             AppliedTag { whole_var, .. } => Some(*whole_var),
             UnwrappedOpaque { whole_var, .. } => Some(*whole_var),
             RecordDestructure { whole_var, .. } => Some(*whole_var),
+            TupleDestructure { whole_var, .. } => Some(*whole_var),
+            List { list_var, .. } => Some(*list_var),
             NumLiteral(var, ..) => Some(*var),
             IntLiteral(var, ..) => Some(*var),
             FloatLiteral(var, ..) => Some(*var),
@@ -118,6 +131,10 @@ impl Pattern {
             | MalformedPattern(..)
             | AbilityMemberSpecialization { .. } => true,
             RecordDestructure { destructs, .. } => destructs.is_empty(),
+            TupleDestructure { destructs, .. } => destructs
+                .iter()
+                .all(|d| d.value.typ.1.value.surely_exhaustive()),
+            List { patterns, .. } => patterns.surely_exhaustive(),
             AppliedTag { .. }
             | NumLiteral(..)
             | IntLiteral(..)
@@ -144,6 +161,8 @@ impl Pattern {
             UnwrappedOpaque { opaque, .. } => C::Opaque(*opaque),
             RecordDestructure { destructs, .. } if destructs.is_empty() => C::EmptyRecord,
             RecordDestructure { .. } => C::Record,
+            TupleDestructure { .. } => C::Tuple,
+            List { .. } => C::List,
             NumLiteral(..) => C::Num,
             IntLiteral(..) => C::Int,
             FloatLiteral(..) => C::Float,
@@ -160,6 +179,37 @@ impl Pattern {
     }
 }
 
+#[derive(Clone, Debug)]
+pub struct ListPatterns {
+    pub patterns: Vec<Loc<Pattern>>,
+    /// Where a rest pattern splits patterns before and after it, if it does at all.
+    /// If present, patterns at index >= the rest index appear after the rest pattern.
+    /// For example:
+    ///   [ .., A, B ] -> patterns = [A, B], rest = 0
+    ///   [ A, .., B ] -> patterns = [A, B], rest = 1
+    ///   [ A, B, .. ] -> patterns = [A, B], rest = 2
+    /// If the rest pattern binds the rest of the list, as in `[ A, ..rest ]`, the bound symbol
+    /// is stored alongside the rest index.
+    pub opt_rest: Option<(usize, Option<Loc<Symbol>>)>,
+}
+
+impl ListPatterns {
+    pub fn arity(&self) -> ListArity {
+        match self.opt_rest {
+            Some((i, _)) => {
+                let before = i;
+                let after = self.patterns.len() - before;
+                ListArity::Slice(before, after)
+            }
+            None => ListArity::Exact(self.patterns.len()),
+        }
+    }
+
+    fn surely_exhaustive(&self) -> bool {
+        self.patterns.is_empty() && matches!(self.opt_rest, Some((0, _)))
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct RecordDestruct {
     pub var: Variable,
@@ -168,6 +218,13 @@ pub struct RecordDestruct {
     pub typ: DestructType,
 }
 
+#[derive(Clone, Debug)]
+pub struct TupleDestruct {
+    pub var: Variable,
+    pub destruct_index: usize,
+    pub typ: (Variable, Loc<Pattern>),
+}
+
 #[derive(Clone, Debug)]
 pub enum DestructType {
     Required,
@@ -608,6 +665,111 @@ pub fn canonicalize_pattern<'a>(
             })
         }
 
+        Tuple(patterns) => {
+            let ext_var = var_store.fresh();
+            let whole_var = var_store.fresh();
+            let mut destructs = Vec::with_capacity(patterns.len());
+
+            for (index, loc_pattern) in patterns.iter().enumerate() {
+                let can_guard = canonicalize_pattern(
+                    env,
+                    var_store,
+                    scope,
+                    output,
+                    pattern_type,
+                    &loc_pattern.value,
+                    loc_pattern.region,
+                    permit_shadows,
+                );
+
+                destructs.push(Loc {
+                    region: loc_pattern.region,
+                    value: TupleDestruct {
+                        var: var_store.fresh(),
+                        destruct_index: index,
+                        typ: (var_store.fresh(), can_guard),
+                    },
+                });
+            }
+
+            Pattern::TupleDestructure {
+                whole_var,
+                ext_var,
+                destructs,
+            }
+        }
+
+        List(patterns) => match pattern_type {
+            WhenBranch => {
+                let mut can_pats = Vec::with_capacity(patterns.len());
+                let mut opt_rest = None;
+
+                for loc_pattern in patterns.iter() {
+                    match loc_pattern.value.extract_spaces().item {
+                        ListRest(opt_name) => {
+                            if opt_rest.is_some() {
+                                env.problem(Problem::MultipleListRestPattern {
+                                    region: loc_pattern.region,
+                                });
+                                continue;
+                            }
+
+                            let opt_symbol = opt_name.map(|name| {
+                                let symbol = match scope.introduce_str(name, loc_pattern.region) {
+                                    Ok(symbol) => symbol,
+                                    Err((shadowed_symbol, shadow, new_symbol)) => {
+                                        env.problem(Problem::RuntimeError(
+                                            RuntimeError::Shadowing {
+                                                original_region: shadowed_symbol.region,
+                                                shadow,
+                                                kind: ShadowKind::Variable,
+                                            },
+                                        ));
+
+                                        new_symbol
+                                    }
+                                };
+
+                                output.references.insert_bound(symbol);
+
+                                Loc::at(loc_pattern.region, symbol)
+                            });
+
+                            opt_rest = Some((can_pats.len(), opt_symbol));
+                        }
+                        pattern => {
+                            let can_pat = canonicalize_pattern(
+                                env,
+                                var_store,
+                                scope,
+                                output,
+                                pattern_type,
+                                &pattern,
+                                loc_pattern.region,
+                                permit_shadows,
+                            );
+
+                            can_pats.push(can_pat);
+                        }
+                    }
+                }
+
+                Pattern::List {
+                    list_var: var_store.fresh(),
+                    elem_var: var_store.fresh(),
+                    patterns: ListPatterns {
+                        patterns: can_pats,
+                        opt_rest,
+                    },
+                }
+            }
+            ptype => unsupported_pattern(env, ptype, region),
+        },
+
+        ListRest(_) => {
+            unreachable!("should have been handled in List");
+        }
+
         RequiredField(_name, _loc_pattern) => {
             unreachable!("should have been handled in RecordDestructure");
         }
@@ -671,6 +833,7 @@ pub enum BindingsFromPattern<'a> {
 pub enum BindingsFromPatternWork<'a> {
     Pattern(&'a Loc<Pattern>),
     Destruct(&'a Loc<RecordDestruct>),
+    ListRest(&'a Loc<Symbol>),
 }
 
 impl<'a> BindingsFromPattern<'a> {
@@ -720,6 +883,21 @@ impl<'a> BindingsFromPattern<'a> {
                             let it = destructs.iter().rev().map(Destruct);
                             stack.extend(it);
                         }
+                        TupleDestructure { destructs, .. } => {
+                            let it = destructs.iter().rev().map(|d| Pattern(&d.value.typ.1));
+                            stack.extend(it);
+                        }
+                        List {
+                            patterns: ListPatterns { patterns, opt_rest },
+                            ..
+                        } => {
+                            if let Some((_, Some(rest_sym))) = opt_rest {
+                                stack.push(ListRest(rest_sym));
+                            }
+
+                            let it = patterns.iter().rev().map(Pattern);
+                            stack.extend(it);
+                        }
                         NumLiteral(..)
                         | IntLiteral(..)
                         | FloatLiteral(..)
@@ -743,6 +921,9 @@ impl<'a> BindingsFromPattern<'a> {
                         }
                     }
                 }
+                BindingsFromPatternWork::ListRest(loc_symbol) => {
+                    return Some((loc_symbol.value, loc_symbol.region));
+                }
             }
         }
 
@@ -13,7 +13,7 @@ use roc_can::expected::PExpected;
 use roc_can::expr::Expr::{self, *};
 use roc_can::expr::{
     AccessorData, AnnotatedMark, ClosureData, DeclarationTag, Declarations, DestructureDef, Field,
-    FunctionDef, OpaqueWrapFunctionData, WhenBranch,
+    FunctionDef, OpaqueWrapFunctionData, TupleAccessorData, WhenBranch,
 };
 use roc_can::pattern::Pattern;
 use roc_can::traverse::symbols_introduced_from_pattern;
@@ -227,6 +227,40 @@ pub fn constrain_expr(
                 constraints.exists(field_vars, and_constraint)
             }
         }
+        Expr::Tuple { tuple_var, elems } => {
+            let mut elem_types = VecMap::with_capacity(elems.len());
+            let mut elem_vars = Vec::with_capacity(elems.len());
+
+            // Constraints need capacity for each elem
+            // + 1 for the tuple itself + 1 for tuple var
+            let mut tuple_constraints = Vec::with_capacity(2 + elems.len());
+
+            for (index, (elem_var, loc_elem_expr)) in elems.iter().enumerate() {
+                let (elem_type, elem_con) =
+                    constrain_field(constraints, env, *elem_var, &*loc_elem_expr);
+
+                elem_vars.push(*elem_var);
+                elem_types.insert(index, elem_type);
+
+                tuple_constraints.push(elem_con);
+            }
+
+            let tuple_type = Type::Tuple(elem_types, TypeExtension::Closed);
+
+            let tuple_con = constraints.equal_types_with_storage(
+                tuple_type,
+                expected,
+                Category::Tuple,
+                region,
+                *tuple_var,
+            );
+
+            tuple_constraints.push(tuple_con);
+            elem_vars.push(*tuple_var);
+
+            let and_constraint = constraints.and_constraint(tuple_constraints);
+            constraints.exists(elem_vars, and_constraint)
+        }
         Update {
             record_var,
             ext_var,
@@ -520,6 +554,30 @@ pub fn constrain_expr(
             constraints.exists_many(vars, all_constraints)
         }
 
+        Dbg { loc_expr, variable } => {
+            // `dbg` evaluates to its argument, so the argument must have the expected type.
+            // The variable is what the value is rendered with at runtime.
+            let store_expected =
+                constraints.store(expected.get_type_ref().clone(), *variable, file!(), line!());
+
+            let expr_con =
+                constrain_expr(constraints, env, loc_expr.region, &loc_expr.value, expected);
+
+            constraints.exists_many([*variable], [store_expected, expr_con])
+        }
+
+        Crash { msg, ret_var } => {
+            let expected_msg = Expected::ForReason(Reason::CrashArg, str_type(), msg.region);
+
+            let msg_con = constrain_expr(constraints, env, msg.region, &msg.value, expected_msg);
+
+            // `crash` never returns, so its result can be of any type
+            let ret_con =
+                constraints.equal_types_var(*ret_var, expected, Category::Unknown, region);
+
+            constraints.exists_many([*ret_var], [msg_con, ret_con])
+        }
+
         If {
             cond_var,
             branch_var,
@@ -955,6 +1013,99 @@ pub fn constrain_expr(
                 cons,
             )
         }
+        TupleAccess {
+            tuple_var,
+            ext_var,
+            elem_var,
+            loc_expr,
+            index,
+        } => {
+            let ext_var = *ext_var;
+            let ext_type = Type::Variable(ext_var);
+            let elem_var = *elem_var;
+            let elem_type = Type::Variable(elem_var);
+
+            let mut tup_elem_types = VecMap::with_capacity(1);
+            tup_elem_types.insert(*index, elem_type);
+
+            let tuple_type = Type::Tuple(tup_elem_types, TypeExtension::from_type(ext_type));
+            let tuple_expected = Expected::NoExpectation(tuple_type);
+
+            let category = Category::TupleAccess(*index);
+
+            let tuple_con = constraints.equal_types_var(
+                *tuple_var,
+                tuple_expected.clone(),
+                category.clone(),
+                region,
+            );
+
+            let constraint =
+                constrain_expr(constraints, env, region, &loc_expr.value, tuple_expected);
+
+            let eq = constraints.equal_types_var(elem_var, expected, category, region);
+            constraints.exists_many([*tuple_var, elem_var, ext_var], [constraint, eq, tuple_con])
+        }
+        TupleAccessor(TupleAccessorData {
+            name: closure_name,
+            function_var,
+            index,
+            tuple_var,
+            closure_var,
+            ext_var,
+            elem_var,
+        }) => {
+            let ext_var = *ext_var;
+            let ext_type = Variable(ext_var);
+            let elem_var = *elem_var;
+            let elem_type = Variable(elem_var);
+
+            let mut elem_types = VecMap::with_capacity(1);
+            elem_types.insert(*index, elem_type.clone());
+            let tuple_type = Type::Tuple(elem_types, TypeExtension::from_type(ext_type));
+
+            let category = Category::TupleAccessor(*index);
+
+            let tuple_expected = Expected::NoExpectation(tuple_type.clone());
+            let tuple_con =
+                constraints.equal_types_var(*tuple_var, tuple_expected, category.clone(), region);
+
+            let lambda_set = Type::ClosureTag {
+                name: *closure_name,
+                captures: vec![],
+                ambient_function: *function_var,
+            };
+
+            let closure_type = Type::Variable(*closure_var);
+
+            let function_type = Type::Function(
+                vec![tuple_type],
+                Box::new(closure_type),
+                Box::new(elem_type),
+            );
+
+            let cons = [
+                constraints.equal_types_var(
+                    *closure_var,
+                    NoExpectation(lambda_set),
+                    category.clone(),
+                    region,
+                ),
+                constraints.equal_types(function_type.clone(), expected, category.clone(), region),
+                constraints.equal_types(
+                    function_type,
+                    NoExpectation(Variable(*function_var)),
+                    category,
+                    region,
+                ),
+                tuple_con,
+            ];
+
+            constraints.exists_many(
+                [*tuple_var, *function_var, *closure_var, elem_var, ext_var],
+                cons,
+            )
+        }
         LetRec(defs, loc_ret, cycle_mark) => {
             let body_con = constrain_expr(
                 constraints,
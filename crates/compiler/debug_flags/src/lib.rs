@@ -21,9 +21,12 @@
 //!              ROC_PRINT_UNIFICATIONS=0 \
 //!                ROC_PRINT_MISMATCHES=0 \
 //!   ROC_PRINT_IR_AFTER_SPECIALIZATION=0 \
+//!        ROC_PRINT_IR_AFTER_INLINING=0 \
+//!   ROC_PRINT_IR_AFTER_SIMPLIFICATION=0 \
 //!      ROC_PRINT_IR_AFTER_RESET_REUSE=0 \
 //!         ROC_PRINT_IR_AFTER_REFCOUNT=0 \
 //!         ROC_PRETTY_PRINT_IR_SYMBOLS=0 \
+//!                   ROC_CHECK_MONO_IR=0 \
 //!         # ...other flags
 //!   cargo"
 //! ```
@@ -96,6 +99,14 @@ flags! {
     /// Writes a pretty-printed mono IR to stderr after function specialization.
     ROC_PRINT_IR_AFTER_SPECIALIZATION
 
+    /// Writes a pretty-printed mono IR to stderr after small procedures are
+    /// inlined.
+    ROC_PRINT_IR_AFTER_INLINING
+
+    /// Writes a pretty-printed mono IR to stderr after constant folding and
+    /// dead code elimination.
+    ROC_PRINT_IR_AFTER_SIMPLIFICATION
+
     /// Writes a pretty-printed mono IR to stderr after insertion of reset/reuse
     /// instructions.
     ROC_PRINT_IR_AFTER_RESET_REUSE
@@ -104,6 +115,11 @@ flags! {
     /// instructions.
     ROC_PRINT_IR_AFTER_REFCOUNT
 
+    /// Checks the mono IR after each pass, and panics with a report if it is malformed:
+    /// symbols used out of scope, layouts that don't match, jumps with the wrong number
+    /// of arguments, or (after refcount insertion) values that are not consumed exactly once.
+    ROC_CHECK_MONO_IR
+
     /// Prints debug information during the alias analysis pass.
     ROC_DEBUG_ALIAS_ANALYSIS
 
@@ -1,16 +1,17 @@
 //! Derivers for the `Decoding` ability.
 
 use roc_can::expr::{
-    AnnotatedMark, ClosureData, Expr, Field, Recursive, WhenBranch, WhenBranchPattern,
+    AnnotatedMark, ClosureData, Expr, Field, IntValue, Recursive, WhenBranch, WhenBranchPattern,
 };
 use roc_can::pattern::Pattern;
 use roc_collections::SendMap;
 use roc_derive_key::decoding::FlatDecodableKey;
 use roc_error_macros::internal_error;
 use roc_module::called_via::CalledVia;
-use roc_module::ident::Lowercase;
+use roc_module::ident::{Lowercase, TagName};
 use roc_module::symbol::Symbol;
 use roc_region::all::{Loc, Region};
+use roc_types::num::IntBound;
 use roc_types::subs::{
     Content, ExhaustiveMark, FlatType, GetSubsSlice, LambdaSet, OptVariable, RecordFields,
     RedundantMark, SubsSlice, UnionLambdas, UnionTags, Variable,
@@ -28,6 +29,7 @@ pub(crate) fn derive_decoder(
     let (body, body_type) = match key {
         FlatDecodableKey::List() => decoder_list(env, def_symbol),
         FlatDecodableKey::Record(fields) => decoder_record(env, def_symbol, fields),
+        FlatDecodableKey::TagUnion(tags) => decoder_tag_union(env, def_symbol, tags),
     };
 
     let specialization_lambda_sets =
@@ -991,6 +993,998 @@ fn decoder_record_initial_state(
     )
 }
 
+/// A tag of a tag union being decoded, e.g. `A a b` in `[A a b, B]`.
+struct DecodedTag {
+    name: TagName,
+    /// The types of the tag's payloads, e.g. `a` and `b`.
+    payload_vars: Vec<Variable>,
+    /// The types of the tag's payloads in the decoding state, e.g. `Result a [NoField]`.
+    state_payload_vars: Vec<Variable>,
+}
+
+// Implements decoding of a tag union. For example, for
+//
+//   [A a b, B]
+//
+// we'd like to generate an impl like
+//
+// decoder : Decoder [A a b, B] fmt | a has Decoding, b has Decoding, fmt has DecoderFormatting
+// decoder =
+//     initialState = \tagName ->
+//         when tagName is
+//             "A" -> Keep (A (Err NoField) (Err NoField))
+//             "B" -> Keep B
+//             _ -> Skip
+//
+//     stepPayload = \state, index ->
+//         when state is
+//             A s0 s1 ->
+//                 when index is
+//                     0 ->
+//                         Next (Decode.custom \bytes, fmt ->
+//                             when Decode.decodeWith bytes Decode.decoder fmt is
+//                                 rec ->
+//                                     {
+//                                         rest: rec.rest,
+//                                         result: when rec.result is
+//                                             Ok val -> Ok (A (Ok val) s1),
+//                                             Err err -> Err err
+//                                     })
+//                     1 -> Next (Decode.custom ... Ok (A s0 (Ok val)) ...)
+//                     _ -> TooLong
+//             B -> TooLong
+//
+//     finalizer = \state ->
+//         when state is
+//             A (Ok a) (Ok b) -> Ok (A a b)
+//             B -> Ok B
+//             _ -> Err TooShort
+//
+//     Decode.custom \bytes, fmt -> Decode.decodeWith bytes (Decode.tag initialState stepPayload finalizer) fmt
+//
+// Recursive tag unions need no special handling: a recursive payload is decoded through
+// `Decode.decoder`, which resolves back to this implementation.
+fn decoder_tag_union(
+    env: &mut Env,
+    _def_symbol: Symbol,
+    tags: Vec<(TagName, u16)>,
+) -> (Expr, Variable) {
+    // [TooShort]
+    let decode_err_var = {
+        let flat_type = FlatType::TagUnion(
+            UnionTags::tag_without_arguments(env.subs, "TooShort".into()),
+            Variable::EMPTY_TAG_UNION,
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    // [NoField]
+    let no_field_var = {
+        let flat_type = FlatType::TagUnion(
+            UnionTags::tag_without_arguments(env.subs, "NoField".into()),
+            Variable::EMPTY_TAG_UNION,
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    let tags: Vec<_> = tags
+        .into_iter()
+        .map(|(name, arity)| {
+            let payload_vars: Vec<_> = (0..arity)
+                .map(|_| env.subs.fresh_unnamed_flex_var())
+                .collect();
+            let state_payload_vars = payload_vars
+                .iter()
+                .map(|&payload_var| {
+                    let flat_type = FlatType::TagUnion(
+                        UnionTags::for_result(env.subs, payload_var, no_field_var),
+                        Variable::EMPTY_TAG_UNION,
+                    );
+
+                    synth_var(env.subs, Content::Structure(flat_type))
+                })
+                .collect();
+
+            DecodedTag {
+                name,
+                payload_vars,
+                state_payload_vars,
+            }
+        })
+        .collect();
+
+    // The decoded type, e.g. [A a b, B]
+    let union_var = tag_union_var(
+        env,
+        tags.iter()
+            .map(|tag| (tag.name.clone(), tag.payload_vars.clone())),
+    );
+    // The decoding state, e.g. [A (Result a [NoField]) (Result b [NoField]), B]
+    let state_var = tag_union_var(
+        env,
+        tags.iter()
+            .map(|tag| (tag.name.clone(), tag.state_payload_vars.clone())),
+    );
+
+    // initialState = ...
+    let (initial_state, initial_state_var) =
+        decoder_tag_union_initial_state(env, &tags, state_var, no_field_var);
+
+    // stepPayload = ...
+    let (step_payload, step_payload_var) =
+        decoder_tag_union_step_payload(env, &tags, state_var, decode_err_var);
+
+    // finalizer = ...
+    let (finalizer, finalizer_var) =
+        decoder_tag_union_finalizer(env, &tags, state_var, union_var, decode_err_var);
+
+    // Build up the type of `Decode.tag` we expect
+    let tag_decoder_var = env.subs.fresh_unnamed_flex_var();
+    let decode_tag_lambda_set = env.subs.fresh_unnamed_flex_var();
+    let decode_tag_var = env.import_builtin_symbol_var(Symbol::DECODE_TAG);
+    let this_decode_tag_var = {
+        let flat_type = FlatType::Func(
+            SubsSlice::insert_into_subs(
+                env.subs,
+                [initial_state_var, step_payload_var, finalizer_var],
+            ),
+            decode_tag_lambda_set,
+            tag_decoder_var,
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    env.unify(decode_tag_var, this_decode_tag_var);
+
+    // Decode.tag initialState stepPayload finalizer
+    let call_decode_tag = Expr::Call(
+        Box::new((
+            this_decode_tag_var,
+            Loc::at_zero(Expr::AbilityMember(
+                Symbol::DECODE_TAG,
+                None,
+                this_decode_tag_var,
+            )),
+            decode_tag_lambda_set,
+            tag_decoder_var,
+        )),
+        vec![
+            (initial_state_var, Loc::at_zero(initial_state)),
+            (step_payload_var, Loc::at_zero(step_payload)),
+            (finalizer_var, Loc::at_zero(finalizer)),
+        ],
+        CalledVia::Space,
+    );
+
+    let bytes_sym = env.new_symbol("bytes");
+    let fmt_sym = env.new_symbol("fmt");
+    let fmt_var = env.subs.fresh_unnamed_flex_var();
+
+    wrap_in_decode_custom_decode_with(
+        env,
+        bytes_sym,
+        (fmt_sym, fmt_var),
+        vec![],
+        (call_decode_tag, tag_decoder_var),
+    )
+}
+
+// Example:
+// initialState = \tagName ->
+//     when tagName is
+//         "A" -> Keep (A (Err NoField) (Err NoField))
+//         "B" -> Keep B
+//         _ -> Skip
+fn decoder_tag_union_initial_state(
+    env: &mut Env,
+    tags: &[DecodedTag],
+    state_var: Variable,
+    no_field_var: Variable,
+) -> (Expr, Variable) {
+    let tag_name_symbol = env.new_symbol("tagName");
+
+    // [Keep state, Skip]
+    let keep_or_skip_var = {
+        let keep_payload_subs_slice = SubsSlice::insert_into_subs(env.subs, [state_var]);
+        let flat_type = FlatType::TagUnion(
+            UnionTags::insert_slices_into_subs(
+                env.subs,
+                [
+                    ("Keep".into(), keep_payload_subs_slice),
+                    ("Skip".into(), Default::default()),
+                ],
+            ),
+            Variable::EMPTY_TAG_UNION,
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    // +1 because of the default branch.
+    let mut branches = Vec::with_capacity(tags.len() + 1);
+
+    for tag in tags {
+        // A (Err NoField) (Err NoField)
+        let arguments = tag
+            .state_payload_vars
+            .iter()
+            .map(|&state_payload_var| {
+                let no_field = Expr::Tag {
+                    tag_union_var: no_field_var,
+                    ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                    name: "NoField".into(),
+                    arguments: Vec::new(),
+                };
+                let err_no_field = Expr::Tag {
+                    tag_union_var: state_payload_var,
+                    ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                    name: "Err".into(),
+                    arguments: vec![(no_field_var, Loc::at_zero(no_field))],
+                };
+
+                (state_payload_var, Loc::at_zero(err_no_field))
+            })
+            .collect();
+        let initial_state = Expr::Tag {
+            tag_union_var: state_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: tag.name.clone(),
+            arguments,
+        };
+
+        // "A" -> Keep (A (Err NoField) (Err NoField))
+        branches.push(WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(Pattern::StrLiteral(tag.name.0.as_str().into())),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(Expr::Tag {
+                tag_union_var: keep_or_skip_var,
+                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                name: "Keep".into(),
+                arguments: vec![(state_var, Loc::at_zero(initial_state))],
+            }),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        });
+    }
+
+    // _ -> Skip
+    branches.push(WhenBranch {
+        patterns: vec![WhenBranchPattern {
+            pattern: Loc::at_zero(Pattern::Underscore),
+            degenerate: false,
+        }],
+        value: Loc::at_zero(Expr::Tag {
+            tag_union_var: keep_or_skip_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: "Skip".into(),
+            arguments: Vec::new(),
+        }),
+        guard: None,
+        redundant: RedundantMark::known_non_redundant(),
+    });
+
+    // when tagName is
+    let body = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(tag_name_symbol))),
+        cond_var: Variable::STR,
+        expr_var: keep_or_skip_var,
+        region: Region::zero(),
+        branches,
+        branches_cond_var: Variable::STR,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    let initial_state_symbol = env.new_symbol("initialState");
+
+    build_closure(
+        env,
+        initial_state_symbol,
+        Vec::new(),
+        vec![(Variable::STR, tag_name_symbol)],
+        (body, keep_or_skip_var),
+    )
+}
+
+// Example:
+// stepPayload = \state, index ->
+//     when state is
+//         A s0 s1 ->
+//             when index is
+//                 0 -> Next (Decode.custom ... Ok (A (Ok val) s1) ...)
+//                 1 -> Next (Decode.custom ... Ok (A s0 (Ok val)) ...)
+//                 _ -> TooLong
+//         B -> TooLong
+fn decoder_tag_union_step_payload(
+    env: &mut Env,
+    tags: &[DecodedTag],
+    state_var: Variable,
+    decode_err_var: Variable,
+) -> (Expr, Variable) {
+    let state_arg_symbol = env.new_symbol("state");
+    let index_arg_symbol = env.new_symbol("index");
+
+    // [Next (Decoder state fmt), TooLong]
+    let next_payload_var = env.subs.fresh_unnamed_flex_var();
+    let next_or_too_long_var = {
+        let next_payload_subs_slice = SubsSlice::insert_into_subs(env.subs, [next_payload_var]);
+        let flat_type = FlatType::TagUnion(
+            UnionTags::insert_slices_into_subs(
+                env.subs,
+                [
+                    ("Next".into(), next_payload_subs_slice),
+                    ("TooLong".into(), Default::default()),
+                ],
+            ),
+            Variable::EMPTY_TAG_UNION,
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    let too_long = |env: &mut Env| Expr::Tag {
+        tag_union_var: next_or_too_long_var,
+        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+        name: "TooLong".into(),
+        arguments: Vec::new(),
+    };
+
+    let mut state_branches = Vec::with_capacity(tags.len());
+
+    for tag in tags {
+        // s0 s1
+        let state_payload_symbols: Vec<_> = (0..tag.payload_vars.len())
+            .map(|i| env.new_symbol(&format!("s{}", i)))
+            .collect();
+
+        let branch_body = if tag.payload_vars.is_empty() {
+            too_long(env)
+        } else {
+            // +1 because of the default branch.
+            let mut index_branches = Vec::with_capacity(tag.payload_vars.len() + 1);
+
+            for index in 0..tag.payload_vars.len() {
+                let (payload_decoder, payload_decoder_var) = decoder_tag_union_payload_decoder(
+                    env,
+                    tag,
+                    index,
+                    &state_payload_symbols,
+                    state_var,
+                    decode_err_var,
+                );
+
+                env.unify(next_payload_var, payload_decoder_var);
+
+                // 0 -> Next (Decode.custom ...)
+                index_branches.push(WhenBranch {
+                    patterns: vec![WhenBranchPattern {
+                        pattern: Loc::at_zero(Pattern::IntLiteral(
+                            Variable::NAT,
+                            Variable::NAT,
+                            index.to_string().into_boxed_str(),
+                            IntValue::I128((index as i128).to_ne_bytes()),
+                            IntBound::None,
+                        )),
+                        degenerate: false,
+                    }],
+                    value: Loc::at_zero(Expr::Tag {
+                        tag_union_var: next_or_too_long_var,
+                        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                        name: "Next".into(),
+                        arguments: vec![(payload_decoder_var, Loc::at_zero(payload_decoder))],
+                    }),
+                    guard: None,
+                    redundant: RedundantMark::known_non_redundant(),
+                });
+            }
+
+            // _ -> TooLong
+            index_branches.push(WhenBranch {
+                patterns: vec![WhenBranchPattern {
+                    pattern: Loc::at_zero(Pattern::Underscore),
+                    degenerate: false,
+                }],
+                value: Loc::at_zero(too_long(env)),
+                guard: None,
+                redundant: RedundantMark::known_non_redundant(),
+            });
+
+            // when index is
+            Expr::When {
+                loc_cond: Box::new(Loc::at_zero(Expr::Var(index_arg_symbol))),
+                cond_var: Variable::NAT,
+                expr_var: next_or_too_long_var,
+                region: Region::zero(),
+                branches: index_branches,
+                branches_cond_var: Variable::NAT,
+                exhaustive: ExhaustiveMark::known_exhaustive(),
+            }
+        };
+
+        // A s0 s1 -> ...
+        let pattern = Pattern::AppliedTag {
+            whole_var: state_var,
+            ext_var: Variable::EMPTY_TAG_UNION,
+            tag_name: tag.name.clone(),
+            arguments: (tag.state_payload_vars.iter())
+                .zip(state_payload_symbols.iter())
+                .map(|(&var, &symbol)| (var, Loc::at_zero(Pattern::Identifier(symbol))))
+                .collect(),
+        };
+
+        state_branches.push(WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(pattern),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(branch_body),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        });
+    }
+
+    if tags.is_empty() {
+        // The state of an empty tag union can never be constructed, but we still need a body.
+        state_branches.push(WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(Pattern::Underscore),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(too_long(env)),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        });
+    }
+
+    // when state is
+    let body = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(state_arg_symbol))),
+        cond_var: state_var,
+        expr_var: next_or_too_long_var,
+        region: Region::zero(),
+        branches: state_branches,
+        branches_cond_var: state_var,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    let step_payload_symbol = env.new_symbol("stepPayload");
+
+    build_closure(
+        env,
+        step_payload_symbol,
+        Vec::new(),
+        vec![
+            (state_var, state_arg_symbol),
+            (Variable::NAT, index_arg_symbol),
+        ],
+        (body, next_or_too_long_var),
+    )
+}
+
+// Example, for the payload at index 1 of `A s0 s1`:
+// Decode.custom \bytes, fmt ->
+//     when Decode.decodeWith bytes Decode.decoder fmt is
+//         rec ->
+//             {
+//                 rest: rec.rest,
+//                 result: when rec.result is
+//                     Ok val -> Ok (A s0 (Ok val)),
+//                     Err err -> Err err
+//             }
+fn decoder_tag_union_payload_decoder(
+    env: &mut Env,
+    tag: &DecodedTag,
+    index: usize,
+    state_payload_symbols: &[Symbol],
+    state_var: Variable,
+    decode_err_var: Variable,
+) -> (Expr, Variable) {
+    let payload_var = tag.payload_vars[index];
+    let state_payload_var = tag.state_payload_vars[index];
+
+    let bytes_arg_symbol = env.new_symbol("bytes");
+    let fmt_arg_symbol = env.new_symbol("fmt");
+    let bytes_arg_var = env.subs.fresh_unnamed_flex_var();
+    let fmt_arg_var = env.subs.fresh_unnamed_flex_var();
+
+    // rec.result : [Ok payload_var, Err DecodeError]
+    let rec_dot_result = {
+        let tag_union = FlatType::TagUnion(
+            UnionTags::for_result(env.subs, payload_var, decode_err_var),
+            Variable::EMPTY_TAG_UNION,
+        );
+
+        synth_var(env.subs, Content::Structure(tag_union))
+    };
+
+    // rec : { rest: List U8, result: (typeof rec.result) }
+    let rec_var = {
+        let fields = RecordFields::insert_into_subs(
+            env.subs,
+            [
+                ("rest".into(), RecordField::Required(Variable::LIST_U8)),
+                ("result".into(), RecordField::Required(rec_dot_result)),
+            ],
+        );
+        let record = FlatType::Record(fields, Variable::EMPTY_RECORD);
+
+        synth_var(env.subs, Content::Structure(record))
+    };
+
+    // `Decode.decoder` for the payload
+    let decoder_var = env.import_builtin_symbol_var(Symbol::DECODE_DECODER);
+    let decode_with_var = env.import_builtin_symbol_var(Symbol::DECODE_DECODE_WITH);
+    let lambda_set_var = env.subs.fresh_unnamed_flex_var();
+    let this_decode_with_var = {
+        let subs_slice =
+            SubsSlice::insert_into_subs(env.subs, [bytes_arg_var, decoder_var, fmt_arg_var]);
+        let this_decode_with_var = synth_var(
+            env.subs,
+            Content::Structure(FlatType::Func(subs_slice, lambda_set_var, rec_var)),
+        );
+
+        env.unify(decode_with_var, this_decode_with_var);
+
+        this_decode_with_var
+    };
+
+    // The result of decoding this payload - either the updated state, or a decoding error.
+    let when_expr_var = {
+        let flat_type = FlatType::TagUnion(
+            UnionTags::for_result(env.subs, state_var, decode_err_var),
+            Variable::EMPTY_TAG_UNION,
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    // What our decoder passed to `Decode.custom` returns - the result of decoding the
+    // payload, and the remaining bytes.
+    let custom_callback_ret_var = {
+        let flat_type = FlatType::Record(
+            RecordFields::insert_into_subs(
+                env.subs,
+                [
+                    ("rest".into(), RecordField::Required(Variable::LIST_U8)),
+                    ("result".into(), RecordField::Required(when_expr_var)),
+                ],
+            ),
+            Variable::EMPTY_RECORD,
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    let rec_symbol = env.new_symbol("rec");
+    let ok_val_symbol = env.new_symbol("val");
+    let err_val_symbol = env.new_symbol("err");
+
+    // Ok (A s0 (Ok val))
+    let ok_branch_expr = {
+        let arguments = (tag.state_payload_vars.iter())
+            .zip(state_payload_symbols.iter())
+            .enumerate()
+            .map(|(i, (&var, &symbol))| {
+                let expr = if i == index {
+                    Expr::Tag {
+                        tag_union_var: state_payload_var,
+                        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                        name: "Ok".into(),
+                        arguments: vec![(payload_var, Loc::at_zero(Expr::Var(ok_val_symbol)))],
+                    }
+                } else {
+                    Expr::Var(symbol)
+                };
+
+                (var, Loc::at_zero(expr))
+            })
+            .collect();
+        let updated_state = Expr::Tag {
+            tag_union_var: state_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: tag.name.clone(),
+            arguments,
+        };
+
+        Expr::Tag {
+            tag_union_var: when_expr_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: "Ok".into(),
+            arguments: vec![(state_var, Loc::at_zero(updated_state))],
+        }
+    };
+
+    // when rec.result is
+    //     Ok val -> Ok (A s0 (Ok val)),
+    //     Err err -> Err err
+    let result_val = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Access {
+            record_var: rec_var,
+            ext_var: env.new_ext_var(ExtensionKind::Record),
+            field_var: rec_dot_result,
+            loc_expr: Box::new(Loc::at_zero(Expr::Var(rec_symbol))),
+            field: "result".into(),
+        })),
+        cond_var: rec_dot_result,
+        expr_var: when_expr_var,
+        region: Region::zero(),
+        branches: vec![
+            WhenBranch {
+                patterns: vec![WhenBranchPattern {
+                    pattern: Loc::at_zero(Pattern::AppliedTag {
+                        whole_var: rec_dot_result,
+                        ext_var: Variable::EMPTY_TAG_UNION,
+                        tag_name: "Ok".into(),
+                        arguments: vec![(
+                            payload_var,
+                            Loc::at_zero(Pattern::Identifier(ok_val_symbol)),
+                        )],
+                    }),
+                    degenerate: false,
+                }],
+                value: Loc::at_zero(ok_branch_expr),
+                guard: None,
+                redundant: RedundantMark::known_non_redundant(),
+            },
+            WhenBranch {
+                patterns: vec![WhenBranchPattern {
+                    pattern: Loc::at_zero(Pattern::AppliedTag {
+                        whole_var: rec_dot_result,
+                        ext_var: Variable::EMPTY_TAG_UNION,
+                        tag_name: "Err".into(),
+                        arguments: vec![(
+                            decode_err_var,
+                            Loc::at_zero(Pattern::Identifier(err_val_symbol)),
+                        )],
+                    }),
+                    degenerate: false,
+                }],
+                value: Loc::at_zero(Expr::Tag {
+                    tag_union_var: when_expr_var,
+                    ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                    name: "Err".into(),
+                    arguments: vec![(decode_err_var, Loc::at_zero(Expr::Var(err_val_symbol)))],
+                }),
+                guard: None,
+                redundant: RedundantMark::known_non_redundant(),
+            },
+        ],
+        branches_cond_var: rec_dot_result,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    // { rest: rec.rest, result: when rec.result is ... }
+    let branch_body = {
+        let mut fields_map = SendMap::default();
+
+        fields_map.insert(
+            "rest".into(),
+            Field {
+                var: Variable::LIST_U8,
+                region: Region::zero(),
+                loc_expr: Box::new(Loc::at_zero(Expr::Access {
+                    record_var: rec_var,
+                    ext_var: env.new_ext_var(ExtensionKind::Record),
+                    field_var: Variable::LIST_U8,
+                    loc_expr: Box::new(Loc::at_zero(Expr::Var(rec_symbol))),
+                    field: "rest".into(),
+                })),
+            },
+        );
+
+        fields_map.insert(
+            "result".into(),
+            Field {
+                var: when_expr_var,
+                region: Region::zero(),
+                loc_expr: Box::new(Loc::at_zero(result_val)),
+            },
+        );
+
+        Expr::Record {
+            record_var: custom_callback_ret_var,
+            fields: fields_map,
+        }
+    };
+
+    // # Uses a single-branch `when` because `let` is more expensive to monomorphize
+    // # due to checks for polymorphic expressions, and `rec` would be polymorphic.
+    // when Decode.decodeWith bytes Decode.decoder fmt is
+    //     rec -> ...
+    let custom_callback_body = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Call(
+            Box::new((
+                this_decode_with_var,
+                Loc::at_zero(Expr::Var(Symbol::DECODE_DECODE_WITH)),
+                lambda_set_var,
+                rec_var,
+            )),
+            vec![
+                (Variable::LIST_U8, Loc::at_zero(Expr::Var(bytes_arg_symbol))),
+                (
+                    decoder_var,
+                    Loc::at_zero(Expr::AbilityMember(
+                        Symbol::DECODE_DECODER,
+                        None,
+                        decoder_var,
+                    )),
+                ),
+                (fmt_arg_var, Loc::at_zero(Expr::Var(fmt_arg_symbol))),
+            ],
+            CalledVia::Space,
+        ))),
+        cond_var: rec_var,
+        expr_var: custom_callback_ret_var,
+        region: Region::zero(),
+        branches: vec![WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(Pattern::Identifier(rec_symbol)),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(branch_body),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        }],
+        branches_cond_var: rec_var,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    // The callback captures the rest of the tag's decoding state.
+    let captures = (tag.state_payload_vars.iter())
+        .zip(state_payload_symbols.iter())
+        .enumerate()
+        .filter(|(i, _)| *i != index)
+        .map(|(_, (&var, &symbol))| (symbol, var))
+        .collect();
+
+    // \bytes, fmt -> …
+    let custom_closure_symbol = env.new_symbol("customCallback");
+    let (custom_callback, custom_callback_var) = build_closure(
+        env,
+        custom_closure_symbol,
+        captures,
+        vec![
+            (bytes_arg_var, bytes_arg_symbol),
+            (fmt_arg_var, fmt_arg_symbol),
+        ],
+        (custom_callback_body, custom_callback_ret_var),
+    );
+
+    // Decode.custom \bytes, fmt -> …
+    let decode_custom_ret_var = env.subs.fresh_unnamed_flex_var();
+    let decode_custom_var = env.import_builtin_symbol_var(Symbol::DECODE_CUSTOM);
+    let decode_custom_closure_var = env.subs.fresh_unnamed_flex_var();
+    let this_decode_custom_var = {
+        let subs_slice = SubsSlice::insert_into_subs(env.subs, [custom_callback_var]);
+        let flat_type =
+            FlatType::Func(subs_slice, decode_custom_closure_var, decode_custom_ret_var);
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    env.unify(decode_custom_var, this_decode_custom_var);
+
+    let decode_custom = Expr::Call(
+        Box::new((
+            this_decode_custom_var,
+            Loc::at_zero(Expr::Var(Symbol::DECODE_CUSTOM)),
+            decode_custom_closure_var,
+            decode_custom_ret_var,
+        )),
+        vec![(custom_callback_var, Loc::at_zero(custom_callback))],
+        CalledVia::Space,
+    );
+
+    (decode_custom, decode_custom_ret_var)
+}
+
+// Example:
+// finalizer = \state ->
+//     when state is
+//         A (Ok a) (Ok b) -> Ok (A a b)
+//         B -> Ok B
+//         _ -> Err TooShort
+fn decoder_tag_union_finalizer(
+    env: &mut Env,
+    tags: &[DecodedTag],
+    state_var: Variable,
+    union_var: Variable,
+    decode_err_var: Variable,
+) -> (Expr, Variable) {
+    let state_arg_symbol = env.new_symbol("state");
+
+    // [Ok union_var, Err DecodeError]
+    let return_type_var = {
+        let flat_type = FlatType::TagUnion(
+            UnionTags::for_result(env.subs, union_var, decode_err_var),
+            Variable::EMPTY_TAG_UNION,
+        );
+
+        synth_var(env.subs, Content::Structure(flat_type))
+    };
+
+    // +1 because of the default branch.
+    let mut branches = Vec::with_capacity(tags.len() + 1);
+
+    for tag in tags {
+        let payload_symbols: Vec<_> = (0..tag.payload_vars.len())
+            .map(|i| env.new_symbol(&format!("p{}", i)))
+            .collect();
+
+        // A (Ok a) (Ok b)
+        let pattern = Pattern::AppliedTag {
+            whole_var: state_var,
+            ext_var: Variable::EMPTY_TAG_UNION,
+            tag_name: tag.name.clone(),
+            arguments: (tag.payload_vars.iter())
+                .zip(tag.state_payload_vars.iter())
+                .zip(payload_symbols.iter())
+                .map(|((&payload_var, &state_payload_var), &symbol)| {
+                    let ok_pattern = Pattern::AppliedTag {
+                        whole_var: state_payload_var,
+                        ext_var: Variable::EMPTY_TAG_UNION,
+                        tag_name: "Ok".into(),
+                        arguments: vec![(payload_var, Loc::at_zero(Pattern::Identifier(symbol)))],
+                    };
+
+                    (state_payload_var, Loc::at_zero(ok_pattern))
+                })
+                .collect(),
+        };
+
+        // Ok (A a b)
+        let decoded = Expr::Tag {
+            tag_union_var: union_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: tag.name.clone(),
+            arguments: (tag.payload_vars.iter())
+                .zip(payload_symbols.iter())
+                .map(|(&var, &symbol)| (var, Loc::at_zero(Expr::Var(symbol))))
+                .collect(),
+        };
+        let body = Expr::Tag {
+            tag_union_var: return_type_var,
+            ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+            name: "Ok".into(),
+            arguments: vec![(union_var, Loc::at_zero(decoded))],
+        };
+
+        branches.push(WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(pattern),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(body),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        });
+    }
+
+    // Any payload that was not decoded makes the whole tag invalid.
+    if tags.is_empty() || tags.iter().any(|tag| !tag.payload_vars.is_empty()) {
+        // _ -> Err TooShort
+        branches.push(WhenBranch {
+            patterns: vec![WhenBranchPattern {
+                pattern: Loc::at_zero(Pattern::Underscore),
+                degenerate: false,
+            }],
+            value: Loc::at_zero(Expr::Tag {
+                tag_union_var: return_type_var,
+                ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                name: "Err".into(),
+                arguments: vec![(
+                    decode_err_var,
+                    Loc::at_zero(Expr::Tag {
+                        tag_union_var: decode_err_var,
+                        ext_var: env.new_ext_var(ExtensionKind::TagUnion),
+                        name: "TooShort".into(),
+                        arguments: Vec::new(),
+                    }),
+                )],
+            }),
+            guard: None,
+            redundant: RedundantMark::known_non_redundant(),
+        });
+    }
+
+    // when state is
+    let body = Expr::When {
+        loc_cond: Box::new(Loc::at_zero(Expr::Var(state_arg_symbol))),
+        cond_var: state_var,
+        expr_var: return_type_var,
+        region: Region::zero(),
+        branches,
+        branches_cond_var: state_var,
+        exhaustive: ExhaustiveMark::known_exhaustive(),
+    };
+
+    let finalizer_symbol = env.new_symbol("finalizer");
+
+    build_closure(
+        env,
+        finalizer_symbol,
+        Vec::new(),
+        vec![(state_var, state_arg_symbol)],
+        (body, return_type_var),
+    )
+}
+
+// Builds the closed tag union `[A a b, B]` from its tags and their payload types.
+fn tag_union_var(env: &mut Env, tags: impl Iterator<Item = (TagName, Vec<Variable>)>) -> Variable {
+    let tags: Vec<_> = tags.collect();
+
+    if tags.is_empty() {
+        return Variable::EMPTY_TAG_UNION;
+    }
+
+    let union_tags = UnionTags::insert_into_subs(env.subs, tags);
+
+    synth_var(
+        env.subs,
+        Content::Structure(FlatType::TagUnion(union_tags, Variable::EMPTY_TAG_UNION)),
+    )
+}
+
+// Builds a closure `\arg1, arg2 -> body` that captures `captures`, returning the closure and its
+// function type.
+fn build_closure(
+    env: &mut Env,
+    name: Symbol,
+    captures: Vec<(Symbol, Variable)>,
+    arguments: Vec<(Variable, Symbol)>,
+    body: (Expr, Variable),
+) -> (Expr, Variable) {
+    let (body, return_type) = body;
+
+    // The lambda set refers back to the function type, so we fill it in below.
+    let function_type = env.subs.fresh_unnamed_flex_var();
+    let closure_type = {
+        let lambda_set = LambdaSet {
+            solved: UnionLambdas::insert_into_subs(
+                env.subs,
+                [(name, captures.iter().map(|(_, var)| *var))],
+            ),
+            recursion_var: OptVariable::NONE,
+            unspecialized: Default::default(),
+            ambient_function: function_type,
+        };
+
+        synth_var(env.subs, Content::LambdaSet(lambda_set))
+    };
+
+    let args_slice = SubsSlice::insert_into_subs(env.subs, arguments.iter().map(|(var, _)| *var));
+    env.subs.set_content(
+        function_type,
+        Content::Structure(FlatType::Func(args_slice, closure_type, return_type)),
+    );
+
+    let closure = Expr::Closure(ClosureData {
+        function_type,
+        closure_type,
+        return_type,
+        name,
+        captured_symbols: captures,
+        recursive: Recursive::NotRecursive,
+        arguments: arguments
+            .into_iter()
+            .map(|(var, symbol)| {
+                (
+                    var,
+                    AnnotatedMark::known_exhaustive(),
+                    Loc::at_zero(Pattern::Identifier(symbol)),
+                )
+            })
+            .collect(),
+        loc_body: Box::new(Loc::at_zero(body)),
+    });
+
+    (closure, function_type)
+}
+
 fn decoder_list(env: &mut Env<'_>, _def_symbol: Symbol) -> (Expr, Variable) {
     // Build
     //
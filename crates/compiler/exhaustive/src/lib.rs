@@ -36,6 +36,7 @@ pub enum RenderAs {
     Tag,
     Opaque,
     Record(Vec<Lowercase>),
+    Tuple,
     Guard,
 }
 
@@ -69,6 +70,54 @@ pub enum Pattern {
     Anything,
     Literal(Literal),
     Ctor(Union, TagId, std::vec::Vec<Pattern>),
+    List(ListArity, std::vec::Vec<Pattern>),
+}
+
+/// The number of elements a list pattern matches on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ListArity {
+    /// A list pattern of an exact size, e.g. `[x, y]`.
+    Exact(usize),
+    /// A list pattern with a rest pattern, matching the given number of elements before and
+    /// after the rest, e.g. `[x, .., y]` is `Slice(1, 1)`.
+    Slice(usize, usize),
+}
+
+impl ListArity {
+    /// The fewest number of elements a list must have to be matched by this arity.
+    pub fn min_len(&self) -> usize {
+        match self {
+            ListArity::Exact(n) => *n,
+            ListArity::Slice(before, after) => before + after,
+        }
+    }
+
+    /// Whether a list of the given length is matched by this arity.
+    pub fn covers_length(&self, length: usize) -> bool {
+        match self {
+            ListArity::Exact(n) => *n == length,
+            ListArity::Slice(..) => length >= self.min_len(),
+        }
+    }
+}
+
+/// The patterns a list pattern of the given arity places on each element of a list of the given
+/// length; elements swallowed by a rest pattern are matched by [Anything].
+fn list_elems_at_length(arity: ListArity, mut elems: Vec<Pattern>, length: usize) -> Vec<Pattern> {
+    debug_assert!(arity.covers_length(length));
+
+    match arity {
+        ListArity::Exact(_) => elems,
+        ListArity::Slice(before, _) => {
+            let after = elems.split_off(before);
+            let num_rest = length - arity.min_len();
+
+            elems.extend(std::iter::repeat(Anything).take(num_rest));
+            elems.extend(after);
+
+            elems
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -140,6 +189,31 @@ fn is_exhaustive(matrix: &RefPatternMatrix, n: usize) -> PatternMatrix {
         vec![std::iter::repeat(Anything).take(n).collect()]
     } else if n == 0 {
         vec![]
+    } else if let Some(max_len) = max_list_len(matrix) {
+        // Lists of every length up to the longest list pattern must be checked individually;
+        // all longer lists are matched the same way, so one more length stands in for all of them.
+        let is_length_exhaustive = |length: usize| {
+            let new_matrix: Vec<_> = matrix
+                .iter()
+                .filter_map(|row| specialize_row_by_list(length, row))
+                .collect();
+            let rest: Vec<Vec<Pattern>> = is_exhaustive(&new_matrix, length + n - 1);
+
+            let arity = if length > max_len {
+                ListArity::Slice(length, 0)
+            } else {
+                ListArity::Exact(length)
+            };
+
+            let mut result = Vec::with_capacity(rest.len());
+            for row in rest {
+                result.push(recover_list(arity, length, row));
+            }
+
+            result
+        };
+
+        (0..=max_len + 1).flat_map(is_length_exhaustive).collect()
     } else {
         let ctors = collect_ctors(matrix);
         let num_seen = ctors.len();
@@ -235,6 +309,15 @@ fn recover_ctor(
     rest
 }
 
+fn recover_list(arity: ListArity, length: usize, mut patterns: Vec<Pattern>) -> Vec<Pattern> {
+    let mut rest = patterns.split_off(length);
+    let elems = patterns;
+
+    rest.push(List(arity, elems));
+
+    rest
+}
+
 /// Check if a new row "vector" is useful given previous rows "matrix"
 pub fn is_useful(mut old_matrix: PatternMatrix, mut vector: Row) -> bool {
     let mut matrix = Vec::with_capacity(old_matrix.len());
@@ -264,6 +347,35 @@ pub fn is_useful(mut old_matrix: PatternMatrix, mut vector: Row) -> bool {
                         vector.extend(args);
                     }
 
+                    List(arity, elems) => {
+                        // keep checking rows that match lists of the same lengths
+                        let lengths = match arity {
+                            ListArity::Exact(n) => n..=n,
+                            ListArity::Slice(..) => {
+                                let max_len =
+                                    max_list_len(&old_matrix).unwrap_or(0).max(arity.min_len());
+
+                                arity.min_len()..=max_len + 1
+                            }
+                        };
+
+                        for length in lengths {
+                            let matrix = old_matrix
+                                .iter()
+                                .filter_map(|row| specialize_row_by_list2(length, row.clone()))
+                                .collect();
+
+                            let mut vector = vector.clone();
+                            vector.extend(list_elems_at_length(arity, elems.clone(), length));
+
+                            if is_useful(matrix, vector) {
+                                break 'outer true;
+                            }
+                        }
+
+                        break false;
+                    }
+
                     Anything => {
                         // check if all alternatives appear in matrix
                         match is_complete(&old_matrix) {
@@ -303,6 +415,27 @@ pub fn is_useful(mut old_matrix: PatternMatrix, mut vector: Row) -> bool {
                                     }
                                 }
 
+                                break false;
+                            }
+                            Complete::YesList(max_len) => {
+                                // Lists of all lengths are covered, but some of the list patterns
+                                // may have elements that make them less general.
+                                for length in 0..=max_len + 1 {
+                                    let matrix = old_matrix
+                                        .iter()
+                                        .filter_map(|row| {
+                                            specialize_row_by_list2(length, row.clone())
+                                        })
+                                        .collect();
+
+                                    let mut vector = vector.clone();
+                                    vector.extend(std::iter::repeat(Anything).take(length));
+
+                                    if is_useful(matrix, vector) {
+                                        break 'outer true;
+                                    }
+                                }
+
                                 break false;
                             }
                         }
@@ -325,7 +458,7 @@ pub fn is_useful(mut old_matrix: PatternMatrix, mut vector: Row) -> bool {
                                 }
                                 Some(Anything) => matrix.push(patterns),
 
-                                Some(Ctor(_, _, _)) => panic!(
+                                Some(Ctor(_, _, _) | List(_, _)) => panic!(
                                     r#"Compiler bug! After type checking, constructors and literals should never align in pattern match exhaustiveness checks."#
                                 ),
 
@@ -366,7 +499,7 @@ fn specialize_row_by_ctor2(
             patterns.extend(std::iter::repeat(Anything).take(arity));
             matrix.push(patterns);
             }
-        Some(Literal(_)) => panic!( "Compiler bug! After type checking, constructors and literal should never align in pattern match exhaustiveness checks."),
+        Some(Literal(_) | List(..)) => panic!( "Compiler bug! After type checking, constructors and literal should never align in pattern match exhaustiveness checks."),
         None => panic!("Compiler error! Empty matrices should not get specialized."),
     }
     }
@@ -399,13 +532,69 @@ fn specialize_row_by_ctor(tag_id: TagId, arity: usize, row: &RefRow) -> Option<R
                 .collect();
             Some(new_patterns)
         }
-        Some(Literal(_)) => unreachable!(
+        Some(Literal(_) | List(..)) => unreachable!(
             r#"Compiler bug! After type checking, a constructor can never align with a literal: that should be a type error!"#
         ),
         None => panic!("Compiler error! Empty matrices should not get specialized."),
     }
 }
 
+/// INVARIANT: (length row == N) ==> (length result == length + N - 1)
+fn specialize_row_by_list2(length: usize, mut row: Row) -> Option<Row> {
+    let head = row.pop();
+    let mut patterns = row;
+
+    match head {
+        Some(List(arity, elems)) => {
+            if arity.covers_length(length) {
+                patterns.extend(list_elems_at_length(arity, elems, length));
+                Some(patterns)
+            } else {
+                None
+            }
+        }
+        Some(Anything) => {
+            patterns.extend(std::iter::repeat(Anything).take(length));
+            Some(patterns)
+        }
+        Some(Literal(_) | Ctor(..)) => panic!(
+            r#"Compiler bug! After type checking, a list can never align with a constructor or literal: that should be a type error!"#
+        ),
+        None => panic!("Compiler error! Empty matrices should not get specialized."),
+    }
+}
+
+/// INVARIANT: (length row == N) ==> (length result == length + N - 1)
+fn specialize_row_by_list(length: usize, row: &RefRow) -> Option<Row> {
+    let mut row = row.to_vec();
+
+    let head = row.pop();
+    let patterns = row;
+
+    match head {
+        Some(List(arity, elems)) => {
+            if arity.covers_length(length) {
+                let mut new_patterns = list_elems_at_length(arity, elems, length);
+                new_patterns.extend(patterns);
+                Some(new_patterns)
+            } else {
+                None
+            }
+        }
+        Some(Anything) => {
+            let new_patterns = std::iter::repeat(Anything)
+                .take(length)
+                .chain(patterns)
+                .collect();
+            Some(new_patterns)
+        }
+        Some(Literal(_) | Ctor(..)) => unreachable!(
+            r#"Compiler bug! After type checking, a list can never align with a constructor or literal: that should be a type error!"#
+        ),
+        None => panic!("Compiler error! Empty matrices should not get specialized."),
+    }
+}
+
 /// INVARIANT: (length row == N) ==> (length result == N-1)
 fn specialize_row_by_anything(row: &RefRow) -> Option<Row> {
     let mut row = row.to_vec();
@@ -420,10 +609,27 @@ fn specialize_row_by_anything(row: &RefRow) -> Option<Row> {
 
 pub enum Complete {
     Yes(Vec<Ctor>),
+    /// Lists of every length are covered; lengths beyond the given one all match the same way.
+    YesList(usize),
     No,
 }
 
 fn is_complete(matrix: &RefPatternMatrix) -> Complete {
+    if let Some(max_len) = max_list_len(matrix) {
+        let is_length_covered = |length| {
+            matrix.iter().any(|row| match row.last() {
+                Some(List(arity, _)) => arity.covers_length(length),
+                _ => false,
+            })
+        };
+
+        return if (0..=max_len + 1).all(is_length_covered) {
+            Complete::YesList(max_len)
+        } else {
+            Complete::No
+        };
+    }
+
     let ctors = collect_ctors(matrix);
     let length = ctors.len();
     let mut it = ctors.into_iter();
@@ -447,6 +653,17 @@ type PatternMatrix = Vec<Vec<Pattern>>;
 type RefRow = [Pattern];
 type Row = Vec<Pattern>;
 
+/// The length of the longest list matched by the head of any row, if any row's head is a list
+fn max_list_len(matrix: &RefPatternMatrix) -> Option<usize> {
+    matrix
+        .iter()
+        .filter_map(|row| match row.last() {
+            Some(List(arity, _)) => Some(arity.min_len()),
+            _ => None,
+        })
+        .max()
+}
+
 fn collect_ctors(matrix: &RefPatternMatrix) -> MutMap<TagId, Union> {
     let mut ctors = MutMap::default();
 
@@ -193,6 +193,15 @@ impl<'a> Formattable for TypeAnnotation<'a> {
 
                 tags.iter().any(|tag| tag.value.is_multiline())
             }
+
+            Tuple { elems, ext } => {
+                match ext {
+                    Some(ann) if ann.value.is_multiline() => return true,
+                    _ => {}
+                }
+
+                elems.iter().any(|elem| elem.value.is_multiline())
+            }
         }
     }
 
@@ -312,6 +321,14 @@ impl<'a> Formattable for TypeAnnotation<'a> {
                 }
             }
 
+            Tuple { elems, ext } => {
+                fmt_collection(buf, indent, Braces::Round, *elems, newlines);
+
+                if let Some(loc_ext_ann) = *ext {
+                    loc_ext_ann.value.format(buf, indent);
+                }
+            }
+
             As(lhs, _spaces, TypeHeader { name, vars }) => {
                 // TODO use _spaces?
                 lhs.value
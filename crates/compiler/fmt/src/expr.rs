@@ -36,6 +36,8 @@ impl<'a> Formattable for Expr<'a> {
             | SingleQuote(_)
             | Access(_, _)
             | AccessorFunction(_)
+            | TupleAccess(_, _)
+            | TupleAccessorFunction(_)
             | Var { .. }
             | Underscore { .. }
             | MalformedIdent(_, _)
@@ -47,6 +49,7 @@ impl<'a> Formattable for Expr<'a> {
             Defs(_, _) | When(_, _) => true,
 
             List(items) => items.iter().any(|loc_expr| loc_expr.is_multiline()),
+            Tuple(items) => items.iter().any(|loc_expr| loc_expr.is_multiline()),
 
             Str(literal) => {
                 use roc_parse::ast::StrLiteral::*;
@@ -69,6 +72,7 @@ impl<'a> Formattable for Expr<'a> {
             Expect(condition, continuation) => {
                 condition.is_multiline() || continuation.is_multiline()
             }
+            Dbg(message) | Crash(message) => message.is_multiline(),
 
             If(branches, final_else) => {
                 final_else.is_multiline()
@@ -367,11 +371,18 @@ impl<'a> Formattable for Expr<'a> {
             Expect(condition, continuation) => {
                 fmt_expect(buf, condition, continuation, self.is_multiline(), indent);
             }
+            Dbg(message) => {
+                fmt_keyword_with_arg(buf, "dbg", message, self.is_multiline(), indent);
+            }
+            Crash(message) => {
+                fmt_keyword_with_arg(buf, "crash", message, self.is_multiline(), indent);
+            }
             If(branches, final_else) => {
                 fmt_if(buf, branches, final_else, self.is_multiline(), indent);
             }
             When(loc_condition, branches) => fmt_when(buf, loc_condition, branches, indent),
             List(items) => fmt_collection(buf, indent, Braces::Square, *items, Newlines::No),
+            Tuple(items) => fmt_collection(buf, indent, Braces::Round, *items, Newlines::No),
             BinOps(lefts, right) => fmt_binops(buf, lefts, right, false, parens, indent),
             UnaryOp(sub_expr, unary_op) => {
                 buf.indent(indent);
@@ -386,12 +397,12 @@ impl<'a> Formattable for Expr<'a> {
 
                 sub_expr.format_with_options(buf, Parens::InApply, newlines, indent);
             }
-            AccessorFunction(key) => {
+            AccessorFunction(key) | TupleAccessorFunction(key) => {
                 buf.indent(indent);
                 buf.push('.');
                 buf.push_str(key);
             }
-            Access(expr, key) => {
+            Access(expr, key) | TupleAccess(expr, key) => {
                 expr.format_with_options(buf, Parens::InApply, Newlines::Yes, indent);
                 buf.push('.');
                 buf.push_str(key);
@@ -835,6 +846,28 @@ fn fmt_expect<'a, 'buf>(
     continuation.format(buf, indent);
 }
 
+/// Formats keywords that take a single argument, like `dbg` and `crash`
+fn fmt_keyword_with_arg<'a, 'buf>(
+    buf: &mut Buf<'buf>,
+    keyword: &str,
+    message: &'a Loc<Expr<'a>>,
+    is_multiline: bool,
+    indent: u16,
+) {
+    buf.indent(indent);
+    buf.push_str(keyword);
+
+    let message_indent = if is_multiline {
+        buf.newline();
+        indent + INDENT
+    } else {
+        buf.spaces(1);
+        indent
+    };
+
+    message.format(buf, message_indent);
+}
+
 fn fmt_if<'a, 'buf>(
     buf: &mut Buf<'buf>,
     branches: &'a [(Loc<Expr<'a>>, Loc<Expr<'a>>)],
@@ -642,7 +642,10 @@ impl<'a> RemoveSpaces<'a> for Expr<'a> {
             Expr::Str(a) => Expr::Str(a.remove_spaces(arena)),
             Expr::Access(a, b) => Expr::Access(arena.alloc(a.remove_spaces(arena)), b),
             Expr::AccessorFunction(a) => Expr::AccessorFunction(a),
+            Expr::TupleAccess(a, b) => Expr::TupleAccess(arena.alloc(a.remove_spaces(arena)), b),
+            Expr::TupleAccessorFunction(a) => Expr::TupleAccessorFunction(a),
             Expr::List(a) => Expr::List(a.remove_spaces(arena)),
+            Expr::Tuple(a) => Expr::Tuple(a.remove_spaces(arena)),
             Expr::RecordUpdate { update, fields } => Expr::RecordUpdate {
                 update: arena.alloc(update.remove_spaces(arena)),
                 fields: fields.remove_spaces(arena),
@@ -682,6 +685,8 @@ impl<'a> RemoveSpaces<'a> for Expr<'a> {
                 arena.alloc(a.remove_spaces(arena)),
                 arena.alloc(b.remove_spaces(arena)),
             ),
+            Expr::Dbg(a) => Expr::Dbg(arena.alloc(a.remove_spaces(arena))),
+            Expr::Crash(a) => Expr::Crash(arena.alloc(a.remove_spaces(arena))),
             Expr::Apply(a, b, c) => Expr::Apply(
                 arena.alloc(a.remove_spaces(arena)),
                 b.remove_spaces(arena),
@@ -722,6 +727,9 @@ impl<'a> RemoveSpaces<'a> for Pattern<'a> {
                 arena.alloc(b.remove_spaces(arena)),
             ),
             Pattern::RecordDestructure(a) => Pattern::RecordDestructure(a.remove_spaces(arena)),
+            Pattern::Tuple(a) => Pattern::Tuple(a.remove_spaces(arena)),
+            Pattern::List(a) => Pattern::List(a.remove_spaces(arena)),
+            Pattern::ListRest(a) => Pattern::ListRest(a),
             Pattern::RequiredField(a, b) => {
                 Pattern::RequiredField(a, arena.alloc(b.remove_spaces(arena)))
             }
@@ -778,6 +786,10 @@ impl<'a> RemoveSpaces<'a> for TypeAnnotation<'a> {
                 ext: ext.remove_spaces(arena),
                 tags: tags.remove_spaces(arena),
             },
+            TypeAnnotation::Tuple { elems, ext } => TypeAnnotation::Tuple {
+                elems: elems.remove_spaces(arena),
+                ext: ext.remove_spaces(arena),
+            },
             TypeAnnotation::Inferred => TypeAnnotation::Inferred,
             TypeAnnotation::Wildcard => TypeAnnotation::Wildcard,
             TypeAnnotation::Where(annot, has_clauses) => TypeAnnotation::Where(
@@ -2795,6 +2795,54 @@ mod test_fmt {
         );
     }
 
+    // TUPLE LITERALS
+
+    #[test]
+    fn two_elem_tuple() {
+        expr_formats_same("(1, 2)");
+        expr_formats_to("( 1 , 2 )", "(1, 2)");
+    }
+
+    #[test]
+    fn tuple_newline() {
+        expr_formats_same(indoc!(
+            r#"
+            (
+                1,
+                "two",
+                3.0,
+            )"#
+        ));
+    }
+
+    #[test]
+    fn tuple_access() {
+        expr_formats_same("t.0");
+        expr_formats_same(".1");
+    }
+
+    #[test]
+    fn tuple_pattern() {
+        expr_formats_same(indoc!(
+            r#"
+            (x, y) = (1, 2)
+
+            x
+            "#
+        ));
+    }
+
+    #[test]
+    fn tuple_type() {
+        expr_formats_same(indoc!(
+            r#"
+            f : (Str, U8)
+
+            f
+            "#
+        ));
+    }
+
     // RECORD LITERALS
 
     #[test]
@@ -3425,6 +3473,43 @@ mod test_fmt {
         ));
     }
 
+    #[test]
+    fn when_with_list_patterns() {
+        expr_formats_same(indoc!(
+            r#"
+            when l is
+                [] ->
+                    0
+
+                [x] ->
+                    x
+
+                [first, .., last] ->
+                    first + last
+
+                [head, ..rest] ->
+                    head
+            "#
+        ));
+
+        expr_formats_to(
+            indoc!(
+                r#"
+                when l is
+                    [ x , .. , y ] ->
+                        x
+                "#
+            ),
+            indoc!(
+                r#"
+                when l is
+                    [x, .., y] ->
+                        x
+                "#
+            ),
+        );
+    }
+
     #[test]
     fn when_with_alternatives_1() {
         expr_formats_same(indoc!(
@@ -5457,6 +5542,53 @@ mod test_fmt {
         ));
     }
 
+    #[test]
+    fn dbg_single_line() {
+        expr_formats_same(indoc!(
+            r#"
+            x = dbg 5
+
+            dbg x + 1
+            "#
+        ));
+    }
+
+    #[test]
+    fn dbg_multiline() {
+        expr_formats_same(indoc!(
+            r#"
+            x =
+                dbg
+                    foo bar
+                    |> baz
+
+            x
+            "#
+        ));
+    }
+
+    #[test]
+    fn crash_in_branch() {
+        expr_formats_same(indoc!(
+            r#"
+            when x is
+                Ok v -> v
+                Err _ -> crash "unreachable"
+            "#
+        ));
+    }
+
+    #[test]
+    fn crash_multiline() {
+        expr_formats_same(indoc!(
+            r#"
+            crash
+                "bad input: "
+                |> Str.concat input
+            "#
+        ));
+    }
+
     // this is a parse error atm
     //    #[test]
     //    fn multiline_apply() {
@@ -0,0 +1,474 @@
+use bumpalo::collections::Vec;
+use gimli::write::{
+    Address, AttributeValue, DwarfUnit, EndianVec, FileId, LineProgram, LineString, Range,
+    RangeList, Sections, Writer,
+};
+use gimli::{Encoding, Format, LineEncoding, RunTimeEndian};
+use object::write::{Object, SectionId, StandardSegment, SymbolId};
+use object::{BinaryFormat, RelocationEncoding, RelocationKind, SectionKind};
+use roc_collections::all::MutMap;
+use roc_error_macros::internal_error;
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_region::all::{LineInfo, Region};
+use std::path::PathBuf;
+
+/// What the debug info needs to know about a procedure, next to its code.
+pub struct ProcDebugInfo<'a> {
+    /// The name debuggers and profilers show, like `UserApp.foo#1`.
+    pub name: String,
+    /// The module whose source the regions point into.
+    pub module_id: ModuleId,
+    pub region: Region,
+    /// The offsets in the procedure where the code for a region of the source starts, in increasing order.
+    pub locations: Vec<'a, (u64, Region)>,
+}
+
+impl<'a> ProcDebugInfo<'a> {
+    /// Debug info for a procedure the backend generates itself, which has no source.
+    pub fn generated(arena: &'a bumpalo::Bump, interns: &Interns, symbol: Symbol) -> Self {
+        let module_string = interns.module_ids.get_name(symbol.module_id()).unwrap();
+
+        ProcDebugInfo {
+            name: format!("{}.{}", module_string, symbol.as_str(interns)),
+            module_id: symbol.module_id(),
+            region: Region::zero(),
+            locations: bumpalo::vec![in arena],
+        }
+    }
+}
+
+/// Builds the `.debug_info` and `.debug_line` sections of an object,
+/// which map the generated machine code back to the Roc source.
+pub struct DebugInfo {
+    dwarf: DwarfUnit,
+    files: MutMap<ModuleId, (FileId, LineInfo)>,
+    /// The symbols that `Address::Symbol` refers to, by index.
+    symbols: std::vec::Vec<SymbolId>,
+    ranges: std::vec::Vec<Range>,
+}
+
+impl DebugInfo {
+    pub fn new(
+        sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+        module_id: ModuleId,
+        address_size: u8,
+    ) -> Self {
+        let encoding = Encoding {
+            format: Format::Dwarf32,
+            version: 4,
+            address_size,
+        };
+
+        let (comp_dir, comp_name) = match sources.get(&module_id) {
+            Some((path, _)) => split_path(path),
+            None => (".".to_string(), "app.roc".to_string()),
+        };
+
+        let mut dwarf = DwarfUnit::new(encoding);
+        dwarf.unit.line_program = LineProgram::new(
+            encoding,
+            LineEncoding::default(),
+            LineString::String(comp_dir.clone().into_bytes()),
+            LineString::String(comp_name.clone().into_bytes()),
+            None,
+        );
+
+        let mut files = MutMap::default();
+        for (source_module_id, (path, src)) in sources.iter() {
+            let (dir, name) = split_path(path);
+            let program = &mut dwarf.unit.line_program;
+            let dir_id = program.add_directory(LineString::String(dir.into_bytes()));
+            let file_id = program.add_file(LineString::String(name.into_bytes()), dir_id, None);
+
+            files.insert(*source_module_id, (file_id, LineInfo::new(src)));
+        }
+
+        let root = dwarf.unit.root();
+        let entry = dwarf.unit.get_mut(root);
+        entry.set(
+            gimli::DW_AT_producer,
+            AttributeValue::String(b"roc dev backend".to_vec()),
+        );
+        entry.set(
+            gimli::DW_AT_name,
+            AttributeValue::String(comp_name.into_bytes()),
+        );
+        entry.set(
+            gimli::DW_AT_comp_dir,
+            AttributeValue::String(comp_dir.into_bytes()),
+        );
+
+        DebugInfo {
+            dwarf,
+            files,
+            symbols: std::vec::Vec::new(),
+            ranges: std::vec::Vec::new(),
+        }
+    }
+
+    /// Describes the procedure that `proc_id` points at, which is `size` bytes long.
+    pub fn add_proc(
+        &mut self,
+        proc_id: SymbolId,
+        linkage_name: &str,
+        size: u64,
+        proc_debug_info: ProcDebugInfo<'_>,
+    ) {
+        let address = Address::Symbol {
+            symbol: self.symbols.len(),
+            addend: 0,
+        };
+        self.symbols.push(proc_id);
+        self.ranges.push(Range::StartLength {
+            begin: address,
+            length: size,
+        });
+
+        let ProcDebugInfo {
+            name,
+            module_id,
+            region,
+            locations,
+        } = proc_debug_info;
+
+        let root = self.dwarf.unit.root();
+        let subprogram = self.dwarf.unit.add(root, gimli::DW_TAG_subprogram);
+        let entry = self.dwarf.unit.get_mut(subprogram);
+        entry.set(gimli::DW_AT_name, AttributeValue::String(name.into_bytes()));
+        entry.set(
+            gimli::DW_AT_linkage_name,
+            AttributeValue::String(linkage_name.as_bytes().to_vec()),
+        );
+        entry.set(gimli::DW_AT_low_pc, AttributeValue::Address(address));
+        entry.set(gimli::DW_AT_high_pc, AttributeValue::Udata(size));
+
+        let (file_id, line_info) = match self.files.get(&module_id) {
+            Some(file) if region != Region::zero() => file,
+            _ => return,
+        };
+        let line = line_info.convert_pos(region.start()).line as u64 + 1;
+        entry.set(
+            gimli::DW_AT_decl_file,
+            AttributeValue::FileIndex(Some(*file_id)),
+        );
+        entry.set(gimli::DW_AT_decl_line, AttributeValue::Udata(line));
+
+        let program = &mut self.dwarf.unit.line_program;
+        program.begin_sequence(Some(address));
+        for (index, (offset, region)) in locations.iter().enumerate() {
+            // Several statements start at the same offset when the first ones generate no code.
+            // The last of them is the one the code belongs to.
+            let superseded = matches!(locations.get(index + 1), Some((next, _)) if next == offset);
+            if superseded || *offset >= size {
+                continue;
+            }
+
+            let position = line_info.convert_pos(region.start());
+            let row = program.row();
+            row.address_offset = *offset;
+            row.file = *file_id;
+            row.line = position.line as u64 + 1;
+            row.column = position.column as u64 + 1;
+            program.generate_row();
+        }
+        program.end_sequence(size);
+    }
+
+    /// Writes the debug sections and their relocations to the object.
+    pub fn write(mut self, output: &mut Object, format: BinaryFormat) {
+        let root = self.dwarf.unit.root();
+        let ranges = self
+            .dwarf
+            .unit
+            .ranges
+            .add(RangeList(std::mem::take(&mut self.ranges)));
+        let entry = self.dwarf.unit.get_mut(root);
+        entry.set(
+            gimli::DW_AT_low_pc,
+            AttributeValue::Address(Address::Constant(0)),
+        );
+        entry.set(gimli::DW_AT_ranges, AttributeValue::RangeListRef(ranges));
+
+        let mut sections = Sections::new(DebugSection::new());
+        if let Err(e) = self.dwarf.write(&mut sections) {
+            internal_error!("failed to write debug info: {:?}", e);
+        }
+
+        // Create all sections first, since relocations can point into any of them.
+        let mut section_ids: MutMap<gimli::SectionId, SectionId> = MutMap::default();
+        let _: Result<(), ()> = sections.for_each(|id, section| {
+            if !section.data.slice().is_empty() {
+                let name = match format {
+                    BinaryFormat::MachO => format!("__{}", &id.name()[1..]),
+                    _ => id.name().to_string(),
+                };
+                let section_id = output.add_section(
+                    output.segment_name(StandardSegment::Debug).to_vec(),
+                    name.into_bytes(),
+                    SectionKind::Debug,
+                );
+                output.append_section_data(section_id, section.data.slice(), 1);
+                section_ids.insert(id, section_id);
+            }
+            Ok(())
+        });
+
+        let _: Result<(), ()> = sections.for_each(|id, section| {
+            let section_id = match section_ids.get(&id) {
+                Some(section_id) => *section_id,
+                None => return Ok(()),
+            };
+
+            for reloc in section.relocations.iter() {
+                let symbol = match reloc.target {
+                    DebugTarget::Symbol(index) => self.symbols[index],
+                    // Mach-O keeps the debug info in the object files and does not relocate offsets into it.
+                    DebugTarget::Section(_) if format == BinaryFormat::MachO => continue,
+                    DebugTarget::Section(target) => match section_ids.get(&target) {
+                        Some(target_id) => output.section_symbol(*target_id),
+                        None => continue,
+                    },
+                };
+
+                let relocation = object::write::Relocation {
+                    offset: reloc.offset,
+                    size: reloc.size * 8,
+                    kind: RelocationKind::Absolute,
+                    encoding: RelocationEncoding::Generic,
+                    symbol,
+                    addend: reloc.addend,
+                };
+                if let Err(e) = output.add_relocation(section_id, relocation) {
+                    internal_error!("{:?}", e);
+                }
+            }
+            Ok(())
+        });
+    }
+}
+
+fn split_path(path: &std::path::Path) -> (String, String) {
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_string_lossy().to_string(),
+        _ => ".".to_string(),
+    };
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    (dir, name)
+}
+
+#[derive(Clone, Copy, Debug)]
+enum DebugTarget {
+    Symbol(usize),
+    Section(gimli::SectionId),
+}
+
+#[derive(Clone, Debug)]
+struct DebugRelocation {
+    offset: u64,
+    size: u8,
+    target: DebugTarget,
+    addend: i64,
+}
+
+/// A debug section that remembers where it refers to symbols or other sections,
+/// so those places can be relocated by the linker.
+#[derive(Clone, Debug)]
+struct DebugSection {
+    data: EndianVec<RunTimeEndian>,
+    relocations: std::vec::Vec<DebugRelocation>,
+}
+
+impl DebugSection {
+    fn new() -> Self {
+        DebugSection {
+            data: EndianVec::new(RunTimeEndian::Little),
+            relocations: std::vec::Vec::new(),
+        }
+    }
+}
+
+impl Writer for DebugSection {
+    type Endian = RunTimeEndian;
+
+    fn endian(&self) -> Self::Endian {
+        self.data.endian()
+    }
+
+    fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    fn write(&mut self, bytes: &[u8]) -> gimli::write::Result<()> {
+        self.data.write(bytes)
+    }
+
+    fn write_at(&mut self, offset: usize, bytes: &[u8]) -> gimli::write::Result<()> {
+        self.data.write_at(offset, bytes)
+    }
+
+    fn write_address(&mut self, address: Address, size: u8) -> gimli::write::Result<()> {
+        match address {
+            Address::Constant(value) => self.write_udata(value, size),
+            Address::Symbol { symbol, addend } => {
+                self.relocations.push(DebugRelocation {
+                    offset: self.len() as u64,
+                    size,
+                    target: DebugTarget::Symbol(symbol),
+                    addend,
+                });
+                self.write_udata(0, size)
+            }
+        }
+    }
+
+    fn write_offset(
+        &mut self,
+        value: usize,
+        section: gimli::SectionId,
+        size: u8,
+    ) -> gimli::write::Result<()> {
+        self.relocations.push(DebugRelocation {
+            offset: self.len() as u64,
+            size,
+            target: DebugTarget::Section(section),
+            addend: value as i64,
+        });
+        self.write_udata(value as u64, size)
+    }
+
+    fn write_offset_at(
+        &mut self,
+        offset: usize,
+        value: usize,
+        section: gimli::SectionId,
+        size: u8,
+    ) -> gimli::write::Result<()> {
+        self.relocations.push(DebugRelocation {
+            offset: offset as u64,
+            size,
+            target: DebugTarget::Section(section),
+            addend: value as i64,
+        });
+        self.write_udata_at(offset, value as u64, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use object::write::{Symbol as ObjectSymbol, SymbolSection};
+    use object::{Architecture, Endianness};
+    use object::{Object as _, ObjectSection, SymbolFlags, SymbolKind, SymbolScope};
+    use roc_region::all::Position;
+
+    fn region(start: u32, end: u32) -> Region {
+        Region::new(Position::new(start), Position::new(end))
+    }
+
+    #[test]
+    fn test_debug_sections_and_relocations() {
+        let arena = bumpalo::Bump::new();
+        let home = ModuleId::ATTR;
+        let mut sources = MutMap::default();
+        sources.insert(
+            home,
+            (
+                PathBuf::from("examples/main.roc"),
+                "main =\n    x = 1\n    x\n".into(),
+            ),
+        );
+
+        let mut output = Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little);
+        let text = output.add_section(vec![], b".text.main".to_vec(), SectionKind::Text);
+        let proc_id = output.add_symbol(ObjectSymbol {
+            name: b"main".to_vec(),
+            value: 0,
+            size: 0,
+            kind: SymbolKind::Text,
+            scope: SymbolScope::Linkage,
+            weak: false,
+            section: SymbolSection::Section(text),
+            flags: SymbolFlags::None,
+        });
+        output.add_symbol_data(proc_id, text, &[0x90; 16], 16);
+
+        let mut debug_info = DebugInfo::new(&sources, home, 8);
+        debug_info.add_proc(
+            proc_id,
+            "main",
+            16,
+            ProcDebugInfo {
+                name: "Test.main#1".to_string(),
+                module_id: home,
+                region: region(0, 4),
+                // `x = 1` and `x`
+                locations: bumpalo::vec![in &arena; (0, region(11, 16)), (8, region(21, 22))],
+            },
+        );
+        debug_info.write(&mut output, BinaryFormat::Elf);
+
+        let bytes = output.write().unwrap();
+        let file = object::File::parse(bytes.as_slice()).unwrap();
+
+        let debug_info_section = file.section_by_name(".debug_info").unwrap();
+        assert!(debug_info_section.size() > 0);
+        // The address of the proc and the offset of the abbreviations need to be relocated.
+        assert!(debug_info_section.relocations().count() >= 2);
+        assert!(file.section_by_name(".debug_abbrev").is_some());
+        let debug_line_section = file.section_by_name(".debug_line").unwrap();
+        // The line program starts at the address of the proc.
+        assert!(debug_line_section.relocations().count() >= 1);
+
+        // Each location is a row in the line program, with 1-based lines and columns.
+        let debug_line =
+            gimli::read::DebugLine::new(debug_line_section.data().unwrap(), gimli::LittleEndian);
+        let program = debug_line
+            .program(gimli::DebugLineOffset(0), 8, None, None)
+            .unwrap();
+        let read_string = |value: gimli::read::AttributeValue<gimli::EndianSlice<_>>| match value {
+            gimli::read::AttributeValue::String(s) => {
+                String::from_utf8_lossy(s.slice()).to_string()
+            }
+            other => panic!("expected an inline string, got {:?}", other),
+        };
+
+        let mut rows = program.rows();
+        let mut decoded = std::vec::Vec::new();
+        let mut end_address = None;
+        while let Some((header, row)) = rows.next_row().unwrap() {
+            if row.end_sequence() {
+                end_address = Some(row.address());
+                continue;
+            }
+
+            let file = row.file(header).unwrap();
+            let column = match row.column() {
+                gimli::read::ColumnType::LeftEdge => 0,
+                gimli::read::ColumnType::Column(column) => column.get(),
+            };
+            decoded.push((
+                row.address(),
+                read_string(file.directory(header).unwrap()),
+                read_string(file.path_name()),
+                row.line().unwrap().get(),
+                column,
+            ));
+        }
+
+        let row = |address, line, column| {
+            (
+                address,
+                "examples".to_string(),
+                "main.roc".to_string(),
+                line,
+                column,
+            )
+        };
+        assert_eq!(decoded, vec![row(0, 2, 5), row(8, 3, 5)]);
+        assert_eq!(end_address, Some(16));
+    }
+}
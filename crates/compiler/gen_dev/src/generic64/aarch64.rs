@@ -1,10 +1,17 @@
 use crate::generic64::{storage::StorageManager, Assembler, CallConv, RegTrait};
-use crate::Relocation;
+use crate::{
+    pointer_layouts, single_register_floats, single_register_int_builtins,
+    single_register_integers, single_register_layouts, Relocation,
+};
 use bumpalo::collections::Vec;
 use packed_struct::prelude::*;
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
 use roc_error_macros::internal_error;
 use roc_module::symbol::Symbol;
-use roc_mono::layout::Layout;
+use roc_mono::layout::{Builtin, Layout, UnionLayout};
+use roc_target::TargetInfo;
+
+const TARGET_INFO: TargetInfo = TargetInfo::default_aarch64();
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
 #[allow(dead_code)]
@@ -101,7 +108,40 @@ impl AArch64GeneralReg {
 
 #[derive(Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
 #[allow(dead_code)]
-pub enum AArch64FloatReg {}
+pub enum AArch64FloatReg {
+    V0 = 0,
+    V1 = 1,
+    V2 = 2,
+    V3 = 3,
+    V4 = 4,
+    V5 = 5,
+    V6 = 6,
+    V7 = 7,
+    V8 = 8,
+    V9 = 9,
+    V10 = 10,
+    V11 = 11,
+    V12 = 12,
+    V13 = 13,
+    V14 = 14,
+    V15 = 15,
+    V16 = 16,
+    V17 = 17,
+    V18 = 18,
+    V19 = 19,
+    V20 = 20,
+    V21 = 21,
+    V22 = 22,
+    V23 = 23,
+    V24 = 24,
+    V25 = 25,
+    V26 = 26,
+    V27 = 27,
+    V28 = 28,
+    V29 = 29,
+    V30 = 30,
+    V31 = 31,
+}
 impl RegTrait for AArch64FloatReg {
     fn value(&self) -> u8 {
         *self as u8
@@ -109,7 +149,14 @@ impl RegTrait for AArch64FloatReg {
 }
 impl std::fmt::Display for AArch64FloatReg {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "TODO",)
+        write!(f, "v{}", self.id())
+    }
+}
+
+impl AArch64FloatReg {
+    #[inline(always)]
+    fn id(&self) -> u8 {
+        *self as u8
     }
 }
 
@@ -137,7 +184,8 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         AArch64GeneralReg::X6,
         AArch64GeneralReg::X7,
     ];
-    const GENERAL_RETURN_REGS: &'static [AArch64GeneralReg] = Self::GENERAL_PARAM_REGS;
+    const GENERAL_RETURN_REGS: &'static [AArch64GeneralReg] =
+        &[AArch64GeneralReg::X0, AArch64GeneralReg::X1];
     const GENERAL_DEFAULT_FREE_REGS: &'static [AArch64GeneralReg] = &[
         // The regs we want to use first should be at the end of this vec.
         // We will use pop to get which reg to use next
@@ -147,6 +195,8 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         // Don't use platform register: AArch64GeneralReg::PR,
         // Don't use link register: AArch64GeneralReg::LR,
         // Don't use zero register/stack pointer: AArch64GeneralReg::ZRSP,
+        // Don't use the intra procedure call scratch registers: AArch64GeneralReg::IP0, AArch64GeneralReg::IP1,
+        // The assembler needs them for immediates and offsets that don't fit in an instruction.
 
         // Use callee saved regs last.
         AArch64GeneralReg::X19,
@@ -175,12 +225,58 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         AArch64GeneralReg::X13,
         AArch64GeneralReg::X14,
         AArch64GeneralReg::X15,
-        AArch64GeneralReg::IP0,
-        AArch64GeneralReg::IP1,
     ];
-    const FLOAT_PARAM_REGS: &'static [AArch64FloatReg] = &[];
-    const FLOAT_RETURN_REGS: &'static [AArch64FloatReg] = Self::FLOAT_PARAM_REGS;
-    const FLOAT_DEFAULT_FREE_REGS: &'static [AArch64FloatReg] = &[];
+    const FLOAT_PARAM_REGS: &'static [AArch64FloatReg] = &[
+        AArch64FloatReg::V0,
+        AArch64FloatReg::V1,
+        AArch64FloatReg::V2,
+        AArch64FloatReg::V3,
+        AArch64FloatReg::V4,
+        AArch64FloatReg::V5,
+        AArch64FloatReg::V6,
+        AArch64FloatReg::V7,
+    ];
+    const FLOAT_RETURN_REGS: &'static [AArch64FloatReg] =
+        &[AArch64FloatReg::V0, AArch64FloatReg::V1];
+    const FLOAT_DEFAULT_FREE_REGS: &'static [AArch64FloatReg] = &[
+        // The regs we want to use first should be at the end of this vec.
+        // We will use pop to get which reg to use next
+
+        // Use callee saved regs last.
+        AArch64FloatReg::V8,
+        AArch64FloatReg::V9,
+        AArch64FloatReg::V10,
+        AArch64FloatReg::V11,
+        AArch64FloatReg::V12,
+        AArch64FloatReg::V13,
+        AArch64FloatReg::V14,
+        AArch64FloatReg::V15,
+        // Use caller saved regs first.
+        AArch64FloatReg::V31,
+        AArch64FloatReg::V30,
+        AArch64FloatReg::V29,
+        AArch64FloatReg::V28,
+        AArch64FloatReg::V27,
+        AArch64FloatReg::V26,
+        AArch64FloatReg::V25,
+        AArch64FloatReg::V24,
+        AArch64FloatReg::V23,
+        AArch64FloatReg::V22,
+        AArch64FloatReg::V21,
+        AArch64FloatReg::V20,
+        AArch64FloatReg::V19,
+        AArch64FloatReg::V18,
+        AArch64FloatReg::V17,
+        AArch64FloatReg::V16,
+        AArch64FloatReg::V7,
+        AArch64FloatReg::V6,
+        AArch64FloatReg::V5,
+        AArch64FloatReg::V4,
+        AArch64FloatReg::V3,
+        AArch64FloatReg::V2,
+        AArch64FloatReg::V1,
+        AArch64FloatReg::V0,
+    ];
 
     const SHADOW_SPACE_SIZE: u8 = 0;
 
@@ -201,8 +297,19 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         )
     }
     #[inline(always)]
-    fn float_callee_saved(_reg: &AArch64FloatReg) -> bool {
-        todo!("AArch64 FloatRegs");
+    fn float_callee_saved(reg: &AArch64FloatReg) -> bool {
+        // Only the bottom 64 bits of these need to be preserved, which is all we ever use.
+        matches!(
+            reg,
+            AArch64FloatReg::V8
+                | AArch64FloatReg::V9
+                | AArch64FloatReg::V10
+                | AArch64FloatReg::V11
+                | AArch64FloatReg::V12
+                | AArch64FloatReg::V13
+                | AArch64FloatReg::V14
+                | AArch64FloatReg::V15
+        )
     }
 
     #[inline(always)]
@@ -213,9 +320,19 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         requested_stack_size: i32,
         fn_call_stack_size: i32,
     ) -> i32 {
-        // Full size is upcast to i64 to make sure we don't overflow here.
+        // Save the frame pointer and link register as a pair, then point the frame pointer at them.
+        // This mirrors `push rbp; mov rbp, rsp` on x86, so args passed on the stack start at FP + 16.
+        stp_pre_reg64_reg64_reg64_imm7(
+            buf,
+            AArch64GeneralReg::FP,
+            AArch64GeneralReg::LR,
+            AArch64GeneralReg::ZRSP,
+            -2,
+        );
+        add_reg64_reg64_imm12(buf, AArch64GeneralReg::FP, AArch64GeneralReg::ZRSP, 0);
+
         let full_stack_size = match requested_stack_size
-            .checked_add(8 * (saved_general_regs.len() + saved_float_regs.len()) as i32 + 8) // The extra 8 is space to store the frame pointer.
+            .checked_add(8 * (saved_general_regs.len() + saved_float_regs.len()) as i32)
             .and_then(|size| size.checked_add(fn_call_stack_size))
         {
             Some(size) => size,
@@ -233,11 +350,6 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         };
         if let Some(aligned_stack_size) = full_stack_size.checked_add(offset as i32) {
             if aligned_stack_size > 0 {
-                AArch64Assembler::mov_reg64_reg64(
-                    buf,
-                    AArch64GeneralReg::FP,
-                    AArch64GeneralReg::ZRSP,
-                );
                 AArch64Assembler::sub_reg64_reg64_imm32(
                     buf,
                     AArch64GeneralReg::ZRSP,
@@ -245,21 +357,15 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
                     aligned_stack_size,
                 );
 
-                // All the following stores could be optimized by using `STP` to store pairs.
-                let mut offset = aligned_stack_size;
-                offset -= 8;
-                AArch64Assembler::mov_stack32_reg64(buf, offset, AArch64GeneralReg::LR);
-                offset -= 8;
-                AArch64Assembler::mov_stack32_reg64(buf, offset, AArch64GeneralReg::FP);
-
-                offset = aligned_stack_size - fn_call_stack_size;
+                // Put values at the top of the stack to avoid conflicts with previously saved variables.
+                let mut offset = aligned_stack_size - fn_call_stack_size;
                 for reg in saved_general_regs {
+                    AArch64Assembler::mov_base32_reg64(buf, -offset, *reg);
                     offset -= 8;
-                    AArch64Assembler::mov_base32_reg64(buf, offset, *reg);
                 }
                 for reg in saved_float_regs {
+                    AArch64Assembler::mov_base32_freg64(buf, -offset, *reg);
                     offset -= 8;
-                    AArch64Assembler::mov_base32_freg64(buf, offset, *reg);
                 }
                 aligned_stack_size
             } else {
@@ -279,21 +385,14 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
         fn_call_stack_size: i32,
     ) {
         if aligned_stack_size > 0 {
-            // All the following stores could be optimized by using `STP` to store pairs.
-            let mut offset = aligned_stack_size;
-            offset -= 8;
-            AArch64Assembler::mov_reg64_stack32(buf, AArch64GeneralReg::LR, offset);
-            offset -= 8;
-            AArch64Assembler::mov_reg64_stack32(buf, AArch64GeneralReg::FP, offset);
-
-            offset = aligned_stack_size - fn_call_stack_size;
+            let mut offset = aligned_stack_size - fn_call_stack_size;
             for reg in saved_general_regs {
+                AArch64Assembler::mov_reg64_base32(buf, *reg, -offset);
                 offset -= 8;
-                AArch64Assembler::mov_reg64_base32(buf, *reg, offset);
             }
             for reg in saved_float_regs {
+                AArch64Assembler::mov_freg64_base32(buf, *reg, -offset);
                 offset -= 8;
-                AArch64Assembler::mov_freg64_base32(buf, *reg, offset);
             }
             AArch64Assembler::add_reg64_reg64_imm32(
                 buf,
@@ -302,87 +401,384 @@ impl CallConv<AArch64GeneralReg, AArch64FloatReg, AArch64Assembler> for AArch64C
                 aligned_stack_size,
             );
         }
+        ldp_post_reg64_reg64_reg64_imm7(
+            buf,
+            AArch64GeneralReg::FP,
+            AArch64GeneralReg::LR,
+            AArch64GeneralReg::ZRSP,
+            2,
+        );
     }
 
     #[inline(always)]
     fn load_args<'a>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             AArch64GeneralReg,
             AArch64FloatReg,
             AArch64Assembler,
             AArch64Call,
         >,
-        _args: &'a [(Layout<'a>, Symbol)],
-        _ret_layout: &Layout<'a>,
+        args: &'a [(Layout<'a>, Symbol)],
+        ret_layout: &Layout<'a>,
     ) {
-        todo!("Loading args for AArch64");
+        let mut arg_offset = Self::SHADOW_SPACE_SIZE as i32 + 16; // 16 is the size of the saved frame pointer and link register.
+        let mut general_i = 0;
+        let mut float_i = 0;
+        if Self::returns_via_arg_pointer(ret_layout) {
+            // The result pointer has its own register and does not take up a param reg.
+            storage_manager.ret_pointer_arg(AArch64GeneralReg::XR);
+        }
+        for (layout, sym) in args.iter() {
+            let stack_size = layout.stack_size(TARGET_INFO);
+            match layout {
+                single_register_integers!() => {
+                    if general_i < Self::GENERAL_PARAM_REGS.len() {
+                        storage_manager.general_reg_arg(sym, Self::GENERAL_PARAM_REGS[general_i]);
+                        general_i += 1;
+                    } else {
+                        storage_manager.primitive_stack_arg(sym, arg_offset);
+                        arg_offset += 8;
+                    }
+                }
+                single_register_floats!() => {
+                    if float_i < Self::FLOAT_PARAM_REGS.len() {
+                        storage_manager.float_reg_arg(sym, Self::FLOAT_PARAM_REGS[float_i]);
+                        float_i += 1;
+                    } else {
+                        storage_manager.primitive_stack_arg(sym, arg_offset);
+                        arg_offset += 8;
+                    }
+                }
+                _ if stack_size == 0 => {
+                    storage_manager.no_data_arg(sym);
+                }
+                _ if Self::passed_by_reference(layout) => {
+                    // The caller passes a pointer to the data.
+                    // Copy it into this frame so it can be used like any other complex symbol.
+                    // Nothing has been allocated to X9 at this point, so it is safe to use as a buffer.
+                    let ptr_reg = if general_i < Self::GENERAL_PARAM_REGS.len() {
+                        general_i += 1;
+                        Self::GENERAL_PARAM_REGS[general_i - 1]
+                    } else {
+                        AArch64Assembler::mov_reg64_base32(buf, AArch64GeneralReg::IP1, arg_offset);
+                        arg_offset += 8;
+                        AArch64GeneralReg::IP1
+                    };
+                    let base_offset = storage_manager.claim_stack_area(sym, stack_size);
+                    for i in (0..stack_size as i32).step_by(8) {
+                        AArch64Assembler::mov_reg64_mem64_offset32(
+                            buf,
+                            AArch64GeneralReg::X9,
+                            ptr_reg,
+                            i,
+                        );
+                        AArch64Assembler::mov_base32_reg64(
+                            buf,
+                            base_offset + i,
+                            AArch64GeneralReg::X9,
+                        );
+                    }
+                }
+                _ => {
+                    // Small composites are passed in consecutive general registers.
+                    // If they don't all fit, it goes on the stack and so does every later general arg.
+                    let reg_count = (stack_size as usize + 7) / 8;
+                    if layout.alignment_bytes(TARGET_INFO) == 16 {
+                        general_i += general_i % 2;
+                    }
+                    if general_i + reg_count <= Self::GENERAL_PARAM_REGS.len() {
+                        let base_offset = storage_manager.claim_stack_area(sym, stack_size);
+                        for i in 0..reg_count {
+                            AArch64Assembler::mov_base32_reg64(
+                                buf,
+                                base_offset + 8 * i as i32,
+                                Self::GENERAL_PARAM_REGS[general_i + i],
+                            );
+                        }
+                        general_i += reg_count;
+                    } else {
+                        general_i = Self::GENERAL_PARAM_REGS.len();
+                        storage_manager.complex_stack_arg(sym, arg_offset, stack_size);
+                        arg_offset += 8 * reg_count as i32;
+                    }
+                }
+            }
+        }
     }
 
     #[inline(always)]
     fn store_args<'a>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             AArch64GeneralReg,
             AArch64FloatReg,
             AArch64Assembler,
             AArch64Call,
         >,
-        _dst: &Symbol,
-        _args: &[Symbol],
-        _arg_layouts: &[Layout<'a>],
-        _ret_layout: &Layout<'a>,
+        dst: &Symbol,
+        args: &[Symbol],
+        arg_layouts: &[Layout<'a>],
+        ret_layout: &Layout<'a>,
     ) {
-        todo!("Storing args for AArch64");
+        let mut tmp_stack_offset = Self::SHADOW_SPACE_SIZE as i32;
+        let mut general_i = 0;
+        let mut float_i = 0;
+        if Self::returns_via_arg_pointer(ret_layout) {
+            // Save space on the stack for the result we will be return.
+            let base_offset =
+                storage_manager.claim_stack_area(dst, ret_layout.stack_size(TARGET_INFO));
+            // Set the result pointer reg to the address base + offset.
+            AArch64Assembler::add_reg64_reg64_imm32(
+                buf,
+                AArch64GeneralReg::XR,
+                AArch64GeneralReg::FP,
+                base_offset,
+            );
+        }
+        for (sym, layout) in args.iter().zip(arg_layouts.iter()) {
+            let stack_size = layout.stack_size(TARGET_INFO);
+            match layout {
+                single_register_integers!() => {
+                    if general_i < Self::GENERAL_PARAM_REGS.len() {
+                        storage_manager.load_to_specified_general_reg(
+                            buf,
+                            sym,
+                            Self::GENERAL_PARAM_REGS[general_i],
+                        );
+                        general_i += 1;
+                    } else {
+                        // Copy to stack using IP1 as buffer.
+                        storage_manager.load_to_specified_general_reg(
+                            buf,
+                            sym,
+                            AArch64GeneralReg::IP1,
+                        );
+                        AArch64Assembler::mov_stack32_reg64(
+                            buf,
+                            tmp_stack_offset,
+                            AArch64GeneralReg::IP1,
+                        );
+                        tmp_stack_offset += 8;
+                    }
+                }
+                single_register_floats!() => {
+                    if float_i < Self::FLOAT_PARAM_REGS.len() {
+                        storage_manager.load_to_specified_float_reg(
+                            buf,
+                            sym,
+                            Self::FLOAT_PARAM_REGS[float_i],
+                        );
+                        float_i += 1;
+                    } else {
+                        // Every float reg may be holding an arg, so copy the bits through IP1 instead.
+                        storage_manager.ensure_symbol_on_stack(buf, sym);
+                        let (base_offset, _) = storage_manager.stack_offset_and_size(sym);
+                        AArch64Assembler::mov_reg64_base32(
+                            buf,
+                            AArch64GeneralReg::IP1,
+                            base_offset,
+                        );
+                        AArch64Assembler::mov_stack32_reg64(
+                            buf,
+                            tmp_stack_offset,
+                            AArch64GeneralReg::IP1,
+                        );
+                        tmp_stack_offset += 8;
+                    }
+                }
+                _ if stack_size == 0 => {}
+                _ if Self::passed_by_reference(layout) => {
+                    // Pass a pointer to our copy of the data.
+                    // Roc never mutates values in place, so there is no need to make another one.
+                    let (base_offset, _) = storage_manager.stack_offset_and_size(sym);
+                    if general_i < Self::GENERAL_PARAM_REGS.len() {
+                        AArch64Assembler::add_reg64_reg64_imm32(
+                            buf,
+                            Self::GENERAL_PARAM_REGS[general_i],
+                            AArch64GeneralReg::FP,
+                            base_offset,
+                        );
+                        general_i += 1;
+                    } else {
+                        AArch64Assembler::add_reg64_reg64_imm32(
+                            buf,
+                            AArch64GeneralReg::IP1,
+                            AArch64GeneralReg::FP,
+                            base_offset,
+                        );
+                        AArch64Assembler::mov_stack32_reg64(
+                            buf,
+                            tmp_stack_offset,
+                            AArch64GeneralReg::IP1,
+                        );
+                        tmp_stack_offset += 8;
+                    }
+                }
+                _ => {
+                    let (base_offset, size) = storage_manager.stack_offset_and_size(sym);
+                    debug_assert_eq!(base_offset % 8, 0);
+                    let reg_count = (size as usize + 7) / 8;
+                    if layout.alignment_bytes(TARGET_INFO) == 16 {
+                        general_i += general_i % 2;
+                    }
+                    if general_i + reg_count <= Self::GENERAL_PARAM_REGS.len() {
+                        for i in 0..reg_count {
+                            AArch64Assembler::mov_reg64_base32(
+                                buf,
+                                Self::GENERAL_PARAM_REGS[general_i + i],
+                                base_offset + 8 * i as i32,
+                            );
+                        }
+                        general_i += reg_count;
+                    } else {
+                        general_i = Self::GENERAL_PARAM_REGS.len();
+                        for i in (0..size as i32).step_by(8) {
+                            AArch64Assembler::mov_reg64_base32(
+                                buf,
+                                AArch64GeneralReg::IP1,
+                                base_offset + i,
+                            );
+                            AArch64Assembler::mov_stack32_reg64(
+                                buf,
+                                tmp_stack_offset + i,
+                                AArch64GeneralReg::IP1,
+                            );
+                        }
+                        tmp_stack_offset += 8 * reg_count as i32;
+                    }
+                }
+            }
+        }
+        storage_manager.update_fn_call_stack_size(tmp_stack_offset as u32);
     }
 
     fn return_complex_symbol<'a>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             AArch64GeneralReg,
             AArch64FloatReg,
             AArch64Assembler,
             AArch64Call,
         >,
-        _sym: &Symbol,
-        _layout: &Layout<'a>,
+        sym: &Symbol,
+        layout: &Layout<'a>,
     ) {
-        todo!("Returning complex symbols for AArch64");
+        match layout {
+            single_register_layouts!() => {
+                internal_error!("single register layouts are not complex symbols");
+            }
+            x if x.stack_size(TARGET_INFO) == 0 => {}
+            x if !Self::returns_via_arg_pointer(x) => {
+                let (base_offset, size) = storage_manager.stack_offset_and_size(sym);
+                debug_assert_eq!(base_offset % 8, 0);
+                if size <= 8 {
+                    AArch64Assembler::mov_reg64_base32(
+                        buf,
+                        Self::GENERAL_RETURN_REGS[0],
+                        base_offset,
+                    );
+                } else if size <= 16 {
+                    AArch64Assembler::mov_reg64_base32(
+                        buf,
+                        Self::GENERAL_RETURN_REGS[0],
+                        base_offset,
+                    );
+                    AArch64Assembler::mov_reg64_base32(
+                        buf,
+                        Self::GENERAL_RETURN_REGS[1],
+                        base_offset + 8,
+                    );
+                } else {
+                    internal_error!(
+                        "types that don't return via arg pointer must be less than 16 bytes"
+                    );
+                }
+            }
+            _ => {
+                // This is a large type returned via the result pointer.
+                // Unlike x86, the pointer does not need to be returned as well.
+                storage_manager.copy_symbol_to_arg_pointer(buf, sym, layout);
+            }
+        }
     }
 
     fn load_returned_complex_symbol<'a>(
-        _buf: &mut Vec<'a, u8>,
-        _storage_manager: &mut StorageManager<
+        buf: &mut Vec<'a, u8>,
+        storage_manager: &mut StorageManager<
             'a,
             AArch64GeneralReg,
             AArch64FloatReg,
             AArch64Assembler,
             AArch64Call,
         >,
-        _sym: &Symbol,
-        _layout: &Layout<'a>,
+        sym: &Symbol,
+        layout: &Layout<'a>,
     ) {
-        todo!("Loading returned complex symbols for AArch64");
+        match layout {
+            single_register_layouts!() => {
+                internal_error!("single register layouts are not complex symbols");
+            }
+            x if x.stack_size(TARGET_INFO) == 0 => {}
+            x if !Self::returns_via_arg_pointer(x) => {
+                let size = layout.stack_size(TARGET_INFO);
+                let offset = storage_manager.claim_stack_area(sym, size);
+                if size <= 8 {
+                    AArch64Assembler::mov_base32_reg64(buf, offset, Self::GENERAL_RETURN_REGS[0]);
+                } else if size <= 16 {
+                    AArch64Assembler::mov_base32_reg64(buf, offset, Self::GENERAL_RETURN_REGS[0]);
+                    AArch64Assembler::mov_base32_reg64(
+                        buf,
+                        offset + 8,
+                        Self::GENERAL_RETURN_REGS[1],
+                    );
+                } else {
+                    internal_error!(
+                        "types that don't return via arg pointer must be less than 16 bytes"
+                    );
+                }
+            }
+            _ => {
+                // This should have been recieved via the result pointer.
+                // That means the value is already loaded onto the stack area we allocated before the call.
+                // Nothing to do.
+            }
+        }
+    }
+}
+
+impl AArch64Call {
+    fn returns_via_arg_pointer(ret_layout: &Layout) -> bool {
+        // TODO: This will need to be more complex/extended to fully support the calling convention.
+        // Homogeneous float aggregates, for example, should be returned in float registers.
+        // details here: https://github.com/ARM-software/abi-aa/blob/main/aapcs64/aapcs64.rst
+        ret_layout.stack_size(TARGET_INFO) > 16
+    }
+
+    fn passed_by_reference(layout: &Layout) -> bool {
+        // Composites that are larger than 16 bytes are replaced by a pointer to a copy of them.
+        !matches!(layout, single_register_layouts!()) && layout.stack_size(TARGET_INFO) > 16
     }
 }
 
 impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     #[inline(always)]
-    fn abs_reg64_reg64(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg, _src: AArch64GeneralReg) {
-        todo!("abs_reg64_reg64 for AArch64");
+    fn abs_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
+        cmp_reg64_imm12(buf, src, 0);
+        cneg_reg64_reg64_cond(buf, dst, src, ConditionCode::MI);
     }
 
     #[inline(always)]
     fn abs_freg64_freg64(
-        _buf: &mut Vec<'_, u8>,
+        buf: &mut Vec<'_, u8>,
         _relocs: &mut Vec<'_, Relocation>,
-        _dst: AArch64FloatReg,
-        _src: AArch64FloatReg,
+        dst: AArch64FloatReg,
+        src: AArch64FloatReg,
     ) {
-        todo!("abs_reg64_reg64 for AArch64");
+        fabs_freg_freg(buf, FloatWidth::F64, dst, src);
     }
 
     #[inline(always)]
@@ -393,11 +789,9 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         imm32: i32,
     ) {
         if imm32 < 0 {
-            todo!("immediate addition with values less than 0");
-        } else if imm32 < 0xFFF {
-            add_reg64_reg64_imm12(buf, dst, src, imm32 as u16);
+            Self::sub_reg64_reg64_imm(buf, dst, src, imm32.unsigned_abs());
         } else {
-            todo!("immediate additions with values greater than 12bits");
+            Self::add_reg64_reg64_imm(buf, dst, src, imm32 as u32);
         }
     }
     #[inline(always)]
@@ -411,76 +805,113 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
     #[inline(always)]
     fn add_freg32_freg32_freg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("adding floats for AArch64");
+        fadd_freg_freg_freg(buf, FloatWidth::F32, dst, src1, src2);
     }
     #[inline(always)]
     fn add_freg64_freg64_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src1: AArch64FloatReg,
-        _src2: AArch64FloatReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64FloatReg,
+        src1: AArch64FloatReg,
+        src2: AArch64FloatReg,
     ) {
-        todo!("adding floats for AArch64");
+        fadd_freg_freg_freg(buf, FloatWidth::F64, dst, src1, src2);
+    }
+
+    #[inline(always)]
+    fn call(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, fn_name: String) {
+        bl_imm26(buf, 0);
+        relocs.push(Relocation::LinkedFunction {
+            offset: buf.len() as u64 - 4,
+            name: fn_name,
+        });
     }
 
     #[inline(always)]
-    fn call(_buf: &mut Vec<'_, u8>, _relocs: &mut Vec<'_, Relocation>, _fn_name: String) {
-        todo!("calling functions literal for AArch64");
+    fn function_pointer(
+        buf: &mut Vec<'_, u8>,
+        relocs: &mut Vec<'_, Relocation>,
+        fn_name: String,
+        dst: AArch64GeneralReg,
+    ) {
+        // Load the address from the GOT so that it also works for symbols in other libraries.
+        // The relocation points at the ADRP, the object builder also relocates the LDR after it.
+        adrp_reg64_imm21(buf, dst, 0);
+        relocs.push(Relocation::LinkedFunction {
+            offset: buf.len() as u64 - 4,
+            name: fn_name,
+        });
+        load_store_reg_reg64_imm12(buf, LoadStoreOp::Ldr64, dst, dst, 0);
     }
 
     #[inline(always)]
     fn imul_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("register multiplication for AArch64");
+        mul_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
-    fn jmp_imm32(_buf: &mut Vec<'_, u8>, _offset: i32) -> usize {
-        todo!("jump instructions for AArch64");
+    fn jmp_imm32(buf: &mut Vec<'_, u8>, offset: i32) -> usize {
+        // Branch offsets are relative to the branch itself rather than the next instruction.
+        debug_assert_eq!(offset % 4, 0);
+        let base_offset = buf.len();
+        b_imm26(buf, offset >> 2);
+        base_offset
     }
 
     #[inline(always)]
     fn tail_call(buf: &mut Vec<'_, u8>) -> u64 {
         Self::jmp_imm32(buf, 0);
-        buf.len() as u64 - 4 // TODO is 4 the correct offset in ARM?
+        buf.len() as u64 - 4
     }
 
     #[inline(always)]
     fn jne_reg64_imm64_imm32(
-        _buf: &mut Vec<'_, u8>,
-        _reg: AArch64GeneralReg,
-        _imm: u64,
-        _offset: i32,
+        buf: &mut Vec<'_, u8>,
+        reg: AArch64GeneralReg,
+        imm: u64,
+        offset: i32,
     ) -> usize {
-        todo!("jump not equal instructions for AArch64");
+        if imm <= 0xFFF {
+            cmp_reg64_imm12(buf, reg, imm as u16);
+        } else {
+            Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm as i64);
+            cmp_reg64_reg64(buf, reg, AArch64GeneralReg::IP0);
+        }
+        // Branch offsets are relative to the branch itself rather than the next instruction.
+        debug_assert_eq!(offset % 4, 0);
+        let base_offset = buf.len();
+        b_cond_imm19(buf, ConditionCode::NE, offset >> 2);
+        base_offset
     }
 
     #[inline(always)]
     fn mov_freg32_imm32(
-        _buf: &mut Vec<'_, u8>,
+        buf: &mut Vec<'_, u8>,
         _relocs: &mut Vec<'_, Relocation>,
-        _dst: AArch64FloatReg,
-        _imm: f32,
+        dst: AArch64FloatReg,
+        imm: f32,
     ) {
-        todo!("loading f32 literal for AArch64");
+        Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm.to_bits() as i64);
+        fmov_freg_reg(buf, FloatWidth::F32, dst, AArch64GeneralReg::IP0);
     }
     #[inline(always)]
     fn mov_freg64_imm64(
-        _buf: &mut Vec<'_, u8>,
+        buf: &mut Vec<'_, u8>,
         _relocs: &mut Vec<'_, Relocation>,
-        _dst: AArch64FloatReg,
-        _imm: f64,
+        dst: AArch64FloatReg,
+        imm: f64,
     ) {
-        todo!("loading f64 literal for AArch64");
+        Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm.to_bits() as i64);
+        fmov_freg_reg(buf, FloatWidth::F64, dst, AArch64GeneralReg::IP0);
     }
     #[inline(always)]
     fn mov_reg64_imm64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm: i64) {
@@ -500,8 +931,8 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         }
     }
     #[inline(always)]
-    fn mov_freg64_freg64(_buf: &mut Vec<'_, u8>, _dst: AArch64FloatReg, _src: AArch64FloatReg) {
-        todo!("moving data between float registers for AArch64");
+    fn mov_freg64_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+        fmov_freg_freg(buf, FloatWidth::F64, dst, src);
     }
     #[inline(always)]
     fn mov_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
@@ -509,34 +940,20 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
     }
 
     #[inline(always)]
-    fn mov_freg64_base32(_buf: &mut Vec<'_, u8>, _dst: AArch64FloatReg, _offset: i32) {
-        todo!("loading floating point reg from base offset for AArch64");
+    fn mov_freg64_base32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, offset: i32) {
+        Self::load_store_offset32(buf, LoadStoreOp::LdrF64, dst, AArch64GeneralReg::FP, offset);
     }
     #[inline(always)]
     fn mov_reg64_base32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32) {
-        if offset < 0 {
-            todo!("negative base offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            ldr_reg64_reg64_imm12(buf, dst, AArch64GeneralReg::FP, (offset as u16) >> 3);
-        } else {
-            todo!("base offsets over 32k for AArch64");
-        }
+        Self::load_store_offset32(buf, LoadStoreOp::Ldr64, dst, AArch64GeneralReg::FP, offset);
     }
     #[inline(always)]
-    fn mov_base32_freg64(_buf: &mut Vec<'_, u8>, _offset: i32, _src: AArch64FloatReg) {
-        todo!("saving floating point reg to base offset for AArch64");
+    fn mov_base32_freg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64FloatReg) {
+        Self::load_store_offset32(buf, LoadStoreOp::StrF64, src, AArch64GeneralReg::FP, offset);
     }
     #[inline(always)]
     fn mov_base32_reg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64GeneralReg) {
-        if offset < 0 {
-            todo!("negative base offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            str_reg64_reg64_imm12(buf, src, AArch64GeneralReg::FP, (offset as u16) >> 3);
-        } else {
-            todo!("base offsets over 32k for AArch64");
-        }
+        Self::load_store_offset32(buf, LoadStoreOp::Str64, src, AArch64GeneralReg::FP, offset);
     }
 
     #[inline(always)]
@@ -546,14 +963,7 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         src: AArch64GeneralReg,
         offset: i32,
     ) {
-        if offset < 0 {
-            todo!("negative mem offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            ldr_reg64_reg64_imm12(buf, dst, src, (offset as u16) >> 3);
-        } else {
-            todo!("mem offsets over 32k for AArch64");
-        }
+        Self::load_store_offset32(buf, LoadStoreOp::Ldr64, dst, src, offset);
     }
     #[inline(always)]
     fn mov_mem64_offset32_reg64(
@@ -562,80 +972,96 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         offset: i32,
         src: AArch64GeneralReg,
     ) {
-        if offset < 0 {
-            todo!("negative mem offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            str_reg64_reg64_imm12(buf, src, dst, (offset as u16) >> 3);
-        } else {
-            todo!("mem offsets over 32k for AArch64");
-        }
+        Self::load_store_offset32(buf, LoadStoreOp::Str64, src, dst, offset);
+    }
+    #[inline(always)]
+    fn mov_mem8_offset32_reg8(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        offset: i32,
+        src: AArch64GeneralReg,
+    ) {
+        Self::load_store_offset32(buf, LoadStoreOp::Strb, src, dst, offset);
     }
 
     #[inline(always)]
     fn movsx_reg64_base32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32, size: u8) {
         debug_assert!(size <= 8);
-        if size == 8 {
-            Self::mov_reg64_base32(buf, dst, offset);
-        } else if size == 4 {
-            todo!("sign extending 4 byte values");
-        } else if size == 2 {
-            todo!("sign extending 2 byte values");
-        } else if size == 1 {
-            todo!("sign extending 1 byte values");
-        } else {
-            internal_error!("Invalid size for sign extension: {}", size);
-        }
+        let op = match size {
+            8 => LoadStoreOp::Ldr64,
+            4 => LoadStoreOp::Ldrsw,
+            2 => LoadStoreOp::Ldrsh,
+            1 => LoadStoreOp::Ldrsb,
+            _ => internal_error!("Invalid size for sign extension: {}", size),
+        };
+        Self::load_store_offset32(buf, op, dst, AArch64GeneralReg::FP, offset);
     }
     #[inline(always)]
     fn movzx_reg64_base32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32, size: u8) {
         debug_assert!(size <= 8);
-        if size == 8 {
-            Self::mov_reg64_base32(buf, dst, offset);
-        } else if size == 4 {
-            todo!("zero extending 4 byte values");
-        } else if size == 2 {
-            todo!("zero extending 2 byte values");
-        } else if size == 1 {
-            todo!("zero extending 1 byte values");
-        } else {
-            internal_error!("Invalid size for zero extension: {}", size);
-        }
+        let op = match size {
+            8 => LoadStoreOp::Ldr64,
+            4 => LoadStoreOp::Ldr32,
+            2 => LoadStoreOp::Ldrh,
+            1 => LoadStoreOp::Ldrb,
+            _ => internal_error!("Invalid size for zero extension: {}", size),
+        };
+        Self::load_store_offset32(buf, op, dst, AArch64GeneralReg::FP, offset);
+    }
+    #[inline(always)]
+    fn movzx_reg64_mem8_offset32(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src: AArch64GeneralReg,
+        offset: i32,
+    ) {
+        Self::load_store_offset32(buf, LoadStoreOp::Ldrb, dst, src, offset);
     }
 
     #[inline(always)]
-    fn mov_freg64_stack32(_buf: &mut Vec<'_, u8>, _dst: AArch64FloatReg, _offset: i32) {
-        todo!("loading floating point reg from stack for AArch64");
+    fn mov_freg64_stack32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, offset: i32) {
+        Self::load_store_offset32(
+            buf,
+            LoadStoreOp::LdrF64,
+            dst,
+            AArch64GeneralReg::ZRSP,
+            offset,
+        );
     }
     #[inline(always)]
     fn mov_reg64_stack32(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, offset: i32) {
-        if offset < 0 {
-            todo!("negative stack offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            ldr_reg64_reg64_imm12(buf, dst, AArch64GeneralReg::ZRSP, (offset as u16) >> 3);
-        } else {
-            todo!("stack offsets over 32k for AArch64");
-        }
+        Self::load_store_offset32(
+            buf,
+            LoadStoreOp::Ldr64,
+            dst,
+            AArch64GeneralReg::ZRSP,
+            offset,
+        );
     }
     #[inline(always)]
-    fn mov_stack32_freg64(_buf: &mut Vec<'_, u8>, _offset: i32, _src: AArch64FloatReg) {
-        todo!("saving floating point reg to stack for AArch64");
+    fn mov_stack32_freg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64FloatReg) {
+        Self::load_store_offset32(
+            buf,
+            LoadStoreOp::StrF64,
+            src,
+            AArch64GeneralReg::ZRSP,
+            offset,
+        );
     }
     #[inline(always)]
     fn mov_stack32_reg64(buf: &mut Vec<'_, u8>, offset: i32, src: AArch64GeneralReg) {
-        if offset < 0 {
-            todo!("negative stack offsets for AArch64");
-        } else if offset < (0xFFF << 8) {
-            debug_assert!(offset % 8 == 0);
-            str_reg64_reg64_imm12(buf, src, AArch64GeneralReg::ZRSP, (offset as u16) >> 3);
-        } else {
-            todo!("stack offsets over 32k for AArch64");
-        }
+        Self::load_store_offset32(
+            buf,
+            LoadStoreOp::Str64,
+            src,
+            AArch64GeneralReg::ZRSP,
+            offset,
+        );
     }
     #[inline(always)]
-    fn neg_reg64_reg64(_buf: &mut Vec<'_, u8>, _dst: AArch64GeneralReg, _src: AArch64GeneralReg) {
-        todo!("neg for AArch64");
+    fn neg_reg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, src: AArch64GeneralReg) {
+        // NEG is equvalent to `SUB Xd, XZR, Xm` in AARCH64.
+        sub_reg64_reg64_reg64(buf, dst, AArch64GeneralReg::ZRSP, src);
     }
 
     #[inline(always)]
@@ -646,116 +1072,187 @@ impl Assembler<AArch64GeneralReg, AArch64FloatReg> for AArch64Assembler {
         imm32: i32,
     ) {
         if imm32 < 0 {
-            todo!("immediate subtractions with values less than 0");
-        } else if imm32 < 0xFFF {
-            sub_reg64_reg64_imm12(buf, dst, src, imm32 as u16);
+            Self::add_reg64_reg64_imm(buf, dst, src, imm32.unsigned_abs());
         } else {
-            todo!("immediate subtractions with values greater than 12bits");
+            Self::sub_reg64_reg64_imm(buf, dst, src, imm32 as u32);
         }
     }
     #[inline(always)]
     fn sub_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
+    ) {
+        sub_reg64_reg64_reg64(buf, dst, src1, src2);
+    }
+
+    #[inline(always)]
+    fn and_reg64_reg64_reg64(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers subtractions for AArch64");
+        and_reg64_reg64_reg64(buf, dst, src1, src2);
     }
 
     #[inline(always)]
     fn eq_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers equality for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::EQ);
     }
 
     #[inline(always)]
     fn neq_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers non-equality for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::NE);
     }
 
     #[inline(always)]
     fn lt_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers less than for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::LT);
     }
 
     #[inline(always)]
-    fn to_float_freg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64GeneralReg,
-    ) {
-        todo!("registers to float for AArch64");
+    fn to_float_freg64_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+        scvtf_freg_reg64(buf, FloatWidth::F64, dst, src);
     }
 
     #[inline(always)]
-    fn to_float_freg32_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64GeneralReg,
-    ) {
-        todo!("registers to float for AArch64");
+    fn to_float_freg32_reg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64GeneralReg) {
+        scvtf_freg_reg64(buf, FloatWidth::F32, dst, src);
     }
 
     #[inline(always)]
-    fn to_float_freg32_freg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64FloatReg,
-    ) {
-        todo!("registers to float for AArch64");
+    fn to_float_freg32_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+        fcvt_freg32_freg64(buf, dst, src);
     }
 
     #[inline(always)]
-    fn to_float_freg64_freg32(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64FloatReg,
-        _src: AArch64FloatReg,
-    ) {
-        todo!("registers to float for AArch64");
+    fn to_float_freg64_freg32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+        fcvt_freg64_freg32(buf, dst, src);
     }
 
     #[inline(always)]
     fn lte_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers less than or equal for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::LE);
     }
 
     #[inline(always)]
     fn gte_reg64_reg64_reg64(
-        _buf: &mut Vec<'_, u8>,
-        _dst: AArch64GeneralReg,
-        _src1: AArch64GeneralReg,
-        _src2: AArch64GeneralReg,
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src1: AArch64GeneralReg,
+        src2: AArch64GeneralReg,
     ) {
-        todo!("registers greater than or equal for AArch64");
+        cmp_reg64_reg64(buf, src1, src2);
+        cset_reg64_cond(buf, dst, ConditionCode::GE);
     }
 
     #[inline(always)]
     fn ret(buf: &mut Vec<'_, u8>) {
         ret_reg64(buf, AArch64GeneralReg::LR)
     }
+
+    #[inline(always)]
+    fn trap(buf: &mut Vec<'_, u8>) {
+        brk(buf)
+    }
 }
 
-impl AArch64Assembler {}
+impl AArch64Assembler {
+    /// Adds an immediate of any size.
+    /// Up to 24 bits are split over two instructions so that the stack pointer can be used.
+    /// Anything larger goes through IP0.
+    #[inline(always)]
+    fn add_reg64_reg64_imm(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src: AArch64GeneralReg,
+        imm: u32,
+    ) {
+        if imm <= 0xFFF {
+            add_reg64_reg64_imm12(buf, dst, src, imm as u16);
+        } else if imm <= 0x00FF_FFFF {
+            add_reg64_reg64_imm12_lsl12(buf, dst, src, (imm >> 12) as u16);
+            if imm & 0xFFF != 0 {
+                add_reg64_reg64_imm12(buf, dst, dst, (imm & 0xFFF) as u16);
+            }
+        } else {
+            debug_assert!(dst != AArch64GeneralReg::ZRSP && src != AArch64GeneralReg::ZRSP);
+            Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm as i64);
+            add_reg64_reg64_reg64(buf, dst, src, AArch64GeneralReg::IP0);
+        }
+    }
+
+    /// Subtracts an immediate of any size.
+    /// Up to 24 bits are split over two instructions so that the stack pointer can be used.
+    /// Anything larger goes through IP0.
+    #[inline(always)]
+    fn sub_reg64_reg64_imm(
+        buf: &mut Vec<'_, u8>,
+        dst: AArch64GeneralReg,
+        src: AArch64GeneralReg,
+        imm: u32,
+    ) {
+        if imm <= 0xFFF {
+            sub_reg64_reg64_imm12(buf, dst, src, imm as u16);
+        } else if imm <= 0x00FF_FFFF {
+            sub_reg64_reg64_imm12_lsl12(buf, dst, src, (imm >> 12) as u16);
+            if imm & 0xFFF != 0 {
+                sub_reg64_reg64_imm12(buf, dst, dst, (imm & 0xFFF) as u16);
+            }
+        } else {
+            debug_assert!(dst != AArch64GeneralReg::ZRSP && src != AArch64GeneralReg::ZRSP);
+            Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, imm as i64);
+            sub_reg64_reg64_reg64(buf, dst, src, AArch64GeneralReg::IP0);
+        }
+    }
+
+    /// Loads or stores `reg` at `base + offset` with whichever addressing mode fits the offset.
+    /// Offsets that don't fit in an immediate are put in IP0.
+    #[inline(always)]
+    fn load_store_offset32<R: RegTrait>(
+        buf: &mut Vec<'_, u8>,
+        op: LoadStoreOp,
+        reg: R,
+        base: AArch64GeneralReg,
+        offset: i32,
+    ) {
+        let scale = 1 << op.size();
+        if offset >= 0 && offset % scale == 0 && offset / scale <= 0xFFF {
+            load_store_reg_reg64_imm12(buf, op, reg, base, (offset / scale) as u16);
+        } else if (-256..256).contains(&offset) {
+            load_store_reg_reg64_imm9(buf, op, reg, base, offset as i16);
+        } else {
+            Self::mov_reg64_imm64(buf, AArch64GeneralReg::IP0, offset as i64);
+            load_store_reg_reg64_reg64(buf, op, reg, base, AArch64GeneralReg::IP0);
+        }
+    }
+}
 
 // Instructions
 // ARM manual section C3
@@ -775,6 +1272,90 @@ trait Aarch64Bytes: PackedStruct {
     }
 }
 
+/// The `ftype` field of floating point instructions.
+#[inline(always)]
+fn float_type(width: FloatWidth) -> u8 {
+    match width {
+        FloatWidth::F32 => 0b00,
+        FloatWidth::F64 => 0b01,
+        FloatWidth::F128 => internal_error!("F128 is not supported by floating point registers"),
+    }
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+#[allow(dead_code)]
+enum ConditionCode {
+    EQ = 0,
+    NE = 1,
+    HS = 2,
+    LO = 3,
+    MI = 4,
+    PL = 5,
+    VS = 6,
+    VC = 7,
+    HI = 8,
+    LS = 9,
+    GE = 10,
+    LT = 11,
+    GT = 12,
+    LE = 13,
+    AL = 14,
+}
+
+impl ConditionCode {
+    #[inline(always)]
+    fn id(&self) -> u8 {
+        *self as u8
+    }
+
+    /// The encoding of the opposite condition.
+    #[inline(always)]
+    fn inverted_id(&self) -> u8 {
+        self.id() ^ 1
+    }
+}
+
+/// The loads and stores the assembler needs, with their `size`, `V` and `opc` fields.
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+enum LoadStoreOp {
+    Ldr64,
+    Str64,
+    LdrF64,
+    StrF64,
+    Ldrb,
+    Ldrsb,
+    Ldrh,
+    Ldrsh,
+    Ldr32,
+    Ldrsw,
+    Strb,
+}
+
+impl LoadStoreOp {
+    #[inline(always)]
+    fn encoding(&self) -> (u8, bool, u8) {
+        match self {
+            LoadStoreOp::Ldr64 => (0b11, false, 0b01),
+            LoadStoreOp::Str64 => (0b11, false, 0b00),
+            LoadStoreOp::LdrF64 => (0b11, true, 0b01),
+            LoadStoreOp::StrF64 => (0b11, true, 0b00),
+            LoadStoreOp::Ldrb => (0b00, false, 0b01),
+            LoadStoreOp::Ldrsb => (0b00, false, 0b10),
+            LoadStoreOp::Ldrh => (0b01, false, 0b01),
+            LoadStoreOp::Ldrsh => (0b01, false, 0b10),
+            LoadStoreOp::Ldr32 => (0b10, false, 0b01),
+            LoadStoreOp::Ldrsw => (0b10, false, 0b10),
+            LoadStoreOp::Strb => (0b00, false, 0b00),
+        }
+    }
+
+    /// Log2 of the number of bytes accessed.
+    #[inline(always)]
+    fn size(&self) -> u8 {
+        self.encoding().0
+    }
+}
+
 #[derive(PackedStruct, Debug)]
 #[packed_struct(endian = "msb")]
 pub struct MoveWideImmediate {
@@ -982,50 +1563,213 @@ impl LogicalShiftedRegister {
 }
 
 #[derive(PackedStruct)]
-pub struct UnconditionalBranchRegister {
-    fixed: Integer<u8, packed_bits::Bits<7>>,
-    z: bool,
-    fixed2: bool,
-    op: Integer<u8, packed_bits::Bits<2>>,
-    fixed3: Integer<u8, packed_bits::Bits<5>>,
-    fixed4: Integer<u8, packed_bits::Bits<4>>,
-    a: bool,
-    m: bool,
-    rn: Integer<u8, packed_bits::Bits<5>>,
-    fixed5: Integer<u8, packed_bits::Bits<5>>,
+#[packed_struct(endian = "msb")]
+pub struct ConditionalSelect {
+    sf: bool,
+    op: bool,
+    s: bool,
+    fixed: Integer<u8, packed_bits::Bits<8>>, // = 0b11010100,
+    reg_m: Integer<u8, packed_bits::Bits<5>>,
+    cond: Integer<u8, packed_bits::Bits<4>>,
+    op2: Integer<u8, packed_bits::Bits<2>>,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
 }
 
-impl Aarch64Bytes for UnconditionalBranchRegister {}
+impl Aarch64Bytes for ConditionalSelect {}
 
-impl UnconditionalBranchRegister {
+impl ConditionalSelect {
     #[inline(always)]
-    fn new(op: u8, rn: AArch64GeneralReg) -> Self {
-        debug_assert!(op <= 0b11);
+    fn new(
+        op: bool,
+        op2: u8,
+        cond: u8,
+        rm: AArch64GeneralReg,
+        rn: AArch64GeneralReg,
+        rd: AArch64GeneralReg,
+    ) -> Self {
+        debug_assert!(op2 <= 0b11);
+        debug_assert!(cond <= 0b1111);
 
         Self {
-            fixed5: 0b00000.into(),
-            rn: rn.id().into(),
-            m: false,
-            a: false,
-            fixed4: 0b0000.into(),
-            fixed3: 0b11111.into(),
-            op: op.into(),
-            fixed2: false,
-            z: false,
-            fixed: 0b1101011.into(),
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            op2: op2.into(),
+            cond: cond.into(),
+            reg_m: rm.id().into(),
+            fixed: 0b11010100.into(),
+            s: false,
+            op,
+            sf: true,
         }
     }
 }
 
-// Uses unsigned Offset
-// opc = 0b01 means load
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct DataProcessingThreeSource {
+    sf: bool,
+    op54: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<5>>, // = 0b11011,
+    op31: Integer<u8, packed_bits::Bits<3>>,
+    reg_m: Integer<u8, packed_bits::Bits<5>>,
+    o0: bool,
+    reg_a: Integer<u8, packed_bits::Bits<5>>,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for DataProcessingThreeSource {}
+
+impl DataProcessingThreeSource {
+    #[inline(always)]
+    fn new(
+        op31: u8,
+        o0: bool,
+        rm: AArch64GeneralReg,
+        ra: AArch64GeneralReg,
+        rn: AArch64GeneralReg,
+        rd: AArch64GeneralReg,
+    ) -> Self {
+        debug_assert!(op31 <= 0b111);
+
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            reg_a: ra.id().into(),
+            o0,
+            reg_m: rm.id().into(),
+            op31: op31.into(),
+            fixed: 0b11011.into(),
+            op54: 0b00.into(),
+            sf: true,
+        }
+    }
+}
+
+#[derive(PackedStruct)]
+pub struct UnconditionalBranchRegister {
+    fixed: Integer<u8, packed_bits::Bits<7>>,
+    z: bool,
+    fixed2: bool,
+    op: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: Integer<u8, packed_bits::Bits<5>>,
+    fixed4: Integer<u8, packed_bits::Bits<4>>,
+    a: bool,
+    m: bool,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    fixed5: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for UnconditionalBranchRegister {}
+
+impl UnconditionalBranchRegister {
+    #[inline(always)]
+    fn new(op: u8, rn: AArch64GeneralReg) -> Self {
+        debug_assert!(op <= 0b11);
+
+        Self {
+            fixed5: 0b00000.into(),
+            rn: rn.id().into(),
+            m: false,
+            a: false,
+            fixed4: 0b0000.into(),
+            fixed3: 0b11111.into(),
+            op: op.into(),
+            fixed2: false,
+            z: false,
+            fixed: 0b1101011.into(),
+        }
+    }
+}
+
+// op = false means B
+// op = true means BL
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct UnconditionalBranchImmediate {
+    op: bool,
+    fixed: Integer<u8, packed_bits::Bits<5>>, // = 0b00101,
+    imm26: Integer<u32, packed_bits::Bits<26>>,
+}
+
+impl Aarch64Bytes for UnconditionalBranchImmediate {}
+
+impl UnconditionalBranchImmediate {
+    #[inline(always)]
+    fn new(op: bool, imm26: i32) -> Self {
+        Self {
+            // The offset is in words and may be negative.
+            imm26: ((imm26 as u32) & 0x03FF_FFFF).into(),
+            fixed: 0b00101.into(),
+            op,
+        }
+    }
+}
+
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct ConditionalBranchImmediate {
+    fixed: Integer<u8, packed_bits::Bits<7>>, // = 0b0101010,
+    o1: bool,
+    imm19: Integer<u32, packed_bits::Bits<19>>,
+    o0: bool,
+    cond: Integer<u8, packed_bits::Bits<4>>,
+}
+
+impl Aarch64Bytes for ConditionalBranchImmediate {}
+
+impl ConditionalBranchImmediate {
+    #[inline(always)]
+    fn new(cond: ConditionCode, imm19: i32) -> Self {
+        Self {
+            cond: cond.id().into(),
+            o0: false,
+            // The offset is in words and may be negative.
+            imm19: ((imm19 as u32) & 0x0007_FFFF).into(),
+            o1: false,
+            fixed: 0b0101010.into(),
+        }
+    }
+}
+
+// op = true means ADRP
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct PCRelativeAddressing {
+    op: bool,
+    immlo: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<5>>, // = 0b10000,
+    immhi: Integer<u32, packed_bits::Bits<19>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for PCRelativeAddressing {}
+
+impl PCRelativeAddressing {
+    #[inline(always)]
+    fn new(op: bool, imm21: i32, rd: AArch64GeneralReg) -> Self {
+        let imm21 = imm21 as u32;
+
+        Self {
+            reg_d: rd.id().into(),
+            immhi: ((imm21 >> 2) & 0x0007_FFFF).into(),
+            fixed: 0b10000.into(),
+            immlo: ((imm21 & 0b11) as u8).into(),
+            op,
+        }
+    }
+}
+
+// Uses unsigned Offset
+// opc = 0b01 means load
 // opc = 0b00 means store
 #[derive(PackedStruct, Debug)]
 #[packed_struct(endian = "msb")]
 pub struct LoadStoreRegisterImmediate {
     size: Integer<u8, packed_bits::Bits<2>>,
     fixed: Integer<u8, packed_bits::Bits<3>>, // = 0b111,
-    fixed2: bool,
+    v: bool,
     fixed3: Integer<u8, packed_bits::Bits<2>>,
     opc: Integer<u8, packed_bits::Bits<2>>,
     imm12: Integer<u16, packed_bits::Bits<12>>,
@@ -1037,30 +1781,274 @@ impl Aarch64Bytes for LoadStoreRegisterImmediate {}
 
 impl LoadStoreRegisterImmediate {
     #[inline(always)]
-    fn new(size: u8, opc: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64GeneralReg) -> Self {
-        debug_assert!(size <= 0b11);
+    fn new(op: LoadStoreOp, imm12: u16, rn: AArch64GeneralReg, rt: u8) -> Self {
         debug_assert!(imm12 <= 0xFFF);
+        let (size, v, opc) = op.encoding();
 
         Self {
-            rt: rt.id().into(),
+            rt: rt.into(),
             rn: rn.id().into(),
             imm12: imm12.into(),
             opc: opc.into(),
             fixed3: 0b01.into(),
-            fixed2: false,
+            v,
             fixed: 0b111.into(),
             size: size.into(),
         }
     }
+}
 
+// Uses a signed, unscaled offset
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct LoadStoreRegisterUnscaled {
+    size: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<3>>, // = 0b111,
+    v: bool,
+    fixed2: Integer<u8, packed_bits::Bits<2>>, // = 0b00,
+    opc: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b0,
+    imm9: Integer<u16, packed_bits::Bits<9>>,
+    fixed4: Integer<u8, packed_bits::Bits<2>>, // = 0b00,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rt: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for LoadStoreRegisterUnscaled {}
+
+impl LoadStoreRegisterUnscaled {
+    #[inline(always)]
+    fn new(op: LoadStoreOp, imm9: i16, rn: AArch64GeneralReg, rt: u8) -> Self {
+        debug_assert!((-256..256).contains(&imm9));
+        let (size, v, opc) = op.encoding();
+
+        Self {
+            rt: rt.into(),
+            rn: rn.id().into(),
+            fixed4: 0b00.into(),
+            imm9: ((imm9 as u16) & 0x1FF).into(),
+            fixed3: false,
+            opc: opc.into(),
+            fixed2: 0b00.into(),
+            v,
+            fixed: 0b111.into(),
+            size: size.into(),
+        }
+    }
+}
+
+// Uses an unshifted 64 bit register as the offset
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct LoadStoreRegisterRegisterOffset {
+    size: Integer<u8, packed_bits::Bits<2>>,
+    fixed: Integer<u8, packed_bits::Bits<3>>, // = 0b111,
+    v: bool,
+    fixed2: Integer<u8, packed_bits::Bits<2>>, // = 0b00,
+    opc: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b1,
+    rm: Integer<u8, packed_bits::Bits<5>>,
+    option: Integer<u8, packed_bits::Bits<3>>,
+    s: bool,
+    fixed4: Integer<u8, packed_bits::Bits<2>>, // = 0b10,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rt: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for LoadStoreRegisterRegisterOffset {}
+
+impl LoadStoreRegisterRegisterOffset {
     #[inline(always)]
-    fn new_load(size: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64GeneralReg) -> Self {
-        Self::new(size, 0b01, imm12, rn, rt)
+    fn new(op: LoadStoreOp, rm: AArch64GeneralReg, rn: AArch64GeneralReg, rt: u8) -> Self {
+        let (size, v, opc) = op.encoding();
+
+        Self {
+            rt: rt.into(),
+            rn: rn.id().into(),
+            fixed4: 0b10.into(),
+            s: false,
+            // LSL, which means a 64 bit offset register.
+            option: 0b011.into(),
+            rm: rm.id().into(),
+            fixed3: true,
+            opc: opc.into(),
+            fixed2: 0b00.into(),
+            v,
+            fixed: 0b111.into(),
+            size: size.into(),
+        }
     }
+}
 
+// Only handles 64 bit general registers.
+// index = 0b01 means post-index
+// index = 0b11 means pre-index
+#[derive(PackedStruct, Debug)]
+#[packed_struct(endian = "msb")]
+pub struct LoadStorePair {
+    opc: Integer<u8, packed_bits::Bits<2>>,   // = 0b10,
+    fixed: Integer<u8, packed_bits::Bits<3>>, // = 0b101,
+    v: bool,
+    fixed2: bool, // = 0b0,
+    index: Integer<u8, packed_bits::Bits<2>>,
+    l: bool,
+    imm7: Integer<u8, packed_bits::Bits<7>>,
+    rt2: Integer<u8, packed_bits::Bits<5>>,
+    rn: Integer<u8, packed_bits::Bits<5>>,
+    rt: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for LoadStorePair {}
+
+impl LoadStorePair {
     #[inline(always)]
-    fn new_store(size: u8, imm12: u16, rn: AArch64GeneralReg, rt: AArch64GeneralReg) -> Self {
-        Self::new(size, 0b00, imm12, rn, rt)
+    fn new(
+        index: u8,
+        l: bool,
+        imm7: i8,
+        rt2: AArch64GeneralReg,
+        rn: AArch64GeneralReg,
+        rt: AArch64GeneralReg,
+    ) -> Self {
+        debug_assert!(index <= 0b11);
+        debug_assert!((-64..64).contains(&imm7));
+
+        Self {
+            rt: rt.id().into(),
+            rn: rn.id().into(),
+            rt2: rt2.id().into(),
+            imm7: ((imm7 as u8) & 0x7F).into(),
+            l,
+            index: index.into(),
+            fixed2: false,
+            v: false,
+            fixed: 0b101.into(),
+            opc: 0b10.into(),
+        }
+    }
+}
+
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct FloatingPointDataProcessingOneSource {
+    m: bool,
+    fixed: bool,
+    s: bool,
+    fixed2: Integer<u8, packed_bits::Bits<5>>, // = 0b11110,
+    ftype: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b1,
+    opcode: Integer<u8, packed_bits::Bits<6>>,
+    fixed4: Integer<u8, packed_bits::Bits<5>>, // = 0b10000,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for FloatingPointDataProcessingOneSource {}
+
+impl FloatingPointDataProcessingOneSource {
+    #[inline(always)]
+    fn new(ftype: FloatWidth, opcode: u8, rn: AArch64FloatReg, rd: AArch64FloatReg) -> Self {
+        debug_assert!(opcode <= 0b111111);
+
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            fixed4: 0b10000.into(),
+            opcode: opcode.into(),
+            fixed3: true,
+            ftype: float_type(ftype).into(),
+            fixed2: 0b11110.into(),
+            s: false,
+            fixed: false,
+            m: false,
+        }
+    }
+}
+
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct FloatingPointDataProcessingTwoSource {
+    m: bool,
+    fixed: bool,
+    s: bool,
+    fixed2: Integer<u8, packed_bits::Bits<5>>, // = 0b11110,
+    ftype: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b1,
+    reg_m: Integer<u8, packed_bits::Bits<5>>,
+    opcode: Integer<u8, packed_bits::Bits<4>>,
+    fixed4: Integer<u8, packed_bits::Bits<2>>, // = 0b10,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for FloatingPointDataProcessingTwoSource {}
+
+impl FloatingPointDataProcessingTwoSource {
+    #[inline(always)]
+    fn new(
+        ftype: FloatWidth,
+        opcode: u8,
+        rm: AArch64FloatReg,
+        rn: AArch64FloatReg,
+        rd: AArch64FloatReg,
+    ) -> Self {
+        debug_assert!(opcode <= 0b1111);
+
+        Self {
+            reg_d: rd.id().into(),
+            reg_n: rn.id().into(),
+            fixed4: 0b10.into(),
+            opcode: opcode.into(),
+            reg_m: rm.id().into(),
+            fixed3: true,
+            ftype: float_type(ftype).into(),
+            fixed2: 0b11110.into(),
+            s: false,
+            fixed: false,
+            m: false,
+        }
+    }
+}
+
+// sf = false means a 32 bit general register
+// sf = true means a 64 bit general register
+#[derive(PackedStruct)]
+#[packed_struct(endian = "msb")]
+pub struct ConversionBetweenFloatAndInteger {
+    sf: bool,
+    fixed: bool,
+    s: bool,
+    fixed2: Integer<u8, packed_bits::Bits<5>>, // = 0b11110,
+    ftype: Integer<u8, packed_bits::Bits<2>>,
+    fixed3: bool, // = 0b1,
+    rmode: Integer<u8, packed_bits::Bits<2>>,
+    opcode: Integer<u8, packed_bits::Bits<3>>,
+    fixed4: Integer<u8, packed_bits::Bits<6>>, // = 0b000000,
+    reg_n: Integer<u8, packed_bits::Bits<5>>,
+    reg_d: Integer<u8, packed_bits::Bits<5>>,
+}
+
+impl Aarch64Bytes for ConversionBetweenFloatAndInteger {}
+
+impl ConversionBetweenFloatAndInteger {
+    #[inline(always)]
+    fn new(sf: bool, ftype: FloatWidth, rmode: u8, opcode: u8, rn: u8, rd: u8) -> Self {
+        debug_assert!(rmode <= 0b11);
+        debug_assert!(opcode <= 0b111);
+
+        Self {
+            reg_d: rd.into(),
+            reg_n: rn.into(),
+            fixed4: 0b000000.into(),
+            opcode: opcode.into(),
+            rmode: rmode.into(),
+            fixed3: true,
+            ftype: float_type(ftype).into(),
+            fixed2: 0b11110.into(),
+            s: false,
+            fixed: false,
+            sf,
+        }
     }
 }
 
@@ -1083,6 +2071,19 @@ fn add_reg64_reg64_imm12(
     buf.extend(inst.bytes());
 }
 
+/// `ADD Xd, Xn, imm12, LSL #12` -> Add Xn and imm12 shifted left by 12 and place the result into Xd.
+#[inline(always)]
+fn add_reg64_reg64_imm12_lsl12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = ArithmeticImmediate::new(false, false, dst, src, imm12, true);
+
+    buf.extend(inst.bytes());
+}
+
 /// `ADD Xd, Xm, Xn` -> Add Xm and Xn and place the result into Xd.
 #[inline(always)]
 fn add_reg64_reg64_reg64(
@@ -1096,16 +2097,232 @@ fn add_reg64_reg64_reg64(
     buf.extend(inst.bytes());
 }
 
-/// `LDR Xt, [Xn, #offset]` -> Load Xn + Offset Xt. ZRSP is SP.
-/// Note: imm12 is the offest divided by 8.
+/// `ADRP Xd, imm21` -> Place the address of the 4KB page imm21 pages away from this instruction into Xd.
 #[inline(always)]
-fn ldr_reg64_reg64_imm12(
+fn adrp_reg64_imm21(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm21: i32) {
+    let inst = PCRelativeAddressing::new(true, imm21, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `AND Xd, Xn, Xm` -> Bitwise and Xn and Xm and place the result into Xd.
+#[inline(always)]
+fn and_reg64_reg64_reg64(
     buf: &mut Vec<'_, u8>,
     dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = LogicalShiftedRegister::new(LogicalOp::AND, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `B imm26` -> Branch imm26 words away from this instruction.
+#[inline(always)]
+fn b_imm26(buf: &mut Vec<'_, u8>, imm26: i32) {
+    let inst = UnconditionalBranchImmediate::new(false, imm26);
+
+    buf.extend(inst.bytes());
+}
+
+/// `B.cond imm19` -> Branch imm19 words away from this instruction if the condition holds.
+#[inline(always)]
+fn b_cond_imm19(buf: &mut Vec<'_, u8>, cond: ConditionCode, imm19: i32) {
+    let inst = ConditionalBranchImmediate::new(cond, imm19);
+
+    buf.extend(inst.bytes());
+}
+
+/// `BL imm26` -> Branch imm26 words away from this instruction and store the return address in LR.
+#[inline(always)]
+fn bl_imm26(buf: &mut Vec<'_, u8>, imm26: i32) {
+    let inst = UnconditionalBranchImmediate::new(true, imm26);
+
+    buf.extend(inst.bytes());
+}
+
+/// `CMP Xn, imm12` -> Compare Xn with imm12 and set the flags. ZRSP is SP.
+#[inline(always)]
+fn cmp_reg64_imm12(buf: &mut Vec<'_, u8>, src: AArch64GeneralReg, imm12: u16) {
+    // CMP is equvalent to `SUBS XZR, Xn, imm12` in AARCH64.
+    let inst = ArithmeticImmediate::new(true, true, AArch64GeneralReg::ZRSP, src, imm12, false);
+
+    buf.extend(inst.bytes());
+}
+
+/// `CMP Xn, Xm` -> Compare Xn with Xm and set the flags.
+#[inline(always)]
+fn cmp_reg64_reg64(buf: &mut Vec<'_, u8>, src1: AArch64GeneralReg, src2: AArch64GeneralReg) {
+    // CMP is equvalent to `SUBS XZR, Xn, Xm` in AARCH64.
+    let inst = ArithmeticShifted::new(
+        true,
+        true,
+        ShiftType::LSL,
+        0,
+        src2,
+        src1,
+        AArch64GeneralReg::ZRSP,
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `CNEG Xd, Xn, cond` -> Place the negation of Xn into Xd if the condition holds, otherwise Xn.
+#[inline(always)]
+fn cneg_reg64_reg64_cond(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+    cond: ConditionCode,
+) {
+    // CNEG is equvalent to `CSNEG Xd, Xn, Xn, invert(cond)` in AARCH64.
+    let inst = ConditionalSelect::new(true, 0b01, cond.inverted_id(), src, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `CSET Xd, cond` -> Place 1 into Xd if the condition holds, otherwise 0.
+#[inline(always)]
+fn cset_reg64_cond(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, cond: ConditionCode) {
+    // CSET is equvalent to `CSINC Xd, XZR, XZR, invert(cond)` in AARCH64.
+    let inst = ConditionalSelect::new(
+        false,
+        0b01,
+        cond.inverted_id(),
+        AArch64GeneralReg::ZRSP,
+        AArch64GeneralReg::ZRSP,
+        dst,
+    );
+
+    buf.extend(inst.bytes());
+}
+
+/// `FABS Fd, Fn` -> Place the absolute value of Fn into Fd.
+#[inline(always)]
+fn fabs_freg_freg(
+    buf: &mut Vec<'_, u8>,
+    ftype: FloatWidth,
+    dst: AArch64FloatReg,
+    src: AArch64FloatReg,
+) {
+    let inst = FloatingPointDataProcessingOneSource::new(ftype, 0b000001, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FADD Fd, Fn, Fm` -> Add Fn and Fm and place the result into Fd.
+#[inline(always)]
+fn fadd_freg_freg_freg(
+    buf: &mut Vec<'_, u8>,
+    ftype: FloatWidth,
+    dst: AArch64FloatReg,
+    src1: AArch64FloatReg,
+    src2: AArch64FloatReg,
+) {
+    let inst = FloatingPointDataProcessingTwoSource::new(ftype, 0b0010, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FCVT Sd, Dn` -> Convert the double Dn to a single and place the result into Sd.
+#[inline(always)]
+fn fcvt_freg32_freg64(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatWidth::F64, 0b000100, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FCVT Dd, Sn` -> Convert the single Sn to a double and place the result into Dd.
+#[inline(always)]
+fn fcvt_freg64_freg32(buf: &mut Vec<'_, u8>, dst: AArch64FloatReg, src: AArch64FloatReg) {
+    let inst = FloatingPointDataProcessingOneSource::new(FloatWidth::F32, 0b000101, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMOV Fd, Fn` -> Move Fn to Fd.
+#[inline(always)]
+fn fmov_freg_freg(
+    buf: &mut Vec<'_, u8>,
+    ftype: FloatWidth,
+    dst: AArch64FloatReg,
+    src: AArch64FloatReg,
+) {
+    let inst = FloatingPointDataProcessingOneSource::new(ftype, 0b000000, src, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `FMOV Fd, Rn` -> Move the bits of Rn to Fd. Rn is Wn for singles and Xn for doubles.
+#[inline(always)]
+fn fmov_freg_reg(
+    buf: &mut Vec<'_, u8>,
+    ftype: FloatWidth,
+    dst: AArch64FloatReg,
+    src: AArch64GeneralReg,
+) {
+    let sf = matches!(ftype, FloatWidth::F64);
+    let inst = ConversionBetweenFloatAndInteger::new(sf, ftype, 0b00, 0b111, src.id(), dst.id());
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDP Xt1, Xt2, [Xn], #offset` -> Load the pair at Xn, then add offset to Xn. ZRSP is SP.
+/// Note: imm7 is the offset divided by 8.
+#[inline(always)]
+fn ldp_post_reg64_reg64_reg64_imm7(
+    buf: &mut Vec<'_, u8>,
+    dst1: AArch64GeneralReg,
+    dst2: AArch64GeneralReg,
+    base: AArch64GeneralReg,
+    imm7: i8,
+) {
+    let inst = LoadStorePair::new(0b01, true, imm7, dst2, base, dst1);
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDR/STR Rt, [Xn, #offset]` -> Load or store Rt at Xn + Offset. ZRSP is SP.
+/// Note: imm12 is the offset divided by the size of the access.
+#[inline(always)]
+fn load_store_reg_reg64_imm12<R: RegTrait>(
+    buf: &mut Vec<'_, u8>,
+    op: LoadStoreOp,
+    reg: R,
     base: AArch64GeneralReg,
     imm12: u16,
 ) {
-    let inst = LoadStoreRegisterImmediate::new_load(0b11, imm12, base, dst);
+    let inst = LoadStoreRegisterImmediate::new(op, imm12, base, reg.value());
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDUR/STUR Rt, [Xn, #offset]` -> Load or store Rt at Xn + Offset. ZRSP is SP.
+/// Note: imm9 is the signed offset in bytes.
+#[inline(always)]
+fn load_store_reg_reg64_imm9<R: RegTrait>(
+    buf: &mut Vec<'_, u8>,
+    op: LoadStoreOp,
+    reg: R,
+    base: AArch64GeneralReg,
+    imm9: i16,
+) {
+    let inst = LoadStoreRegisterUnscaled::new(op, imm9, base, reg.value());
+
+    buf.extend(inst.bytes());
+}
+
+/// `LDR/STR Rt, [Xn, Xm]` -> Load or store Rt at Xn + Xm. ZRSP is SP for Xn and ZR for Xm.
+#[inline(always)]
+fn load_store_reg_reg64_reg64<R: RegTrait>(
+    buf: &mut Vec<'_, u8>,
+    op: LoadStoreOp,
+    reg: R,
+    base: AArch64GeneralReg,
+    offset: AArch64GeneralReg,
+) {
+    let inst = LoadStoreRegisterRegisterOffset::new(op, offset, base, reg.value());
 
     buf.extend(inst.bytes());
 }
@@ -1142,16 +2359,45 @@ fn movz_reg64_imm16(buf: &mut Vec<'_, u8>, dst: AArch64GeneralReg, imm16: u16, h
     buf.extend(inst.bytes());
 }
 
-/// `STR Xt, [Xn, #offset]` -> Store Xt to Xn + Offset. ZRSP is SP.
-/// Note: imm12 is the offest divided by 8.
+/// `MUL Xd, Xn, Xm` -> Multiply Xn and Xm and place the result into Xd.
 #[inline(always)]
-fn str_reg64_reg64_imm12(
+fn mul_reg64_reg64_reg64(
     buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    // MUL is equvalent to `MADD Xd, Xn, Xm, XZR` in AARCH64.
+    let inst =
+        DataProcessingThreeSource::new(0b000, false, src2, AArch64GeneralReg::ZRSP, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
+/// `SCVTF Fd, Xn` -> Convert the signed integer Xn to a float and place the result into Fd.
+#[inline(always)]
+fn scvtf_freg_reg64(
+    buf: &mut Vec<'_, u8>,
+    ftype: FloatWidth,
+    dst: AArch64FloatReg,
     src: AArch64GeneralReg,
+) {
+    let inst = ConversionBetweenFloatAndInteger::new(true, ftype, 0b00, 0b010, src.id(), dst.id());
+
+    buf.extend(inst.bytes());
+}
+
+/// `STP Xt1, Xt2, [Xn, #offset]!` -> Add offset to Xn, then store the pair at Xn. ZRSP is SP.
+/// Note: imm7 is the offset divided by 8.
+#[inline(always)]
+fn stp_pre_reg64_reg64_reg64_imm7(
+    buf: &mut Vec<'_, u8>,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
     base: AArch64GeneralReg,
-    imm12: u16,
+    imm7: i8,
 ) {
-    let inst = LoadStoreRegisterImmediate::new_store(0b11, imm12, base, src);
+    let inst = LoadStorePair::new(0b11, false, imm7, src2, base, src1);
 
     buf.extend(inst.bytes());
 }
@@ -1169,6 +2415,32 @@ fn sub_reg64_reg64_imm12(
     buf.extend(inst.bytes());
 }
 
+/// `SUB Xd, Xn, imm12, LSL #12` -> Subtract Xn and imm12 shifted left by 12 and place the result into Xd.
+#[inline(always)]
+fn sub_reg64_reg64_imm12_lsl12(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src: AArch64GeneralReg,
+    imm12: u16,
+) {
+    let inst = ArithmeticImmediate::new(true, false, dst, src, imm12, true);
+
+    buf.extend(inst.bytes());
+}
+
+/// `SUB Xd, Xn, Xm` -> Subtract Xn and Xm and place the result into Xd.
+#[inline(always)]
+fn sub_reg64_reg64_reg64(
+    buf: &mut Vec<'_, u8>,
+    dst: AArch64GeneralReg,
+    src1: AArch64GeneralReg,
+    src2: AArch64GeneralReg,
+) {
+    let inst = ArithmeticShifted::new(true, false, ShiftType::LSL, 0, src2, src1, dst);
+
+    buf.extend(inst.bytes());
+}
+
 /// `RET Xn` -> Return to the address stored in Xn.
 #[inline(always)]
 fn ret_reg64(buf: &mut Vec<'_, u8>, xn: AArch64GeneralReg) {
@@ -1177,6 +2449,12 @@ fn ret_reg64(buf: &mut Vec<'_, u8>, xn: AArch64GeneralReg) {
     buf.extend(inst.bytes());
 }
 
+/// `BRK #0` -> Raise a breakpoint exception.
+#[inline(always)]
+fn brk(buf: &mut Vec<'_, u8>) {
+    buf.extend(0xD420_0000u32.to_le_bytes());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1189,20 +2467,73 @@ mod tests {
     }
     use ZRSPKind::*;
 
-    impl AArch64GeneralReg {
-        fn capstone_string(&self, zrsp_kind: ZRSPKind) -> String {
+    impl AArch64GeneralReg {
+        fn capstone_string(&self, zrsp_kind: ZRSPKind) -> String {
+            match self {
+                AArch64GeneralReg::XR => "x8".to_owned(),
+                AArch64GeneralReg::IP0 => "x16".to_owned(),
+                AArch64GeneralReg::IP1 => "x17".to_owned(),
+                AArch64GeneralReg::PR => "x18".to_owned(),
+                AArch64GeneralReg::FP => "x29".to_owned(),
+                AArch64GeneralReg::LR => "x30".to_owned(),
+                AArch64GeneralReg::ZRSP => match zrsp_kind {
+                    UsesZR => "xzr".to_owned(),
+                    UsesSP => "sp".to_owned(),
+                },
+                _ => format!("{}", self),
+            }
+        }
+
+        fn capstone_string_32bit(&self) -> String {
+            match self {
+                AArch64GeneralReg::ZRSP => "wzr".to_owned(),
+                _ => format!("w{}", self.id()),
+            }
+        }
+    }
+
+    impl AArch64FloatReg {
+        fn capstone_string(&self, width: FloatWidth) -> String {
+            match width {
+                FloatWidth::F32 => format!("s{}", self.id()),
+                FloatWidth::F64 => format!("d{}", self.id()),
+                FloatWidth::F128 => format!("q{}", self.id()),
+            }
+        }
+    }
+
+    impl ConditionCode {
+        fn capstone_string(&self) -> String {
+            format!("{:?}", self).to_lowercase()
+        }
+    }
+
+    impl LoadStoreOp {
+        fn capstone_string(&self, unscaled: bool) -> String {
+            let name = match self {
+                LoadStoreOp::Ldr64 | LoadStoreOp::LdrF64 | LoadStoreOp::Ldr32 => "ldr",
+                LoadStoreOp::Str64 | LoadStoreOp::StrF64 => "str",
+                LoadStoreOp::Ldrb => "ldrb",
+                LoadStoreOp::Ldrsb => "ldrsb",
+                LoadStoreOp::Ldrh => "ldrh",
+                LoadStoreOp::Ldrsh => "ldrsh",
+                LoadStoreOp::Ldrsw => "ldrsw",
+                LoadStoreOp::Strb => "strb",
+            };
+            if unscaled {
+                format!("{}u{}", &name[..2], &name[2..])
+            } else {
+                name.to_owned()
+            }
+        }
+
+        /// The name of the general register being loaded or stored.
+        fn capstone_reg_string(&self, reg: AArch64GeneralReg) -> String {
             match self {
-                AArch64GeneralReg::XR => "x8".to_owned(),
-                AArch64GeneralReg::IP0 => "x16".to_owned(),
-                AArch64GeneralReg::IP1 => "x17".to_owned(),
-                AArch64GeneralReg::PR => "x18".to_owned(),
-                AArch64GeneralReg::FP => "x29".to_owned(),
-                AArch64GeneralReg::LR => "x30".to_owned(),
-                AArch64GeneralReg::ZRSP => match zrsp_kind {
-                    UsesZR => "xzr".to_owned(),
-                    UsesSP => "sp".to_owned(),
-                },
-                _ => format!("{}", self),
+                LoadStoreOp::Ldrb | LoadStoreOp::Ldrh | LoadStoreOp::Ldr32 | LoadStoreOp::Strb => {
+                    reg.capstone_string_32bit()
+                }
+                _ => reg.capstone_string(UsesZR),
             }
         }
     }
@@ -1245,6 +2576,102 @@ mod tests {
         AArch64GeneralReg::LR,
         AArch64GeneralReg::ZRSP,
     ];
+    const ALL_GENERAL_REGS_WITHOUT_ZRSP: &[AArch64GeneralReg] = &[
+        AArch64GeneralReg::X0,
+        AArch64GeneralReg::X1,
+        AArch64GeneralReg::X2,
+        AArch64GeneralReg::X3,
+        AArch64GeneralReg::X4,
+        AArch64GeneralReg::X5,
+        AArch64GeneralReg::X6,
+        AArch64GeneralReg::X7,
+        AArch64GeneralReg::XR,
+        AArch64GeneralReg::X9,
+        AArch64GeneralReg::X10,
+        AArch64GeneralReg::X11,
+        AArch64GeneralReg::X12,
+        AArch64GeneralReg::X13,
+        AArch64GeneralReg::X14,
+        AArch64GeneralReg::X15,
+        AArch64GeneralReg::IP0,
+        AArch64GeneralReg::IP1,
+        AArch64GeneralReg::PR,
+        AArch64GeneralReg::X19,
+        AArch64GeneralReg::X20,
+        AArch64GeneralReg::X21,
+        AArch64GeneralReg::X22,
+        AArch64GeneralReg::X23,
+        AArch64GeneralReg::X24,
+        AArch64GeneralReg::X25,
+        AArch64GeneralReg::X26,
+        AArch64GeneralReg::X27,
+        AArch64GeneralReg::X28,
+        AArch64GeneralReg::FP,
+        AArch64GeneralReg::LR,
+    ];
+    const ALL_FLOAT_REGS: &[AArch64FloatReg] = &[
+        AArch64FloatReg::V0,
+        AArch64FloatReg::V1,
+        AArch64FloatReg::V2,
+        AArch64FloatReg::V3,
+        AArch64FloatReg::V4,
+        AArch64FloatReg::V5,
+        AArch64FloatReg::V6,
+        AArch64FloatReg::V7,
+        AArch64FloatReg::V8,
+        AArch64FloatReg::V9,
+        AArch64FloatReg::V10,
+        AArch64FloatReg::V11,
+        AArch64FloatReg::V12,
+        AArch64FloatReg::V13,
+        AArch64FloatReg::V14,
+        AArch64FloatReg::V15,
+        AArch64FloatReg::V16,
+        AArch64FloatReg::V17,
+        AArch64FloatReg::V18,
+        AArch64FloatReg::V19,
+        AArch64FloatReg::V20,
+        AArch64FloatReg::V21,
+        AArch64FloatReg::V22,
+        AArch64FloatReg::V23,
+        AArch64FloatReg::V24,
+        AArch64FloatReg::V25,
+        AArch64FloatReg::V26,
+        AArch64FloatReg::V27,
+        AArch64FloatReg::V28,
+        AArch64FloatReg::V29,
+        AArch64FloatReg::V30,
+        AArch64FloatReg::V31,
+    ];
+    const ALL_FLOAT_WIDTHS: &[FloatWidth] = &[FloatWidth::F32, FloatWidth::F64];
+    const ALL_CONDITION_CODES: &[ConditionCode] = &[
+        ConditionCode::EQ,
+        ConditionCode::NE,
+        ConditionCode::HS,
+        ConditionCode::LO,
+        ConditionCode::MI,
+        ConditionCode::PL,
+        ConditionCode::VS,
+        ConditionCode::VC,
+        ConditionCode::HI,
+        ConditionCode::LS,
+        ConditionCode::GE,
+        ConditionCode::LT,
+        ConditionCode::GT,
+        ConditionCode::LE,
+    ];
+    const ALL_GENERAL_LOAD_STORE_OPS: &[LoadStoreOp] = &[
+        LoadStoreOp::Ldr64,
+        LoadStoreOp::Str64,
+        LoadStoreOp::Ldrb,
+        LoadStoreOp::Ldrsb,
+        LoadStoreOp::Ldrh,
+        LoadStoreOp::Ldrsh,
+        LoadStoreOp::Ldr32,
+        LoadStoreOp::Ldrsw,
+        LoadStoreOp::Strb,
+    ];
+    const ALL_FLOAT_LOAD_STORE_OPS: &[LoadStoreOp] = &[LoadStoreOp::LdrF64, LoadStoreOp::StrF64];
 
     fn setup_capstone_and_arena<T>(
         arena: &bumpalo::Bump,
@@ -1292,21 +2719,401 @@ mod tests {
     }
 
     #[test]
-    fn test_ldr_reg64_reg64_imm12() {
+    fn test_add_reg64_reg64_imm12_lsl12() {
         disassembler_test!(
-            ldr_reg64_reg64_imm12,
+            add_reg64_reg64_imm12_lsl12,
             |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
-                "ldr {}, [{}, #0x{:x}]",
-                reg1.capstone_string(UsesZR),
+                "add {}, {}, #0x{:x}, lsl #12",
+                reg1.capstone_string(UsesSP),
                 reg2.capstone_string(UsesSP),
-                imm << 3
+                imm
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
+    #[test]
+    fn test_adrp_reg64_imm21() {
+        disassembler_test!(
+            adrp_reg64_imm21,
+            |reg1: AArch64GeneralReg, imm: i32| format!(
+                "adrp {}, #0x{:x}",
+                reg1.capstone_string(UsesZR),
+                imm << 12
+            ),
+            ALL_GENERAL_REGS,
+            [0x123, 0x1234]
+        );
+    }
+
+    #[test]
+    fn test_and_reg64_reg64_reg64() {
+        disassembler_test!(
+            and_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "and {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
             ),
             ALL_GENERAL_REGS,
             ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_b_imm26() {
+        disassembler_test!(
+            b_imm26,
+            |imm: i32| format!("b #0x{:x}", imm << 2),
+            [0x123, 0x12345]
+        );
+    }
+
+    #[test]
+    fn test_b_cond_imm19() {
+        disassembler_test!(
+            b_cond_imm19,
+            |cond: ConditionCode, imm: i32| format!(
+                "b.{} #0x{:x}",
+                cond.capstone_string(),
+                imm << 2
+            ),
+            ALL_CONDITION_CODES,
+            [0x123, 0x1234]
+        );
+    }
+
+    #[test]
+    fn test_bl_imm26() {
+        disassembler_test!(
+            bl_imm26,
+            |imm: i32| format!("bl #0x{:x}", imm << 2),
+            [0x123, 0x12345]
+        );
+    }
+
+    #[test]
+    fn test_cmp_reg64_imm12() {
+        disassembler_test!(
+            cmp_reg64_imm12,
+            |reg1: AArch64GeneralReg, imm| format!(
+                "cmp {}, #0x{:x}",
+                reg1.capstone_string(UsesSP),
+                imm
+            ),
+            ALL_GENERAL_REGS,
             [0x123]
         );
     }
 
+    #[test]
+    fn test_cmp_reg64_reg64() {
+        disassembler_test!(
+            cmp_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg| format!(
+                "cmp {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS_WITHOUT_ZRSP,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_cneg_reg64_reg64_cond() {
+        disassembler_test!(
+            cneg_reg64_reg64_cond,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, cond: ConditionCode| format!(
+                "cneg {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                cond.capstone_string()
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS_WITHOUT_ZRSP,
+            ALL_CONDITION_CODES
+        );
+    }
+
+    #[test]
+    fn test_cset_reg64_cond() {
+        disassembler_test!(
+            cset_reg64_cond,
+            |reg1: AArch64GeneralReg, cond: ConditionCode| format!(
+                "cset {}, {}",
+                reg1.capstone_string(UsesZR),
+                cond.capstone_string()
+            ),
+            ALL_GENERAL_REGS,
+            ALL_CONDITION_CODES
+        );
+    }
+
+    #[test]
+    fn test_fabs_freg_freg() {
+        disassembler_test!(
+            fabs_freg_freg,
+            |width: FloatWidth, reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fabs {}, {}",
+                reg1.capstone_string(width),
+                reg2.capstone_string(width)
+            ),
+            ALL_FLOAT_WIDTHS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fadd_freg_freg_freg() {
+        for width in ALL_FLOAT_WIDTHS {
+            disassembler_test!(
+                |buf: &mut bumpalo::collections::Vec<'_, u8>,
+                 reg1: AArch64FloatReg,
+                 reg2: AArch64FloatReg,
+                 reg3: AArch64FloatReg| fadd_freg_freg_freg(
+                    buf, *width, reg1, reg2, reg3
+                ),
+                |reg1: AArch64FloatReg, reg2: AArch64FloatReg, reg3: AArch64FloatReg| format!(
+                    "fadd {}, {}, {}",
+                    reg1.capstone_string(*width),
+                    reg2.capstone_string(*width),
+                    reg3.capstone_string(*width)
+                ),
+                ALL_FLOAT_REGS,
+                ALL_FLOAT_REGS,
+                ALL_FLOAT_REGS
+            );
+        }
+    }
+
+    #[test]
+    fn test_fcvt_freg32_freg64() {
+        disassembler_test!(
+            fcvt_freg32_freg64,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fcvt {}, {}",
+                reg1.capstone_string(FloatWidth::F32),
+                reg2.capstone_string(FloatWidth::F64)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fcvt_freg64_freg32() {
+        disassembler_test!(
+            fcvt_freg64_freg32,
+            |reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fcvt {}, {}",
+                reg1.capstone_string(FloatWidth::F64),
+                reg2.capstone_string(FloatWidth::F32)
+            ),
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fmov_freg_freg() {
+        disassembler_test!(
+            fmov_freg_freg,
+            |width: FloatWidth, reg1: AArch64FloatReg, reg2: AArch64FloatReg| format!(
+                "fmov {}, {}",
+                reg1.capstone_string(width),
+                reg2.capstone_string(width)
+            ),
+            ALL_FLOAT_WIDTHS,
+            ALL_FLOAT_REGS,
+            ALL_FLOAT_REGS
+        );
+    }
+
+    #[test]
+    fn test_fmov_freg_reg() {
+        disassembler_test!(
+            fmov_freg_reg,
+            |width: FloatWidth, reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "fmov {}, {}",
+                reg1.capstone_string(width),
+                match width {
+                    FloatWidth::F32 => reg2.capstone_string_32bit(),
+                    _ => reg2.capstone_string(UsesZR),
+                }
+            ),
+            ALL_FLOAT_WIDTHS,
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_ldp_post_reg64_reg64_reg64_imm7() {
+        disassembler_test!(
+            |buf: &mut bumpalo::collections::Vec<'_, u8>,
+             reg1: AArch64GeneralReg,
+             reg2: AArch64GeneralReg,
+             imm: i8| ldp_post_reg64_reg64_reg64_imm7(
+                buf,
+                reg1,
+                reg2,
+                AArch64GeneralReg::ZRSP,
+                imm
+            ),
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm: i8| format!(
+                "ldp {}, {}, [sp], #0x{:x}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                (imm as i32) << 3
+            ),
+            [AArch64GeneralReg::FP, AArch64GeneralReg::X19],
+            [AArch64GeneralReg::LR, AArch64GeneralReg::X20],
+            [2, 0x12]
+        );
+    }
+
+    #[test]
+    fn test_load_store_reg_reg64_imm12() {
+        for op in ALL_GENERAL_LOAD_STORE_OPS {
+            disassembler_test!(
+                |buf: &mut bumpalo::collections::Vec<'_, u8>,
+                 reg1: AArch64GeneralReg,
+                 reg2: AArch64GeneralReg,
+                 imm: u16| load_store_reg_reg64_imm12(buf, *op, reg1, reg2, imm),
+                |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm: u16| format!(
+                    "{} {}, [{}, #0x{:x}]",
+                    op.capstone_string(false),
+                    op.capstone_reg_string(reg1),
+                    reg2.capstone_string(UsesSP),
+                    imm << op.size()
+                ),
+                ALL_GENERAL_REGS,
+                ALL_GENERAL_REGS,
+                [0x123]
+            );
+        }
+        for op in ALL_FLOAT_LOAD_STORE_OPS {
+            disassembler_test!(
+                |buf: &mut bumpalo::collections::Vec<'_, u8>,
+                 reg1: AArch64FloatReg,
+                 reg2: AArch64GeneralReg,
+                 imm: u16| load_store_reg_reg64_imm12(buf, *op, reg1, reg2, imm),
+                |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, imm: u16| format!(
+                    "{} {}, [{}, #0x{:x}]",
+                    op.capstone_string(false),
+                    reg1.capstone_string(FloatWidth::F64),
+                    reg2.capstone_string(UsesSP),
+                    imm << op.size()
+                ),
+                ALL_FLOAT_REGS,
+                ALL_GENERAL_REGS,
+                [0x123]
+            );
+        }
+    }
+
+    #[test]
+    fn test_load_store_reg_reg64_imm9() {
+        fn imm_string(imm: i16) -> String {
+            if imm < 0 {
+                format!("#-0x{:x}", -imm)
+            } else {
+                format!("#0x{:x}", imm)
+            }
+        }
+
+        for op in ALL_GENERAL_LOAD_STORE_OPS {
+            disassembler_test!(
+                |buf: &mut bumpalo::collections::Vec<'_, u8>,
+                 reg1: AArch64GeneralReg,
+                 reg2: AArch64GeneralReg,
+                 imm: i16| load_store_reg_reg64_imm9(buf, *op, reg1, reg2, imm),
+                |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm: i16| format!(
+                    "{} {}, [{}, {}]",
+                    op.capstone_string(true),
+                    op.capstone_reg_string(reg1),
+                    reg2.capstone_string(UsesSP),
+                    imm_string(imm)
+                ),
+                ALL_GENERAL_REGS,
+                ALL_GENERAL_REGS,
+                [-0x100, -0x12, 0xFF]
+            );
+        }
+        for op in ALL_FLOAT_LOAD_STORE_OPS {
+            disassembler_test!(
+                |buf: &mut bumpalo::collections::Vec<'_, u8>,
+                 reg1: AArch64FloatReg,
+                 reg2: AArch64GeneralReg,
+                 imm: i16| load_store_reg_reg64_imm9(buf, *op, reg1, reg2, imm),
+                |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, imm: i16| format!(
+                    "{} {}, [{}, {}]",
+                    op.capstone_string(true),
+                    reg1.capstone_string(FloatWidth::F64),
+                    reg2.capstone_string(UsesSP),
+                    imm_string(imm)
+                ),
+                ALL_FLOAT_REGS,
+                ALL_GENERAL_REGS,
+                [-0x100, -0x12, 0xFF]
+            );
+        }
+    }
+
+    #[test]
+    fn test_load_store_reg_reg64_reg64() {
+        for op in ALL_GENERAL_LOAD_STORE_OPS {
+            disassembler_test!(
+                |buf: &mut bumpalo::collections::Vec<'_, u8>,
+                 reg1: AArch64GeneralReg,
+                 reg2: AArch64GeneralReg,
+                 reg3: AArch64GeneralReg| load_store_reg_reg64_reg64(
+                    buf, *op, reg1, reg2, reg3
+                ),
+                |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| {
+                    format!(
+                        "{} {}, [{}, {}]",
+                        op.capstone_string(false),
+                        op.capstone_reg_string(reg1),
+                        reg2.capstone_string(UsesSP),
+                        reg3.capstone_string(UsesZR)
+                    )
+                },
+                ALL_GENERAL_REGS,
+                ALL_GENERAL_REGS,
+                ALL_GENERAL_REGS
+            );
+        }
+        for op in ALL_FLOAT_LOAD_STORE_OPS {
+            disassembler_test!(
+                |buf: &mut bumpalo::collections::Vec<'_, u8>,
+                 reg1: AArch64FloatReg,
+                 reg2: AArch64GeneralReg,
+                 reg3: AArch64GeneralReg| load_store_reg_reg64_reg64(
+                    buf, *op, reg1, reg2, reg3
+                ),
+                |reg1: AArch64FloatReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| {
+                    format!(
+                        "{} {}, [{}, {}]",
+                        op.capstone_string(false),
+                        reg1.capstone_string(FloatWidth::F64),
+                        reg2.capstone_string(UsesSP),
+                        reg3.capstone_string(UsesZR)
+                    )
+                },
+                ALL_FLOAT_REGS,
+                ALL_GENERAL_REGS,
+                ALL_GENERAL_REGS
+            );
+        }
+    }
+
     #[test]
     fn test_mov_reg64_reg64() {
         disassembler_test!(
@@ -1358,18 +3165,58 @@ mod tests {
     }
 
     #[test]
-    fn test_str_reg64_reg64_imm12() {
+    fn test_mul_reg64_reg64_reg64() {
         disassembler_test!(
-            str_reg64_reg64_imm12,
-            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
-                "str {}, [{}, #0x{:x}]",
+            mul_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "mul {}, {}, {}",
                 reg1.capstone_string(UsesZR),
-                reg2.capstone_string(UsesSP),
-                imm << 3
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
             ),
             ALL_GENERAL_REGS,
             ALL_GENERAL_REGS,
-            [0x123]
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_scvtf_freg_reg64() {
+        disassembler_test!(
+            scvtf_freg_reg64,
+            |width: FloatWidth, reg1: AArch64FloatReg, reg2: AArch64GeneralReg| format!(
+                "scvtf {}, {}",
+                reg1.capstone_string(width),
+                reg2.capstone_string(UsesZR)
+            ),
+            ALL_FLOAT_WIDTHS,
+            ALL_FLOAT_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
+    #[test]
+    fn test_stp_pre_reg64_reg64_reg64_imm7() {
+        disassembler_test!(
+            |buf: &mut bumpalo::collections::Vec<'_, u8>,
+             reg1: AArch64GeneralReg,
+             reg2: AArch64GeneralReg,
+             imm: i8| stp_pre_reg64_reg64_reg64_imm7(
+                buf,
+                reg1,
+                reg2,
+                AArch64GeneralReg::ZRSP,
+                imm
+            ),
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm: i8| format!(
+                "stp {}, {}, [sp, #-0x{:x}]!",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                (-(imm as i32)) << 3
+            ),
+            [AArch64GeneralReg::FP, AArch64GeneralReg::X19],
+            [AArch64GeneralReg::LR, AArch64GeneralReg::X20],
+            [-2, -0x12]
         );
     }
 
@@ -1389,6 +3236,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_sub_reg64_reg64_imm12_lsl12() {
+        disassembler_test!(
+            sub_reg64_reg64_imm12_lsl12,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, imm| format!(
+                "sub {}, {}, #0x{:x}, lsl #12",
+                reg1.capstone_string(UsesSP),
+                reg2.capstone_string(UsesSP),
+                imm
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS,
+            [0x123]
+        );
+    }
+
+    #[test]
+    fn test_sub_reg64_reg64_reg64() {
+        disassembler_test!(
+            sub_reg64_reg64_reg64,
+            |reg1: AArch64GeneralReg, reg2: AArch64GeneralReg, reg3: AArch64GeneralReg| format!(
+                "sub {}, {}, {}",
+                reg1.capstone_string(UsesZR),
+                reg2.capstone_string(UsesZR),
+                reg3.capstone_string(UsesZR)
+            ),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS_WITHOUT_ZRSP,
+            ALL_GENERAL_REGS
+        );
+    }
+
     #[test]
     fn test_ret_reg64() {
         disassembler_test!(
@@ -1401,4 +3280,9 @@ mod tests {
             ALL_GENERAL_REGS
         );
     }
+
+    #[test]
+    fn test_brk() {
+        disassembler_test!(brk, || "brk #0");
+    }
 }
@@ -0,0 +1,493 @@
+use bumpalo::{collections::Vec, Bump};
+use roc_collections::all::MutMap;
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::Symbol;
+use roc_mono::ir::{CallType, Expr, JoinPointId, ListLiteralElement, Stmt};
+use roc_mono::layout::{Layout, UnionLayout};
+
+/// The live interval of a symbol within a procedure.
+/// Positions are the order in which the backend generates the statements of the procedure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LiveInterval<'a> {
+    /// The position of the statement that defines the symbol.
+    pub start: u32,
+    /// The positions of all statements that use the symbol, in increasing order.
+    pub uses: Vec<'a, u32>,
+    /// Whether the symbol has to survive a call to another function.
+    pub crosses_call: bool,
+}
+
+impl<'a> LiveInterval<'a> {
+    /// The position of the last statement that needs the symbol.
+    pub fn end(&self) -> u32 {
+        self.uses.last().copied().unwrap_or(self.start)
+    }
+}
+
+/// Liveness is a linear scan over the mono ir of a procedure.
+/// It numbers statements in the order `build_stmt` visits them and records where every symbol is defined and used.
+/// The storage manager uses this to decide which symbols should stay in registers and which kind of register they belong in.
+#[derive(Clone, Debug)]
+pub struct Liveness<'a> {
+    positions: MutMap<*const Stmt<'a>, u32>,
+    intervals: MutMap<Symbol, LiveInterval<'a>>,
+    // The positions of all statements that may call another function, in increasing order.
+    calls: Vec<'a, u32>,
+    // The position of every join point and the first and last position of its body.
+    join_bodies: MutMap<JoinPointId, (u32, u32, u32)>,
+    // Every jump, with its position.
+    jumps: Vec<'a, (JoinPointId, u32)>,
+    next_position: u32,
+}
+
+impl<'a> Liveness<'a> {
+    pub fn new(arena: &'a Bump, args: &[(Layout<'a>, Symbol)], body: &Stmt<'a>) -> Self {
+        let mut liveness = Liveness {
+            positions: MutMap::default(),
+            intervals: MutMap::default(),
+            calls: bumpalo::vec![in arena],
+            join_bodies: MutMap::default(),
+            jumps: bumpalo::vec![in arena],
+            next_position: 1,
+        };
+        // Arguments are defined before the first statement.
+        for (_, sym) in args {
+            liveness.define(arena, *sym, 0);
+        }
+        liveness.scan_stmt(arena, body);
+        liveness.extend_over_loops();
+
+        let calls = &liveness.calls;
+        for interval in liveness.intervals.values_mut() {
+            let end = interval.end();
+            let first_call_after_start = calls.partition_point(|call| *call <= interval.start);
+            interval.crosses_call = calls
+                .get(first_call_after_start)
+                .map(|call| *call < end)
+                .unwrap_or(false);
+        }
+        liveness
+    }
+
+    /// Returns the position of the statement if it is part of the procedure.
+    /// Statements generated while building, like expanded refcounting, have no position.
+    pub fn position(&self, stmt: &Stmt<'a>) -> Option<u32> {
+        self.positions.get(&(stmt as *const Stmt<'a>)).copied()
+    }
+
+    /// Returns the first position at or after `position` where the symbol is needed.
+    /// A symbol that is defined at `position` is needed right now.
+    /// Symbols without an interval are temporaries of the current statement and are also needed right now.
+    pub fn next_use(&self, sym: &Symbol, position: u32) -> u32 {
+        match self.intervals.get(sym) {
+            Some(interval) if interval.start < position => {
+                let next = interval.uses.partition_point(|u| *u < position);
+                interval.uses.get(next).copied().unwrap_or(u32::MAX)
+            }
+            Some(interval) => interval.start,
+            None => position,
+        }
+    }
+
+    /// Returns true if the symbol has to survive a call to another function.
+    pub fn crosses_call(&self, sym: &Symbol) -> bool {
+        self.intervals
+            .get(sym)
+            .map(|interval| interval.crosses_call)
+            .unwrap_or(false)
+    }
+
+    #[cfg(test)]
+    pub fn interval(&self, sym: &Symbol) -> Option<&LiveInterval<'a>> {
+        self.intervals.get(sym)
+    }
+
+    /// A jump from inside the body of its join point starts the body over, like a loop.
+    /// Every symbol that is defined before the join point and used in its body is needed again on
+    /// the next iteration, so it must stay live until the end of the body.
+    /// The parameters don't need this, they get new values with every jump.
+    fn extend_over_loops(&mut self) {
+        for (id, position) in self.jumps.iter() {
+            let (join_position, body_start, body_end) = match self.join_bodies.get(id) {
+                Some(&(join, start, end)) if start <= *position && *position <= end => {
+                    (join, start, end)
+                }
+                _ => continue,
+            };
+
+            for interval in self.intervals.values_mut() {
+                let used_in_body = interval
+                    .uses
+                    .iter()
+                    .any(|u| body_start <= *u && *u <= body_end);
+
+                if interval.start < join_position && used_in_body && interval.end() < body_end {
+                    interval.uses.push(body_end);
+                }
+            }
+        }
+    }
+
+    fn define(&mut self, arena: &'a Bump, sym: Symbol, position: u32) {
+        self.intervals.insert(
+            sym,
+            LiveInterval {
+                start: position,
+                uses: bumpalo::vec![in arena],
+                crosses_call: false,
+            },
+        );
+    }
+
+    fn use_sym(&mut self, sym: Symbol, position: u32) {
+        if let Some(interval) = self.intervals.get_mut(&sym) {
+            if interval.uses.last() != Some(&position) {
+                interval.uses.push(position);
+            }
+        }
+    }
+
+    /// This must iterate through the ast in the same way that build_stmt does. i.e. remainder before join body.
+    fn scan_stmt(&mut self, arena: &'a Bump, stmt: &Stmt<'a>) {
+        let position = self.next_position;
+        self.next_position += 1;
+        self.positions.insert(stmt, position);
+
+        match stmt {
+            Stmt::Let(sym, expr, layout, following) => {
+                self.scan_expr(expr, position);
+                if expr_may_call(expr, layout) {
+                    self.calls.push(position);
+                }
+                self.define(arena, *sym, position);
+                self.scan_stmt(arena, following);
+            }
+            Stmt::Switch {
+                cond_symbol,
+                branches,
+                default_branch,
+                ..
+            } => {
+                self.use_sym(*cond_symbol, position);
+                for (_, _, branch) in *branches {
+                    self.scan_stmt(arena, branch);
+                }
+                self.scan_stmt(arena, default_branch.1);
+            }
+            Stmt::Ret(sym) => {
+                self.use_sym(*sym, position);
+            }
+            Stmt::Refcounting(modify, following) => {
+                // Refcounting is expanded into a call to a helper proc.
+                self.use_sym(modify.get_symbol(), position);
+                self.calls.push(position);
+                self.scan_stmt(arena, following);
+            }
+            Stmt::Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => {
+                for param in *parameters {
+                    self.define(arena, param.symbol, position);
+                }
+                self.scan_stmt(arena, remainder);
+
+                let body_start = self.next_position;
+                self.scan_stmt(arena, body);
+                self.join_bodies
+                    .insert(*id, (position, body_start, self.next_position - 1));
+            }
+            Stmt::Jump(id, symbols) => {
+                for sym in *symbols {
+                    self.use_sym(*sym, position);
+                }
+                self.jumps.push((*id, position));
+            }
+            Stmt::Dbg {
+                symbol, remainder, ..
+            } => {
+                self.use_sym(*symbol, position);
+                self.calls.push(position);
+                self.scan_stmt(arena, remainder);
+            }
+            Stmt::Crash(message) => {
+                self.use_sym(*message, position);
+                self.calls.push(position);
+            }
+            Stmt::Expect {
+                condition,
+                lookups,
+                remainder,
+                ..
+            } => {
+                self.use_sym(*condition, position);
+                for sym in *lookups {
+                    self.use_sym(*sym, position);
+                }
+                self.scan_stmt(arena, remainder);
+            }
+            Stmt::RuntimeError(_) => {}
+        }
+    }
+
+    fn scan_expr(&mut self, expr: &Expr<'a>, position: u32) {
+        match expr {
+            Expr::Literal(_) | Expr::EmptyArray | Expr::RuntimeErrorFunction(_) => {}
+            Expr::Call(call) => {
+                for sym in call.arguments {
+                    self.use_sym(*sym, position);
+                }
+            }
+            Expr::Tag { arguments, .. } | Expr::Struct(arguments) => {
+                for sym in *arguments {
+                    self.use_sym(*sym, position);
+                }
+            }
+            Expr::ExprBox { symbol } | Expr::ExprUnbox { symbol } => {
+                self.use_sym(*symbol, position);
+            }
+            Expr::StructAtIndex { structure, .. }
+            | Expr::GetTagId { structure, .. }
+            | Expr::UnionAtIndex { structure, .. } => {
+                self.use_sym(*structure, position);
+            }
+            Expr::SetUnionAtIndex {
+                structure, value, ..
+            } => {
+                self.use_sym(*structure, position);
+                self.use_sym(*value, position);
+            }
+            Expr::Array { elems, .. } => {
+                for elem in *elems {
+                    if let ListLiteralElement::Symbol(sym) = elem {
+                        self.use_sym(*sym, position);
+                    }
+                }
+            }
+            Expr::Reuse {
+                symbol, arguments, ..
+            } => {
+                self.use_sym(*symbol, position);
+                for sym in *arguments {
+                    self.use_sym(*sym, position);
+                }
+            }
+            Expr::Reset { symbol, .. } => {
+                self.use_sym(*symbol, position);
+            }
+        }
+    }
+}
+
+/// Returns true if generating the expression may emit a call to another function.
+/// Low levels that are generated inline are the exception, everything else is assumed to call.
+fn expr_may_call(expr: &Expr<'_>, layout: &Layout<'_>) -> bool {
+    match expr {
+        Expr::Call(call) => match &call.call_type {
+            CallType::LowLevel { op, .. } => !matches!(
+                op,
+                LowLevel::NumAbs
+                    | LowLevel::NumAdd
+                    | LowLevel::NumMul
+                    | LowLevel::NumNeg
+                    | LowLevel::NumSub
+                    | LowLevel::NumSubWrap
+                    | LowLevel::And
+                    | LowLevel::Eq
+                    | LowLevel::NotEq
+                    | LowLevel::NumLt
+                    | LowLevel::NumLte
+                    | LowLevel::NumGte
+                    | LowLevel::NumToFrac
+                    | LowLevel::ListLen
+                    | LowLevel::ListGetUnsafe
+                    | LowLevel::PtrCast
+            ),
+            CallType::ByName { .. } | CallType::Foreign { .. } | CallType::HigherOrder(_) => true,
+        },
+        // Heap allocated values call the allocator.
+        Expr::Tag { .. } => !matches!(layout, Layout::Union(UnionLayout::NonRecursive(_))),
+        Expr::ExprBox { .. } | Expr::Reuse { .. } => true,
+        Expr::Array { elems, .. } => !elems.is_empty(),
+        Expr::Literal(_)
+        | Expr::Struct(_)
+        | Expr::StructAtIndex { .. }
+        | Expr::GetTagId { .. }
+        | Expr::UnionAtIndex { .. }
+        | Expr::SetUnionAtIndex { .. }
+        | Expr::ExprUnbox { .. }
+        | Expr::EmptyArray
+        | Expr::Reset { .. }
+        | Expr::RuntimeErrorFunction(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use roc_builtins::bitcode::IntWidth;
+    use roc_module::ident::ForeignSymbol;
+    use roc_module::symbol::{Interns, ModuleId};
+    use roc_mono::ir::{Call, Literal, Param, UpdateModeId};
+    use roc_mono::layout::Builtin;
+    use roc_region::all::Region;
+
+    fn sym(id: u32) -> Symbol {
+        Interns::from_index(ModuleId::ATTR, id)
+    }
+
+    fn num_add<'a>(arena: &'a Bump, args: [Symbol; 2]) -> Expr<'a> {
+        Expr::Call(Call {
+            call_type: CallType::LowLevel {
+                op: LowLevel::NumAdd,
+                update_mode: UpdateModeId::BACKEND_DUMMY,
+            },
+            arguments: arena.alloc(args),
+        })
+    }
+
+    #[test]
+    fn test_intervals_and_calls() {
+        let arena = Bump::new();
+        let i64_layout = Layout::Builtin(Builtin::Int(IntWidth::I64));
+        let (a, b, c, d) = (sym(1), sym(2), sym(3), sym(4));
+
+        // a = 1
+        // b = a + a
+        // c = f b
+        // d = a + c
+        // ret d
+        let ret = arena.alloc(Stmt::Ret(d));
+        let let_d = arena.alloc(Stmt::Let(d, num_add(&arena, [a, c]), i64_layout, ret));
+        let let_c = arena.alloc(Stmt::Let(
+            c,
+            Expr::Call(Call {
+                call_type: CallType::Foreign {
+                    foreign_symbol: ForeignSymbol::from("f"),
+                    ret_layout: arena.alloc(i64_layout),
+                },
+                arguments: arena.alloc([b]),
+            }),
+            i64_layout,
+            let_d,
+        ));
+        let let_b = arena.alloc(Stmt::Let(b, num_add(&arena, [a, a]), i64_layout, let_c));
+        let body = Stmt::Let(
+            a,
+            Expr::Literal(Literal::Int(1i128.to_ne_bytes())),
+            i64_layout,
+            let_b,
+        );
+
+        let liveness = Liveness::new(&arena, &[], &body);
+
+        assert_eq!(liveness.position(&body), Some(1));
+        assert_eq!(liveness.position(let_c), Some(3));
+        assert_eq!(liveness.position(ret), Some(5));
+
+        let a_interval = liveness.interval(&a).unwrap();
+        assert_eq!(a_interval.start, 1);
+        assert_eq!(a_interval.uses.as_slice(), &[2, 4]);
+        assert_eq!(a_interval.end(), 4);
+
+        // a is needed after the call, b is only needed as an argument to it.
+        assert!(liveness.crosses_call(&a));
+        assert!(!liveness.crosses_call(&b));
+        assert!(!liveness.crosses_call(&c));
+
+        assert_eq!(liveness.next_use(&a, 3), 4);
+        assert_eq!(liveness.next_use(&b, 3), 3);
+        assert_eq!(liveness.next_use(&c, 3), 3);
+        assert_eq!(liveness.next_use(&a, 5), u32::MAX);
+        assert_eq!(liveness.next_use(&Symbol::DEV_TMP, 3), 3);
+    }
+
+    #[test]
+    fn test_loop_keeps_outer_symbols_live() {
+        let arena = Bump::new();
+        let i64_layout = Layout::Builtin(Builtin::Int(IntWidth::I64));
+        let (a, x, b, c) = (sym(1), sym(2), sym(3), sym(4));
+        let id = JoinPointId(sym(5));
+
+        // a = 1
+        // join j x =
+        //     b = x + a
+        //     c = b + b
+        //     jump j c
+        // in
+        // jump j a
+        let jump_c = arena.alloc(Stmt::Jump(id, arena.alloc([c])));
+        let let_c = arena.alloc(Stmt::Let(c, num_add(&arena, [b, b]), i64_layout, jump_c));
+        let let_b = arena.alloc(Stmt::Let(b, num_add(&arena, [x, a]), i64_layout, let_c));
+        let join = arena.alloc(Stmt::Join {
+            id,
+            parameters: arena.alloc([Param {
+                symbol: x,
+                borrow: false,
+                layout: i64_layout,
+            }]),
+            body: let_b,
+            remainder: arena.alloc(Stmt::Jump(id, arena.alloc([a]))),
+        });
+        let body = Stmt::Let(
+            a,
+            Expr::Literal(Literal::Int(1i128.to_ne_bytes())),
+            i64_layout,
+            join,
+        );
+
+        let liveness = Liveness::new(&arena, &[], &body);
+
+        assert_eq!(liveness.position(let_b), Some(4));
+        assert_eq!(liveness.position(jump_c), Some(6));
+
+        // a is needed again by every iteration, so it lives until the jump back.
+        let a_interval = liveness.interval(&a).unwrap();
+        assert_eq!(a_interval.uses.as_slice(), &[3, 4, 6]);
+        assert_eq!(liveness.next_use(&a, 5), 6);
+
+        // The parameter and the symbols defined in the body get new values with every iteration.
+        assert_eq!(liveness.interval(&x).unwrap().uses.as_slice(), &[4]);
+        assert_eq!(liveness.interval(&b).unwrap().uses.as_slice(), &[5]);
+        assert_eq!(liveness.interval(&c).unwrap().uses.as_slice(), &[6]);
+    }
+
+    #[test]
+    fn test_expect_remainder() {
+        let arena = Bump::new();
+        let i64_layout = Layout::Builtin(Builtin::Int(IntWidth::I64));
+        let (a, cond, b) = (sym(1), sym(2), sym(3));
+
+        // a = 1
+        // cond = a + a
+        // expect cond
+        // b = a + a
+        // ret b
+        let ret = arena.alloc(Stmt::Ret(b));
+        let let_b = arena.alloc(Stmt::Let(b, num_add(&arena, [a, a]), i64_layout, ret));
+        let expect = arena.alloc(Stmt::Expect {
+            condition: cond,
+            region: Region::zero(),
+            lookups: arena.alloc([a]),
+            layouts: arena.alloc([i64_layout]),
+            remainder: let_b,
+        });
+        let let_cond = arena.alloc(Stmt::Let(cond, num_add(&arena, [a, a]), i64_layout, expect));
+        let body = Stmt::Let(
+            a,
+            Expr::Literal(Literal::Int(1i128.to_ne_bytes())),
+            i64_layout,
+            let_cond,
+        );
+
+        let liveness = Liveness::new(&arena, &[], &body);
+
+        assert_eq!(liveness.position(let_b), Some(4));
+        assert_eq!(liveness.position(ret), Some(5));
+        assert_eq!(liveness.interval(&a).unwrap().uses.as_slice(), &[2, 3, 4]);
+        assert_eq!(liveness.interval(&cond).unwrap().uses.as_slice(), &[3]);
+        assert_eq!(liveness.interval(&b).unwrap().uses.as_slice(), &[5]);
+    }
+}
@@ -1,26 +1,31 @@
 use crate::{
-    single_register_floats, single_register_int_builtins, single_register_integers, Backend, Env,
-    Relocation,
+    pointer_layouts, single_register_floats, single_register_int_builtins,
+    single_register_integers, Backend, Env, HigherOrderCaller, Relocation,
 };
 use bumpalo::collections::Vec;
 use roc_builtins::bitcode::{self, FloatWidth, IntWidth};
 use roc_collections::all::MutMap;
 use roc_error_macros::internal_error;
 use roc_module::symbol::{Interns, Symbol};
-use roc_mono::code_gen_help::CodeGenHelp;
+use roc_mono::code_gen_help::{CodeGenHelp, HelperOp};
 use roc_mono::ir::{
-    BranchInfo, JoinPointId, ListLiteralElement, Literal, Param, ProcLayout, SelfRecursive, Stmt,
+    BranchInfo, HigherOrderLowLevel, JoinPointId, ListLiteralElement, Literal, Param, ProcLayout,
+    SelfRecursive, Stmt,
 };
-use roc_mono::layout::{Builtin, Layout, TagIdIntType, UnionLayout};
+use roc_mono::layout::{Builtin, Discriminant, Layout, LayoutIds, TagIdIntType, UnionLayout};
+use roc_mono::low_level::HigherOrder;
+use roc_region::all::Region;
 use roc_target::TargetInfo;
 use std::marker::PhantomData;
 
 pub(crate) mod aarch64;
 #[cfg(test)]
 mod disassembler_test_macro;
+pub(crate) mod liveness;
 pub(crate) mod storage;
 pub(crate) mod x86_64;
 
+use liveness::Liveness;
 use storage::StorageManager;
 
 const REFCOUNT_ONE: u64 = i64::MIN as u64;
@@ -145,6 +150,14 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
 
     fn call(buf: &mut Vec<'_, u8>, relocs: &mut Vec<'_, Relocation>, fn_name: String);
 
+    /// Loads the address of the function `fn_name` into `dst`.
+    fn function_pointer(
+        buf: &mut Vec<'_, u8>,
+        relocs: &mut Vec<'_, Relocation>,
+        fn_name: String,
+        dst: GeneralReg,
+    );
+
     /// Jumps by an offset of offset bytes unconditionally.
     /// It should always generate the same number of bytes to enable replacement if offset changes.
     /// It returns the base offset to calculate the jump from (generally the instruction after the jump).
@@ -196,6 +209,8 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
         offset: i32,
         src: GeneralReg,
     );
+    /// Stores the lowest byte of `src` at `dst + offset`.
+    fn mov_mem8_offset32_reg8(buf: &mut Vec<'_, u8>, dst: GeneralReg, offset: i32, src: GeneralReg);
 
     /// Sign extends the data at `offset` with `size` as it copies it to `dst`
     /// size must be less than or equal to 8.
@@ -203,6 +218,13 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
     /// Zero extends the data at `offset` with `size` as it copies it to `dst`
     /// size must be less than or equal to 8.
     fn movzx_reg64_base32(buf: &mut Vec<'_, u8>, dst: GeneralReg, offset: i32, size: u8);
+    /// Zero extends the byte at `src + offset` as it copies it to `dst`.
+    fn movzx_reg64_mem8_offset32(
+        buf: &mut Vec<'_, u8>,
+        dst: GeneralReg,
+        src: GeneralReg,
+        offset: i32,
+    );
 
     fn mov_freg64_stack32(buf: &mut Vec<'_, u8>, dst: FloatReg, offset: i32);
     fn mov_reg64_stack32(buf: &mut Vec<'_, u8>, dst: GeneralReg, offset: i32);
@@ -225,6 +247,13 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
         src2: GeneralReg,
     );
 
+    fn and_reg64_reg64_reg64(
+        buf: &mut Vec<'_, u8>,
+        dst: GeneralReg,
+        src1: GeneralReg,
+        src2: GeneralReg,
+    );
+
     fn eq_reg64_reg64_reg64(
         buf: &mut Vec<'_, u8>,
         dst: GeneralReg,
@@ -269,6 +298,8 @@ pub trait Assembler<GeneralReg: RegTrait, FloatReg: RegTrait>: Sized + Copy {
     );
 
     fn ret(buf: &mut Vec<'_, u8>);
+
+    fn trap(buf: &mut Vec<'_, u8>);
 }
 
 pub trait RegTrait:
@@ -292,6 +323,7 @@ pub struct Backend64Bit<
     interns: &'a mut Interns,
     helper_proc_gen: CodeGenHelp<'a>,
     helper_proc_symbols: Vec<'a, (Symbol, ProcLayout<'a>)>,
+    higher_order_callers: Vec<'a, (Symbol, String, HigherOrderCaller<'a>)>,
     buf: Vec<'a, u8>,
     relocs: Vec<'a, Relocation>,
     proc_name: Option<String>,
@@ -305,6 +337,10 @@ pub struct Backend64Bit<
     join_map: MutMap<JoinPointId, Vec<'a, (u64, u64)>>,
 
     storage_manager: StorageManager<'a, GeneralReg, FloatReg, ASM, CC>,
+
+    proc_region: Region,
+    symbol_regions: MutMap<Symbol, Region>,
+    source_locations: Vec<'a, (u64, Region)>,
 }
 
 /// new creates a new backend that will output to the specific Object.
@@ -326,6 +362,7 @@ pub fn new_backend_64bit<
         interns,
         helper_proc_gen: CodeGenHelp::new(env.arena, target_info, env.module_id),
         helper_proc_symbols: bumpalo::vec![in env.arena],
+        higher_order_callers: bumpalo::vec![in env.arena],
         proc_name: None,
         is_self_recursive: None,
         buf: bumpalo::vec![in env.arena],
@@ -336,6 +373,9 @@ pub fn new_backend_64bit<
         literal_map: MutMap::default(),
         join_map: MutMap::default(),
         storage_manager: storage::new_storage_manager(env, target_info),
+        proc_region: Region::zero(),
+        symbol_regions: MutMap::default(),
+        source_locations: bumpalo::vec![in env.arena],
     }
 }
 
@@ -365,6 +405,14 @@ impl<
     fn helper_proc_symbols(&self) -> &Vec<'a, (Symbol, ProcLayout<'a>)> {
         &self.helper_proc_symbols
     }
+    fn higher_order_callers_mut(
+        &mut self,
+    ) -> &mut Vec<'a, (Symbol, String, HigherOrderCaller<'a>)> {
+        &mut self.higher_order_callers
+    }
+    fn higher_order_callers(&self) -> &Vec<'a, (Symbol, String, HigherOrderCaller<'a>)> {
+        &self.higher_order_callers
+    }
 
     fn reset(&mut self, name: String, is_self_recursive: SelfRecursive) {
         self.proc_name = Some(name);
@@ -375,6 +423,9 @@ impl<
         self.free_map.clear();
         self.buf.clear();
         self.storage_manager.reset();
+        self.proc_region = Region::zero();
+        self.symbol_regions.clear();
+        self.source_locations.clear();
     }
 
     fn literal_map(&mut self) -> &mut MutMap<Symbol, (*const Literal<'a>, *const Layout<'a>)> {
@@ -397,6 +448,29 @@ impl<
         &mut self.free_map
     }
 
+    fn analyze_liveness(&mut self, args: &'a [(Layout<'a>, Symbol)], body: &Stmt<'a>) {
+        let liveness = Liveness::new(self.env.arena, args, body);
+        self.storage_manager.set_liveness(liveness);
+    }
+
+    fn enter_stmt(&mut self, stmt: &Stmt<'a>) {
+        self.storage_manager.enter_stmt(stmt);
+        if let Stmt::Let(sym, ..) = stmt {
+            if let Some(region) = self.symbol_regions.get(sym) {
+                self.source_locations.push((self.buf.len() as u64, *region));
+            }
+        }
+    }
+
+    fn load_source_regions(&mut self, proc_region: Region, symbol_regions: &'a [(Symbol, Region)]) {
+        self.proc_region = proc_region;
+        self.symbol_regions.extend(symbol_regions.iter().copied());
+    }
+
+    fn take_source_locations(&mut self) -> Vec<'a, (u64, Region)> {
+        std::mem::replace(&mut self.source_locations, bumpalo::vec![in self.env.arena])
+    }
+
     fn finalize(&mut self) -> (Vec<u8>, Vec<Relocation>) {
         let mut out = bumpalo::vec![in self.env.arena];
 
@@ -412,6 +486,19 @@ impl<
         );
         let setup_offset = out.len();
 
+        // Update source locations to include stack setup offset.
+        // The stack setup belongs to the start of the procedure.
+        let old_source_locations =
+            std::mem::replace(&mut self.source_locations, bumpalo::vec![in self.env.arena]);
+        if self.proc_region != Region::zero() {
+            self.source_locations.push((0, self.proc_region));
+        }
+        self.source_locations.extend(
+            old_source_locations
+                .into_iter()
+                .map(|(offset, region)| (offset + setup_offset as u64, region)),
+        );
+
         // Deal with jumps to the return address.
         let old_relocs = std::mem::replace(&mut self.relocs, bumpalo::vec![in self.env.arena]);
 
@@ -777,6 +864,22 @@ impl<
         }
     }
 
+    fn build_and(&mut self, dst: &Symbol, src1: &Symbol, src2: &Symbol, layout: &Layout<'a>) {
+        match layout {
+            single_register_integers!() => {
+                let dst_reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
+                let src1_reg = self
+                    .storage_manager
+                    .load_to_general_reg(&mut self.buf, src1);
+                let src2_reg = self
+                    .storage_manager
+                    .load_to_general_reg(&mut self.buf, src2);
+                ASM::and_reg64_reg64_reg64(&mut self.buf, dst_reg, src1_reg, src2_reg);
+            }
+            x => todo!("And: layout, {:?}", x),
+        }
+    }
+
     fn build_eq(&mut self, dst: &Symbol, src1: &Symbol, src2: &Symbol, arg_layout: &Layout<'a>) {
         match arg_layout {
             Layout::Builtin(single_register_int_builtins!()) => {
@@ -1091,7 +1194,272 @@ impl<
         self.free_symbol(&Symbol::DEV_TMP5);
     }
 
+    fn build_list_swap(
+        &mut self,
+        dst: &Symbol,
+        args: &'a [Symbol],
+        arg_layouts: &[Layout<'a>],
+        ret_layout: &Layout<'a>,
+    ) {
+        // The zig builtin also takes the alignment and element width, and an update mode.
+        let list_layout = arg_layouts[0];
+        let elem_layout = self.list_element_layout(&list_layout);
+        let u32_layout = Layout::Builtin(Builtin::Int(IntWidth::U32));
+        let u64_layout = Layout::Builtin(Builtin::Int(IntWidth::U64));
+        let u8_layout = Layout::Builtin(Builtin::Int(IntWidth::U8));
+
+        let target_info = self.storage_manager.target_info();
+        let alignment =
+            self.int_literal_arg(u32_layout, elem_layout.alignment_bytes(target_info) as i128);
+        let elem_width =
+            self.int_literal_arg(u64_layout, elem_layout.stack_size(target_info) as i128);
+        // Always Immutable, the dev backend does not do in place updates yet.
+        let update_mode = self.int_literal_arg(u8_layout, 0);
+
+        let lowlevel_args = [
+            args[0],
+            alignment,
+            elem_width,
+            args[1],
+            args[2],
+            update_mode,
+        ];
+        let lowlevel_arg_layouts = [
+            list_layout,
+            u32_layout,
+            u64_layout,
+            arg_layouts[1],
+            arg_layouts[2],
+            u8_layout,
+        ];
+
+        self.build_fn_call(
+            dst,
+            bitcode::LIST_SWAP.to_string(),
+            &lowlevel_args,
+            &lowlevel_arg_layouts,
+            ret_layout,
+        );
+        self.free_symbol(&alignment);
+        self.free_symbol(&elem_width);
+        self.free_symbol(&update_mode);
+    }
+
+    fn build_list_sublist(
+        &mut self,
+        dst: &Symbol,
+        args: &'a [Symbol],
+        arg_layouts: &[Layout<'a>],
+        ret_layout: &Layout<'a>,
+    ) {
+        // The zig builtin also takes the alignment and element width,
+        // and a function to decrement the elements that are dropped.
+        let list_layout = arg_layouts[0];
+        let elem_layout = self.list_element_layout(&list_layout);
+        let u32_layout = Layout::Builtin(Builtin::Int(IntWidth::U32));
+        let u64_layout = Layout::Builtin(Builtin::Int(IntWidth::U64));
+
+        let target_info = self.storage_manager.target_info();
+        let alignment =
+            self.int_literal_arg(u32_layout, elem_layout.alignment_bytes(target_info) as i128);
+        let elem_width =
+            self.int_literal_arg(u64_layout, elem_layout.stack_size(target_info) as i128);
+        let dec = self.dec_caller_arg(elem_layout);
+
+        let lowlevel_args = [args[0], alignment, elem_width, args[1], args[2], dec];
+        let lowlevel_arg_layouts = [
+            list_layout,
+            u32_layout,
+            u64_layout,
+            arg_layouts[1],
+            arg_layouts[2],
+            u64_layout,
+        ];
+
+        self.build_fn_call(
+            dst,
+            bitcode::LIST_SUBLIST.to_string(),
+            &lowlevel_args,
+            &lowlevel_arg_layouts,
+            ret_layout,
+        );
+        self.free_symbol(&alignment);
+        self.free_symbol(&elem_width);
+        self.free_symbol(&dec);
+    }
+
+    fn build_higher_order_lowlevel(
+        &mut self,
+        dst: &Symbol,
+        higher_order: &HigherOrderLowLevel<'a>,
+        ret_layout: &Layout<'a>,
+    ) {
+        let passed_function = &higher_order.passed_function;
+        let captured_environment = passed_function.captured_environment;
+        let target_info = self.storage_manager.target_info();
+        let u32_layout = Layout::Builtin(Builtin::Int(IntWidth::U32));
+        let u64_layout = Layout::Builtin(Builtin::Int(IntWidth::U64));
+
+        // Zig only sees the closure data as an opaque pointer.
+        // The passed function takes it by value as its last argument, if there is any.
+        let closure_data = match self.layout_map.get(&captured_environment).copied() {
+            Some(Layout::LambdaSet(lambda_set)) => lambda_set.is_represented(),
+            Some(Layout::Struct {
+                field_layouts: &[], ..
+            }) => None,
+            x => internal_error!("Closure data has an invalid layout: {:?}", x),
+        };
+
+        let lists = match higher_order.op {
+            HigherOrder::ListMap { xs } | HigherOrder::ListSortWith { xs } => {
+                bumpalo::vec![in self.env.arena; xs]
+            }
+            HigherOrder::ListMap2 { xs, ys } => bumpalo::vec![in self.env.arena; xs, ys],
+            HigherOrder::ListMap3 { xs, ys, zs } => {
+                bumpalo::vec![in self.env.arena; xs, ys, zs]
+            }
+            HigherOrder::ListMap4 { xs, ys, zs, ws } => {
+                bumpalo::vec![in self.env.arena; xs, ys, zs, ws]
+            }
+        };
+        let mut list_layouts = bumpalo::vec![in self.env.arena];
+        let mut elem_layouts = bumpalo::vec![in self.env.arena];
+        for list in lists.iter() {
+            let list_layout = *self.layout_map.get(list).unwrap();
+            elem_layouts.push(self.list_element_layout(&list_layout));
+            list_layouts.push(list_layout);
+        }
+
+        let function_sym = passed_function.name.name();
+        let layout_id = LayoutIds::default().get(function_sym, &passed_function.return_layout);
+        let function = self.symbol_to_string(function_sym, layout_id);
+        let n_args = passed_function.argument_layouts.len() - closure_data.iter().count();
+
+        let (caller, alignment_layout) = match higher_order.op {
+            HigherOrder::ListSortWith { .. } => (
+                HigherOrderCaller::Compare {
+                    function,
+                    closure_data,
+                    elem_layout: elem_layouts[0],
+                    ret_layout: passed_function.return_layout,
+                },
+                elem_layouts[0],
+            ),
+            _ => (
+                HigherOrderCaller::Map {
+                    function,
+                    closure_data,
+                    arg_layouts: &passed_function.argument_layouts[..n_args],
+                    ret_layout: passed_function.return_layout,
+                },
+                passed_function.return_layout,
+            ),
+        };
+        let caller_name = self.register_higher_order_caller(caller);
+        let caller = self.create_symbol("#caller");
+        self.load_fn_ptr(&caller, caller_name);
+
+        let data = self.create_symbol("#data");
+        match closure_data {
+            Some(_) => self.build_stack_address(&data, &captured_environment),
+            None => self.load_literal(&data, &u64_layout, &Literal::Int(0i128.to_ne_bytes())),
+        }
+
+        let closure_data_layout = closure_data.unwrap_or(Layout::UNIT);
+        let inc = self.refcount_proc_name(closure_data_layout, HelperOp::Inc);
+        let inc_n_name = self.register_higher_order_caller(HigherOrderCaller::IncN {
+            layout: closure_data_layout,
+            inc,
+        });
+        let inc_n_data = self.create_symbol("#inc_n_data");
+        self.load_fn_ptr(&inc_n_data, inc_n_name);
+
+        let data_is_owned = self.create_symbol("#data_is_owned");
+        self.load_literal(
+            &data_is_owned,
+            &Layout::Builtin(Builtin::Bool),
+            &Literal::Bool(passed_function.owns_captured_environment),
+        );
+
+        let alignment = self.int_literal_arg(
+            u32_layout,
+            alignment_layout.alignment_bytes(target_info) as i128,
+        );
+
+        let mut lowlevel_args = bumpalo::vec![in self.env.arena];
+        let mut lowlevel_arg_layouts = bumpalo::vec![in self.env.arena];
+        lowlevel_args.extend(lists.iter().copied());
+        lowlevel_arg_layouts.extend(list_layouts.iter().copied());
+        lowlevel_args.extend([caller, data, inc_n_data, data_is_owned, alignment]);
+        lowlevel_arg_layouts.extend([
+            u64_layout,
+            u64_layout,
+            u64_layout,
+            Layout::Builtin(Builtin::Bool),
+            u32_layout,
+        ]);
+
+        // The element widths of each list, followed by the width of the output for maps.
+        let mut literal_args = bumpalo::vec![in self.env.arena];
+        for elem_layout in elem_layouts.iter() {
+            let width =
+                self.int_literal_arg(u64_layout, elem_layout.stack_size(target_info) as i128);
+            literal_args.push(width);
+        }
+        if !matches!(higher_order.op, HigherOrder::ListSortWith { .. }) {
+            let width = self.int_literal_arg(
+                u64_layout,
+                passed_function.return_layout.stack_size(target_info) as i128,
+            );
+            literal_args.push(width);
+        }
+
+        // When mapping multiple lists, the extra elements of the longer lists are decremented.
+        if lists.len() > 1 {
+            for elem_layout in elem_layouts.iter() {
+                let dec = self.dec_caller_arg(*elem_layout);
+                literal_args.push(dec);
+            }
+        }
+
+        lowlevel_args.extend(literal_args.iter().copied());
+        lowlevel_arg_layouts.extend(literal_args.iter().map(|_| u64_layout));
+
+        let fn_name = match higher_order.op {
+            HigherOrder::ListMap { .. } => bitcode::LIST_MAP,
+            HigherOrder::ListMap2 { .. } => bitcode::LIST_MAP2,
+            HigherOrder::ListMap3 { .. } => bitcode::LIST_MAP3,
+            HigherOrder::ListMap4 { .. } => bitcode::LIST_MAP4,
+            HigherOrder::ListSortWith { .. } => bitcode::LIST_SORT_WITH,
+        };
+
+        self.build_fn_call(
+            dst,
+            fn_name.to_string(),
+            &lowlevel_args,
+            &lowlevel_arg_layouts,
+            ret_layout,
+        );
+
+        for sym in [caller, data, inc_n_data, data_is_owned, alignment] {
+            self.free_symbol(&sym);
+        }
+        for sym in literal_args.iter() {
+            self.free_symbol(sym);
+        }
+    }
+
     fn build_ptr_cast(&mut self, dst: &Symbol, src: &Symbol) {
+        if !self.storage_manager.is_stored_primitive(src) {
+            return self.build_stack_address(dst, src);
+        }
+
+        let dst_reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
+        let src_reg = self.storage_manager.load_to_general_reg(&mut self.buf, src);
+        ASM::mov_reg64_reg64(&mut self.buf, dst_reg, src_reg);
+    }
+
+    fn build_stack_address(&mut self, dst: &Symbol, src: &Symbol) {
         let dst_reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
         self.storage_manager
             .ensure_symbol_on_stack(&mut self.buf, src);
@@ -1099,6 +1467,21 @@ impl<
         ASM::add_reg64_reg64_imm32(&mut self.buf, dst_reg, CC::BASE_PTR_REG, offset);
     }
 
+    fn load_fn_ptr(&mut self, dst: &Symbol, fn_name: String) {
+        let dst_reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
+        ASM::function_pointer(&mut self.buf, &mut self.relocs, fn_name, dst_reg);
+    }
+
+    fn load_from_ptr(&mut self, dst: &Symbol, ptr: &Symbol, layout: &Layout<'a>) {
+        self.storage_manager
+            .load_from_pointer(&mut self.buf, dst, ptr, 0, layout);
+    }
+
+    fn store_to_ptr(&mut self, ptr: &Symbol, src: &Symbol, layout: &Layout<'a>) {
+        self.storage_manager
+            .copy_symbol_to_pointer(&mut self.buf, ptr, 0, src, layout);
+    }
+
     fn create_empty_array(&mut self, sym: &Symbol) {
         let base_offset = self.storage_manager.claim_stack_area(sym, 24);
         self.storage_manager
@@ -1251,7 +1634,7 @@ impl<
         union_layout: &UnionLayout<'a>,
     ) {
         match union_layout {
-            UnionLayout::NonRecursive(tag_layouts) | UnionLayout::Recursive(tag_layouts) => {
+            UnionLayout::NonRecursive(tag_layouts) => {
                 self.storage_manager.load_field_at_index(
                     sym,
                     structure,
@@ -1259,13 +1642,136 @@ impl<
                     tag_layouts[tag_id as usize],
                 );
             }
-            x => todo!("loading from union type: {:?}", x),
+            _ => {
+                let target_info = self.storage_manager.target_info();
+                let field_layouts = heap_union_field_layouts(union_layout, tag_id);
+                let offset: u32 = field_layouts[..index as usize]
+                    .iter()
+                    .map(|layout| layout.stack_size(target_info))
+                    .sum();
+
+                let data = self.create_symbol("#union_data");
+                self.load_union_data_pointer(&data, structure, union_layout);
+                self.storage_manager.load_from_pointer(
+                    &mut self.buf,
+                    sym,
+                    &data,
+                    offset as i32,
+                    &field_layouts[index as usize],
+                );
+                self.free_symbol(&data);
+            }
         }
     }
 
+    fn set_union_at_index(
+        &mut self,
+        sym: &Symbol,
+        structure: &Symbol,
+        tag_id: TagIdIntType,
+        index: u64,
+        union_layout: &UnionLayout<'a>,
+        value: &Symbol,
+    ) {
+        let target_info = self.storage_manager.target_info();
+        let field_layouts = heap_union_field_layouts(union_layout, tag_id);
+        let offset: u32 = field_layouts[..index as usize]
+            .iter()
+            .map(|layout| layout.stack_size(target_info))
+            .sum();
+
+        let data = self.create_symbol("#union_data");
+        self.load_union_data_pointer(&data, structure, union_layout);
+        self.storage_manager.copy_symbol_to_pointer(
+            &mut self.buf,
+            &data,
+            offset as i32,
+            value,
+            &field_layouts[index as usize],
+        );
+        self.free_symbol(&data);
+
+        self.create_struct(sym, &Layout::UNIT, &[]);
+    }
+
     fn get_tag_id(&mut self, sym: &Symbol, structure: &Symbol, union_layout: &UnionLayout<'a>) {
-        self.storage_manager
-            .load_union_tag_id(&mut self.buf, sym, structure, union_layout);
+        let target_info = self.storage_manager.target_info();
+        match union_layout {
+            UnionLayout::NonRecursive(_) => {
+                self.storage_manager
+                    .load_union_tag_id(&mut self.buf, sym, structure, union_layout);
+            }
+            UnionLayout::NonNullableUnwrapped(_) => {
+                let reg = self.storage_manager.claim_general_reg(&mut self.buf, sym);
+                ASM::mov_reg64_imm64(&mut self.buf, reg, 0);
+            }
+            UnionLayout::NullableUnwrapped { nullable_id, .. } => {
+                // The tag is the nullable one exactly when the pointer is null.
+                let ptr_reg = self
+                    .storage_manager
+                    .load_to_general_reg(&mut self.buf, structure);
+                let reg = self.storage_manager.claim_general_reg(&mut self.buf, sym);
+                let nullable_id = *nullable_id;
+                self.storage_manager.with_tmp_general_reg(
+                    &mut self.buf,
+                    |_storage_manager, buf, zero_reg| {
+                        ASM::mov_reg64_imm64(buf, zero_reg, 0);
+                        if nullable_id {
+                            ASM::eq_reg64_reg64_reg64(buf, reg, ptr_reg, zero_reg);
+                        } else {
+                            ASM::neq_reg64_reg64_reg64(buf, reg, ptr_reg, zero_reg);
+                        }
+                    },
+                );
+            }
+            _ if union_layout.stores_tag_id_in_pointer(target_info) => {
+                let (_, mask) = UnionLayout::tag_id_pointer_bits_and_mask(target_info);
+                let nullable_id = match union_layout {
+                    UnionLayout::NullableWrapped { nullable_id, .. } => *nullable_id,
+                    _ => 0,
+                };
+                let ptr_reg = self
+                    .storage_manager
+                    .load_to_general_reg(&mut self.buf, structure);
+                let reg = self.storage_manager.claim_general_reg(&mut self.buf, sym);
+                self.storage_manager.with_tmp_general_reg(
+                    &mut self.buf,
+                    |storage_manager, buf, tmp_reg| {
+                        ASM::mov_reg64_imm64(buf, tmp_reg, mask as i64);
+                        ASM::and_reg64_reg64_reg64(buf, reg, ptr_reg, tmp_reg);
+                        if nullable_id != 0 {
+                            // A null pointer has no tag bits set, so add the nullable id to it.
+                            storage_manager.with_tmp_general_reg(buf, |_, buf, is_null_reg| {
+                                ASM::mov_reg64_imm64(buf, tmp_reg, 0);
+                                ASM::eq_reg64_reg64_reg64(buf, is_null_reg, ptr_reg, tmp_reg);
+                                ASM::mov_reg64_imm64(buf, tmp_reg, nullable_id as i64);
+                                ASM::imul_reg64_reg64_reg64(buf, is_null_reg, is_null_reg, tmp_reg);
+                                ASM::add_reg64_reg64_reg64(buf, reg, reg, is_null_reg);
+                            });
+                        }
+                    },
+                );
+            }
+            UnionLayout::Recursive(_) => {
+                let id_offset = union_layout.tag_id_offset(target_info).unwrap();
+                match union_layout.discriminant() {
+                    Discriminant::U0 | Discriminant::U1 | Discriminant::U8 => {
+                        let ptr_reg = self
+                            .storage_manager
+                            .load_to_general_reg(&mut self.buf, structure);
+                        let reg = self.storage_manager.claim_general_reg(&mut self.buf, sym);
+                        ASM::movzx_reg64_mem8_offset32(
+                            &mut self.buf,
+                            reg,
+                            ptr_reg,
+                            id_offset as i32,
+                        );
+                    }
+                    Discriminant::U16 => todo!("loading 16 bit tag ids"),
+                }
+            }
+            x => todo!("getting tag id of union with layout ({:?})", x),
+        }
     }
 
     fn tag(
@@ -1274,9 +1780,87 @@ impl<
         fields: &'a [Symbol],
         union_layout: &UnionLayout<'a>,
         tag_id: TagIdIntType,
+        reuse: Option<Symbol>,
     ) {
+        let target_info = self.storage_manager.target_info();
+        if let UnionLayout::NonRecursive(_) = union_layout {
+            return self.storage_manager.create_union(
+                &mut self.buf,
+                sym,
+                union_layout,
+                fields,
+                tag_id,
+            );
+        }
+
+        if union_layout.tag_is_null(tag_id) {
+            let reg = self.storage_manager.claim_general_reg(&mut self.buf, sym);
+            ASM::mov_reg64_imm64(&mut self.buf, reg, 0);
+            return;
+        }
+
+        let (data_size, data_alignment) = union_layout.data_size_and_alignment(target_info);
+        let data = self.create_symbol("#union_data");
+        match reuse {
+            Some(reused) => self.reuse_or_allocate(&data, &reused, data_size, data_alignment),
+            None => self.allocate_with_refcount(&data, data_size, data_alignment),
+        }
+
+        let field_layouts = heap_union_field_layouts(union_layout, tag_id);
+        let mut offset = 0;
+        for (field, field_layout) in fields.iter().zip(field_layouts.iter()) {
+            self.storage_manager.copy_symbol_to_pointer(
+                &mut self.buf,
+                &data,
+                offset,
+                field,
+                field_layout,
+            );
+            offset += field_layout.stack_size(target_info) as i32;
+        }
+
+        if union_layout.stores_tag_id_as_data(target_info) {
+            let id_offset = union_layout.tag_id_offset(target_info).unwrap();
+            let data_reg = self
+                .storage_manager
+                .load_to_general_reg(&mut self.buf, &data);
+            match union_layout.discriminant() {
+                Discriminant::U0 | Discriminant::U1 | Discriminant::U8 => {
+                    self.storage_manager.with_tmp_general_reg(
+                        &mut self.buf,
+                        |_storage_manager, buf, reg| {
+                            ASM::mov_reg64_imm64(buf, reg, tag_id as i64);
+                            ASM::mov_mem8_offset32_reg8(buf, data_reg, id_offset as i32, reg);
+                        },
+                    );
+                }
+                Discriminant::U16 => todo!("storing 16 bit tag ids"),
+            }
+        }
+
+        let data_reg = self
+            .storage_manager
+            .load_to_general_reg(&mut self.buf, &data);
+        let reg = self.storage_manager.claim_general_reg(&mut self.buf, sym);
+        if union_layout.stores_tag_id_in_pointer(target_info) {
+            ASM::add_reg64_reg64_imm32(&mut self.buf, reg, data_reg, tag_id as i32);
+        } else {
+            ASM::mov_reg64_reg64(&mut self.buf, reg, data_reg);
+        }
+        self.free_symbol(&data);
+    }
+
+    fn expr_box(&mut self, sym: &Symbol, value: &Symbol, inner_layout: &Layout<'a>) {
+        let (size, alignment) =
+            inner_layout.stack_size_and_alignment(self.storage_manager.target_info());
+        self.allocate_with_refcount(sym, size, alignment);
         self.storage_manager
-            .create_union(&mut self.buf, sym, union_layout, fields, tag_id)
+            .copy_symbol_to_pointer(&mut self.buf, sym, 0, value, inner_layout);
+    }
+
+    fn expr_unbox(&mut self, sym: &Symbol, ptr: &Symbol, inner_layout: &Layout<'a>) {
+        self.storage_manager
+            .load_from_pointer(&mut self.buf, sym, ptr, 0, inner_layout);
     }
 
     fn load_literal(&mut self, sym: &Symbol, layout: &Layout<'a>, lit: &Literal<'a>) {
@@ -1350,6 +1934,10 @@ impl<
         self.storage_manager.free_symbol(sym);
     }
 
+    fn trap(&mut self) {
+        ASM::trap(&mut self.buf);
+    }
+
     fn return_symbol(&mut self, sym: &Symbol, layout: &Layout<'a>) {
         if self.storage_manager.is_stored_primitive(sym) {
             // Just load it to the correct type of reg as a stand alone value.
@@ -1395,6 +1983,140 @@ impl<
         CC: CallConv<GeneralReg, FloatReg, ASM>,
     > Backend64Bit<'a, GeneralReg, FloatReg, ASM, CC>
 {
+    /// Loads an integer into a new symbol, for passing extra arguments to zig builtins.
+    fn int_literal_arg(&mut self, layout: Layout<'a>, value: i128) -> Symbol {
+        let sym = self.create_symbol("#literal_arg");
+        self.load_literal(&sym, &layout, &Literal::Int(value.to_ne_bytes()));
+        sym
+    }
+
+    /// Loads a pointer to a function that decrements values of `layout` into a new symbol,
+    /// for zig builtins that drop list elements.
+    fn dec_caller_arg(&mut self, layout: Layout<'a>) -> Symbol {
+        let dec = self.refcount_proc_name(layout, HelperOp::Dec);
+        let dec_caller_name =
+            self.register_higher_order_caller(HigherOrderCaller::Dec { layout, dec });
+        let sym = self.create_symbol("#dec");
+        self.load_fn_ptr(&sym, dec_caller_name);
+        sym
+    }
+
+    /// Loads a pointer to new refcounted memory for `data_bytes` of data into `dst`.
+    fn allocate_with_refcount(&mut self, dst: &Symbol, data_bytes: u32, alignment: u32) {
+        let usize_layout = Layout::usize(self.storage_manager.target_info());
+        let data_bytes = self.int_literal_arg(usize_layout, data_bytes as i128);
+        let alignment = self.int_literal_arg(Layout::u32(), alignment as i128);
+
+        self.build_fn_call(
+            dst,
+            bitcode::UTILS_ALLOCATE_WITH_REFCOUNT.to_string(),
+            &[data_bytes, alignment],
+            &[usize_layout, Layout::u32()],
+            &usize_layout,
+        );
+        self.free_symbol(&data_bytes);
+        self.free_symbol(&alignment);
+    }
+
+    /// Loads a pointer to data into `dst`, reusing the allocation of `reused` if the reset
+    /// that produced it succeeded and allocating new memory otherwise.
+    /// A successful reset holds the address of the refcount, which directly precedes the data.
+    fn reuse_or_allocate(
+        &mut self,
+        dst: &Symbol,
+        reused: &Symbol,
+        data_bytes: u32,
+        alignment: u32,
+    ) {
+        // Both paths must leave every symbol in the same place, so start from a clean slate.
+        self.storage_manager.free_all_to_stack(&mut self.buf);
+        let dst_offset = self.storage_manager.claim_primitive_stack_slot(dst);
+        let (reused_offset, _) = self.storage_manager.stack_offset_and_size(reused);
+        let ptr_width = self.storage_manager.target_info().ptr_width() as i32;
+
+        // Jump to the reuse path if the reset succeeded.
+        // Since we don't know the offset yet, set it to 0 and overwrite later.
+        let mut jne_location = 0;
+        let mut start_offset = 0;
+        let mut cond_reg = None;
+        self.storage_manager
+            .with_tmp_general_reg(&mut self.buf, |_storage_manager, buf, reg| {
+                ASM::mov_reg64_base32(buf, reg, reused_offset);
+                jne_location = buf.len();
+                start_offset = ASM::jne_reg64_imm64_imm32(buf, reg, 0, 0);
+                cond_reg = Some(reg);
+            });
+
+        let allocation = self.create_symbol("#allocation");
+        self.allocate_with_refcount(&allocation, data_bytes, alignment);
+        let allocation_reg = self
+            .storage_manager
+            .load_to_general_reg(&mut self.buf, &allocation);
+        ASM::mov_base32_reg64(&mut self.buf, dst_offset, allocation_reg);
+        self.free_symbol(&allocation);
+        self.storage_manager.free_all_to_stack(&mut self.buf);
+
+        let jmp_location = self.buf.len();
+        let jmp_offset = ASM::jmp_imm32(&mut self.buf, 0x1234_5678);
+
+        // Overwrite the original jne with the correct offset.
+        let mut tmp = bumpalo::vec![in self.env.arena];
+        let jne_offset = self.buf.len() - start_offset;
+        ASM::jne_reg64_imm64_imm32(&mut tmp, cond_reg.unwrap(), 0, jne_offset as i32);
+        for (i, byte) in tmp.iter().enumerate() {
+            self.buf[jne_location + i] = *byte;
+        }
+
+        self.storage_manager
+            .with_tmp_general_reg(&mut self.buf, |_storage_manager, buf, reg| {
+                ASM::mov_reg64_base32(buf, reg, reused_offset);
+                ASM::add_reg64_reg64_imm32(buf, reg, reg, ptr_width);
+                ASM::mov_base32_reg64(buf, dst_offset, reg);
+            });
+
+        let end_offset = self.buf.len();
+        self.update_jmp_imm32_offset(
+            &mut tmp,
+            jmp_location as u64,
+            jmp_offset as u64,
+            end_offset as u64,
+        );
+    }
+
+    /// Loads the pointer to the data of a heap allocated union into `dst`,
+    /// clearing any tag id bits stored in the pointer.
+    fn load_union_data_pointer(
+        &mut self,
+        dst: &Symbol,
+        structure: &Symbol,
+        union_layout: &UnionLayout<'a>,
+    ) {
+        let target_info = self.storage_manager.target_info();
+        let ptr_reg = self
+            .storage_manager
+            .load_to_general_reg(&mut self.buf, structure);
+        let reg = self.storage_manager.claim_general_reg(&mut self.buf, dst);
+        if union_layout.stores_tag_id_in_pointer(target_info) {
+            let (_, mask) = UnionLayout::tag_id_pointer_bits_and_mask(target_info);
+            self.storage_manager.with_tmp_general_reg(
+                &mut self.buf,
+                |_storage_manager, buf, tmp_reg| {
+                    ASM::mov_reg64_imm64(buf, tmp_reg, !(mask as i64));
+                    ASM::and_reg64_reg64_reg64(buf, reg, ptr_reg, tmp_reg);
+                },
+            );
+        } else {
+            ASM::mov_reg64_reg64(&mut self.buf, reg, ptr_reg);
+        }
+    }
+
+    fn list_element_layout(&self, list_layout: &Layout<'a>) -> Layout<'a> {
+        match list_layout {
+            Layout::Builtin(Builtin::List(elem_layout)) => **elem_layout,
+            x => internal_error!("Expected a list layout, found: {:?}", x),
+        }
+    }
+
     /// Updates a jump instruction to a new offset and returns the number of bytes written.
     fn update_jmp_imm32_offset(
         &mut self,
@@ -1412,6 +2134,29 @@ impl<
     }
 }
 
+/// The field layouts of `tag_id` in a union that is stored on the heap.
+fn heap_union_field_layouts<'a>(
+    union_layout: &UnionLayout<'a>,
+    tag_id: TagIdIntType,
+) -> &'a [Layout<'a>] {
+    match union_layout {
+        UnionLayout::NonRecursive(tags) | UnionLayout::Recursive(tags) => tags[tag_id as usize],
+        UnionLayout::NonNullableUnwrapped(fields) => *fields,
+        UnionLayout::NullableWrapped {
+            nullable_id,
+            other_tags,
+        } => {
+            let index = if tag_id > *nullable_id {
+                tag_id - 1
+            } else {
+                tag_id
+            };
+            other_tags[index as usize]
+        }
+        UnionLayout::NullableUnwrapped { other_fields, .. } => *other_fields,
+    }
+}
+
 #[macro_export]
 macro_rules! sign_extended_int_builtins {
     () => {
@@ -1442,10 +2187,25 @@ macro_rules! single_register_int_builtins {
     };
 }
 
+/// Layouts that are represented by a pointer to heap allocated data.
+#[macro_export]
+macro_rules! pointer_layouts {
+    () => {
+        Layout::RecursivePointer
+            | Layout::Boxed(_)
+            | Layout::Union(
+                UnionLayout::Recursive(_)
+                    | UnionLayout::NonNullableUnwrapped(_)
+                    | UnionLayout::NullableWrapped { .. }
+                    | UnionLayout::NullableUnwrapped { .. },
+            )
+    };
+}
+
 #[macro_export]
 macro_rules! single_register_integers {
     () => {
-        Layout::Builtin(Builtin::Bool | single_register_int_builtins!()) | Layout::RecursivePointer
+        Layout::Builtin(Builtin::Bool | single_register_int_builtins!()) | pointer_layouts!()
     };
 }
 
@@ -1,7 +1,7 @@
 use crate::{
-    generic64::{Assembler, CallConv, RegTrait},
-    sign_extended_int_builtins, single_register_floats, single_register_int_builtins,
-    single_register_integers, single_register_layouts, Env,
+    generic64::{liveness::Liveness, Assembler, CallConv, RegTrait},
+    pointer_layouts, sign_extended_int_builtins, single_register_floats,
+    single_register_int_builtins, single_register_integers, single_register_layouts, Env,
 };
 use bumpalo::collections::Vec;
 use roc_builtins::bitcode::{FloatWidth, IntWidth};
@@ -9,7 +9,7 @@ use roc_collections::all::{MutMap, MutSet};
 use roc_error_macros::internal_error;
 use roc_module::symbol::Symbol;
 use roc_mono::{
-    ir::{JoinPointId, Param},
+    ir::{JoinPointId, Param, Stmt},
     layout::{Builtin, Layout, TagIdIntType, UnionLayout},
 };
 use roc_target::TargetInfo;
@@ -107,9 +107,8 @@ pub struct StorageManager<
     general_free_regs: Vec<'a, GeneralReg>,
     float_free_regs: Vec<'a, FloatReg>,
 
-    // The last major thing we need is a way to decide what reg to free when all of them are full.
-    // Theoretically we want a basic lru cache for the currently loaded symbols.
-    // For now just a vec of used registers and the symbols they contain.
+    // The used registers and the symbols they contain.
+    // When all registers are full, the liveness of these symbols decides which one gets freed.
     general_used_regs: Vec<'a, (GeneralReg, Symbol)>,
     float_used_regs: Vec<'a, (FloatReg, Symbol)>,
 
@@ -123,6 +122,12 @@ pub struct StorageManager<
 
     // The amount of extra stack space needed to pass args for function calling.
     fn_call_stack_size: u32,
+
+    // The live intervals of the symbols in the current procedure.
+    // This is shared between the copies of the storage manager made for each branch of a switch.
+    liveness: Option<Rc<Liveness<'a>>>,
+    // The position of the statement currently being built.
+    position: u32,
 }
 
 pub fn new_storage_manager<
@@ -152,6 +157,8 @@ pub fn new_storage_manager<
         free_stack_chunks: bumpalo::vec![in env.arena],
         stack_size: 0,
         fn_call_stack_size: 0,
+        liveness: None,
+        position: 0,
     }
 }
 
@@ -180,6 +187,61 @@ impl<
         self.free_stack_chunks.clear();
         self.stack_size = 0;
         self.fn_call_stack_size = 0;
+        self.liveness = None;
+        self.position = 0;
+    }
+
+    pub fn set_liveness(&mut self, liveness: Liveness<'a>) {
+        self.liveness = Some(Rc::new(liveness));
+        self.position = 0;
+    }
+
+    /// Moves the current position to the statement about to be built.
+    /// Statements generated while building, like expanded refcounting, keep the position of the statement they came from.
+    pub fn enter_stmt(&mut self, stmt: &Stmt<'a>) {
+        if let Some(position) = self
+            .liveness
+            .as_ref()
+            .and_then(|liveness| liveness.position(stmt))
+        {
+            self.position = position;
+        }
+    }
+
+    /// Returns true if the symbol has to survive a call to another function.
+    fn crosses_call(&self, sym: &Symbol) -> bool {
+        match &self.liveness {
+            Some(liveness) => liveness.crosses_call(sym),
+            None => false,
+        }
+    }
+
+    /// Returns the position of the next statement that needs the symbol.
+    fn next_use(&self, sym: &Symbol) -> u32 {
+        match &self.liveness {
+            Some(liveness) => liveness.next_use(sym, self.position),
+            None => self.position,
+        }
+    }
+
+    /// Picks which of the used registers to free when no register is free.
+    /// Like in linear scan, the symbol that is needed furthest in the future gets spilled.
+    /// On ties, prefer symbols that already have a copy on the stack and then the register that was used first.
+    fn spill_candidate<R: RegTrait>(&self, used_regs: &[(R, Symbol)]) -> usize {
+        let mut candidate = 0;
+        let mut candidate_key = (0, false);
+        for (i, (_, sym)) in used_regs.iter().enumerate() {
+            let on_stack = matches!(
+                self.symbol_storage_map.get(sym),
+                Some(Stack(Primitive { reg: Some(_), .. }))
+            );
+            let key = (self.next_use(sym), on_stack);
+            if i == 0 || key > candidate_key {
+                candidate = i;
+                candidate_key = key;
+            }
+        }
+        candidate
     }
 
     pub fn target_info(&self) -> TargetInfo {
@@ -215,15 +277,26 @@ impl<
     }
 
     /// Get a general register from the free list.
+    /// Symbols that live across a call prefer callee saved registers so they are not spilled by the call.
+    /// Everything else prefers caller saved registers so they don't need to be saved in the prologue.
     /// Will free data to the stack if necessary to get the register.
-    fn get_general_reg(&mut self, buf: &mut Vec<'a, u8>) -> GeneralReg {
-        if let Some(reg) = self.general_free_regs.pop() {
+    fn get_general_reg(&mut self, buf: &mut Vec<'a, u8>, callee_saved: bool) -> GeneralReg {
+        let preferred = self
+            .general_free_regs
+            .iter()
+            .rposition(|reg| CC::general_callee_saved(reg) == callee_saved);
+        let free_reg = match preferred {
+            Some(index) => Some(self.general_free_regs.remove(index)),
+            None => self.general_free_regs.pop(),
+        };
+        if let Some(reg) = free_reg {
             if CC::general_callee_saved(&reg) {
                 self.general_used_callee_saved_regs.insert(reg);
             }
             reg
         } else if !self.general_used_regs.is_empty() {
-            let (reg, sym) = self.general_used_regs.remove(0);
+            let index = self.spill_candidate(&self.general_used_regs);
+            let (reg, sym) = self.general_used_regs.remove(index);
             self.free_to_stack(buf, &sym, General(reg));
             reg
         } else {
@@ -232,15 +305,25 @@ impl<
     }
 
     /// Get a float register from the free list.
+    /// Uses the same preferences as `get_general_reg`.
     /// Will free data to the stack if necessary to get the register.
-    fn get_float_reg(&mut self, buf: &mut Vec<'a, u8>) -> FloatReg {
-        if let Some(reg) = self.float_free_regs.pop() {
+    fn get_float_reg(&mut self, buf: &mut Vec<'a, u8>, callee_saved: bool) -> FloatReg {
+        let preferred = self
+            .float_free_regs
+            .iter()
+            .rposition(|reg| CC::float_callee_saved(reg) == callee_saved);
+        let free_reg = match preferred {
+            Some(index) => Some(self.float_free_regs.remove(index)),
+            None => self.float_free_regs.pop(),
+        };
+        if let Some(reg) = free_reg {
             if CC::float_callee_saved(&reg) {
                 self.float_used_callee_saved_regs.insert(reg);
             }
             reg
         } else if !self.float_used_regs.is_empty() {
-            let (reg, sym) = self.float_used_regs.remove(0);
+            let index = self.spill_candidate(&self.float_used_regs);
+            let (reg, sym) = self.float_used_regs.remove(index);
             self.free_to_stack(buf, &sym, Float(reg));
             reg
         } else {
@@ -252,7 +335,7 @@ impl<
     /// They symbol should not already have storage.
     pub fn claim_general_reg(&mut self, buf: &mut Vec<'a, u8>, sym: &Symbol) -> GeneralReg {
         debug_assert_eq!(self.symbol_storage_map.get(sym), None);
-        let reg = self.get_general_reg(buf);
+        let reg = self.get_general_reg(buf, self.crosses_call(sym));
         self.general_used_regs.push((reg, *sym));
         self.symbol_storage_map.insert(*sym, Reg(General(reg)));
         reg
@@ -262,7 +345,7 @@ impl<
     /// They symbol should not already have storage.
     pub fn claim_float_reg(&mut self, buf: &mut Vec<'a, u8>, sym: &Symbol) -> FloatReg {
         debug_assert_eq!(self.symbol_storage_map.get(sym), None);
-        let reg = self.get_float_reg(buf);
+        let reg = self.get_float_reg(buf, self.crosses_call(sym));
         self.float_used_regs.push((reg, *sym));
         self.symbol_storage_map.insert(*sym, Reg(Float(reg)));
         reg
@@ -275,7 +358,7 @@ impl<
         buf: &mut Vec<'a, u8>,
         callback: F,
     ) {
-        let reg = self.get_general_reg(buf);
+        let reg = self.get_general_reg(buf, false);
         callback(self, buf, reg);
         self.general_free_regs.push(reg);
     }
@@ -288,7 +371,7 @@ impl<
         buf: &mut Vec<'a, u8>,
         callback: F,
     ) {
-        let reg = self.get_float_reg(buf);
+        let reg = self.get_float_reg(buf, false);
         callback(self, buf, reg);
         self.float_free_regs.push(reg);
     }
@@ -320,7 +403,7 @@ impl<
                 base_offset,
             }) => {
                 debug_assert_eq!(base_offset % 8, 0);
-                let reg = self.get_general_reg(buf);
+                let reg = self.get_general_reg(buf, self.crosses_call(sym));
                 ASM::mov_reg64_base32(buf, reg, base_offset);
                 self.general_used_regs.push((reg, *sym));
                 self.symbol_storage_map.insert(
@@ -337,7 +420,7 @@ impl<
                 size,
                 sign_extend,
             }) => {
-                let reg = self.get_general_reg(buf);
+                let reg = self.get_general_reg(buf, self.crosses_call(sym));
                 if sign_extend {
                     ASM::movsx_reg64_base32(buf, reg, base_offset, size as u8);
                 } else {
@@ -384,7 +467,7 @@ impl<
                 base_offset,
             }) => {
                 debug_assert_eq!(base_offset % 8, 0);
-                let reg = self.get_float_reg(buf);
+                let reg = self.get_float_reg(buf, self.crosses_call(sym));
                 ASM::mov_freg64_base32(buf, reg, base_offset);
                 self.float_used_regs.push((reg, *sym));
                 self.symbol_storage_map.insert(
@@ -400,7 +483,7 @@ impl<
                 base_offset, size, ..
             }) if base_offset % 8 == 0 && size == 8 => {
                 // The primitive is aligned and the data is exactly 8 bytes, treat it like regular stack.
-                let reg = self.get_float_reg(buf);
+                let reg = self.get_float_reg(buf, self.crosses_call(sym));
                 ASM::mov_freg64_base32(buf, reg, base_offset);
                 self.float_used_regs.push((reg, *sym));
                 self.symbol_storage_map.insert(*sym, Reg(Float(reg)));
@@ -723,7 +806,7 @@ impl<
         layout: &Layout<'a>,
     ) {
         match layout {
-            Layout::Builtin(Builtin::Int(IntWidth::I64 | IntWidth::U64)) => {
+            Layout::Builtin(Builtin::Int(IntWidth::I64 | IntWidth::U64)) | pointer_layouts!() => {
                 debug_assert_eq!(to_offset % 8, 0);
                 let reg = self.load_to_general_reg(buf, sym);
                 ASM::mov_base32_reg64(buf, to_offset, reg);
@@ -754,6 +837,100 @@ impl<
         }
     }
 
+    /// Loads a value of `layout` from `offset` bytes past the address held in `ptr` into `sym`.
+    /// Primitives are loaded into registers, everything else is copied onto the stack.
+    pub fn load_from_pointer(
+        &mut self,
+        buf: &mut Vec<'a, u8>,
+        sym: &Symbol,
+        ptr: &Symbol,
+        offset: i32,
+        layout: &Layout<'a>,
+    ) {
+        let size = layout.stack_size(self.target_info);
+        match layout {
+            _ if size == 0 => {
+                self.symbol_storage_map.insert(*sym, NoData);
+            }
+            single_register_integers!() if size == 8 => {
+                let ptr_reg = self.load_to_general_reg(buf, ptr);
+                let reg = self.claim_general_reg(buf, sym);
+                ASM::mov_reg64_mem64_offset32(buf, reg, ptr_reg, offset);
+            }
+            Layout::Builtin(Builtin::Bool | Builtin::Int(IntWidth::U8)) => {
+                let ptr_reg = self.load_to_general_reg(buf, ptr);
+                let reg = self.claim_general_reg(buf, sym);
+                ASM::movzx_reg64_mem8_offset32(buf, reg, ptr_reg, offset);
+            }
+            Layout::Builtin(Builtin::Float(FloatWidth::F64)) => {
+                let ptr_reg = self.load_to_general_reg(buf, ptr);
+                let base_offset = self.claim_stack_size(8);
+                self.with_tmp_general_reg(buf, |_storage_manager, buf, tmp_reg| {
+                    ASM::mov_reg64_mem64_offset32(buf, tmp_reg, ptr_reg, offset);
+                    ASM::mov_base32_reg64(buf, base_offset, tmp_reg);
+                });
+                self.primitive_stack_arg(sym, base_offset);
+            }
+            _ if !is_primitive(layout) && size % 8 == 0 => {
+                let ptr_reg = self.load_to_general_reg(buf, ptr);
+                let base_offset = self.claim_stack_area(sym, size);
+                self.with_tmp_general_reg(buf, |_storage_manager, buf, tmp_reg| {
+                    for i in (0..size as i32).step_by(8) {
+                        ASM::mov_reg64_mem64_offset32(buf, tmp_reg, ptr_reg, offset + i);
+                        ASM::mov_base32_reg64(buf, base_offset + i, tmp_reg);
+                    }
+                });
+            }
+            x => todo!("loading data from a pointer with layout, {:?}", x),
+        }
+    }
+
+    /// Copies `sym` to `offset` bytes past the address held in `ptr`.
+    pub fn copy_symbol_to_pointer(
+        &mut self,
+        buf: &mut Vec<'a, u8>,
+        ptr: &Symbol,
+        offset: i32,
+        sym: &Symbol,
+        layout: &Layout<'a>,
+    ) {
+        let size = layout.stack_size(self.target_info);
+        match layout {
+            _ if size == 0 => {}
+            single_register_integers!() if size == 8 => {
+                let ptr_reg = self.load_to_general_reg(buf, ptr);
+                let reg = self.load_to_general_reg(buf, sym);
+                ASM::mov_mem64_offset32_reg64(buf, ptr_reg, offset, reg);
+            }
+            Layout::Builtin(Builtin::Bool | Builtin::Int(IntWidth::I8 | IntWidth::U8)) => {
+                let ptr_reg = self.load_to_general_reg(buf, ptr);
+                let reg = self.load_to_general_reg(buf, sym);
+                ASM::mov_mem8_offset32_reg8(buf, ptr_reg, offset, reg);
+            }
+            Layout::Builtin(Builtin::Float(FloatWidth::F64)) => {
+                self.ensure_symbol_on_stack(buf, sym);
+                let (base_offset, _) = self.stack_offset_and_size(sym);
+                let ptr_reg = self.load_to_general_reg(buf, ptr);
+                self.with_tmp_general_reg(buf, |_storage_manager, buf, tmp_reg| {
+                    ASM::mov_reg64_base32(buf, tmp_reg, base_offset);
+                    ASM::mov_mem64_offset32_reg64(buf, ptr_reg, offset, tmp_reg);
+                });
+            }
+            _ if !is_primitive(layout) && size % 8 == 0 => {
+                let (base_offset, stack_size) = self.stack_offset_and_size(sym);
+                debug_assert_eq!(stack_size, size);
+                let ptr_reg = self.load_to_general_reg(buf, ptr);
+                self.with_tmp_general_reg(buf, |_storage_manager, buf, tmp_reg| {
+                    for i in (0..size as i32).step_by(8) {
+                        ASM::mov_reg64_base32(buf, tmp_reg, base_offset + i);
+                        ASM::mov_mem64_offset32_reg64(buf, ptr_reg, offset + i, tmp_reg);
+                    }
+                });
+            }
+            x => todo!("copying data to a pointer with layout, {:?}", x),
+        }
+    }
+
     #[allow(dead_code)]
     /// Ensures that a register is free. If it is not free, data will be moved to make it free.
     fn ensure_reg_free(
@@ -946,6 +1123,14 @@ impl<
         self.allocation_map.insert(*sym, Rc::new((base_offset, 8)));
     }
 
+    /// Claims a stack slot for a primitive that will be written directly to the stack.
+    /// This is used for values that are set on multiple branches.
+    pub fn claim_primitive_stack_slot(&mut self, sym: &Symbol) -> i32 {
+        let base_offset = self.claim_stack_size(8);
+        self.primitive_stack_arg(sym, base_offset);
+        base_offset
+    }
+
     /// Specifies a complex is loaded at the specific base offset.
     pub fn complex_stack_arg(&mut self, sym: &Symbol, base_offset: i32, size: u32) {
         self.symbol_storage_map
@@ -1310,3 +1495,172 @@ impl<
 fn is_primitive(layout: &Layout<'_>) -> bool {
     matches!(layout, single_register_layouts!())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generic64::x86_64::{
+        X86_64Assembler, X86_64FloatReg, X86_64GeneralReg, X86_64SystemV,
+    };
+    use bumpalo::Bump;
+    use roc_module::ident::ForeignSymbol;
+    use roc_module::low_level::LowLevel;
+    use roc_module::symbol::{Interns, ModuleId};
+    use roc_mono::ir::{Call, CallType, Expr, Literal, UpdateModeId};
+
+    type X86_64StorageManager<'a> =
+        StorageManager<'a, X86_64GeneralReg, X86_64FloatReg, X86_64Assembler, X86_64SystemV>;
+
+    const I64: Layout<'static> = Layout::Builtin(Builtin::Int(IntWidth::I64));
+
+    fn sym(id: u32) -> Symbol {
+        Interns::from_index(ModuleId::ATTR, id)
+    }
+
+    fn int<'a>(value: i128) -> Expr<'a> {
+        Expr::Literal(Literal::Int(value.to_ne_bytes()))
+    }
+
+    fn num_add<'a>(arena: &'a Bump, args: [Symbol; 2]) -> Expr<'a> {
+        Expr::Call(Call {
+            call_type: CallType::LowLevel {
+                op: LowLevel::NumAdd,
+                update_mode: UpdateModeId::BACKEND_DUMMY,
+            },
+            arguments: arena.alloc(args),
+        })
+    }
+
+    /// Chains the definitions into a body that returns `ret`.
+    /// Returns the statements in order, so the first one is the whole body.
+    fn lets<'a>(
+        arena: &'a Bump,
+        defs: std::vec::Vec<(Symbol, Expr<'a>)>,
+        ret: Symbol,
+    ) -> std::vec::Vec<&'a Stmt<'a>> {
+        let mut stmts = vec![&*arena.alloc(Stmt::Ret(ret))];
+        for (sym, expr) in defs.into_iter().rev() {
+            let next = stmts[stmts.len() - 1];
+            stmts.push(arena.alloc(Stmt::Let(sym, expr, I64, next)));
+        }
+        stmts.reverse();
+        stmts
+    }
+
+    fn storage_manager<'a>(env: &'a Env<'a>, body: &Stmt<'a>) -> X86_64StorageManager<'a> {
+        let mut storage_manager = new_storage_manager(env, TargetInfo::default_x86_64());
+        storage_manager.reset();
+        storage_manager.set_liveness(Liveness::new(env.arena, &[], body));
+        storage_manager
+    }
+
+    fn with_env<F: FnOnce(&Env)>(f: F) {
+        let arena = Bump::new();
+        let sources = MutMap::default();
+        let env = Env {
+            arena: &arena,
+            module_id: ModuleId::ATTR,
+            exposed_to_host: MutSet::default(),
+            lazy_literals: false,
+            generate_allocators: false,
+            sources: &sources,
+        };
+        f(&env)
+    }
+
+    #[test]
+    fn symbols_crossing_calls_get_callee_saved_regs() {
+        with_env(|env| {
+            let arena = env.arena;
+            let (a, b, c, d) = (sym(1), sym(2), sym(3), sym(4));
+            let call = Expr::Call(Call {
+                call_type: CallType::Foreign {
+                    foreign_symbol: ForeignSymbol::from("f"),
+                    ret_layout: arena.alloc(I64),
+                },
+                arguments: arena.alloc([b]),
+            });
+
+            // a = 1
+            // b = 2
+            // c = f b
+            // d = a + c
+            // ret d
+            let stmts = lets(
+                arena,
+                vec![
+                    (a, int(1)),
+                    (b, int(2)),
+                    (c, call),
+                    (d, num_add(arena, [a, c])),
+                ],
+                d,
+            );
+            let mut storage_manager = storage_manager(env, stmts[0]);
+            let mut buf = bumpalo::vec![in arena];
+
+            storage_manager.enter_stmt(stmts[0]);
+            let a_reg = storage_manager.claim_general_reg(&mut buf, &a);
+            storage_manager.enter_stmt(stmts[1]);
+            let b_reg = storage_manager.claim_general_reg(&mut buf, &b);
+
+            // a is still needed after the call, b is only an argument to it.
+            assert!(X86_64SystemV::general_callee_saved(&a_reg));
+            assert!(!X86_64SystemV::general_callee_saved(&b_reg));
+            assert_eq!(
+                storage_manager.general_used_callee_saved_regs().as_slice(),
+                &[a_reg]
+            );
+        })
+    }
+
+    #[test]
+    fn spill_symbol_with_furthest_next_use() {
+        with_env(|env| {
+            let arena = env.arena;
+            let regs = X86_64SystemV::GENERAL_DEFAULT_FREE_REGS.len() as u32;
+            let syms: std::vec::Vec<Symbol> = (1..=regs + 1).map(sym).collect();
+            let first = syms[0];
+            let last = syms[regs as usize];
+
+            // Every symbol is defined, one after the other, and then used in order.
+            // The first one is used last, so once the registers run out it is the one to spill.
+            let mut defs: std::vec::Vec<_> = syms.iter().map(|s| (*s, int(1))).collect();
+            let mut uses: std::vec::Vec<_> = syms[1..].to_vec();
+            uses.push(first);
+            for (i, s) in uses.iter().enumerate() {
+                defs.push((sym(100 + i as u32), num_add(arena, [*s, *s])));
+            }
+            let ret = sym(100 + regs);
+            let stmts = lets(arena, defs, ret);
+
+            let mut storage_manager = storage_manager(env, stmts[0]);
+            let mut buf = bumpalo::vec![in arena];
+
+            for (stmt, s) in stmts.iter().zip(&syms[..regs as usize]) {
+                storage_manager.enter_stmt(stmt);
+                storage_manager.claim_general_reg(&mut buf, s);
+            }
+            assert!(buf.is_empty());
+
+            storage_manager.enter_stmt(stmts[regs as usize]);
+            let reg = storage_manager.claim_general_reg(&mut buf, &last);
+
+            assert!(!buf.is_empty());
+            assert!(matches!(
+                storage_manager.symbol_storage_map.get(&first),
+                Some(Stack(Primitive { reg: None, .. }))
+            ));
+            assert_eq!(
+                storage_manager.symbol_storage_map.get(&last),
+                Some(&Reg(General(reg)))
+            );
+            for s in &syms[1..regs as usize] {
+                assert!(matches!(
+                    storage_manager.symbol_storage_map.get(s),
+                    Some(Reg(_))
+                ));
+            }
+        })
+    }
+}
@@ -1,13 +1,13 @@
 use crate::generic64::{storage::StorageManager, Assembler, CallConv, RegTrait};
 use crate::{
-    single_register_floats, single_register_int_builtins, single_register_integers,
-    single_register_layouts, Relocation,
+    pointer_layouts, single_register_floats, single_register_int_builtins,
+    single_register_integers, single_register_layouts, Relocation,
 };
 use bumpalo::collections::Vec;
 use roc_builtins::bitcode::{FloatWidth, IntWidth};
 use roc_error_macros::internal_error;
 use roc_module::symbol::Symbol;
-use roc_mono::layout::{Builtin, Layout};
+use roc_mono::layout::{Builtin, Layout, UnionLayout};
 use roc_target::TargetInfo;
 
 const TARGET_INFO: TargetInfo = TargetInfo::default_x86_64();
@@ -998,6 +998,20 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         });
     }
 
+    #[inline(always)]
+    fn function_pointer(
+        buf: &mut Vec<'_, u8>,
+        relocs: &mut Vec<'_, Relocation>,
+        fn_name: String,
+        dst: X86_64GeneralReg,
+    ) {
+        lea_reg64_rip_offset32(buf, dst, 0);
+        relocs.push(Relocation::LinkedFunction {
+            offset: buf.len() as u64 - 4,
+            name: fn_name,
+        });
+    }
+
     #[inline(always)]
     fn imul_reg64_reg64_reg64(
         buf: &mut Vec<'_, u8>,
@@ -1111,6 +1125,15 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
     ) {
         mov_base64_offset32_reg64(buf, dst, offset, src)
     }
+    #[inline(always)]
+    fn mov_mem8_offset32_reg8(
+        buf: &mut Vec<'_, u8>,
+        dst: X86_64GeneralReg,
+        offset: i32,
+        src: X86_64GeneralReg,
+    ) {
+        mov_base8_offset32_reg8(buf, dst, offset, src)
+    }
 
     #[inline(always)]
     fn movsx_reg64_base32(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, offset: i32, size: u8) {
@@ -1142,6 +1165,15 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
             internal_error!("Invalid size for zero extension: {}", size);
         }
     }
+    #[inline(always)]
+    fn movzx_reg64_mem8_offset32(
+        buf: &mut Vec<'_, u8>,
+        dst: X86_64GeneralReg,
+        src: X86_64GeneralReg,
+        offset: i32,
+    ) {
+        movzx_reg64_base8_offset32(buf, dst, src, offset)
+    }
 
     #[inline(always)]
     fn mov_freg64_stack32(buf: &mut Vec<'_, u8>, dst: X86_64FloatReg, offset: i32) {
@@ -1187,6 +1219,23 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
         sub_reg64_reg64(buf, dst, src2);
     }
 
+    #[inline(always)]
+    fn and_reg64_reg64_reg64(
+        buf: &mut Vec<'_, u8>,
+        dst: X86_64GeneralReg,
+        src1: X86_64GeneralReg,
+        src2: X86_64GeneralReg,
+    ) {
+        if dst == src1 {
+            and_reg64_reg64(buf, dst, src2);
+        } else if dst == src2 {
+            and_reg64_reg64(buf, dst, src1);
+        } else {
+            mov_reg64_reg64(buf, dst, src1);
+            and_reg64_reg64(buf, dst, src2);
+        }
+    }
+
     #[inline(always)]
     fn eq_reg64_reg64_reg64(
         buf: &mut Vec<'_, u8>,
@@ -1266,6 +1315,11 @@ impl Assembler<X86_64GeneralReg, X86_64FloatReg> for X86_64Assembler {
     fn ret(buf: &mut Vec<'_, u8>) {
         ret(buf);
     }
+
+    #[inline(always)]
+    fn trap(buf: &mut Vec<'_, u8>) {
+        ud2(buf);
+    }
 }
 
 impl X86_64Assembler {
@@ -1416,6 +1470,12 @@ fn andpd_freg64_freg64(buf: &mut Vec<'_, u8>, dst: X86_64FloatReg, src: X86_64Fl
     }
 }
 
+/// `AND r/m64,r64` -> Bitwise logical and r64 to r/m64.
+#[inline(always)]
+fn and_reg64_reg64(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, src: X86_64GeneralReg) {
+    binop_reg64_reg64(0x21, buf, dst, src);
+}
+
 /// r/m64 AND imm8 (sign-extended).
 #[inline(always)]
 fn and_reg64_imm8(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, imm: i8) {
@@ -1482,6 +1542,16 @@ fn jne_imm32(buf: &mut Vec<'_, u8>, imm: i32) {
     buf.extend(&imm.to_le_bytes());
 }
 
+/// `LEA r64,m` -> Store the effective address of m in r64, where m references the instruction pointer + offset.
+#[inline(always)]
+fn lea_reg64_rip_offset32(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, offset: u32) {
+    let rex = add_reg_extension(dst, REX_W);
+    let dst_mod = dst as u8 % 8;
+    buf.reserve(7);
+    buf.extend(&[rex, 0x8D, 0x05 | (dst_mod << 3)]);
+    buf.extend(&offset.to_le_bytes());
+}
+
 /// `MOV r/m64, imm32` -> Move imm32 sign extended to 64-bits to r/m64.
 #[inline(always)]
 fn mov_reg64_imm32(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, imm: i32) {
@@ -1545,6 +1615,28 @@ fn mov_base64_offset32_reg64(
     buf.extend(&offset.to_le_bytes());
 }
 
+/// `MOV r/m8,r8` -> Move r8 to r/m8, where m8 references a base + offset.
+#[inline(always)]
+fn mov_base8_offset32_reg8(
+    buf: &mut Vec<'_, u8>,
+    base: X86_64GeneralReg,
+    offset: i32,
+    src: X86_64GeneralReg,
+) {
+    // The REX prefix is always needed so that SPL, BPL, SIL, and DIL can be used.
+    let rex = add_rm_extension(base, REX);
+    let rex = add_reg_extension(src, rex);
+    let src_mod = (src as u8 % 8) << 3;
+    let base_mod = base as u8 % 8;
+    buf.reserve(8);
+    buf.extend(&[rex, 0x88, 0x80 | src_mod | base_mod]);
+    // Using RSP or R12 requires a secondary index byte.
+    if base == X86_64GeneralReg::RSP || base == X86_64GeneralReg::R12 {
+        buf.push(0x24);
+    }
+    buf.extend(&offset.to_le_bytes());
+}
+
 /// `MOV r64,r/m64` -> Move r/m64 to r64, where m64 references a base + offset.
 #[inline(always)]
 fn mov_reg64_base64_offset32(
@@ -1853,6 +1945,12 @@ fn ret(buf: &mut Vec<'_, u8>) {
     buf.push(0xC3);
 }
 
+/// `UD2` -> Raise an invalid opcode exception.
+#[inline(always)]
+fn ud2(buf: &mut Vec<'_, u8>) {
+    buf.extend(&[0x0F, 0x0B]);
+}
+
 /// `SUB r/m64, imm32` -> Subtract imm32 sign-extended to 64-bits from r/m64.
 #[inline(always)]
 fn sub_reg64_imm32(buf: &mut Vec<'_, u8>, dst: X86_64GeneralReg, imm: i32) {
@@ -2006,6 +2104,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_and_reg64_reg64() {
+        disassembler_test!(
+            and_reg64_reg64,
+            |reg1, reg2| format!("and {}, {}", reg1, reg2),
+            ALL_GENERAL_REGS,
+            ALL_GENERAL_REGS
+        );
+    }
+
     #[test]
     fn test_sub_reg64_reg64() {
         disassembler_test!(
@@ -2106,6 +2214,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_lea_reg64_rip_offset32() {
+        disassembler_test!(
+            lea_reg64_rip_offset32,
+            |reg, imm| format!("lea {}, [rip + 0x{:x}]", reg, imm),
+            ALL_GENERAL_REGS,
+            [TEST_I32 as u32]
+        );
+    }
+
     #[test]
     fn test_mov_reg64_imm32() {
         disassembler_test!(
@@ -2186,6 +2304,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_mov_base8_offset32_reg8() {
+        disassembler_test!(
+            mov_base8_offset32_reg8,
+            |reg1, imm, reg2: X86_64GeneralReg| format!(
+                "mov byte ptr [{} + 0x{:x}], {}",
+                reg1,
+                imm,
+                reg2.low_8bits_string()
+            ),
+            ALL_GENERAL_REGS,
+            [TEST_I32],
+            ALL_GENERAL_REGS
+        );
+    }
+
     #[test]
     fn test_movzx_reg64_base8_offset32() {
         disassembler_test!(
@@ -2285,6 +2419,11 @@ mod tests {
         disassembler_test!(ret, || "ret");
     }
 
+    #[test]
+    fn test_ud2() {
+        disassembler_test!(ud2, || "ud2");
+    }
+
     #[test]
     fn test_sub_reg64_imm32() {
         disassembler_test!(
@@ -9,13 +9,16 @@ use roc_error_macros::internal_error;
 use roc_module::ident::ModuleName;
 use roc_module::low_level::{LowLevel, LowLevelWrapperType};
 use roc_module::symbol::{Interns, ModuleId, Symbol};
-use roc_mono::code_gen_help::CodeGenHelp;
+use roc_mono::code_gen_help::{CodeGenHelp, HelperOp};
 use roc_mono::ir::{
-    BranchInfo, CallType, Expr, JoinPointId, ListLiteralElement, Literal, Param, Proc, ProcLayout,
-    SelfRecursive, Stmt,
+    BranchInfo, CallType, Expr, HigherOrderLowLevel, JoinPointId, ListLiteralElement, Literal,
+    Param, Proc, ProcLayout, SelfRecursive, Stmt,
 };
 use roc_mono::layout::{Builtin, Layout, LayoutId, LayoutIds, TagIdIntType, UnionLayout};
+use roc_region::all::Region;
+use std::path::PathBuf;
 
+mod debug_info;
 mod generic64;
 mod object_builder;
 pub use object_builder::build_module;
@@ -27,6 +30,8 @@ pub struct Env<'a> {
     pub exposed_to_host: MutSet<Symbol>,
     pub lazy_literals: bool,
     pub generate_allocators: bool,
+    /// The source of every module, used to map the generated code back to it in the debug info.
+    pub sources: &'a MutMap<ModuleId, (PathBuf, Box<str>)>,
 }
 
 // These relocations likely will need a length.
@@ -55,6 +60,48 @@ pub enum Relocation {
     },
 }
 
+/// A function that a zig builtin calls back into, such as the function passed to `List.map`.
+/// Zig hands every value over by pointer, so these wrappers load the arguments,
+/// call the Roc function, and pass the result back.
+#[derive(Debug, Clone)]
+enum HigherOrderCaller<'a> {
+    /// `(closure_data*, arg_1*, ..., arg_n*, result*)`, used by `List.map` through `List.map4`.
+    Map {
+        function: String,
+        closure_data: Option<Layout<'a>>,
+        arg_layouts: &'a [Layout<'a>],
+        ret_layout: Layout<'a>,
+    },
+    /// `(closure_data*, a*, b*) -> Ordering`, used by `List.sortWith`.
+    Compare {
+        function: String,
+        closure_data: Option<Layout<'a>>,
+        elem_layout: Layout<'a>,
+        ret_layout: Layout<'a>,
+    },
+    /// `(value*, n)`, increments the refcount of a value `n` times.
+    IncN {
+        layout: Layout<'a>,
+        inc: Option<String>,
+    },
+    /// `(value*)`, decrements the refcount of a value.
+    Dec {
+        layout: Layout<'a>,
+        dec: Option<String>,
+    },
+}
+
+const ARG_SYMBOLS: [Symbol; 8] = [
+    Symbol::ARG_1,
+    Symbol::ARG_2,
+    Symbol::ARG_3,
+    Symbol::ARG_4,
+    Symbol::ARG_5,
+    Symbol::ARG_6,
+    Symbol::ARG_7,
+    Symbol::ARG_8,
+];
+
 trait Backend<'a> {
     fn env(&self) -> &Env<'a>;
     fn interns(&self) -> &Interns;
@@ -80,6 +127,20 @@ trait Backend<'a> {
 
     fn helper_proc_symbols(&self) -> &Vec<'a, (Symbol, ProcLayout<'a>)>;
 
+    fn higher_order_callers_mut(&mut self)
+        -> &mut Vec<'a, (Symbol, String, HigherOrderCaller<'a>)>;
+
+    fn higher_order_callers(&self) -> &Vec<'a, (Symbol, String, HigherOrderCaller<'a>)>;
+
+    /// Creates a new symbol in the module being built, for values the backend introduces itself.
+    fn create_symbol(&mut self, debug_name: &str) -> Symbol {
+        let (env, interns, _) = self.env_interns_helpers_mut();
+        let module_id = env.module_id;
+        let ident_ids = interns.all_ident_ids.get_mut(&module_id).unwrap();
+        let ident_id = ident_ids.add_str(debug_name);
+        Symbol::new(module_id, ident_id)
+    }
+
     /// reset resets any registers or other values that may be occupied at the end of a procedure.
     /// It also passes basic procedure information to the builder for setup of the next function.
     fn reset(&mut self, name: String, is_self_recursive: SelfRecursive);
@@ -107,13 +168,23 @@ trait Backend<'a> {
         let layout_id = layout_ids.get(proc.name.name(), &proc.ret_layout);
         let proc_name = self.symbol_to_string(proc.name.name(), layout_id);
         self.reset(proc_name, proc.is_self_recursive);
+        self.load_source_regions(proc.region, proc.symbol_regions);
         self.load_args(proc.args, &proc.ret_layout);
         for (layout, sym) in proc.args {
             self.set_layout_map(*sym, layout);
         }
         self.scan_ast(&proc.body);
         self.create_free_map();
+        self.analyze_liveness(proc.args, &proc.body);
         self.build_stmt(&proc.body, &proc.ret_layout);
+        let helper_proc_names = self.helper_proc_names(layout_ids);
+        let (bytes, relocs) = self.finalize();
+        (bytes, relocs, helper_proc_names)
+    }
+
+    /// The names of the helper procs and higher order callers that have been requested so far.
+    /// These are defined by the object builder once they are referenced.
+    fn helper_proc_names(&self, layout_ids: &mut LayoutIds<'a>) -> Vec<'a, (Symbol, String)> {
         let mut helper_proc_names = bumpalo::vec![in self.env().arena];
         helper_proc_names.reserve(self.helper_proc_symbols().len());
         for (rc_proc_sym, rc_proc_layout) in self.helper_proc_symbols() {
@@ -123,12 +194,174 @@ trait Backend<'a> {
 
             helper_proc_names.push((*rc_proc_sym, name));
         }
+        for (caller_sym, caller_name, _) in self.higher_order_callers() {
+            helper_proc_names.push((*caller_sym, caller_name.clone()));
+        }
+        helper_proc_names
+    }
+
+    /// Queues a wrapper for a zig builtin to call back into and returns its name.
+    /// The wrappers are only built once all procedures are done, since they need a fresh backend state.
+    fn register_higher_order_caller(&mut self, caller: HigherOrderCaller<'a>) -> String {
+        let debug_name = format!("#higher_order_caller{}", self.higher_order_callers().len());
+        let caller_sym = self.create_symbol(&debug_name);
+        let layout_id = LayoutIds::default().get(caller_sym, &Layout::UNIT);
+        let caller_name = self.symbol_to_string(caller_sym, layout_id);
+        self.higher_order_callers_mut()
+            .push((caller_sym, caller_name.clone(), caller));
+        caller_name
+    }
+
+    /// Returns the name of the helper proc that performs `op` on values of `layout`,
+    /// or `None` if they contain nothing refcounted.
+    fn refcount_proc_name(&mut self, layout: Layout<'a>, op: HelperOp) -> Option<String> {
+        if !layout.contains_refcounted() {
+            return None;
+        }
+
+        let (rc_proc_sym, new_specializations) = {
+            let (env, interns, rc_proc_gen) = self.env_interns_helpers_mut();
+            let module_id = env.module_id;
+            let ident_ids = interns.all_ident_ids.get_mut(&module_id).unwrap();
+
+            rc_proc_gen.gen_refcount_proc(ident_ids, layout, op)
+        };
+
+        for spec in new_specializations.into_iter() {
+            self.helper_proc_symbols_mut().push(spec);
+        }
+
+        let layout_id = LayoutIds::default().get(rc_proc_sym, &Layout::UNIT);
+        Some(self.symbol_to_string(rc_proc_sym, layout_id))
+    }
+
+    /// build_higher_order_caller builds a wrapper queued by register_higher_order_caller.
+    /// Returns the procedure bytes, its relocations, and the names of the helper procs it references.
+    fn build_higher_order_caller(
+        &mut self,
+        name: String,
+        caller: HigherOrderCaller<'a>,
+        layout_ids: &mut LayoutIds<'a>,
+    ) -> (Vec<u8>, Vec<Relocation>, Vec<'a, (Symbol, String)>) {
+        let arena = self.env().arena;
+        let ptr_layout = Layout::Builtin(Builtin::Int(IntWidth::U64));
+        self.reset(name, SelfRecursive::NotSelfRecursive);
+
+        match caller {
+            HigherOrderCaller::Map {
+                function,
+                closure_data,
+                arg_layouts,
+                ret_layout,
+            } => {
+                let ptr_args = arena.alloc_slice_fill_iter(
+                    ARG_SYMBOLS[..arg_layouts.len() + 2]
+                        .iter()
+                        .map(|sym| (ptr_layout, *sym)),
+                );
+                self.load_args(ptr_args, &Layout::UNIT);
+
+                let result = self.create_symbol("#result");
+                self.call_with_args_from_pointers(
+                    &result,
+                    function,
+                    closure_data,
+                    arg_layouts,
+                    &ret_layout,
+                );
+
+                let result_ptr = ARG_SYMBOLS[arg_layouts.len() + 1];
+                self.store_to_ptr(&result_ptr, &result, &ret_layout);
+            }
+            HigherOrderCaller::Compare {
+                function,
+                closure_data,
+                elem_layout,
+                ret_layout,
+            } => {
+                let ptr_args = arena
+                    .alloc_slice_fill_iter(ARG_SYMBOLS[..3].iter().map(|sym| (ptr_layout, *sym)));
+                self.load_args(ptr_args, &ret_layout);
+
+                let result = self.create_symbol("#result");
+                self.call_with_args_from_pointers(
+                    &result,
+                    function,
+                    closure_data,
+                    arena.alloc([elem_layout, elem_layout]),
+                    &ret_layout,
+                );
+
+                self.return_symbol(&result, &ret_layout);
+            }
+            HigherOrderCaller::IncN { layout, inc } => {
+                let isize_layout = Layout::Builtin(Builtin::Int(IntWidth::I64));
+                let args =
+                    arena.alloc([(ptr_layout, Symbol::ARG_1), (isize_layout, Symbol::ARG_2)]);
+                self.load_args(args, &Layout::UNIT);
+
+                if let Some(inc) = inc {
+                    let value = self.create_symbol("#value");
+                    self.load_from_ptr(&value, &Symbol::ARG_1, &layout);
+                    self.build_fn_call(
+                        &Symbol::DEV_TMP,
+                        inc,
+                        &[value, Symbol::ARG_2],
+                        &[layout, isize_layout],
+                        &Layout::UNIT,
+                    );
+                }
+            }
+            HigherOrderCaller::Dec { layout, dec } => {
+                self.load_args(arena.alloc([(ptr_layout, Symbol::ARG_1)]), &Layout::UNIT);
+
+                if let Some(dec) = dec {
+                    let value = self.create_symbol("#value");
+                    self.load_from_ptr(&value, &Symbol::ARG_1, &layout);
+                    self.build_fn_call(&Symbol::DEV_TMP, dec, &[value], &[layout], &Layout::UNIT);
+                }
+            }
+        }
+
+        let helper_proc_names = self.helper_proc_names(layout_ids);
         let (bytes, relocs) = self.finalize();
         (bytes, relocs, helper_proc_names)
     }
 
+    /// Loads the arguments of a higher order caller from the pointers in ARG_2 onwards,
+    /// and the closure data from the pointer in ARG_1, then calls `function` with them.
+    fn call_with_args_from_pointers(
+        &mut self,
+        dst: &Symbol,
+        function: String,
+        closure_data: Option<Layout<'a>>,
+        arg_layouts: &[Layout<'a>],
+        ret_layout: &Layout<'a>,
+    ) {
+        let mut args = bumpalo::vec![in self.env().arena];
+        let mut layouts = bumpalo::vec![in self.env().arena];
+
+        for (layout, ptr) in arg_layouts.iter().zip(&ARG_SYMBOLS[1..]) {
+            let arg = self.create_symbol("#arg");
+            self.load_from_ptr(&arg, ptr, layout);
+            args.push(arg);
+            layouts.push(*layout);
+        }
+
+        // The closure data is always the last argument of the passed function.
+        if let Some(layout) = closure_data {
+            let arg = self.create_symbol("#closure_data");
+            self.load_from_ptr(&arg, &Symbol::ARG_1, &layout);
+            args.push(arg);
+            layouts.push(layout);
+        }
+
+        self.build_fn_call(dst, function, &args, &layouts, ret_layout);
+    }
+
     /// build_stmt builds a statement and outputs at the end of the buffer.
     fn build_stmt(&mut self, stmt: &Stmt<'a>, ret_layout: &Layout<'a>) {
+        self.enter_stmt(stmt);
         match stmt {
             Stmt::Let(sym, expr, layout, following) => {
                 self.build_expr(sym, expr, layout);
@@ -206,6 +439,22 @@ trait Backend<'a> {
                 self.build_jump(id, args, arg_layouts.into_bump_slice(), ret_layout);
                 self.free_symbols(stmt);
             }
+            Stmt::Dbg {
+                symbol,
+                region,
+                remainder,
+                ..
+            } => {
+                self.load_literal_symbols(&[*symbol]);
+                self.build_dbg(symbol, *region);
+                self.free_symbols(stmt);
+                self.build_stmt(remainder, ret_layout);
+            }
+            Stmt::Crash(message) => {
+                self.load_literal_symbols(&[*message]);
+                self.build_crash(message);
+                self.free_symbols(stmt);
+            }
             x => todo!("the statement, {:?}", x),
         }
     }
@@ -219,6 +468,73 @@ trait Backend<'a> {
         ret_layout: &Layout<'a>,
     );
 
+    /// build_dbg reports the value of a symbol to the host by calling `roc_dbg`.
+    /// The host receives the module id and the region of the `dbg`, followed by a pointer to the value.
+    /// Outside of tests, `roc_dbg` is the builtins' `dbg_to_parent`, which hands the value to `roc dev`.
+    fn build_dbg(&mut self, sym: &Symbol, region: Region) {
+        let module_id: u32 = unsafe { std::mem::transmute(sym.module_id()) };
+
+        let u32_layout = Layout::Builtin(Builtin::Int(IntWidth::U32));
+        let ptr_layout = Layout::Builtin(Builtin::Int(IntWidth::U64));
+
+        let header = [
+            (Symbol::DEV_TMP, module_id),
+            (Symbol::DEV_TMP2, region.start().offset),
+            (Symbol::DEV_TMP3, region.end().offset),
+        ];
+
+        for (tmp, value) in header {
+            self.load_literal(
+                &tmp,
+                &u32_layout,
+                &Literal::Int((value as i128).to_ne_bytes()),
+            );
+        }
+
+        self.build_stack_address(&Symbol::DEV_TMP4, sym);
+
+        self.build_fn_call(
+            &Symbol::DEV_TMP5,
+            "roc_dbg".to_string(),
+            &[
+                Symbol::DEV_TMP,
+                Symbol::DEV_TMP2,
+                Symbol::DEV_TMP3,
+                Symbol::DEV_TMP4,
+            ],
+            &[u32_layout, u32_layout, u32_layout, ptr_layout],
+            &Layout::UNIT,
+        );
+
+        for tmp in [
+            Symbol::DEV_TMP,
+            Symbol::DEV_TMP2,
+            Symbol::DEV_TMP3,
+            Symbol::DEV_TMP4,
+            Symbol::DEV_TMP5,
+        ] {
+            self.free_symbol(&tmp);
+        }
+    }
+
+    /// build_crash hands the `Str` message of a `crash` to the host's `roc_panic`.
+    /// A builtin copies it into a null-terminated string first.
+    /// `roc_panic` must not return; if it does anyway, the trap after the call stops the program
+    /// instead of falling through into the code that follows.
+    fn build_crash(&mut self, message: &Symbol) {
+        self.build_fn_call(
+            &Symbol::DEV_TMP,
+            bitcode::STR_CRASH.to_string(),
+            &[*message],
+            &[Layout::Builtin(Builtin::Str)],
+            &Layout::UNIT,
+        );
+
+        self.trap();
+
+        self.free_symbol(&Symbol::DEV_TMP);
+    }
+
     // build_join generates a instructions for a join statement.
     fn build_join(
         &mut self,
@@ -307,6 +623,12 @@ trait Backend<'a> {
                             layout,
                         )
                     }
+
+                    CallType::HigherOrder(higher_order) => {
+                        // Now that the arguments are needed, load them if they are literals.
+                        self.load_literal_symbols(arguments);
+                        self.build_higher_order_lowlevel(sym, higher_order, layout)
+                    }
                     x => todo!("the call type, {:?}", x),
                 }
             }
@@ -345,6 +667,16 @@ trait Backend<'a> {
             } => {
                 self.load_union_at_index(sym, structure, *tag_id, *index, union_layout);
             }
+            Expr::SetUnionAtIndex {
+                structure,
+                tag_id,
+                union_layout,
+                index,
+                value,
+            } => {
+                self.load_literal_symbols(&[*value]);
+                self.set_union_at_index(sym, structure, *tag_id, *index, union_layout, value);
+            }
             Expr::GetTagId {
                 structure,
                 union_layout,
@@ -358,7 +690,47 @@ trait Backend<'a> {
                 ..
             } => {
                 self.load_literal_symbols(arguments);
-                self.tag(sym, arguments, tag_layout, *tag_id);
+                self.tag(sym, arguments, tag_layout, *tag_id, None);
+            }
+            Expr::Reuse {
+                symbol: reused,
+                tag_layout,
+                tag_id,
+                arguments,
+                ..
+            } => {
+                self.load_literal_symbols(arguments);
+                self.tag(sym, arguments, tag_layout, *tag_id, Some(*reused));
+            }
+            Expr::Reset { symbol, .. } => {
+                let reset_layout = *self.layout_map().get(symbol).unwrap();
+
+                // Expand the Reset into a call to a helper proc that returns the allocation of the
+                // value if it is unique and can be reused, or null otherwise.
+                let (reset_expr, new_specializations) = {
+                    let (env, interns, rc_proc_gen) = self.env_interns_helpers_mut();
+                    let module_id = env.module_id;
+                    let ident_ids = interns.all_ident_ids.get_mut(&module_id).unwrap();
+
+                    rc_proc_gen.call_reset_refcount(ident_ids, reset_layout, *symbol)
+                };
+
+                for spec in new_specializations.into_iter() {
+                    self.helper_proc_symbols_mut().push(spec);
+                }
+
+                self.build_expr(sym, &reset_expr, layout);
+            }
+            Expr::ExprBox { symbol } => {
+                let inner_layout = match layout {
+                    Layout::Boxed(inner_layout) => **inner_layout,
+                    x => internal_error!("Box expression with a non-box layout: {:?}", x),
+                };
+                self.load_literal_symbols(&[*symbol]);
+                self.expr_box(sym, symbol, &inner_layout);
+            }
+            Expr::ExprUnbox { symbol } => {
+                self.expr_unbox(sym, symbol, layout);
             }
             x => todo!("the expression, {:?}", x),
         }
@@ -461,7 +833,7 @@ trait Backend<'a> {
                 arg_layouts,
                 ret_layout,
             ),
-            LowLevel::NumSub => {
+            LowLevel::NumSub | LowLevel::NumSubWrap => {
                 debug_assert_eq!(
                     2,
                     args.len(),
@@ -477,6 +849,18 @@ trait Backend<'a> {
                 );
                 self.build_num_sub(sym, &args[0], &args[1], ret_layout)
             }
+            LowLevel::And => {
+                debug_assert_eq!(2, args.len(), "And: expected to have exactly two argument");
+                debug_assert_eq!(
+                    arg_layouts[0], arg_layouts[1],
+                    "And: expected all arguments of to have the same layout"
+                );
+                debug_assert_eq!(
+                    arg_layouts[0], *ret_layout,
+                    "And: expected to have the same argument and return layout"
+                );
+                self.build_and(sym, &args[0], &args[1], ret_layout)
+            }
             LowLevel::Eq => {
                 debug_assert_eq!(2, args.len(), "Eq: expected to have exactly two argument");
                 debug_assert_eq!(
@@ -605,6 +989,36 @@ trait Backend<'a> {
                 );
                 self.build_list_replace_unsafe(sym, args, arg_layouts, ret_layout)
             }
+            LowLevel::ListSwap => {
+                debug_assert_eq!(
+                    3,
+                    args.len(),
+                    "ListSwap: expected to have exactly three arguments"
+                );
+                self.build_list_swap(sym, args, arg_layouts, ret_layout)
+            }
+            LowLevel::ListSublist => {
+                debug_assert_eq!(
+                    3,
+                    args.len(),
+                    "ListSublist: expected to have exactly three arguments"
+                );
+                self.build_list_sublist(sym, args, arg_layouts, ret_layout)
+            }
+            LowLevel::DictHashBytes => self.build_fn_call(
+                sym,
+                bitcode::DICT_HASH_BYTES.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
+            LowLevel::DictHashMix => self.build_fn_call(
+                sym,
+                bitcode::DICT_HASH_MIX.to_string(),
+                args,
+                arg_layouts,
+                ret_layout,
+            ),
             LowLevel::StrConcat => self.build_fn_call(
                 sym,
                 bitcode::STR_CONCAT.to_string(),
@@ -678,7 +1092,13 @@ trait Backend<'a> {
                 self.load_literal_symbols(args);
                 self.build_fn_call(sym, fn_name, args, arg_layouts, ret_layout)
             }
-            _ => todo!("the function, {:?}", func_sym),
+            _ => {
+                // Everything else is a regular procedure from the builtin modules,
+                // specialized and built along with the app.
+                let layout_id = LayoutIds::default().get(func_sym, ret_layout);
+                let fn_name = self.symbol_to_string(func_sym, layout_id);
+                self.build_fn_call(sym, fn_name, args, arg_layouts, ret_layout)
+            }
         }
     }
 
@@ -708,6 +1128,9 @@ trait Backend<'a> {
     /// build_num_sub stores the `src1 - src2` difference into dst.
     fn build_num_sub(&mut self, dst: &Symbol, src1: &Symbol, src2: &Symbol, layout: &Layout<'a>);
 
+    /// build_and stores the bitwise and of `src1 & src2` into dst.
+    fn build_and(&mut self, dst: &Symbol, src1: &Symbol, src2: &Symbol, layout: &Layout<'a>);
+
     /// build_eq stores the result of `src1 == src2` into dst.
     fn build_eq(&mut self, dst: &Symbol, src1: &Symbol, src2: &Symbol, arg_layout: &Layout<'a>);
 
@@ -765,9 +1188,49 @@ trait Backend<'a> {
         ret_layout: &Layout<'a>,
     );
 
-    /// build_refcount_getptr loads the pointer to the reference count of src into dst.
+    /// build_list_swap returns the list with the elements at the two indices swapped.
+    fn build_list_swap(
+        &mut self,
+        dst: &Symbol,
+        args: &'a [Symbol],
+        arg_layouts: &[Layout<'a>],
+        ret_layout: &Layout<'a>,
+    );
+
+    /// build_list_sublist returns the slice of the list starting at an index with a length.
+    fn build_list_sublist(
+        &mut self,
+        dst: &Symbol,
+        args: &'a [Symbol],
+        arg_layouts: &[Layout<'a>],
+        ret_layout: &Layout<'a>,
+    );
+
+    /// build_higher_order_lowlevel calls a zig list builtin that takes a function, like List.map.
+    /// The function is passed as a pointer to a wrapper that zig can call.
+    fn build_higher_order_lowlevel(
+        &mut self,
+        dst: &Symbol,
+        higher_order: &HigherOrderLowLevel<'a>,
+        ret_layout: &Layout<'a>,
+    );
+
+    /// build_ptr_cast reinterprets src as a pointer or integer in dst.
+    /// Values that are not stored in a single register are cast to their address on the stack.
     fn build_ptr_cast(&mut self, dst: &Symbol, src: &Symbol);
 
+    /// build_stack_address loads the address of src into dst, moving src onto the stack if needed.
+    fn build_stack_address(&mut self, dst: &Symbol, src: &Symbol);
+
+    /// load_fn_ptr loads the address of the function `fn_name` into dst.
+    fn load_fn_ptr(&mut self, dst: &Symbol, fn_name: String);
+
+    /// load_from_ptr loads a value with the given layout from the address in ptr into dst.
+    fn load_from_ptr(&mut self, dst: &Symbol, ptr: &Symbol, layout: &Layout<'a>);
+
+    /// store_to_ptr stores src to the address in ptr.
+    fn store_to_ptr(&mut self, ptr: &Symbol, src: &Symbol, layout: &Layout<'a>);
+
     /// literal_map gets the map from symbol to literal and layout, used for lazy loading and literal folding.
     fn literal_map(&mut self) -> &mut MutMap<Symbol, (*const Literal<'a>, *const Layout<'a>)>;
 
@@ -821,21 +1284,44 @@ trait Backend<'a> {
         union_layout: &UnionLayout<'a>,
     );
 
+    /// set_union_at_index overwrites the value at `index` for `tag_id` with `value`,
+    /// and makes `sym` the unit struct.
+    fn set_union_at_index(
+        &mut self,
+        sym: &Symbol,
+        structure: &Symbol,
+        tag_id: TagIdIntType,
+        index: u64,
+        union_layout: &UnionLayout<'a>,
+        value: &Symbol,
+    );
+
     /// get_tag_id loads the tag id from a the union.
     fn get_tag_id(&mut self, sym: &Symbol, structure: &Symbol, union_layout: &UnionLayout<'a>);
 
     /// tag sets the tag for a union.
+    /// Heap allocated unions reuse the memory of `reuse` if a reset made it available.
     fn tag(
         &mut self,
         sym: &Symbol,
         args: &'a [Symbol],
         tag_layout: &UnionLayout<'a>,
         tag_id: TagIdIntType,
+        reuse: Option<Symbol>,
     );
 
+    /// expr_box allocates memory on the heap and moves `value` into it.
+    fn expr_box(&mut self, sym: &Symbol, value: &Symbol, inner_layout: &Layout<'a>);
+
+    /// expr_unbox loads the value that `ptr` points to into `sym`.
+    fn expr_unbox(&mut self, sym: &Symbol, ptr: &Symbol, inner_layout: &Layout<'a>);
+
     /// return_symbol moves a symbol to the correct return location for the backend and adds a jump to the end of the function.
     fn return_symbol(&mut self, sym: &Symbol, layout: &Layout<'a>);
 
+    /// trap stops the program with an illegal instruction, for places that control must never reach.
+    fn trap(&mut self);
+
     /// free_symbols will free all symbols for the given statement.
     fn free_symbols(&mut self, stmt: &Stmt<'a>) {
         if let Some(syms) = self.free_map().remove(&(stmt as *const Stmt<'a>)) {
@@ -894,6 +1380,20 @@ trait Backend<'a> {
     /// set_free_map sets the free map to the given map.
     fn set_free_map(&mut self, map: MutMap<*const Stmt<'a>, Vec<'a, Symbol>>);
 
+    /// analyze_liveness computes the live intervals of all symbols in the procedure.
+    /// The backend uses them to decide which symbols to keep in registers.
+    fn analyze_liveness(&mut self, args: &'a [(Layout<'a>, Symbol)], body: &Stmt<'a>);
+
+    /// enter_stmt lets the backend know which statement it is about to build.
+    fn enter_stmt(&mut self, stmt: &Stmt<'a>);
+
+    /// load_source_regions lets the backend know where the procedure and its named values are in the source.
+    /// The backend uses them to map the code it generates back to the source.
+    fn load_source_regions(&mut self, proc_region: Region, symbol_regions: &'a [(Symbol, Region)]);
+
+    /// take_source_locations returns the offsets in the last finalized procedure where the code for a region of the source starts.
+    fn take_source_locations(&mut self) -> Vec<'a, (u64, Region)>;
+
     /// scan_ast runs through the ast and fill the last seen map.
     /// This must iterate through the ast in the same way that build_stmt does. i.e. then before else.
     fn scan_ast(&mut self, stmt: &Stmt<'a>) {
@@ -932,6 +1432,12 @@ trait Backend<'a> {
                     Expr::UnionAtIndex { structure, .. } => {
                         self.set_last_seen(*structure, stmt);
                     }
+                    Expr::SetUnionAtIndex {
+                        structure, value, ..
+                    } => {
+                        self.set_last_seen(*structure, stmt);
+                        self.set_last_seen(*value, stmt);
+                    }
                     Expr::Array { elems, .. } => {
                         for elem in *elems {
                             if let ListLiteralElement::Symbol(sym) = elem {
@@ -1004,6 +1510,17 @@ trait Backend<'a> {
                 }
             }
 
+            Stmt::Dbg {
+                symbol, remainder, ..
+            } => {
+                self.set_last_seen(*symbol, stmt);
+                self.scan_ast(remainder);
+            }
+
+            Stmt::Crash(message) => {
+                self.set_last_seen(*message, stmt);
+            }
+
             Stmt::Expect { .. } => todo!("expect is not implemented in the wasm backend"),
 
             Stmt::RuntimeError(_) => {}
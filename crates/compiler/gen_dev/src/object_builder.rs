@@ -1,11 +1,12 @@
+use crate::debug_info::{DebugInfo, ProcDebugInfo};
 use crate::generic64::{aarch64, new_backend_64bit, x86_64};
 use crate::{Backend, Env, Relocation};
 use bumpalo::collections::Vec;
 use object::write::{self, SectionId, SymbolId};
 use object::write::{Object, StandardSection, StandardSegment, Symbol, SymbolSection};
 use object::{
-    Architecture, BinaryFormat, Endianness, RelocationEncoding, RelocationKind, SectionKind,
-    SymbolFlags, SymbolKind, SymbolScope,
+    elf, macho, Architecture, BinaryFormat, Endianness, RelocationEncoding, RelocationKind,
+    SectionKind, SymbolFlags, SymbolKind, SymbolScope,
 };
 use roc_collections::all::MutMap;
 use roc_error_macros::internal_error;
@@ -40,11 +41,7 @@ pub fn build_module<'a>(
                 x86_64::X86_64Assembler,
                 x86_64::X86_64SystemV,
             >(env, TargetInfo::default_x86_64(), interns);
-            build_object(
-                procedures,
-                backend,
-                Object::new(BinaryFormat::Elf, Architecture::X86_64, Endianness::Little),
-            )
+            build_object(procedures, backend, BinaryFormat::Elf, Architecture::X86_64)
         }
         Triple {
             architecture: TargetArch::X86_64,
@@ -60,11 +57,8 @@ pub fn build_module<'a>(
             build_object(
                 procedures,
                 backend,
-                Object::new(
-                    BinaryFormat::MachO,
-                    Architecture::X86_64,
-                    Endianness::Little,
-                ),
+                BinaryFormat::MachO,
+                Architecture::X86_64,
             )
         }
         Triple {
@@ -81,7 +75,8 @@ pub fn build_module<'a>(
             build_object(
                 procedures,
                 backend,
-                Object::new(BinaryFormat::Elf, Architecture::Aarch64, Endianness::Little),
+                BinaryFormat::Elf,
+                Architecture::Aarch64,
             )
         }
         Triple {
@@ -98,11 +93,8 @@ pub fn build_module<'a>(
             build_object(
                 procedures,
                 backend,
-                Object::new(
-                    BinaryFormat::MachO,
-                    Architecture::Aarch64,
-                    Endianness::Little,
-                ),
+                BinaryFormat::MachO,
+                Architecture::Aarch64,
             )
         }
         x => unimplemented!("the target, {:?}", x),
@@ -112,6 +104,8 @@ pub fn build_module<'a>(
 fn generate_wrapper<'a, B: Backend<'a>>(
     backend: &mut B,
     output: &mut Object,
+    format: BinaryFormat,
+    architecture: Architecture,
     wrapper_name: String,
     wraps: String,
 ) {
@@ -144,18 +138,18 @@ fn generate_wrapper<'a, B: Backend<'a>>(
     };
     output.add_symbol(symbol);
     if let Some(sym_id) = output.symbol_id(name) {
-        let reloc = write::Relocation {
-            offset: offset + proc_offset,
-            size: 32,
-            kind: RelocationKind::PltRelative,
-            encoding: RelocationEncoding::X86Branch,
-            symbol: sym_id,
-            addend: -4,
-        };
-
-        match output.add_relocation(text_section, reloc) {
-            Ok(obj) => obj,
-            Err(e) => internal_error!("{:?}", e),
+        for reloc in linked_function_relocations(
+            format,
+            architecture,
+            proc_data,
+            offset,
+            proc_offset,
+            sym_id,
+        ) {
+            match output.add_relocation(text_section, reloc) {
+                Ok(obj) => obj,
+                Err(e) => internal_error!("{:?}", e),
+            }
         }
     } else {
         internal_error!("failed to find fn symbol for {:?}", wraps);
@@ -165,12 +159,17 @@ fn generate_wrapper<'a, B: Backend<'a>>(
 fn build_object<'a, B: Backend<'a>>(
     procedures: MutMap<(symbol::Symbol, ProcLayout<'a>), Proc<'a>>,
     mut backend: B,
-    mut output: Object<'a>,
+    format: BinaryFormat,
+    architecture: Architecture,
 ) -> Object<'a> {
+    let mut output = Object::new(format, architecture, Endianness::Little);
     let data_section = output.section_id(StandardSection::Data);
 
     let arena = backend.env().arena;
 
+    // Both supported architectures are 64 bit.
+    let mut debug_info = DebugInfo::new(backend.env().sources, backend.env().module_id, 8);
+
     /*
     // Commented out because we couldn't figure out how to get it to work on mac - see https://github.com/roc-lang/roc/pull/1323
     let comment = output.add_section(vec![], b".comment".to_vec(), SectionKind::OtherString);
@@ -185,27 +184,53 @@ fn build_object<'a, B: Backend<'a>>(
         generate_wrapper(
             &mut backend,
             &mut output,
+            format,
+            architecture,
             "roc_alloc".into(),
             "malloc".into(),
         );
         generate_wrapper(
             &mut backend,
             &mut output,
+            format,
+            architecture,
             "roc_realloc".into(),
             "realloc".into(),
         );
         generate_wrapper(
             &mut backend,
             &mut output,
+            format,
+            architecture,
             "roc_dealloc".into(),
             "free".into(),
         );
         generate_wrapper(
             &mut backend,
             &mut output,
+            format,
+            architecture,
             "roc_panic".into(),
             "roc_builtins.utils.test_panic".into(),
         );
+        generate_wrapper(
+            &mut backend,
+            &mut output,
+            format,
+            architecture,
+            "roc_dbg".into(),
+            "roc_builtins.utils.test_dbg".into(),
+        );
+    } else {
+        // `roc dev` renders `dbg` values itself, so platforms don't have to
+        generate_wrapper(
+            &mut backend,
+            &mut output,
+            format,
+            architecture,
+            "roc_dbg".into(),
+            "roc_builtins.utils.dbg_to_parent".into(),
+        );
     }
 
     // Setup layout_ids for procedure calls.
@@ -227,20 +252,55 @@ fn build_object<'a, B: Backend<'a>>(
 
     // Build procedures from user code
     let mut relocations = bumpalo::vec![in arena];
-    for (fn_name, section_id, proc_id, proc) in procs {
+    for (fn_name, debug_name, section_id, proc_id, proc) in procs {
         build_proc(
             &mut output,
+            format,
+            architecture,
             &mut backend,
             &mut relocations,
             &mut layout_ids,
+            &mut debug_info,
             data_section,
             fn_name,
+            debug_name,
             section_id,
             proc_id,
             proc,
         )
     }
 
+    // Build the wrappers that zig builtins use to call procedures passed to higher-order functions
+    loop {
+        let empty = bumpalo::collections::Vec::new_in(arena);
+        let higher_order_callers = std::mem::replace(backend.higher_order_callers_mut(), empty);
+        if higher_order_callers.is_empty() {
+            break;
+        }
+
+        for (sym, fn_name, caller) in higher_order_callers {
+            let (section_id, proc_id) = helper_section_and_symbol(&mut output, sym, &fn_name);
+            let (proc_data, relocs, helper_proc_names) =
+                backend.build_higher_order_caller(fn_name.clone(), caller, &mut layout_ids);
+            let proc_debug_info = ProcDebugInfo::generated(arena, backend.interns(), sym);
+            add_proc_data(
+                &mut output,
+                format,
+                architecture,
+                &mut relocations,
+                &mut debug_info,
+                data_section,
+                fn_name,
+                section_id,
+                proc_id,
+                proc_data,
+                relocs,
+                helper_proc_names,
+                proc_debug_info,
+            )
+        }
+    }
+
     // Generate IR for specialized helper procs (refcounting & equality)
     let helper_procs = {
         let module_id = backend.env().module_id;
@@ -262,45 +322,24 @@ fn build_object<'a, B: Backend<'a>>(
     for ((sym, layout), proc) in helper_symbols_and_layouts.into_iter().zip(helper_procs) {
         let layout_id = layout_ids.get_toplevel(sym, &layout);
         let fn_name = backend.symbol_to_string(sym, layout_id);
-        if let Some(proc_id) = output.symbol_id(fn_name.as_bytes()) {
-            if let SymbolSection::Section(section_id) = output.symbol(proc_id).section {
-                helper_names_symbols_procs.push((fn_name, section_id, proc_id, proc));
-                continue;
-            }
-        } else {
-            // The symbol isn't defined yet and will just be used by other rc procs.
-            let section_id = output.add_section(
-                output.segment_name(StandardSegment::Text).to_vec(),
-                format!(".text.{:x}", sym.as_u64()).as_bytes().to_vec(),
-                SectionKind::Text,
-            );
-
-            let rc_symbol = Symbol {
-                name: fn_name.as_bytes().to_vec(),
-                value: 0,
-                size: 0,
-                kind: SymbolKind::Text,
-                scope: SymbolScope::Linkage,
-                weak: false,
-                section: SymbolSection::Section(section_id),
-                flags: SymbolFlags::None,
-            };
-            let proc_id = output.add_symbol(rc_symbol);
-            helper_names_symbols_procs.push((fn_name, section_id, proc_id, proc));
-            continue;
-        }
-        internal_error!("failed to create rc fn for symbol {:?}", sym);
+        let debug_name = layout_id.to_demangled_string(sym, backend.interns());
+        let (section_id, proc_id) = helper_section_and_symbol(&mut output, sym, &fn_name);
+        helper_names_symbols_procs.push((fn_name, debug_name, section_id, proc_id, proc));
     }
 
     // Build helpers
-    for (fn_name, section_id, proc_id, proc) in helper_names_symbols_procs {
+    for (fn_name, debug_name, section_id, proc_id, proc) in helper_names_symbols_procs {
         build_proc(
             &mut output,
+            format,
+            architecture,
             &mut backend,
             &mut relocations,
             &mut layout_ids,
+            &mut debug_info,
             data_section,
             fn_name,
+            debug_name,
             section_id,
             proc_id,
             proc,
@@ -314,13 +353,16 @@ fn build_object<'a, B: Backend<'a>>(
             Err(e) => internal_error!("{:?}", e),
         }
     }
+
+    debug_info.write(&mut output, format);
+
     output
 }
 
 fn build_proc_symbol<'a, B: Backend<'a>>(
     output: &mut Object<'a>,
     layout_ids: &mut LayoutIds<'a>,
-    procs: &mut Vec<'a, (String, SectionId, SymbolId, Proc<'a>)>,
+    procs: &mut Vec<'a, (String, String, SectionId, SymbolId, Proc<'a>)>,
     backend: &B,
     sym: roc_module::symbol::Symbol,
     layout: ProcLayout<'a>,
@@ -328,6 +370,7 @@ fn build_proc_symbol<'a, B: Backend<'a>>(
 ) {
     let layout_id = layout_ids.get_toplevel(sym, &layout);
     let base_name = backend.symbol_to_string(sym, layout_id);
+    let debug_name = layout_id.to_demangled_string(sym, backend.interns());
 
     let fn_name = if backend.env().exposed_to_host.contains(&sym) {
         layout_ids
@@ -360,26 +403,119 @@ fn build_proc_symbol<'a, B: Backend<'a>>(
         flags: SymbolFlags::None,
     };
     let proc_id = output.add_symbol(proc_symbol);
-    procs.push((fn_name, section_id, proc_id, proc));
+    procs.push((fn_name, debug_name, section_id, proc_id, proc));
+}
+
+/// Finds the section of a helper that was already referenced, or creates a symbol for it.
+fn helper_section_and_symbol(
+    output: &mut Object,
+    sym: roc_module::symbol::Symbol,
+    fn_name: &str,
+) -> (SectionId, SymbolId) {
+    if let Some(proc_id) = output.symbol_id(fn_name.as_bytes()) {
+        if let SymbolSection::Section(section_id) = output.symbol(proc_id).section {
+            return (section_id, proc_id);
+        }
+        internal_error!("failed to create rc fn for symbol {:?}", sym);
+    }
+
+    // The symbol isn't defined yet and will just be used by other rc procs.
+    let section_id = output.add_section(
+        output.segment_name(StandardSegment::Text).to_vec(),
+        format!(".text.{:x}", sym.as_u64()).as_bytes().to_vec(),
+        SectionKind::Text,
+    );
+
+    let rc_symbol = Symbol {
+        name: fn_name.as_bytes().to_vec(),
+        value: 0,
+        size: 0,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Linkage,
+        weak: false,
+        section: SymbolSection::Section(section_id),
+        flags: SymbolFlags::None,
+    };
+    let proc_id = output.add_symbol(rc_symbol);
+    (section_id, proc_id)
 }
 
 #[allow(clippy::too_many_arguments)]
 fn build_proc<'a, B: Backend<'a>>(
     output: &mut Object,
+    format: BinaryFormat,
+    architecture: Architecture,
     backend: &mut B,
     relocations: &mut Vec<'a, (SectionId, object::write::Relocation)>,
     layout_ids: &mut LayoutIds<'a>,
+    debug_info: &mut DebugInfo,
     data_section: SectionId,
     fn_name: String,
+    debug_name: String,
     section_id: SectionId,
     proc_id: SymbolId,
     proc: Proc<'a>,
 ) {
-    let mut local_data_index = 0;
+    let module_id = proc.name.name().module_id();
+    let region = proc.region;
     let (proc_data, relocs, rc_proc_names) = backend.build_proc(proc, layout_ids);
+    let proc_debug_info = ProcDebugInfo {
+        name: debug_name,
+        module_id,
+        region,
+        locations: backend.take_source_locations(),
+    };
+    add_proc_data(
+        output,
+        format,
+        architecture,
+        relocations,
+        debug_info,
+        data_section,
+        fn_name,
+        section_id,
+        proc_id,
+        proc_data,
+        relocs,
+        rc_proc_names,
+        proc_debug_info,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+fn add_proc_data<'a>(
+    output: &mut Object,
+    format: BinaryFormat,
+    architecture: Architecture,
+    relocations: &mut Vec<'a, (SectionId, object::write::Relocation)>,
+    debug_info: &mut DebugInfo,
+    data_section: SectionId,
+    fn_name: String,
+    section_id: SectionId,
+    proc_id: SymbolId,
+    proc_data: Vec<u8>,
+    relocs: Vec<Relocation>,
+    rc_proc_names: Vec<'a, (roc_module::symbol::Symbol, String)>,
+    proc_debug_info: ProcDebugInfo<'a>,
+) {
+    let mut local_data_index = 0;
     let proc_offset = output.add_symbol_data(proc_id, section_id, &proc_data, 16);
+
+    // The linker needs the mangled name, but tools that only read the symbol table should show the demangled one.
+    let demangled_symbol = Symbol {
+        name: proc_debug_info.name.as_bytes().to_vec(),
+        value: proc_offset,
+        size: proc_data.len() as u64,
+        kind: SymbolKind::Text,
+        scope: SymbolScope::Compilation,
+        weak: false,
+        section: SymbolSection::Section(section_id),
+        flags: SymbolFlags::None,
+    };
+    output.add_symbol(demangled_symbol);
+    debug_info.add_proc(proc_id, &fn_name, proc_data.len() as u64, proc_debug_info);
     for reloc in relocs.iter() {
-        let elfreloc = match reloc {
+        let elfrelocs = match reloc {
             Relocation::LocalData { offset, data } => {
                 let data_symbol = write::Symbol {
                     name: format!("{}.data{}", fn_name, local_data_index)
@@ -396,25 +532,25 @@ fn build_proc<'a, B: Backend<'a>>(
                 local_data_index += 1;
                 let data_id = output.add_symbol(data_symbol);
                 output.add_symbol_data(data_id, data_section, data, 4);
-                write::Relocation {
+                vec![write::Relocation {
                     offset: offset + proc_offset,
                     size: 32,
                     kind: RelocationKind::Relative,
                     encoding: RelocationEncoding::Generic,
                     symbol: data_id,
                     addend: -4,
-                }
+                }]
             }
             Relocation::LinkedData { offset, name } => {
                 if let Some(sym_id) = output.symbol_id(name.as_bytes()) {
-                    write::Relocation {
+                    vec![write::Relocation {
                         offset: offset + proc_offset,
                         size: 32,
                         kind: RelocationKind::GotRelative,
                         encoding: RelocationEncoding::Generic,
                         symbol: sym_id,
                         addend: -4,
-                    }
+                    }]
                 } else {
                     internal_error!("failed to find data symbol for {:?}", name);
                 }
@@ -459,20 +595,109 @@ fn build_proc<'a, B: Backend<'a>>(
                     }
                 }
                 if let Some(sym_id) = output.symbol_id(name.as_bytes()) {
-                    write::Relocation {
-                        offset: offset + proc_offset,
-                        size: 32,
-                        kind: RelocationKind::PltRelative,
-                        encoding: RelocationEncoding::X86Branch,
-                        symbol: sym_id,
-                        addend: -4,
-                    }
+                    linked_function_relocations(
+                        format,
+                        architecture,
+                        &proc_data,
+                        *offset,
+                        proc_offset,
+                        sym_id,
+                    )
                 } else {
                     internal_error!("failed to find fn symbol for {:?}", name);
                 }
             }
             Relocation::JmpToReturn { .. } => unreachable!(),
         };
-        relocations.push((section_id, elfreloc));
+        for elfreloc in elfrelocs {
+            relocations.push((section_id, elfreloc));
+        }
+    }
+}
+
+/// The relocations needed for a call to, or the address of, a linked function at `offset`.
+/// On aarch64, the instruction at `offset` decides which relocations to use.
+fn linked_function_relocations(
+    format: BinaryFormat,
+    architecture: Architecture,
+    proc_data: &[u8],
+    offset: u64,
+    proc_offset: u64,
+    symbol: SymbolId,
+) -> std::vec::Vec<write::Relocation> {
+    match architecture {
+        Architecture::X86_64 => vec![write::Relocation {
+            offset: offset + proc_offset,
+            size: 32,
+            kind: RelocationKind::PltRelative,
+            encoding: RelocationEncoding::X86Branch,
+            symbol,
+            addend: -4,
+        }],
+        Architecture::Aarch64 => {
+            let relocation = |offset: u64, elf_kind: u32, macho_kind: u8, relative: bool| {
+                let kind = match format {
+                    BinaryFormat::Elf => RelocationKind::Elf(elf_kind),
+                    BinaryFormat::MachO => RelocationKind::MachO {
+                        value: macho_kind,
+                        relative,
+                    },
+                    x => internal_error!("unsupported binary format for aarch64: {:?}", x),
+                };
+                write::Relocation {
+                    offset: offset + proc_offset,
+                    size: 32,
+                    kind,
+                    encoding: RelocationEncoding::Generic,
+                    symbol,
+                    addend: 0,
+                }
+            };
+
+            let start = offset as usize;
+            let mut inst_bytes = [0; 4];
+            inst_bytes.copy_from_slice(&proc_data[start..start + 4]);
+            let inst = u32::from_le_bytes(inst_bytes);
+
+            if inst >> 26 == 0b100101 {
+                // BL
+                vec![relocation(
+                    offset,
+                    elf::R_AARCH64_CALL26,
+                    macho::ARM64_RELOC_BRANCH26,
+                    true,
+                )]
+            } else if inst >> 26 == 0b000101 {
+                // B
+                vec![relocation(
+                    offset,
+                    elf::R_AARCH64_JUMP26,
+                    macho::ARM64_RELOC_BRANCH26,
+                    true,
+                )]
+            } else if inst & 0x9F00_0000 == 0x9000_0000 {
+                // ADRP followed by the LDR that loads the address out of the GOT.
+                vec![
+                    relocation(
+                        offset,
+                        elf::R_AARCH64_ADR_GOT_PAGE,
+                        macho::ARM64_RELOC_GOT_LOAD_PAGE21,
+                        true,
+                    ),
+                    relocation(
+                        offset + 4,
+                        elf::R_AARCH64_LD64_GOT_LO12_NC,
+                        macho::ARM64_RELOC_GOT_LOAD_PAGEOFF12,
+                        false,
+                    ),
+                ]
+            } else {
+                internal_error!(
+                    "unexpected instruction for a linked function: {:#010x}",
+                    inst
+                )
+            }
+        }
+        x => internal_error!("unsupported architecture: {:?}", x),
     }
 }
@@ -1,7 +1,9 @@
 use crate::llvm::bitcode::{
     call_bitcode_fn, call_bitcode_fn_fixing_for_convention, call_list_bitcode_fn,
-    call_str_bitcode_fn, call_void_bitcode_fn, pass_list_or_string_to_zig_32bit, BitcodeReturns,
+    call_str_bitcode_fn, call_void_bitcode_fn, pass_list_or_string_to_zig_32bit,
+    pass_string_to_zig_64bit, BitcodeReturns,
 };
+use crate::llvm::build_dict::{dict_hash_bytes, dict_hash_mix};
 use crate::llvm::build_list::{
     self, allocate_list, empty_polymorphic_list, list_append_unsafe, list_capacity, list_concat,
     list_drop_at, list_get_unsafe, list_len, list_map, list_map2, list_map3, list_map4,
@@ -13,6 +15,7 @@ use crate::llvm::compare::{generic_eq, generic_neq};
 use crate::llvm::convert::{
     self, argument_type_from_layout, basic_type_from_builtin, basic_type_from_layout, zig_str_type,
 };
+use crate::llvm::debug_info::DebugSources;
 use crate::llvm::expect::clone_to_shared_memory;
 use crate::llvm::refcounting::{
     build_reset, decrement_refcount_layout, increment_refcount_layout, PointerToRefcount,
@@ -59,6 +62,7 @@ use roc_mono::layout::{
     Builtin, CapturesNiche, LambdaName, LambdaSet, Layout, LayoutIds, RawFunctionLayout,
     TagIdIntType, UnionLayout,
 };
+use roc_region::all::Region;
 use roc_std::RocDec;
 use roc_target::{PtrWidth, TargetInfo};
 use std::convert::TryInto;
@@ -216,6 +220,8 @@ pub struct Env<'a, 'ctx, 'env> {
     pub target_info: TargetInfo,
     pub mode: LlvmBackendMode,
     pub exposed_to_host: MutSet<Symbol>,
+    /// When present, procs get debug info that points at their Roc source.
+    pub debug_sources: Option<DebugSources<'ctx>>,
 }
 
 #[repr(u32)]
@@ -393,6 +399,54 @@ impl<'a, 'ctx, 'env> Env<'a, 'ctx, 'env> {
         call.set_call_convention(C_CALL_CONV);
     }
 
+    /// Reports the value behind `value_ptr` to the host, along with where the `dbg` is in the source
+    pub fn call_dbg(&self, module_id: ModuleId, region: Region, value_ptr: PointerValue<'ctx>) {
+        let i32_type = self.context.i32_type();
+        let opaque_ptr_type = self.context.i8_type().ptr_type(AddressSpace::Generic);
+
+        // unlike `roc_panic`, the builtins never call `roc_dbg`, so we may have to declare it
+        let function = match self.module.get_function("roc_dbg") {
+            Some(function) => function,
+            None => {
+                let fn_type = self.context.void_type().fn_type(
+                    &[
+                        i32_type.into(),
+                        i32_type.into(),
+                        i32_type.into(),
+                        opaque_ptr_type.into(),
+                    ],
+                    false,
+                );
+
+                self.module
+                    .add_function("roc_dbg", fn_type, Some(Linkage::External))
+            }
+        };
+
+        let module_id: u32 = unsafe { std::mem::transmute(module_id) };
+
+        let module_id = i32_type.const_int(module_id as u64, false);
+        let region_start = i32_type.const_int(region.start().offset as u64, false);
+        let region_end = i32_type.const_int(region.end().offset as u64, false);
+
+        let value_ptr = self
+            .builder
+            .build_pointer_cast(value_ptr, opaque_ptr_type, "to_opaque");
+
+        let call = self.builder.build_call(
+            function,
+            &[
+                module_id.into(),
+                region_start.into(),
+                region_end.into(),
+                value_ptr.into(),
+            ],
+            "roc_dbg",
+        );
+
+        call.set_call_convention(C_CALL_CONV);
+    }
+
     pub fn new_debug_info(module: &Module<'ctx>) -> (DebugInfoBuilder<'ctx>, DICompileUnit<'ctx>) {
         module.create_debug_info_builder(
             true,
@@ -1500,6 +1554,57 @@ pub fn build_exp_expr<'a, 'ctx, 'env>(
             }
         }
 
+        SetUnionAtIndex {
+            structure,
+            tag_id,
+            union_layout,
+            index,
+            value,
+        } => {
+            let argument = load_symbol(scope, structure).into_pointer_value();
+            let value = load_symbol(scope, value);
+
+            let (field_layouts, data_ptr) = match union_layout {
+                UnionLayout::NonRecursive(_) => {
+                    unreachable!("a non-recursive tag is not stored on the heap")
+                }
+                UnionLayout::Recursive(tag_layouts) => (
+                    tag_layouts[*tag_id as usize],
+                    tag_pointer_clear_tag_id(env, argument),
+                ),
+                UnionLayout::NonNullableUnwrapped(field_layouts) => (*field_layouts, argument),
+                UnionLayout::NullableWrapped {
+                    nullable_id,
+                    other_tags,
+                } => {
+                    debug_assert_ne!(*tag_id, *nullable_id);
+
+                    let tag_index = if *tag_id < *nullable_id {
+                        *tag_id
+                    } else {
+                        tag_id - 1
+                    };
+
+                    (
+                        other_tags[tag_index as usize],
+                        tag_pointer_clear_tag_id(env, argument),
+                    )
+                }
+                UnionLayout::NullableUnwrapped {
+                    nullable_id,
+                    other_fields,
+                } => {
+                    debug_assert_ne!(*tag_id != 0, *nullable_id);
+
+                    (*other_fields, argument)
+                }
+            };
+
+            store_at_index_ptr(env, field_layouts, *index as usize, data_ptr, value);
+
+            build_struct(env, scope, &[]).into()
+        }
+
         GetTagId {
             structure,
             union_layout,
@@ -2097,6 +2202,37 @@ fn lookup_at_index_ptr2<'a, 'ctx, 'env>(
     }
 }
 
+/// The inverse of `lookup_at_index_ptr2`: overwrite a field of a tag that is stored on the heap
+fn store_at_index_ptr<'a, 'ctx, 'env>(
+    env: &Env<'a, 'ctx, 'env>,
+    field_layouts: &[Layout<'a>],
+    index: usize,
+    value: PointerValue<'ctx>,
+    field_value: BasicValueEnum<'ctx>,
+) {
+    let struct_layout = Layout::struct_no_name_order(field_layouts);
+    let struct_type = basic_type_from_layout(env, &struct_layout);
+
+    let data_ptr = env
+        .builder
+        .build_bitcast(
+            value,
+            struct_type.ptr_type(AddressSpace::Generic),
+            "cast_store_at_index_ptr",
+        )
+        .into_pointer_value();
+
+    let elem_ptr = env
+        .builder
+        .build_struct_gep(data_ptr, index as u32, "at_index_struct_gep_data")
+        .unwrap();
+
+    let field_layout = field_layouts[index];
+    let field_value = build_tag_field_value(env, field_value, field_layout);
+
+    store_roc_value(env, field_layout, elem_ptr, field_value);
+}
+
 pub fn reserve_with_refcount<'a, 'ctx, 'env>(
     env: &Env<'a, 'ctx, 'env>,
     layout: &Layout<'a>,
@@ -2448,6 +2584,10 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
             for (symbol, expr, layout) in queue {
                 debug_assert!(layout != &Layout::RecursivePointer);
 
+                if let Some(debug_sources) = &env.debug_sources {
+                    debug_sources.enter_let(env, parent, *symbol);
+                }
+
                 let val = build_exp_expr(
                     env,
                     layout_ids,
@@ -2458,6 +2598,10 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
                     expr,
                 );
 
+                if let Some(debug_sources) = &env.debug_sources {
+                    debug_sources.declare_local(env, parent, *symbol, layout, val);
+                }
+
                 // Make a new scope which includes the binding we just encountered.
                 // This should be done *after* compiling the bound expr, since any
                 // recursive (in the LetRec sense) bindings should already have
@@ -2771,6 +2915,10 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
             layouts: _,
             remainder,
         } => {
+            if let Some(debug_sources) = &env.debug_sources {
+                debug_sources.enter_region(env, parent, cond_symbol.module_id(), *region);
+            }
+
             let bd = env.builder;
             let context = env.context;
 
@@ -2836,6 +2984,73 @@ pub fn build_exp_stmt<'a, 'ctx, 'env>(
             )
         }
 
+        Dbg {
+            symbol,
+            region,
+            layout: _,
+            remainder,
+        } => {
+            if let Some(debug_sources) = &env.debug_sources {
+                debug_sources.enter_region(env, parent, symbol.module_id(), *region);
+            }
+
+            if env.mode.runs_expects() {
+                match env.target_info.ptr_width() {
+                    roc_target::PtrWidth::Bytes8 => {
+                        // a dbg frame looks just like a failed expect with one lookup,
+                        // so the value can be rendered in the same way
+                        clone_to_shared_memory(
+                            env,
+                            scope,
+                            layout_ids,
+                            *symbol,
+                            *region,
+                            &[*symbol],
+                        );
+
+                        if env.mode.runs_expects_in_separate_process() {
+                            let func = env
+                                .module
+                                .get_function(bitcode::UTILS_EXPECT_FAILED_FINALIZE)
+                                .unwrap();
+
+                            env.builder
+                                .build_call(func, &[], "call_expect_finalize_failed");
+                        }
+                    }
+                    roc_target::PtrWidth::Bytes4 => {
+                        // there is no shared memory on WASM; the value is not reported
+                    }
+                }
+            } else if env.mode.has_host() {
+                let (value, layout) = load_symbol_and_layout(scope, symbol);
+
+                let basic_type = basic_type_from_layout(env, layout);
+                let value_ptr = create_entry_block_alloca(env, parent, basic_type, "dbg_value");
+                store_roc_value(env, *layout, value_ptr, value);
+
+                env.call_dbg(symbol.module_id(), *region, value_ptr);
+            }
+
+            build_exp_stmt(
+                env,
+                layout_ids,
+                func_spec_solutions,
+                scope,
+                parent,
+                remainder,
+            )
+        }
+
+        Crash(message) => {
+            let message = load_symbol(scope, message);
+            build_crash(env, message);
+
+            // unused value (must return a BasicValue)
+            let zero = env.context.i64_type().const_zero();
+            zero.into()
+        }
+
         RuntimeError(error_msg) => {
             throw_exception(env, error_msg);
 
@@ -4477,7 +4692,10 @@ fn build_proc_header<'a, 'ctx, 'env>(
         Linkage::Internal,
     );
 
-    let subprogram = env.new_subprogram(&fn_name);
+    let subprogram = match &env.debug_sources {
+        Some(debug_sources) => debug_sources.proc_subprogram(env, &fn_name, symbol, proc),
+        None => env.new_subprogram(&fn_name),
+    };
     fn_val.set_subprogram(subprogram);
 
     if env.exposed_to_host.contains(&symbol) {
@@ -4827,7 +5045,9 @@ pub fn build_proc<'a, 'ctx, 'env>(
 
     builder.position_at_end(entry);
 
-    debug_info_init!(env, fn_val);
+    if env.debug_sources.is_none() {
+        debug_info_init!(env, fn_val);
+    }
 
     // Add args to scope
     for (arg_val, (layout, arg_symbol)) in fn_val.get_param_iter().zip(args) {
@@ -4835,6 +5055,10 @@ pub fn build_proc<'a, 'ctx, 'env>(
         scope.insert(*arg_symbol, (*layout, arg_val));
     }
 
+    if let Some(debug_sources) = &env.debug_sources {
+        debug_sources.enter_proc(env, fn_val, proc.name.name(), proc);
+    }
+
     let body = build_exp_stmt(
         env,
         layout_ids,
@@ -6009,6 +6233,24 @@ fn run_low_level<'a, 'ctx, 'env>(
                 update_mode,
             )
         }
+        DictHashBytes => {
+            // Dict.hashBytesLowLevel : List U8, U64 -> U64
+            debug_assert_eq!(args.len(), 2);
+
+            let bytes = load_symbol(scope, &args[0]).into_struct_value();
+            let seed = load_symbol(scope, &args[1]).into_int_value();
+
+            dict_hash_bytes(env, bytes, seed)
+        }
+        DictHashMix => {
+            // Dict.hashMixLowLevel : U64, U64 -> U64
+            debug_assert_eq!(args.len(), 2);
+
+            let a = load_symbol(scope, &args[0]).into_int_value();
+            let b = load_symbol(scope, &args[1]).into_int_value();
+
+            dict_hash_mix(env, a, b)
+        }
         ListIsUnique => {
             // List.isUnique : List a -> Bool
             debug_assert_eq!(args.len(), 1);
@@ -7898,6 +8140,25 @@ fn throw_exception<'a, 'ctx, 'env>(env: &Env<'a, 'ctx, 'env>, message: &str) {
     builder.build_unreachable();
 }
 
+/// Hands the message of a `crash` to the host's `roc_panic`. The message is a roc `Str`, so it
+/// goes through a builtin that copies it into a null-terminated string first.
+fn build_crash<'a, 'ctx, 'env>(env: &Env<'a, 'ctx, 'env>, message: BasicValueEnum<'ctx>) {
+    match env.target_info.ptr_width() {
+        PtrWidth::Bytes4 => {
+            let (ptr, len) = pass_list_or_string_to_zig_32bit(env, message.into_struct_value());
+
+            call_void_bitcode_fn(env, &[ptr.into(), len.into()], bitcode::STR_CRASH);
+        }
+        PtrWidth::Bytes8 => {
+            let message = pass_string_to_zig_64bit(env, message);
+
+            call_void_bitcode_fn(env, &[message.into()], bitcode::STR_CRASH);
+        }
+    }
+
+    env.builder.build_unreachable();
+}
+
 fn get_foreign_symbol<'a, 'ctx, 'env>(
     env: &Env<'a, 'ctx, 'env>,
     foreign_symbol: roc_module::ident::ForeignSymbol,
@@ -0,0 +1,309 @@
+use crate::llvm::build::{create_entry_block_alloca, Env};
+use inkwell::debug_info::{
+    AsDIScope, DIFile, DIFlags, DIFlagsConstants, DILocation, DISubprogram, DIType,
+    DebugInfoBuilder,
+};
+use inkwell::values::{BasicValueEnum, FunctionValue};
+use roc_builtins::bitcode::FloatWidth;
+use roc_collections::all::MutMap;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_mono::ir::Proc;
+use roc_mono::layout::{Builtin, Layout};
+use roc_region::all::{LineInfo, Region};
+use std::path::PathBuf;
+
+// DWARF base type encodings
+const DW_ATE_BOOLEAN: u32 = 0x02;
+const DW_ATE_FLOAT: u32 = 0x04;
+const DW_ATE_SIGNED: u32 = 0x05;
+const DW_ATE_UNSIGNED: u32 = 0x08;
+
+/// The Roc sources of all modules, and the regions that the statements of all procs came from.
+/// When an `Env` has these, procs get debug info that points at the Roc source,
+/// so they can be stepped through in a debugger.
+pub struct DebugSources<'ctx> {
+    files: MutMap<ModuleId, (DIFile<'ctx>, LineInfo)>,
+    symbol_regions: MutMap<Symbol, Region>,
+}
+
+impl<'ctx> DebugSources<'ctx> {
+    pub fn new<'a, 'p>(
+        dibuilder: &DebugInfoBuilder<'ctx>,
+        sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+        procedures: impl Iterator<Item = &'p Proc<'a>>,
+    ) -> Self
+    where
+        'a: 'p,
+    {
+        let files = sources
+            .iter()
+            .map(|(module_id, (path, src))| {
+                let filename = path.file_name().and_then(|name| name.to_str());
+                let directory = path.parent().and_then(|dir| dir.to_str());
+                let file = dibuilder.create_file(filename.unwrap_or(""), directory.unwrap_or("."));
+
+                (*module_id, (file, LineInfo::new(src)))
+            })
+            .collect();
+
+        let symbol_regions = procedures
+            .flat_map(|proc| proc.symbol_regions.iter().copied())
+            .collect();
+
+        Self {
+            files,
+            symbol_regions,
+        }
+    }
+
+    /// The file, line, and column of a region in the source of a module.
+    /// Like DWARF, lines and columns start at 1.
+    fn location(&self, module_id: ModuleId, region: Region) -> Option<(DIFile<'ctx>, u32, u32)> {
+        let (file, line_info) = self.files.get(&module_id)?;
+        let position = line_info.convert_pos(region.start());
+
+        Some((*file, position.line + 1, position.column + 1))
+    }
+
+    fn debug_location<'a, 'env>(
+        &self,
+        env: &Env<'a, 'ctx, 'env>,
+        fn_val: FunctionValue<'ctx>,
+        line: u32,
+        column: u32,
+    ) -> Option<DILocation<'ctx>> {
+        let subprogram = fn_val.get_subprogram()?;
+
+        Some(env.dibuilder.create_debug_location(
+            env.context,
+            line,
+            column,
+            subprogram.as_debug_info_scope(),
+            /* inlined_at */ None,
+        ))
+    }
+
+    /// Creates the subprogram for a proc.
+    /// Debuggers show it as `Module.function`, the name of the specialization becomes the linkage name.
+    pub fn proc_subprogram<'a, 'env>(
+        &self,
+        env: &Env<'a, 'ctx, 'env>,
+        fn_name: &str,
+        symbol: Symbol,
+        proc: &Proc<'a>,
+    ) -> DISubprogram<'ctx> {
+        let (file, line) = match self.location(symbol.module_id(), proc.region) {
+            Some((file, line, _)) => (file, line),
+            None => (env.compile_unit.get_file(), 0),
+        };
+
+        let name = format!(
+            "{}.{}",
+            env.interns.module_name(symbol.module_id()).as_str(),
+            symbol.as_str(&env.interns)
+        );
+
+        let subroutine_type = env.dibuilder.create_subroutine_type(
+            file,
+            /* return type */ None,
+            /* parameter types */ &[],
+            DIFlags::PUBLIC,
+        );
+
+        env.dibuilder.create_function(
+            /* scope */ file.as_debug_info_scope(),
+            /* func name */ &name,
+            /* linkage_name */ Some(fn_name),
+            /* file */ file,
+            /* line_no */ line,
+            /* DIType */ subroutine_type,
+            /* is_local_to_unit */ true,
+            /* is_definition */ true,
+            /* scope_line */ line,
+            /* flags */ DIFlags::PUBLIC,
+            /* is_optimized */ false,
+        )
+    }
+
+    /// Points the builder at the start of the proc, and describes its arguments to the debugger.
+    /// The builder must be positioned in the entry block of the proc.
+    pub fn enter_proc<'a, 'env>(
+        &self,
+        env: &Env<'a, 'ctx, 'env>,
+        fn_val: FunctionValue<'ctx>,
+        symbol: Symbol,
+        proc: &Proc<'a>,
+    ) {
+        let (line, column) = match self.location(symbol.module_id(), proc.region) {
+            Some((_, line, column)) => (line, column),
+            None => (0, 0),
+        };
+
+        if let Some(loc) = self.debug_location(env, fn_val, line, column) {
+            env.builder.set_current_debug_location(env.context, loc);
+        }
+
+        for (index, (arg_val, (layout, arg_symbol))) in
+            fn_val.get_param_iter().zip(proc.args).enumerate()
+        {
+            let arg_no = index as u32 + 1;
+            self.declare_variable(
+                env,
+                fn_val,
+                *arg_symbol,
+                layout,
+                arg_val,
+                (symbol.module_id(), proc.region),
+                Some(arg_no),
+            );
+        }
+    }
+
+    /// Points the builder at the source of the statement that binds a symbol, if it has one.
+    pub fn enter_let<'a, 'env>(
+        &self,
+        env: &Env<'a, 'ctx, 'env>,
+        fn_val: FunctionValue<'ctx>,
+        symbol: Symbol,
+    ) {
+        if let Some(region) = self.symbol_regions.get(&symbol) {
+            self.enter_region(env, fn_val, symbol.module_id(), *region);
+        }
+    }
+
+    /// Points the builder at a region in the source of a module.
+    pub fn enter_region<'a, 'env>(
+        &self,
+        env: &Env<'a, 'ctx, 'env>,
+        fn_val: FunctionValue<'ctx>,
+        module_id: ModuleId,
+        region: Region,
+    ) {
+        if let Some((_, line, column)) = self.location(module_id, region) {
+            if let Some(loc) = self.debug_location(env, fn_val, line, column) {
+                env.builder.set_current_debug_location(env.context, loc);
+            }
+        }
+    }
+
+    /// Describes a local variable to the debugger, if the symbol was named in the source.
+    pub fn declare_local<'a, 'env>(
+        &self,
+        env: &Env<'a, 'ctx, 'env>,
+        fn_val: FunctionValue<'ctx>,
+        symbol: Symbol,
+        layout: &Layout<'a>,
+        value: BasicValueEnum<'ctx>,
+    ) {
+        // Symbols made up by the compiler are named after a number, there is nothing to show for them.
+        let is_named = !symbol
+            .as_str(&env.interns)
+            .starts_with(|c: char| c.is_ascii_digit());
+
+        if let (true, Some(region)) = (is_named, self.symbol_regions.get(&symbol)) {
+            self.declare_variable(
+                env,
+                fn_val,
+                symbol,
+                layout,
+                value,
+                (symbol.module_id(), *region),
+                None,
+            );
+        }
+    }
+
+    /// Stores the value in a stack slot and declares the slot as the variable.
+    /// This is how frontends describe variables before mem2reg, which keeps the description up to date.
+    /// Only values with a DWARF base type are described.
+    #[allow(clippy::too_many_arguments)]
+    fn declare_variable<'a, 'env>(
+        &self,
+        env: &Env<'a, 'ctx, 'env>,
+        fn_val: FunctionValue<'ctx>,
+        symbol: Symbol,
+        layout: &Layout<'a>,
+        value: BasicValueEnum<'ctx>,
+        (module_id, region): (ModuleId, Region),
+        arg_no: Option<u32>,
+    ) {
+        let ditype = match base_type(env, layout) {
+            Some(ditype) => ditype,
+            None => return,
+        };
+        let (file, line, column) = match self.location(module_id, region) {
+            Some(location) => location,
+            None => return,
+        };
+        let (subprogram, loc, block) = match (
+            fn_val.get_subprogram(),
+            self.debug_location(env, fn_val, line, column),
+            env.builder.get_insert_block(),
+        ) {
+            (Some(subprogram), Some(loc), Some(block)) => (subprogram, loc, block),
+            _ => return,
+        };
+
+        let name = symbol.as_str(&env.interns);
+        let scope = subprogram.as_debug_info_scope();
+        let variable = match arg_no {
+            Some(arg_no) => env.dibuilder.create_parameter_variable(
+                scope,
+                name,
+                arg_no,
+                file,
+                line,
+                ditype,
+                /* always_preserve */ true,
+                DIFlags::PUBLIC,
+            ),
+            None => env.dibuilder.create_auto_variable(
+                scope,
+                name,
+                file,
+                line,
+                ditype,
+                /* always_preserve */ true,
+                DIFlags::PUBLIC,
+                /* align_in_bits */ 0,
+            ),
+        };
+
+        let slot = create_entry_block_alloca(env, fn_val, value.get_type(), name);
+        env.builder.build_store(slot, value);
+        env.dibuilder
+            .insert_declare_at_end(slot, Some(variable), None, loc, block);
+    }
+}
+
+fn base_type<'a, 'ctx, 'env>(
+    env: &Env<'a, 'ctx, 'env>,
+    layout: &Layout<'a>,
+) -> Option<DIType<'ctx>> {
+    let (name, size_in_bits, encoding) = match layout {
+        Layout::Builtin(Builtin::Bool) => ("Bool".to_string(), 8, DW_ATE_BOOLEAN),
+        Layout::Builtin(Builtin::Int(width)) => {
+            let encoding = if width.is_signed() {
+                DW_ATE_SIGNED
+            } else {
+                DW_ATE_UNSIGNED
+            };
+
+            (format!("{:?}", width), width.stack_size() * 8, encoding)
+        }
+        Layout::Builtin(Builtin::Float(width)) => {
+            let name = match width {
+                FloatWidth::F32 => "F32",
+                FloatWidth::F64 => "F64",
+            };
+
+            (name.to_string(), width.stack_size() * 8, DW_ATE_FLOAT)
+        }
+        _ => return None,
+    };
+
+    env.dibuilder
+        .create_basic_type(&name, size_in_bits as u64, encoding, DIFlags::PUBLIC)
+        .ok()
+        .map(|basic_type| basic_type.as_type())
+}
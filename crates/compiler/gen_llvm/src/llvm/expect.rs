@@ -120,6 +120,15 @@ pub(crate) fn clone_to_shared_memory<'a, 'ctx, 'env>(
 
     offset = write_header(env, original_ptr, offset, condition, region);
 
+    // reserve space for the offset where this frame ends, so that a reader can find the next one
+    let frame_end_slot = offset;
+
+    let ptr_width = env
+        .ptr_int()
+        .const_int(env.target_info.ptr_size() as _, false);
+
+    offset = env.builder.build_int_add(offset, ptr_width, "offset");
+
     let after_header = offset;
 
     let space_for_offsets = env
@@ -167,14 +176,12 @@ pub(crate) fn clone_to_shared_memory<'a, 'ctx, 'env>(
         for lookup_start in lookup_starts {
             build_copy(env, original_ptr, offset, lookup_start.into());
 
-            let ptr_width = env
-                .ptr_int()
-                .const_int(env.target_info.ptr_size() as _, false);
-
             offset = env.builder.build_int_add(offset, ptr_width, "offset")
         }
     }
 
+    build_copy(env, original_ptr, frame_end_slot, offset.into());
+
     let one = env.ptr_int().const_int(1, false);
     let new_count = env.builder.build_int_add(count, one, "inc");
     write_state(env, original_ptr, new_count, offset)
@@ -2,22 +2,23 @@ use bitvec::vec::BitVec;
 use bumpalo::collections::{String, Vec};
 
 use code_builder::Align;
-use roc_builtins::bitcode::{FloatWidth, IntWidth};
+use roc_builtins::bitcode::{self, FloatWidth, IntWidth};
 use roc_collections::all::MutMap;
 use roc_error_macros::internal_error;
 use roc_module::low_level::{LowLevel, LowLevelWrapperType};
 use roc_module::symbol::{Interns, Symbol};
 use roc_mono::code_gen_help::{CodeGenHelp, HelperOp, REFCOUNT_MAX};
 use roc_mono::ir::{
-    BranchInfo, CallType, Expr, JoinPointId, ListLiteralElement, Literal, ModifyRc, Param, Proc,
-    ProcLayout, Stmt,
+    BranchInfo, Call, CallType, Expr, JoinPointId, ListLiteralElement, Literal, ModifyRc, Param,
+    Proc, ProcLayout, Stmt,
 };
 use roc_mono::layout::{Builtin, Layout, LayoutIds, TagIdIntType, UnionLayout};
+use roc_region::all::Region;
 use roc_std::RocDec;
 
-use crate::layout::{CallConv, ReturnMethod, WasmLayout};
+use crate::layout::{CallConv, ReturnMethod, StackMemoryFormat, WasmLayout};
 use crate::low_level::{call_higher_order_lowlevel, LowLevelCall};
-use crate::storage::{AddressValue, Storage, StoredValue, StoredVarKind};
+use crate::storage::{AddressValue, StackMemoryLocation, Storage, StoredValue, StoredVarKind};
 use crate::wasm_module::linking::{DataSymbol, WasmObjectSymbol};
 use crate::wasm_module::sections::{
     ConstExpr, DataMode, DataSegment, Export, Global, GlobalType, Import, ImportDesc, Limits,
@@ -65,9 +66,13 @@ pub struct WasmBackend<'a> {
     pub code_builder: CodeBuilder<'a>,
     pub storage: Storage<'a>,
 
+    /// how the current procedure returns its value
+    return_method: ReturnMethod,
     /// how many blocks deep are we (used for jumps)
     block_depth: u32,
     joinpoint_label_map: MutMap<JoinPointId, (u32, Vec<'a, StoredValue>)>,
+    /// source regions of the named values in the current procedure
+    symbol_regions: &'a [(Symbol, Region)],
 }
 
 impl<'a> WasmBackend<'a> {
@@ -124,8 +129,10 @@ impl<'a> WasmBackend<'a> {
             can_relocate_heap,
 
             // Function-level data
+            return_method: ReturnMethod::NoReturnValue,
             block_depth: 0,
             joinpoint_label_map: MutMap::default(),
+            symbol_regions: &[],
             code_builder: CodeBuilder::new(env.arena),
             storage: Storage::new(env.arena),
         }
@@ -313,7 +320,7 @@ impl<'a> WasmBackend<'a> {
 
         self.module.add_function_signature(Signature {
             param_types: bumpalo::vec![in self.env.arena],
-            ret_type: None,
+            ret_types: bumpalo::vec![in self.env.arena],
         });
 
         self.module.export.append(Export {
@@ -370,6 +377,7 @@ impl<'a> WasmBackend<'a> {
 
         self.storage.clear();
         self.joinpoint_label_map.clear();
+        self.symbol_regions = &[];
         assert_eq!(self.block_depth, 0);
     }
 
@@ -386,6 +394,12 @@ impl<'a> WasmBackend<'a> {
 
         self.append_proc_debug_name(proc.name.name());
 
+        if !proc.region.is_empty() {
+            self.code_builder
+                .set_source_region(proc.name.name().module_id(), proc.region);
+        }
+        self.symbol_regions = proc.symbol_regions;
+
         self.start_proc(proc);
 
         self.stmt(&proc.body);
@@ -398,20 +412,36 @@ impl<'a> WasmBackend<'a> {
         }
     }
 
+    /// The calling convention for a call to a Roc procedure
+    /// The host calls exposed procedures using the C calling convention, so they can't use multi-value returns.
+    fn proc_call_conv(&self, proc_name: Symbol) -> CallConv {
+        if self.env.features.multi_value && !self.env.exposed_to_host.contains(&proc_name) {
+            CallConv::RocMultiValue
+        } else {
+            CallConv::C
+        }
+    }
+
     fn start_proc(&mut self, proc: &Proc<'a>) {
         use ReturnMethod::*;
+        use ValueType::*;
         let ret_layout = WasmLayout::new(&proc.ret_layout);
 
-        let ret_type = match ret_layout.return_method(CallConv::C) {
-            Primitive(ty, _) => Some(ty),
-            NoReturnValue => None,
+        self.return_method = ret_layout.return_method(self.proc_call_conv(proc.name.name()));
+        let ret_types: &[ValueType] = match self.return_method {
+            Primitive(I32, _) => &[I32],
+            Primitive(I64, _) => &[I64],
+            Primitive(F32, _) => &[F32],
+            Primitive(F64, _) => &[F64],
+            NoReturnValue => &[],
             WriteToPointerArg => {
                 self.storage.arg_types.push(PTR_TYPE);
-                None
+                &[]
             }
             ZigPackedStruct => {
                 internal_error!("C calling convention does not return Zig packed structs")
             }
+            MultiValue(value_types) => value_types,
         };
 
         // Create a block so we can exit the function without skipping stack frame "pop" code.
@@ -421,14 +451,14 @@ impl<'a> WasmBackend<'a> {
         self.storage
             .allocate_args(proc.args, &mut self.code_builder, self.env.arena);
 
-        if let Some(ty) = ret_type {
-            let ret_var = self.storage.create_anonymous_local(ty);
-            self.storage.return_var = Some(ret_var);
+        for ty in ret_types {
+            let ret_var = self.storage.create_anonymous_local(*ty);
+            self.storage.return_vars.push(ret_var);
         }
 
         self.module.add_function_signature(Signature {
             param_types: self.storage.arg_types.clone(),
-            ret_type,
+            ret_types: Vec::from_iter_in(ret_types.iter().copied(), self.env.arena),
         });
     }
 
@@ -436,8 +466,8 @@ impl<'a> WasmBackend<'a> {
         // end the block from start_proc, to ensure all paths pop stack memory (if any)
         self.end_block();
 
-        if let Some(ret_var) = self.storage.return_var {
-            self.code_builder.get_local(ret_var);
+        for ret_var in self.storage.return_vars.iter() {
+            self.code_builder.get_local(*ret_var);
         }
 
         // Write local declarations and stack frame push/pop code
@@ -447,6 +477,8 @@ impl<'a> WasmBackend<'a> {
             self.storage.stack_frame_pointer,
         );
 
+        self.append_local_debug_names();
+
         if DEBUG_SETTINGS.storage_map {
             println!("\nStorage:");
             for (sym, storage) in self.storage.symbol_storage_map.iter() {
@@ -455,18 +487,46 @@ impl<'a> WasmBackend<'a> {
         }
     }
 
+    /// Name the function in the "name" section, so that it shows up in browser devtools.
+    /// Procs are generated in the same order as proc_lookup, so the current one is the next code builder.
     fn append_proc_debug_name(&mut self, sym: Symbol) {
-        let proc_index = self
-            .proc_lookup
-            .iter()
-            .position(|ProcLookupData { name, .. }| *name == sym)
-            .unwrap();
+        let proc_index = self.module.code.code_builders.len();
+        let ProcLookupData { name, layout, .. } = &self.proc_lookup[proc_index];
+        debug_assert_eq!(*name, sym);
         let wasm_fn_index = self.fn_index_offset + proc_index as u32;
 
-        let name = String::from_str_in(sym.as_str(self.interns), self.env.arena).into_bump_str();
+        let demangled = self
+            .layout_ids
+            .get_toplevel(sym, layout)
+            .to_demangled_string(sym, self.interns);
+        let name = String::from_str_in(&demangled, self.env.arena).into_bump_str();
         self.module.names.append_function(wasm_fn_index, name);
     }
 
+    /// Name the locals of the current function after the symbols stored in them
+    fn append_local_debug_names(&mut self) {
+        let wasm_fn_index = self.fn_index_offset + self.module.code.code_builders.len() as u32;
+
+        let mut local_names = Vec::with_capacity_in(self.storage.local_types.len(), self.env.arena);
+        for (sym, storage) in self.storage.symbol_storage_map.iter() {
+            let local_id = match storage {
+                StoredValue::Local { local_id, .. } => *local_id,
+                StoredValue::StackMemory {
+                    location: StackMemoryLocation::PointerArg(local_id),
+                    ..
+                } => *local_id,
+                _ => continue,
+            };
+            let name = String::from_str_in(sym.as_str(self.interns), self.env.arena);
+            local_names.push((local_id.0, name.into_bump_str()));
+        }
+
+        local_names.sort_by_key(|(index, _)| *index);
+        local_names.dedup_by_key(|(index, _)| *index);
+
+        self.module.names.append_locals(wasm_fn_index, local_names);
+    }
+
     /// Build a wrapper around a Roc procedure so that it can be called from Zig builtins List.map*
     ///
     /// The generic Zig code passes *pointers* to all of the argument values (e.g. on the heap in a List).
@@ -497,8 +557,14 @@ impl<'a> WasmBackend<'a> {
             x => internal_error!("Higher-order wrapper: invalid return layout {:?}", x),
         };
 
+        // Wasm locals for the wrapper's arguments. Any other locals come after them.
+        self.storage.arg_types = bumpalo::vec![in self.env.arena; I32; wrapper_arg_layouts.len()];
+
+        let inner_name = self.proc_lookup[inner_lookup_idx].name;
+        let inner_return_method = inner_ret_layout.return_method(self.proc_call_conv(inner_name));
+
         let mut n_inner_wasm_args = 0;
-        let ret_type_and_size = match inner_ret_layout.return_method(CallConv::C) {
+        let ret_type_and_size = match inner_return_method {
             ReturnMethod::NoReturnValue => None,
             ReturnMethod::Primitive(ty, size) => {
                 // If the inner function returns a primitive, load the address to store it at
@@ -512,6 +578,8 @@ impl<'a> WasmBackend<'a> {
                 n_inner_wasm_args += 1;
                 None
             }
+            // Stored after the call
+            ReturnMethod::MultiValue(_) => None,
             x => internal_error!("A Roc function should never use ReturnMethod {:?}", x),
         };
 
@@ -561,9 +629,30 @@ impl<'a> WasmBackend<'a> {
 
         // Call the wrapped inner function
         let inner_wasm_fn_index = self.fn_index_offset + inner_lookup_idx as u32;
-        let has_return_val = ret_type_and_size.is_some();
-        self.code_builder
-            .call(inner_wasm_fn_index, n_inner_wasm_args, has_return_val);
+        if let ReturnMethod::MultiValue(value_types) = inner_return_method {
+            let align = match inner_ret_layout {
+                WasmLayout::StackMemory {
+                    alignment_bytes, ..
+                } => Align::from(alignment_bytes),
+                WasmLayout::Primitive(..) => internal_error!("Primitives are not multi-value"),
+            };
+            self.code_builder.call_multi_value(
+                inner_wasm_fn_index,
+                n_inner_wasm_args,
+                value_types.len(),
+            );
+            self.storage.store_multi_value_return(
+                &mut self.code_builder,
+                heap_return_ptr_id,
+                0,
+                align,
+                value_types,
+            );
+        } else {
+            let has_return_val = ret_type_and_size.is_some();
+            self.code_builder
+                .call(inner_wasm_fn_index, n_inner_wasm_args, has_return_val);
+        }
 
         // If the inner function returns a primitive, store it to the address we loaded at the very beginning
         if let Some((ty, size)) = ret_type_and_size {
@@ -580,12 +669,13 @@ impl<'a> WasmBackend<'a> {
             }
         }
 
-        // Write empty function header (local variables array with zero length)
-        self.code_builder.build_fn_header_and_footer(&[], 0, None);
+        // Write function header (no locals unless there were multiple return values to store)
+        self.code_builder
+            .build_fn_header_and_footer(&self.storage.local_types, 0, None);
 
         self.module.add_function_signature(Signature {
-            param_types: bumpalo::vec![in self.env.arena; I32; wrapper_arg_layouts.len()],
-            ret_type: None,
+            param_types: self.storage.arg_types.clone(),
+            ret_types: bumpalo::vec![in self.env.arena],
         });
 
         self.append_proc_debug_name(wrapper_name);
@@ -638,7 +728,7 @@ impl<'a> WasmBackend<'a> {
 
         self.module.add_function_signature(Signature {
             param_types: bumpalo::vec![in self.env.arena; I32; 3],
-            ret_type: Some(ValueType::I32),
+            ret_types: bumpalo::vec![in self.env.arena; I32],
         });
 
         self.append_proc_debug_name(wrapper_name);
@@ -709,6 +799,10 @@ impl<'a> WasmBackend<'a> {
 
             Stmt::Expect { .. } => todo!("expect is not implemented in the wasm backend"),
 
+            // There is no host hook for printing yet, so just carry on with the rest of the program
+            Stmt::Dbg { remainder, .. } => self.stmt(remainder),
+
+            Stmt::Crash(message) => self.stmt_crash(*message),
             Stmt::RuntimeError(msg) => self.stmt_runtime_error(msg),
         }
     }
@@ -739,9 +833,27 @@ impl<'a> WasmBackend<'a> {
                 print!("\nlet {:?} = {}", sym, expr.to_pretty(200));
             }
 
-            let kind = match following {
-                Stmt::Ret(ret_sym) if *sym == *ret_sym => StoredVarKind::ReturnValue,
-                _ => StoredVarKind::Variable,
+            if let Some((_, region)) = self.symbol_regions.iter().find(|(s, _)| s == sym) {
+                self.code_builder
+                    .set_source_region(sym.module_id(), *region);
+            }
+
+            let is_returned = matches!(following, Stmt::Ret(ret_sym) if *sym == *ret_sym);
+
+            if is_returned {
+                if let Expr::Call(call) = expr {
+                    if self.try_tail_call(*sym, layout, call) {
+                        return;
+                    }
+                }
+            }
+
+            // Multiple return values are loaded from the stack frame, there's no pointer to write to
+            let kind = if is_returned && !matches!(self.return_method, ReturnMethod::MultiValue(_))
+            {
+                StoredVarKind::ReturnValue
+            } else {
+                StoredVarKind::Variable
             };
 
             self.stmt_let_store_expr(*sym, layout, expr, kind);
@@ -778,6 +890,14 @@ impl<'a> WasmBackend<'a> {
         let storage = self.storage.symbol_storage_map.get(&sym).unwrap();
 
         match storage {
+            StackMemory { .. } if matches!(self.return_method, ReturnMethod::MultiValue(_)) => {
+                // Loads the struct as the same sequence of values as a Zig by-value argument
+                self.storage.load_symbol_zig(&mut self.code_builder, sym);
+                for ret_var in self.storage.return_vars.iter().rev() {
+                    self.code_builder.set_local(*ret_var);
+                }
+            }
+
             StackMemory {
                 location,
                 size,
@@ -804,8 +924,8 @@ impl<'a> WasmBackend<'a> {
 
                 // If we have a return value, store it to the return variable
                 // This avoids complications with block result types when returning from nested blocks
-                if let Some(ret_var) = self.storage.return_var {
-                    self.code_builder.set_local(ret_var);
+                for ret_var in self.storage.return_vars.iter().rev() {
+                    self.code_builder.set_local(*ret_var);
                 }
             }
         }
@@ -989,6 +1109,24 @@ impl<'a> WasmBackend<'a> {
         self.code_builder.unreachable_();
     }
 
+    pub fn stmt_crash(&mut self, message: Symbol) {
+        // The host expects a zero-terminated string, which a Zig builtin makes from the Roc Str
+        // The builtin returns nothing, so the return symbol is never loaded
+        let (num_wasm_args, has_return_val, _) = self.storage.load_symbols_for_call(
+            self.env.arena,
+            &mut self.code_builder,
+            &[message],
+            message,
+            &WasmLayout::new(&Layout::UNIT),
+            CallConv::Zig,
+        );
+        debug_assert!(!has_return_val);
+
+        self.call_host_fn_after_loading_args(bitcode::STR_CRASH, num_wasm_args, false);
+
+        self.code_builder.unreachable_();
+    }
+
     /**********************************************************
 
             EXPRESSIONS
@@ -1035,6 +1173,17 @@ impl<'a> WasmBackend<'a> {
                 index,
             } => self.expr_union_at_index(*structure, *tag_id, union_layout, *index, sym),
 
+            Expr::SetUnionAtIndex {
+                structure,
+                tag_id,
+                union_layout,
+                index,
+                value,
+            } => {
+                self.expr_set_union_at_index(*structure, *tag_id, union_layout, *index, *value);
+                self.expr_struct(sym, layout, storage, &[]);
+            }
+
             Expr::ExprBox { symbol: arg_sym } => self.expr_box(sym, *arg_sym, layout, storage),
 
             Expr::ExprUnbox { symbol: arg_sym } => self.expr_unbox(sym, *arg_sym),
@@ -1266,6 +1415,7 @@ impl<'a> WasmBackend<'a> {
             return self.expr_call_low_level(lowlevel, arguments, ret_sym, ret_layout, ret_storage);
         }
 
+        let call_conv = self.proc_call_conv(func_sym);
         let (num_wasm_args, has_return_val, ret_zig_packed_struct) =
             self.storage.load_symbols_for_call(
                 self.env.arena,
@@ -1273,10 +1423,118 @@ impl<'a> WasmBackend<'a> {
                 arguments,
                 ret_sym,
                 &wasm_layout,
-                CallConv::C,
+                call_conv,
             );
         debug_assert!(!ret_zig_packed_struct);
 
+        let wasm_fn_index = self.roc_proc_fn_index(func_sym, proc_layout);
+
+        if let ReturnMethod::MultiValue(value_types) = wasm_layout.return_method(call_conv) {
+            self.code_builder
+                .call_multi_value(wasm_fn_index, num_wasm_args, value_types.len());
+
+            match ret_storage {
+                StoredValue::StackMemory {
+                    location,
+                    alignment_bytes,
+                    ..
+                } => {
+                    let (to_ptr, to_offset) =
+                        location.local_and_offset(self.storage.stack_frame_pointer);
+                    self.storage.store_multi_value_return(
+                        &mut self.code_builder,
+                        to_ptr,
+                        to_offset,
+                        Align::from(*alignment_bytes),
+                        value_types,
+                    );
+                }
+                _ => {
+                    internal_error!("Multiple return values should always be stored to StackMemory")
+                }
+            }
+        } else {
+            self.code_builder
+                .call(wasm_fn_index, num_wasm_args, has_return_val);
+        }
+    }
+
+    /// Generate a tail call for `let x = f args in ret x`, if the tail-call proposal is enabled.
+    /// Our stack frame is released before the call, since the code at the end of the function won't run.
+    /// Returns false if that's not possible, and a normal call should be generated instead.
+    fn try_tail_call(&mut self, ret_sym: Symbol, ret_layout: &Layout<'a>, call: &Call<'a>) -> bool {
+        if !self.env.features.tail_calls {
+            return false;
+        }
+
+        let (func_sym, proc_layout) = match &call.call_type {
+            CallType::ByName {
+                name,
+                arg_layouts,
+                ret_layout: result,
+                ..
+            } => {
+                let proc_layout = ProcLayout {
+                    arguments: arg_layouts,
+                    result: **result,
+                    captures_niche: name.captures_niche(),
+                };
+                (name.name(), proc_layout)
+            }
+            _ => return false,
+        };
+
+        // Lowlevel wrappers are inlined, so there's no call to make
+        if let LowLevelWrapperType::CanBeReplacedBy(_) = LowLevelWrapperType::from_symbol(func_sym)
+        {
+            return false;
+        }
+
+        // The callee returns its value straight to our caller, so it has to do it the same way we would
+        let call_conv = self.proc_call_conv(func_sym);
+        let wasm_layout = WasmLayout::new(ret_layout);
+        if wasm_layout.return_method(call_conv) != self.return_method {
+            return false;
+        }
+
+        // Structs are passed by reference, so they must not be in the stack frame we're releasing
+        let refers_to_frame = call.arguments.iter().any(|arg| {
+            matches!(
+                self.storage.get(arg),
+                StoredValue::StackMemory {
+                    location: StackMemoryLocation::FrameOffset(_),
+                    format: StackMemoryFormat::DataStructure,
+                    size,
+                    ..
+                } if *size > 0
+            )
+        });
+        if refers_to_frame {
+            return false;
+        }
+
+        // Pass on the return pointer that our caller gave us
+        if self.return_method == ReturnMethod::WriteToPointerArg {
+            self.storage
+                .allocate_var(*ret_layout, ret_sym, StoredVarKind::ReturnValue);
+        }
+
+        let (num_wasm_args, _, _) = self.storage.load_symbols_for_call(
+            self.env.arena,
+            &mut self.code_builder,
+            call.arguments,
+            ret_sym,
+            &wasm_layout,
+            call_conv,
+        );
+
+        let wasm_fn_index = self.roc_proc_fn_index(func_sym, &proc_layout);
+        self.code_builder.return_call(wasm_fn_index, num_wasm_args);
+
+        true
+    }
+
+    fn roc_proc_fn_index(&self, func_sym: Symbol, proc_layout: &ProcLayout<'a>) -> u32 {
         let roc_proc_index = self
             .proc_lookup
             .iter()
@@ -1290,10 +1548,7 @@ impl<'a> WasmBackend<'a> {
                 );
             });
 
-        let wasm_fn_index = self.fn_index_offset + roc_proc_index as u32;
-
-        self.code_builder
-            .call(wasm_fn_index, num_wasm_args, has_return_val);
+        self.fn_index_offset + roc_proc_index as u32
     }
 
     fn expr_call_low_level(
@@ -1817,6 +2072,79 @@ impl<'a> WasmBackend<'a> {
         );
     }
 
+    fn expr_set_union_at_index(
+        &mut self,
+        structure: Symbol,
+        tag_id: TagIdIntType,
+        union_layout: &UnionLayout<'a>,
+        index: u64,
+        value: Symbol,
+    ) {
+        use UnionLayout::*;
+
+        debug_assert!(!union_layout.tag_is_null(tag_id));
+
+        let tag_index = tag_id as usize;
+        let field_layouts = match union_layout {
+            Recursive(tags) => tags[tag_index],
+            NonNullableUnwrapped(layouts) => *layouts,
+            NullableWrapped {
+                other_tags,
+                nullable_id,
+            } => {
+                let index = if tag_index > *nullable_id as usize {
+                    tag_index - 1
+                } else {
+                    tag_index
+                };
+                other_tags[index]
+            }
+            NullableUnwrapped { other_fields, .. } => *other_fields,
+            NonRecursive(_) => {
+                internal_error!(
+                    "Cannot write to a field of non-recursive union {:?}",
+                    structure
+                )
+            }
+        };
+
+        let field_offset: u32 = field_layouts
+            .iter()
+            .take(index as usize)
+            .map(|field_layout| field_layout.stack_size(TARGET_INFO))
+            .sum();
+
+        // Heap-allocated unions are always stored as a pointer in a local
+        let structure_storage = self.storage.get(&structure).to_owned();
+        let stored_with_local = self.storage.ensure_value_has_local(
+            &mut self.code_builder,
+            structure,
+            structure_storage,
+        );
+        let tag_local_id = match stored_with_local {
+            StoredValue::Local { local_id, .. } => local_id,
+            _ => internal_error!("{:?} should be a pointer in a local variable", structure),
+        };
+
+        let ptr_local_id = if union_layout.stores_tag_id_in_pointer(TARGET_INFO) {
+            let masked = self.storage.create_anonymous_local(ValueType::I32);
+            self.code_builder.get_local(tag_local_id);
+            self.code_builder.i32_const(-4); // 11111111...1100
+            self.code_builder.i32_and();
+            self.code_builder.set_local(masked);
+            masked
+        } else {
+            tag_local_id
+        };
+
+        self.storage.copy_value_to_memory(
+            &mut self.code_builder,
+            ptr_local_id,
+            field_offset,
+            value,
+        );
+    }
+
     /*******************************************************************
      * Box
      *******************************************************************/
@@ -14,6 +14,8 @@ pub enum ReturnMethod {
     NoReturnValue,
     /// This layout is returned as a packed struct in an integer. Only used by Zig, not C.
     ZigPackedStruct,
+    /// This layout is returned as several Wasm values, one per 4 or 8 bytes (multi-value proposal)
+    MultiValue(&'static [ValueType]),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -141,6 +143,10 @@ pub enum CallConv {
     /// The calling convention that Zig 0.9 generates for Wasm when we *ask* it
     /// for the .C calling convention, due to bugs in the Zig compiler.
     Zig,
+    /// Like C, but small structs are returned as multiple values instead of via a pointer argument.
+    /// Only used between Roc procedures, when the multi-value proposal is enabled.
+    /// Procedures exposed to the host keep the C calling convention.
+    RocMultiValue,
 }
 
 impl CallConv {
@@ -162,7 +168,7 @@ impl CallConv {
                     return &[];
                 }
                 match self {
-                    CallConv::C => {
+                    CallConv::C | CallConv::RocMultiValue => {
                         &[I32] // Always pass structs by reference (pointer to stack memory)
                     }
 
@@ -187,6 +193,7 @@ impl CallConv {
     pub fn stack_memory_return_method(&self, size: u32, format: StackMemoryFormat) -> ReturnMethod {
         use ReturnMethod::*;
         use StackMemoryFormat::*;
+        use ValueType::*;
 
         match format {
             Int128 | Float128 | Decimal => WriteToPointerArg,
@@ -205,6 +212,15 @@ impl CallConv {
                             WriteToPointerArg
                         }
                     }
+
+                    // Only sizes that can be stored back to memory without writing past the end
+                    CallConv::RocMultiValue => match size {
+                        4 => MultiValue(&[I32]),
+                        8 => MultiValue(&[I64]),
+                        12 => MultiValue(&[I64, I32]),
+                        16 => MultiValue(&[I64, I64]),
+                        _ => WriteToPointerArg,
+                    },
                 }
             }
         }
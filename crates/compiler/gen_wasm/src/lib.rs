@@ -1,6 +1,7 @@
 mod backend;
 mod layout;
 mod low_level;
+mod source_map;
 mod storage;
 pub mod wasm_module;
 
@@ -18,9 +19,11 @@ use roc_mono::code_gen_help::CodeGenHelp;
 use roc_mono::ir::{Proc, ProcLayout};
 use roc_mono::layout::LayoutIds;
 use roc_target::TargetInfo;
+use std::path::PathBuf;
 use wasm_module::parse::ParseError;
 
 use crate::backend::{ProcLookupData, ProcSource, WasmBackend};
+use crate::source_map::{append_source_mapping_url, SourceMap};
 use crate::wasm_module::{Align, CodeBuilder, LocalId, ValueType, WasmModule};
 
 const TARGET_INFO: TargetInfo = TargetInfo::default_wasm32();
@@ -46,12 +49,30 @@ pub struct Env<'a> {
     pub module_id: ModuleId,
     pub exposed_to_host: MutSet<Symbol>,
     pub stack_bytes: u32,
+    pub features: WasmFeatures,
 }
 
 impl Env<'_> {
     pub const DEFAULT_STACK_BYTES: u32 = 1024 * 1024;
 }
 
+/// WebAssembly proposals that the generated code is allowed to use.
+/// They are opt-in, because not every runtime supports them yet.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WasmFeatures {
+    /// Emit `return_call` for calls in tail position, so that mutual recursion runs in constant stack space
+    pub tail_calls: bool,
+    /// Return small structs from Roc procedures as multiple values, instead of writing to a pointer argument
+    pub multi_value: bool,
+}
+
+impl WasmFeatures {
+    pub const ALL: Self = WasmFeatures {
+        tail_calls: true,
+        multi_value: true,
+    };
+}
+
 /// Parse the preprocessed host binary
 /// If successful, the module can be passed to build_app_binary
 pub fn parse_host<'a>(arena: &'a Bump, host_bytes: &[u8]) -> Result<WasmModule<'a>, ParseError> {
@@ -79,6 +100,32 @@ pub fn build_app_binary<'a>(
     buffer
 }
 
+/// Like build_app_binary, but also generate a source map, so that browser devtools show Roc source.
+///   sources          path and source text of each Roc module
+///   wasm_file_name   name of the output file. The source map should be written next to it, as `<wasm_file_name>.map`
+/// Returns the Wasm binary and the source map JSON.
+pub fn build_app_binary_with_source_map<'a>(
+    env: &'a Env<'a>,
+    interns: &'a mut Interns,
+    host_module: WasmModule<'a>,
+    procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    wasm_file_name: &str,
+) -> (std::vec::Vec<u8>, String) {
+    let (mut wasm_module, called_preload_fns, _) =
+        build_app_module(env, interns, host_module, procedures);
+
+    wasm_module.eliminate_dead_code(env.arena, called_preload_fns);
+
+    let mut buffer = std::vec::Vec::with_capacity(wasm_module.size());
+    wasm_module.serialize(&mut buffer);
+
+    let source_map = SourceMap::new(&wasm_module, &buffer, sources);
+    append_source_mapping_url(&mut buffer, &format!("{}.map", wasm_file_name));
+
+    (buffer, source_map.to_json(wasm_file_name))
+}
+
 /// Generate an unserialized Wasm module
 /// Shared by all consumers of gen_wasm: roc_build, roc_repl_wasm, and test_gen
 /// (roc_repl_wasm and test_gen will add more generated code for a wrapper function
@@ -130,6 +130,26 @@ fn symbol_is_signed_int(backend: &WasmBackend<'_>, symbol: Symbol) -> bool {
     layout_is_signed_int(&backend.storage.symbol_layouts[&symbol])
 }
 
+fn layout_int_width(layout: &Layout) -> IntWidth {
+    match layout {
+        Layout::Builtin(Builtin::Int(int_width)) => *int_width,
+        x => internal_error!("Expected an integer layout, found {:?}", x),
+    }
+}
+
+/// The address of a 128-bit number in stack memory
+fn num128_local_and_offset(backend: &WasmBackend<'_>, symbol: Symbol) -> (LocalId, u32) {
+    match backend.storage.get(&symbol) {
+        StoredValue::StackMemory { location, .. } => {
+            location.local_and_offset(backend.storage.stack_frame_pointer)
+        }
+        _ => internal_error!(
+            "128-bit number {:?} should be stored in stack memory",
+            symbol
+        ),
+    }
+}
+
 pub struct LowLevelCall<'a> {
     pub lowlevel: LowLevel,
     pub arguments: &'a [Symbol],
@@ -180,6 +200,43 @@ impl<'a> LowLevelCall<'a> {
         }
     }
 
+    /// Compare two 128-bit numbers with a Zig builtin.
+    /// Leaves the tag id of the Roc ordering on the value stack: EQ = 0, GT = 1, LT = 2
+    fn compare_num128(&self, backend: &mut WasmBackend<'a>) {
+        let width = match backend.storage.symbol_layouts[&self.arguments[0]] {
+            Layout::Builtin(Builtin::Int(width)) => width,
+            // A Dec is an I128 scaled by 10^18, so they have the same ordering
+            Layout::Builtin(Builtin::Decimal) => IntWidth::I128,
+            x => internal_error!("{:?} is not defined for {:?}", self.lowlevel, x),
+        };
+        self.load_args_and_call_zig(backend, &bitcode::NUM_COMPARE[width]);
+    }
+
+    /// Shift a 128-bit integer with a Zig builtin.
+    /// The Roc arguments are the number of bits and then the integer, but Zig takes them the other way round.
+    fn shift_num128(&self, backend: &mut WasmBackend<'a>, name: &'a str) {
+        let (num_wasm_args, has_return_val, _) = backend.storage.load_symbols_for_call(
+            backend.env.arena,
+            &mut backend.code_builder,
+            &[self.arguments[1], self.arguments[0]],
+            self.ret_symbol,
+            &WasmLayout::new(&self.ret_layout),
+            CallConv::Zig,
+        );
+        backend.call_host_fn_after_loading_args(name, num_wasm_args, has_return_val);
+    }
+
+    /// Copy a 128-bit number argument to the return value, for casts that don't change the bits
+    fn copy_num128(&self, backend: &mut WasmBackend<'a>) {
+        let (ret_local, ret_offset) = num128_local_and_offset(backend, self.ret_symbol);
+        backend.storage.copy_value_to_memory(
+            &mut backend.code_builder,
+            ret_local,
+            ret_offset,
+            self.arguments[0],
+        );
+    }
+
     /// Wrap an integer that should have less than 32 bits, but is represented in Wasm as i32.
     /// This may seem like deliberately introducing an error!
     /// But we want all targets to behave the same, and hash algos rely on wrapping.
@@ -346,6 +403,10 @@ impl<'a> LowLevelCall<'a> {
 
             ListIsUnique => self.load_args_and_call_zig(backend, bitcode::LIST_IS_UNIQUE),
 
+            // Dict
+            DictHashBytes => self.load_args_and_call_zig(backend, bitcode::DICT_HASH_BYTES),
+            DictHashMix => self.load_args_and_call_zig(backend, bitcode::DICT_HASH_MIX),
+
             ListMap | ListMap2 | ListMap3 | ListMap4 | ListSortWith => {
                 internal_error!("HigherOrder lowlevels should not be handled here")
             }
@@ -1071,6 +1132,11 @@ impl<'a> LowLevelCall<'a> {
                     }
                     F32 => backend.code_builder.f32_gt(),
                     F64 => backend.code_builder.f64_gt(),
+                    I128 | Decimal => {
+                        self.compare_num128(backend);
+                        backend.code_builder.i32_const(1); // GT
+                        backend.code_builder.i32_eq();
+                    }
                     x => todo!("{:?} for {:?}", self.lowlevel, x),
                 }
             }
@@ -1093,6 +1159,11 @@ impl<'a> LowLevelCall<'a> {
                     }
                     F32 => backend.code_builder.f32_ge(),
                     F64 => backend.code_builder.f64_ge(),
+                    I128 | Decimal => {
+                        self.compare_num128(backend);
+                        backend.code_builder.i32_const(2); // LT
+                        backend.code_builder.i32_ne();
+                    }
                     x => todo!("{:?} for {:?}", self.lowlevel, x),
                 }
             }
@@ -1115,6 +1186,11 @@ impl<'a> LowLevelCall<'a> {
                     }
                     F32 => backend.code_builder.f32_lt(),
                     F64 => backend.code_builder.f64_lt(),
+                    I128 | Decimal => {
+                        self.compare_num128(backend);
+                        backend.code_builder.i32_const(2); // LT
+                        backend.code_builder.i32_eq();
+                    }
                     x => todo!("{:?} for {:?}", self.lowlevel, x),
                 }
             }
@@ -1138,6 +1214,11 @@ impl<'a> LowLevelCall<'a> {
                     }
                     F32 => backend.code_builder.f32_le(),
                     F64 => backend.code_builder.f64_le(),
+                    I128 | Decimal => {
+                        self.compare_num128(backend);
+                        backend.code_builder.i32_const(1); // GT
+                        backend.code_builder.i32_ne();
+                    }
                     x => todo!("{:?} for {:?}", self.lowlevel, x),
                 }
             }
@@ -1188,6 +1269,7 @@ impl<'a> LowLevelCall<'a> {
                         backend.code_builder.f64_lt();
                         backend.code_builder.i32_add();
                     }
+                    I128 | Decimal => self.compare_num128(backend),
                     x => todo!("{:?} for {:?}", self.lowlevel, x),
                 }
             }
@@ -1211,6 +1293,10 @@ impl<'a> LowLevelCall<'a> {
                     }
                     F32 => backend.code_builder.f32_div(),
                     F64 => backend.code_builder.f64_div(),
+                    I128 => {
+                        let width = layout_int_width(&self.ret_layout);
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_DIV_TRUNC[width])
+                    }
                     Decimal => self.load_args_and_call_zig(backend, bitcode::DEC_DIV),
                     x => todo!("{:?} for {:?}", self.lowlevel, x),
                 }
@@ -1227,6 +1313,10 @@ impl<'a> LowLevelCall<'a> {
                 match CodeGenNumType::for_symbol(backend, self.arguments[0]) {
                     I32 => backend.code_builder.i32_rem_s(),
                     I64 => backend.code_builder.i64_rem_s(),
+                    I128 => {
+                        let width = layout_int_width(&self.ret_layout);
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_REM[width])
+                    }
                     _ => todo!("{:?} for {:?}", self.lowlevel, self.ret_layout),
                 }
             }
@@ -1326,6 +1416,11 @@ impl<'a> LowLevelCall<'a> {
                         code_builder.get_local(tmp);
                     }
 
+                    I128 => self.load_args_and_call_zig(
+                        backend,
+                        &bitcode::NUM_IS_MULTIPLE_OF[layout_int_width(&layout)],
+                    ),
+
                     _ => panic_ret_type(),
                 }
             }
@@ -1390,6 +1485,11 @@ impl<'a> LowLevelCall<'a> {
                     }
                     F32 => backend.code_builder.f32_abs(),
                     F64 => backend.code_builder.f64_abs(),
+                    I128 => {
+                        let width = layout_int_width(&self.ret_layout);
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_ABS[width])
+                    }
+                    Decimal => self.load_args_and_call_zig(backend, bitcode::DEC_ABS),
                     _ => todo!("{:?} for {:?}", self.lowlevel, self.ret_layout),
                 }
             }
@@ -1423,6 +1523,11 @@ impl<'a> LowLevelCall<'a> {
                     }
                     F32 => backend.code_builder.f32_neg(),
                     F64 => backend.code_builder.f64_neg(),
+                    I128 => {
+                        let width = layout_int_width(&self.ret_layout);
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_NEG[width])
+                    }
+                    Decimal => self.load_args_and_call_zig(backend, bitcode::DEC_NEGATE),
                     _ => todo!("{:?} for {:?}", self.lowlevel, self.ret_layout),
                 }
             }
@@ -1460,9 +1565,14 @@ impl<'a> LowLevelCall<'a> {
                 _ => panic_ret_type(),
             },
             NumToFrac => {
-                self.load_args(backend);
                 let ret_type = CodeGenNumType::from(self.ret_layout);
                 let arg_type = CodeGenNumType::for_symbol(backend, self.arguments[0]);
+                if (ret_type, arg_type) == (Decimal, Decimal) {
+                    self.copy_num128(backend);
+                    return;
+                }
+
+                self.load_args(backend);
                 match (ret_type, arg_type) {
                     (F32, I32) => backend.code_builder.f32_convert_s_i32(),
                     (F32, I64) => backend.code_builder.f32_convert_s_i64(),
@@ -1474,6 +1584,34 @@ impl<'a> LowLevelCall<'a> {
                     (F64, F32) => backend.code_builder.f64_promote_f32(),
                     (F64, F64) => {}
 
+                    (F32, I128) => {
+                        let arg_width =
+                            layout_int_width(&backend.storage.symbol_layouts[&self.arguments[0]]);
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_INT_TO_F32[arg_width]);
+                    }
+                    (F64, I128) => {
+                        let arg_width =
+                            layout_int_width(&backend.storage.symbol_layouts[&self.arguments[0]]);
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_INT_TO_F64[arg_width]);
+                    }
+                    (F32, Decimal) => {
+                        self.load_args_and_call_zig(backend, bitcode::DEC_TO_F64);
+                        backend.code_builder.f32_demote_f64();
+                    }
+                    (F64, Decimal) => self.load_args_and_call_zig(backend, bitcode::DEC_TO_F64),
+
+                    (Decimal, I32 | I64 | I128) => {
+                        let arg_width =
+                            layout_int_width(&backend.storage.symbol_layouts[&self.arguments[0]]);
+                        self.load_args_and_call_zig(backend, &bitcode::DEC_FROM_INT[arg_width]);
+                    }
+                    (Decimal, F32) => {
+                        // The return pointer and the F32 are already loaded
+                        backend.code_builder.f64_promote_f32();
+                        backend.call_host_fn_after_loading_args(bitcode::DEC_FROM_F64, 2, false);
+                    }
+                    (Decimal, F64) => self.load_args_and_call_zig(backend, bitcode::DEC_FROM_F64),
+
                     _ => todo!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
                 }
             }
@@ -1491,7 +1629,7 @@ impl<'a> LowLevelCall<'a> {
                 let width = match ret_type {
                     CodeGenNumType::I32 => IntWidth::I32,
                     CodeGenNumType::I64 => IntWidth::I64,
-                    CodeGenNumType::I128 => todo!("{:?} for I128", self.lowlevel),
+                    CodeGenNumType::I128 => layout_int_width(&self.ret_layout),
                     _ => internal_error!("Invalid return type for round: {:?}", ret_type),
                 };
 
@@ -1526,7 +1664,24 @@ impl<'a> LowLevelCall<'a> {
                     (I32, F64) => backend.code_builder.i32_trunc_s_f64(),
                     (I64, F32) => backend.code_builder.i64_trunc_s_f32(),
                     (I64, F64) => backend.code_builder.i64_trunc_s_f64(),
-                    (I128, _) => todo!("{:?} for I128", self.lowlevel),
+                    // The float is already a whole number, so rounding it just converts it.
+                    // The return pointer was loaded before the float.
+                    (I128, F32) => {
+                        let width = layout_int_width(&self.ret_layout);
+                        backend.call_host_fn_after_loading_args(
+                            &bitcode::NUM_ROUND_F32[width],
+                            2,
+                            false,
+                        );
+                    }
+                    (I128, F64) => {
+                        let width = layout_int_width(&self.ret_layout);
+                        backend.call_host_fn_after_loading_args(
+                            &bitcode::NUM_ROUND_F64[width],
+                            2,
+                            false,
+                        );
+                    }
                     _ => panic_ret_type(),
                 }
             }
@@ -1542,7 +1697,7 @@ impl<'a> LowLevelCall<'a> {
                 let width = match ret_type {
                     CodeGenNumType::I32 => IntWidth::I32,
                     CodeGenNumType::I64 => IntWidth::I64,
-                    CodeGenNumType::I128 => todo!("{:?} for I128", self.lowlevel),
+                    CodeGenNumType::I128 => layout_int_width(&self.ret_layout),
                     _ => internal_error!("Invalid return type for pow: {:?}", ret_type),
                 };
 
@@ -1576,7 +1731,10 @@ impl<'a> LowLevelCall<'a> {
                 match CodeGenNumType::from(self.ret_layout) {
                     I32 => backend.code_builder.i32_and(),
                     I64 => backend.code_builder.i64_and(),
-                    I128 => todo!("{:?} for I128", self.lowlevel),
+                    I128 => {
+                        let width = layout_int_width(&self.ret_layout);
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_BITWISE_AND[width])
+                    }
                     _ => panic_ret_type(),
                 }
             }
@@ -1585,7 +1743,10 @@ impl<'a> LowLevelCall<'a> {
                 match CodeGenNumType::from(self.ret_layout) {
                     I32 => backend.code_builder.i32_xor(),
                     I64 => backend.code_builder.i64_xor(),
-                    I128 => todo!("{:?} for I128", self.lowlevel),
+                    I128 => {
+                        let width = layout_int_width(&self.ret_layout);
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_BITWISE_XOR[width])
+                    }
                     _ => panic_ret_type(),
                 }
             }
@@ -1594,20 +1755,29 @@ impl<'a> LowLevelCall<'a> {
                 match CodeGenNumType::from(self.ret_layout) {
                     I32 => backend.code_builder.i32_or(),
                     I64 => backend.code_builder.i64_or(),
-                    I128 => todo!("{:?} for I128", self.lowlevel),
+                    I128 => {
+                        let width = layout_int_width(&self.ret_layout);
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_BITWISE_OR[width])
+                    }
                     _ => panic_ret_type(),
                 }
             }
             NumShiftLeftBy => {
+                let num_type = CodeGenNumType::from(self.ret_layout);
+                if num_type == I128 {
+                    let width = layout_int_width(&self.ret_layout);
+                    self.shift_num128(backend, &bitcode::NUM_SHIFT_LEFT_BY[width]);
+                    return;
+                }
+
                 // Swap order of arguments
                 backend.storage.load_symbols(
                     &mut backend.code_builder,
                     &[self.arguments[1], self.arguments[0]],
                 );
-                match CodeGenNumType::from(self.ret_layout) {
+                match num_type {
                     I32 => backend.code_builder.i32_shl(),
                     I64 => backend.code_builder.i64_shl(),
-                    I128 => todo!("{:?} for I128", self.lowlevel),
                     _ => panic_ret_type(),
                 }
             }
@@ -1652,7 +1822,10 @@ impl<'a> LowLevelCall<'a> {
                             .load_symbols(&mut backend.code_builder, &[num, bits]);
                         backend.code_builder.i64_shr_s();
                     }
-                    I128 => todo!("{:?} for I128", self.lowlevel),
+                    I128 => {
+                        let width = layout_int_width(&self.ret_layout);
+                        self.shift_num128(backend, &bitcode::NUM_SHIFT_RIGHT_BY[width])
+                    }
                     _ => panic_ret_type(),
                 }
             }
@@ -1694,12 +1867,15 @@ impl<'a> LowLevelCall<'a> {
                         );
                         backend.code_builder.i64_shr_u();
                     }
-                    I128 => todo!("{:?} for I128", self.lowlevel),
+                    I128 => {
+                        let width = layout_int_width(&self.ret_layout);
+                        self.shift_num128(backend, &bitcode::NUM_SHIFT_RIGHT_ZF_BY[width])
+                    }
                     _ => panic_ret_type(),
                 }
             }
             NumIntCast => {
-                self.load_args(backend);
+                let arg = self.arguments[0];
                 let arg_layout = backend.storage.symbol_layouts[&self.arguments[0]];
                 let arg_type = CodeGenNumType::from(arg_layout);
                 let arg_width = match arg_layout {
@@ -1714,19 +1890,72 @@ impl<'a> LowLevelCall<'a> {
                 };
 
                 match (ret_type, arg_type) {
-                    (I32, I32) => self.wrap_small_int(backend, ret_width),
+                    (I32, I32) => {
+                        self.load_args(backend);
+                        self.wrap_small_int(backend, ret_width);
+                    }
                     (I32, I64) => {
+                        self.load_args(backend);
                         backend.code_builder.i32_wrap_i64();
                         self.wrap_small_int(backend, ret_width);
                     }
                     (I64, I32) => {
+                        self.load_args(backend);
                         if arg_width.is_signed() {
                             backend.code_builder.i64_extend_s_i32()
                         } else {
                             backend.code_builder.i64_extend_u_i32()
                         }
                     }
-                    (I64, I64) => {}
+                    (I64, I64) => self.load_args(backend),
+
+                    (I128, I128) => self.copy_num128(backend),
+                    (I128, I32 | I64) => {
+                        let (ret_local, ret_offset) =
+                            num128_local_and_offset(backend, self.ret_symbol);
+
+                        // low half
+                        backend.code_builder.get_local(ret_local);
+                        backend
+                            .storage
+                            .load_symbols(&mut backend.code_builder, &[arg]);
+                        if arg_type == I32 {
+                            if arg_width.is_signed() {
+                                backend.code_builder.i64_extend_s_i32()
+                            } else {
+                                backend.code_builder.i64_extend_u_i32()
+                            }
+                        }
+                        backend.code_builder.i64_store(Align::Bytes8, ret_offset);
+
+                        // high half: all ones for negative numbers, otherwise zero
+                        backend.code_builder.get_local(ret_local);
+                        if arg_width.is_signed() {
+                            backend
+                                .storage
+                                .load_symbols(&mut backend.code_builder, &[arg]);
+                            if arg_type == I32 {
+                                backend.code_builder.i64_extend_s_i32();
+                            }
+                            backend.code_builder.i64_const(63);
+                            backend.code_builder.i64_shr_s();
+                        } else {
+                            backend.code_builder.i64_const(0);
+                        }
+                        backend
+                            .code_builder
+                            .i64_store(Align::Bytes8, ret_offset + 8);
+                    }
+                    (I32 | I64, I128) => {
+                        // Truncate to the low half
+                        let (arg_local, arg_offset) = num128_local_and_offset(backend, arg);
+                        backend.code_builder.get_local(arg_local);
+                        backend.code_builder.i64_load(Align::Bytes8, arg_offset);
+                        if ret_type == I32 {
+                            backend.code_builder.i32_wrap_i64();
+                            self.wrap_small_int(backend, ret_width);
+                        }
+                    }
 
                     _ => todo!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
                 }
@@ -1775,6 +2004,19 @@ impl<'a> LowLevelCall<'a> {
                             backend.code_builder.f64_convert_u_i64()
                         }
                     }
+                    (F32, I128) => {
+                        let arg_width = layout_int_width(&arg_layout);
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_INT_TO_F32[arg_width]);
+                    }
+                    (F64, I128) => {
+                        let arg_width = layout_int_width(&arg_layout);
+                        self.load_args_and_call_zig(backend, &bitcode::NUM_INT_TO_F64[arg_width]);
+                    }
+                    (F32, Decimal) => {
+                        self.load_args_and_call_zig(backend, bitcode::DEC_TO_F64);
+                        backend.code_builder.f32_demote_f64();
+                    }
+                    (F64, Decimal) => self.load_args_and_call_zig(backend, bitcode::DEC_TO_F64),
                     _ => todo!("{:?}: {:?} -> {:?}", self.lowlevel, arg_type, ret_type),
                 }
             }
@@ -0,0 +1,242 @@
+//! Source maps for the Wasm module, so that browser devtools can show Roc source
+//! in stack traces and in the debugger.
+//!
+//! https://sourcemaps.info/spec.html
+//!
+//! For WebAssembly, the "generated position" of a mapping is a byte offset into the binary.
+//! Browsers treat the whole module as a single line, with the byte offset as the column.
+
+use std::collections::hash_map::Entry;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use roc_collections::all::MutMap;
+use roc_error_macros::internal_error;
+use roc_module::symbol::ModuleId;
+use roc_region::all::LineInfo;
+
+use crate::wasm_module::parse::Parse;
+use crate::wasm_module::sections::SectionId;
+use crate::wasm_module::serialize::SerialBuffer;
+use crate::wasm_module::WasmModule;
+
+/// Name of the custom section that tells the browser where to find the source map
+const SOURCE_MAPPING_URL: &str = "sourceMappingURL";
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Mapping {
+    wasm_offset: u32,
+    source: u32,
+    line: u32,
+    column: u32,
+}
+
+#[derive(Debug)]
+pub struct SourceMap {
+    sources: Vec<(PathBuf, Box<str>)>,
+    mappings: Vec<Mapping>,
+}
+
+impl SourceMap {
+    /// Map the source regions recorded in the code builders to offsets in the serialized module.
+    /// Regions in modules that we have no source for (e.g. builtins) are left out.
+    pub fn new(
+        module: &WasmModule,
+        module_bytes: &[u8],
+        sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    ) -> Self {
+        let mut source_indices: MutMap<ModuleId, (u32, LineInfo)> = MutMap::default();
+        let mut source_list = Vec::new();
+        let mut mappings = Vec::new();
+
+        let mut fn_offset = code_builders_offset(module, module_bytes);
+        for code_builder in module.code.code_builders.iter() {
+            for (offset, module_id, region) in code_builder.source_offsets() {
+                let (source, line_info) = match source_indices.entry(module_id) {
+                    Entry::Occupied(entry) => entry.into_mut(),
+                    Entry::Vacant(entry) => match sources.get(&module_id) {
+                        Some((path, src)) => {
+                            let index = source_list.len() as u32;
+                            source_list.push((path.clone(), src.clone()));
+                            entry.insert((index, LineInfo::new(src)))
+                        }
+                        None => continue,
+                    },
+                };
+
+                let position = line_info.convert_pos(region.start());
+                mappings.push(Mapping {
+                    wasm_offset: (fn_offset + offset) as u32,
+                    source: *source,
+                    line: position.line,
+                    column: position.column,
+                });
+            }
+            fn_offset += code_builder.size();
+        }
+
+        mappings.sort();
+        mappings.dedup_by_key(|m| m.wasm_offset);
+
+        SourceMap {
+            sources: source_list,
+            mappings,
+        }
+    }
+
+    /// Serialize to the JSON format that browsers understand
+    ///   file_name    name of the Wasm file that this source map describes
+    pub fn to_json(&self, file_name: &str) -> String {
+        let mut json = String::with_capacity(256 + 8 * self.mappings.len());
+
+        json.push_str("{\"version\":3,\"file\":");
+        write_json_string(&mut json, file_name);
+
+        json.push_str(",\"sources\":[");
+        for (i, (path, _)) in self.sources.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            write_json_string(&mut json, &path.to_string_lossy());
+        }
+
+        // Include the source text, so devtools don't need to fetch the .roc files
+        json.push_str("],\"sourcesContent\":[");
+        for (i, (_, src)) in self.sources.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            write_json_string(&mut json, src);
+        }
+
+        json.push_str("],\"names\":[],\"mappings\":\"");
+        self.write_mappings(&mut json);
+        json.push_str("\"}");
+
+        json
+    }
+
+    /// All mappings are on the single "line" of the Wasm binary, separated by commas.
+    /// Each field is encoded relative to its value in the previous mapping.
+    fn write_mappings(&self, json: &mut String) {
+        let mut prev = Mapping {
+            wasm_offset: 0,
+            source: 0,
+            line: 0,
+            column: 0,
+        };
+
+        for (i, mapping) in self.mappings.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            encode_vlq(json, mapping.wasm_offset as i64 - prev.wasm_offset as i64);
+            encode_vlq(json, mapping.source as i64 - prev.source as i64);
+            encode_vlq(json, mapping.line as i64 - prev.line as i64);
+            encode_vlq(json, mapping.column as i64 - prev.column as i64);
+            prev = *mapping;
+        }
+    }
+}
+
+/// Append a custom section telling the browser where to find the source map, relative to the Wasm file.
+/// It goes at the end, so it doesn't move any of the code that the source map refers to.
+pub fn append_source_mapping_url(module_bytes: &mut Vec<u8>, url: &str) {
+    let mut body = Vec::with_capacity(SOURCE_MAPPING_URL.len() + url.len() + 10);
+    for s in [SOURCE_MAPPING_URL, url] {
+        body.encode_u32(s.len() as u32);
+        body.append_slice(s.as_bytes());
+    }
+
+    module_bytes.append_u8(SectionId::Custom as u8);
+    module_bytes.encode_u32(body.len() as u32);
+    module_bytes.append_slice(&body);
+}
+
+/// Offset of the first Roc function in the serialized module.
+/// The code builders are serialized at the end of the Code section, after any functions from the host.
+fn code_builders_offset(module: &WasmModule, module_bytes: &[u8]) -> usize {
+    let builders_size: usize = module.code.code_builders.iter().map(|cb| cb.size()).sum();
+
+    // Skip the magic number and version
+    let mut cursor = 8;
+    while cursor < module_bytes.len() {
+        let id = module_bytes[cursor];
+        cursor += 1;
+        let size = u32::parse((), module_bytes, &mut cursor).unwrap() as usize;
+        cursor += size;
+        if id == SectionId::Code as u8 {
+            return cursor - builders_size;
+        }
+    }
+
+    internal_error!("The Wasm module has no Code section")
+}
+
+/// Base64 variable-length quantity, with the sign in the lowest bit
+fn encode_vlq(buffer: &mut String, value: i64) {
+    let mut vlq = if value < 0 {
+        ((-value as u64) << 1) | 1
+    } else {
+        (value as u64) << 1
+    };
+
+    loop {
+        let mut digit = (vlq & 0x1f) as usize;
+        vlq >>= 5;
+        if vlq > 0 {
+            digit |= 0x20;
+        }
+        buffer.push(BASE64_CHARS[digit] as char);
+        if vlq == 0 {
+            break;
+        }
+    }
+}
+
+fn write_json_string(buffer: &mut String, s: &str) {
+    buffer.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => buffer.push_str("\\\""),
+            '\\' => buffer.push_str("\\\\"),
+            '\n' => buffer.push_str("\\n"),
+            '\r' => buffer.push_str("\\r"),
+            '\t' => buffer.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(buffer, "\\u{:04x}", c as u32).unwrap(),
+            c => buffer.push(c),
+        }
+    }
+    buffer.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn vlq(value: i64) -> String {
+        let mut buffer = String::new();
+        encode_vlq(&mut buffer, value);
+        buffer
+    }
+
+    #[test]
+    fn test_encode_vlq() {
+        assert_eq!(vlq(0), "A");
+        assert_eq!(vlq(1), "C");
+        assert_eq!(vlq(-1), "D");
+        assert_eq!(vlq(15), "e");
+        assert_eq!(vlq(16), "gB");
+        assert_eq!(vlq(123), "2H");
+        assert_eq!(vlq(-123), "3H");
+    }
+
+    #[test]
+    fn test_json_string() {
+        let mut buffer = String::new();
+        write_json_string(&mut buffer, "a\"b\\c\nd");
+        assert_eq!(buffer, r#""a\"b\\c\nd""#);
+    }
+}
@@ -87,7 +87,7 @@ pub enum AddressValue {
 /// including the VM stack, local variables, and linear memory
 #[derive(Debug)]
 pub struct Storage<'a> {
-    pub return_var: Option<LocalId>,
+    pub return_vars: Vec<'a, LocalId>,
     pub arg_types: Vec<'a, ValueType>,
     pub local_types: Vec<'a, ValueType>,
     pub symbol_layouts: MutMap<Symbol, Layout<'a>>,
@@ -99,7 +99,7 @@ pub struct Storage<'a> {
 impl<'a> Storage<'a> {
     pub fn new(arena: &'a Bump) -> Self {
         Storage {
-            return_var: None,
+            return_vars: Vec::with_capacity_in(2, arena),
             arg_types: Vec::with_capacity_in(8, arena),
             local_types: Vec::with_capacity_in(32, arena),
             symbol_layouts: MutMap::default(),
@@ -110,7 +110,7 @@ impl<'a> Storage<'a> {
     }
 
     pub fn clear(&mut self) {
-        self.return_var = None;
+        self.return_vars.clear();
         self.arg_types.clear();
         self.local_types.clear();
         self.symbol_layouts.clear();
@@ -513,6 +513,8 @@ impl<'a> Storage<'a> {
                 symbols_to_load.push(return_symbol);
                 true
             }
+            // The caller stores the values to memory after the call, see store_multi_value_return
+            MultiValue(_) => false,
         };
 
         for arg in arguments {
@@ -536,7 +538,9 @@ impl<'a> Storage<'a> {
 
             for arg in arguments {
                 match call_conv {
-                    CallConv::C => self.load_symbol_ccc(code_builder, *arg),
+                    CallConv::C | CallConv::RocMultiValue => {
+                        self.load_symbol_ccc(code_builder, *arg)
+                    }
                     CallConv::Zig => self.load_symbol_zig(code_builder, *arg),
                 }
             }
@@ -549,6 +553,36 @@ impl<'a> Storage<'a> {
         )
     }
 
+    /// Store a struct that was returned from a call as multiple values.
+    /// The values are on top of the VM stack, above where the address would need to be,
+    /// so we move them to locals first.
+    pub fn store_multi_value_return(
+        &mut self,
+        code_builder: &mut CodeBuilder,
+        to_ptr: LocalId,
+        to_offset: u32,
+        align: Align,
+        value_types: &[ValueType],
+    ) {
+        // The last value is on top of the stack, so store in reverse order
+        for (i, value_type) in value_types.iter().enumerate().rev() {
+            let value_offset: u32 = value_types[..i]
+                .iter()
+                .map(|ty| if *ty == ValueType::I64 { 8 } else { 4 })
+                .sum();
+
+            let value_local = self.create_anonymous_local(*value_type);
+            code_builder.set_local(value_local);
+            code_builder.get_local(to_ptr);
+            code_builder.get_local(value_local);
+            match value_type {
+                ValueType::I32 => code_builder.i32_store(align, to_offset + value_offset),
+                ValueType::I64 => code_builder.i64_store(align, to_offset + value_offset),
+                _ => internal_error!("Structs are only returned as integer values"),
+            }
+        }
+    }
+
     /// Generate code to copy a StoredValue to an arbitrary memory location
     /// (defined by a pointer and offset).
     pub fn copy_value_to_memory(
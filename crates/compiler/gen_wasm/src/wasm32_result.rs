@@ -95,7 +95,7 @@ fn insert_wrapper_metadata<'a>(
 
     module.add_function_signature(Signature {
         param_types: Vec::with_capacity_in(0, arena),
-        ret_type: Some(ValueType::I32),
+        ret_types: bumpalo::vec![in arena; ValueType::I32],
     });
 
     module.export.append(Export {
@@ -3,7 +3,8 @@ use bumpalo::Bump;
 use core::panic;
 use roc_error_macros::internal_error;
 
-use roc_module::symbol::Symbol;
+use roc_module::symbol::{ModuleId, Symbol};
+use roc_region::all::Region;
 
 use super::opcodes::{OpCode, OpCode::*};
 use super::serialize::{SerialBuffer, Serialize};
@@ -182,6 +183,13 @@ pub struct CodeBuilder<'a> {
     /// Relocations for calls to JS imports
     /// When we remove unused imports, the live ones are re-indexed
     import_relocations: Vec<'a, (usize, u32)>,
+
+    /// Code locations of tail calls. The stack frame has to be popped before each of them,
+    /// since the code at the end of the function will never run.
+    tail_calls: Vec<'a, usize>,
+
+    /// Code locations where the code for a region of Roc source starts, for the source map
+    source_regions: Vec<'a, (usize, ModuleId, Region)>,
 }
 
 impl<'a> Serialize for CodeBuilder<'a> {
@@ -209,6 +217,8 @@ impl<'a> CodeBuilder<'a> {
             inner_length: Vec::with_capacity_in(5, arena),
             vm_block_stack,
             import_relocations: Vec::with_capacity_in(0, arena),
+            tail_calls: Vec::with_capacity_in(0, arena),
+            source_regions: Vec::with_capacity_in(0, arena),
         }
     }
 
@@ -237,6 +247,31 @@ impl<'a> CodeBuilder<'a> {
         }
     }
 
+    /// Mark the current code location as the start of a region of Roc source
+    pub fn set_source_region(&mut self, module_id: ModuleId, region: Region) {
+        self.source_regions
+            .push((self.code.len(), module_id, region));
+    }
+
+    /// Source regions, with offsets into the serialized function (including its length)
+    /// Only valid after build_fn_header_and_footer
+    pub fn source_offsets(&self) -> impl Iterator<Item = (usize, ModuleId, Region)> + '_ {
+        let header_len = self.inner_length.len() + self.preamble.len();
+
+        self.source_regions
+            .iter()
+            .map(move |(code_pos, module_id, region)| {
+                let inserted_len: usize = self
+                    .insertions
+                    .iter()
+                    .take_while(|ins| ins.at <= *code_pos)
+                    .map(|ins| ins.end - ins.start)
+                    .sum();
+
+                (header_len + code_pos + inserted_len, *module_id, *region)
+            })
+    }
+
     /**********************************************************
 
         SYMBOLS
@@ -496,6 +531,27 @@ impl<'a> CodeBuilder<'a> {
         self.set_global(STACK_POINTER_GLOBAL_ID);
     }
 
+    /// Insert instruction bytes to release the stack frame before each tail call
+    fn insert_tail_call_stack_frame_pops(&mut self, frame_size: i32, frame_pointer: LocalId) {
+        for i in 0..self.tail_calls.len() {
+            let start = self.insert_bytes.len();
+
+            self.insert_bytes.push(GETLOCAL as u8);
+            self.insert_bytes.encode_u32(frame_pointer.0);
+            self.insert_bytes.push(I32CONST as u8);
+            self.insert_bytes.encode_i32(frame_size);
+            self.insert_bytes.push(I32ADD as u8);
+            self.insert_bytes.push(SETGLOBAL as u8);
+            self.insert_bytes.encode_u32(STACK_POINTER_GLOBAL_ID);
+
+            self.insertions.push(Insertion {
+                at: self.tail_calls[i],
+                start,
+                end: self.insert_bytes.len(),
+            });
+        }
+    }
+
     /// Build the function header: local declarations, stack frame push/pop code, and function length
     /// After this, all bytes have been generated (but not yet serialized) and we know the final size.
     pub fn build_fn_header_and_footer(
@@ -511,6 +567,7 @@ impl<'a> CodeBuilder<'a> {
                 let aligned_size = round_up_to_alignment!(frame_size, FRAME_ALIGNMENT_BYTES);
                 self.build_stack_frame_push(aligned_size, frame_ptr_id);
                 self.build_stack_frame_pop(aligned_size, frame_ptr_id); // footer
+                self.insert_tail_call_stack_frame_pops(aligned_size, frame_ptr_id);
             }
         }
 
@@ -689,22 +746,24 @@ impl<'a> CodeBuilder<'a> {
     instruction_no_args!(return_, RETURN, 0, false);
 
     pub fn call(&mut self, function_index: u32, n_args: usize, has_return_val: bool) {
-        self.call_impl(function_index, n_args, has_return_val, false)
+        self.call_impl(function_index, n_args, has_return_val as usize, false)
     }
 
     pub fn call_import(&mut self, function_index: u32, n_args: usize, has_return_val: bool) {
-        self.call_impl(function_index, n_args, has_return_val, true)
+        self.call_impl(function_index, n_args, has_return_val as usize, true)
+    }
+
+    /// Call a function that returns several values (multi-value proposal)
+    pub fn call_multi_value(&mut self, function_index: u32, n_args: usize, n_results: usize) {
+        self.call_impl(function_index, n_args, n_results, false)
     }
 
     #[inline(always)]
-    fn call_impl(
-        &mut self,
-        function_index: u32,
-        n_args: usize,
-        has_return_val: bool,
-        is_import: bool,
-    ) {
-        self.inst_base(CALL, n_args, has_return_val);
+    fn call_impl(&mut self, function_index: u32, n_args: usize, n_results: usize, is_import: bool) {
+        self.inst_base(CALL, n_args, false);
+        for _ in 0..n_results {
+            self.current_stack_mut().push(Symbol::WASM_TMP);
+        }
 
         if is_import {
             self.import_relocations
@@ -728,6 +787,38 @@ impl<'a> CodeBuilder<'a> {
         );
     }
 
+    /// Call a function and return its result from this one, without growing the call stack
+    /// (tail-call proposal). The callee must have the same result types as this function.
+    pub fn return_call(&mut self, function_index: u32, n_args: usize) {
+        self.tail_calls.push(self.code.len());
+        self.inst_base(RETURNCALL, n_args, false);
+        self.code.encode_padded_u32(function_index);
+
+        log_instruction!(
+            "{:10}\t{}\t{:?}",
+            format!("{:?}", RETURNCALL),
+            function_index,
+            self.vm_block_stack
+        );
+    }
+
+    /// Like `return_call`, but the function index is popped from the value stack
+    /// and looked up in table 0. Roc doesn't use function pointers, but the host might.
+    #[allow(dead_code)]
+    pub fn return_call_indirect(&mut self, signature_index: u32, n_args: usize) {
+        self.tail_calls.push(self.code.len());
+        self.inst_base(RETURNCALLINDIRECT, n_args + 1, false);
+        self.code.encode_u32(signature_index);
+        self.code.encode_u32(0); // table index
+
+        log_instruction!(
+            "{:10}\t{}\t{:?}",
+            format!("{:?}", RETURNCALLINDIRECT),
+            signature_index,
+            self.vm_block_stack
+        );
+    }
+
     instruction_no_args!(drop_, DROP, 1, false);
     instruction_no_args!(select, SELECT, 3, true);
 
@@ -938,3 +1029,164 @@ impl<'a> CodeBuilder<'a> {
     instruction_no_args!(f32_reinterpret_i32, F32REINTERPRETI32, 1, true);
     instruction_no_args!(f64_reinterpret_i64, F64REINTERPRETI64, 1, true);
 }
+
+/*******************************************************************
+ *
+ * Unit tests
+ *
+ *******************************************************************/
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serialize(code_builder: &CodeBuilder<'_>) -> std::vec::Vec<u8> {
+        let mut buffer = std::vec::Vec::with_capacity(code_builder.size());
+        code_builder.serialize(&mut buffer);
+        assert_eq!(buffer.len(), code_builder.size());
+        buffer
+    }
+
+    #[test]
+    fn test_return_call() {
+        let arena = &Bump::new();
+        let mut code_builder = CodeBuilder::new(arena);
+        code_builder.i32_const(1);
+        code_builder.i32_const(2);
+        code_builder.return_call(5, 2);
+        code_builder.build_fn_header_and_footer(&[], 0, None);
+
+        assert_eq!(
+            serialize(&code_builder),
+            [
+                12, // function length
+                0,  // no local declarations
+                I32CONST as u8,
+                1,
+                I32CONST as u8,
+                2,
+                RETURNCALL as u8,
+                0x85, // function index 5, padded for relocation
+                0x80,
+                0x80,
+                0x80,
+                0x00,
+                END as u8,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_return_call_pops_stack_frame() {
+        let arena = &Bump::new();
+        let frame_pointer = LocalId(0);
+        let mut code_builder = CodeBuilder::new(arena);
+        code_builder.i32_const(1);
+        code_builder.return_call(5, 1);
+        code_builder.build_fn_header_and_footer(&[ValueType::I32], 16, Some(frame_pointer));
+
+        let frame_pop = [
+            GETLOCAL as u8,
+            0,
+            I32CONST as u8,
+            16,
+            I32ADD as u8,
+            SETGLOBAL as u8,
+            STACK_POINTER_GLOBAL_ID as u8,
+        ];
+
+        let mut expected = vec![
+            35, // function length
+            1,  // one batch of local declarations
+            1,
+            ValueType::I32 as u8,
+            // stack frame push
+            GETGLOBAL as u8,
+            STACK_POINTER_GLOBAL_ID as u8,
+            I32CONST as u8,
+            16,
+            I32SUB as u8,
+            TEELOCAL as u8,
+            0,
+            SETGLOBAL as u8,
+            STACK_POINTER_GLOBAL_ID as u8,
+            // body
+            I32CONST as u8,
+            1,
+        ];
+        // The frame is released before the tail call, since the footer never runs
+        expected.extend(frame_pop);
+        expected.extend([RETURNCALL as u8, 0x85, 0x80, 0x80, 0x80, 0x00]);
+        expected.extend(frame_pop);
+        expected.push(END as u8);
+
+        assert_eq!(serialize(&code_builder), expected);
+    }
+
+    #[test]
+    fn test_return_call_indirect() {
+        let arena = &Bump::new();
+        let mut code_builder = CodeBuilder::new(arena);
+        code_builder.i32_const(1);
+        code_builder.i32_const(7); // function pointer
+        code_builder.return_call_indirect(3, 1);
+        code_builder.build_fn_header_and_footer(&[], 0, None);
+
+        assert_eq!(
+            serialize(&code_builder),
+            [
+                9,
+                0,
+                I32CONST as u8,
+                1,
+                I32CONST as u8,
+                7,
+                RETURNCALLINDIRECT as u8,
+                3, // signature index
+                0, // table index
+                END as u8,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_call_multi_value() {
+        let arena = &Bump::new();
+        let mut code_builder = CodeBuilder::new(arena);
+        code_builder.i32_const(1);
+        code_builder.call_multi_value(4, 1, 2);
+
+        // Both results are on the VM stack
+        assert_eq!(code_builder.current_stack().len(), 2);
+        code_builder.set_local(LocalId(1));
+        code_builder.set_local(LocalId(0));
+        assert!(code_builder.current_stack().is_empty());
+
+        code_builder.build_fn_header_and_footer(&[ValueType::I64, ValueType::I32], 0, None);
+
+        assert_eq!(
+            serialize(&code_builder),
+            [
+                18,
+                2, // two batches of local declarations
+                1,
+                ValueType::I64 as u8,
+                1,
+                ValueType::I32 as u8,
+                I32CONST as u8,
+                1,
+                CALL as u8,
+                0x84, // function index 4, padded for relocation
+                0x80,
+                0x80,
+                0x80,
+                0x00,
+                SETLOCAL as u8,
+                1,
+                SETLOCAL as u8,
+                0,
+                END as u8,
+            ]
+        );
+    }
+}
@@ -4,6 +4,7 @@ pub mod opcodes;
 pub mod parse;
 pub mod sections;
 pub mod serialize;
+pub mod wat;
 
 use std::iter::repeat;
 
@@ -62,14 +62,14 @@ fn decode_i32(bytes: &[u8]) -> Result<(i32, usize), ()> {
     let mut shift = 0;
     for (i, byte) in bytes.iter().take(MAX_SIZE_ENCODED_U32).enumerate() {
         value |= ((byte & 0x7f) as i32) << shift;
+        shift += 7;
         if (byte & 0x80) == 0 {
             let is_negative = byte & 0x40 != 0;
-            if shift < MAX_SIZE_ENCODED_U32 && is_negative {
+            if shift < 32 && is_negative {
                 value |= -1 << shift;
             }
             return Ok((value, i + 1));
         }
-        shift += 7;
     }
     Err(())
 }
@@ -92,6 +92,41 @@ impl Parse<()> for i32 {
     }
 }
 
+/// Decode a signed 64-bit integer from the provided buffer in LEB-128 format
+/// Return the integer itself and the offset after it ends
+fn decode_i64(bytes: &[u8]) -> Result<(i64, usize), ()> {
+    const MAX_LEN: usize = 10;
+    let mut value = 0;
+    let mut shift = 0;
+    for (i, byte) in bytes.iter().take(MAX_LEN).enumerate() {
+        value |= ((byte & 0x7f) as i64) << shift;
+        shift += 7;
+        if (byte & 0x80) == 0 {
+            let is_negative = byte & 0x40 != 0;
+            if shift < 64 && is_negative {
+                value |= -1 << shift;
+            }
+            return Ok((value, i + 1));
+        }
+    }
+    Err(())
+}
+
+impl Parse<()> for i64 {
+    fn parse(_ctx: (), bytes: &[u8], cursor: &mut usize) -> Result<Self, ParseError> {
+        match decode_i64(&bytes[*cursor..]) {
+            Ok((value, len)) => {
+                *cursor += len;
+                Ok(value)
+            }
+            Err(()) => Err(ParseError {
+                offset: *cursor,
+                message: "Failed to decode i64 as LEB-128".into(),
+            }),
+        }
+    }
+}
+
 impl<'a> Parse<&'a Bump> for &'a str {
     fn parse(arena: &'a Bump, bytes: &[u8], cursor: &mut usize) -> Result<Self, ParseError> {
         let len = u32::parse((), bytes, cursor)?;
@@ -204,7 +239,7 @@ impl SkipBytes for String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::wasm_module::parse::decode_u32;
+    use crate::wasm_module::parse::{decode_i32, decode_i64, decode_u32};
 
     #[test]
     fn test_decode_u32() {
@@ -223,6 +258,42 @@ mod tests {
         assert!(matches!(decode_u32(&[]), Err(_)));
     }
 
+    #[test]
+    fn test_decode_i32() {
+        assert_eq!(decode_i32(&[0]), Ok((0, 1)));
+        assert_eq!(decode_i32(&[0x3f]), Ok((63, 1)));
+        assert_eq!(decode_i32(&[0x7e]), Ok((-2, 1)));
+        assert_eq!(decode_i32(&[0x80, 0x7f]), Ok((-128, 2)));
+        assert_eq!(
+            decode_i32(&[0xff, 0xff, 0xff, 0xff, 0x07]),
+            Ok((i32::MAX, MAX_SIZE_ENCODED_U32))
+        );
+        assert_eq!(
+            decode_i32(&[0x80, 0x80, 0x80, 0x80, 0x78]),
+            Ok((i32::MIN, MAX_SIZE_ENCODED_U32))
+        );
+    }
+
+    #[test]
+    fn test_decode_i64() {
+        assert_eq!(decode_i64(&[0]), Ok((0, 1)));
+        assert_eq!(decode_i64(&[0x3f]), Ok((63, 1)));
+        assert_eq!(decode_i64(&[0x40]), Ok((-64, 1)));
+        assert_eq!(decode_i64(&[0x7f]), Ok((-1, 1)));
+        assert_eq!(decode_i64(&[0x80, 0x01]), Ok((128, 2)));
+        assert_eq!(decode_i64(&[0x80, 0x7f]), Ok((-128, 2)));
+        assert_eq!(
+            decode_i64(&[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x00]),
+            Ok((i64::MAX, 10))
+        );
+        assert_eq!(
+            decode_i64(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x7f]),
+            Ok((i64::MIN, 10))
+        );
+        assert!(decode_i64(&[0x80; 11]).is_err());
+        assert!(decode_i64(&[]).is_err());
+    }
+
     #[test]
     fn test_parse_u32_sequence() {
         let bytes = &[0, 0x80, 0x01, 0xff, 0xff, 0xff, 0xff, 0x0f];
@@ -194,7 +194,8 @@ pub fn update_section_size<T: SerialBuffer>(buffer: &mut T, header_indices: Sect
 #[derive(PartialEq, Eq, Debug)]
 pub struct Signature<'a> {
     pub param_types: Vec<'a, ValueType>,
-    pub ret_type: Option<ValueType>,
+    /// More than one result type requires the multi-value proposal
+    pub ret_types: Vec<'a, ValueType>,
 }
 
 impl Signature<'_> {
@@ -205,7 +206,7 @@ impl<'a> Serialize for Signature<'a> {
     fn serialize<T: SerialBuffer>(&self, buffer: &mut T) {
         buffer.append_u8(Self::SEPARATOR);
         self.param_types.serialize(buffer);
-        self.ret_type.serialize(buffer);
+        self.ret_types.serialize(buffer);
     }
 }
 
@@ -814,7 +815,7 @@ impl ConstExpr {
         Ok(value)
     }
 
-    fn unwrap_i32(&self) -> i32 {
+    pub(super) fn unwrap_i32(&self) -> i32 {
         match self {
             Self::I32(x) => *x,
             _ => internal_error!("Expected ConstExpr to be I32"),
@@ -1462,6 +1463,8 @@ enum NameSubSections {
 
 pub struct NameSection<'a> {
     pub function_names: Vec<'a, (u32, &'a str)>,
+    /// Names of the locals of each function, sorted by function index and then by local index
+    pub local_names: Vec<'a, (u32, Vec<'a, (u32, &'a str)>)>,
 }
 
 impl<'a> NameSection<'a> {
@@ -1469,19 +1472,41 @@ impl<'a> NameSection<'a> {
     const NAME: &'static str = "name";
 
     pub fn size(&self) -> usize {
-        self.function_names
+        let function_names_size: usize = self
+            .function_names
             .iter()
             .map(|(_, s)| MAX_SIZE_ENCODED_U32 + s.len())
-            .sum()
+            .sum();
+
+        let local_names_size: usize = self
+            .local_names
+            .iter()
+            .map(|(_, locals)| {
+                2 * MAX_SIZE_ENCODED_U32
+                    + locals
+                        .iter()
+                        .map(|(_, s)| MAX_SIZE_ENCODED_U32 + s.len())
+                        .sum::<usize>()
+            })
+            .sum();
+
+        function_names_size + local_names_size
     }
 
     pub fn append_function(&mut self, index: u32, name: &'a str) {
         self.function_names.push((index, name));
     }
 
+    pub fn append_locals(&mut self, fn_index: u32, names: Vec<'a, (u32, &'a str)>) {
+        if !names.is_empty() {
+            self.local_names.push((fn_index, names));
+        }
+    }
+
     pub fn empty(arena: &'a Bump) -> Self {
         NameSection {
             function_names: bumpalo::vec![in arena],
+            local_names: bumpalo::vec![in arena],
         }
     }
 
@@ -1507,7 +1532,10 @@ impl<'a> NameSection<'a> {
         let mut function_names = Vec::from_iter_in(names, arena);
         function_names.sort_by_key(|(idx, _name)| *idx);
 
-        NameSection { function_names }
+        NameSection {
+            function_names,
+            local_names: bumpalo::vec![in arena],
+        }
     }
 }
 
@@ -1566,6 +1594,7 @@ impl<'a> Parse<&'a Bump> for NameSection<'a> {
         let count = u32::parse((), module_bytes, cursor)?;
         let mut section = NameSection {
             function_names: Vec::with_capacity_in(count as usize, arena),
+            local_names: bumpalo::vec![in arena],
         };
 
         // Function names
@@ -1599,6 +1628,25 @@ impl<'a> Serialize for NameSection<'a> {
                 (buffer.size() - subsection_start) as u32,
             );
 
+            if !self.local_names.is_empty() {
+                let subsection_id = NameSubSections::LocalNames as u8;
+                subsection_id.serialize(buffer);
+
+                let subsection_size_index = buffer.encode_padded_u32(0);
+                let subsection_start = buffer.size();
+
+                (self.local_names.len() as u32).serialize(buffer);
+                for (fn_index, locals) in self.local_names.iter() {
+                    fn_index.serialize(buffer);
+                    locals.serialize(buffer);
+                }
+
+                buffer.overwrite_padded_u32(
+                    subsection_size_index,
+                    (buffer.size() - subsection_start) as u32,
+                );
+            }
+
             update_section_size(buffer, header_indices);
         }
     }
@@ -1612,6 +1660,13 @@ impl<'a> Debug for NameSection<'a> {
             writeln!(f, "  {:4}: {}", index, name)?;
         }
 
+        for (fn_index, locals) in self.local_names.iter() {
+            writeln!(f, "  locals of {}:", fn_index)?;
+            for (index, name) in locals.iter() {
+                writeln!(f, "    {:4}: {}", index, name)?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -1647,15 +1702,19 @@ mod tests {
         let signatures = [
             Signature {
                 param_types: bumpalo::vec![in arena],
-                ret_type: None,
+                ret_types: bumpalo::vec![in arena],
             },
             Signature {
                 param_types: bumpalo::vec![in arena; I32, I64, F32, F64],
-                ret_type: None,
+                ret_types: bumpalo::vec![in arena],
             },
             Signature {
                 param_types: bumpalo::vec![in arena; I32, I32, I32],
-                ret_type: Some(I32),
+                ret_types: bumpalo::vec![in arena; I32],
+            },
+            Signature {
+                param_types: bumpalo::vec![in arena; I32, I64],
+                ret_types: bumpalo::vec![in arena; I64, I32],
             },
         ];
         let capacity = signatures.len();
@@ -1670,4 +1729,24 @@ mod tests {
         }
         test_assert_types_preload(arena, &section);
     }
+
+    #[test]
+    fn test_name_section() {
+        let arena = &Bump::new();
+        let mut section = NameSection::empty(arena);
+        section.append_function(3, "UserApp.main#1");
+        section.append_function(4, "UserApp.add#2");
+        section.append_locals(4, bumpalo::vec![in arena; (0, "x"), (1, "y")]);
+        section.append_locals(3, bumpalo::vec![in arena]);
+
+        let mut serialized = Vec::with_capacity_in(section.size(), arena);
+        section.serialize(&mut serialized);
+
+        // The host's function names survive, and the local names subsection is skipped
+        let mut cursor = 0;
+        let parsed = NameSection::parse(arena, &serialized, &mut cursor).unwrap();
+        assert_eq!(cursor, serialized.len());
+        assert_eq!(parsed.function_names, section.function_names);
+        assert!(parsed.local_names.is_empty());
+    }
 }
@@ -0,0 +1,980 @@
+//! Print a Wasm binary in the WebAssembly text format, for `roc build --emit=wat`
+//! https://webassembly.github.io/spec/core/text/index.html
+//!
+//! We print from the serialized bytes rather than from a `WasmModule`, since many of its sections
+//! are stored as opaque bytes. This also means we show exactly what went into the output file,
+//! including the functions from the host.
+
+use bumpalo::Bump;
+use roc_collections::all::{MutMap, MutSet};
+
+use super::code_builder::ValueType;
+use super::opcodes::{immediates_for, OpCode, OpImmediates};
+use super::parse::{Parse, ParseError};
+use super::sections::{
+    DataMode, ElementSegment, Export, ExportType, GlobalType, ImportDesc, Limits, SectionId,
+    TableType,
+};
+
+const INDENT: &str = "  ";
+const BLOCK_NO_RESULT: u8 = 0x40;
+
+struct FunctionType {
+    params: Vec<ValueType>,
+    results: Vec<ValueType>,
+}
+
+struct WatPrinter<'a> {
+    arena: &'a Bump,
+    bytes: &'a [u8],
+    /// Content of each non-custom section, by section ID
+    sections: MutMap<u8, std::ops::Range<usize>>,
+    types: Vec<FunctionType>,
+    function_names: MutMap<u32, String>,
+    local_names: MutMap<u32, MutMap<u32, String>>,
+    out: String,
+}
+
+/// Print a Wasm binary in the WebAssembly text format
+pub fn print_wat(arena: &Bump, module_bytes: &[u8]) -> Result<String, ParseError> {
+    let mut printer = WatPrinter::new(arena, module_bytes)?;
+    printer.print_module()?;
+    Ok(printer.out)
+}
+
+impl<'a> WatPrinter<'a> {
+    fn new(arena: &'a Bump, bytes: &'a [u8]) -> Result<Self, ParseError> {
+        if bytes.len() < 8 || bytes[0..4] != *b"\0asm" {
+            return Err(ParseError {
+                offset: 0,
+                message: "This file is not a WebAssembly binary".into(),
+            });
+        }
+
+        let mut printer = WatPrinter {
+            arena,
+            bytes,
+            sections: MutMap::default(),
+            types: Vec::new(),
+            function_names: MutMap::default(),
+            local_names: MutMap::default(),
+            out: String::with_capacity(bytes.len() * 4),
+        };
+
+        let mut cursor = 8;
+        while cursor < bytes.len() {
+            let id = bytes[cursor];
+            cursor += 1;
+            let size = u32::parse((), bytes, &mut cursor)? as usize;
+            let range = cursor..(cursor + size);
+            cursor = range.end;
+
+            if id == SectionId::Custom as u8 {
+                let mut name_cursor = range.start;
+                let name = <&str>::parse(arena, bytes, &mut name_cursor)?;
+                if name == "name" {
+                    printer.parse_names(name_cursor, range.end)?;
+                }
+            } else {
+                printer.sections.insert(id, range);
+            }
+        }
+
+        printer.parse_types()?;
+
+        Ok(printer)
+    }
+
+    /// Function and local names, from the "name" custom section
+    /// https://webassembly.github.io/spec/core/appendix/custom.html#name-section
+    fn parse_names(&mut self, mut cursor: usize, end: usize) -> Result<(), ParseError> {
+        const FUNCTION_NAMES: u8 = 1;
+        const LOCAL_NAMES: u8 = 2;
+
+        while cursor < end {
+            let subsection_id = self.bytes[cursor];
+            cursor += 1;
+            let size = u32::parse((), self.bytes, &mut cursor)? as usize;
+            let subsection_end = cursor + size;
+
+            if subsection_id == FUNCTION_NAMES {
+                let mut used = MutSet::default();
+                let count = u32::parse((), self.bytes, &mut cursor)?;
+                for _ in 0..count {
+                    let index = u32::parse((), self.bytes, &mut cursor)?;
+                    let name = <&str>::parse(self.arena, self.bytes, &mut cursor)?;
+                    self.function_names
+                        .insert(index, unique_identifier(name, index, &mut used));
+                }
+            } else if subsection_id == LOCAL_NAMES {
+                let count = u32::parse((), self.bytes, &mut cursor)?;
+                for _ in 0..count {
+                    let fn_index = u32::parse((), self.bytes, &mut cursor)?;
+                    let mut used = MutSet::default();
+                    let mut locals = MutMap::default();
+                    let local_count = u32::parse((), self.bytes, &mut cursor)?;
+                    for _ in 0..local_count {
+                        let index = u32::parse((), self.bytes, &mut cursor)?;
+                        let name = <&str>::parse(self.arena, self.bytes, &mut cursor)?;
+                        locals.insert(index, unique_identifier(name, index, &mut used));
+                    }
+                    self.local_names.insert(fn_index, locals);
+                }
+            }
+
+            cursor = subsection_end;
+        }
+
+        Ok(())
+    }
+
+    fn parse_types(&mut self) -> Result<(), ParseError> {
+        const FUNCTION_TYPE: u8 = 0x60;
+
+        let mut cursor = match self.section_start(SectionId::Type) {
+            Some(start) => start,
+            None => return Ok(()),
+        };
+        let count = u32::parse((), self.bytes, &mut cursor)?;
+        for _ in 0..count {
+            if self.bytes[cursor] != FUNCTION_TYPE {
+                return Err(ParseError {
+                    offset: cursor,
+                    message: "Type section: expected a function type".into(),
+                });
+            }
+            cursor += 1;
+            let params = self.parse_value_types(&mut cursor)?;
+            let results = self.parse_value_types(&mut cursor)?;
+            self.types.push(FunctionType { params, results });
+        }
+
+        Ok(())
+    }
+
+    fn parse_value_types(&self, cursor: &mut usize) -> Result<Vec<ValueType>, ParseError> {
+        let count = u32::parse((), self.bytes, cursor)?;
+        let value_types = self.bytes[*cursor..][..count as usize]
+            .iter()
+            .map(|byte| ValueType::from(*byte))
+            .collect();
+        *cursor += count as usize;
+        Ok(value_types)
+    }
+
+    fn section_start(&self, id: SectionId) -> Option<usize> {
+        self.sections.get(&(id as u8)).map(|range| range.start)
+    }
+
+    fn function_ref(&self, index: u32) -> String {
+        match self.function_names.get(&index) {
+            Some(name) => name.clone(),
+            None => index.to_string(),
+        }
+    }
+
+    fn local_ref(&self, fn_index: u32, index: u32) -> String {
+        match self.local_names.get(&fn_index).and_then(|l| l.get(&index)) {
+            Some(name) => name.clone(),
+            None => index.to_string(),
+        }
+    }
+
+    /**********************************************************
+
+        SECTIONS
+
+    ***********************************************************/
+
+    fn print_module(&mut self) -> Result<(), ParseError> {
+        push_line(&mut self.out, 0, "(module");
+
+        for (index, ty) in self.types.iter().enumerate() {
+            let text = format!("(type (;{};) (func{}))", index, signature_text(ty, None));
+            push_line(&mut self.out, 1, &text);
+        }
+
+        let imported_fn_count = self.print_imports()?;
+        self.print_functions(imported_fn_count)?;
+        self.print_tables()?;
+        self.print_memories()?;
+        self.print_globals()?;
+        self.print_exports()?;
+        self.print_start()?;
+        self.print_elements()?;
+        self.print_data()?;
+
+        push_line(&mut self.out, 0, ")");
+        Ok(())
+    }
+
+    fn print_imports(&mut self) -> Result<u32, ParseError> {
+        let mut cursor = match self.section_start(SectionId::Import) {
+            Some(start) => start,
+            None => return Ok(0),
+        };
+
+        let mut fn_index = 0;
+        let count = u32::parse((), self.bytes, &mut cursor)?;
+        for _ in 0..count {
+            let module = <&str>::parse(self.arena, self.bytes, &mut cursor)?;
+            let name = <&str>::parse(self.arena, self.bytes, &mut cursor)?;
+            let description = match ImportDesc::parse((), self.bytes, &mut cursor)? {
+                ImportDesc::Func { signature_index } => {
+                    let text = format!(
+                        "(func {} (type {}))",
+                        self.function_ref(fn_index),
+                        signature_index
+                    );
+                    fn_index += 1;
+                    text
+                }
+                ImportDesc::Table { ty } => format!("(table {})", table_type_text(&ty)),
+                ImportDesc::Mem { limits } => format!("(memory {})", limits_text(&limits)),
+                ImportDesc::Global { ty } => format!("(global {})", global_type_text(ty)),
+            };
+            let text = format!(
+                "(import {} {} {})",
+                string_text(module.as_bytes()),
+                string_text(name.as_bytes()),
+                description
+            );
+            push_line(&mut self.out, 1, &text);
+        }
+
+        Ok(fn_index)
+    }
+
+    fn print_functions(&mut self, imported_fn_count: u32) -> Result<(), ParseError> {
+        let (mut fn_cursor, mut code_cursor) = match (
+            self.section_start(SectionId::Function),
+            self.section_start(SectionId::Code),
+        ) {
+            (Some(fn_start), Some(code_start)) => (fn_start, code_start),
+            _ => return Ok(()),
+        };
+
+        let count = u32::parse((), self.bytes, &mut fn_cursor)?;
+        let code_count = u32::parse((), self.bytes, &mut code_cursor)?;
+        if count != code_count {
+            return Err(ParseError {
+                offset: code_cursor,
+                message: format!(
+                    "Function section has {} functions but Code section has {}",
+                    count, code_count
+                ),
+            });
+        }
+
+        for i in 0..count {
+            let fn_index = imported_fn_count + i;
+            let type_index = u32::parse((), self.bytes, &mut fn_cursor)? as usize;
+            let body_size = u32::parse((), self.bytes, &mut code_cursor)? as usize;
+            let body_end = code_cursor + body_size;
+
+            let ty = self.types.get(type_index).ok_or_else(|| ParseError {
+                offset: fn_cursor,
+                message: format!("Invalid type index {}", type_index),
+            })?;
+            let local_names = self.local_names.get(&fn_index);
+            let header = format!(
+                "(func {} (type {}){}",
+                self.function_ref(fn_index),
+                type_index,
+                signature_text(ty, local_names)
+            );
+            let n_params = ty.params.len() as u32;
+            push_line(&mut self.out, 1, &header);
+
+            // Local declarations are run-length encoded
+            let mut local_index = n_params;
+            let n_groups = u32::parse((), self.bytes, &mut code_cursor)?;
+            for _ in 0..n_groups {
+                let n_locals = u32::parse((), self.bytes, &mut code_cursor)?;
+                let value_type = ValueType::from(self.bytes[code_cursor]);
+                code_cursor += 1;
+                for _ in 0..n_locals {
+                    let text = match local_names.and_then(|l| l.get(&local_index)) {
+                        Some(name) => format!("(local {} {})", name, value_type_text(value_type)),
+                        None => format!("(local {})", value_type_text(value_type)),
+                    };
+                    push_line(&mut self.out, 2, &text);
+                    local_index += 1;
+                }
+            }
+
+            self.print_instructions(fn_index, code_cursor, body_end)?;
+            push_line(&mut self.out, 1, ")");
+
+            code_cursor = body_end;
+        }
+
+        Ok(())
+    }
+
+    fn print_tables(&mut self) -> Result<(), ParseError> {
+        let mut cursor = match self.section_start(SectionId::Table) {
+            Some(start) => start,
+            None => return Ok(()),
+        };
+        let count = u32::parse((), self.bytes, &mut cursor)?;
+        for index in 0..count {
+            let ty = TableType::parse((), self.bytes, &mut cursor)?;
+            let text = format!("(table (;{};) {})", index, table_type_text(&ty));
+            push_line(&mut self.out, 1, &text);
+        }
+        Ok(())
+    }
+
+    fn print_memories(&mut self) -> Result<(), ParseError> {
+        let mut cursor = match self.section_start(SectionId::Memory) {
+            Some(start) => start,
+            None => return Ok(()),
+        };
+        let count = u32::parse((), self.bytes, &mut cursor)?;
+        for index in 0..count {
+            let limits = Limits::parse((), self.bytes, &mut cursor)?;
+            let text = format!("(memory (;{};) {})", index, limits_text(&limits));
+            push_line(&mut self.out, 1, &text);
+        }
+        Ok(())
+    }
+
+    fn print_globals(&mut self) -> Result<(), ParseError> {
+        let mut cursor = match self.section_start(SectionId::Global) {
+            Some(start) => start,
+            None => return Ok(()),
+        };
+        let count = u32::parse((), self.bytes, &mut cursor)?;
+        for index in 0..count {
+            let ty = GlobalType::parse((), self.bytes, &mut cursor)?;
+            let init = self.const_expr_text(&mut cursor)?;
+            let text = format!("(global (;{};) {} {})", index, global_type_text(ty), init);
+            push_line(&mut self.out, 1, &text);
+        }
+        Ok(())
+    }
+
+    fn print_exports(&mut self) -> Result<(), ParseError> {
+        let mut cursor = match self.section_start(SectionId::Export) {
+            Some(start) => start,
+            None => return Ok(()),
+        };
+        let count = u32::parse((), self.bytes, &mut cursor)?;
+        for _ in 0..count {
+            let export = Export::parse(self.arena, self.bytes, &mut cursor)?;
+            let target = match export.ty {
+                ExportType::Func => format!("(func {})", self.function_ref(export.index)),
+                ExportType::Table => format!("(table {})", export.index),
+                ExportType::Mem => format!("(memory {})", export.index),
+                ExportType::Global => format!("(global {})", export.index),
+            };
+            let text = format!(
+                "(export {} {})",
+                string_text(export.name.as_bytes()),
+                target
+            );
+            push_line(&mut self.out, 1, &text);
+        }
+        Ok(())
+    }
+
+    fn print_start(&mut self) -> Result<(), ParseError> {
+        // The Start section has no count, just a function index
+        if let Some(mut cursor) = self.section_start(SectionId::Start) {
+            let fn_index = u32::parse((), self.bytes, &mut cursor)?;
+            let text = format!("(start {})", self.function_ref(fn_index));
+            push_line(&mut self.out, 1, &text);
+        }
+        Ok(())
+    }
+
+    fn print_elements(&mut self) -> Result<(), ParseError> {
+        let mut cursor = match self.section_start(SectionId::Element) {
+            Some(start) => start,
+            None => return Ok(()),
+        };
+        let count = u32::parse((), self.bytes, &mut cursor)?;
+        for index in 0..count {
+            let segment = ElementSegment::parse(self.arena, self.bytes, &mut cursor)?;
+            let mut text = format!(
+                "(elem (;{};) ({} {}) func",
+                index,
+                OpCode::I32CONST.text_name(),
+                segment.offset.unwrap_i32()
+            );
+            for fn_index in segment.fn_indices.iter() {
+                text.push(' ');
+                text.push_str(&self.function_ref(*fn_index));
+            }
+            text.push(')');
+            push_line(&mut self.out, 1, &text);
+        }
+        Ok(())
+    }
+
+    fn print_data(&mut self) -> Result<(), ParseError> {
+        let mut cursor = match self.section_start(SectionId::Data) {
+            Some(start) => start,
+            None => return Ok(()),
+        };
+        let count = u32::parse((), self.bytes, &mut cursor)?;
+        for index in 0..count {
+            let mode = match DataMode::parse((), self.bytes, &mut cursor)? {
+                DataMode::Active { offset } => format!(
+                    " ({} {})",
+                    OpCode::I32CONST.text_name(),
+                    offset.unwrap_i32()
+                ),
+                DataMode::Passive => String::new(),
+            };
+            let len = u32::parse((), self.bytes, &mut cursor)? as usize;
+            let init = &self.bytes[cursor..][..len];
+            cursor += len;
+
+            let text = format!("(data (;{};){} {})", index, mode, string_text(init));
+            push_line(&mut self.out, 1, &text);
+        }
+        Ok(())
+    }
+
+    /**********************************************************
+
+        INSTRUCTIONS
+
+    ***********************************************************/
+
+    fn print_instructions(
+        &mut self,
+        fn_index: u32,
+        mut cursor: usize,
+        end: usize,
+    ) -> Result<(), ParseError> {
+        let mut depth = 2;
+
+        while cursor < end {
+            let opcode = self.opcode_at(cursor)?;
+
+            // The function body's own `end` is implicit in the text format
+            if opcode == OpCode::END && cursor + 1 == end {
+                break;
+            }
+            if matches!(opcode, OpCode::END | OpCode::ELSE) {
+                depth -= 1;
+            }
+
+            let text = self.instruction_text(fn_index, &mut cursor)?;
+            push_line(&mut self.out, depth, &text);
+
+            if matches!(
+                opcode,
+                OpCode::BLOCK | OpCode::LOOP | OpCode::IF | OpCode::ELSE
+            ) {
+                depth += 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn opcode_at(&self, cursor: usize) -> Result<OpCode, ParseError> {
+        let opcode: OpCode = unsafe { std::mem::transmute(self.bytes[cursor]) };
+        // will return Err if transmute was invalid
+        immediates_for(opcode).map_err(|message| ParseError {
+            message,
+            offset: cursor,
+        })?;
+        Ok(opcode)
+    }
+
+    /// Print one instruction and its immediates, and move the cursor past them
+    fn instruction_text(&self, fn_index: u32, cursor: &mut usize) -> Result<String, ParseError> {
+        use OpCode::*;
+        use OpImmediates::*;
+
+        let opcode = self.opcode_at(*cursor)?;
+        *cursor += 1;
+
+        let mut text = String::from(opcode.text_name());
+        let bytes = self.bytes;
+
+        match immediates_for(opcode).unwrap() {
+            NoImmediate => {}
+            Byte1 => {
+                let byte = bytes[*cursor];
+                *cursor += 1;
+                if matches!(opcode, BLOCK | LOOP | IF) && byte != BLOCK_NO_RESULT {
+                    text.push_str(&format!(" (result {})", value_type_text(byte.into())));
+                }
+            }
+            Bytes4 => {
+                let value = f32::from_le_bytes(bytes[*cursor..][..4].try_into().unwrap());
+                *cursor += 4;
+                text.push(' ');
+                text.push_str(&float_text(value as f64, value.is_nan()));
+            }
+            Bytes8 => {
+                let value = f64::from_le_bytes(bytes[*cursor..][..8].try_into().unwrap());
+                *cursor += 8;
+                text.push(' ');
+                text.push_str(&float_text(value, value.is_nan()));
+            }
+            Leb32x1 => {
+                let immediate = match opcode {
+                    I32CONST => i32::parse((), bytes, cursor)?.to_string(),
+                    CALL | RETURNCALL => self.function_ref(u32::parse((), bytes, cursor)?),
+                    GETLOCAL | SETLOCAL | TEELOCAL => {
+                        self.local_ref(fn_index, u32::parse((), bytes, cursor)?)
+                    }
+                    _ => u32::parse((), bytes, cursor)?.to_string(),
+                };
+                text.push(' ');
+                text.push_str(&immediate);
+            }
+            Leb64x1 => {
+                let value = i64::parse((), bytes, cursor)?;
+                text.push_str(&format!(" {}", value));
+            }
+            Leb32x2 => {
+                let a = u32::parse((), bytes, cursor)?;
+                let b = u32::parse((), bytes, cursor)?;
+                if matches!(opcode, CALLINDIRECT | RETURNCALLINDIRECT) {
+                    // type index, then table index. There's only one table.
+                    text.push_str(&format!(" (type {})", a));
+                } else {
+                    // memory access: log2 of the alignment, then the offset
+                    if b != 0 {
+                        text.push_str(&format!(" offset={}", b));
+                    }
+                    text.push_str(&format!(" align={}", 1u32 << a));
+                }
+            }
+            BrTable => {
+                // The default label comes after the others
+                let n_labels = 1 + u32::parse((), bytes, cursor)?;
+                for _ in 0..n_labels {
+                    text.push_str(&format!(" {}", u32::parse((), bytes, cursor)?));
+                }
+            }
+        }
+
+        Ok(text)
+    }
+
+    /// Constant expressions initialise globals and data segments
+    fn const_expr_text(&self, cursor: &mut usize) -> Result<String, ParseError> {
+        let mut text = String::new();
+        while self.bytes[*cursor] != OpCode::END as u8 {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push('(');
+            text.push_str(&self.instruction_text(0, cursor)?);
+            text.push(')');
+        }
+        *cursor += 1;
+        Ok(text)
+    }
+}
+
+fn push_line(out: &mut String, depth: usize, text: &str) {
+    for _ in 0..depth {
+        out.push_str(INDENT);
+    }
+    out.push_str(text);
+    out.push('\n');
+}
+
+/// Turn a name into a valid text format identifier, unique among the names already used
+fn unique_identifier(name: &str, index: u32, used: &mut MutSet<String>) -> String {
+    let is_id_char = |c: char| c.is_ascii_alphanumeric() || "!#$%&'*+-./:<=>?@\\^_`|~".contains(c);
+    let sanitized: String = name
+        .chars()
+        .map(|c| if is_id_char(c) { c } else { '_' })
+        .collect();
+
+    let mut identifier = format!("${}", sanitized);
+    if used.contains(&identifier) {
+        identifier = format!("${}.{}", sanitized, index);
+    }
+    used.insert(identifier.clone());
+    identifier
+}
+
+fn value_type_text(value_type: ValueType) -> &'static str {
+    match value_type {
+        ValueType::I32 => "i32",
+        ValueType::I64 => "i64",
+        ValueType::F32 => "f32",
+        ValueType::F64 => "f64",
+    }
+}
+
+/// Params and results of a function. Named params have to be declared one by one.
+fn signature_text(ty: &FunctionType, local_names: Option<&MutMap<u32, String>>) -> String {
+    let mut text = String::new();
+
+    for (index, param) in ty.params.iter().enumerate() {
+        match local_names.and_then(|l| l.get(&(index as u32))) {
+            Some(name) => text.push_str(&format!(" (param {} {})", name, value_type_text(*param))),
+            None => text.push_str(&format!(" (param {})", value_type_text(*param))),
+        }
+    }
+
+    if !ty.results.is_empty() {
+        text.push_str(" (result");
+        for result in ty.results.iter() {
+            text.push(' ');
+            text.push_str(value_type_text(*result));
+        }
+        text.push(')');
+    }
+
+    text
+}
+
+fn limits_text(limits: &Limits) -> String {
+    match limits {
+        Limits::Min(min) => min.to_string(),
+        Limits::MinMax(min, max) => format!("{} {}", min, max),
+    }
+}
+
+fn table_type_text(ty: &TableType) -> String {
+    format!("{} funcref", limits_text(&ty.limits))
+}
+
+fn global_type_text(ty: GlobalType) -> String {
+    if ty.is_mutable {
+        format!("(mut {})", value_type_text(ty.value_type))
+    } else {
+        value_type_text(ty.value_type).to_string()
+    }
+}
+
+fn float_text(value: f64, is_nan: bool) -> String {
+    if is_nan {
+        "nan".into()
+    } else if value.is_infinite() {
+        if value > 0.0 { "inf" } else { "-inf" }.into()
+    } else {
+        format!("{:?}", value)
+    }
+}
+
+/// A string literal. Anything other than printable ASCII is written as hex escapes.
+fn string_text(bytes: &[u8]) -> String {
+    let mut text = String::with_capacity(bytes.len() + 2);
+    text.push('"');
+    for byte in bytes {
+        match byte {
+            b'"' | b'\\' => text.push_str(&format!("\\{}", *byte as char)),
+            0x20..=0x7e => text.push(*byte as char),
+            _ => text.push_str(&format!("\\{:02x}", byte)),
+        }
+    }
+    text.push('"');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wasm_module::serialize::{SerialBuffer, Serialize};
+
+    #[test]
+    fn test_print_wat() {
+        let arena = &Bump::new();
+        let mut bytes = std::vec::Vec::new();
+        bytes.extend_from_slice(b"\0asm\x01\0\0\0");
+
+        // (type (func (param i32) (result i32)))
+        bytes.extend_from_slice(&[SectionId::Type as u8, 6, 1, 0x60, 1, 0x7f, 1, 0x7f]);
+        // one function of type 0
+        bytes.extend_from_slice(&[SectionId::Function as u8, 2, 1, 0]);
+        // (export "double" (func 0))
+        bytes.extend_from_slice(&[SectionId::Export as u8, 10, 1, 6]);
+        bytes.extend_from_slice(b"double");
+        bytes.extend_from_slice(&[ExportType::Func as u8, 0]);
+
+        // local.get 0, i32.const -2, i32.mul, end
+        let code = [
+            OpCode::GETLOCAL as u8,
+            0,
+            OpCode::I32CONST as u8,
+            0x7e,
+            OpCode::I32MUL as u8,
+            OpCode::END as u8,
+        ];
+        bytes.extend_from_slice(&[SectionId::Code as u8, 3 + code.len() as u8, 1]);
+        bytes.extend_from_slice(&[1 + code.len() as u8, 0]);
+        bytes.extend_from_slice(&code);
+
+        // name the function and its argument
+        let mut names = std::vec::Vec::new();
+        "name".serialize(&mut names);
+        names.extend_from_slice(&[1, 9, 1, 0, 6]);
+        names.extend_from_slice(b"double");
+        names.extend_from_slice(&[2, 6, 1, 0, 1, 0, 1, b'x']);
+        bytes.append_u8(SectionId::Custom as u8);
+        bytes.encode_u32(names.len() as u32);
+        bytes.extend_from_slice(&names);
+
+        let wat = print_wat(arena, &bytes).unwrap();
+        assert_eq!(
+            wat,
+            [
+                "(module",
+                "  (type (;0;) (func (param i32) (result i32)))",
+                "  (func $double (type 0) (param $x i32) (result i32)",
+                "    local.get $x",
+                "    i32.const -2",
+                "    i32.mul",
+                "  )",
+                "  (export \"double\" (func $double))",
+                ")",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    /// Build a module from its function types, and the type index and body of each function.
+    /// Other sections go between the Function and Code sections, in the order given.
+    fn module_bytes(
+        types: &[&[u8]],
+        functions: &[(u8, &[u8])],
+        other_sections: &[(SectionId, &[u8])],
+    ) -> std::vec::Vec<u8> {
+        fn section(bytes: &mut std::vec::Vec<u8>, id: SectionId, content: &[u8]) {
+            bytes.append_u8(id as u8);
+            bytes.encode_u32(content.len() as u32);
+            bytes.extend_from_slice(content);
+        }
+
+        let mut bytes = std::vec::Vec::new();
+        bytes.extend_from_slice(b"\0asm\x01\0\0\0");
+
+        let mut type_section = vec![types.len() as u8];
+        for ty in types {
+            type_section.push(0x60);
+            type_section.extend_from_slice(ty);
+        }
+        section(&mut bytes, SectionId::Type, &type_section);
+
+        let mut function_section = vec![functions.len() as u8];
+        function_section.extend(functions.iter().map(|(type_index, _)| *type_index));
+        section(&mut bytes, SectionId::Function, &function_section);
+
+        for (id, content) in other_sections {
+            section(&mut bytes, *id, content);
+        }
+
+        // No local declarations in any of the function bodies
+        let mut code_section = vec![functions.len() as u8];
+        for (_, body) in functions {
+            code_section.encode_u32(1 + body.len() as u32);
+            code_section.push(0);
+            code_section.extend_from_slice(body);
+        }
+        section(&mut bytes, SectionId::Code, &code_section);
+
+        bytes
+    }
+
+    fn print_lines(bytes: &[u8]) -> std::vec::Vec<String> {
+        let arena = &Bump::new();
+        let wat = print_wat(arena, bytes).unwrap();
+        wat.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_print_control_flow() {
+        use OpCode::*;
+        let body = [
+            BLOCK as u8,
+            0x7f,
+            LOOP as u8,
+            BLOCK_NO_RESULT,
+            GETLOCAL as u8,
+            0,
+            BRIF as u8,
+            0,
+            END as u8,
+            GETLOCAL as u8,
+            0,
+            IF as u8,
+            0x7f,
+            I32CONST as u8,
+            1,
+            ELSE as u8,
+            I32CONST as u8,
+            2,
+            END as u8,
+            BR as u8,
+            0,
+            END as u8,
+            GETLOCAL as u8,
+            0,
+            BRTABLE as u8,
+            1,
+            0,
+            0,
+            END as u8,
+        ];
+        let bytes = module_bytes(&[&[1, 0x7f, 1, 0x7f]], &[(0, &body)], &[]);
+
+        assert_eq!(
+            print_lines(&bytes),
+            [
+                "(module",
+                "  (type (;0;) (func (param i32) (result i32)))",
+                "  (func 0 (type 0) (param i32) (result i32)",
+                "    block (result i32)",
+                "      loop",
+                "        local.get 0",
+                "        br_if 0",
+                "      end",
+                "      local.get 0",
+                "      if (result i32)",
+                "        i32.const 1",
+                "      else",
+                "        i32.const 2",
+                "      end",
+                "      br 0",
+                "    end",
+                "    local.get 0",
+                "    br_table 0 0",
+                "  )",
+                ")",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_print_memory_ops() {
+        use OpCode::*;
+        // Alignment is encoded as its log2, and the offset is only printed if nonzero
+        let body = [
+            GETLOCAL as u8,
+            0,
+            GETLOCAL as u8,
+            0,
+            I32LOAD as u8,
+            2,
+            8,
+            I32STORE as u8,
+            2,
+            0,
+            GETLOCAL as u8,
+            0,
+            I64LOAD as u8,
+            3,
+            0x80,
+            0x01,
+            DROP as u8,
+            END as u8,
+        ];
+        let bytes = module_bytes(
+            &[&[1, 0x7f, 0]],
+            &[(0, &body)],
+            &[(SectionId::Memory, &[1, 0, 1])],
+        );
+
+        assert_eq!(
+            print_lines(&bytes),
+            [
+                "(module",
+                "  (type (;0;) (func (param i32)))",
+                "  (func 0 (type 0) (param i32)",
+                "    local.get 0",
+                "    local.get 0",
+                "    i32.load offset=8 align=4",
+                "    i32.store align=4",
+                "    local.get 0",
+                "    i64.load offset=128 align=8",
+                "    drop",
+                "  )",
+                "  (memory (;0;) 1)",
+                ")",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_print_tail_calls() {
+        use OpCode::*;
+        let direct = [GETLOCAL as u8, 0, RETURNCALL as u8, 1, END as u8];
+        let indirect = [
+            GETLOCAL as u8,
+            0,
+            I32CONST as u8,
+            0,
+            RETURNCALLINDIRECT as u8,
+            0,
+            0,
+            END as u8,
+        ];
+        let bytes = module_bytes(
+            &[&[1, 0x7f, 1, 0x7f]],
+            &[(0, &direct), (0, &indirect)],
+            &[
+                (SectionId::Table, &[1, 0x70, 0, 1]),
+                (
+                    SectionId::Element,
+                    &[1, 0, I32CONST as u8, 0, END as u8, 1, 0],
+                ),
+            ],
+        );
+
+        assert_eq!(
+            print_lines(&bytes),
+            [
+                "(module",
+                "  (type (;0;) (func (param i32) (result i32)))",
+                "  (func 0 (type 0) (param i32) (result i32)",
+                "    local.get 0",
+                "    return_call 1",
+                "  )",
+                "  (func 1 (type 0) (param i32) (result i32)",
+                "    local.get 0",
+                "    i32.const 0",
+                "    return_call_indirect (type 0)",
+                "  )",
+                "  (table (;0;) 1 funcref)",
+                "  (elem (;0;) (i32.const 0) func 0)",
+                ")",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_print_multi_value() {
+        use OpCode::*;
+        let pair = [GETLOCAL as u8, 0, I64CONST as u8, 7, END as u8];
+        let call_pair = [I32CONST as u8, 5, CALL as u8, 0, END as u8];
+        let bytes = module_bytes(
+            &[&[1, 0x7f, 2, 0x7f, 0x7e], &[0, 2, 0x7f, 0x7e]],
+            &[(0, &pair), (1, &call_pair)],
+            &[],
+        );
+
+        assert_eq!(
+            print_lines(&bytes),
+            [
+                "(module",
+                "  (type (;0;) (func (param i32) (result i32 i64)))",
+                "  (type (;1;) (func (result i32 i64)))",
+                "  (func 0 (type 0) (param i32) (result i32 i64)",
+                "    local.get 0",
+                "    i64.const 7",
+                "  )",
+                "  (func 1 (type 1) (result i32 i64)",
+                "    i32.const 5",
+                "    call 0",
+                "  )",
+                ")",
+            ]
+        );
+    }
+}
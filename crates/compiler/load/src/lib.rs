@@ -17,8 +17,9 @@ const SKIP_SUBS_CACHE: bool = {
 pub use roc_load_internal::docs;
 pub use roc_load_internal::file::{
     EntryPoint, ExecutionMode, Expectations, LoadConfig, LoadResult, LoadStart, LoadedModule,
-    LoadingProblem, MonomorphizedModule, Phase, Threading,
+    LoadingProblem, MonoPasses, MonomorphizedModule, Phase, Threading,
 };
+pub use roc_load_internal::type_cache::default_type_cache_dir;
 
 #[allow(clippy::too_many_arguments)]
 fn load<'a>(
@@ -49,6 +50,8 @@ pub fn load_single_threaded<'a>(
         exposed_types,
         target_info,
         cached_subs,
+        None,
+        MonoPasses::default(),
         render,
         exec_mode,
     )
@@ -0,0 +1,74 @@
+//! Hashes the compiler sources that the solved types of a module depend on, so that the on-disk
+//! type cache is invalidated whenever the compiler that wrote it changes.
+use std::path::Path;
+use std::process::Command;
+
+/// Directories, relative to this crate, whose contents can change how a module is typechecked
+/// or how its types are laid out in the cache.
+const TYPE_SOURCE_DIRS: &[&str] = &[
+    "src",
+    "../builtins/roc",
+    "../can/src",
+    "../collections/src",
+    "../constrain/src",
+    "../derive/src",
+    "../derive_key/src",
+    "../exhaustive/src",
+    "../late_solve/src",
+    "../module/src",
+    "../parse/src",
+    "../region/src",
+    "../solve/src",
+    "../types/src",
+    "../unify/src",
+];
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+fn main() {
+    let mut hash = FNV_OFFSET_BASIS;
+
+    for dir in TYPE_SOURCE_DIRS {
+        println!("cargo:rerun-if-changed={}", dir);
+        hash_dir(Path::new(dir), &mut hash);
+    }
+
+    // The cached types are raw memory, so their layout also depends on the Rust compiler.
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    if let Ok(output) = Command::new(rustc).arg("--version").output() {
+        fnv1a(&mut hash, &output.stdout);
+    }
+    fnv1a(
+        &mut hash,
+        std::env::var("TARGET").unwrap_or_default().as_bytes(),
+    );
+
+    println!("cargo:rustc-env=ROC_TYPE_CACHE_COMPILER_HASH={:016x}", hash);
+}
+
+fn hash_dir(dir: &Path, hash: &mut u64) {
+    let mut entries: Vec<_> = match std::fs::read_dir(dir) {
+        Ok(entries) => entries.filter_map(Result::ok).map(|e| e.path()).collect(),
+        Err(_) => return,
+    };
+
+    // read_dir makes no promises about the order of its entries
+    entries.sort();
+
+    for path in entries {
+        if path.is_dir() {
+            hash_dir(&path, hash);
+        } else if let Ok(contents) = std::fs::read(&path) {
+            fnv1a(hash, path.to_string_lossy().as_bytes());
+            fnv1a(hash, &contents);
+        }
+    }
+}
+
+fn fnv1a(hash: &mut u64, bytes: &[u8]) {
+    for byte in bytes {
+        *hash ^= *byte as u64;
+        *hash = hash.wrapping_mul(FNV_PRIME);
+    }
+}
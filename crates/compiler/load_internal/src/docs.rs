@@ -1,5 +1,7 @@
 use crate::docs::DocEntry::DetachedDoc;
-use crate::docs::TypeAnnotation::{Apply, BoundVariable, Function, NoTypeAnn, Record, TagUnion};
+use crate::docs::TypeAnnotation::{
+    Apply, BoundVariable, Function, NoTypeAnn, Record, TagUnion, Tuple,
+};
 use crate::file::LoadedModule;
 use roc_can::scope::Scope;
 use roc_module::ident::ModuleName;
@@ -60,6 +62,10 @@ pub enum TypeAnnotation {
         fields: Vec<RecordField>,
         extension: Box<TypeAnnotation>,
     },
+    Tuple {
+        elems: Vec<TypeAnnotation>,
+        extension: Box<TypeAnnotation>,
+    },
     Ability {
         members: Vec<AbilityMember>,
     },
@@ -362,6 +368,22 @@ fn type_to_docs(in_func_type_ann: bool, type_annotation: ast::TypeAnnotation) ->
                 extension: Box::new(extension),
             }
         }
+        ast::TypeAnnotation::Tuple { elems, ext } => {
+            let doc_elems = elems
+                .items
+                .iter()
+                .map(|elem| type_to_docs(in_func_type_ann, elem.value))
+                .collect();
+            let extension = match ext {
+                None => NoTypeAnn,
+                Some(ext_type_ann) => type_to_docs(in_func_type_ann, ext_type_ann.value),
+            };
+
+            Tuple {
+                elems: doc_elems,
+                extension: Box::new(extension),
+            }
+        }
         ast::TypeAnnotation::SpaceBefore(&sub_type_ann, _) => {
             type_to_docs(in_func_type_ann, sub_type_ann)
         }
@@ -18,8 +18,9 @@ use roc_constrain::module::constrain_module;
 use roc_debug_flags::dbg_do;
 #[cfg(debug_assertions)]
 use roc_debug_flags::{
-    ROC_PRINT_IR_AFTER_REFCOUNT, ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SPECIALIZATION,
-    ROC_PRINT_LOAD_LOG,
+    ROC_CHECK_MONO_IR, ROC_PRINT_IR_AFTER_INLINING, ROC_PRINT_IR_AFTER_REFCOUNT,
+    ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SIMPLIFICATION,
+    ROC_PRINT_IR_AFTER_SPECIALIZATION, ROC_PRINT_LOAD_LOG,
 };
 use roc_derive::SharedDerivedModule;
 use roc_error_macros::internal_error;
@@ -58,8 +59,10 @@ use std::str::from_utf8_unchecked;
 use std::sync::Arc;
 use std::{env, fs};
 
+use crate::type_cache::{self, CacheLookup, CachedModule, Fingerprint};
 use crate::work::Dependencies;
 pub use crate::work::Phase;
+pub use roc_mono::ir::MonoPasses;
 
 #[cfg(target_family = "wasm")]
 use crate::wasm_instant::{Duration, Instant};
@@ -123,6 +126,11 @@ pub struct LoadConfig {
     pub render: RenderTarget,
     pub threading: Threading,
     pub exec_mode: ExecutionMode,
+    /// Where to cache the solved types of interface modules between runs.
+    pub type_cache_dir: Option<PathBuf>,
+    /// Optional passes to run over the mono IR once all procs are specialized.
+    /// Only used when generating code, i.e. with [ExecutionMode::Executable] or [ExecutionMode::Test].
+    pub mono_passes: MonoPasses,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -193,6 +201,8 @@ impl Default for ModuleCache<'_> {
             ENCODE,
             DECODE,
             JSON,
+            HASH,
+            INSPECT,
         }
 
         Self {
@@ -364,11 +374,38 @@ fn start_phase<'a>(
                     }
                 }
 
+                if let Some(type_cache_dir) = &state.type_cache_dir {
+                    let dep_fingerprints: Option<Vec<_>> = parsed
+                        .imported_modules
+                        .keys()
+                        .filter(|imported| !imported.is_builtin())
+                        .map(|imported| state.type_fingerprints.get(imported))
+                        .collect();
+
+                    if let Some(dep_fingerprints) = dep_fingerprints {
+                        let fingerprint =
+                            Fingerprint::new(module_id, &module_ids, parsed.src, dep_fingerprints);
+
+                        // Only interface modules are cached; apps and platforms depend on each
+                        // other through `requires` and `provides`, which the fingerprint does not
+                        // capture.
+                        if let ModuleNameEnum::Interface(_) = parsed.module_name {
+                            let lookup = type_cache::lookup(type_cache_dir, fingerprint.clone());
+                            state.type_cache_lookups.insert(module_id, lookup);
+                        }
+
+                        state.type_fingerprints.insert(module_id, fingerprint);
+                    }
+                }
+
                 let skip_constraint_gen = {
                     // Give this its own scope to make sure that the Guard from the lock() is dropped
                     // immediately after contains_key returns
                     state.cached_subs.lock().contains_key(&module_id)
-                };
+                } || matches!(
+                    state.type_cache_lookups.get(&module_id),
+                    Some(CacheLookup::Hit(_))
+                );
 
                 BuildTask::CanonicalizeAndConstrain {
                     parsed,
@@ -399,6 +436,7 @@ fn start_phase<'a>(
                 } = constrained;
 
                 let derived_module = SharedDerivedModule::clone(&state.derived_module);
+                let type_cache = state.type_cache_lookups.remove(&module_id);
 
                 BuildTask::solve_module(
                     module,
@@ -413,6 +451,7 @@ fn start_phase<'a>(
                     dep_idents,
                     declarations,
                     state.cached_subs.clone(),
+                    type_cache,
                     derived_module,
                 )
             }
@@ -721,6 +760,7 @@ pub struct Expectations {
     pub subs: roc_types::subs::Subs,
     pub path: PathBuf,
     pub expectations: VecMap<Region, Vec<(Symbol, Variable)>>,
+    pub dbgs: VecMap<Region, Variable>,
     pub ident_ids: IdentIds,
 }
 
@@ -765,6 +805,7 @@ struct ParsedModule<'a> {
 }
 
 type LocExpects = VecMap<Region, Vec<(Symbol, Variable)>>;
+type LocDbgs = VecMap<Region, Variable>;
 
 /// A message sent out _from_ a worker thread,
 /// representing a result of work done, or a request for further work
@@ -784,6 +825,7 @@ enum Msg<'a> {
         module_timing: ModuleTiming,
         abilities_store: AbilitiesStore,
         loc_expects: LocExpects,
+        loc_dbgs: LocDbgs,
     },
     FinishedAllTypeChecking {
         solved_subs: Solved<Subs>,
@@ -928,6 +970,13 @@ struct State<'a> {
 
     // cached subs (used for builtin modules, could include packages in the future too)
     cached_subs: CachedSubs,
+
+    /// Where solved user modules are cached between runs, if anywhere.
+    type_cache_dir: Option<PathBuf>,
+    type_fingerprints: MutMap<ModuleId, Fingerprint>,
+    type_cache_lookups: MutMap<ModuleId, CacheLookup>,
+
+    mono_passes: MonoPasses,
 }
 
 type CachedSubs = Arc<Mutex<MutMap<ModuleId, (Subs, Vec<(Symbol, Variable)>)>>>;
@@ -945,6 +994,8 @@ impl<'a> State<'a> {
         arc_modules: Arc<Mutex<PackageModuleIds<'a>>>,
         ident_ids_by_module: SharedIdentIdsByModule,
         cached_subs: MutMap<ModuleId, (Subs, Vec<(Symbol, Variable)>)>,
+        type_cache_dir: Option<PathBuf>,
+        mono_passes: MonoPasses,
         render: RenderTarget,
         number_of_workers: usize,
         exec_mode: ExecutionMode,
@@ -953,6 +1004,10 @@ impl<'a> State<'a> {
 
         let dependencies = Dependencies::new(exec_mode.goal_phase());
 
+        if let Some(dir) = &type_cache_dir {
+            type_cache::prune(dir);
+        }
+
         Self {
             root_id,
             root_subs: None,
@@ -976,6 +1031,10 @@ impl<'a> State<'a> {
             timings: MutMap::default(),
             layout_caches: std::vec::Vec::with_capacity(number_of_workers),
             cached_subs: Arc::new(Mutex::new(cached_subs)),
+            type_cache_dir,
+            type_fingerprints: MutMap::default(),
+            type_cache_lookups: MutMap::default(),
+            mono_passes,
             render,
             exec_mode,
             make_specializations_pass: MakeSpecializationsPass::Pass(1),
@@ -995,6 +1054,8 @@ pub struct ModuleTiming {
     pub find_specializations: Duration,
     // indexed by make specializations pass
     pub make_specializations: Vec<Duration>,
+    /// Whether this module's types were read from the type cache instead of being solved.
+    pub type_cache_hit: bool,
     // TODO pub monomorphize: Duration,
     /// Total duration will always be more than the sum of the other fields, due
     /// to things like state lookups in between phases, waiting on other threads, etc.
@@ -1013,6 +1074,7 @@ impl ModuleTiming {
             solve: Duration::default(),
             find_specializations: Duration::default(),
             make_specializations: Vec::with_capacity(2),
+            type_cache_hit: false,
             start_time,
             end_time: start_time, // just for now; we'll overwrite this at the end
         }
@@ -1033,6 +1095,7 @@ impl ModuleTiming {
             solve,
             find_specializations,
             make_specializations,
+            type_cache_hit: _,
             start_time,
             end_time,
         } = self;
@@ -1088,6 +1151,7 @@ enum BuildTask<'a> {
         declarations: Declarations,
         dep_idents: IdentIdsByModule,
         cached_subs: CachedSubs,
+        type_cache: Option<CacheLookup>,
         derived_module: SharedDerivedModule,
     },
     BuildPendingSpecializations {
@@ -1190,6 +1254,8 @@ pub fn load_and_typecheck_str<'a>(
         render,
         threading,
         exec_mode: ExecutionMode::Check,
+        type_cache_dir: None,
+        mono_passes: MonoPasses::default(),
     };
 
     match load(arena, load_start, exposed_types, cached_subs, load_config)? {
@@ -1435,6 +1501,8 @@ pub fn load<'a>(
             exposed_types,
             load_config.target_info,
             cached_subs,
+            load_config.type_cache_dir,
+            load_config.mono_passes,
             load_config.render,
             load_config.exec_mode,
         ),
@@ -1444,6 +1512,8 @@ pub fn load<'a>(
             exposed_types,
             load_config.target_info,
             cached_subs,
+            load_config.type_cache_dir,
+            load_config.mono_passes,
             load_config.render,
             threads,
             load_config.exec_mode,
@@ -1459,6 +1529,8 @@ pub fn load_single_threaded<'a>(
     exposed_types: ExposedByModule,
     target_info: TargetInfo,
     cached_subs: MutMap<ModuleId, (Subs, Vec<(Symbol, Variable)>)>,
+    type_cache_dir: Option<PathBuf>,
+    mono_passes: MonoPasses,
     render: RenderTarget,
     exec_mode: ExecutionMode,
 ) -> Result<LoadResult<'a>, LoadingProblem<'a>> {
@@ -1485,6 +1557,8 @@ pub fn load_single_threaded<'a>(
         arc_modules,
         ident_ids_by_module,
         cached_subs,
+        type_cache_dir,
+        mono_passes,
         render,
         number_of_workers,
         exec_mode,
@@ -1660,6 +1734,8 @@ fn load_multi_threaded<'a>(
     exposed_types: ExposedByModule,
     target_info: TargetInfo,
     cached_subs: MutMap<ModuleId, (Subs, Vec<(Symbol, Variable)>)>,
+    type_cache_dir: Option<PathBuf>,
+    mono_passes: MonoPasses,
     render: RenderTarget,
     available_threads: usize,
     exec_mode: ExecutionMode,
@@ -1702,6 +1778,8 @@ fn load_multi_threaded<'a>(
         arc_modules,
         ident_ids_by_module,
         cached_subs,
+        type_cache_dir,
+        mono_passes,
         render,
         num_workers,
         exec_mode,
@@ -1974,6 +2052,16 @@ macro_rules! debug_print_ir {
     };
 }
 
+/// Panic with a report if a pass left the mono IR malformed.
+/// The refcounts are checked by `Proc::insert_refcount_operations` itself.
+macro_rules! check_mono_ir {
+    ($arena:expr, $state:expr, $after_pass:expr) => {
+        dbg_do!(ROC_CHECK_MONO_IR, {
+            roc_mono::debug::assert_procs_are_valid($arena, &$state.procedures, None, $after_pass);
+        })
+    };
+}
+
 /// Report modules that are imported, but from which nothing is used
 fn report_unused_imported_modules<'a>(
     state: &mut State<'a>,
@@ -2209,6 +2297,22 @@ fn update<'a>(
                 header
                     .imported_modules
                     .insert(ModuleId::ENCODE, Region::zero());
+
+                header
+                    .package_qualified_imported_modules
+                    .insert(PackageQualified::Unqualified(ModuleId::HASH));
+
+                header
+                    .imported_modules
+                    .insert(ModuleId::HASH, Region::zero());
+
+                header
+                    .package_qualified_imported_modules
+                    .insert(PackageQualified::Unqualified(ModuleId::INSPECT));
+
+                header
+                    .imported_modules
+                    .insert(ModuleId::INSPECT, Region::zero());
             }
 
             state
@@ -2325,6 +2429,7 @@ fn update<'a>(
             mut module_timing,
             abilities_store,
             loc_expects,
+            loc_dbgs,
         } => {
             log!("solved types for {:?}", module_id);
             module_timing.end_time = Instant::now();
@@ -2334,11 +2439,12 @@ fn update<'a>(
                 .type_problems
                 .insert(module_id, solved_module.problems);
 
-            if !loc_expects.is_empty() {
+            if !loc_expects.is_empty() || !loc_dbgs.is_empty() {
                 let (path, _) = state.module_cache.sources.get(&module_id).unwrap();
 
                 let expectations = Expectations {
                     expectations: loc_expects,
+                    dbgs: loc_dbgs,
                     subs: solved_subs.clone().into_inner(),
                     path: path.to_owned(),
                     ident_ids: ident_ids.clone(),
@@ -2605,9 +2711,30 @@ fn update<'a>(
                     log!("specializations complete from {:?}", module_id);
 
                     debug_print_ir!(state, ROC_PRINT_IR_AFTER_SPECIALIZATION);
+                    check_mono_ir!(arena, state, "specialization");
 
                     let ident_ids = state.constrained_ident_ids.get_mut(&module_id).unwrap();
 
+                    if state.mono_passes.inline {
+                        Proc::inline_small_procs(
+                            arena,
+                            module_id,
+                            ident_ids,
+                            &mut update_mode_ids,
+                            &mut state.procedures,
+                        );
+
+                        debug_print_ir!(state, ROC_PRINT_IR_AFTER_INLINING);
+                        check_mono_ir!(arena, state, "inlining");
+                    }
+
+                    if state.mono_passes.simplify {
+                        Proc::simplify_procs(arena, &mut state.procedures);
+
+                        debug_print_ir!(state, ROC_PRINT_IR_AFTER_SIMPLIFICATION);
+                        check_mono_ir!(arena, state, "simplification");
+                    }
+
                     Proc::insert_reset_reuse_operations(
                         arena,
                         module_id,
@@ -2617,6 +2744,7 @@ fn update<'a>(
                     );
 
                     debug_print_ir!(state, ROC_PRINT_IR_AFTER_RESET_REUSE);
+                    check_mono_ir!(arena, state, "reset/reuse insertion");
 
                     Proc::insert_refcount_operations(
                         arena,
@@ -3132,6 +3260,8 @@ fn load_module<'a>(
         "Encode", ModuleId::ENCODE
         "Decode", ModuleId::DECODE
         "Json", ModuleId::JSON
+        "Hash", ModuleId::HASH
+        "Inspect", ModuleId::INSPECT
     }
 
     let (filename, opt_shorthand) = module_name_to_path(src_dir, module_name, arc_shorthands);
@@ -3951,6 +4081,7 @@ impl<'a> BuildTask<'a> {
         dep_idents: IdentIdsByModule,
         declarations: Declarations,
         cached_subs: CachedSubs,
+        type_cache: Option<CacheLookup>,
         derived_module: SharedDerivedModule,
     ) -> Self {
         let exposed_by_module = exposed_types.retain_modules(imported_modules.keys());
@@ -3971,6 +4102,7 @@ impl<'a> BuildTask<'a> {
             dep_idents,
             module_timing,
             cached_subs,
+            type_cache,
             derived_module,
         }
     }
@@ -4258,6 +4390,7 @@ fn run_solve<'a>(
     decls: Declarations,
     dep_idents: IdentIdsByModule,
     cached_subs: CachedSubs,
+    type_cache: Option<CacheLookup>,
     derived_module: SharedDerivedModule,
 ) -> Msg<'a> {
     let solve_start = Instant::now();
@@ -4269,6 +4402,7 @@ fn run_solve<'a>(
 
     let mut module = module;
     let loc_expects = std::mem::take(&mut module.loc_expects);
+    let loc_dbgs = std::mem::take(&mut module.loc_dbgs);
     let module = module;
 
     let (solved_subs, solved_implementations, exposed_vars_by_symbol, problems, abilities_store) = {
@@ -4296,15 +4430,65 @@ fn run_solve<'a>(
                 }
             }
         } else {
-            run_solve_solve(
-                exposed_for_module,
-                constraints,
-                constraint,
-                pending_derives,
-                var_store,
-                module,
-                derived_module,
-            )
+            match type_cache {
+                Some(CacheLookup::Hit(cached)) => {
+                    let CachedModule {
+                        subs,
+                        exposed_vars_by_symbol,
+                        abilities_store,
+                    } = cached;
+
+                    module_timing.type_cache_hit = true;
+
+                    let solved_implementations =
+                        extract_module_owned_implementations(module_id, &abilities_store);
+
+                    (
+                        Solved(subs),
+                        solved_implementations,
+                        exposed_vars_by_symbol,
+                        vec![],
+                        abilities_store,
+                    )
+                }
+                Some(CacheLookup::Miss(slot)) => {
+                    let solved = run_solve_solve(
+                        exposed_for_module,
+                        constraints,
+                        constraint,
+                        pending_derives,
+                        var_store,
+                        module,
+                        derived_module,
+                    );
+
+                    let (solved_subs, _, exposed_vars_by_symbol, problems, abilities_store) =
+                        &solved;
+
+                    // Modules with type errors are solved again every time, so that their
+                    // problems are reported every time.
+                    if problems.is_empty() && solved_subs.inner().problems.is_empty() {
+                        // Failing to write the cache only means the module is solved again
+                        // next time, so there is no need to report it.
+                        let _ = slot.store(
+                            solved_subs.inner(),
+                            exposed_vars_by_symbol,
+                            abilities_store,
+                        );
+                    }
+
+                    solved
+                }
+                None => run_solve_solve(
+                    exposed_for_module,
+                    constraints,
+                    constraint,
+                    pending_derives,
+                    var_store,
+                    module,
+                    derived_module,
+                ),
+            }
         }
     };
 
@@ -4340,6 +4524,7 @@ fn run_solve<'a>(
         module_timing,
         abilities_store,
         loc_expects,
+        loc_dbgs,
     }
 }
 
@@ -4520,7 +4705,12 @@ fn canonicalize_and_constrain<'a>(
             Vacant(vacant) => {
                 let should_include_builtin = matches!(
                     name.module_id(),
-                    ModuleId::ENCODE | ModuleId::DECODE | ModuleId::DICT | ModuleId::SET
+                    ModuleId::ENCODE
+                        | ModuleId::DECODE
+                        | ModuleId::DICT
+                        | ModuleId::SET
+                        | ModuleId::HASH
+                        | ModuleId::INSPECT
                 );
 
                 if !name.is_builtin() || should_include_builtin {
@@ -4540,6 +4730,7 @@ fn canonicalize_and_constrain<'a>(
         rigid_variables: module_output.rigid_variables,
         abilities_store: module_output.scope.abilities_store,
         loc_expects: module_output.loc_expects,
+        loc_dbgs: module_output.loc_dbgs,
     };
 
     let constrained_module = ConstrainedModule {
@@ -4689,6 +4880,7 @@ fn make_specializations<'a>(
         abilities: AbilitiesView::World(&world_abilities),
         exposed_by_module,
         derived_module: &derived_module,
+        symbol_regions: bumpalo::collections::Vec::new_in(arena),
     };
 
     let mut procs = Procs::new_in(arena);
@@ -4783,6 +4975,7 @@ fn build_pending_specializations<'a>(
         abilities: AbilitiesView::Module(&abilities_store),
         exposed_by_module,
         derived_module: &derived_module,
+        symbol_regions: bumpalo::collections::Vec::new_in(arena),
     };
 
     // Add modules' decls to Procs
@@ -4851,6 +5044,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 procs_base.partial_procs.insert(symbol, proc);
@@ -4989,6 +5183,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 procs_base.partial_procs.insert(symbol, proc);
@@ -5059,6 +5254,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 // extend the region of the expect expression with the region of the preceding
@@ -5133,6 +5329,7 @@ fn build_pending_specializations<'a>(
                     body_var: expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    region: body.region,
                 };
 
                 // extend the region of the expect expression with the region of the preceding
@@ -5216,6 +5413,7 @@ fn load_derived_partial_procs<'a>(
             abilities: AbilitiesView::World(world_abilities),
             exposed_by_module,
             derived_module,
+            symbol_regions: bumpalo::collections::Vec::new_in(arena),
         };
 
         let partial_proc = match derived_expr {
@@ -5253,6 +5451,8 @@ fn load_derived_partial_procs<'a>(
                     body_var: derived_expr_var,
                     // This is a 0-arity thunk, so it cannot be recursive
                     is_self_recursive: false,
+                    // Derived implementations have no source
+                    region: Region::zero(),
                 }
             }
         };
@@ -5332,6 +5532,7 @@ fn run_task<'a>(
             declarations,
             dep_idents,
             cached_subs,
+            type_cache,
             derived_module,
         } => Ok(run_solve(
             module,
@@ -5345,6 +5546,7 @@ fn run_task<'a>(
             declarations,
             dep_idents,
             cached_subs,
+            type_cache,
             derived_module,
         )),
         BuildPendingSpecializations {
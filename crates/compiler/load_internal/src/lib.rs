@@ -3,6 +3,7 @@
 #![allow(clippy::large_enum_variant)]
 pub mod docs;
 pub mod file;
+pub mod type_cache;
 mod work;
 
 #[cfg(target_family = "wasm")]
@@ -0,0 +1,388 @@
+//! An on-disk cache of typechecked modules.
+//!
+//! After an interface module is solved without errors, its [Subs], exposed variables and
+//! [AbilitiesStore] are written to the cache directory, keyed by a hash of the module's source
+//! and the hashes of everything it imports. The next time the module is loaded with the same
+//! source and dependencies, constraint generation and solving are skipped and the cached types
+//! are used instead, in the same way the builtins' pre-solved [Subs] are.
+//!
+//! The cached types mention [Symbol]s and [Variable]s by their numeric IDs. Variables and the
+//! module's own identifiers are assigned deterministically during canonicalization, so they line
+//! up as long as the source is unchanged. [ModuleId]s depend on the order modules were
+//! discovered in, so each entry also records the module IDs it was solved with, and is only
+//! reused if they match the current load.
+//!
+//! Entries are also keyed by a hash of the compiler sources computed by this crate's build
+//! script, so a cache written by a different compiler is never read back. Since such entries can
+//! never be used again, [prune] removes them, along with any entry that has not been written for
+//! a while.
+use roc_can::abilities::AbilitiesStore;
+use roc_collections::all::WyHash;
+use roc_module::symbol::{ModuleId, ModuleIds, Symbol};
+use roc_types::subs::{Subs, Variable};
+use std::hash::Hasher;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Bump this whenever the serialized format of [Subs] or [AbilitiesStore] changes.
+const CACHE_FORMAT_VERSION: u64 = 2;
+
+/// Hash of the compiler sources and the Rust toolchain, see `build.rs`.
+const COMPILER_HASH: &str = env!("ROC_TYPE_CACHE_COMPILER_HASH");
+
+const CACHE_FILE_EXTENSION: &str = "rtc";
+
+/// Entries that have not been written for this long are removed by [prune].
+const MAX_ENTRY_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// The directory typechecked modules are cached in by default: `$XDG_CACHE_HOME/roc/types`,
+/// falling back to `$HOME/.cache/roc/types`.
+///
+/// Setting `ROC_TYPE_CACHE=0` turns the cache off.
+pub fn default_type_cache_dir() -> Option<PathBuf> {
+    if std::env::var_os("ROC_TYPE_CACHE").map_or(false, |value| value == "0") {
+        return None;
+    }
+
+    let cache_home = match std::env::var_os("XDG_CACHE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(std::env::var_os("HOME")?).join(".cache"),
+    };
+
+    Some(cache_home.join("roc").join("types"))
+}
+
+/// Identifies the exact inputs a module was typechecked with.
+#[derive(Debug, Clone)]
+pub(crate) struct Fingerprint {
+    /// Hash of the module's source and the fingerprints of its dependencies.
+    hash: u64,
+    /// The module, and every non-builtin module it transitively depends on, sorted by name.
+    modules: Vec<(ModuleId, Box<str>)>,
+}
+
+impl Fingerprint {
+    pub fn new<'f>(
+        module_id: ModuleId,
+        module_ids: &ModuleIds,
+        src: &str,
+        deps: impl IntoIterator<Item = &'f Fingerprint>,
+    ) -> Self {
+        let module_name: Box<str> = match module_ids.get_name(module_id) {
+            Some(name) => name.as_str().into(),
+            None => format!("{:?}", module_id).into(),
+        };
+
+        // The hash names files on disk, so it must not depend on the Rust version the way
+        // `DefaultHasher` does. Every input is written with its length to keep them apart.
+        let mut hasher = WyHash::with_seed(0);
+        hasher.write_u64(CACHE_FORMAT_VERSION);
+        write_bytes(&mut hasher, COMPILER_HASH.as_bytes());
+        write_bytes(&mut hasher, module_name.as_bytes());
+        write_bytes(&mut hasher, src.as_bytes());
+
+        let mut dep_hashes = Vec::new();
+        let mut modules = vec![(module_id, module_name)];
+
+        for dep in deps {
+            dep_hashes.push(dep.hash);
+
+            for (id, name) in dep.modules.iter() {
+                if !modules.iter().any(|(known, _)| known == id) {
+                    modules.push((*id, name.clone()));
+                }
+            }
+        }
+
+        dep_hashes.sort_unstable();
+        modules.sort_by(|(_, a), (_, b)| a.cmp(b));
+
+        hasher.write_u64(dep_hashes.len() as u64);
+        for dep_hash in dep_hashes {
+            hasher.write_u64(dep_hash);
+        }
+
+        Self {
+            hash: hasher.finish(),
+            modules,
+        }
+    }
+}
+
+fn write_bytes(hasher: &mut impl Hasher, bytes: &[u8]) {
+    hasher.write_u64(bytes.len() as u64);
+    hasher.write(bytes);
+}
+
+/// Types of a module that were read back from the cache.
+#[derive(Debug)]
+pub(crate) struct CachedModule {
+    pub subs: Subs,
+    pub exposed_vars_by_symbol: Vec<(Symbol, Variable)>,
+    pub abilities_store: AbilitiesStore,
+}
+
+/// The result of looking a module up in the cache.
+#[derive(Debug)]
+pub(crate) enum CacheLookup {
+    Hit(CachedModule),
+    /// The module must be solved; the result can then be stored in this slot.
+    Miss(CacheSlot),
+}
+
+#[derive(Debug)]
+pub(crate) struct CacheSlot {
+    path: PathBuf,
+    fingerprint: Fingerprint,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug)]
+struct CacheHeader {
+    version: u64,
+    hash: u64,
+    modules: u64,
+    module_name_bytes: u64,
+}
+
+impl CacheHeader {
+    fn to_array(self) -> [u8; std::mem::size_of::<Self>()] {
+        // Safety: With repr(c) all fields are in order and properly aligned without padding.
+        unsafe { std::mem::transmute(self) }
+    }
+
+    fn from_array(array: [u8; std::mem::size_of::<Self>()]) -> Self {
+        // Safety: With repr(c) all fields are in order and properly aligned without padding.
+        unsafe { std::mem::transmute(array) }
+    }
+}
+
+pub(crate) fn lookup(cache_dir: &Path, fingerprint: Fingerprint) -> CacheLookup {
+    let path = cache_dir.join(format!(
+        "{}-{:016x}.{}",
+        COMPILER_HASH, fingerprint.hash, CACHE_FILE_EXTENSION
+    ));
+
+    match std::fs::read(&path) {
+        Ok(bytes) => match deserialize(AlignedBytes::new(&bytes).as_slice(), &fingerprint) {
+            Some(cached) => CacheLookup::Hit(cached),
+            None => CacheLookup::Miss(CacheSlot { path, fingerprint }),
+        },
+        Err(_) => CacheLookup::Miss(CacheSlot { path, fingerprint }),
+    }
+}
+
+/// Removes the entries in `cache_dir` that were written by a different compiler, and those that
+/// have not been written for [MAX_ENTRY_AGE]. A module whose entry is removed is simply solved
+/// and cached again the next time it is loaded.
+pub(crate) fn prune(cache_dir: &Path) {
+    let entries = match std::fs::read_dir(cache_dir) {
+        Ok(entries) => entries,
+        Err(_) => return,
+    };
+
+    let now = SystemTime::now();
+    let current_prefix = format!("{}-", COMPILER_HASH);
+
+    for entry in entries.filter_map(Result::ok) {
+        let file_name = entry.file_name();
+        let file_name = file_name.to_string_lossy();
+
+        // Leave anything that isn't a cache entry (or one being written) alone
+        let is_entry = match file_name.split_once('.') {
+            Some((_, extension)) => extension.split('.').next() == Some(CACHE_FILE_EXTENSION),
+            None => false,
+        };
+
+        if !is_entry {
+            continue;
+        }
+
+        let too_old = entry
+            .metadata()
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .map_or(false, |age| age > MAX_ENTRY_AGE);
+
+        if too_old || !file_name.starts_with(&current_prefix) {
+            // Another load may have removed it already, which is just as good
+            let _ = std::fs::remove_file(entry.path());
+        }
+    }
+}
+
+/// A copy of a file's bytes that starts at an 8-byte boundary, so that the values in it can be
+/// read in place.
+struct AlignedBytes {
+    words: Vec<u64>,
+    len: usize,
+}
+
+impl AlignedBytes {
+    fn new(bytes: &[u8]) -> Self {
+        let mut words = vec![0u64; round_to_multiple_of(bytes.len(), 8) / 8];
+
+        // Safety: `words` spans at least `bytes.len()` bytes.
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                bytes.as_ptr(),
+                words.as_mut_ptr() as *mut u8,
+                bytes.len(),
+            );
+        }
+
+        Self {
+            words,
+            len: bytes.len(),
+        }
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        // Safety: the first `len` bytes of `words` were initialized in `new`.
+        unsafe { std::slice::from_raw_parts(self.words.as_ptr() as *const u8, self.len) }
+    }
+}
+
+impl CacheSlot {
+    /// Writes the solved types of a module to the cache. The file is written in full before it
+    /// becomes visible, so concurrent loads never see a partially written entry.
+    pub fn store(
+        &self,
+        subs: &Subs,
+        exposed_vars_by_symbol: &[(Symbol, Variable)],
+        abilities_store: &AbilitiesStore,
+    ) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        serialize(
+            &self.fingerprint,
+            subs,
+            exposed_vars_by_symbol,
+            abilities_store,
+            &mut bytes,
+        )?;
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        let tmp_path =
+            self.path
+                .with_extension(format!("{}.{}", CACHE_FILE_EXTENSION, std::process::id()));
+        std::fs::File::create(&tmp_path)?.write_all(&bytes)?;
+        std::fs::rename(&tmp_path, &self.path)
+    }
+}
+
+fn serialize(
+    fingerprint: &Fingerprint,
+    subs: &Subs,
+    exposed_vars_by_symbol: &[(Symbol, Variable)],
+    abilities_store: &AbilitiesStore,
+    writer: &mut impl Write,
+) -> io::Result<usize> {
+    let module_ids: Vec<ModuleId> = fingerprint.modules.iter().map(|(id, _)| *id).collect();
+    let mut module_name_lengths = Vec::with_capacity(module_ids.len());
+    let mut module_names = Vec::new();
+    for (_, name) in fingerprint.modules.iter() {
+        module_name_lengths.push(name.len() as u32);
+        module_names.extend_from_slice(name.as_bytes());
+    }
+
+    let header = CacheHeader {
+        version: CACHE_FORMAT_VERSION,
+        hash: fingerprint.hash,
+        modules: module_ids.len() as u64,
+        module_name_bytes: module_names.len() as u64,
+    };
+
+    let module_name_lengths: Vec<u8> = module_name_lengths
+        .iter()
+        .flat_map(|len| len.to_ne_bytes())
+        .collect();
+
+    let mut written = 0;
+    for bytes in [
+        &header.to_array()[..],
+        module_id_bytes(&module_ids),
+        &module_name_lengths,
+        &module_names,
+    ] {
+        writer.write_all(bytes)?;
+        written += bytes.len();
+    }
+
+    written = abilities_store.serialize(writer, written)?;
+
+    // Subs expects to be deserialized from the start of a buffer, so it goes last
+    let padding = round_to_multiple_of(written, std::mem::align_of::<u64>()) - written;
+    writer.write_all(&[0; 8][..padding])?;
+    written += padding;
+
+    Ok(written + subs.serialize(exposed_vars_by_symbol, writer)?)
+}
+
+fn deserialize(bytes: &[u8], fingerprint: &Fingerprint) -> Option<CachedModule> {
+    const HEADER_SIZE: usize = std::mem::size_of::<CacheHeader>();
+
+    let mut offset = 0;
+    let mut take = |length: usize| {
+        let taken = bytes.get(offset..)?.get(..length)?;
+        offset += length;
+        Some(taken)
+    };
+
+    let header = CacheHeader::from_array(take(HEADER_SIZE)?.try_into().unwrap());
+
+    if header.version != CACHE_FORMAT_VERSION
+        || header.hash != fingerprint.hash
+        || header.modules as usize != fingerprint.modules.len()
+    {
+        return None;
+    }
+
+    let id_bytes = std::mem::size_of::<ModuleId>();
+    let module_ids = take(fingerprint.modules.len() * id_bytes)?;
+    let module_name_lengths = take(fingerprint.modules.len() * 4)?;
+    let module_names = take(header.module_name_bytes as usize)?;
+
+    // The cached types are only meaningful if every module they mention still has the same ID.
+    let mut name_start = 0;
+    for (i, (id, name)) in fingerprint.modules.iter().enumerate() {
+        let cached_id = &module_ids[i * id_bytes..][..id_bytes];
+        let name_len = u32::from_ne_bytes(module_name_lengths[i * 4..][..4].try_into().unwrap());
+        let cached_name = module_names.get(name_start..)?.get(..name_len as usize)?;
+        name_start += name_len as usize;
+
+        if cached_id != module_id_bytes(std::slice::from_ref(id)) || cached_name != name.as_bytes()
+        {
+            return None;
+        }
+    }
+
+    let (abilities_store, offset) = AbilitiesStore::deserialize(bytes, offset)?;
+
+    let offset = round_to_multiple_of(offset, std::mem::align_of::<u64>());
+    let (subs, exposed_vars_by_symbol) = Subs::try_deserialize(bytes.get(offset..)?)?;
+
+    Some(CachedModule {
+        subs,
+        exposed_vars_by_symbol: exposed_vars_by_symbol.to_vec(),
+        abilities_store,
+    })
+}
+
+fn module_id_bytes(module_ids: &[ModuleId]) -> &[u8] {
+    // Safety: a ModuleId is a plain 4-byte integer.
+    unsafe {
+        std::slice::from_raw_parts(
+            module_ids.as_ptr() as *const u8,
+            std::mem::size_of_val(module_ids),
+        )
+    }
+}
+
+fn round_to_multiple_of(value: usize, base: usize) -> usize {
+    (value + (base - 1)) / base * base
+}
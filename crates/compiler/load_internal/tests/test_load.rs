@@ -17,7 +17,7 @@ mod helpers;
 use crate::helpers::fixtures_dir;
 use bumpalo::Bump;
 use roc_can::module::ExposedByModule;
-use roc_load_internal::file::{ExecutionMode, LoadConfig, Threading};
+use roc_load_internal::file::{ExecutionMode, LoadConfig, MonoPasses, Threading};
 use roc_load_internal::file::{LoadResult, LoadStart, LoadedModule, LoadingProblem};
 use roc_module::ident::ModuleName;
 use roc_module::symbol::{Interns, ModuleId};
@@ -37,6 +37,16 @@ fn load_and_typecheck(
     filename: PathBuf,
     exposed_types: ExposedByModule,
     target_info: TargetInfo,
+) -> Result<LoadedModule, LoadingProblem> {
+    load_and_typecheck_with_type_cache(arena, filename, exposed_types, target_info, None)
+}
+
+fn load_and_typecheck_with_type_cache(
+    arena: &Bump,
+    filename: PathBuf,
+    exposed_types: ExposedByModule,
+    target_info: TargetInfo,
+    type_cache_dir: Option<PathBuf>,
 ) -> Result<LoadedModule, LoadingProblem> {
     use LoadResult::*;
 
@@ -46,6 +56,8 @@ fn load_and_typecheck(
         render: RenderTarget::Generic,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Check,
+        type_cache_dir,
+        mono_passes: MonoPasses::default(),
     };
 
     match roc_load_internal::file::load(
@@ -175,11 +187,26 @@ fn load_fixture(
     dir_name: &str,
     module_name: &str,
     subs_by_module: ExposedByModule,
+) -> LoadedModule {
+    load_fixture_with_type_cache(dir_name, module_name, subs_by_module, None)
+}
+
+fn load_fixture_with_type_cache(
+    dir_name: &str,
+    module_name: &str,
+    subs_by_module: ExposedByModule,
+    type_cache_dir: Option<PathBuf>,
 ) -> LoadedModule {
     let src_dir = fixtures_dir().join(dir_name);
     let filename = src_dir.join(format!("{}.roc", module_name));
     let arena = Bump::new();
-    let loaded = load_and_typecheck(&arena, filename, subs_by_module, TARGET_INFO);
+    let loaded = load_and_typecheck_with_type_cache(
+        &arena,
+        filename,
+        subs_by_module,
+        TARGET_INFO,
+        type_cache_dir,
+    );
     let mut loaded_module = match loaded {
         Ok(x) => x,
         Err(roc_load_internal::file::LoadingProblem::FormattedReport(report)) => {
@@ -481,12 +508,12 @@ fn load_astar() {
     expect_types(
         loaded_module,
         hashmap! {
-            "findPath" => "{ costFunction : position, position -> F64, end : position, moveFunction : position -> Set position, start : position } -> Result (List position) [KeyNotFound]*",
-            "initialModel" => "position -> Model position",
-            "reconstructPath" => "Dict position position, position -> List position",
-            "updateCost" => "position, position, Model position -> Model position",
-            "cheapestOpen" => "(position -> F64), Model position -> Result position [KeyNotFound]*",
-            "astar" => "(position, position -> F64), (position -> Set position), position, Model position -> [Err [KeyNotFound]*, Ok (List position)]*",
+            "findPath" => "{ costFunction : position, position -> F64, end : position, moveFunction : position -> Set position, start : position } -> Result (List position) [KeyNotFound]* | position has Hash",
+            "initialModel" => "position -> Model position | position has Hash",
+            "reconstructPath" => "Dict position position, position -> List position | position has Hash",
+            "updateCost" => "position, position, Model position -> Model position | position has Hash",
+            "cheapestOpen" => "(position -> F64), Model position -> Result position [KeyNotFound]* | position has Hash",
+            "astar" => "(position, position -> F64), (position -> Set position), position, Model position -> [Err [KeyNotFound]*, Ok (List position)]* | position has Hash",
         },
     );
 }
@@ -527,6 +554,226 @@ fn iface_dep_types() {
     );
 }
 
+#[test]
+fn iface_dep_types_from_type_cache() {
+    let cache = roc_test_utils::TmpDir::new("tmp/iface_dep_types_from_type_cache");
+    let cache_dir = cache.path().to_path_buf();
+
+    let expected_types = hashmap! {
+        "blah2" => "Float *",
+        "blah3" => "Str",
+        "str" => "Str",
+        "alwaysThree" => "* -> Float *",
+        "identity" => "a -> a",
+        "z" => "Float *",
+        "w" => "Dep1.Identity {}",
+        "succeed" => "a -> Dep1.Identity a",
+        "yay" => "Res.Res {} err",
+        "withDefault" => "Res.Res a err, a -> a",
+    };
+
+    // The first load solves every module and fills the cache
+    let loaded_module = load_fixture_with_type_cache(
+        "interface_with_deps",
+        "Primary",
+        Default::default(),
+        Some(cache_dir.clone()),
+    );
+    assert_eq!(count_type_cache_hits(&loaded_module), 0);
+    expect_types(loaded_module, expected_types.clone());
+
+    let cached_modules = std::fs::read_dir(&cache_dir).unwrap().count();
+    assert!(cached_modules > 0);
+
+    // The second load reuses the cached types, and must see the same thing
+    let loaded_module = load_fixture_with_type_cache(
+        "interface_with_deps",
+        "Primary",
+        Default::default(),
+        Some(cache_dir.clone()),
+    );
+    assert_eq!(count_type_cache_hits(&loaded_module), cached_modules);
+    expect_types(loaded_module, expected_types);
+
+    assert_eq!(
+        std::fs::read_dir(&cache_dir).unwrap().count(),
+        cached_modules
+    );
+}
+
+/// Writes `files` to `dir` and typechecks the last one, caching types in `cache_dir`.
+fn load_with_type_cache(
+    dir: &std::path::Path,
+    files: &[(&str, &str)],
+    cache_dir: &std::path::Path,
+) -> LoadedModule {
+    for (name, source) in files {
+        std::fs::write(dir.join(format!("{}.roc", name)), source).unwrap();
+    }
+
+    let (root, _) = files.last().unwrap();
+    let arena = Bump::new();
+
+    match load_and_typecheck_with_type_cache(
+        &arena,
+        dir.join(format!("{}.roc", root)),
+        Default::default(),
+        TARGET_INFO,
+        Some(cache_dir.to_path_buf()),
+    ) {
+        Ok(loaded_module) => loaded_module,
+        Err(LoadingProblem::FormattedReport(report)) => panic!("{}", report),
+        Err(e) => panic!("{:?}", e),
+    }
+}
+
+fn count_cache_entries(cache_dir: &std::path::Path) -> usize {
+    std::fs::read_dir(cache_dir).unwrap().count()
+}
+
+fn count_type_cache_hits(loaded_module: &LoadedModule) -> usize {
+    loaded_module
+        .timings
+        .values()
+        .filter(|timing| timing.type_cache_hit)
+        .count()
+}
+
+#[test]
+fn type_cache_invalidated_by_module_change() {
+    let src = roc_test_utils::TmpDir::new("tmp/type_cache_invalidated_by_module_change");
+    let cache = roc_test_utils::TmpDir::new("tmp/type_cache_invalidated_by_module_change_cache");
+
+    let before = indoc!(
+        r#"
+        interface Main exposes [x] imports []
+
+        x = 1.5
+        "#
+    );
+    let after = indoc!(
+        r#"
+        interface Main exposes [x] imports []
+
+        x = "one and a half"
+        "#
+    );
+
+    let loaded_module = load_with_type_cache(src.path(), &[("Main", before)], cache.path());
+    expect_types(loaded_module, hashmap! { "x" => "Float *" });
+    let entries = count_cache_entries(cache.path());
+
+    let loaded_module = load_with_type_cache(src.path(), &[("Main", after)], cache.path());
+    expect_types(loaded_module, hashmap! { "x" => "Str" });
+    assert_eq!(count_cache_entries(cache.path()), entries + 1);
+
+    // going back to the old source finds the old entry again
+    let loaded_module = load_with_type_cache(src.path(), &[("Main", before)], cache.path());
+    expect_types(loaded_module, hashmap! { "x" => "Float *" });
+    assert_eq!(count_cache_entries(cache.path()), entries + 1);
+}
+
+#[test]
+fn type_cache_invalidated_by_dependency_change() {
+    let src = roc_test_utils::TmpDir::new("tmp/type_cache_invalidated_by_dependency_change");
+    let cache =
+        roc_test_utils::TmpDir::new("tmp/type_cache_invalidated_by_dependency_change_cache");
+
+    let main = indoc!(
+        r#"
+        interface Main exposes [x] imports [Dep]
+
+        x = Dep.value
+        "#
+    );
+    let dep_before = indoc!(
+        r#"
+        interface Dep exposes [value] imports []
+
+        value = 1.5
+        "#
+    );
+    let dep_after = indoc!(
+        r#"
+        interface Dep exposes [value] imports []
+
+        value = "one and a half"
+        "#
+    );
+
+    let loaded_module = load_with_type_cache(
+        src.path(),
+        &[("Dep", dep_before), ("Main", main)],
+        cache.path(),
+    );
+    expect_types(loaded_module, hashmap! { "x" => "Float *" });
+    let entries = count_cache_entries(cache.path());
+
+    // Main itself is unchanged, but must be solved again because Dep changed
+    let loaded_module = load_with_type_cache(
+        src.path(),
+        &[("Dep", dep_after), ("Main", main)],
+        cache.path(),
+    );
+    expect_types(loaded_module, hashmap! { "x" => "Str" });
+    assert_eq!(count_cache_entries(cache.path()), entries + 2);
+}
+
+#[test]
+fn type_cache_ignores_corrupt_entries() {
+    let src = roc_test_utils::TmpDir::new("tmp/type_cache_ignores_corrupt_entries");
+    let cache = roc_test_utils::TmpDir::new("tmp/type_cache_ignores_corrupt_entries_cache");
+
+    let main = indoc!(
+        r#"
+        interface Main exposes [x] imports []
+
+        x = 1.5
+        "#
+    );
+
+    load_with_type_cache(src.path(), &[("Main", main)], cache.path());
+
+    // Cut every entry short, as if a write had been interrupted
+    for entry in std::fs::read_dir(cache.path()).unwrap() {
+        let path = entry.unwrap().path();
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() / 2]).unwrap();
+    }
+
+    let loaded_module = load_with_type_cache(src.path(), &[("Main", main)], cache.path());
+    assert_eq!(count_type_cache_hits(&loaded_module), 0);
+    expect_types(loaded_module, hashmap! { "x" => "Float *" });
+}
+
+#[test]
+fn type_cache_prunes_entries_of_other_compilers() {
+    let src = roc_test_utils::TmpDir::new("tmp/type_cache_prunes_entries_of_other_compilers");
+    let cache =
+        roc_test_utils::TmpDir::new("tmp/type_cache_prunes_entries_of_other_compilers_cache");
+
+    let main = indoc!(
+        r#"
+        interface Main exposes [x] imports []
+
+        x = 1.5
+        "#
+    );
+
+    let stale_entry = cache.path().join("0123456789abcdef-0123456789abcdef.rtc");
+    let unrelated_file = cache.path().join("notes.txt");
+    std::fs::write(&stale_entry, b"written by some other compiler").unwrap();
+    std::fs::write(&unrelated_file, b"not a cache entry").unwrap();
+
+    load_with_type_cache(src.path(), &[("Main", main)], cache.path());
+
+    assert!(!stale_entry.exists());
+    assert!(unrelated_file.exists());
+
+    // only the entry for Main is left next to the unrelated file
+    assert_eq!(count_cache_entries(cache.path()), 2);
+}
+
 #[test]
 fn app_dep_types() {
     let subs_by_module = Default::default();
@@ -48,6 +48,8 @@ pub enum LowLevel {
     ListSwap,
     ListIsUnique,
     ListGetCapacity,
+    DictHashBytes,
+    DictHashMix,
     NumAdd,
     NumAddWrap,
     NumAddChecked,
@@ -262,6 +264,8 @@ map_symbol_to_lowlevel! {
     ListSublist <= LIST_SUBLIST_LOWLEVEL,
     ListDropAt <= LIST_DROP_AT,
     ListSwap <= LIST_SWAP,
+    DictHashBytes <= DICT_HASH_BYTES_LOWLEVEL,
+    DictHashMix <= DICT_HASH_MIX_LOWLEVEL,
     NumAdd <= NUM_ADD,
     NumAddWrap <= NUM_ADD_WRAP,
     NumAddChecked <= NUM_ADD_CHECKED_LOWLEVEL,
@@ -50,6 +50,11 @@ const PRETTY_PRINT_DEBUG_SYMBOLS: bool = true;
 pub const DERIVABLE_ABILITIES: &[(Symbol, &[Symbol])] = &[
     (Symbol::ENCODE_ENCODING, &[Symbol::ENCODE_TO_ENCODER]),
     (Symbol::DECODE_DECODING, &[Symbol::DECODE_DECODER]),
+    (Symbol::HASH_HASH_ABILITY, &[Symbol::HASH_HASH]),
+    (
+        Symbol::INSPECT_INSPECT_ABILITY,
+        &[Symbol::INSPECT_TO_INSPECTOR],
+    ),
 ];
 
 /// In Debug builds only, Symbol has a name() method that lets
@@ -1342,6 +1347,8 @@ define_builtins! {
 
         15 DICT_WITH_CAPACITY: "withCapacity"
         16 DICT_CAPACITY: "capacity"
+        17 DICT_HASH_BYTES_LOWLEVEL: "hashBytesLowLevel"
+        18 DICT_HASH_MIX_LOWLEVEL: "hashMixLowLevel"
     }
     9 SET: "Set" => {
         0 SET_SET: "Set" // the Set.Set type alias
@@ -1422,10 +1429,62 @@ define_builtins! {
         24 DECODE_DECODE_WITH: "decodeWith"
         25 DECODE_FROM_BYTES_PARTIAL: "fromBytesPartial"
         26 DECODE_FROM_BYTES: "fromBytes"
+        27 DECODE_TAG: "tag"
     }
     13 JSON: "Json" => {
         0 JSON_JSON: "Json"
     }
-
-    num_modules: 14 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
+    14 HASH: "Hash" => {
+        0 HASH_HASH_ABILITY: "Hash"
+        1 HASH_HASH: "hash"
+        2 HASH_HASHER: "Hasher"
+        3 HASH_ADD_BYTES: "addBytes"
+        4 HASH_ADD_U8: "addU8"
+        5 HASH_ADD_U16: "addU16"
+        6 HASH_ADD_U32: "addU32"
+        7 HASH_ADD_U64: "addU64"
+        8 HASH_ADD_U128: "addU128"
+        9 HASH_ADD_I8: "addI8"
+        10 HASH_ADD_I16: "addI16"
+        11 HASH_ADD_I32: "addI32"
+        12 HASH_ADD_I64: "addI64"
+        13 HASH_ADD_I128: "addI128"
+        14 HASH_ADD_NAT: "addNat"
+        15 HASH_COMPLETE: "complete"
+        16 HASH_HASH_STR_BYTES: "hashStrBytes"
+        17 HASH_HASH_LIST: "hashList"
+    }
+    15 INSPECT: "Inspect" => {
+        0 INSPECT_INSPECT_ABILITY: "Inspect"
+        1 INSPECT_TO_INSPECTOR: "toInspector"
+        2 INSPECT_INSPECT_FORMATTER: "InspectFormatter"
+        3 INSPECT_INSPECTOR: "Inspector"
+        4 INSPECT_INIT: "init"
+        5 INSPECT_U8: "u8"
+        6 INSPECT_U16: "u16"
+        7 INSPECT_U32: "u32"
+        8 INSPECT_U64: "u64"
+        9 INSPECT_U128: "u128"
+        10 INSPECT_I8: "i8"
+        11 INSPECT_I16: "i16"
+        12 INSPECT_I32: "i32"
+        13 INSPECT_I64: "i64"
+        14 INSPECT_I128: "i128"
+        15 INSPECT_NAT: "nat"
+        16 INSPECT_F32: "f32"
+        17 INSPECT_F64: "f64"
+        18 INSPECT_DEC: "dec"
+        19 INSPECT_STR: "str"
+        20 INSPECT_LIST: "list"
+        21 INSPECT_RECORD: "record"
+        22 INSPECT_TAG: "tag"
+        23 INSPECT_OPAQUE: "opaque"
+        24 INSPECT_CUSTOM: "custom"
+        25 INSPECT_APPLY: "apply"
+        26 INSPECT_INSPECT: "inspect"
+        27 INSPECT_DBG_FORMATTER: "DbgFormatter"
+        28 INSPECT_TO_DBG_STR: "toDbgStr"
+    }
+
+    num_modules: 16 // Keep this count up to date by hand! (TODO: see the mut_map! macro for how we could determine this count correctly in the macro)
 }
@@ -308,7 +308,7 @@ impl<'a> ParamMap<'a> {
                     stack.push(cont);
                 }
 
-                Expect { remainder, .. } => stack.push(remainder),
+                Expect { remainder, .. } | Dbg { remainder, .. } => stack.push(remainder),
 
                 Switch {
                     branches,
@@ -320,7 +320,7 @@ impl<'a> ParamMap<'a> {
                 }
                 Refcounting(_, _) => unreachable!("these have not been introduced yet"),
 
-                Ret(_) | Jump(_, _) | RuntimeError(_) => {
+                Ret(_) | Jump(_, _) | Crash(_) | RuntimeError(_) => {
                     // these are terminal, do nothing
                 }
             }
@@ -702,6 +702,12 @@ impl<'a> BorrowInfState<'a> {
                 // if the extracted value is owned, the structure must be too
                 self.if_is_owned_then_own(z, *x);
             }
+
+            SetUnionAtIndex { value: y, .. } => {
+                // the structure is only written to, but the value is stored in it,
+                // so if it is an argument to the current function, it must be owned
+                self.own_args_if_param(&[*y]);
+            }
         }
     }
 
@@ -816,13 +822,13 @@ impl<'a> BorrowInfState<'a> {
                 self.collect_stmt(param_map, default_branch.1);
             }
 
-            Expect { remainder, .. } => {
+            Expect { remainder, .. } | Dbg { remainder, .. } => {
                 self.collect_stmt(param_map, remainder);
             }
 
             Refcounting(_, _) => unreachable!("these have not been introduced yet"),
 
-            Ret(_) | RuntimeError(_) => {
+            Ret(_) | Crash(_) | RuntimeError(_) => {
                 // these are terminal, do nothing
             }
         }
@@ -930,6 +936,9 @@ pub fn lowlevel_borrow_signature(arena: &Bump, op: LowLevel) -> &[bool] {
 
         ListIsUnique => arena.alloc_slice_copy(&[borrowed]),
 
+        DictHashBytes => arena.alloc_slice_copy(&[borrowed, irrelevant]),
+        DictHashMix => arena.alloc_slice_copy(&[irrelevant, irrelevant]),
+
         BoxExpr | UnboxExpr => {
             unreachable!("These lowlevel operations are turned into mono Expr's")
         }
@@ -987,11 +996,11 @@ fn call_info_stmt<'a>(arena: &'a Bump, stmt: &Stmt<'a>, info: &mut CallInfo<'a>)
                 stack.push(default_branch.1);
             }
 
-            Expect { remainder, .. } => stack.push(remainder),
+            Expect { remainder, .. } | Dbg { remainder, .. } => stack.push(remainder),
 
             Refcounting(_, _) => unreachable!("these have not been introduced yet"),
 
-            Ret(_) | Jump(_, _) | RuntimeError(_) => {
+            Ret(_) | Jump(_, _) | Crash(_) | RuntimeError(_) => {
                 // these are terminal, do nothing
             }
         }
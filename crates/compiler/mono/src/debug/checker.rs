@@ -0,0 +1,1378 @@
+//! Checks that the mono IR is well-formed. A pass that breaks the IR otherwise shows up as an
+//! LLVM verifier failure or a segfault at runtime, far away from its cause.
+//!
+//! The checker confirms that
+//!
+//! - every symbol is defined before it is used, and not defined twice on one path
+//! - the layout of a `let` matches the layout its expression produces
+//! - symbols are used at the layout they are defined with
+//! - a `Jump` passes as many arguments as its `Join` has parameters
+//! - after `inc_dec`, every owned refcounted value is consumed exactly once on every path
+//!
+//! For the refcounts, we follow each path through a proc with a count per symbol. A value starts
+//! out owned once, except for borrowed parameters and projections out of another value, which
+//! start at zero (`inc_dec` inserts an explicit `inc` when they are owned). An `inc` adds to the
+//! count, a `dec` or a consuming use takes one away. At a `Ret`, every count must be back at zero.
+//! A join point body is checked once, starting from the counts where the join point is defined;
+//! each `Jump` to it must leave those same counts behind.
+
+use crate::borrow::{foreign_borrow_signature, lowlevel_borrow_signature, ParamMap};
+use crate::inc_dec::free_variables;
+use crate::ir::{
+    symbol_to_doc_string, BranchInfo, Call, CallType, Expr, JoinPointId, ListLiteralElement,
+    Literal, ModifyRc, Param, Proc, ProcLayout, Stmt,
+};
+use crate::layout::{Builtin, Layout, TagIdIntType, UnionLayout};
+use bumpalo::Bump;
+use roc_builtins::bitcode::IntWidth;
+use roc_collections::all::MutMap;
+use roc_module::symbol::Symbol;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem<'a> {
+    /// A symbol is used where it is not in scope
+    UndefinedSymbol(Symbol),
+    /// A symbol is defined while it is already in scope
+    RedefinedSymbol(Symbol),
+    /// A `Jump` to a join point that is not in scope
+    UndefinedJoinPoint(JoinPointId),
+    /// The layout of a `let` does not match the layout its expression produces
+    LetLayoutMismatch {
+        symbol: Symbol,
+        let_layout: Layout<'a>,
+        expr_layout: Layout<'a>,
+    },
+    /// A literal is bound at a layout that cannot hold it
+    LiteralLayoutMismatch {
+        symbol: Symbol,
+        literal: Literal<'a>,
+        layout: Layout<'a>,
+    },
+    /// A symbol is used at a different layout than it is defined with
+    SymbolLayoutMismatch {
+        symbol: Symbol,
+        def_layout: Layout<'a>,
+        use_layout: Layout<'a>,
+    },
+    /// A struct or union field is accessed at an index that does not exist
+    FieldIndexOutOfBounds { symbol: Symbol, index: u64 },
+    /// A call passes a different number of arguments than the callee has parameters
+    CallArityMismatch {
+        symbol: Symbol,
+        parameters: usize,
+        arguments: usize,
+    },
+    /// A `Jump` passes a different number of arguments than its `Join` has parameters
+    JumpArityMismatch {
+        id: JoinPointId,
+        parameters: usize,
+        arguments: usize,
+    },
+    /// An owned value is consumed more often than it is owned
+    ConsumedTooOften(Symbol),
+    /// An owned value is still owned when the proc returns
+    Leaked { symbol: Symbol, count: i64 },
+    /// A value is owned a different number of times at a `Jump` than at its `Join`
+    JumpRefcountMismatch {
+        id: JoinPointId,
+        symbol: Symbol,
+        at_join: i64,
+        at_jump: i64,
+    },
+}
+
+pub type Problems<'a> = MutMap<(Symbol, ProcLayout<'a>), Vec<Problem<'a>>>;
+
+/// Check all procs. The refcounts are only checked when `borrow_params` is given, which is
+/// what `inc_dec` was run with; before that, the IR has no refcount operations to check.
+pub fn check_procs<'a>(
+    arena: &'a Bump,
+    procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    borrow_params: Option<&ParamMap<'a>>,
+) -> Problems<'a> {
+    let mut problems = Problems::default();
+
+    for (key, proc) in procs.iter() {
+        let mut checker = Checker {
+            arena,
+            problems: Vec::new(),
+            scope: MutMap::default(),
+            defined: Vec::new(),
+            join_points: MutMap::default(),
+            ret_layout: proc.ret_layout,
+        };
+
+        for (layout, symbol) in proc.args.iter() {
+            checker.define(*symbol, *layout);
+        }
+        checker.check_stmt(&proc.body);
+
+        // Refcounts of malformed IR would only add noise to the report
+        if let (Some(param_map), true) = (borrow_params, checker.problems.is_empty()) {
+            let mut rc_checker = RefcountChecker {
+                arena,
+                param_map,
+                problems: Vec::new(),
+            };
+
+            rc_checker.check_proc(proc, key.1);
+            checker.problems.extend(rc_checker.problems);
+        }
+
+        if !checker.problems.is_empty() {
+            problems.insert(*key, checker.problems);
+        }
+    }
+
+    problems
+}
+
+/// Describe the problems, each followed by the proc it was found in
+pub fn format_problems<'a>(
+    procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    problems: &Problems<'a>,
+) -> String {
+    let mut output = String::new();
+
+    for (key, proc_problems) in problems.iter() {
+        output.push_str(&format!(
+            "In {} : {:?}\n",
+            symbol_to_doc_string(key.0),
+            key.1
+        ));
+
+        for problem in proc_problems {
+            output.push_str("    ");
+            output.push_str(&format_problem(problem));
+            output.push('\n');
+        }
+
+        if let Some(proc) = procs.get(key) {
+            output.push('\n');
+            output.push_str(&proc.to_pretty(200));
+            output.push_str("\n\n");
+        }
+    }
+
+    output
+}
+
+fn format_problem(problem: &Problem<'_>) -> String {
+    use Problem::*;
+
+    let s = symbol_to_doc_string;
+
+    match problem {
+        UndefinedSymbol(symbol) => format!("{} is used but not in scope", s(*symbol)),
+        RedefinedSymbol(symbol) => format!("{} is defined while already in scope", s(*symbol)),
+        UndefinedJoinPoint(id) => format!("jump to {}, which is not in scope", s(id.0)),
+        LetLayoutMismatch {
+            symbol,
+            let_layout,
+            expr_layout,
+        } => format!(
+            "{} is bound at layout {:?}, but its expression has layout {:?}",
+            s(*symbol),
+            let_layout,
+            expr_layout
+        ),
+        LiteralLayoutMismatch {
+            symbol,
+            literal,
+            layout,
+        } => format!(
+            "{} is bound to the literal {:?} at layout {:?}",
+            s(*symbol),
+            literal,
+            layout
+        ),
+        SymbolLayoutMismatch {
+            symbol,
+            def_layout,
+            use_layout,
+        } => format!(
+            "{} is defined at layout {:?}, but used at layout {:?}",
+            s(*symbol),
+            def_layout,
+            use_layout
+        ),
+        FieldIndexOutOfBounds { symbol, index } => {
+            format!("{} uses field {}, which does not exist", s(*symbol), index)
+        }
+        CallArityMismatch {
+            symbol,
+            parameters,
+            arguments,
+        } => format!(
+            "{} calls a function of {} parameters with {} arguments",
+            s(*symbol),
+            parameters,
+            arguments
+        ),
+        JumpArityMismatch {
+            id,
+            parameters,
+            arguments,
+        } => format!(
+            "jump to {}, which has {} parameters, with {} arguments",
+            s(id.0),
+            parameters,
+            arguments
+        ),
+        ConsumedTooOften(symbol) => format!("{} is consumed more often than owned", s(*symbol)),
+        Leaked { symbol, count } => {
+            format!(
+                "{} is still owned {} time(s) at a return",
+                s(*symbol),
+                count
+            )
+        }
+        JumpRefcountMismatch {
+            id,
+            symbol,
+            at_join,
+            at_jump,
+        } => format!(
+            "{} is owned {} time(s) at a jump to {}, but {} time(s) where it is defined",
+            s(*symbol),
+            at_jump,
+            s(id.0),
+            at_join
+        ),
+    }
+}
+
+struct Checker<'a> {
+    arena: &'a Bump,
+    problems: Vec<Problem<'a>>,
+    /// The symbols in scope, with their layouts
+    scope: MutMap<Symbol, Layout<'a>>,
+    /// The symbols in scope, in the order they were defined, so we can leave a scope
+    defined: Vec<Symbol>,
+    join_points: MutMap<JoinPointId, &'a [Param<'a>]>,
+    ret_layout: Layout<'a>,
+}
+
+impl<'a> Checker<'a> {
+    fn define(&mut self, symbol: Symbol, layout: Layout<'a>) {
+        if self.scope.insert(symbol, layout).is_some() {
+            self.problems.push(Problem::RedefinedSymbol(symbol));
+        } else {
+            self.defined.push(symbol);
+        }
+    }
+
+    fn leave_scope(&mut self, defined_len: usize) {
+        for symbol in self.defined.drain(defined_len..) {
+            self.scope.remove(&symbol);
+        }
+    }
+
+    fn use_symbol(&mut self, symbol: Symbol) -> Option<Layout<'a>> {
+        let layout = self.scope.get(&symbol).copied();
+
+        if layout.is_none() {
+            self.problems.push(Problem::UndefinedSymbol(symbol));
+        }
+
+        layout
+    }
+
+    fn use_symbol_at(&mut self, symbol: Symbol, use_layout: Layout<'a>) {
+        if let Some(def_layout) = self.use_symbol(symbol) {
+            if !layouts_agree(&def_layout, &use_layout) {
+                self.problems.push(Problem::SymbolLayoutMismatch {
+                    symbol,
+                    def_layout,
+                    use_layout,
+                });
+            }
+        }
+    }
+
+    fn check_stmt(&mut self, mut stmt: &Stmt<'a>) {
+        let defined_len = self.defined.len();
+        let mut shadowed_join_points = Vec::new();
+
+        // let-chains can be very long, so we don't recurse into the continuation of a statement
+        loop {
+            match stmt {
+                Stmt::Let(symbol, expr, layout, cont) => {
+                    self.check_expr(*symbol, expr, *layout);
+                    self.define(*symbol, *layout);
+                    stmt = cont;
+                }
+                Stmt::Refcounting(modify, cont) => {
+                    self.use_symbol(modify.get_symbol());
+                    stmt = cont;
+                }
+                Stmt::Expect {
+                    condition,
+                    lookups,
+                    layouts,
+                    remainder,
+                    ..
+                } => {
+                    self.use_symbol_at(*condition, Layout::bool());
+                    for (symbol, layout) in lookups.iter().zip(layouts.iter()) {
+                        self.use_symbol_at(*symbol, *layout);
+                    }
+                    stmt = remainder;
+                }
+                Stmt::Dbg {
+                    symbol,
+                    layout,
+                    remainder,
+                    ..
+                } => {
+                    self.use_symbol_at(*symbol, *layout);
+                    stmt = remainder;
+                }
+                Stmt::Switch {
+                    cond_symbol,
+                    cond_layout,
+                    branches,
+                    default_branch,
+                    ..
+                } => {
+                    self.use_symbol_at(*cond_symbol, *cond_layout);
+
+                    let branches = branches.iter().map(|(_, info, branch)| (info, branch));
+                    for (info, branch) in branches.chain([(&default_branch.0, default_branch.1)]) {
+                        if let BranchInfo::Constructor {
+                            scrutinee, layout, ..
+                        } = info
+                        {
+                            self.use_symbol_at(*scrutinee, *layout);
+                        }
+                        self.check_stmt(branch);
+                    }
+                    break;
+                }
+                Stmt::Join {
+                    id,
+                    parameters,
+                    body,
+                    remainder,
+                } => {
+                    // the body can jump to itself
+                    let shadowed = self.join_points.insert(*id, parameters);
+                    shadowed_join_points.push((*id, shadowed));
+
+                    let body_defined_len = self.defined.len();
+                    for param in parameters.iter() {
+                        self.define(param.symbol, param.layout);
+                    }
+                    self.check_stmt(body);
+                    self.leave_scope(body_defined_len);
+
+                    stmt = remainder;
+                }
+                Stmt::Jump(id, arguments) => {
+                    match self.join_points.get(id) {
+                        Some(parameters) => {
+                            let parameters: &'a [Param<'a>] = parameters;
+
+                            if parameters.len() != arguments.len() {
+                                self.problems.push(Problem::JumpArityMismatch {
+                                    id: *id,
+                                    parameters: parameters.len(),
+                                    arguments: arguments.len(),
+                                });
+                            }
+
+                            for (argument, param) in arguments.iter().zip(parameters.iter()) {
+                                self.use_symbol_at(*argument, param.layout);
+                            }
+                        }
+                        None => {
+                            self.problems.push(Problem::UndefinedJoinPoint(*id));
+
+                            for argument in arguments.iter() {
+                                self.use_symbol(*argument);
+                            }
+                        }
+                    }
+                    break;
+                }
+                Stmt::Ret(symbol) => {
+                    self.use_symbol_at(*symbol, self.ret_layout);
+                    break;
+                }
+                Stmt::Crash(symbol) => {
+                    self.use_symbol(*symbol);
+                    break;
+                }
+                Stmt::RuntimeError(_) => break,
+            }
+        }
+
+        for (id, shadowed) in shadowed_join_points.into_iter().rev() {
+            match shadowed {
+                Some(parameters) => self.join_points.insert(id, parameters),
+                None => self.join_points.remove(&id),
+            };
+        }
+        self.leave_scope(defined_len);
+    }
+
+    fn check_expr(&mut self, symbol: Symbol, expr: &Expr<'a>, layout: Layout<'a>) {
+        use Expr::*;
+
+        let expr_layout = match expr {
+            Literal(literal) => {
+                if !literal_fits(literal, &layout) {
+                    self.problems.push(Problem::LiteralLayoutMismatch {
+                        symbol,
+                        literal: *literal,
+                        layout,
+                    });
+                }
+                None
+            }
+            Call(call) => self.check_call(symbol, call),
+            Tag {
+                tag_layout,
+                arguments,
+                ..
+            }
+            | Reuse {
+                tag_layout,
+                arguments,
+                ..
+            } => {
+                if let Reuse { symbol: token, .. } = expr {
+                    self.use_symbol(*token);
+                }
+                for argument in arguments.iter() {
+                    self.use_symbol(*argument);
+                }
+                Some(Layout::Union(*tag_layout))
+            }
+            Struct(fields) => {
+                // a field that is not in scope could have any layout
+                let arena = self.arena;
+                let field_layouts = arena.alloc_slice_fill_iter(
+                    fields
+                        .iter()
+                        .map(|field| self.use_symbol(*field).unwrap_or(Layout::RecursivePointer)),
+                );
+
+                Some(Layout::struct_no_name_order(field_layouts))
+            }
+            StructAtIndex {
+                index,
+                field_layouts,
+                structure,
+            } => {
+                if let Some(structure_layout) = self.use_symbol(*structure) {
+                    let expected = Layout::struct_no_name_order(field_layouts);
+                    if !layouts_agree(&structure_layout, &expected) {
+                        self.problems.push(Problem::SymbolLayoutMismatch {
+                            symbol: *structure,
+                            def_layout: structure_layout,
+                            use_layout: expected,
+                        });
+                    }
+                }
+
+                match field_layouts.get(*index as usize) {
+                    Some(field_layout) => Some(*field_layout),
+                    None => {
+                        self.problems.push(Problem::FieldIndexOutOfBounds {
+                            symbol,
+                            index: *index,
+                        });
+                        None
+                    }
+                }
+            }
+            GetTagId {
+                structure,
+                union_layout,
+            } => {
+                self.use_symbol_at(*structure, Layout::Union(*union_layout));
+                Some(union_layout.tag_id_layout())
+            }
+            UnionAtIndex {
+                structure,
+                tag_id,
+                union_layout,
+                index,
+            } => {
+                self.use_symbol_at(*structure, Layout::Union(*union_layout));
+
+                let field_layout = union_field_layout(*union_layout, *tag_id, *index as usize);
+                if field_layout.is_none() {
+                    self.problems.push(Problem::FieldIndexOutOfBounds {
+                        symbol,
+                        index: *index,
+                    });
+                }
+                field_layout
+            }
+            SetUnionAtIndex {
+                structure,
+                tag_id,
+                union_layout,
+                index,
+                value,
+            } => {
+                self.use_symbol_at(*structure, Layout::Union(*union_layout));
+
+                match union_field_layout(*union_layout, *tag_id, *index as usize) {
+                    Some(field_layout) => self.use_symbol_at(*value, field_layout),
+                    None => {
+                        self.use_symbol(*value);
+                        self.problems.push(Problem::FieldIndexOutOfBounds {
+                            symbol,
+                            index: *index,
+                        });
+                    }
+                }
+
+                Some(Layout::UNIT)
+            }
+            Array { elem_layout, elems } => {
+                for elem in elems.iter() {
+                    if let ListLiteralElement::Symbol(elem) = elem {
+                        self.use_symbol_at(*elem, *elem_layout);
+                    }
+                }
+
+                Some(Layout::Builtin(Builtin::List(
+                    self.arena.alloc(*elem_layout),
+                )))
+            }
+            // the element layout of an empty list is not known
+            EmptyArray => Some(Layout::Builtin(Builtin::List(&Layout::RecursivePointer))),
+            ExprBox { symbol: inner } => self
+                .use_symbol(*inner)
+                .map(|inner_layout| Layout::Boxed(self.arena.alloc(inner_layout))),
+            ExprUnbox { symbol: boxed } => match self.use_symbol(*boxed) {
+                Some(Layout::Boxed(inner_layout)) => Some(*inner_layout),
+                Some(other) => {
+                    self.problems.push(Problem::SymbolLayoutMismatch {
+                        symbol: *boxed,
+                        def_layout: other,
+                        use_layout: Layout::Boxed(&Layout::RecursivePointer),
+                    });
+                    None
+                }
+                None => None,
+            },
+            Reset { symbol: reset, .. } => {
+                self.use_symbol(*reset);
+                None
+            }
+            RuntimeErrorFunction(_) => None,
+        };
+
+        if let Some(expr_layout) = expr_layout {
+            if !layouts_agree(&layout, &expr_layout) {
+                self.problems.push(Problem::LetLayoutMismatch {
+                    symbol,
+                    let_layout: layout,
+                    expr_layout,
+                });
+            }
+        }
+    }
+
+    fn check_call(&mut self, symbol: Symbol, call: &Call<'a>) -> Option<Layout<'a>> {
+        match &call.call_type {
+            CallType::ByName {
+                ret_layout,
+                arg_layouts,
+                ..
+            } => {
+                if arg_layouts.len() != call.arguments.len() {
+                    self.problems.push(Problem::CallArityMismatch {
+                        symbol,
+                        parameters: arg_layouts.len(),
+                        arguments: call.arguments.len(),
+                    });
+                }
+
+                for (argument, layout) in call.arguments.iter().zip(arg_layouts.iter()) {
+                    self.use_symbol_at(*argument, *layout);
+                }
+
+                Some(**ret_layout)
+            }
+            CallType::Foreign { ret_layout, .. } => {
+                for argument in call.arguments.iter() {
+                    self.use_symbol(*argument);
+                }
+
+                Some(**ret_layout)
+            }
+            CallType::LowLevel { .. } => {
+                for argument in call.arguments.iter() {
+                    self.use_symbol(*argument);
+                }
+
+                None
+            }
+            CallType::HigherOrder(higher_order) => {
+                for argument in call.arguments.iter() {
+                    self.use_symbol(*argument);
+                }
+                self.use_symbol(higher_order.passed_function.captured_environment);
+
+                None
+            }
+        }
+    }
+}
+
+fn literal_fits(literal: &Literal<'_>, layout: &Layout<'_>) -> bool {
+    use Builtin::*;
+
+    let builtin = match layout.runtime_representation() {
+        Layout::Builtin(builtin) => builtin,
+        _ => return false,
+    };
+
+    match literal {
+        Literal::Int(_) | Literal::U128(_) => matches!(builtin, Int(_)),
+        Literal::Float(_) => matches!(builtin, Float(_)),
+        Literal::Decimal(_) => matches!(builtin, Decimal),
+        Literal::Str(_) => matches!(builtin, Str),
+        Literal::Bool(_) => matches!(builtin, Bool),
+        Literal::Byte(_) => matches!(builtin, Int(IntWidth::U8)),
+    }
+}
+
+/// Like `UnionLayout::layout_at`, but `None` for a field that does not exist
+fn union_field_layout(
+    union_layout: UnionLayout<'_>,
+    tag_id: TagIdIntType,
+    index: usize,
+) -> Option<Layout<'_>> {
+    let field_layouts = match union_layout {
+        UnionLayout::NonRecursive(tags) | UnionLayout::Recursive(tags) => {
+            tags.get(tag_id as usize)?
+        }
+        UnionLayout::NonNullableUnwrapped(fields) => fields,
+        UnionLayout::NullableWrapped {
+            nullable_id,
+            other_tags,
+        } => {
+            if tag_id == nullable_id {
+                return None;
+            }
+
+            let tag_index = if tag_id < nullable_id {
+                tag_id
+            } else {
+                tag_id - 1
+            };
+            other_tags.get(tag_index as usize)?
+        }
+        UnionLayout::NullableUnwrapped {
+            nullable_id,
+            other_fields,
+        } => {
+            if nullable_id == (tag_id != 0) {
+                return None;
+            }
+
+            other_fields
+        }
+    };
+
+    field_layouts
+        .get(index)
+        .map(|field_layout| match field_layout {
+            Layout::RecursivePointer => Layout::Union(union_layout),
+            other => *other,
+        })
+}
+
+/// Layouts agree if they have the same runtime representation, where a recursive pointer stands
+/// for any union it could point to.
+fn layouts_agree(a: &Layout<'_>, b: &Layout<'_>) -> bool {
+    if a == b {
+        return true;
+    }
+
+    match (a.runtime_representation(), b.runtime_representation()) {
+        (Layout::RecursivePointer, _) | (_, Layout::RecursivePointer) => true,
+        (
+            Layout::Struct {
+                field_layouts: fields_a,
+                ..
+            },
+            Layout::Struct {
+                field_layouts: fields_b,
+                ..
+            },
+        ) => all_agree(fields_a, fields_b),
+        (Layout::Builtin(Builtin::List(a)), Layout::Builtin(Builtin::List(b))) => {
+            layouts_agree(a, b)
+        }
+        (Layout::Boxed(a), Layout::Boxed(b)) => layouts_agree(a, b),
+        (Layout::Union(a), Layout::Union(b)) => unions_agree(a, b),
+        (a, b) => a == b,
+    }
+}
+
+fn unions_agree(a: UnionLayout<'_>, b: UnionLayout<'_>) -> bool {
+    use UnionLayout::*;
+
+    match (a, b) {
+        (NonRecursive(tags_a), NonRecursive(tags_b)) | (Recursive(tags_a), Recursive(tags_b)) => {
+            tags_agree(tags_a, tags_b)
+        }
+        (NonNullableUnwrapped(fields_a), NonNullableUnwrapped(fields_b)) => {
+            all_agree(fields_a, fields_b)
+        }
+        (
+            NullableWrapped {
+                nullable_id: id_a,
+                other_tags: tags_a,
+            },
+            NullableWrapped {
+                nullable_id: id_b,
+                other_tags: tags_b,
+            },
+        ) => id_a == id_b && tags_agree(tags_a, tags_b),
+        (
+            NullableUnwrapped {
+                nullable_id: id_a,
+                other_fields: fields_a,
+            },
+            NullableUnwrapped {
+                nullable_id: id_b,
+                other_fields: fields_b,
+            },
+        ) => id_a == id_b && all_agree(fields_a, fields_b),
+        _ => false,
+    }
+}
+
+fn tags_agree(tags_a: &[&[Layout<'_>]], tags_b: &[&[Layout<'_>]]) -> bool {
+    tags_a.len() == tags_b.len()
+        && tags_a
+            .iter()
+            .zip(tags_b.iter())
+            .all(|(a, b)| all_agree(a, b))
+}
+
+fn all_agree(layouts_a: &[Layout<'_>], layouts_b: &[Layout<'_>]) -> bool {
+    layouts_a.len() == layouts_b.len()
+        && layouts_a
+            .iter()
+            .zip(layouts_b.iter())
+            .all(|(a, b)| layouts_agree(a, b))
+}
+
+/// How many times each tracked symbol is owned on the current path
+type Counts = MutMap<Symbol, i64>;
+
+#[derive(Clone)]
+struct JoinPointInfo<'a> {
+    parameters: &'a [Param<'a>],
+    /// The counts of the symbols from outside the join point that its body uses
+    at_join: Counts,
+}
+
+struct RefcountChecker<'a, 'r> {
+    arena: &'a Bump,
+    param_map: &'r ParamMap<'a>,
+    problems: Vec<Problem<'a>>,
+}
+
+impl<'a, 'r> RefcountChecker<'a, 'r> {
+    fn check_proc(&mut self, proc: &Proc<'a>, layout: ProcLayout<'a>) {
+        let mut counts = Counts::default();
+
+        match self.param_map.get_symbol(proc.name.name(), layout) {
+            Some(params) => {
+                for param in params.iter() {
+                    track_param(&mut counts, param);
+                }
+            }
+            None => {
+                // inc_dec treats all params of a proc it has no borrow signature for as owned
+                for (layout, symbol) in proc.args.iter() {
+                    if layout.contains_refcounted() {
+                        counts.insert(*symbol, 1);
+                    }
+                }
+            }
+        }
+
+        self.check_stmt(&proc.body, &mut counts, &MutMap::default());
+    }
+
+    fn consume(&mut self, counts: &mut Counts, symbol: Symbol) {
+        // symbols that are not tracked are not refcounted
+        if let Some(count) = counts.get_mut(&symbol) {
+            if *count == 0 {
+                self.problems.push(Problem::ConsumedTooOften(symbol));
+            } else {
+                *count -= 1;
+            }
+        }
+    }
+
+    fn consume_all(&mut self, counts: &mut Counts, symbols: &[Symbol]) {
+        for symbol in symbols {
+            self.consume(counts, *symbol);
+        }
+    }
+
+    fn check_stmt(
+        &mut self,
+        mut stmt: &Stmt<'a>,
+        counts: &mut Counts,
+        join_points: &MutMap<JoinPointId, JoinPointInfo<'a>>,
+    ) {
+        let mut join_points = join_points.clone();
+
+        loop {
+            match stmt {
+                Stmt::Let(symbol, expr, layout, cont) => {
+                    self.check_expr(expr, counts);
+
+                    if layout.contains_refcounted() {
+                        let count = match expr {
+                            Expr::StructAtIndex { .. }
+                            | Expr::UnionAtIndex { .. }
+                            | Expr::GetTagId { .. }
+                            | Expr::ExprUnbox { .. } => 0,
+                            _ => 1,
+                        };
+                        counts.insert(*symbol, count);
+                    }
+                    stmt = cont;
+                }
+                Stmt::Refcounting(modify, cont) => {
+                    match modify {
+                        ModifyRc::Inc(symbol, n) => {
+                            *counts.entry(*symbol).or_insert(0) += *n as i64;
+                        }
+                        ModifyRc::Dec(symbol) | ModifyRc::DecRef(symbol) => {
+                            self.consume(counts, *symbol);
+                        }
+                    }
+                    stmt = cont;
+                }
+                Stmt::Expect {
+                    lookups, remainder, ..
+                } => {
+                    self.consume_all(counts, lookups);
+                    stmt = remainder;
+                }
+                Stmt::Dbg {
+                    symbol, remainder, ..
+                } => {
+                    self.consume(counts, *symbol);
+                    stmt = remainder;
+                }
+                Stmt::Switch {
+                    branches,
+                    default_branch,
+                    ..
+                } => {
+                    let branches = branches.iter().map(|(_, _, branch)| branch);
+                    for branch in branches.chain([default_branch.1]) {
+                        self.check_stmt(branch, &mut counts.clone(), &join_points);
+                    }
+                    return;
+                }
+                Stmt::Join {
+                    id,
+                    parameters,
+                    body,
+                    remainder,
+                } => {
+                    let free = free_variables(body);
+                    let at_join: Counts = counts
+                        .iter()
+                        .filter(|(symbol, _)| free.contains(symbol))
+                        .map(|(symbol, count)| (*symbol, *count))
+                        .collect();
+
+                    join_points.insert(
+                        *id,
+                        JoinPointInfo {
+                            parameters,
+                            at_join: at_join.clone(),
+                        },
+                    );
+
+                    let mut body_counts = at_join;
+                    for param in parameters.iter() {
+                        track_param(&mut body_counts, param);
+                    }
+                    self.check_stmt(body, &mut body_counts, &join_points);
+
+                    stmt = remainder;
+                }
+                Stmt::Jump(id, arguments) => {
+                    // the scope check has reported a jump to an unknown join point
+                    if let Some(info) = join_points.get(id) {
+                        for (argument, param) in arguments.iter().zip(info.parameters.iter()) {
+                            if !param.borrow {
+                                self.consume(counts, *argument);
+                            }
+                        }
+
+                        for (symbol, count) in counts.iter() {
+                            let at_join = info.at_join.get(symbol).copied().unwrap_or(0);
+                            if *count != at_join {
+                                self.problems.push(Problem::JumpRefcountMismatch {
+                                    id: *id,
+                                    symbol: *symbol,
+                                    at_join,
+                                    at_jump: *count,
+                                });
+                            }
+                        }
+                    }
+                    return;
+                }
+                Stmt::Ret(symbol) => {
+                    self.consume(counts, *symbol);
+
+                    for (symbol, count) in counts.iter() {
+                        if *count != 0 {
+                            self.problems.push(Problem::Leaked {
+                                symbol: *symbol,
+                                count: *count,
+                            });
+                        }
+                    }
+                    return;
+                }
+                Stmt::Crash(_) | Stmt::RuntimeError(_) => {
+                    // the program stops here, so leaking is fine
+                    return;
+                }
+            }
+        }
+    }
+
+    fn check_expr(&mut self, expr: &Expr<'a>, counts: &mut Counts) {
+        use Expr::*;
+
+        match expr {
+            Tag { arguments, .. } | Struct(arguments) => self.consume_all(counts, arguments),
+            Reuse {
+                symbol, arguments, ..
+            } => {
+                self.consume(counts, *symbol);
+                self.consume_all(counts, arguments);
+            }
+            Array { elems, .. } => {
+                for elem in elems.iter() {
+                    if let ListLiteralElement::Symbol(symbol) = elem {
+                        self.consume(counts, *symbol);
+                    }
+                }
+            }
+            ExprBox { symbol } | Reset { symbol, .. } => self.consume(counts, *symbol),
+            SetUnionAtIndex { value, .. } => self.consume(counts, *value),
+            Call(call) => self.check_call(call, counts),
+            Literal(_)
+            | StructAtIndex { .. }
+            | GetTagId { .. }
+            | UnionAtIndex { .. }
+            | EmptyArray
+            | ExprUnbox { .. }
+            | RuntimeErrorFunction(_) => {}
+        }
+    }
+
+    fn check_call(&mut self, call: &Call<'a>, counts: &mut Counts) {
+        let borrow_signature = match &call.call_type {
+            CallType::ByName {
+                name,
+                ret_layout,
+                arg_layouts,
+                ..
+            } => {
+                let layout = ProcLayout {
+                    arguments: arg_layouts,
+                    result: **ret_layout,
+                    captures_niche: name.captures_niche(),
+                };
+
+                match self.param_map.get_symbol(name.name(), layout) {
+                    Some(params) => {
+                        for (argument, param) in call.arguments.iter().zip(params.iter()) {
+                            if !param.borrow {
+                                self.consume(counts, *argument);
+                            }
+                        }
+                        return;
+                    }
+                    None => {
+                        self.consume_all(counts, call.arguments);
+                        return;
+                    }
+                }
+            }
+            CallType::Foreign { .. } => foreign_borrow_signature(self.arena, call.arguments.len()),
+            CallType::LowLevel { op, .. } => lowlevel_borrow_signature(self.arena, *op),
+            CallType::HigherOrder(_) => {
+                // inc_dec surrounds these calls with the incs and decs for their arguments
+                return;
+            }
+        };
+
+        for (argument, borrowed) in call.arguments.iter().zip(borrow_signature.iter()) {
+            if !*borrowed {
+                self.consume(counts, *argument);
+            }
+        }
+    }
+}
+
+fn track_param(counts: &mut Counts, param: &Param<'_>) {
+    if param.layout.contains_refcounted() {
+        counts.insert(param.symbol, if param.borrow { 0 } else { 1 });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::borrow::infer_borrow;
+    use crate::debug::parse_procs;
+    use roc_module::ident::ModuleName;
+    use roc_module::symbol::Interns;
+
+    /// Parse the procs and check them. The refcounts are checked against inferred borrow
+    /// signatures, which needs the calls and jumps to have the right number of arguments.
+    /// Returns the problems of all the procs, and a way to make the symbol `Test.<n>`.
+    fn check<'a>(
+        arena: &'a Bump,
+        src: &str,
+        check_refcounts: bool,
+    ) -> (Vec<Problem<'a>>, impl Fn(u32) -> Symbol) {
+        let mut interns = Interns::default();
+        let procs: MutMap<_, _> = parse_procs(arena, src, &mut interns)
+            .unwrap_or_else(|error| panic!("{}", error))
+            .into_iter()
+            .collect();
+
+        let param_map = check_refcounts.then(|| infer_borrow(arena, &procs));
+        let problems = check_procs(arena, &procs, param_map.as_ref())
+            .into_values()
+            .flatten()
+            .collect();
+
+        let home = interns.module_id(&ModuleName::from("Test"));
+        let test_symbol = move |ident_id| Interns::from_index(home, ident_id);
+
+        (problems, test_symbol)
+    }
+
+    #[test]
+    fn well_formed() {
+        let arena = &Bump::new();
+        let (problems, _) = check(
+            arena,
+            r#"
+            procedure Test.0 () -> I64 {
+                let Test.1 : Str = str "a";
+                join Test.2 () {
+                    dec Test.1;
+                    let Test.3 : I64 = int 0;
+                    ret Test.3;
+                }
+                jump Test.2 ();
+            }
+            "#,
+            true,
+        );
+
+        assert!(problems.is_empty(), "{:?}", problems);
+    }
+
+    #[test]
+    fn undefined_symbol() {
+        let arena = &Bump::new();
+        let (problems, test) = check(
+            arena,
+            r#"
+            procedure Test.0 () -> I64 {
+                ret Test.1;
+            }
+            "#,
+            false,
+        );
+
+        assert_eq!(problems, [Problem::UndefinedSymbol(test(1))]);
+    }
+
+    #[test]
+    fn redefined_symbol() {
+        let arena = &Bump::new();
+        let (problems, test) = check(
+            arena,
+            r#"
+            procedure Test.0 () -> I64 {
+                let Test.1 : I64 = int 1;
+                let Test.1 : I64 = int 2;
+                ret Test.1;
+            }
+            "#,
+            false,
+        );
+
+        assert_eq!(problems, [Problem::RedefinedSymbol(test(1))]);
+    }
+
+    #[test]
+    fn undefined_join_point() {
+        let arena = &Bump::new();
+        let (problems, test) = check(
+            arena,
+            r#"
+            procedure Test.0 () -> I64 {
+                jump Test.2 ();
+            }
+            "#,
+            false,
+        );
+
+        assert_eq!(
+            problems,
+            [Problem::UndefinedJoinPoint(JoinPointId(test(2)))]
+        );
+    }
+
+    #[test]
+    fn let_layout_mismatch() {
+        let arena = &Bump::new();
+        let (problems, test) = check(
+            arena,
+            r#"
+            procedure Test.0 () -> I64 {
+                let Test.1 : I64 = int 1;
+                let Test.2 : Str = struct (Test.1);
+                ret Test.1;
+            }
+            "#,
+            false,
+        );
+
+        assert_eq!(
+            problems,
+            [Problem::LetLayoutMismatch {
+                symbol: test(2),
+                let_layout: Layout::Builtin(Builtin::Str),
+                expr_layout: Layout::struct_no_name_order(arena.alloc([Layout::i64()])),
+            }]
+        );
+    }
+
+    #[test]
+    fn literal_layout_mismatch() {
+        let arena = &Bump::new();
+        let (problems, test) = check(
+            arena,
+            r#"
+            procedure Test.0 () -> Str {
+                let Test.1 : Str = int 1;
+                ret Test.1;
+            }
+            "#,
+            false,
+        );
+
+        assert_eq!(
+            problems,
+            [Problem::LiteralLayoutMismatch {
+                symbol: test(1),
+                literal: Literal::Int(1i128.to_ne_bytes()),
+                layout: Layout::Builtin(Builtin::Str),
+            }]
+        );
+    }
+
+    #[test]
+    fn symbol_layout_mismatch() {
+        let arena = &Bump::new();
+        let (problems, test) = check(
+            arena,
+            r#"
+            procedure Test.0 () -> I64 {
+                let Test.1 : F64 = float 1.5;
+                ret Test.1;
+            }
+            "#,
+            false,
+        );
+
+        assert_eq!(
+            problems,
+            [Problem::SymbolLayoutMismatch {
+                symbol: test(1),
+                def_layout: Layout::f64(),
+                use_layout: Layout::i64(),
+            }]
+        );
+    }
+
+    #[test]
+    fn field_index_out_of_bounds() {
+        let arena = &Bump::new();
+        let (problems, test) = check(
+            arena,
+            r#"
+            procedure Test.0 () -> I64 {
+                let Test.1 : I64 = int 1;
+                let Test.2 : {I64} = struct (Test.1);
+                let Test.3 : I64 = struct_at Test.2 1 of (I64);
+                ret Test.3;
+            }
+            "#,
+            false,
+        );
+
+        assert_eq!(
+            problems,
+            [Problem::FieldIndexOutOfBounds {
+                symbol: test(3),
+                index: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn call_arity_mismatch() {
+        let arena = &Bump::new();
+        let (problems, test) = check(
+            arena,
+            r#"
+            procedure Test.1 (Test.2 : I64) -> I64 {
+                ret Test.2;
+            }
+
+            procedure Test.0 () -> I64 {
+                let Test.3 : I64 = int 1;
+                let Test.4 : I64 = call Test.1 (Test.3, Test.3) : (I64) -> I64 spec 0;
+                ret Test.4;
+            }
+            "#,
+            false,
+        );
+
+        assert_eq!(
+            problems,
+            [Problem::CallArityMismatch {
+                symbol: test(4),
+                parameters: 1,
+                arguments: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn jump_arity_mismatch() {
+        let arena = &Bump::new();
+        let (problems, test) = check(
+            arena,
+            r#"
+            procedure Test.0 () -> I64 {
+                join Test.2 (Test.3 : I64) {
+                    ret Test.3;
+                }
+                let Test.1 : I64 = int 1;
+                jump Test.2 (Test.1, Test.1);
+            }
+            "#,
+            false,
+        );
+
+        assert_eq!(
+            problems,
+            [Problem::JumpArityMismatch {
+                id: JoinPointId(test(2)),
+                parameters: 1,
+                arguments: 2,
+            }]
+        );
+    }
+
+    #[test]
+    fn consumed_too_often() {
+        let arena = &Bump::new();
+        let (problems, test) = check(
+            arena,
+            r#"
+            procedure Test.0 () -> I64 {
+                let Test.1 : Str = str "a";
+                dec Test.1;
+                dec Test.1;
+                let Test.2 : I64 = int 0;
+                ret Test.2;
+            }
+            "#,
+            true,
+        );
+
+        assert_eq!(problems, [Problem::ConsumedTooOften(test(1))]);
+    }
+
+    #[test]
+    fn leaked() {
+        let arena = &Bump::new();
+        let (problems, test) = check(
+            arena,
+            r#"
+            procedure Test.0 () -> I64 {
+                let Test.1 : Str = str "a";
+                let Test.2 : I64 = int 0;
+                ret Test.2;
+            }
+            "#,
+            true,
+        );
+
+        assert_eq!(
+            problems,
+            [Problem::Leaked {
+                symbol: test(1),
+                count: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn jump_refcount_mismatch() {
+        let arena = &Bump::new();
+        let (problems, test) = check(
+            arena,
+            r#"
+            procedure Test.0 () -> I64 {
+                let Test.1 : Str = str "a";
+                join Test.2 () {
+                    dec Test.1;
+                    let Test.3 : I64 = int 0;
+                    ret Test.3;
+                }
+                inc Test.1 1;
+                jump Test.2 ();
+            }
+            "#,
+            true,
+        );
+
+        assert_eq!(
+            problems,
+            [Problem::JumpRefcountMismatch {
+                id: JoinPointId(test(2)),
+                symbol: test(1),
+                at_join: 1,
+                at_jump: 2,
+            }]
+        );
+    }
+}
@@ -0,0 +1,36 @@
+//! Tools for finding out what went wrong in the mono IR
+
+mod checker;
+mod parse;
+mod text;
+
+pub use checker::{check_procs, format_problems, Problem, Problems};
+pub use parse::{parse_procs, ParseError};
+pub use text::procs_to_text;
+
+use crate::borrow::ParamMap;
+use crate::ir::{Proc, ProcLayout};
+use bumpalo::Bump;
+use roc_collections::all::MutMap;
+use roc_error_macros::internal_error;
+use roc_module::symbol::Symbol;
+
+/// Check the procs, and report the problems if the mono IR is malformed.
+///   after_pass      the pass that produced the procs, to name in the report
+///   borrow_params   the borrow signatures that `inc_dec` used, to check the refcounts
+pub fn assert_procs_are_valid<'a>(
+    arena: &'a Bump,
+    procs: &MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    borrow_params: Option<&ParamMap<'a>>,
+    after_pass: &str,
+) {
+    let problems = check_procs(arena, procs, borrow_params);
+
+    if !problems.is_empty() {
+        internal_error!(
+            "The mono IR is malformed after {}:\n\n{}",
+            after_pass,
+            format_problems(procs, &problems)
+        );
+    }
+}
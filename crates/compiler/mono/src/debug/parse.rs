@@ -0,0 +1,1169 @@
+//! Parser for the textual mono IR syntax described in `text.rs`
+
+use crate::ir::{
+    BranchInfo, Call, CallSpecId, CallType, Expr, HigherOrderLowLevel, HostExposedLayouts,
+    JoinPointId, ListLiteralElement, Literal, ModifyRc, Param, PassedFunction, Proc, ProcLayout,
+    SelfRecursive, Stmt, UpdateModeId,
+};
+use crate::layout::{Builtin, CapturesNiche, LambdaName, LambdaSet, Layout, UnionLayout};
+use crate::low_level::HigherOrder;
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
+use roc_module::ident::{ForeignSymbol, ModuleName};
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::{Interns, Symbol};
+use roc_region::all::{Position, Region};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line of the token where parsing failed
+    pub line: usize,
+    /// 1-based column of the token where parsing failed
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.line, self.column, self.message)
+    }
+}
+
+type ParseResult<T> = Result<T, ParseError>;
+
+/// Parse procs in the textual syntax, in the order they are written, together with the
+/// key they would have in the procedures of a module.
+///
+/// Module names are looked up in (or added to) `interns`, so a symbol like `Test.3` refers
+/// to ident 3 of the module named `Test`.
+pub fn parse_procs<'a>(
+    arena: &'a Bump,
+    src: &str,
+    interns: &mut Interns,
+) -> ParseResult<std::vec::Vec<((Symbol, ProcLayout<'a>), Proc<'a>)>> {
+    let mut parser = Parser {
+        arena,
+        interns,
+        src,
+        tokens: tokenize(src)?,
+        pos: 0,
+    };
+
+    let mut procs = std::vec::Vec::new();
+    while parser.pos < parser.tokens.len() {
+        let proc = parser.proc()?;
+        let layout = ProcLayout {
+            arguments: arena.alloc_slice_fill_iter(proc.args.iter().map(|(layout, _)| *layout)),
+            result: proc.ret_layout,
+            captures_niche: proc.name.captures_niche(),
+        };
+        procs.push(((proc.name.name(), layout), proc));
+    }
+
+    Ok(procs)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token<'s> {
+    Word(&'s str),
+    Str(String),
+    Punct(&'static str),
+}
+
+const PUNCTUATION: &[&str] = &["->", "=>", "(", ")", "{", "}", "[", "]", ",", ";", ":", "="];
+
+fn tokenize(src: &str) -> ParseResult<std::vec::Vec<(Token<'_>, usize)>> {
+    let mut tokens = std::vec::Vec::new();
+    let mut offset = 0;
+
+    'tokens: while offset < src.len() {
+        let rest = &src[offset..];
+        let c = rest.chars().next().unwrap();
+
+        if c.is_whitespace() {
+            offset += c.len_utf8();
+            continue;
+        }
+
+        for punct in PUNCTUATION {
+            if rest.starts_with(punct) {
+                tokens.push((Token::Punct(punct), offset));
+                offset += punct.len();
+                continue 'tokens;
+            }
+        }
+
+        if c == '"' {
+            let (string, len) = unescape(src, offset)?;
+            tokens.push((Token::Str(string), offset));
+            offset += len;
+            continue;
+        }
+
+        let len = rest
+            .char_indices()
+            .find(|(i, c)| {
+                c.is_whitespace()
+                    || *c == '"'
+                    || PUNCTUATION
+                        .iter()
+                        .any(|punct| rest[*i..].starts_with(punct))
+            })
+            .map(|(i, _)| i)
+            .unwrap_or(rest.len());
+        tokens.push((Token::Word(&rest[..len]), offset));
+        offset += len;
+    }
+
+    Ok(tokens)
+}
+
+/// Read the string literal starting at `start`, returning its contents and its length in the source
+fn unescape(src: &str, start: usize) -> ParseResult<(String, usize)> {
+    let mut string = String::new();
+    let mut chars = src[start..].char_indices().skip(1);
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Ok((string, i + 1)),
+            '\\' => {
+                let escaped = match chars.next() {
+                    Some((_, 'n')) => '\n',
+                    Some((_, 'r')) => '\r',
+                    Some((_, 't')) => '\t',
+                    Some((_, '0')) => '\0',
+                    Some((_, '\\')) => '\\',
+                    Some((_, '"')) => '"',
+                    Some((_, '\'')) => '\'',
+                    Some((_, 'u')) => {
+                        let hex: String = chars
+                            .by_ref()
+                            .map(|(_, c)| c)
+                            .skip_while(|c| *c == '{')
+                            .take_while(|c| *c != '}')
+                            .collect();
+                        match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                            Some(c) => c,
+                            None => return Err(error_at(src, start + i, "invalid unicode escape")),
+                        }
+                    }
+                    _ => return Err(error_at(src, start + i, "invalid escape")),
+                };
+                string.push(escaped);
+            }
+            c => string.push(c),
+        }
+    }
+
+    Err(error_at(src, start, "unterminated string"))
+}
+
+fn error_at(src: &str, offset: usize, message: impl Into<String>) -> ParseError {
+    let before = &src[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+
+    ParseError {
+        line,
+        column: before[line_start..].chars().count() + 1,
+        message: message.into(),
+    }
+}
+
+/// Everything that comes before the rest of a block. Building the statements from the end of a
+/// block keeps long let-chains from recursing.
+enum Prefix<'a> {
+    Let(Symbol, Expr<'a>, Layout<'a>),
+    Refcounting(ModifyRc),
+    Expect {
+        condition: Symbol,
+        region: Region,
+        lookups: &'a [Symbol],
+        layouts: &'a [Layout<'a>],
+    },
+    Dbg {
+        symbol: Symbol,
+        region: Region,
+        layout: Layout<'a>,
+    },
+    Join {
+        id: JoinPointId,
+        parameters: &'a [Param<'a>],
+        body: &'a Stmt<'a>,
+    },
+}
+
+struct Parser<'a, 's, 'i> {
+    arena: &'a Bump,
+    interns: &'i mut Interns,
+    src: &'s str,
+    tokens: std::vec::Vec<(Token<'s>, usize)>,
+    pos: usize,
+}
+
+impl<'a, 's, 'i> Parser<'a, 's, 'i> {
+    fn error<T>(&self, message: impl Into<String>) -> ParseResult<T> {
+        let offset = match self.tokens.get(self.pos) {
+            Some((_, offset)) => *offset,
+            None => self.src.len(),
+        };
+
+        Err(error_at(self.src, offset, message))
+    }
+
+    fn peek(&self) -> Option<&Token<'s>> {
+        self.tokens.get(self.pos).map(|(token, _)| token)
+    }
+
+    fn next(&mut self) -> ParseResult<Token<'s>> {
+        match self.tokens.get(self.pos) {
+            Some((token, _)) => {
+                self.pos += 1;
+                Ok(token.clone())
+            }
+            None => self.error("unexpected end of input"),
+        }
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Punct(p)) if *p == punct) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> ParseResult<()> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            self.error(format!("expected `{}`", punct))
+        }
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Word(w)) if *w == word) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_word(&mut self, word: &str) -> ParseResult<()> {
+        if self.eat_word(word) {
+            Ok(())
+        } else {
+            self.error(format!("expected `{}`", word))
+        }
+    }
+
+    fn word(&mut self) -> ParseResult<&'s str> {
+        match self.peek() {
+            Some(Token::Word(word)) => {
+                let word = *word;
+                self.pos += 1;
+                Ok(word)
+            }
+            _ => self.error("expected a word"),
+        }
+    }
+
+    fn string(&mut self) -> ParseResult<&'a str> {
+        match self.peek() {
+            Some(Token::Str(string)) => {
+                let string = self.arena.alloc_str(string);
+                self.pos += 1;
+                Ok(string)
+            }
+            _ => self.error("expected a string"),
+        }
+    }
+
+    fn number<T: FromStr>(&mut self) -> ParseResult<T> {
+        let word = self.word()?;
+
+        match word.parse() {
+            Ok(n) => Ok(n),
+            Err(_) => {
+                self.pos -= 1;
+                self.error(format!("`{}` is not a valid number here", word))
+            }
+        }
+    }
+
+    fn symbol(&mut self) -> ParseResult<Symbol> {
+        let word = self.word()?;
+
+        let parsed = word.rsplit_once('.').and_then(|(module_name, ident_id)| {
+            match (module_name.is_empty(), ident_id.parse::<u32>()) {
+                (false, Ok(ident_id)) => Some((module_name, ident_id)),
+                _ => None,
+            }
+        });
+
+        match parsed {
+            Some((module_name, ident_id)) => {
+                let module_id = self.interns.module_id(&ModuleName::from(module_name));
+                Ok(Interns::from_index(module_id, ident_id))
+            }
+            None => {
+                self.pos -= 1;
+                self.error(format!("`{}` is not a symbol like `Test.1`", word))
+            }
+        }
+    }
+
+    fn region(&mut self) -> ParseResult<Region> {
+        let word = self.word()?;
+
+        let parsed = word
+            .strip_prefix('@')
+            .and_then(|range| range.split_once('-'))
+            .and_then(|(start, end)| Some((start.parse().ok()?, end.parse().ok()?)));
+
+        match parsed {
+            Some((start, end)) => Ok(Region::new(Position::new(start), Position::new(end))),
+            None => {
+                self.pos -= 1;
+                self.error(format!("`{}` is not a region like `@12-34`", word))
+            }
+        }
+    }
+
+    /// A comma-separated sequence between `open` and `close`
+    fn sequence<T>(
+        &mut self,
+        open: &str,
+        close: &str,
+        mut item: impl FnMut(&mut Self) -> ParseResult<T>,
+    ) -> ParseResult<Vec<'a, T>> {
+        let mut items = Vec::new_in(self.arena);
+
+        self.expect_punct(open)?;
+        if self.eat_punct(close) {
+            return Ok(items);
+        }
+
+        loop {
+            items.push(item(self)?);
+
+            if self.eat_punct(close) {
+                return Ok(items);
+            }
+            self.expect_punct(",")?;
+        }
+    }
+
+    fn symbols(&mut self) -> ParseResult<&'a [Symbol]> {
+        Ok(self.sequence("(", ")", Self::symbol)?.into_bump_slice())
+    }
+
+    fn layouts(&mut self) -> ParseResult<&'a [Layout<'a>]> {
+        Ok(self.sequence("(", ")", Self::layout)?.into_bump_slice())
+    }
+
+    fn niche(&mut self) -> ParseResult<CapturesNiche<'a>> {
+        if self.eat_word("niche") {
+            Ok(CapturesNiche::from_layouts(self.layouts()?))
+        } else {
+            Ok(CapturesNiche::no_niche())
+        }
+    }
+
+    fn proc(&mut self) -> ParseResult<Proc<'a>> {
+        self.expect_word("procedure")?;
+        let name = self.symbol()?;
+        let captures_niche = self.niche()?;
+        let args = self.sequence("(", ")", |p| {
+            let symbol = p.symbol()?;
+            p.expect_punct(":")?;
+            Ok((p.layout()?, symbol))
+        })?;
+        self.expect_punct("->")?;
+        let ret_layout = self.layout()?;
+
+        let mut closure_data_layout = None;
+        let mut is_self_recursive = SelfRecursive::NotSelfRecursive;
+        let mut must_own_arguments = false;
+        let mut region = Region::zero();
+        let mut symbol_regions: &[(Symbol, Region)] = &[];
+
+        loop {
+            if self.eat_word("closure") {
+                closure_data_layout = Some(self.layout()?);
+            } else if self.eat_word("self_recursive") {
+                is_self_recursive = SelfRecursive::SelfRecursive(JoinPointId(self.symbol()?));
+            } else if self.eat_word("must_own") {
+                must_own_arguments = true;
+            } else if self.eat_word("regions") {
+                symbol_regions = self
+                    .sequence("(", ")", |p| Ok((p.symbol()?, p.region()?)))?
+                    .into_bump_slice();
+            } else if matches!(self.peek(), Some(Token::Word(w)) if w.starts_with('@')) {
+                region = self.region()?;
+            } else {
+                break;
+            }
+        }
+
+        let body = self.block()?;
+
+        Ok(Proc {
+            name: LambdaName::from_parts(name, captures_niche),
+            args: args.into_bump_slice(),
+            body: body.clone(),
+            closure_data_layout,
+            ret_layout,
+            is_self_recursive,
+            must_own_arguments,
+            host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+            region,
+            symbol_regions,
+        })
+    }
+
+    fn block(&mut self) -> ParseResult<&'a Stmt<'a>> {
+        self.expect_punct("{")?;
+        let stmt = self.stmt()?;
+        self.expect_punct("}")?;
+
+        Ok(stmt)
+    }
+
+    fn stmt(&mut self) -> ParseResult<&'a Stmt<'a>> {
+        let mut prefixes = std::vec::Vec::new();
+
+        let mut stmt = loop {
+            let keyword = self.word()?;
+
+            match keyword {
+                "let" => {
+                    let symbol = self.symbol()?;
+                    self.expect_punct(":")?;
+                    let layout = self.layout()?;
+                    self.expect_punct("=")?;
+                    let expr = self.expr()?;
+                    self.expect_punct(";")?;
+                    prefixes.push(Prefix::Let(symbol, expr, layout));
+                }
+                "inc" => {
+                    let symbol = self.symbol()?;
+                    let n = self.number()?;
+                    self.expect_punct(";")?;
+                    prefixes.push(Prefix::Refcounting(ModifyRc::Inc(symbol, n)));
+                }
+                "dec" => {
+                    let symbol = self.symbol()?;
+                    self.expect_punct(";")?;
+                    prefixes.push(Prefix::Refcounting(ModifyRc::Dec(symbol)));
+                }
+                "decref" => {
+                    let symbol = self.symbol()?;
+                    self.expect_punct(";")?;
+                    prefixes.push(Prefix::Refcounting(ModifyRc::DecRef(symbol)));
+                }
+                "expect" => {
+                    let condition = self.symbol()?;
+                    let region = self.region()?;
+                    let lookups = self.sequence("(", ")", |p| {
+                        let symbol = p.symbol()?;
+                        p.expect_punct(":")?;
+                        Ok((symbol, p.layout()?))
+                    })?;
+                    self.expect_punct(";")?;
+                    prefixes.push(Prefix::Expect {
+                        condition,
+                        region,
+                        lookups: self
+                            .arena
+                            .alloc_slice_fill_iter(lookups.iter().map(|(symbol, _)| *symbol)),
+                        layouts: self
+                            .arena
+                            .alloc_slice_fill_iter(lookups.iter().map(|(_, layout)| *layout)),
+                    });
+                }
+                "dbg" => {
+                    let symbol = self.symbol()?;
+                    self.expect_punct(":")?;
+                    let layout = self.layout()?;
+                    let region = self.region()?;
+                    self.expect_punct(";")?;
+                    prefixes.push(Prefix::Dbg {
+                        symbol,
+                        region,
+                        layout,
+                    });
+                }
+                "join" => {
+                    let id = JoinPointId(self.symbol()?);
+                    let parameters = self.sequence("(", ")", |p| {
+                        let borrow = p.eat_word("borrowed");
+                        let symbol = p.symbol()?;
+                        p.expect_punct(":")?;
+                        Ok(Param {
+                            symbol,
+                            borrow,
+                            layout: p.layout()?,
+                        })
+                    })?;
+                    let body = self.block()?;
+                    prefixes.push(Prefix::Join {
+                        id,
+                        parameters: parameters.into_bump_slice(),
+                        body,
+                    });
+                }
+                "ret" => {
+                    let symbol = self.symbol()?;
+                    self.expect_punct(";")?;
+                    break Stmt::Ret(symbol);
+                }
+                "jump" => {
+                    let id = JoinPointId(self.symbol()?);
+                    let arguments = self.symbols()?;
+                    self.expect_punct(";")?;
+                    break Stmt::Jump(id, arguments);
+                }
+                "crash" => {
+                    let symbol = self.symbol()?;
+                    self.expect_punct(";")?;
+                    break Stmt::Crash(symbol);
+                }
+                "runtime_error" => {
+                    let message = self.string()?;
+                    self.expect_punct(";")?;
+                    break Stmt::RuntimeError(message);
+                }
+                "switch" => break self.switch()?,
+                _ => {
+                    self.pos -= 1;
+                    return self.error(format!("`{}` does not start a statement", keyword));
+                }
+            }
+        };
+
+        for prefix in prefixes.into_iter().rev() {
+            let rest = self.arena.alloc(stmt);
+
+            stmt = match prefix {
+                Prefix::Let(symbol, expr, layout) => Stmt::Let(symbol, expr, layout, rest),
+                Prefix::Refcounting(modify) => Stmt::Refcounting(modify, rest),
+                Prefix::Expect {
+                    condition,
+                    region,
+                    lookups,
+                    layouts,
+                } => Stmt::Expect {
+                    condition,
+                    region,
+                    lookups,
+                    layouts,
+                    remainder: rest,
+                },
+                Prefix::Dbg {
+                    symbol,
+                    region,
+                    layout,
+                } => Stmt::Dbg {
+                    symbol,
+                    region,
+                    layout,
+                    remainder: rest,
+                },
+                Prefix::Join {
+                    id,
+                    parameters,
+                    body,
+                } => Stmt::Join {
+                    id,
+                    parameters,
+                    body,
+                    remainder: rest,
+                },
+            };
+        }
+
+        Ok(self.arena.alloc(stmt))
+    }
+
+    fn switch(&mut self) -> ParseResult<Stmt<'a>> {
+        let cond_symbol = self.symbol()?;
+        self.expect_punct(":")?;
+        let cond_layout = self.layout()?;
+        self.expect_punct("->")?;
+        let ret_layout = self.layout()?;
+        self.expect_punct("{")?;
+
+        let mut branches = Vec::new_in(self.arena);
+        let default_branch = loop {
+            if self.eat_word("default") {
+                let info = self.branch_info()?;
+                self.expect_punct("=>")?;
+                let branch = self.block()?;
+                self.expect_punct("}")?;
+                break (info, branch);
+            }
+
+            let value = self.number()?;
+            let info = self.branch_info()?;
+            self.expect_punct("=>")?;
+            let branch = self.block()?;
+            branches.push((value, info, branch.clone()));
+        };
+
+        Ok(Stmt::Switch {
+            cond_symbol,
+            cond_layout,
+            branches: branches.into_bump_slice(),
+            default_branch,
+            ret_layout,
+        })
+    }
+
+    fn branch_info(&mut self) -> ParseResult<BranchInfo<'a>> {
+        if !self.eat_punct("(") {
+            return Ok(BranchInfo::None);
+        }
+
+        let scrutinee = self.symbol()?;
+        self.expect_punct(":")?;
+        let layout = self.layout()?;
+        self.expect_word("tag")?;
+        let tag_id = self.number()?;
+        self.expect_punct(")")?;
+
+        Ok(BranchInfo::Constructor {
+            scrutinee,
+            layout,
+            tag_id,
+        })
+    }
+
+    fn literal(&mut self, keyword: &str) -> ParseResult<Option<Literal<'a>>> {
+        let literal = match keyword {
+            "int" => Literal::Int(self.number::<i128>()?.to_ne_bytes()),
+            "u128" => Literal::U128(self.number::<u128>()?.to_ne_bytes()),
+            "float" => Literal::Float(self.number()?),
+            "dec" => Literal::Decimal(self.number::<i128>()?.to_ne_bytes()),
+            "str" => Literal::Str(self.string()?),
+            "bool" => Literal::Bool(self.number()?),
+            "byte" => Literal::Byte(self.number()?),
+            _ => return Ok(None),
+        };
+
+        Ok(Some(literal))
+    }
+
+    fn update_mode(&mut self) -> ParseResult<UpdateModeId> {
+        self.expect_word("mode")?;
+        Ok(UpdateModeId::from_u32(self.number()?))
+    }
+
+    fn spec(&mut self) -> ParseResult<CallSpecId> {
+        self.expect_word("spec")?;
+        Ok(CallSpecId::from_u32(self.number()?))
+    }
+
+    fn expr(&mut self) -> ParseResult<Expr<'a>> {
+        let keyword = self.word()?;
+
+        if let Some(literal) = self.literal(keyword)? {
+            return Ok(Expr::Literal(literal));
+        }
+
+        let expr = match keyword {
+            "call" => {
+                let name = self.symbol()?;
+                let captures_niche = self.niche()?;
+                let arguments = self.symbols()?;
+                self.expect_punct(":")?;
+                let arg_layouts = self.layouts()?;
+                self.expect_punct("->")?;
+                let ret_layout = self.arena.alloc(self.layout()?);
+                let specialization_id = self.spec()?;
+
+                Expr::Call(Call {
+                    call_type: CallType::ByName {
+                        name: LambdaName::from_parts(name, captures_niche),
+                        ret_layout,
+                        arg_layouts,
+                        specialization_id,
+                    },
+                    arguments,
+                })
+            }
+            "foreign" => {
+                let foreign_symbol = ForeignSymbol::from(self.string()?.to_string());
+                let arguments = self.symbols()?;
+                self.expect_punct("->")?;
+                let ret_layout = self.arena.alloc(self.layout()?);
+
+                Expr::Call(Call {
+                    call_type: CallType::Foreign {
+                        foreign_symbol,
+                        ret_layout,
+                    },
+                    arguments,
+                })
+            }
+            "lowlevel" => {
+                let name = self.word()?;
+                let op = match lowlevel_from_name(name) {
+                    Some(op) => op,
+                    None => {
+                        self.pos -= 1;
+                        return self.error(format!("`{}` is not a lowlevel", name));
+                    }
+                };
+                let arguments = self.symbols()?;
+                let update_mode = self.update_mode()?;
+
+                Expr::Call(Call {
+                    call_type: CallType::LowLevel { op, update_mode },
+                    arguments,
+                })
+            }
+            "higher_order" => {
+                let op = self.higher_order()?;
+                let arguments = self.symbols()?;
+                let update_mode = self.update_mode()?;
+                self.expect_word("passing")?;
+                let name = self.symbol()?;
+                let captures_niche = self.niche()?;
+                self.expect_punct(":")?;
+                let argument_layouts = self.layouts()?;
+                self.expect_punct("->")?;
+                let return_layout = self.layout()?;
+                let specialization_id = self.spec()?;
+                self.expect_word("env")?;
+                let captured_environment = self.symbol()?;
+                let owns_captured_environment = self.eat_word("owned");
+                let closure_env_layout = if self.eat_word("closure_env") {
+                    Some(self.layout()?)
+                } else {
+                    None
+                };
+
+                let higher_order = HigherOrderLowLevel {
+                    op,
+                    closure_env_layout,
+                    update_mode,
+                    passed_function: PassedFunction {
+                        name: LambdaName::from_parts(name, captures_niche),
+                        argument_layouts,
+                        return_layout,
+                        specialization_id,
+                        captured_environment,
+                        owns_captured_environment,
+                    },
+                };
+
+                Expr::Call(Call {
+                    call_type: CallType::HigherOrder(self.arena.alloc(higher_order)),
+                    arguments,
+                })
+            }
+            "tag" => {
+                let tag_layout = self.union_layout()?;
+                let tag_id = self.number()?;
+                let arguments = self.symbols()?;
+
+                Expr::Tag {
+                    tag_layout,
+                    tag_id,
+                    arguments,
+                }
+            }
+            "struct" => Expr::Struct(self.symbols()?),
+            "struct_at" => {
+                let structure = self.symbol()?;
+                let index = self.number()?;
+                self.expect_word("of")?;
+                let field_layouts = self.layouts()?;
+
+                Expr::StructAtIndex {
+                    index,
+                    field_layouts,
+                    structure,
+                }
+            }
+            "get_tag_id" => {
+                let structure = self.symbol()?;
+                self.expect_word("of")?;
+                let union_layout = self.union_layout()?;
+
+                Expr::GetTagId {
+                    structure,
+                    union_layout,
+                }
+            }
+            "union_at" => {
+                let structure = self.symbol()?;
+                self.expect_word("tag")?;
+                let tag_id = self.number()?;
+                self.expect_word("index")?;
+                let index = self.number()?;
+                self.expect_word("of")?;
+                let union_layout = self.union_layout()?;
+
+                Expr::UnionAtIndex {
+                    structure,
+                    tag_id,
+                    union_layout,
+                    index,
+                }
+            }
+            "set_union_at" => {
+                let structure = self.symbol()?;
+                self.expect_word("tag")?;
+                let tag_id = self.number()?;
+                self.expect_word("index")?;
+                let index = self.number()?;
+                self.expect_word("of")?;
+                let union_layout = self.union_layout()?;
+                self.expect_word("value")?;
+                let value = self.symbol()?;
+
+                Expr::SetUnionAtIndex {
+                    structure,
+                    tag_id,
+                    union_layout,
+                    index,
+                    value,
+                }
+            }
+            "array" => {
+                let elem_layout = self.layout()?;
+                let elems = self.sequence("[", "]", |p| {
+                    let keyword = p.word()?;
+                    match p.literal(keyword)? {
+                        Some(literal) => Ok(ListLiteralElement::Literal(literal)),
+                        None => {
+                            p.pos -= 1;
+                            Ok(ListLiteralElement::Symbol(p.symbol()?))
+                        }
+                    }
+                })?;
+
+                Expr::Array {
+                    elem_layout,
+                    elems: elems.into_bump_slice(),
+                }
+            }
+            "empty_array" => Expr::EmptyArray,
+            "box" => Expr::ExprBox {
+                symbol: self.symbol()?,
+            },
+            "unbox" => Expr::ExprUnbox {
+                symbol: self.symbol()?,
+            },
+            "reuse" => {
+                let symbol = self.symbol()?;
+                let update_mode = self.update_mode()?;
+                let update_tag_id = self.eat_word("update_tag_id");
+                self.expect_word("tag")?;
+                let tag_layout = self.union_layout()?;
+                let tag_id = self.number()?;
+                let arguments = self.symbols()?;
+
+                Expr::Reuse {
+                    symbol,
+                    update_tag_id,
+                    update_mode,
+                    tag_layout,
+                    tag_id,
+                    arguments,
+                }
+            }
+            "reset" => {
+                let symbol = self.symbol()?;
+                let update_mode = self.update_mode()?;
+
+                Expr::Reset {
+                    symbol,
+                    update_mode,
+                }
+            }
+            "runtime_error_function" => Expr::RuntimeErrorFunction(self.string()?),
+            _ => {
+                self.pos -= 1;
+                return self.error(format!("`{}` does not start an expression", keyword));
+            }
+        };
+
+        Ok(expr)
+    }
+
+    fn higher_order(&mut self) -> ParseResult<HigherOrder> {
+        use HigherOrder::*;
+
+        let name = self.word()?;
+        let symbols = self.sequence("[", "]", Self::symbol)?;
+
+        let op = match (name, symbols.as_slice()) {
+            ("ListMap", [xs]) => ListMap { xs: *xs },
+            ("ListMap2", [xs, ys]) => ListMap2 { xs: *xs, ys: *ys },
+            ("ListMap3", [xs, ys, zs]) => ListMap3 {
+                xs: *xs,
+                ys: *ys,
+                zs: *zs,
+            },
+            ("ListMap4", [xs, ys, zs, ws]) => ListMap4 {
+                xs: *xs,
+                ys: *ys,
+                zs: *zs,
+                ws: *ws,
+            },
+            ("ListSortWith", [xs]) => ListSortWith { xs: *xs },
+            _ => {
+                return self.error(format!(
+                    "`{}` with {} symbols is not a higher order lowlevel",
+                    name,
+                    symbols.len()
+                ))
+            }
+        };
+
+        Ok(op)
+    }
+
+    fn layout(&mut self) -> ParseResult<Layout<'a>> {
+        if matches!(self.peek(), Some(Token::Punct("{"))) {
+            let field_layouts = self.sequence("{", "}", Self::layout)?;
+            return Ok(Layout::struct_no_name_order(
+                field_layouts.into_bump_slice(),
+            ));
+        }
+
+        let word = self.word()?;
+        let layout = match word {
+            "*" => Layout::RecursivePointer,
+            "Bool" => Layout::Builtin(Builtin::Bool),
+            "Dec" => Layout::Builtin(Builtin::Decimal),
+            "Str" => Layout::Builtin(Builtin::Str),
+            "List" => Layout::Builtin(Builtin::List(self.arena.alloc(self.layout()?))),
+            "Box" => Layout::Boxed(self.arena.alloc(self.layout()?)),
+            "LambdaSet" => {
+                let set = self.sequence("[", "]", |p| Ok((p.symbol()?, p.layouts()?)))?;
+                self.expect_word("as")?;
+                let representation = self.arena.alloc(self.layout()?);
+
+                Layout::LambdaSet(LambdaSet::from_parts(set.into_bump_slice(), representation))
+            }
+            "NonRecursive"
+            | "Recursive"
+            | "NonNullableUnwrapped"
+            | "NullableWrapped"
+            | "NullableUnwrapped" => {
+                self.pos -= 1;
+                Layout::Union(self.union_layout()?)
+            }
+            _ => match (int_width_from_name(word), float_width_from_name(word)) {
+                (Some(width), _) => Layout::Builtin(Builtin::Int(width)),
+                (_, Some(width)) => Layout::Builtin(Builtin::Float(width)),
+                (None, None) => {
+                    self.pos -= 1;
+                    return self.error(format!("`{}` does not start a layout", word));
+                }
+            },
+        };
+
+        Ok(layout)
+    }
+
+    fn tags(&mut self) -> ParseResult<&'a [&'a [Layout<'a>]]> {
+        Ok(self.sequence("[", "]", Self::layouts)?.into_bump_slice())
+    }
+
+    fn union_layout(&mut self) -> ParseResult<UnionLayout<'a>> {
+        let word = self.word()?;
+
+        let union_layout = match word {
+            "NonRecursive" => UnionLayout::NonRecursive(self.tags()?),
+            "Recursive" => UnionLayout::Recursive(self.tags()?),
+            "NonNullableUnwrapped" => UnionLayout::NonNullableUnwrapped(self.layouts()?),
+            "NullableWrapped" => UnionLayout::NullableWrapped {
+                nullable_id: self.number()?,
+                other_tags: self.tags()?,
+            },
+            "NullableUnwrapped" => UnionLayout::NullableUnwrapped {
+                nullable_id: self.number()?,
+                other_fields: self.layouts()?,
+            },
+            _ => {
+                self.pos -= 1;
+                return self.error(format!("`{}` does not start a union layout", word));
+            }
+        };
+
+        Ok(union_layout)
+    }
+}
+
+fn int_width_from_name(name: &str) -> Option<IntWidth> {
+    use IntWidth::*;
+
+    let width = match name {
+        "U8" => U8,
+        "U16" => U16,
+        "U32" => U32,
+        "U64" => U64,
+        "U128" => U128,
+        "I8" => I8,
+        "I16" => I16,
+        "I32" => I32,
+        "I64" => I64,
+        "I128" => I128,
+        _ => return None,
+    };
+
+    Some(width)
+}
+
+fn float_width_from_name(name: &str) -> Option<FloatWidth> {
+    match name {
+        "F32" => Some(FloatWidth::F32),
+        "F64" => Some(FloatWidth::F64),
+        "F128" => Some(FloatWidth::F128),
+        _ => None,
+    }
+}
+
+/// We use a macro to make sure that every `LowLevel` can be parsed
+macro_rules! lowlevels {
+    ($($lowlevel:ident),* $(,)?) => {
+        fn lowlevel_from_name(name: &str) -> Option<LowLevel> {
+            match name {
+                $(
+                    stringify!($lowlevel) => Some(LowLevel::$lowlevel),
+                )*
+                _ => None,
+            }
+        }
+
+        fn _enforce_exhaustiveness(lowlevel: LowLevel) {
+            // when adding a new lowlevel, this match will stop being exhaustive;
+            // add the lowlevel to the invocation below
+            match lowlevel {
+                $(
+                    LowLevel::$lowlevel => {}
+                )*
+            }
+        }
+    };
+}
+
+lowlevels! {
+    StrConcat,
+    StrJoinWith,
+    StrIsEmpty,
+    StrStartsWith,
+    StrStartsWithScalar,
+    StrEndsWith,
+    StrSplit,
+    StrCountGraphemes,
+    StrCountUtf8Bytes,
+    StrFromInt,
+    StrFromUtf8Range,
+    StrToUtf8,
+    StrRepeat,
+    StrFromFloat,
+    StrTrim,
+    StrTrimLeft,
+    StrTrimRight,
+    StrToNum,
+    StrToScalars,
+    StrGetUnsafe,
+    StrSubstringUnsafe,
+    StrReserve,
+    StrAppendScalar,
+    StrGetScalarUnsafe,
+    StrGetCapacity,
+    ListLen,
+    ListWithCapacity,
+    ListReserve,
+    ListAppendUnsafe,
+    ListGetUnsafe,
+    ListReplaceUnsafe,
+    ListConcat,
+    ListPrepend,
+    ListMap,
+    ListMap2,
+    ListMap3,
+    ListMap4,
+    ListSortWith,
+    ListSublist,
+    ListDropAt,
+    ListSwap,
+    ListIsUnique,
+    ListGetCapacity,
+    DictHashBytes,
+    DictHashMix,
+    NumAdd,
+    NumAddWrap,
+    NumAddChecked,
+    NumAddSaturated,
+    NumSub,
+    NumSubWrap,
+    NumSubChecked,
+    NumSubSaturated,
+    NumMul,
+    NumMulWrap,
+    NumMulSaturated,
+    NumMulChecked,
+    NumGt,
+    NumGte,
+    NumLt,
+    NumLte,
+    NumCompare,
+    NumDivUnchecked,
+    NumDivCeilUnchecked,
+    NumRemUnchecked,
+    NumIsMultipleOf,
+    NumAbs,
+    NumNeg,
+    NumSin,
+    NumCos,
+    NumSqrtUnchecked,
+    NumLogUnchecked,
+    NumRound,
+    NumToFrac,
+    NumPow,
+    NumCeiling,
+    NumPowInt,
+    NumFloor,
+    NumIsFinite,
+    NumAtan,
+    NumAcos,
+    NumAsin,
+    NumBytesToU16,
+    NumBytesToU32,
+    NumBitwiseAnd,
+    NumBitwiseXor,
+    NumBitwiseOr,
+    NumShiftLeftBy,
+    NumShiftRightBy,
+    NumShiftRightZfBy,
+    NumIntCast,
+    NumToFloatCast,
+    NumToIntChecked,
+    NumToFloatChecked,
+    NumToStr,
+    Eq,
+    NotEq,
+    And,
+    Or,
+    Not,
+    Hash,
+    PtrCast,
+    RefCountInc,
+    RefCountDec,
+    BoxExpr,
+    UnboxExpr,
+    Unreachable,
+}
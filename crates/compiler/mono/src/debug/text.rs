@@ -0,0 +1,750 @@
+//! A textual syntax for the mono IR, which `parse_procs` reads back into arena structures.
+//! Unlike `Proc::to_pretty`, it keeps everything a backend needs, so a backend bug can be
+//! reproduced from a hand-written (or hand-reduced) IR fragment.
+//!
+//! ```text
+//! procedure Num.19 (#Attr.2 : I64, #Attr.3 : I64) -> I64 {
+//!     let Num.257 : I64 = lowlevel NumAdd (#Attr.2, #Attr.3) mode 0;
+//!     ret Num.257;
+//! }
+//! ```
+//!
+//! A symbol is written as its module name and ident id, e.g. `List.90` or `#Attr.2`.
+//! Whitespace is insignificant.
+//!
+//! ## Procs
+//!
+//! ```text
+//! procedure <symbol> [niche (<layout>, ...)] (<symbol> : <layout>, ...) -> <layout>
+//!     [closure <layout>] [self_recursive <symbol>] [must_own] [@<start>-<end>]
+//!     [regions (<symbol> @<start>-<end>, ...)] { <stmt> }
+//! ```
+//!
+//! Host exposed layouts are not part of the syntax; parsed procs are never host exposed.
+//!
+//! ## Statements
+//!
+//! ```text
+//! let <symbol> : <layout> = <expr>; <stmt>
+//! ret <symbol>;
+//! inc <symbol> <n>; <stmt>
+//! dec <symbol>; <stmt>
+//! decref <symbol>; <stmt>
+//! switch <symbol> : <layout> -> <layout> {
+//!     <n> [(<symbol> : <layout> tag <n>)] => { <stmt> }
+//!     ...
+//!     default [(<symbol> : <layout> tag <n>)] => { <stmt> }
+//! }
+//! join <symbol> ([borrowed] <symbol> : <layout>, ...) { <stmt> } <stmt>
+//! jump <symbol> (<symbol>, ...);
+//! expect <symbol> @<start>-<end> (<symbol> : <layout>, ...); <stmt>
+//! dbg <symbol> : <layout> @<start>-<end>; <stmt>
+//! crash <symbol>;
+//! runtime_error "<message>";
+//! ```
+//!
+//! ## Expressions
+//!
+//! ```text
+//! int <i128> | u128 <u128> | float <f64> | dec <raw i128> | str "<string>" | bool <true|false> | byte <u8>
+//! call <symbol> [niche (<layout>, ...)] (<symbol>, ...) : (<layout>, ...) -> <layout> spec <n>
+//! foreign "<name>" (<symbol>, ...) -> <layout>
+//! lowlevel <LowLevel> (<symbol>, ...) mode <n>
+//! higher_order <HigherOrder> [<symbol>, ...] (<symbol>, ...) mode <n>
+//!     passing <symbol> [niche (<layout>, ...)] : (<layout>, ...) -> <layout> spec <n>
+//!     env <symbol> [owned] [closure_env <layout>]
+//! tag <union layout> <n> (<symbol>, ...)
+//! struct (<symbol>, ...)
+//! struct_at <symbol> <n> of (<layout>, ...)
+//! get_tag_id <symbol> of <union layout>
+//! union_at <symbol> tag <n> index <n> of <union layout>
+//! set_union_at <symbol> tag <n> index <n> of <union layout> value <symbol>
+//! array <layout> [<symbol or literal>, ...]
+//! empty_array
+//! box <symbol>
+//! unbox <symbol>
+//! reuse <symbol> mode <n> [update_tag_id] tag <union layout> <n> (<symbol>, ...)
+//! reset <symbol> mode <n>
+//! runtime_error_function "<message>"
+//! ```
+//!
+//! where `<HigherOrder>` is followed by the symbols of its fields, e.g. `ListMap2 [Test.4, Test.5]`.
+//!
+//! ## Layouts
+//!
+//! ```text
+//! I8 | I16 | I32 | I64 | I128 | U8 | U16 | U32 | U64 | U128 | F32 | F64 | F128 | Bool | Dec | Str
+//! List <layout> | Box <layout> | {<layout>, ...} | *
+//! NonRecursive[(<layout>, ...), ...]
+//! Recursive[(<layout>, ...), ...]
+//! NonNullableUnwrapped(<layout>, ...)
+//! NullableWrapped <nullable id> [(<layout>, ...), ...]
+//! NullableUnwrapped <true|false> (<layout>, ...)
+//! LambdaSet[<symbol> (<layout>, ...), ...] as <layout>
+//! ```
+//!
+//! `*` is the recursive pointer. The field order hash of a struct is not part of the syntax,
+//! so a struct parses to `Layout::struct_no_name_order`.
+
+use crate::ir::{
+    BranchInfo, CallType, Expr, ListLiteralElement, Literal, ModifyRc, Param, Proc, SelfRecursive,
+    Stmt,
+};
+use crate::layout::{Builtin, CapturesNiche, Layout, UnionLayout};
+use crate::low_level::HigherOrder;
+use roc_module::symbol::{Interns, Symbol};
+use roc_region::all::Region;
+use std::fmt::Write;
+
+const INDENT: &str = "    ";
+
+/// Write the procs in the textual syntax, in the order they are given
+pub fn procs_to_text<'a, 'p, I>(procs: I, interns: &Interns) -> String
+where
+    'a: 'p,
+    I: IntoIterator<Item = &'p Proc<'a>>,
+{
+    let mut printer = Printer {
+        interns,
+        buf: String::new(),
+        indent: 0,
+    };
+
+    for (i, proc) in procs.into_iter().enumerate() {
+        if i > 0 {
+            printer.buf.push('\n');
+        }
+        printer.proc(proc);
+    }
+
+    printer.buf
+}
+
+struct Printer<'i> {
+    interns: &'i Interns,
+    buf: String,
+    indent: usize,
+}
+
+impl<'i> Printer<'i> {
+    fn str(&mut self, s: &str) {
+        self.buf.push_str(s);
+    }
+
+    fn newline(&mut self) {
+        self.buf.push('\n');
+        for _ in 0..self.indent {
+            self.buf.push_str(INDENT);
+        }
+    }
+
+    fn symbol(&mut self, symbol: Symbol) {
+        let module_name = self.interns.module_name(symbol.module_id());
+        write!(self.buf, "{}.{}", module_name, symbol.ident_id().index()).unwrap();
+    }
+
+    fn number<T: std::fmt::Display>(&mut self, n: T) {
+        write!(self.buf, "{}", n).unwrap();
+    }
+
+    fn region(&mut self, region: Region) {
+        write!(
+            self.buf,
+            "@{}-{}",
+            region.start().offset,
+            region.end().offset
+        )
+        .unwrap();
+    }
+
+    fn string(&mut self, s: &str) {
+        write!(self.buf, "{:?}", s).unwrap();
+    }
+
+    fn list<T>(&mut self, items: &[T], mut item: impl FnMut(&mut Self, &T)) {
+        self.str("(");
+        for (i, x) in items.iter().enumerate() {
+            if i > 0 {
+                self.str(", ");
+            }
+            item(self, x);
+        }
+        self.str(")");
+    }
+
+    fn symbols(&mut self, symbols: &[Symbol]) {
+        self.list(symbols, |p, s| p.symbol(*s));
+    }
+
+    fn layouts(&mut self, layouts: &[Layout<'_>]) {
+        self.list(layouts, |p, l| p.layout(l));
+    }
+
+    fn niche(&mut self, niche: CapturesNiche<'_>) {
+        if !niche.layouts().is_empty() {
+            self.str(" niche ");
+            self.layouts(niche.layouts());
+        }
+    }
+
+    fn proc(&mut self, proc: &Proc<'_>) {
+        self.str("procedure ");
+        self.symbol(proc.name.name());
+        self.niche(proc.name.captures_niche());
+        self.str(" ");
+        self.list(proc.args, |p, (layout, symbol)| {
+            p.symbol(*symbol);
+            p.str(" : ");
+            p.layout(layout);
+        });
+        self.str(" -> ");
+        self.layout(&proc.ret_layout);
+
+        if let Some(layout) = &proc.closure_data_layout {
+            self.str(" closure ");
+            self.layout(layout);
+        }
+        if let SelfRecursive::SelfRecursive(id) = proc.is_self_recursive {
+            self.str(" self_recursive ");
+            self.symbol(id.0);
+        }
+        if proc.must_own_arguments {
+            self.str(" must_own");
+        }
+        if proc.region != Region::zero() {
+            self.str(" ");
+            self.region(proc.region);
+        }
+        if !proc.symbol_regions.is_empty() {
+            self.str(" regions ");
+            self.list(proc.symbol_regions, |p, (symbol, region)| {
+                p.symbol(*symbol);
+                p.str(" ");
+                p.region(*region);
+            });
+        }
+
+        self.block(&proc.body);
+        self.buf.push('\n');
+    }
+
+    fn block(&mut self, stmt: &Stmt<'_>) {
+        self.str(" {");
+        self.indent += 1;
+        self.stmt(stmt);
+        self.indent -= 1;
+        self.newline();
+        self.str("}");
+    }
+
+    fn stmt(&mut self, mut stmt: &Stmt<'_>) {
+        loop {
+            self.newline();
+
+            match stmt {
+                Stmt::Let(symbol, expr, layout, cont) => {
+                    self.str("let ");
+                    self.symbol(*symbol);
+                    self.str(" : ");
+                    self.layout(layout);
+                    self.str(" = ");
+                    self.expr(expr);
+                    self.str(";");
+                    stmt = cont;
+                }
+                Stmt::Refcounting(modify, cont) => {
+                    match modify {
+                        ModifyRc::Inc(symbol, n) => {
+                            self.str("inc ");
+                            self.symbol(*symbol);
+                            self.str(" ");
+                            self.number(n);
+                        }
+                        ModifyRc::Dec(symbol) => {
+                            self.str("dec ");
+                            self.symbol(*symbol);
+                        }
+                        ModifyRc::DecRef(symbol) => {
+                            self.str("decref ");
+                            self.symbol(*symbol);
+                        }
+                    }
+                    self.str(";");
+                    stmt = cont;
+                }
+                Stmt::Expect {
+                    condition,
+                    region,
+                    lookups,
+                    layouts,
+                    remainder,
+                } => {
+                    self.str("expect ");
+                    self.symbol(*condition);
+                    self.str(" ");
+                    self.region(*region);
+                    self.str(" ");
+                    let lookups: Vec<_> = lookups.iter().zip(layouts.iter()).collect();
+                    self.list(&lookups, |p, (symbol, layout)| {
+                        p.symbol(**symbol);
+                        p.str(" : ");
+                        p.layout(layout);
+                    });
+                    self.str(";");
+                    stmt = remainder;
+                }
+                Stmt::Dbg {
+                    symbol,
+                    region,
+                    layout,
+                    remainder,
+                } => {
+                    self.str("dbg ");
+                    self.symbol(*symbol);
+                    self.str(" : ");
+                    self.layout(layout);
+                    self.str(" ");
+                    self.region(*region);
+                    self.str(";");
+                    stmt = remainder;
+                }
+                Stmt::Join {
+                    id,
+                    parameters,
+                    body,
+                    remainder,
+                } => {
+                    self.str("join ");
+                    self.symbol(id.0);
+                    self.str(" ");
+                    self.list(parameters, |p, param: &Param| {
+                        if param.borrow {
+                            p.str("borrowed ");
+                        }
+                        p.symbol(param.symbol);
+                        p.str(" : ");
+                        p.layout(&param.layout);
+                    });
+                    self.block(body);
+                    stmt = remainder;
+                }
+                Stmt::Switch {
+                    cond_symbol,
+                    cond_layout,
+                    branches,
+                    default_branch,
+                    ret_layout,
+                } => {
+                    self.str("switch ");
+                    self.symbol(*cond_symbol);
+                    self.str(" : ");
+                    self.layout(cond_layout);
+                    self.str(" -> ");
+                    self.layout(ret_layout);
+                    self.str(" {");
+                    self.indent += 1;
+                    for (value, info, branch) in branches.iter() {
+                        self.newline();
+                        self.number(value);
+                        self.branch(info, branch);
+                    }
+                    self.newline();
+                    self.str("default");
+                    self.branch(&default_branch.0, default_branch.1);
+                    self.indent -= 1;
+                    self.newline();
+                    self.str("}");
+                    return;
+                }
+                Stmt::Ret(symbol) => {
+                    self.str("ret ");
+                    self.symbol(*symbol);
+                    self.str(";");
+                    return;
+                }
+                Stmt::Jump(id, arguments) => {
+                    self.str("jump ");
+                    self.symbol(id.0);
+                    self.str(" ");
+                    self.symbols(arguments);
+                    self.str(";");
+                    return;
+                }
+                Stmt::Crash(symbol) => {
+                    self.str("crash ");
+                    self.symbol(*symbol);
+                    self.str(";");
+                    return;
+                }
+                Stmt::RuntimeError(message) => {
+                    self.str("runtime_error ");
+                    self.string(message);
+                    self.str(";");
+                    return;
+                }
+            }
+        }
+    }
+
+    fn branch(&mut self, info: &BranchInfo<'_>, branch: &Stmt<'_>) {
+        if let BranchInfo::Constructor {
+            scrutinee,
+            layout,
+            tag_id,
+        } = info
+        {
+            self.str(" (");
+            self.symbol(*scrutinee);
+            self.str(" : ");
+            self.layout(layout);
+            self.str(" tag ");
+            self.number(tag_id);
+            self.str(")");
+        }
+        self.str(" =>");
+        self.block(branch);
+    }
+
+    fn literal(&mut self, literal: &Literal<'_>) {
+        match literal {
+            Literal::Int(bytes) => {
+                self.str("int ");
+                self.number(i128::from_ne_bytes(*bytes));
+            }
+            Literal::U128(bytes) => {
+                self.str("u128 ");
+                self.number(u128::from_ne_bytes(*bytes));
+            }
+            Literal::Float(float) => write!(self.buf, "float {:?}", float).unwrap(),
+            Literal::Decimal(bytes) => {
+                self.str("dec ");
+                self.number(i128::from_ne_bytes(*bytes));
+            }
+            Literal::Str(s) => {
+                self.str("str ");
+                self.string(s);
+            }
+            Literal::Bool(b) => {
+                self.str("bool ");
+                self.number(b);
+            }
+            Literal::Byte(b) => {
+                self.str("byte ");
+                self.number(b);
+            }
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr<'_>) {
+        match expr {
+            Expr::Literal(literal) => self.literal(literal),
+            Expr::Call(call) => match &call.call_type {
+                CallType::ByName {
+                    name,
+                    ret_layout,
+                    arg_layouts,
+                    specialization_id,
+                } => {
+                    self.str("call ");
+                    self.symbol(name.name());
+                    self.niche(name.captures_niche());
+                    self.str(" ");
+                    self.symbols(call.arguments);
+                    self.str(" : ");
+                    self.layouts(arg_layouts);
+                    self.str(" -> ");
+                    self.layout(ret_layout);
+                    self.str(" spec ");
+                    self.number(specialization_id.to_u32());
+                }
+                CallType::Foreign {
+                    foreign_symbol,
+                    ret_layout,
+                } => {
+                    self.str("foreign ");
+                    self.string(foreign_symbol.as_str());
+                    self.str(" ");
+                    self.symbols(call.arguments);
+                    self.str(" -> ");
+                    self.layout(ret_layout);
+                }
+                CallType::LowLevel { op, update_mode } => {
+                    write!(self.buf, "lowlevel {:?} ", op).unwrap();
+                    self.symbols(call.arguments);
+                    self.str(" mode ");
+                    self.number(update_mode.to_u32());
+                }
+                CallType::HigherOrder(higher_order) => {
+                    let passed_function = &higher_order.passed_function;
+
+                    self.str("higher_order ");
+                    self.higher_order(&higher_order.op);
+                    self.str(" ");
+                    self.symbols(call.arguments);
+                    self.str(" mode ");
+                    self.number(higher_order.update_mode.to_u32());
+                    self.str(" passing ");
+                    self.symbol(passed_function.name.name());
+                    self.niche(passed_function.name.captures_niche());
+                    self.str(" : ");
+                    self.layouts(passed_function.argument_layouts);
+                    self.str(" -> ");
+                    self.layout(&passed_function.return_layout);
+                    self.str(" spec ");
+                    self.number(passed_function.specialization_id.to_u32());
+                    self.str(" env ");
+                    self.symbol(passed_function.captured_environment);
+                    if passed_function.owns_captured_environment {
+                        self.str(" owned");
+                    }
+                    if let Some(layout) = &higher_order.closure_env_layout {
+                        self.str(" closure_env ");
+                        self.layout(layout);
+                    }
+                }
+            },
+            Expr::Tag {
+                tag_layout,
+                tag_id,
+                arguments,
+            } => {
+                self.str("tag ");
+                self.union_layout(tag_layout);
+                self.str(" ");
+                self.number(tag_id);
+                self.str(" ");
+                self.symbols(arguments);
+            }
+            Expr::Struct(fields) => {
+                self.str("struct ");
+                self.symbols(fields);
+            }
+            Expr::StructAtIndex {
+                index,
+                field_layouts,
+                structure,
+            } => {
+                self.str("struct_at ");
+                self.symbol(*structure);
+                self.str(" ");
+                self.number(index);
+                self.str(" of ");
+                self.layouts(field_layouts);
+            }
+            Expr::GetTagId {
+                structure,
+                union_layout,
+            } => {
+                self.str("get_tag_id ");
+                self.symbol(*structure);
+                self.str(" of ");
+                self.union_layout(union_layout);
+            }
+            Expr::UnionAtIndex {
+                structure,
+                tag_id,
+                union_layout,
+                index,
+            } => {
+                self.str("union_at ");
+                self.symbol(*structure);
+                self.str(" tag ");
+                self.number(tag_id);
+                self.str(" index ");
+                self.number(index);
+                self.str(" of ");
+                self.union_layout(union_layout);
+            }
+            Expr::SetUnionAtIndex {
+                structure,
+                tag_id,
+                union_layout,
+                index,
+                value,
+            } => {
+                self.str("set_union_at ");
+                self.symbol(*structure);
+                self.str(" tag ");
+                self.number(tag_id);
+                self.str(" index ");
+                self.number(index);
+                self.str(" of ");
+                self.union_layout(union_layout);
+                self.str(" value ");
+                self.symbol(*value);
+            }
+            Expr::Array { elem_layout, elems } => {
+                self.str("array ");
+                self.layout(elem_layout);
+                self.str(" [");
+                for (i, elem) in elems.iter().enumerate() {
+                    if i > 0 {
+                        self.str(", ");
+                    }
+                    match elem {
+                        ListLiteralElement::Literal(literal) => self.literal(literal),
+                        ListLiteralElement::Symbol(symbol) => self.symbol(*symbol),
+                    }
+                }
+                self.str("]");
+            }
+            Expr::EmptyArray => self.str("empty_array"),
+            Expr::ExprBox { symbol } => {
+                self.str("box ");
+                self.symbol(*symbol);
+            }
+            Expr::ExprUnbox { symbol } => {
+                self.str("unbox ");
+                self.symbol(*symbol);
+            }
+            Expr::Reuse {
+                symbol,
+                update_tag_id,
+                update_mode,
+                tag_layout,
+                tag_id,
+                arguments,
+            } => {
+                self.str("reuse ");
+                self.symbol(*symbol);
+                self.str(" mode ");
+                self.number(update_mode.to_u32());
+                if *update_tag_id {
+                    self.str(" update_tag_id");
+                }
+                self.str(" tag ");
+                self.union_layout(tag_layout);
+                self.str(" ");
+                self.number(tag_id);
+                self.str(" ");
+                self.symbols(arguments);
+            }
+            Expr::Reset {
+                symbol,
+                update_mode,
+            } => {
+                self.str("reset ");
+                self.symbol(*symbol);
+                self.str(" mode ");
+                self.number(update_mode.to_u32());
+            }
+            Expr::RuntimeErrorFunction(message) => {
+                self.str("runtime_error_function ");
+                self.string(message);
+            }
+        }
+    }
+
+    fn higher_order(&mut self, op: &HigherOrder) {
+        use HigherOrder::*;
+
+        let (name, symbols) = match op {
+            ListMap { xs } => ("ListMap", vec![*xs]),
+            ListMap2 { xs, ys } => ("ListMap2", vec![*xs, *ys]),
+            ListMap3 { xs, ys, zs } => ("ListMap3", vec![*xs, *ys, *zs]),
+            ListMap4 { xs, ys, zs, ws } => ("ListMap4", vec![*xs, *ys, *zs, *ws]),
+            ListSortWith { xs } => ("ListSortWith", vec![*xs]),
+        };
+
+        self.str(name);
+        self.str(" [");
+        for (i, symbol) in symbols.iter().enumerate() {
+            if i > 0 {
+                self.str(", ");
+            }
+            self.symbol(*symbol);
+        }
+        self.str("]");
+    }
+
+    fn layout(&mut self, layout: &Layout<'_>) {
+        match layout {
+            Layout::Builtin(builtin) => match builtin {
+                Builtin::Int(width) => write!(self.buf, "{:?}", width).unwrap(),
+                Builtin::Float(width) => write!(self.buf, "{:?}", width).unwrap(),
+                Builtin::Bool => self.str("Bool"),
+                Builtin::Decimal => self.str("Dec"),
+                Builtin::Str => self.str("Str"),
+                Builtin::List(elem_layout) => {
+                    self.str("List ");
+                    self.layout(elem_layout);
+                }
+            },
+            Layout::Struct { field_layouts, .. } => {
+                self.str("{");
+                for (i, field_layout) in field_layouts.iter().enumerate() {
+                    if i > 0 {
+                        self.str(", ");
+                    }
+                    self.layout(field_layout);
+                }
+                self.str("}");
+            }
+            Layout::Boxed(inner) => {
+                self.str("Box ");
+                self.layout(inner);
+            }
+            Layout::Union(union_layout) => self.union_layout(union_layout),
+            Layout::LambdaSet(lambda_set) => {
+                self.str("LambdaSet[");
+                for (i, lambda_name) in lambda_set.iter_set().enumerate() {
+                    if i > 0 {
+                        self.str(", ");
+                    }
+                    self.symbol(lambda_name.name());
+                    self.str(" ");
+                    self.layouts(lambda_name.captures_niche().layouts());
+                }
+                self.str("] as ");
+                self.layout(&lambda_set.runtime_representation());
+            }
+            Layout::RecursivePointer => self.str("*"),
+        }
+    }
+
+    fn tags(&mut self, tags: &[&[Layout<'_>]]) {
+        self.str("[");
+        for (i, tag) in tags.iter().enumerate() {
+            if i > 0 {
+                self.str(", ");
+            }
+            self.layouts(tag);
+        }
+        self.str("]");
+    }
+
+    fn union_layout(&mut self, union_layout: &UnionLayout<'_>) {
+        match union_layout {
+            UnionLayout::NonRecursive(tags) => {
+                self.str("NonRecursive");
+                self.tags(tags);
+            }
+            UnionLayout::Recursive(tags) => {
+                self.str("Recursive");
+                self.tags(tags);
+            }
+            UnionLayout::NonNullableUnwrapped(fields) => {
+                self.str("NonNullableUnwrapped");
+                self.layouts(fields);
+            }
+            UnionLayout::NullableWrapped {
+                nullable_id,
+                other_tags,
+            } => {
+                self.str("NullableWrapped ");
+                self.number(nullable_id);
+                self.str(" ");
+                self.tags(other_tags);
+            }
+            UnionLayout::NullableUnwrapped {
+                nullable_id,
+                other_fields,
+            } => {
+                self.str("NullableUnwrapped ");
+                self.number(nullable_id);
+                self.str(" ");
+                self.layouts(other_fields);
+            }
+        }
+    }
+}
@@ -1,10 +1,12 @@
 use crate::ir::{
-    BranchInfo, DestructType, Env, Expr, JoinPointId, Literal, Param, Pattern, Procs, Stmt,
+    build_list_index_probe, BranchInfo, Call, CallType, DestructType, Env, Expr, JoinPointId,
+    ListIndex, Literal, Param, Pattern, Procs, Stmt,
 };
 use crate::layout::{Builtin, Layout, LayoutCache, TagIdIntType, UnionLayout};
 use roc_builtins::bitcode::{FloatWidth, IntWidth};
 use roc_collections::all::{MutMap, MutSet};
-use roc_exhaustive::{Ctor, CtorName, RenderAs, TagId, Union};
+use roc_error_macros::internal_error;
+use roc_exhaustive::{Ctor, CtorName, ListArity, RenderAs, TagId, Union};
 use roc_module::ident::TagName;
 use roc_module::low_level::LowLevel;
 use roc_module::symbol::Symbol;
@@ -13,6 +15,7 @@ use roc_module::symbol::Symbol;
 
 type Label = u64;
 const RECORD_TAG_NAME: &str = "#Record";
+const TUPLE_TAG_NAME: &str = "#Tuple";
 
 /// Users of this module will mainly interact with this function. It takes
 /// some normal branches and gives out a decision tree that has "labels" at all
@@ -94,6 +97,16 @@ enum Test<'a> {
         tag_id: TagIdIntType,
         num_alts: usize,
     },
+    IsListLen {
+        bound: ListLenBound,
+        len: u64,
+    },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum ListLenBound {
+    Exact,
+    AtLeast,
 }
 
 impl<'a> Test<'a> {
@@ -109,6 +122,7 @@ impl<'a> Test<'a> {
             Test::IsStr(_) => false,
             Test::IsBit(_) => true,
             Test::IsByte { .. } => true,
+            Test::IsListLen { .. } => false,
         }
     }
 }
@@ -152,6 +166,10 @@ impl<'a> Hash for Test<'a> {
                 state.write_u8(6);
                 v.hash(state);
             }
+            IsListLen { len, bound } => {
+                state.write_u8(7);
+                (len, bound).hash(state);
+            }
         }
     }
 }
@@ -330,6 +348,7 @@ fn tests_are_complete_help(last_test: &Test, number_of_tests: usize) -> bool {
         Test::IsFloat(_, _) => false,
         Test::IsDecimal(_) => false,
         Test::IsStr(_) => false,
+        Test::IsListLen { .. } => false,
     }
 }
 
@@ -466,6 +485,8 @@ fn tests_at_path<'a>(
         all_tests.extend(test_at_path(selected_path, branch));
     }
 
+    let all_tests = split_list_len_tests(all_tests);
+
     // The rust HashMap also uses equality, here we really want to use the custom hash function
     // defined on Test to determine whether a test is unique. So we have to do the hashing
     // explicitly
@@ -491,6 +512,62 @@ fn tests_at_path<'a>(
     unique
 }
 
+/// List length tests may overlap; for example both `[_, ..]` and `[_, _]` match lists of length
+/// 2. Split the tests up so that any list satisfies exactly one of them, in the same way that
+/// constructor tests are disjoint.
+fn split_list_len_tests(tests: Vec<GuardedTest>) -> Vec<GuardedTest> {
+    // All lists at least this long are matched in the same way by every list pattern here.
+    let threshold = tests
+        .iter()
+        .filter_map(|test| match test {
+            GuardedTest::TestNotGuarded {
+                test: Test::IsListLen { bound, len },
+            } => match bound {
+                ListLenBound::Exact => Some(len + 1),
+                ListLenBound::AtLeast => Some(*len),
+            },
+            _ => None,
+        })
+        .max();
+
+    let threshold = match threshold {
+        Some(threshold) => threshold,
+        None => return tests,
+    };
+
+    let mut result = Vec::with_capacity(tests.len());
+
+    for test in tests {
+        match test {
+            GuardedTest::TestNotGuarded {
+                test:
+                    Test::IsListLen {
+                        bound: ListLenBound::AtLeast,
+                        len,
+                    },
+            } => {
+                let exact_tests = (len..threshold).map(|len| GuardedTest::TestNotGuarded {
+                    test: Test::IsListLen {
+                        bound: ListLenBound::Exact,
+                        len,
+                    },
+                });
+
+                result.extend(exact_tests);
+                result.push(GuardedTest::TestNotGuarded {
+                    test: Test::IsListLen {
+                        bound: ListLenBound::AtLeast,
+                        len: threshold,
+                    },
+                });
+            }
+            _ => result.push(test),
+        }
+    }
+
+    result
+}
+
 fn test_at_path<'a>(
     selected_path: &[PathInstruction],
     branch: &Branch<'a>,
@@ -548,6 +625,30 @@ fn test_at_path<'a>(
                     }
                 }
 
+                TupleDestructure(destructs, _) => {
+                    // not rendered, so pick the easiest
+                    let union = Union {
+                        render_as: RenderAs::Tag,
+                        alternatives: vec![Ctor {
+                            tag_id: TagId(0),
+                            name: CtorName::Tag(TagName(TUPLE_TAG_NAME.into())),
+                            arity: destructs.len(),
+                        }],
+                    };
+
+                    let arguments = destructs
+                        .iter()
+                        .map(|destruct| (destruct.pat.clone(), destruct.layout))
+                        .collect();
+
+                    IsCtor {
+                        tag_id: 0,
+                        ctor_name: CtorName::Tag(TagName(TUPLE_TAG_NAME.into())),
+                        union,
+                        arguments,
+                    }
+                }
+
                 NewtypeDestructure {
                     tag_name,
                     arguments,
@@ -604,6 +705,13 @@ fn test_at_path<'a>(
                 FloatLiteral(v, precision) => IsFloat(*v, *precision),
                 DecimalLiteral(v) => IsDecimal(*v),
                 StrLiteral(v) => IsStr(v.clone()),
+                List { arity, .. } => IsListLen {
+                    bound: match arity {
+                        ListArity::Exact(_) => ListLenBound::Exact,
+                        ListArity::Slice(_, _) => ListLenBound::AtLeast,
+                    },
+                    len: arity.min_len() as _,
+                },
             };
 
             let guarded_test = GuardedTest::TestNotGuarded { test };
@@ -727,6 +835,42 @@ fn to_relevant_branch_help<'a>(
             _ => None,
         },
 
+        TupleDestructure(destructs, _) => match test {
+            IsCtor {
+                ctor_name: test_name,
+                tag_id,
+                ..
+            } => {
+                debug_assert!(test_name == &CtorName::Tag(TagName(TUPLE_TAG_NAME.into())));
+                let destructs_len = destructs.len();
+                let sub_positions = destructs.into_iter().enumerate().map(|(index, destruct)| {
+                    let pattern = destruct.pat.clone();
+
+                    let mut new_path = path.to_vec();
+                    let next_instr = if destructs_len == 1 {
+                        PathInstruction::NewType
+                    } else {
+                        PathInstruction::TagIndex {
+                            index: index as u64,
+                            tag_id: *tag_id,
+                        }
+                    };
+                    new_path.push(next_instr);
+
+                    (new_path, pattern)
+                });
+                start.extend(sub_positions);
+                start.extend(end);
+
+                Some(Branch {
+                    goal: branch.goal,
+                    guard: branch.guard.clone(),
+                    patterns: start,
+                })
+            }
+            _ => None,
+        },
+
         OpaqueUnwrap { opaque, argument } => match test {
             IsCtor {
                 ctor_name: test_opaque_tag_name,
@@ -951,6 +1095,45 @@ fn to_relevant_branch_help<'a>(
 
             _ => None,
         },
+
+        List {
+            arity: my_arity,
+            elements,
+            ..
+        } => match test {
+            // The pattern is relevant only if it matches every list that passes the test.
+            // Because list length tests are split up to be disjoint, this means an exact-length
+            // test can be passed on to a pattern with a rest, but not the other way around.
+            IsListLen {
+                bound: test_bound,
+                len,
+            } if my_arity.covers_length(*len as _)
+                && !matches!(
+                    (test_bound, my_arity),
+                    (ListLenBound::AtLeast, ListArity::Exact(_))
+                ) =>
+            {
+                let sub_positions = elements.into_iter().enumerate().map(|(index, elem_pat)| {
+                    let mut new_path = path.to_vec();
+
+                    let probe_index = ListIndex::from_pattern_index(index, my_arity);
+                    new_path.push(PathInstruction::ListIndex { index: probe_index });
+
+                    (new_path, elem_pat)
+                });
+
+                start.extend(sub_positions);
+                start.extend(end);
+
+                Some(Branch {
+                    goal: branch.goal,
+                    guard: branch.guard.clone(),
+                    patterns: start,
+                })
+            }
+
+            _ => None,
+        },
     }
 }
 
@@ -1010,6 +1193,7 @@ fn needs_tests(pattern: &Pattern) -> bool {
 
         NewtypeDestructure { .. }
         | RecordDestructure(..)
+        | TupleDestructure(..)
         | AppliedTag { .. }
         | OpaqueUnwrap { .. }
         | BitLiteral { .. }
@@ -1017,7 +1201,8 @@ fn needs_tests(pattern: &Pattern) -> bool {
         | IntLiteral(_, _)
         | FloatLiteral(_, _)
         | DecimalLiteral(_)
-        | StrLiteral(_) => true,
+        | StrLiteral(_)
+        | List { .. } => true,
     }
 }
 
@@ -1262,6 +1447,7 @@ pub fn optimize_when<'a>(
 enum PathInstruction {
     NewType,
     TagIndex { index: u64, tag_id: TagIdIntType },
+    ListIndex { index: ListIndex },
 }
 
 fn path_to_expr_help<'a>(
@@ -1331,19 +1517,44 @@ fn path_to_expr_help<'a>(
                     }
                 }
             }
+
+            PathInstruction::ListIndex { index } => {
+                let elem_layout = match layout {
+                    Layout::Builtin(Builtin::List(elem_layout)) => *elem_layout,
+                    _ => internal_error!("{:?}", &layout),
+                };
+
+                let (index_sym, new_stores) = build_list_index_probe(env, symbol, index);
+
+                stores.extend(new_stores);
+
+                let load_sym = env.unique_symbol();
+                let load_expr = Expr::Call(Call {
+                    call_type: CallType::LowLevel {
+                        op: LowLevel::ListGetUnsafe,
+                        update_mode: env.next_update_mode_id(),
+                    },
+                    arguments: env.arena.alloc([symbol, index_sym]),
+                });
+
+                stores.push((load_sym, elem_layout, load_expr));
+
+                layout = elem_layout;
+                symbol = load_sym;
+            }
         }
     }
 
     (symbol, stores, layout)
 }
 
-fn test_to_equality<'a>(
+fn test_to_comparison<'a>(
     env: &mut Env<'a, '_>,
     cond_symbol: Symbol,
     cond_layout: &Layout<'a>,
     path: &[PathInstruction],
     test: Test<'a>,
-) -> (StoresVec<'a>, Symbol, Symbol, Option<ConstructorKnown<'a>>) {
+) -> (StoresVec<'a>, Comparison, Option<ConstructorKnown<'a>>) {
     let (rhs_symbol, mut stores, test_layout) =
         path_to_expr_help(env, cond_symbol, path, *cond_layout);
 
@@ -1373,8 +1584,7 @@ fn test_to_equality<'a>(
 
                     (
                         stores,
-                        lhs_symbol,
-                        rhs_symbol,
+                        (lhs_symbol, Comparator::Eq, rhs_symbol),
                         Some(ConstructorKnown::OnlyPass {
                             scrutinee: path_symbol,
                             layout: *cond_layout,
@@ -1391,7 +1601,7 @@ fn test_to_equality<'a>(
             let lhs_symbol = env.unique_symbol();
             stores.push((lhs_symbol, Layout::int_width(precision), lhs));
 
-            (stores, lhs_symbol, rhs_symbol, None)
+            (stores, (lhs_symbol, Comparator::Eq, rhs_symbol), None)
         }
 
         Test::IsFloat(test_int, precision) => {
@@ -1401,7 +1611,7 @@ fn test_to_equality<'a>(
             let lhs_symbol = env.unique_symbol();
             stores.push((lhs_symbol, Layout::float_width(precision), lhs));
 
-            (stores, lhs_symbol, rhs_symbol, None)
+            (stores, (lhs_symbol, Comparator::Eq, rhs_symbol), None)
         }
 
         Test::IsDecimal(test_dec) => {
@@ -1409,7 +1619,7 @@ fn test_to_equality<'a>(
             let lhs_symbol = env.unique_symbol();
             stores.push((lhs_symbol, *cond_layout, lhs));
 
-            (stores, lhs_symbol, rhs_symbol, None)
+            (stores, (lhs_symbol, Comparator::Eq, rhs_symbol), None)
         }
 
         Test::IsByte {
@@ -1421,7 +1631,7 @@ fn test_to_equality<'a>(
             let lhs_symbol = env.unique_symbol();
             stores.push((lhs_symbol, Layout::u8(), lhs));
 
-            (stores, lhs_symbol, rhs_symbol, None)
+            (stores, (lhs_symbol, Comparator::Eq, rhs_symbol), None)
         }
 
         Test::IsBit(test_bit) => {
@@ -1429,7 +1639,7 @@ fn test_to_equality<'a>(
             let lhs_symbol = env.unique_symbol();
             stores.push((lhs_symbol, Layout::Builtin(Builtin::Bool), lhs));
 
-            (stores, lhs_symbol, rhs_symbol, None)
+            (stores, (lhs_symbol, Comparator::Eq, rhs_symbol), None)
         }
 
         Test::IsStr(test_str) => {
@@ -1438,15 +1648,59 @@ fn test_to_equality<'a>(
 
             stores.push((lhs_symbol, Layout::Builtin(Builtin::Str), lhs));
 
-            (stores, lhs_symbol, rhs_symbol, None)
+            (stores, (lhs_symbol, Comparator::Eq, rhs_symbol), None)
+        }
+
+        Test::IsListLen { bound, len } => {
+            let list_layout = test_layout;
+            let list_sym = rhs_symbol;
+
+            match list_layout {
+                Layout::Builtin(Builtin::List(_elem_layout)) => {
+                    let real_len_expr = Expr::Call(Call {
+                        call_type: CallType::LowLevel {
+                            op: LowLevel::ListLen,
+                            update_mode: env.next_update_mode_id(),
+                        },
+                        arguments: env.arena.alloc([list_sym]),
+                    });
+                    let test_len_expr = Expr::Literal(Literal::Int((len as i128).to_ne_bytes()));
+
+                    let real_len = env.unique_symbol();
+                    let test_len = env.unique_symbol();
+
+                    let usize_layout = Layout::usize(env.target_info);
+
+                    stores.push((real_len, usize_layout, real_len_expr));
+                    stores.push((test_len, usize_layout, test_len_expr));
+
+                    let comparison = match bound {
+                        ListLenBound::Exact => (real_len, Comparator::Eq, test_len),
+                        ListLenBound::AtLeast => (real_len, Comparator::Geq, test_len),
+                    };
+
+                    (stores, comparison, None)
+                }
+                _ => internal_error!(
+                    "test path is not a list: {:#?}",
+                    (cond_layout, test_layout, path)
+                ),
+            }
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparator {
+    Eq,
+    Geq,
+}
+
+type Comparison = (Symbol, Comparator, Symbol);
+
 type Tests<'a> = std::vec::Vec<(
     bumpalo::collections::Vec<'a, (Symbol, Layout<'a>, Expr<'a>)>,
-    Symbol,
-    Symbol,
+    Comparison,
     Option<ConstructorKnown<'a>>,
 )>;
 
@@ -1460,7 +1714,13 @@ fn stores_and_condition<'a>(
 
     // Assumption: there is at most 1 guard, and it is the outer layer.
     for (path, test) in test_chain {
-        tests.push(test_to_equality(env, cond_symbol, cond_layout, &path, test))
+        tests.push(test_to_comparison(
+            env,
+            cond_symbol,
+            cond_layout,
+            &path,
+            test,
+        ))
     }
 
     tests
@@ -1470,8 +1730,7 @@ fn compile_test<'a>(
     env: &mut Env<'a, '_>,
     ret_layout: Layout<'a>,
     stores: bumpalo::collections::Vec<'a, (Symbol, Layout<'a>, Expr<'a>)>,
-    lhs: Symbol,
-    rhs: Symbol,
+    comparison: Comparison,
     fail: &'a Stmt<'a>,
     cond: Stmt<'a>,
 ) -> Stmt<'a> {
@@ -1480,8 +1739,7 @@ fn compile_test<'a>(
         ConstructorKnown::Neither,
         ret_layout,
         stores,
-        lhs,
-        rhs,
+        comparison,
         fail,
         cond,
     )
@@ -1493,8 +1751,7 @@ fn compile_test_help<'a>(
     branch_info: ConstructorKnown<'a>,
     ret_layout: Layout<'a>,
     stores: bumpalo::collections::Vec<'a, (Symbol, Layout<'a>, Expr<'a>)>,
-    lhs: Symbol,
-    rhs: Symbol,
+    (lhs, cmp, rhs): Comparison,
     fail: &'a Stmt<'a>,
     mut cond: Stmt<'a>,
 ) -> Stmt<'a> {
@@ -1554,7 +1811,10 @@ fn compile_test_help<'a>(
         default_branch,
     };
 
-    let op = LowLevel::Eq;
+    let op = match cmp {
+        Comparator::Eq => LowLevel::Eq,
+        Comparator::Geq => LowLevel::NumGte,
+    };
     let test = Expr::Call(crate::ir::Call {
         call_type: crate::ir::CallType::LowLevel {
             op,
@@ -1586,13 +1846,14 @@ fn compile_tests<'a>(
     fail: &'a Stmt<'a>,
     mut cond: Stmt<'a>,
 ) -> Stmt<'a> {
-    for (new_stores, lhs, rhs, opt_constructor_info) in tests.into_iter() {
+    for (new_stores, comparison, opt_constructor_info) in tests.into_iter() {
         match opt_constructor_info {
             None => {
-                cond = compile_test(env, ret_layout, new_stores, lhs, rhs, fail, cond);
+                cond = compile_test(env, ret_layout, new_stores, comparison, fail, cond);
             }
             Some(cinfo) => {
-                cond = compile_test_help(env, cinfo, ret_layout, new_stores, lhs, rhs, fail, cond);
+                cond =
+                    compile_test_help(env, cinfo, ret_layout, new_stores, comparison, fail, cond);
             }
         }
     }
@@ -1775,15 +2036,14 @@ fn decide_to_branching<'a>(
             if number_of_tests == 1 {
                 // if there is just one test, compile to a simple if-then-else
 
-                let (new_stores, lhs, rhs, _cinfo) = tests.into_iter().next().unwrap();
+                let (new_stores, comparison, _cinfo) = tests.into_iter().next().unwrap();
 
                 compile_test_help(
                     env,
                     chain_branch_info,
                     ret_layout,
                     new_stores,
-                    lhs,
-                    rhs,
+                    comparison,
                     fail,
                     pass_expr,
                 )
@@ -1850,6 +2110,7 @@ fn decide_to_branching<'a>(
                     Test::IsCtor { tag_id, .. } => tag_id as u64,
                     Test::IsDecimal(_) => unreachable!("decimals cannot be switched on"),
                     Test::IsStr(_) => unreachable!("strings cannot be switched on"),
+                    Test::IsListLen { .. } => unreachable!("list lengths cannot be switched on"),
                 };
 
                 // branch info is only useful for refcounted values
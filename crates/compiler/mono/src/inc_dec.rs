@@ -98,7 +98,7 @@ pub fn occurring_variables(stmt: &Stmt<'_>) -> (MutSet<Symbol>, MutSet<Symbol>)
                 stack.push(cont);
             }
 
-            Ret(symbol) => {
+            Ret(symbol) | Crash(symbol) => {
                 result.insert(*symbol);
             }
 
@@ -119,6 +119,13 @@ pub fn occurring_variables(stmt: &Stmt<'_>) -> (MutSet<Symbol>, MutSet<Symbol>)
                 stack.push(remainder);
             }
 
+            Dbg {
+                symbol, remainder, ..
+            } => {
+                result.insert(*symbol);
+                stack.push(remainder);
+            }
+
             Jump(_, arguments) => {
                 result.extend(arguments.iter().copied());
             }
@@ -206,6 +213,13 @@ pub fn occurring_variables_expr(expr: &Expr<'_>, result: &mut MutSet<Symbol>) {
         } => {
             result.insert(*symbol);
         }
+
+        SetUnionAtIndex {
+            structure, value, ..
+        } => {
+            result.insert(*structure);
+            result.insert(*value);
+        }
     }
 }
 
@@ -891,6 +905,22 @@ impl<'a> Context<'a> {
                 self.arena.alloc(Stmt::Let(z, v, l, b))
             }
 
+            SetUnionAtIndex {
+                structure: x,
+                value: y,
+                ..
+            } => {
+                // the structure is borrowed, like in UnionAtIndex,
+                // and the value is consumed, like the arguments of a Tag
+                let b = self.add_dec_if_needed(x, b, b_live_vars);
+
+                self.add_inc_before_consume_all(
+                    &[y],
+                    self.arena.alloc(Stmt::Let(z, v, l, b)),
+                    b_live_vars,
+                )
+            }
+
             ExprBox { symbol: x } => {
                 // mimics Tag
                 self.add_inc_before_consume_all(
@@ -1199,6 +1229,37 @@ impl<'a> Context<'a> {
                 (expect, b_live_vars)
             }
 
+            Dbg {
+                symbol,
+                region,
+                layout,
+                remainder,
+            } => {
+                let (b, mut b_live_vars) = self.visit_stmt(codegen, remainder);
+
+                // the dbg'd value is only borrowed; release it once it has been printed
+                let b = self.add_dec_if_needed(*symbol, b, &b_live_vars);
+
+                let dbg = self.arena.alloc(Stmt::Dbg {
+                    symbol: *symbol,
+                    region: *region,
+                    layout: *layout,
+                    remainder: b,
+                });
+
+                b_live_vars.insert(*symbol);
+
+                (dbg, b_live_vars)
+            }
+
+            Crash(x) => {
+                // the message is only borrowed by the host; the program ends before it is freed
+                let mut live_vars = MutSet::default();
+                live_vars.insert(*x);
+
+                (stmt, live_vars)
+            }
+
             RuntimeError(_) | Refcounting(_, _) => (stmt, MutSet::default()),
         }
     }
@@ -1291,7 +1352,7 @@ pub fn collect_stmt(
             vars
         }
 
-        Ret(symbol) => {
+        Ret(symbol) | Crash(symbol) => {
             vars.insert(*symbol);
             vars
         }
@@ -1313,6 +1374,13 @@ pub fn collect_stmt(
             collect_stmt(remainder, jp_live_vars, vars)
         }
 
+        Dbg {
+            symbol, remainder, ..
+        } => {
+            vars.insert(*symbol);
+            collect_stmt(remainder, jp_live_vars, vars)
+        }
+
         Join {
             id: j,
             parameters,
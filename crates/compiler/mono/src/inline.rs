@@ -0,0 +1,750 @@
+//! This module inlines calls to small procedures. The dev and wasm backends have no optimizer
+//! of their own, so without it they pay a full call for every tiny helper like `Bool.not`, or
+//! the `Num.add` wrapper around the `NumAdd` lowlevel. It runs before reset/reuse and refcount
+//! insertion, so those passes see the inlined code like any other.
+//!
+//! A call in the middle of a proc becomes a join point, whose body is the rest of the caller.
+//! Every `ret` in the inlined body becomes a jump to it:
+//!
+//! ```text
+//! let x = CallByName f a b;            joinpoint j x:
+//! <rest>                          =>       <rest>
+//!                                      in
+//!                                      <body of f, with `ret y` replaced by `jump j y`>
+//! ```
+//!
+//! If the body of the callee is a straight line of `let`s, the join point is not needed:
+//! its result is bound to `x` directly, followed by the rest of the caller. And a call in
+//! tail position keeps the `ret`s of the callee.
+
+use crate::ir::{
+    BranchInfo, CallSpecId, CallType, Expr, HigherOrderLowLevel, JoinPointId, ListLiteralElement,
+    ModifyRc, Param, PassedFunction, Proc, ProcLayout, SelfRecursive, Stmt, UpdateModeIds,
+};
+use crate::low_level::HigherOrder;
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+use roc_collections::all::MutMap;
+use roc_module::symbol::{IdentIds, ModuleId, Symbol};
+
+/// Procs with at most this many statements are inlined
+const INLINE_THRESHOLD: usize = 8;
+
+type ProcKey<'a> = (Symbol, ProcLayout<'a>);
+
+pub fn inline_procs<'a, 'i>(
+    arena: &'a Bump,
+    home: ModuleId,
+    ident_ids: &'i mut IdentIds,
+    update_mode_ids: &'i mut UpdateModeIds,
+    procs: &mut MutMap<ProcKey<'a>, Proc<'a>>,
+) {
+    // Only the original bodies are inlined, so mutually recursive procs can't unfold forever
+    let inlinable: MutMap<ProcKey<'a>, Proc<'a>> = procs
+        .iter()
+        .filter(|(key, proc)| is_inlinable(**key, proc))
+        .map(|(key, proc)| (*key, proc.clone()))
+        .collect();
+
+    if inlinable.is_empty() {
+        return;
+    }
+
+    // Alias analysis needs the calls in a proc to have distinct ids, including the copies
+    let last_call_spec_id = procs
+        .values()
+        .filter_map(|proc| max_call_spec_id(&proc.body))
+        .max()
+        .unwrap_or(CallSpecId::BACKEND_DUMMY);
+
+    let mut env = Env {
+        arena,
+        home,
+        ident_ids,
+        update_mode_ids,
+        inlinable: &inlinable,
+        last_call_spec_id,
+    };
+
+    for (key, proc) in procs.iter_mut() {
+        if let Some(body) = env.inline_stmt(*key, &proc.body) {
+            proc.body = body;
+        }
+    }
+}
+
+fn is_inlinable<'a>(key: ProcKey<'a>, proc: &Proc<'a>) -> bool {
+    matches!(proc.is_self_recursive, SelfRecursive::NotSelfRecursive)
+        && stmt_size(&proc.body) <= INLINE_THRESHOLD
+        && !calls(&proc.body, key)
+}
+
+fn stmt_size(stmt: &Stmt) -> usize {
+    use Stmt::*;
+
+    match stmt {
+        Let(_, _, _, cont) => 1 + stmt_size(cont),
+        Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            let branches_size: usize = branches.iter().map(|(_, _, b)| stmt_size(b)).sum();
+            1 + branches_size + stmt_size(default_branch.1)
+        }
+        Join {
+            body, remainder, ..
+        } => 1 + stmt_size(body) + stmt_size(remainder),
+        Refcounting(_, cont) => 1 + stmt_size(cont),
+        Expect { remainder, .. } | Dbg { remainder, .. } => 1 + stmt_size(remainder),
+        Ret(_) | Jump(_, _) | Crash(_) | RuntimeError(_) => 1,
+    }
+}
+
+/// Whether the statement calls the proc, e.g. because it is recursive but not tail recursive
+fn calls<'a>(stmt: &Stmt<'a>, key: ProcKey<'a>) -> bool {
+    use Stmt::*;
+
+    match stmt {
+        Let(_, expr, _, cont) => call_key(expr) == Some(key) || calls(cont, key),
+        Switch {
+            branches,
+            default_branch,
+            ..
+        } => branches.iter().any(|(_, _, b)| calls(b, key)) || calls(default_branch.1, key),
+        Join {
+            body, remainder, ..
+        } => calls(body, key) || calls(remainder, key),
+        Refcounting(_, cont) => calls(cont, key),
+        Expect { remainder, .. } | Dbg { remainder, .. } => calls(remainder, key),
+        Ret(_) | Jump(_, _) | Crash(_) | RuntimeError(_) => false,
+    }
+}
+
+fn call_key<'a>(expr: &Expr<'a>) -> Option<ProcKey<'a>> {
+    match expr {
+        Expr::Call(crate::ir::Call {
+            call_type:
+                CallType::ByName {
+                    name,
+                    ret_layout,
+                    arg_layouts,
+                    ..
+                },
+            ..
+        }) => Some((
+            name.name(),
+            ProcLayout {
+                arguments: arg_layouts,
+                result: **ret_layout,
+                captures_niche: name.captures_niche(),
+            },
+        )),
+        _ => None,
+    }
+}
+
+fn max_call_spec_id(stmt: &Stmt) -> Option<CallSpecId> {
+    use Stmt::*;
+
+    match stmt {
+        Let(_, expr, _, cont) => {
+            let here = match expr {
+                Expr::Call(crate::ir::Call {
+                    call_type:
+                        CallType::ByName {
+                            specialization_id, ..
+                        },
+                    ..
+                }) => Some(*specialization_id),
+                Expr::Call(crate::ir::Call {
+                    call_type: CallType::HigherOrder(higher_order),
+                    ..
+                }) => Some(higher_order.passed_function.specialization_id),
+                _ => None,
+            };
+            here.max(max_call_spec_id(cont))
+        }
+        Switch {
+            branches,
+            default_branch,
+            ..
+        } => branches
+            .iter()
+            .filter_map(|(_, _, b)| max_call_spec_id(b))
+            .max()
+            .max(max_call_spec_id(default_branch.1)),
+        Join {
+            body, remainder, ..
+        } => max_call_spec_id(body).max(max_call_spec_id(remainder)),
+        Refcounting(_, cont) => max_call_spec_id(cont),
+        Expect { remainder, .. } | Dbg { remainder, .. } => max_call_spec_id(remainder),
+        Ret(_) | Jump(_, _) | Crash(_) | RuntimeError(_) => None,
+    }
+}
+
+/// What a `ret` in the inlined body turns into
+#[derive(Clone, Copy)]
+enum OnReturn<'a> {
+    /// The call was in tail position, so the callee returns for the caller
+    Ret,
+    /// Jump to the join point that holds the rest of the caller
+    Jump(JoinPointId),
+    /// The callee is a straight line of `let`s, and its result is bound to the symbol the
+    /// caller expects. Continue with the rest of the caller.
+    Continue(&'a Stmt<'a>),
+}
+
+/// New names for the symbols and join points of an inlined body
+#[derive(Default)]
+struct Renames {
+    symbols: MutMap<Symbol, Symbol>,
+    join_points: MutMap<JoinPointId, JoinPointId>,
+}
+
+impl Renames {
+    fn get(&self, symbol: Symbol) -> Symbol {
+        self.symbols.get(&symbol).copied().unwrap_or(symbol)
+    }
+}
+
+struct Env<'a, 'i, 'r> {
+    arena: &'a Bump,
+    home: ModuleId,
+    ident_ids: &'i mut IdentIds,
+    update_mode_ids: &'i mut UpdateModeIds,
+    inlinable: &'r MutMap<ProcKey<'a>, Proc<'a>>,
+    last_call_spec_id: CallSpecId,
+}
+
+impl<'a, 'i, 'r> Env<'a, 'i, 'r> {
+    fn unique_symbol(&mut self) -> Symbol {
+        let ident_id = self.ident_ids.gen_unique();
+
+        Symbol::new(self.home, ident_id)
+    }
+
+    fn next_call_spec_id(&mut self) -> CallSpecId {
+        self.last_call_spec_id = self.last_call_spec_id.next();
+        self.last_call_spec_id
+    }
+
+    /// Returns `None` if there is nothing to inline in the statement
+    fn inline_stmt(&mut self, caller: ProcKey<'a>, stmt: &Stmt<'a>) -> Option<Stmt<'a>> {
+        use Stmt::*;
+
+        match stmt {
+            Let(symbol, expr, layout, cont) => {
+                let new_cont = self.inline_stmt(caller, cont);
+
+                let inlinable = self.inlinable;
+                let callee = call_key(expr)
+                    .filter(|key| *key != caller)
+                    .and_then(|key| inlinable.get(&key));
+
+                match (callee, expr) {
+                    (Some(callee), Expr::Call(crate::ir::Call { arguments, .. })) => {
+                        let cont: &'a Stmt<'a> = match new_cont {
+                            Some(new_cont) => self.arena.alloc(new_cont),
+                            None => cont,
+                        };
+                        Some(self.inline_call(callee, *symbol, *layout, arguments, cont))
+                    }
+                    _ => new_cont.map(|new_cont| {
+                        Let(*symbol, expr.clone(), *layout, self.arena.alloc(new_cont))
+                    }),
+                }
+            }
+            Switch {
+                cond_symbol,
+                cond_layout,
+                branches,
+                default_branch,
+                ret_layout,
+            } => {
+                let new_default = self.inline_stmt(caller, default_branch.1);
+                let new_branches: std::vec::Vec<_> = branches
+                    .iter()
+                    .map(|(_, _, branch)| self.inline_stmt(caller, branch))
+                    .collect();
+
+                if new_default.is_none() && new_branches.iter().all(Option::is_none) {
+                    return None;
+                }
+
+                let branches = Vec::from_iter_in(
+                    branches
+                        .iter()
+                        .zip(new_branches)
+                        .map(|((label, info, branch), new_branch)| {
+                            (
+                                *label,
+                                info.clone(),
+                                new_branch.unwrap_or_else(|| branch.clone()),
+                            )
+                        }),
+                    self.arena,
+                );
+
+                let default_branch: (BranchInfo<'a>, &'a Stmt<'a>) = (
+                    default_branch.0.clone(),
+                    match new_default {
+                        Some(new_default) => self.arena.alloc(new_default),
+                        None => default_branch.1,
+                    },
+                );
+
+                Some(Switch {
+                    cond_symbol: *cond_symbol,
+                    cond_layout: *cond_layout,
+                    branches: branches.into_bump_slice(),
+                    default_branch,
+                    ret_layout: *ret_layout,
+                })
+            }
+            Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => {
+                let new_body = self.inline_stmt(caller, body);
+                let new_remainder = self.inline_stmt(caller, remainder);
+
+                if new_body.is_none() && new_remainder.is_none() {
+                    return None;
+                }
+
+                Some(Join {
+                    id: *id,
+                    parameters,
+                    body: match new_body {
+                        Some(new_body) => self.arena.alloc(new_body),
+                        None => body,
+                    },
+                    remainder: match new_remainder {
+                        Some(new_remainder) => self.arena.alloc(new_remainder),
+                        None => remainder,
+                    },
+                })
+            }
+            Refcounting(modify, cont) => self
+                .inline_stmt(caller, cont)
+                .map(|cont| Refcounting(*modify, self.arena.alloc(cont))),
+            Expect {
+                condition,
+                region,
+                lookups,
+                layouts,
+                remainder,
+            } => self.inline_stmt(caller, remainder).map(|cont| Expect {
+                condition: *condition,
+                region: *region,
+                lookups,
+                layouts,
+                remainder: self.arena.alloc(cont),
+            }),
+            Dbg {
+                symbol,
+                region,
+                layout,
+                remainder,
+            } => self.inline_stmt(caller, remainder).map(|cont| Dbg {
+                symbol: *symbol,
+                region: *region,
+                layout: *layout,
+                remainder: self.arena.alloc(cont),
+            }),
+            Ret(_) | Jump(_, _) | Crash(_) | RuntimeError(_) => None,
+        }
+    }
+
+    /// Replace `let symbol = CallByName callee arguments; cont` by the body of the callee
+    fn inline_call(
+        &mut self,
+        callee: &Proc<'a>,
+        symbol: Symbol,
+        layout: crate::layout::Layout<'a>,
+        arguments: &[Symbol],
+        cont: &'a Stmt<'a>,
+    ) -> Stmt<'a> {
+        let mut renames = Renames::default();
+        for ((_, param), argument) in callee.args.iter().zip(arguments) {
+            renames.symbols.insert(*param, *argument);
+        }
+
+        let is_param = |s: Symbol| callee.args.iter().any(|(_, param)| *param == s);
+
+        match straight_line_result(&callee.body) {
+            Some(result) if !is_param(result) => {
+                renames.symbols.insert(result, symbol);
+                self.copy_stmt(&callee.body, &mut renames, OnReturn::Continue(cont))
+            }
+            _ if matches!(cont, Stmt::Ret(s) if *s == symbol) => {
+                self.copy_stmt(&callee.body, &mut renames, OnReturn::Ret)
+            }
+            _ => {
+                let id = JoinPointId(self.unique_symbol());
+                let remainder = self.copy_stmt(&callee.body, &mut renames, OnReturn::Jump(id));
+
+                let parameters = self.arena.alloc([Param {
+                    symbol,
+                    borrow: false,
+                    layout,
+                }]);
+
+                Stmt::Join {
+                    id,
+                    parameters,
+                    body: cont,
+                    remainder: self.arena.alloc(remainder),
+                }
+            }
+        }
+    }
+
+    /// Symbols bound in the copy get fresh names, so they are unique in the caller
+    fn bind(&mut self, renames: &mut Renames, symbol: Symbol) -> Symbol {
+        match renames.symbols.get(&symbol) {
+            Some(renamed) => *renamed,
+            None => {
+                let renamed = self.unique_symbol();
+                renames.symbols.insert(symbol, renamed);
+                renamed
+            }
+        }
+    }
+
+    fn copy_stmt(
+        &mut self,
+        stmt: &Stmt<'a>,
+        renames: &mut Renames,
+        on_return: OnReturn<'a>,
+    ) -> Stmt<'a> {
+        use Stmt::*;
+
+        match stmt {
+            Let(symbol, expr, layout, cont) => {
+                let expr = self.copy_expr(expr, renames);
+                let symbol = self.bind(renames, *symbol);
+                let cont = self.copy_stmt(cont, renames, on_return);
+
+                Let(symbol, expr, *layout, self.arena.alloc(cont))
+            }
+            Switch {
+                cond_symbol,
+                cond_layout,
+                branches,
+                default_branch,
+                ret_layout,
+            } => {
+                let mut new_branches = Vec::with_capacity_in(branches.len(), self.arena);
+                for (label, info, branch) in branches.iter() {
+                    let info = copy_branch_info(info, renames);
+                    let branch = self.copy_stmt(branch, renames, on_return);
+                    new_branches.push((*label, info, branch));
+                }
+
+                let default_info = copy_branch_info(&default_branch.0, renames);
+                let default_stmt = self.copy_stmt(default_branch.1, renames, on_return);
+
+                Switch {
+                    cond_symbol: renames.get(*cond_symbol),
+                    cond_layout: *cond_layout,
+                    branches: new_branches.into_bump_slice(),
+                    default_branch: (default_info, self.arena.alloc(default_stmt)),
+                    ret_layout: *ret_layout,
+                }
+            }
+            Ret(symbol) => match on_return {
+                OnReturn::Ret => Ret(renames.get(*symbol)),
+                OnReturn::Jump(id) => Jump(id, self.arena.alloc([renames.get(*symbol)])),
+                OnReturn::Continue(cont) => cont.clone(),
+            },
+            Refcounting(modify, cont) => {
+                let modify = match modify {
+                    ModifyRc::Inc(symbol, n) => ModifyRc::Inc(renames.get(*symbol), *n),
+                    ModifyRc::Dec(symbol) => ModifyRc::Dec(renames.get(*symbol)),
+                    ModifyRc::DecRef(symbol) => ModifyRc::DecRef(renames.get(*symbol)),
+                };
+                let cont = self.copy_stmt(cont, renames, on_return);
+
+                Refcounting(modify, self.arena.alloc(cont))
+            }
+            Expect {
+                condition,
+                region,
+                lookups,
+                layouts,
+                remainder,
+            } => {
+                let lookups =
+                    Vec::from_iter_in(lookups.iter().map(|s| renames.get(*s)), self.arena);
+                let remainder = self.copy_stmt(remainder, renames, on_return);
+
+                Expect {
+                    condition: renames.get(*condition),
+                    region: *region,
+                    lookups: lookups.into_bump_slice(),
+                    layouts,
+                    remainder: self.arena.alloc(remainder),
+                }
+            }
+            Dbg {
+                symbol,
+                region,
+                layout,
+                remainder,
+            } => {
+                let remainder = self.copy_stmt(remainder, renames, on_return);
+
+                Dbg {
+                    symbol: renames.get(*symbol),
+                    region: *region,
+                    layout: *layout,
+                    remainder: self.arena.alloc(remainder),
+                }
+            }
+            Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => {
+                let new_id = JoinPointId(self.unique_symbol());
+                renames.join_points.insert(*id, new_id);
+
+                let arena = self.arena;
+                let parameters = Vec::from_iter_in(
+                    parameters.iter().map(|param| Param {
+                        symbol: self.bind(renames, param.symbol),
+                        ..*param
+                    }),
+                    arena,
+                );
+                let body = self.copy_stmt(body, renames, on_return);
+                let remainder = self.copy_stmt(remainder, renames, on_return);
+
+                Join {
+                    id: new_id,
+                    parameters: parameters.into_bump_slice(),
+                    body: self.arena.alloc(body),
+                    remainder: self.arena.alloc(remainder),
+                }
+            }
+            Jump(id, arguments) => {
+                let id = renames.join_points.get(id).copied().unwrap_or(*id);
+                let arguments = self.copy_symbols(arguments, renames);
+
+                Jump(id, arguments)
+            }
+            Crash(symbol) => Crash(renames.get(*symbol)),
+            RuntimeError(message) => RuntimeError(message),
+        }
+    }
+
+    fn copy_symbols(&self, symbols: &[Symbol], renames: &Renames) -> &'a [Symbol] {
+        Vec::from_iter_in(symbols.iter().map(|s| renames.get(*s)), self.arena).into_bump_slice()
+    }
+
+    fn copy_expr(&mut self, expr: &Expr<'a>, renames: &Renames) -> Expr<'a> {
+        use Expr::*;
+
+        match expr {
+            Literal(literal) => Literal(*literal),
+            Call(call) => {
+                let call_type = match &call.call_type {
+                    CallType::ByName {
+                        name,
+                        ret_layout,
+                        arg_layouts,
+                        ..
+                    } => CallType::ByName {
+                        name: *name,
+                        ret_layout,
+                        arg_layouts,
+                        specialization_id: self.next_call_spec_id(),
+                    },
+                    CallType::Foreign {
+                        foreign_symbol,
+                        ret_layout,
+                    } => CallType::Foreign {
+                        foreign_symbol: foreign_symbol.clone(),
+                        ret_layout,
+                    },
+                    CallType::LowLevel { op, .. } => CallType::LowLevel {
+                        op: *op,
+                        update_mode: self.update_mode_ids.next_id(),
+                    },
+                    CallType::HigherOrder(higher_order) => {
+                        let passed_function = PassedFunction {
+                            specialization_id: self.next_call_spec_id(),
+                            captured_environment: renames
+                                .get(higher_order.passed_function.captured_environment),
+                            ..higher_order.passed_function
+                        };
+
+                        CallType::HigherOrder(self.arena.alloc(HigherOrderLowLevel {
+                            op: copy_higher_order(&higher_order.op, renames),
+                            closure_env_layout: higher_order.closure_env_layout,
+                            update_mode: self.update_mode_ids.next_id(),
+                            passed_function,
+                        }))
+                    }
+                };
+
+                Call(crate::ir::Call {
+                    call_type,
+                    arguments: self.copy_symbols(call.arguments, renames),
+                })
+            }
+            Tag {
+                tag_layout,
+                tag_id,
+                arguments,
+            } => Tag {
+                tag_layout: *tag_layout,
+                tag_id: *tag_id,
+                arguments: self.copy_symbols(arguments, renames),
+            },
+            Struct(fields) => Struct(self.copy_symbols(fields, renames)),
+            StructAtIndex {
+                index,
+                field_layouts,
+                structure,
+            } => StructAtIndex {
+                index: *index,
+                field_layouts,
+                structure: renames.get(*structure),
+            },
+            GetTagId {
+                structure,
+                union_layout,
+            } => GetTagId {
+                structure: renames.get(*structure),
+                union_layout: *union_layout,
+            },
+            UnionAtIndex {
+                structure,
+                tag_id,
+                union_layout,
+                index,
+            } => UnionAtIndex {
+                structure: renames.get(*structure),
+                tag_id: *tag_id,
+                union_layout: *union_layout,
+                index: *index,
+            },
+            SetUnionAtIndex {
+                structure,
+                tag_id,
+                union_layout,
+                index,
+                value,
+            } => SetUnionAtIndex {
+                structure: renames.get(*structure),
+                tag_id: *tag_id,
+                union_layout: *union_layout,
+                index: *index,
+                value: renames.get(*value),
+            },
+            Array { elem_layout, elems } => {
+                let elems = Vec::from_iter_in(
+                    elems.iter().map(|elem| match elem {
+                        ListLiteralElement::Literal(literal) => {
+                            ListLiteralElement::Literal(*literal)
+                        }
+                        ListLiteralElement::Symbol(s) => {
+                            ListLiteralElement::Symbol(renames.get(*s))
+                        }
+                    }),
+                    self.arena,
+                );
+
+                Array {
+                    elem_layout: *elem_layout,
+                    elems: elems.into_bump_slice(),
+                }
+            }
+            EmptyArray => EmptyArray,
+            ExprBox { symbol } => ExprBox {
+                symbol: renames.get(*symbol),
+            },
+            ExprUnbox { symbol } => ExprUnbox {
+                symbol: renames.get(*symbol),
+            },
+            Reuse {
+                symbol,
+                update_tag_id,
+                tag_layout,
+                tag_id,
+                arguments,
+                ..
+            } => Reuse {
+                symbol: renames.get(*symbol),
+                update_tag_id: *update_tag_id,
+                update_mode: self.update_mode_ids.next_id(),
+                tag_layout: *tag_layout,
+                tag_id: *tag_id,
+                arguments: self.copy_symbols(arguments, renames),
+            },
+            Reset { symbol, .. } => Reset {
+                symbol: renames.get(*symbol),
+                update_mode: self.update_mode_ids.next_id(),
+            },
+            RuntimeErrorFunction(message) => RuntimeErrorFunction(message),
+        }
+    }
+}
+
+/// The returned symbol, if the body is just a sequence of `let`s followed by a `ret`
+fn straight_line_result(stmt: &Stmt) -> Option<Symbol> {
+    match stmt {
+        Stmt::Let(_, _, _, cont) => straight_line_result(cont),
+        Stmt::Ret(symbol) => Some(*symbol),
+        _ => None,
+    }
+}
+
+fn copy_branch_info<'a>(info: &BranchInfo<'a>, renames: &Renames) -> BranchInfo<'a> {
+    match info {
+        BranchInfo::None => BranchInfo::None,
+        BranchInfo::Constructor {
+            scrutinee,
+            layout,
+            tag_id,
+        } => BranchInfo::Constructor {
+            scrutinee: renames.get(*scrutinee),
+            layout: *layout,
+            tag_id: *tag_id,
+        },
+    }
+}
+
+fn copy_higher_order(op: &HigherOrder, renames: &Renames) -> HigherOrder {
+    use HigherOrder::*;
+
+    match *op {
+        ListMap { xs } => ListMap {
+            xs: renames.get(xs),
+        },
+        ListMap2 { xs, ys } => ListMap2 {
+            xs: renames.get(xs),
+            ys: renames.get(ys),
+        },
+        ListMap3 { xs, ys, zs } => ListMap3 {
+            xs: renames.get(xs),
+            ys: renames.get(ys),
+            zs: renames.get(zs),
+        },
+        ListMap4 { xs, ys, zs, ws } => ListMap4 {
+            xs: renames.get(xs),
+            ys: renames.get(ys),
+            zs: renames.get(zs),
+            ws: renames.get(ws),
+        },
+        ListSortWith { xs } => ListSortWith {
+            xs: renames.get(xs),
+        },
+    }
+}
@@ -16,12 +16,13 @@ use roc_collections::VecMap;
 use roc_debug_flags::dbg_do;
 #[cfg(debug_assertions)]
 use roc_debug_flags::{
-    ROC_PRINT_IR_AFTER_REFCOUNT, ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SPECIALIZATION,
-    ROC_PRINT_RUNTIME_ERROR_GEN,
+    ROC_CHECK_MONO_IR, ROC_PRINT_IR_AFTER_INLINING, ROC_PRINT_IR_AFTER_REFCOUNT,
+    ROC_PRINT_IR_AFTER_RESET_REUSE, ROC_PRINT_IR_AFTER_SIMPLIFICATION,
+    ROC_PRINT_IR_AFTER_SPECIALIZATION, ROC_PRINT_RUNTIME_ERROR_GEN,
 };
 use roc_derive::SharedDerivedModule;
 use roc_error_macros::{internal_error, todo_abilities};
-use roc_exhaustive::{Ctor, CtorName, RenderAs, TagId};
+use roc_exhaustive::{Ctor, CtorName, ListArity, RenderAs, TagId};
 use roc_late_solve::{resolve_ability_specialization, AbilitiesView, Resolved, UnificationFailed};
 use roc_module::ident::{ForeignSymbol, Lowercase, TagName};
 use roc_module::low_level::LowLevel;
@@ -42,6 +43,12 @@ pub fn pretty_print_ir_symbols() -> bool {
     dbg_do!(ROC_PRINT_IR_AFTER_SPECIALIZATION, {
         return true;
     });
+    dbg_do!(ROC_PRINT_IR_AFTER_INLINING, {
+        return true;
+    });
+    dbg_do!(ROC_PRINT_IR_AFTER_SIMPLIFICATION, {
+        return true;
+    });
     dbg_do!(ROC_PRINT_IR_AFTER_RESET_REUSE, {
         return true;
     });
@@ -104,6 +111,28 @@ pub enum OptLevel {
     Optimize,
 }
 
+/// Optional passes over the mono IR, that run once all procs are specialized
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MonoPasses {
+    /// Inline calls to small procs, see [crate::inline]
+    pub inline: bool,
+    /// Fold constants and remove dead code, see [crate::simplify]
+    pub simplify: bool,
+}
+
+impl MonoPasses {
+    /// The dev and wasm backends have no optimizer of their own, and LLVM's alias analysis
+    /// also gets more precise when small helpers are inlined, so every opt level inlines.
+    /// LLVM folds constants and removes dead code better than we can, so only the
+    /// development backends need us to do it.
+    pub fn for_opt_level(opt_level: OptLevel) -> Self {
+        MonoPasses {
+            inline: true,
+            simplify: matches!(opt_level, OptLevel::Development),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub struct EntryPoint<'a> {
     pub symbol: Symbol,
@@ -209,6 +238,8 @@ pub struct PartialProc<'a> {
     pub body: roc_can::expr::Expr,
     pub body_var: Variable,
     pub is_self_recursive: bool,
+    /// The region of the body in the source of the module that defines it.
+    pub region: Region,
 }
 
 impl<'a> PartialProc<'a> {
@@ -238,6 +269,7 @@ impl<'a> PartialProc<'a> {
                     body: body.value,
                     body_var: ret_var,
                     is_self_recursive,
+                    region: body.region,
                 }
             }
 
@@ -255,6 +287,7 @@ impl<'a> PartialProc<'a> {
                     body: roc_can::expr::Expr::RuntimeError(error.value),
                     body_var: ret_var,
                     is_self_recursive: false,
+                    region: error.region,
                 }
             }
         }
@@ -313,6 +346,12 @@ pub struct Proc<'a> {
     pub is_self_recursive: SelfRecursive,
     pub must_own_arguments: bool,
     pub host_exposed_layouts: HostExposedLayouts<'a>,
+    /// The region of the proc in the source of the module that defines it.
+    /// Procs generated by the compiler have a zero region.
+    pub region: Region,
+    /// The region of the source that each statement of the body was lowered from, keyed by the
+    /// symbol that the statement binds. Used for debug info.
+    pub symbol_regions: &'a [(Symbol, Region)],
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -409,6 +448,29 @@ impl<'a> Proc<'a> {
             borrow_params,
             procs,
         );
+
+        dbg_do!(ROC_CHECK_MONO_IR, {
+            crate::debug::assert_procs_are_valid(
+                arena,
+                procs,
+                Some(borrow_params),
+                "refcount insertion",
+            );
+        });
+    }
+
+    pub fn inline_small_procs<'i>(
+        arena: &'a Bump,
+        home: ModuleId,
+        ident_ids: &'i mut IdentIds,
+        update_mode_ids: &'i mut UpdateModeIds,
+        procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    ) {
+        crate::inline::inline_procs(arena, home, ident_ids, update_mode_ids, procs);
+    }
+
+    pub fn simplify_procs(arena: &'a Bump, procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>) {
+        crate::simplify::simplify_procs(arena, procs);
     }
 
     pub fn insert_reset_reuse_operations<'i>(
@@ -443,7 +505,7 @@ impl<'a> Proc<'a> {
         use self::SelfRecursive::*;
         if let SelfRecursive(id) = self.is_self_recursive {
             let transformed = crate::tail_recursion::make_tail_recursive(
-                env.arena,
+                env,
                 id,
                 self.name,
                 self.body.clone(),
@@ -1081,6 +1143,7 @@ impl<'a> Procs<'a> {
                                         body: body.value,
                                         body_var: ret_var,
                                         is_self_recursive,
+                                        region: body.region,
                                     };
 
                                     self.partial_procs.insert(name.name(), partial_proc);
@@ -1117,6 +1180,7 @@ impl<'a> Procs<'a> {
                                     body: body.value,
                                     body_var: ret_var,
                                     is_self_recursive,
+                                    region: body.region,
                                 };
 
                                 self.partial_procs.insert(name.name(), partial_proc)
@@ -1306,6 +1370,10 @@ pub struct Env<'a, 'i> {
     pub abilities: AbilitiesView<'i>,
     pub exposed_by_module: &'i ExposedByModule,
     pub derived_module: &'i SharedDerivedModule,
+    /// Regions of the definitions and expressions lowered so far, keyed by the symbol they are
+    /// bound to, for debug info.
+    /// Each specialized proc takes the entries that were added while lowering its body.
+    pub symbol_regions: Vec<'a, (Symbol, Region)>,
 }
 
 impl<'a, 'i> Env<'a, 'i> {
@@ -1437,6 +1505,14 @@ pub enum Stmt<'a> {
         /// what happens after the expect
         remainder: &'a Stmt<'a>,
     },
+    Dbg {
+        /// the value that is reported to the host
+        symbol: Symbol,
+        region: Region,
+        layout: Layout<'a>,
+        /// what happens after the dbg
+        remainder: &'a Stmt<'a>,
+    },
     /// a join point `join f <params> = <continuation> in remainder`
     Join {
         id: JoinPointId,
@@ -1448,6 +1524,8 @@ pub enum Stmt<'a> {
         remainder: &'a Stmt<'a>,
     },
     Jump(JoinPointId, &'a [Symbol]),
+    /// abort the program, handing the user's `Str` message in the symbol to `roc_panic`
+    Crash(Symbol),
     RuntimeError(&'a str),
 }
 
@@ -1641,7 +1719,7 @@ impl<'a> Call<'a> {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct CallSpecId {
     id: u32,
 }
@@ -1651,6 +1729,21 @@ impl CallSpecId {
         self.id.to_ne_bytes()
     }
 
+    /// For passes after specialization that copy calls, and need ids that are not used yet
+    pub(crate) fn next(self) -> Self {
+        Self { id: self.id + 1 }
+    }
+
+    /// For the textual mono IR
+    pub(crate) fn to_u32(self) -> u32 {
+        self.id
+    }
+
+    /// For the textual mono IR
+    pub(crate) fn from_u32(id: u32) -> Self {
+        Self { id }
+    }
+
     /// Dummy value for generating refcount helper procs in the backends
     /// This happens *after* specialization so it's safe
     pub const BACKEND_DUMMY: Self = Self { id: 0 };
@@ -1666,6 +1759,16 @@ impl UpdateModeId {
         self.id.to_ne_bytes()
     }
 
+    /// For the textual mono IR
+    pub(crate) fn to_u32(self) -> u32 {
+        self.id
+    }
+
+    /// For the textual mono IR
+    pub(crate) fn from_u32(id: u32) -> Self {
+        Self { id }
+    }
+
     /// Dummy value for generating refcount helper procs in the backends
     /// This happens *after* alias analysis so it's safe
     pub const BACKEND_DUMMY: Self = Self { id: 0 };
@@ -1770,6 +1873,19 @@ pub enum Expr<'a> {
         index: u64,
     },
 
+    /// Overwrite a field of a heap-allocated tag in place, and evaluate to the unit struct.
+    ///
+    /// This is only introduced by tail recursion modulo cons, to fill in the hole of a cell that
+    /// was allocated before the value of its recursive field was known. The old value of the
+    /// field is not released, so it must be the nullable tag of the union.
+    SetUnionAtIndex {
+        structure: Symbol,
+        tag_id: TagIdIntType,
+        union_layout: UnionLayout<'a>,
+        index: u64,
+        value: Symbol,
+    },
+
     Array {
         elem_layout: Layout<'a>,
         elems: &'a [ListLiteralElement<'a>],
@@ -1962,6 +2078,21 @@ impl<'a> Expr<'a> {
             } => alloc
                 .text(format!("UnionAtIndex (Id {}) (Index {}) ", tag_id, index))
                 .append(symbol_to_doc(alloc, *structure)),
+
+            SetUnionAtIndex {
+                tag_id,
+                structure,
+                index,
+                value,
+                ..
+            } => alloc
+                .text(format!(
+                    "SetUnionAtIndex (Id {}) (Index {}) ",
+                    tag_id, index
+                ))
+                .append(symbol_to_doc(alloc, *structure))
+                .append(alloc.space())
+                .append(symbol_to_doc(alloc, *value)),
         }
     }
 
@@ -2024,6 +2155,15 @@ impl<'a> Stmt<'a> {
                 .append(alloc.hardline())
                 .append(remainder.to_doc(alloc)),
 
+            Dbg {
+                symbol, remainder, ..
+            } => alloc
+                .text("dbg ")
+                .append(symbol_to_doc(alloc, *symbol))
+                .append(";")
+                .append(alloc.hardline())
+                .append(remainder.to_doc(alloc)),
+
             Ret(symbol) => alloc
                 .text("ret ")
                 .append(symbol_to_doc(alloc, *symbol))
@@ -2084,6 +2224,11 @@ impl<'a> Stmt<'a> {
                 }
             }
 
+            Crash(symbol) => alloc
+                .text("crash ")
+                .append(symbol_to_doc(alloc, *symbol))
+                .append(";"),
+
             RuntimeError(s) => alloc.text(format!("Error {}", s)),
 
             Join {
@@ -2210,6 +2355,8 @@ fn from_can_let<'a>(
     }
 
     if let roc_can::pattern::Pattern::Identifier(symbol) = &def.loc_pattern.value {
+        env.symbol_regions.push((*symbol, def.loc_pattern.region));
+
         return match def.loc_expr.value {
             Closure(closure_data) => {
                 register_capturing_closure(env, procs, layout_cache, *symbol, closure_data);
@@ -2227,6 +2374,17 @@ fn from_can_let<'a>(
 
                 lower_rest!(variable, cont.value)
             }
+            TupleAccessor(accessor_data) => {
+                let fresh_tuple_symbol = env.unique_symbol();
+                register_noncapturing_closure(
+                    env,
+                    procs,
+                    *symbol,
+                    accessor_data.to_closure_data(fresh_tuple_symbol),
+                );
+
+                lower_rest!(variable, cont.value)
+            }
             Var(original) | AbilityMember(original, _, _) => {
                 // a variable is aliased, e.g.
                 //
@@ -2578,7 +2736,10 @@ fn pattern_to_when<'a>(
             (env.unique_symbol(), Loc::at_zero(RuntimeError(error)))
         }
 
-        AppliedTag { .. } | RecordDestructure { .. } | UnwrappedOpaque { .. } => {
+        AppliedTag { .. }
+        | RecordDestructure { .. }
+        | TupleDestructure { .. }
+        | UnwrappedOpaque { .. } => {
             let symbol = env.unique_symbol();
 
             let wrapped_body = When {
@@ -2608,7 +2769,8 @@ fn pattern_to_when<'a>(
         | NumLiteral(..)
         | FloatLiteral(..)
         | StrLiteral(..)
-        | roc_can::pattern::Pattern::SingleQuote(..) => {
+        | roc_can::pattern::Pattern::SingleQuote(..)
+        | List { .. } => {
             // These patters are refutable, and thus should never occur outside a `when` expression
             // They should have been replaced with `UnsupportedPattern` during canonicalization
             unreachable!("refutable pattern {:?} where irrefutable pattern is expected. This should never happen!", pattern.value)
@@ -2906,6 +3068,8 @@ fn generate_runtime_error_function<'a>(
         is_self_recursive: SelfRecursive::NotSelfRecursive,
         must_own_arguments: false,
         host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+        region: Region::zero(),
+        symbol_regions: &[],
     }
 }
 
@@ -2954,6 +3118,7 @@ fn specialize_external<'a>(
 
     let body = partial_proc.body.clone();
     let body_var = partial_proc.body_var;
+    let partial_proc_region = partial_proc.region;
 
     // determine the layout of aliases/rigids exposed to the host
     let host_exposed_layouts = if host_exposed_variables.is_empty() {
@@ -3018,6 +3183,8 @@ fn specialize_external<'a>(
                         is_self_recursive: SelfRecursive::NotSelfRecursive,
                         must_own_arguments: false,
                         host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+                        region: partial_proc_region,
+                        symbol_regions: &[],
                     };
 
                     let top_level = ProcLayout::new(
@@ -3045,6 +3212,8 @@ fn specialize_external<'a>(
                         is_self_recursive: SelfRecursive::NotSelfRecursive,
                         must_own_arguments: false,
                         host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+                        region: partial_proc_region,
+                        symbol_regions: &[],
                     };
 
                     let top_level =
@@ -3070,7 +3239,11 @@ fn specialize_external<'a>(
         }
     };
 
+    let symbol_regions_start = env.symbol_regions.len();
     let mut specialized_body = from_can(env, body_var, body, procs, layout_cache);
+    let symbol_regions =
+        Vec::from_iter_in(env.symbol_regions.drain(symbol_regions_start..), env.arena)
+            .into_bump_slice();
 
     match specialized {
         SpecializedLayout::FunctionPointerBody {
@@ -3106,6 +3279,8 @@ fn specialize_external<'a>(
                 is_self_recursive: recursivity,
                 must_own_arguments: false,
                 host_exposed_layouts,
+                region: partial_proc_region,
+                symbol_regions,
             };
 
             Ok(proc)
@@ -3284,6 +3459,8 @@ fn specialize_external<'a>(
                 is_self_recursive: recursivity,
                 must_own_arguments: false,
                 host_exposed_layouts,
+                region: partial_proc_region,
+                symbol_regions,
             };
 
             Ok(proc)
@@ -3963,106 +4140,119 @@ pub fn with_hole<'a>(
                 Err(_) => return Stmt::RuntimeError("Can't create record with improper layout"),
             };
 
-            let mut field_symbols = Vec::with_capacity_in(fields.len(), env.arena);
-            let mut can_fields = Vec::with_capacity_in(fields.len(), env.arena);
-
-            #[allow(clippy::enum_variant_names)]
-            enum Field {
-                // TODO: rename this since it can handle unspecialized expressions now too
-                FunctionOrUnspecialized(Symbol, Variable),
-                ValueSymbol,
-                Field(roc_can::expr::Field),
-            }
-
-            for (label, variable, _) in sorted_fields.into_iter() {
-                // TODO how should function pointers be handled here?
-                use ReuseSymbol::*;
-                match fields.remove(&label) {
-                    Some(field) => {
-                        match can_reuse_symbol(env, procs, &field.loc_expr.value, field.var) {
-                            Imported(symbol)
-                            | LocalFunction(symbol)
-                            | UnspecializedExpr(symbol) => {
-                                field_symbols.push(symbol);
-                                can_fields.push(Field::FunctionOrUnspecialized(symbol, variable));
-                            }
-                            Value(symbol) => {
-                                let reusable = procs.symbol_specializations.get_or_insert(
-                                    env,
-                                    layout_cache,
-                                    symbol,
-                                    field.var,
-                                );
-                                field_symbols.push(reusable);
-                                can_fields.push(Field::ValueSymbol);
-                            }
-                            NotASymbol => {
-                                field_symbols.push(env.unique_symbol());
-                                can_fields.push(Field::Field(field));
-                            }
-                        }
-                    }
-                    None => {
-                        // this field was optional, but not given
-                        continue;
-                    }
-                }
-            }
+            // optional fields that were not given are skipped
+            let fields = sorted_fields
+                .into_iter()
+                .filter_map(|(label, variable, _)| {
+                    let field = fields.remove(&label)?;
 
-            // creating a record from the var will unpack it if it's just a single field.
-            let layout = match layout_cache.from_var(env.arena, record_var, env.subs) {
-                Ok(layout) => layout,
-                Err(_) => return Stmt::RuntimeError("Can't create record with improper layout"),
-            };
+                    Some((variable, field.var, *field.loc_expr))
+                })
+                .collect();
 
-            let field_symbols = field_symbols.into_bump_slice();
+            struct_with_hole(
+                env,
+                record_var,
+                fields,
+                procs,
+                layout_cache,
+                assigned,
+                hole,
+                "Can't create record with improper layout",
+            )
+        }
 
-            let mut stmt = if let [only_field] = field_symbols {
-                let mut hole = hole.clone();
-                substitute_in_exprs(env.arena, &mut hole, assigned, *only_field);
-                hole
-            } else {
-                Stmt::Let(assigned, Expr::Struct(field_symbols), layout, hole)
+        EmptyRecord => let_empty_struct(assigned, hole),
+
+        Tuple {
+            tuple_var, elems, ..
+        } => {
+            let sorted_elems = match crate::layout::sort_tuple_elems(
+                env.arena,
+                tuple_var,
+                env.subs,
+                env.target_info,
+            ) {
+                Ok(elems) => elems,
+                Err(_) => return Stmt::RuntimeError("Can't create tuple with improper layout"),
             };
 
-            for (opt_field, symbol) in can_fields.into_iter().rev().zip(field_symbols.iter().rev())
-            {
-                match opt_field {
-                    Field::ValueSymbol => {
-                        // this symbol is already defined; nothing to do
-                    }
-                    Field::FunctionOrUnspecialized(symbol, variable) => {
-                        stmt = specialize_symbol(
-                            env,
-                            procs,
-                            layout_cache,
-                            Some(variable),
-                            symbol,
-                            stmt,
-                            symbol,
-                        );
-                    }
-                    Field::Field(field) => {
-                        stmt = with_hole(
-                            env,
-                            field.loc_expr.value,
-                            field.var,
-                            procs,
-                            layout_cache,
-                            *symbol,
-                            env.arena.alloc(stmt),
-                        );
-                    }
-                }
-            }
+            let mut elems: std::vec::Vec<_> = elems.into_iter().map(Some).collect();
 
-            stmt
-        }
+            let elems = sorted_elems
+                .into_iter()
+                .map(|(index, variable, _)| {
+                    let (var, loc_expr) = elems[index]
+                        .take()
+                        .expect("tuple element not in its own type");
 
-        EmptyRecord => let_empty_struct(assigned, hole),
+                    (variable, var, *loc_expr)
+                })
+                .collect();
+
+            struct_with_hole(
+                env,
+                tuple_var,
+                elems,
+                procs,
+                layout_cache,
+                assigned,
+                hole,
+                "Can't create tuple with improper layout",
+            )
+        }
 
         Expect { .. } => unreachable!("I think this is unreachable"),
 
+        Dbg {
+            loc_expr,
+            variable: dbg_var,
+        } => {
+            let res_layout = layout_cache.from_var(env.arena, dbg_var, env.subs);
+            let layout = return_on_layout_error!(env, res_layout, "Dbg");
+
+            // `dbg` evaluates to its argument, so the argument is assigned directly to the hole
+            let stmt = Stmt::Dbg {
+                symbol: assigned,
+                region: loc_expr.region,
+                layout,
+                remainder: hole,
+            };
+
+            with_hole(
+                env,
+                loc_expr.value,
+                dbg_var,
+                procs,
+                layout_cache,
+                assigned,
+                env.arena.alloc(stmt),
+            )
+        }
+
+        Crash { msg, ret_var: _ } => {
+            // `crash` diverges, so the hole is never filled
+            let msg_symbol = possible_reuse_symbol_or_specialize(
+                env,
+                procs,
+                layout_cache,
+                &msg.value,
+                Variable::STR,
+            );
+
+            let stmt = Stmt::Crash(msg_symbol);
+
+            assign_to_symbol(
+                env,
+                procs,
+                layout_cache,
+                Variable::STR,
+                *msg,
+                msg_symbol,
+                stmt,
+            )
+        }
+
         If {
             cond_var,
             branch_var,
@@ -4377,62 +4567,105 @@ pub fn with_hole<'a>(
                 }
             }
 
-            let record_symbol = possible_reuse_symbol_or_specialize(
+            struct_access_with_hole(
                 env,
                 procs,
                 layout_cache,
-                &loc_expr.value,
                 record_var,
-            );
+                field_var,
+                field_layouts.into_bump_slice(),
+                index.expect("field not in its own type"),
+                *loc_expr,
+                assigned,
+                hole,
+            )
+        }
 
-            let mut stmt = match field_layouts.as_slice() {
-                [_] => {
-                    let mut hole = hole.clone();
-                    substitute_in_exprs(env.arena, &mut hole, assigned, record_symbol);
+        TupleAccess {
+            tuple_var,
+            elem_var,
+            index: accessed_index,
+            loc_expr,
+            ..
+        } => {
+            let sorted_elems = match crate::layout::sort_tuple_elems(
+                env.arena,
+                tuple_var,
+                env.subs,
+                env.target_info,
+            ) {
+                Ok(elems) => elems,
+                Err(_) => return Stmt::RuntimeError("Can't access tuple with improper layout"),
+            };
 
-                    hole
-                }
-                _ => {
-                    let expr = Expr::StructAtIndex {
-                        index: index.expect("field not in its own type") as u64,
-                        field_layouts: field_layouts.into_bump_slice(),
-                        structure: record_symbol,
-                    };
+            let mut final_index = None;
+            let mut elem_layouts = Vec::with_capacity_in(sorted_elems.len(), env.arena);
 
-                    let layout = layout_cache
-                        .from_var(env.arena, field_var, env.subs)
-                        .unwrap_or_else(|err| {
-                            panic!("TODO turn fn_var into a RuntimeError {:?}", err)
-                        });
+            for (current, (index, _, elem_layout)) in sorted_elems.into_iter().enumerate() {
+                elem_layouts.push(elem_layout);
 
-                    Stmt::Let(assigned, expr, layout, hole)
+                if index == accessed_index {
+                    final_index = Some(current);
                 }
-            };
+            }
 
-            stmt = assign_to_symbol(
+            struct_access_with_hole(
                 env,
                 procs,
                 layout_cache,
-                record_var,
+                tuple_var,
+                elem_var,
+                elem_layouts.into_bump_slice(),
+                final_index.expect("elem not in its own type"),
                 *loc_expr,
-                record_symbol,
-                stmt,
-            );
+                assigned,
+                hole,
+            )
+        }
 
-            stmt
+        TupleAccessor(accessor_data) => {
+            let elem_var = accessor_data.elem_var;
+            let fresh_tuple_symbol = env.unique_symbol();
+
+            accessor_with_hole(
+                env,
+                procs,
+                layout_cache,
+                accessor_data.to_closure_data(fresh_tuple_symbol),
+                elem_var,
+                assigned,
+                hole,
+                "Expr::TupleAccessor",
+            )
         }
 
         Accessor(accessor_data) => {
             let field_var = accessor_data.field_var;
             let fresh_record_symbol = env.unique_symbol();
 
+            accessor_with_hole(
+                env,
+                procs,
+                layout_cache,
+                accessor_data.to_closure_data(fresh_record_symbol),
+                field_var,
+                assigned,
+                hole,
+                "Expr::Accessor",
+            )
+        }
+
+        OpaqueWrapFunction(wrap_fn_data) => {
+            let opaque_var = wrap_fn_data.opaque_var;
+            let arg_symbol = env.unique_symbol();
+
             let ClosureData {
                 name,
                 function_type,
                 arguments,
                 loc_body,
                 ..
-            } = accessor_data.to_closure_data(fresh_record_symbol);
+            } = wrap_fn_data.to_closure_data(arg_symbol);
 
             match procs.insert_anonymous(
                 env,
@@ -4441,14 +4674,14 @@ pub fn with_hole<'a>(
                 arguments,
                 *loc_body,
                 CapturedSymbols::None,
-                field_var,
+                opaque_var,
                 layout_cache,
             ) {
                 Ok(_) => {
                     let raw_layout = return_on_layout_error!(
                         env,
                         layout_cache.raw_from_var(env.arena, function_type, env.subs),
-                        "Expr::Accessor"
+                        "Expr::OpaqueWrapFunction"
                     );
 
                     match raw_layout {
@@ -4464,7 +4697,9 @@ pub fn with_hole<'a>(
                                 hole,
                             )
                         }
-                        RawFunctionLayout::ZeroArgumentThunk(_) => unreachable!(),
+                        RawFunctionLayout::ZeroArgumentThunk(_) => {
+                            internal_error!("should not be a thunk!")
+                        }
                     }
                 }
 
@@ -4474,72 +4709,18 @@ pub fn with_hole<'a>(
             }
         }
 
-        OpaqueWrapFunction(wrap_fn_data) => {
-            let opaque_var = wrap_fn_data.opaque_var;
-            let arg_symbol = env.unique_symbol();
+        Update {
+            record_var,
+            symbol: structure,
+            updates,
+            ..
+        } => {
+            use FieldType::*;
 
-            let ClosureData {
-                name,
-                function_type,
-                arguments,
-                loc_body,
-                ..
-            } = wrap_fn_data.to_closure_data(arg_symbol);
-
-            match procs.insert_anonymous(
-                env,
-                LambdaName::no_niche(name),
-                function_type,
-                arguments,
-                *loc_body,
-                CapturedSymbols::None,
-                opaque_var,
-                layout_cache,
-            ) {
-                Ok(_) => {
-                    let raw_layout = return_on_layout_error!(
-                        env,
-                        layout_cache.raw_from_var(env.arena, function_type, env.subs),
-                        "Expr::OpaqueWrapFunction"
-                    );
-
-                    match raw_layout {
-                        RawFunctionLayout::Function(_, lambda_set, _) => {
-                            let lambda_name =
-                                find_lambda_name(env, layout_cache, lambda_set, name, &[]);
-                            construct_closure_data(
-                                env,
-                                lambda_set,
-                                lambda_name,
-                                &[],
-                                assigned,
-                                hole,
-                            )
-                        }
-                        RawFunctionLayout::ZeroArgumentThunk(_) => {
-                            internal_error!("should not be a thunk!")
-                        }
-                    }
-                }
-
-                Err(_error) => Stmt::RuntimeError(
-                    "TODO convert anonymous function error to a RuntimeError string",
-                ),
-            }
-        }
-
-        Update {
-            record_var,
-            symbol: structure,
-            updates,
-            ..
-        } => {
-            use FieldType::*;
-
-            enum FieldType<'a> {
-                CopyExisting(u64),
-                UpdateExisting(&'a roc_can::expr::Field),
-            }
+            enum FieldType<'a> {
+                CopyExisting(u64),
+                UpdateExisting(&'a roc_can::expr::Field),
+            }
 
             // Strategy: turn a record update into the creation of a new record.
             // This has the benefit that we don't need to do anything special for reference
@@ -5260,6 +5441,10 @@ fn late_resolve_ability_specialization<'a>(
                         // The immediate is an ability member itself, so it must be resolved!
                         late_resolve_ability_specialization(env, imm, None, specialization_var)
                     }
+                    roc_derive_key::Derived::SingleLambdaSetImmediate(imm) => {
+                        // The immediate is a plain function, so it can be used directly.
+                        imm
+                    }
                     roc_derive_key::Derived::Key(derive_key) => {
                         let mut derived_module = env
                             .derived_module
@@ -6396,6 +6581,25 @@ fn substitute_in_stmt_help<'a>(
             Some(arena.alloc(expect))
         }
 
+        Dbg {
+            symbol,
+            region,
+            layout,
+            remainder,
+        } => {
+            let new_remainder =
+                substitute_in_stmt_help(arena, remainder, subs).unwrap_or(remainder);
+
+            let dbg = Dbg {
+                symbol: substitute(subs, *symbol).unwrap_or(*symbol),
+                region: *region,
+                layout: *layout,
+                remainder: new_remainder,
+            };
+
+            Some(arena.alloc(dbg))
+        }
+
         Jump(id, args) => {
             let mut did_change = false;
             let new_args = Vec::from_iter_in(
@@ -6418,6 +6622,8 @@ fn substitute_in_stmt_help<'a>(
             }
         }
 
+        Crash(symbol) => substitute(subs, *symbol).map(|new| &*arena.alloc(Crash(new))),
+
         RuntimeError(_) => None,
     }
 }
@@ -6621,6 +6827,23 @@ fn substitute_in_expr<'a>(
             }),
             None => None,
         },
+
+        SetUnionAtIndex {
+            structure,
+            tag_id,
+            union_layout,
+            index,
+            value,
+        } => match (substitute(subs, *structure), substitute(subs, *value)) {
+            (None, None) => None,
+            (new_structure, new_value) => Some(SetUnionAtIndex {
+                structure: new_structure.unwrap_or(*structure),
+                tag_id: *tag_id,
+                union_layout: *union_layout,
+                index: *index,
+                value: new_value.unwrap_or(*value),
+            }),
+        },
     }
 }
 
@@ -6726,6 +6949,27 @@ fn store_pattern_help<'a>(
             return store_pattern_help(env, procs, layout_cache, pattern, outer_symbol, stmt);
         }
 
+        List {
+            arity,
+            list_layout,
+            element_layout,
+            elements,
+            opt_rest,
+        } => {
+            return store_list_pattern(
+                env,
+                procs,
+                layout_cache,
+                outer_symbol,
+                *arity,
+                *list_layout,
+                *element_layout,
+                elements,
+                opt_rest,
+                stmt,
+            )
+        }
+
         RecordDestructure(destructs, [_single_field]) => {
             for destruct in destructs {
                 match &destruct.typ {
@@ -6784,6 +7028,46 @@ fn store_pattern_help<'a>(
                 return StorePattern::NotProductive(stmt);
             }
         }
+
+        TupleDestructure(destructs, [_single_elem]) => {
+            if let Some(destruct) = destructs.first() {
+                return store_pattern_help(
+                    env,
+                    procs,
+                    layout_cache,
+                    &destruct.pat,
+                    outer_symbol,
+                    stmt,
+                );
+            }
+        }
+        TupleDestructure(destructs, sorted_elems) => {
+            let mut is_productive = false;
+            for (index, destruct) in destructs.iter().enumerate().rev() {
+                match store_tuple_destruct(
+                    env,
+                    procs,
+                    layout_cache,
+                    destruct,
+                    index as u64,
+                    outer_symbol,
+                    sorted_elems,
+                    stmt,
+                ) {
+                    StorePattern::Productive(new) => {
+                        is_productive = true;
+                        stmt = new;
+                    }
+                    StorePattern::NotProductive(new) => {
+                        stmt = new;
+                    }
+                }
+            }
+
+            if !is_productive {
+                return StorePattern::NotProductive(stmt);
+            }
+        }
     }
 
     StorePattern::Productive(stmt)
@@ -6868,6 +7152,145 @@ fn store_tag_pattern<'a>(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
+fn store_list_pattern<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    list_sym: Symbol,
+    list_arity: ListArity,
+    list_layout: Layout<'a>,
+    element_layout: Layout<'a>,
+    elements: &[Pattern<'a>],
+    opt_rest: &Option<(usize, Option<Symbol>)>,
+    mut stmt: Stmt<'a>,
+) -> StorePattern<'a> {
+    use Pattern::*;
+
+    let mut is_productive = false;
+
+    for (index, element) in elements.iter().enumerate().rev() {
+        let element_symbol = match element {
+            Identifier(symbol) => {
+                // Pattern can define only one specialization
+                let symbol = procs
+                    .symbol_specializations
+                    .remove_single(*symbol)
+                    .unwrap_or(*symbol);
+
+                is_productive = true;
+                symbol
+            }
+            Underscore
+            | IntLiteral(_, _)
+            | FloatLiteral(_, _)
+            | DecimalLiteral(_)
+            | EnumLiteral { .. }
+            | BitLiteral { .. }
+            | StrLiteral(_) => {
+                continue;
+            }
+            _ => {
+                // store the element in a symbol, and continue matching on it
+                let symbol = env.unique_symbol();
+
+                match store_pattern_help(env, procs, layout_cache, element, symbol, stmt) {
+                    StorePattern::Productive(new) => {
+                        is_productive = true;
+                        stmt = new;
+                        symbol
+                    }
+                    StorePattern::NotProductive(new) => {
+                        // only if we bind one of its (sub)elements to a used name should we
+                        // load the element
+                        stmt = new;
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let list_index = ListIndex::from_pattern_index(index, list_arity);
+        let (index_sym, stores) = build_list_index_probe(env, list_sym, &list_index);
+
+        let load = Expr::Call(Call {
+            call_type: CallType::LowLevel {
+                op: LowLevel::ListGetUnsafe,
+                update_mode: env.next_update_mode_id(),
+            },
+            arguments: env.arena.alloc([list_sym, index_sym]),
+        });
+
+        stmt = Stmt::Let(element_symbol, load, element_layout, env.arena.alloc(stmt));
+
+        for (symbol, layout, expr) in stores.into_iter().rev() {
+            stmt = Stmt::Let(symbol, expr, layout, env.arena.alloc(stmt));
+        }
+    }
+
+    if let (Some((_, Some(rest_symbol))), ListArity::Slice(head, tail)) = (opt_rest, list_arity) {
+        let rest_symbol = procs
+            .symbol_specializations
+            .remove_single(*rest_symbol)
+            .unwrap_or(*rest_symbol);
+
+        // rest = List.sublist lst { start: head, len: List.len lst - (head + tail) }
+        let usize_layout = Layout::usize(env.target_info);
+
+        let len_sym = env.unique_symbol();
+        let len_expr = Expr::Call(Call {
+            call_type: CallType::LowLevel {
+                op: LowLevel::ListLen,
+                update_mode: env.next_update_mode_id(),
+            },
+            arguments: env.arena.alloc([list_sym]),
+        });
+
+        let start_sym = env.unique_symbol();
+        let start_expr = Expr::Literal(Literal::Int((head as i128).to_ne_bytes()));
+
+        let taken_sym = env.unique_symbol();
+        let taken_expr = Expr::Literal(Literal::Int(((head + tail) as i128).to_ne_bytes()));
+
+        let rest_len_sym = env.unique_symbol();
+        let rest_len_expr = Expr::Call(Call {
+            call_type: CallType::LowLevel {
+                op: LowLevel::NumSubWrap,
+                update_mode: env.next_update_mode_id(),
+            },
+            arguments: env.arena.alloc([len_sym, taken_sym]),
+        });
+
+        let sublist_expr = Expr::Call(Call {
+            call_type: CallType::LowLevel {
+                op: LowLevel::ListSublist,
+                update_mode: env.next_update_mode_id(),
+            },
+            arguments: env.arena.alloc([list_sym, start_sym, rest_len_sym]),
+        });
+
+        let stores = [
+            (len_sym, usize_layout, len_expr),
+            (start_sym, usize_layout, start_expr),
+            (taken_sym, usize_layout, taken_expr),
+            (rest_len_sym, usize_layout, rest_len_expr),
+            (rest_symbol, list_layout, sublist_expr),
+        ];
+
+        for (symbol, layout, expr) in stores.into_iter().rev() {
+            stmt = Stmt::Let(symbol, expr, layout, env.arena.alloc(stmt));
+        }
+
+        is_productive = true;
+    }
+
+    if is_productive {
+        StorePattern::Productive(stmt)
+    } else {
+        StorePattern::NotProductive(stmt)
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 fn store_newtype_pattern<'a>(
     env: &mut Env<'a, '_>,
@@ -7049,6 +7472,72 @@ fn store_record_destruct<'a>(
     StorePattern::Productive(stmt)
 }
 
+#[allow(clippy::too_many_arguments)]
+fn store_tuple_destruct<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    destruct: &TupleDestruct<'a>,
+    index: u64,
+    outer_symbol: Symbol,
+    sorted_elems: &'a [Layout<'a>],
+    mut stmt: Stmt<'a>,
+) -> StorePattern<'a> {
+    use Pattern::*;
+
+    let load = Expr::StructAtIndex {
+        index,
+        field_layouts: sorted_elems,
+        structure: outer_symbol,
+    };
+
+    match &destruct.pat {
+        Identifier(symbol) => {
+            let specialization_symbol = procs
+                .symbol_specializations
+                .remove_single(*symbol)
+                // Can happen when the symbol was never used under this body, and hence has no
+                // requested specialization.
+                .unwrap_or(*symbol);
+
+            stmt = Stmt::Let(
+                specialization_symbol,
+                load,
+                destruct.layout,
+                env.arena.alloc(stmt),
+            );
+        }
+        Underscore => {
+            // important that this is special-cased to do nothing: mono tuple patterns will extract
+            // all the elements, but those not bound in the source code are guarded with the
+            // underscore pattern, and must not be stored/loaded.
+            return StorePattern::NotProductive(stmt);
+        }
+        IntLiteral(_, _)
+        | FloatLiteral(_, _)
+        | DecimalLiteral(_)
+        | EnumLiteral { .. }
+        | BitLiteral { .. }
+        | StrLiteral(_) => {
+            return StorePattern::NotProductive(stmt);
+        }
+
+        _ => {
+            let symbol = env.unique_symbol();
+
+            match store_pattern_help(env, procs, layout_cache, &destruct.pat, symbol, stmt) {
+                StorePattern::Productive(new) => {
+                    stmt = new;
+                    stmt = Stmt::Let(symbol, load, destruct.layout, env.arena.alloc(stmt));
+                }
+                StorePattern::NotProductive(stmt) => return StorePattern::NotProductive(stmt),
+            }
+        }
+    }
+
+    StorePattern::Productive(stmt)
+}
+
 /// We want to re-use symbols that are not function symbols
 /// for any other expression, we create a new symbol, and will
 /// later make sure it gets assigned the correct value.
@@ -7250,6 +7739,209 @@ fn let_empty_struct<'a>(assigned: Symbol, hole: &'a Stmt<'a>) -> Stmt<'a> {
     Stmt::Let(assigned, Expr::Struct(&[]), Layout::UNIT, hole)
 }
 
+/// How a field of a record or an element of a tuple gets its symbol
+enum StructField {
+    /// A function or a polymorphic expression, that must be specialized to the field's type
+    Unspecialized(Symbol, Variable),
+    /// A value that is already bound to a symbol
+    ValueSymbol,
+    /// Any other expression, which is evaluated into a fresh symbol
+    Expr(Variable, Loc<roc_can::expr::Expr>),
+}
+
+/// Build a record or tuple from its fields, given in layout order as
+/// `(type of the field in the struct, type of the expression, expression)`.
+/// A struct with a single field is represented by that field.
+#[allow(clippy::too_many_arguments)]
+fn struct_with_hole<'a>(
+    env: &mut Env<'a, '_>,
+    struct_var: Variable,
+    fields: std::vec::Vec<(Variable, Variable, Loc<roc_can::expr::Expr>)>,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    assigned: Symbol,
+    hole: &'a Stmt<'a>,
+    layout_error: &'static str,
+) -> Stmt<'a> {
+    let mut field_symbols = Vec::with_capacity_in(fields.len(), env.arena);
+    let mut can_fields = Vec::with_capacity_in(fields.len(), env.arena);
+
+    for (variable, var, loc_expr) in fields {
+        // TODO how should function pointers be handled here?
+        use ReuseSymbol::*;
+        match can_reuse_symbol(env, procs, &loc_expr.value, var) {
+            Imported(symbol) | LocalFunction(symbol) | UnspecializedExpr(symbol) => {
+                field_symbols.push(symbol);
+                can_fields.push(StructField::Unspecialized(symbol, variable));
+            }
+            Value(symbol) => {
+                let reusable =
+                    procs
+                        .symbol_specializations
+                        .get_or_insert(env, layout_cache, symbol, var);
+                field_symbols.push(reusable);
+                can_fields.push(StructField::ValueSymbol);
+            }
+            NotASymbol => {
+                field_symbols.push(env.unique_symbol());
+                can_fields.push(StructField::Expr(var, loc_expr));
+            }
+        }
+    }
+
+    // creating a struct from the var will unpack it if it's just a single field.
+    let layout = match layout_cache.from_var(env.arena, struct_var, env.subs) {
+        Ok(layout) => layout,
+        Err(_) => return Stmt::RuntimeError(layout_error),
+    };
+
+    let field_symbols = field_symbols.into_bump_slice();
+
+    let mut stmt = if let [only_field] = field_symbols {
+        let mut hole = hole.clone();
+        substitute_in_exprs(env.arena, &mut hole, assigned, *only_field);
+        hole
+    } else {
+        Stmt::Let(assigned, Expr::Struct(field_symbols), layout, hole)
+    };
+
+    for (opt_field, symbol) in can_fields.into_iter().rev().zip(field_symbols.iter().rev()) {
+        match opt_field {
+            StructField::ValueSymbol => {
+                // this symbol is already defined; nothing to do
+            }
+            StructField::Unspecialized(symbol, variable) => {
+                stmt = specialize_symbol(
+                    env,
+                    procs,
+                    layout_cache,
+                    Some(variable),
+                    symbol,
+                    stmt,
+                    symbol,
+                );
+            }
+            StructField::Expr(var, loc_expr) => {
+                stmt = with_hole(
+                    env,
+                    loc_expr.value,
+                    var,
+                    procs,
+                    layout_cache,
+                    *symbol,
+                    env.arena.alloc(stmt),
+                );
+            }
+        }
+    }
+
+    stmt
+}
+
+/// Look up the field at `index` (in layout order) of a record or tuple.
+/// A struct with a single field is represented by that field.
+#[allow(clippy::too_many_arguments)]
+fn struct_access_with_hole<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    struct_var: Variable,
+    field_var: Variable,
+    field_layouts: &'a [Layout<'a>],
+    index: usize,
+    loc_expr: Loc<roc_can::expr::Expr>,
+    assigned: Symbol,
+    hole: &'a Stmt<'a>,
+) -> Stmt<'a> {
+    let struct_symbol =
+        possible_reuse_symbol_or_specialize(env, procs, layout_cache, &loc_expr.value, struct_var);
+
+    let stmt = match field_layouts {
+        [_] => {
+            let mut hole = hole.clone();
+            substitute_in_exprs(env.arena, &mut hole, assigned, struct_symbol);
+
+            hole
+        }
+        _ => {
+            let expr = Expr::StructAtIndex {
+                index: index as u64,
+                field_layouts,
+                structure: struct_symbol,
+            };
+
+            let layout = layout_cache
+                .from_var(env.arena, field_var, env.subs)
+                .unwrap_or_else(|err| panic!("TODO turn fn_var into a RuntimeError {:?}", err));
+
+            Stmt::Let(assigned, expr, layout, hole)
+        }
+    };
+
+    assign_to_symbol(
+        env,
+        procs,
+        layout_cache,
+        struct_var,
+        loc_expr,
+        struct_symbol,
+        stmt,
+    )
+}
+
+/// Lower a record field accessor like `.name`, or a tuple element accessor like `.0`,
+/// to a closure
+#[allow(clippy::too_many_arguments)]
+fn accessor_with_hole<'a>(
+    env: &mut Env<'a, '_>,
+    procs: &mut Procs<'a>,
+    layout_cache: &mut LayoutCache<'a>,
+    closure_data: ClosureData,
+    result_var: Variable,
+    assigned: Symbol,
+    hole: &'a Stmt<'a>,
+    context_msg: &'static str,
+) -> Stmt<'a> {
+    let ClosureData {
+        name,
+        function_type,
+        arguments,
+        loc_body,
+        ..
+    } = closure_data;
+
+    match procs.insert_anonymous(
+        env,
+        LambdaName::no_niche(name),
+        function_type,
+        arguments,
+        *loc_body,
+        CapturedSymbols::None,
+        result_var,
+        layout_cache,
+    ) {
+        Ok(_) => {
+            let raw_layout = return_on_layout_error!(
+                env,
+                layout_cache.raw_from_var(env.arena, function_type, env.subs),
+                context_msg
+            );
+
+            match raw_layout {
+                RawFunctionLayout::Function(_, lambda_set, _) => {
+                    let lambda_name = find_lambda_name(env, layout_cache, lambda_set, name, &[]);
+                    construct_closure_data(env, lambda_set, lambda_name, &[], assigned, hole)
+                }
+                RawFunctionLayout::ZeroArgumentThunk(_) => unreachable!(),
+            }
+        }
+
+        Err(_error) => {
+            Stmt::RuntimeError("TODO convert anonymous function error to a RuntimeError string")
+        }
+    }
+}
+
 /// If the symbol is a function or polymorphic value, make sure it is properly specialized
 fn specialize_symbol<'a>(
     env: &mut Env<'a, '_>,
@@ -7464,6 +8156,8 @@ fn assign_to_symbol<'a>(
     use ReuseSymbol::*;
     match can_reuse_symbol(env, procs, &loc_arg.value, arg_var) {
         Imported(original) | LocalFunction(original) | UnspecializedExpr(original) => {
+            env.symbol_regions.push((symbol, loc_arg.region));
+
             // for functions we must make sure they are specialized correctly
             specialize_symbol(
                 env,
@@ -7476,15 +8170,19 @@ fn assign_to_symbol<'a>(
             )
         }
         Value(_symbol) => result,
-        NotASymbol => with_hole(
-            env,
-            loc_arg.value,
-            arg_var,
-            procs,
-            layout_cache,
-            symbol,
-            env.arena.alloc(result),
-        ),
+        NotASymbol => {
+            env.symbol_regions.push((symbol, loc_arg.region));
+
+            with_hole(
+                env,
+                loc_arg.value,
+                arg_var,
+                procs,
+                layout_cache,
+                symbol,
+                env.arena.alloc(result),
+            )
+        }
     }
 }
 
@@ -8257,6 +8955,7 @@ pub enum Pattern<'a> {
     StrLiteral(Box<str>),
 
     RecordDestructure(Vec<'a, RecordDestruct<'a>>, &'a [Layout<'a>]),
+    TupleDestructure(Vec<'a, TupleDestruct<'a>>, &'a [Layout<'a>]),
     NewtypeDestructure {
         tag_name: TagName,
         arguments: Vec<'a, (Pattern<'a>, Layout<'a>)>,
@@ -8272,6 +8971,91 @@ pub enum Pattern<'a> {
         opaque: Symbol,
         argument: Box<(Pattern<'a>, Layout<'a>)>,
     },
+    List {
+        arity: ListArity,
+        list_layout: Layout<'a>,
+        element_layout: Layout<'a>,
+        elements: Vec<'a, Pattern<'a>>,
+        /// The index of the rest pattern among the elements, and the symbol it binds, if any.
+        opt_rest: Option<(usize, Option<Symbol>)>,
+    },
+}
+
+/// An index into a list, as read by a list pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ListIndex(
+    /// Positive if we should index from the head, negative if we should index from the tail.
+    /// 0 is `lst[0]`
+    /// -1 is `lst[List.len lst - 1]`
+    i64,
+);
+
+impl ListIndex {
+    pub(crate) fn from_pattern_index(index: usize, arity: ListArity) -> Self {
+        match arity {
+            ListArity::Exact(_) => Self(index as _),
+            ListArity::Slice(head, tail) => {
+                if index < head {
+                    Self(index as _)
+                } else {
+                    // Elements after the rest pattern are indexed from the end of the list,
+                    // e.g. in `[_, .., x, y]` the `x` is at index -2.
+                    Self(-((head + tail - index) as i64))
+                }
+            }
+        }
+    }
+}
+
+pub(crate) type Store<'a> = (Symbol, Layout<'a>, Expr<'a>);
+
+/// Builds the [Store]s needed to compute the index of a list element read by a list pattern.
+/// The last store defines the index symbol.
+pub(crate) fn build_list_index_probe<'a>(
+    env: &mut Env<'a, '_>,
+    list_sym: Symbol,
+    list_index: &ListIndex,
+) -> (Symbol, std::vec::Vec<Store<'a>>) {
+    let usize_layout = Layout::usize(env.target_info);
+
+    let list_index = list_index.0;
+    let index_sym = env.unique_symbol();
+
+    if list_index >= 0 {
+        let index_expr = Expr::Literal(Literal::Int((list_index as i128).to_ne_bytes()));
+
+        (index_sym, vec![(index_sym, usize_layout, index_expr)])
+    } else {
+        // Index from the end of the list: `List.len lst - offset`
+        let len_sym = env.unique_symbol();
+        let len_expr = Expr::Call(Call {
+            call_type: CallType::LowLevel {
+                op: LowLevel::ListLen,
+                update_mode: env.next_update_mode_id(),
+            },
+            arguments: env.arena.alloc([list_sym]),
+        });
+
+        let offset = list_index.unsigned_abs();
+        let offset_sym = env.unique_symbol();
+        let offset_expr = Expr::Literal(Literal::Int((offset as i128).to_ne_bytes()));
+
+        let index_expr = Expr::Call(Call {
+            call_type: CallType::LowLevel {
+                op: LowLevel::NumSubWrap,
+                update_mode: env.next_update_mode_id(),
+            },
+            arguments: env.arena.alloc([len_sym, offset_sym]),
+        });
+
+        let stores = vec![
+            (len_sym, usize_layout, len_expr),
+            (offset_sym, usize_layout, offset_expr),
+            (index_sym, usize_layout, index_expr),
+        ];
+
+        (index_sym, stores)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -8282,6 +9066,14 @@ pub struct RecordDestruct<'a> {
     pub typ: DestructType<'a>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct TupleDestruct<'a> {
+    pub index: usize,
+    pub variable: Variable,
+    pub layout: Layout<'a>,
+    pub pat: Pattern<'a>,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum DestructType<'a> {
     Required(Symbol),
@@ -8949,6 +9741,96 @@ fn from_can_pattern_help<'a>(
                 field_layouts.into_bump_slice(),
             ))
         }
+
+        TupleDestructure {
+            whole_var,
+            destructs,
+            ..
+        } => {
+            // sorted elements based on the type
+            let sorted_elems =
+                crate::layout::sort_tuple_elems(env.arena, *whole_var, env.subs, env.target_info)
+                    .map_err(RuntimeError::from)?;
+
+            // sorted elements based on the destruct
+            let mut mono_destructs = Vec::with_capacity_in(destructs.len(), env.arena);
+            let mut destructs_by_index = Vec::with_capacity_in(destructs.len(), env.arena);
+            destructs_by_index.extend(destructs.iter().map(Some));
+
+            let mut elem_layouts = Vec::with_capacity_in(sorted_elems.len(), env.arena);
+
+            for (index, variable, elem_layout) in sorted_elems.into_iter() {
+                match destructs_by_index.get_mut(index).and_then(Option::take) {
+                    Some(destruct) => {
+                        // this element is destructured by the pattern
+                        mono_destructs.push(TupleDestruct {
+                            index,
+                            variable,
+                            layout: elem_layout,
+                            pat: from_can_pattern_help(
+                                env,
+                                procs,
+                                layout_cache,
+                                &destruct.value.typ.1.value,
+                                assignments,
+                            )?,
+                        });
+                    }
+                    None => {
+                        // this element is not destructured by the pattern
+                        // put in an underscore
+                        mono_destructs.push(TupleDestruct {
+                            index,
+                            variable,
+                            layout: elem_layout,
+                            pat: Pattern::Underscore,
+                        });
+                    }
+                }
+
+                // the layout of this element is part of the layout of the tuple
+                elem_layouts.push(elem_layout);
+            }
+
+            Ok(Pattern::TupleDestructure(
+                mono_destructs,
+                elem_layouts.into_bump_slice(),
+            ))
+        }
+
+        List {
+            list_var,
+            elem_var,
+            patterns,
+        } => {
+            let list_layout = layout_cache
+                .from_var(env.arena, *list_var, env.subs)
+                .map_err(RuntimeError::from)?;
+
+            let element_layout = layout_cache
+                .from_var(env.arena, *elem_var, env.subs)
+                .map_err(RuntimeError::from)?;
+
+            let mut mono_patterns = Vec::with_capacity_in(patterns.patterns.len(), env.arena);
+            for loc_pat in patterns.patterns.iter() {
+                let mono_pat =
+                    from_can_pattern_help(env, procs, layout_cache, &loc_pat.value, assignments)?;
+                mono_patterns.push(mono_pat);
+            }
+
+            let opt_rest = patterns
+                .opt_rest
+                .as_ref()
+                .map(|(index, opt_name)| (*index, opt_name.as_ref().map(|name| name.value)));
+
+            Ok(Pattern::List {
+                arity: patterns.arity(),
+                list_layout,
+                element_layout,
+                elements: mono_patterns,
+                opt_rest,
+            })
+        }
     }
 }
 
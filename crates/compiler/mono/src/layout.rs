@@ -10,10 +10,13 @@ use roc_problem::can::RuntimeError;
 use roc_target::{PtrWidth, TargetInfo};
 use roc_types::num::NumericRange;
 use roc_types::subs::{
-    self, Content, FlatType, Label, OptVariable, RecordFields, Subs, UnionTags,
+    self, Content, FlatType, Label, OptVariable, RecordFields, Subs, TupleElems, UnionTags,
     UnsortedUnionLabels, Variable,
 };
-use roc_types::types::{gather_fields_unsorted_iter, RecordField, RecordFieldsError};
+use roc_types::types::{
+    gather_fields_unsorted_iter, gather_tuple_elems_unsorted_iter, RecordField, RecordFieldsError,
+    TupleElemsError,
+};
 use std::cmp::Ordering;
 use std::collections::hash_map::{DefaultHasher, Entry};
 use std::collections::HashMap;
@@ -248,6 +251,17 @@ impl FieldOrderHash {
         fields.iter().for_each(|field| field.hash(&mut hasher));
         Self(hasher.finish())
     }
+
+    pub fn from_ordered_tuple_elems(elems: &[usize]) -> Self {
+        if elems.is_empty() {
+            // HACK: we must make sure this is always equivalent to a `ZERO_FIELD_HASH`.
+            return Self::ZERO_FIELD_HASH;
+        }
+
+        let mut hasher = DefaultHasher::new();
+        elems.iter().for_each(|elem| elem.hash(&mut hasher));
+        Self(hasher.finish())
+    }
 }
 
 /// Types for code gen must be monomorphic. No type variables allowed!
@@ -741,6 +755,17 @@ impl CapturesNiche<'_> {
     }
 }
 
+impl<'a> CapturesNiche<'a> {
+    /// For the textual mono IR
+    pub(crate) fn from_layouts(layouts: &'a [Layout<'a>]) -> Self {
+        Self(layouts)
+    }
+
+    pub(crate) fn layouts(&self) -> &'a [Layout<'a>] {
+        self.0
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
 pub struct LambdaName<'a> {
     name: Symbol,
@@ -771,6 +796,14 @@ impl<'a> LambdaName<'a> {
         }
     }
 
+    /// For the textual mono IR
+    pub(crate) fn from_parts(name: Symbol, captures_niche: CapturesNiche<'a>) -> Self {
+        Self {
+            name,
+            captures_niche,
+        }
+    }
+
     #[inline(always)]
     pub fn replace_name(&self, name: Symbol) -> Self {
         Self {
@@ -836,6 +869,17 @@ pub enum ClosureCallOptions<'a> {
 }
 
 impl<'a> LambdaSet<'a> {
+    /// For the textual mono IR
+    pub(crate) fn from_parts(
+        set: &'a [(Symbol, &'a [Layout<'a>])],
+        representation: &'a Layout<'a>,
+    ) -> Self {
+        Self {
+            set,
+            representation,
+        }
+    }
+
     pub fn runtime_representation(&self) -> Layout<'a> {
         *self.representation
     }
@@ -2343,6 +2387,41 @@ fn layout_from_flat_type<'a>(
                 })
             }
         }
+        Tuple(elems, ext_var) => {
+            // extract any values from the ext_var
+
+            let mut sortables = Vec::with_capacity_in(elems.len(), arena);
+            let it = match elems.unsorted_iterator(subs, ext_var) {
+                Ok(it) => it,
+                Err(TupleElemsError) => return Err(LayoutProblem::Erroneous),
+            };
+
+            for (index, elem_var) in it {
+                sortables.push((index, Layout::from_var(env, elem_var)?));
+            }
+
+            sortables.sort_by(|(index1, layout1), (index2, layout2)| {
+                cmp_fields(index1, layout1, index2, layout2, target_info)
+            });
+
+            let ordered_elem_indices =
+                Vec::from_iter_in(sortables.iter().map(|(index, _)| *index), arena);
+            let field_order_hash =
+                FieldOrderHash::from_ordered_tuple_elems(ordered_elem_indices.as_slice());
+
+            if sortables.len() == 1 {
+                // If the tuple has only one element that isn't zero-sized,
+                // unwrap it.
+                Ok(sortables.pop().unwrap().1)
+            } else {
+                let layouts = Vec::from_iter_in(sortables.into_iter().map(|t| t.1), arena);
+
+                Ok(Layout::Struct {
+                    field_order_hash,
+                    field_layouts: layouts.into_bump_slice(),
+                })
+            }
+        }
         TagUnion(tags, ext_var) => {
             let (tags, ext_var) = tags.unsorted_tags_and_ext(subs, ext_var);
 
@@ -2371,6 +2450,7 @@ fn layout_from_flat_type<'a>(
         EmptyTagUnion => Ok(Layout::VOID),
         Erroneous(_) => Err(LayoutProblem::Erroneous),
         EmptyRecord => Ok(Layout::UNIT),
+        EmptyTuple => Ok(Layout::UNIT),
     }
 }
 
@@ -2436,6 +2516,49 @@ fn sort_record_fields_help<'a>(
     Ok(sorted_fields)
 }
 
+pub type SortedTupleElem<'a> = (usize, Variable, Layout<'a>);
+
+pub fn sort_tuple_elems<'a>(
+    arena: &'a Bump,
+    var: Variable,
+    subs: &Subs,
+    target_info: TargetInfo,
+) -> Result<Vec<'a, SortedTupleElem<'a>>, LayoutProblem> {
+    let mut env = Env {
+        arena,
+        subs,
+        seen: Vec::new_in(arena),
+        target_info,
+    };
+
+    let (it, _) = match gather_tuple_elems_unsorted_iter(subs, TupleElems::empty(), var) {
+        Ok(it) => it,
+        Err(_) => return Err(LayoutProblem::Erroneous),
+    };
+
+    sort_tuple_elems_help(&mut env, it)
+}
+
+fn sort_tuple_elems_help<'a>(
+    env: &mut Env<'a, '_>,
+    elems_map: impl Iterator<Item = (usize, Variable)>,
+) -> Result<Vec<'a, SortedTupleElem<'a>>, LayoutProblem> {
+    let target_info = env.target_info;
+
+    let mut sorted_elems = Vec::with_capacity_in(elems_map.size_hint().0, env.arena);
+
+    for (index, elem_var) in elems_map {
+        let layout = Layout::from_var(env, elem_var)?;
+        sorted_elems.push((index, elem_var, layout));
+    }
+
+    sorted_elems.sort_by(|(index1, _, layout1), (index2, _, layout2)| {
+        cmp_fields(index1, layout1, index2, layout2, target_info)
+    });
+
+    Ok(sorted_elems)
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum TagOrClosure {
     Tag(TagName),
@@ -3246,6 +3369,23 @@ pub fn ext_var_is_empty_record(_subs: &Subs, _ext_var: Variable) -> bool {
     unreachable!();
 }
 
+#[cfg(debug_assertions)]
+pub fn ext_var_is_empty_tuple(subs: &Subs, ext_var: Variable) -> bool {
+    // the ext_var is empty
+    let elems = match roc_types::types::gather_tuple_elems(subs, TupleElems::empty(), ext_var) {
+        Ok(elems) => elems,
+        Err(_) => return false,
+    };
+
+    elems.elems.is_empty()
+}
+
+#[cfg(not(debug_assertions))]
+pub fn ext_var_is_empty_tuple(_subs: &Subs, _ext_var: Variable) -> bool {
+    // This should only ever be used in debug_assert! macros
+    unreachable!();
+}
+
 #[cfg(debug_assertions)]
 pub fn ext_var_is_empty_tag_union(subs: &Subs, ext_var: Variable) -> bool {
     // the ext_var is empty
@@ -3363,6 +3503,14 @@ impl LayoutId {
         let ident_string = symbol.as_str(interns);
         format!("roc__{}_{}_exposed", ident_string, self.0)
     }
+
+    // Returns something like "UserApp.foo#1" when given a symbol that interns to "foo"
+    // and a LayoutId of 1. This is the name that debuggers and profilers show.
+    pub fn to_demangled_string(self, symbol: Symbol, interns: &Interns) -> String {
+        let ident_string = symbol.as_str(interns);
+        let module_string = interns.module_ids.get_name(symbol.module_id()).unwrap();
+        format!("{}.{}#{}", module_string, ident_string, self.0)
+    }
 }
 
 struct IdsByLayout<'a> {
@@ -4,12 +4,15 @@
 
 pub mod borrow;
 pub mod code_gen_help;
+pub mod debug;
 pub mod inc_dec;
+pub mod inline;
 pub mod ir;
 pub mod layout;
 pub mod layout_soa;
 pub mod low_level;
 pub mod reset_reuse;
+pub mod simplify;
 pub mod tail_recursion;
 
 // Temporary, while we can build up test cases and optimize the exhaustiveness checking.
@@ -216,7 +216,30 @@ fn function_s<'a, 'i>(
             }
         }
 
-        Ret(_) | Jump(_, _) | RuntimeError(_) => stmt,
+        Dbg {
+            symbol,
+            region,
+            layout,
+            remainder,
+        } => {
+            let continuation: &Stmt = *remainder;
+            let new_continuation = function_s(env, w, c, continuation);
+
+            if std::ptr::eq(continuation, new_continuation) || continuation == new_continuation {
+                stmt
+            } else {
+                let new_dbg = Dbg {
+                    symbol: *symbol,
+                    region: *region,
+                    layout: *layout,
+                    remainder: new_continuation,
+                };
+
+                arena.alloc(new_dbg)
+            }
+        }
+
+        Ret(_) | Jump(_, _) | Crash(_) | RuntimeError(_) => stmt,
     }
 }
 
@@ -277,6 +300,7 @@ fn insert_reset<'a>(
             | EmptyArray
             | Reuse { .. }
             | Reset { .. }
+            | SetUnionAtIndex { .. }
             | RuntimeErrorFunction(_) => break,
         }
     }
@@ -334,6 +358,10 @@ fn function_d_main<'a, 'i>(
                     // It may work only if the new cell is consumed, but we ignore this case.
                     (stmt, true)
                 }
+                Expr::SetUnionAtIndex { value, .. } if *value == x => {
+                    // likewise when `x` is stored in an existing cell
+                    (stmt, true)
+                }
                 _ => {
                     let (b, found) = function_d_main(env, x, c, continuation);
 
@@ -446,6 +474,30 @@ fn function_d_main<'a, 'i>(
                 (arena.alloc(refcounting), found)
             }
         }
+
+        Dbg {
+            symbol,
+            region,
+            layout,
+            remainder,
+        } => {
+            let (b, found) = function_d_main(env, x, c, remainder);
+
+            let b = if found || *symbol != x {
+                b
+            } else {
+                try_function_s(env, x, c, b)
+            };
+
+            let dbg = Dbg {
+                symbol: *symbol,
+                region: *region,
+                layout: *layout,
+                remainder: b,
+            };
+
+            (arena.alloc(dbg), found)
+        }
         Join {
             id,
             parameters,
@@ -477,7 +529,9 @@ fn function_d_main<'a, 'i>(
 
             (arena.alloc(new_join), found)
         }
-        Ret(_) | Jump(_, _) | RuntimeError(_) => (stmt, has_live_var(&env.jp_live_vars, stmt, x)),
+        Ret(_) | Jump(_, _) | Crash(_) | RuntimeError(_) => {
+            (stmt, has_live_var(&env.jp_live_vars, stmt, x))
+        }
     }
 }
 
@@ -618,7 +672,25 @@ fn function_r<'a, 'i>(env: &mut Env<'a, 'i>, stmt: &'a Stmt<'a>) -> &'a Stmt<'a>
             arena.alloc(expect)
         }
 
-        Ret(_) | Jump(_, _) | RuntimeError(_) => {
+        Dbg {
+            symbol,
+            region,
+            layout,
+            remainder,
+        } => {
+            let b = function_r(env, remainder);
+
+            let dbg = Dbg {
+                symbol: *symbol,
+                region: *region,
+                layout: *layout,
+                remainder: b,
+            };
+
+            arena.alloc(dbg)
+        }
+
+        Ret(_) | Jump(_, _) | Crash(_) | RuntimeError(_) => {
             // terminals
             stmt
         }
@@ -653,6 +725,9 @@ fn has_live_var<'a>(jp_live_vars: &JPLiveVarMap, stmt: &'a Stmt<'a>, needle: Sym
             remainder,
             ..
         } => *condition == needle || has_live_var(jp_live_vars, remainder, needle),
+        Dbg {
+            symbol, remainder, ..
+        } => *symbol == needle || has_live_var(jp_live_vars, remainder, needle),
         Join {
             id,
             parameters,
@@ -678,6 +753,7 @@ fn has_live_var<'a>(jp_live_vars: &JPLiveVarMap, stmt: &'a Stmt<'a>, needle: Sym
         Jump(id, arguments) => {
             arguments.iter().any(|s| *s == needle) || jp_live_vars[id].contains(&needle)
         }
+        Crash(symbol) => *symbol == needle,
         RuntimeError(_) => false,
     }
 }
@@ -704,6 +780,9 @@ fn has_live_var_expr<'a>(expr: &'a Expr<'a>, needle: Symbol) -> bool {
         Expr::StructAtIndex { structure, .. }
         | Expr::GetTagId { structure, .. }
         | Expr::UnionAtIndex { structure, .. } => *structure == needle,
+        Expr::SetUnionAtIndex {
+            structure, value, ..
+        } => needle == *structure || needle == *value,
         Expr::EmptyArray => false,
         Expr::Reuse {
             symbol, arguments, ..
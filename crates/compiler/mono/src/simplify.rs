@@ -0,0 +1,626 @@
+//! Constant folding and dead code elimination. LLVM does both on its own, but the dev and
+//! wasm backends generate code for the mono IR as it is. This pass
+//!
+//! - folds `LowLevel` numeric and boolean operations whose arguments are literals
+//! - resolves a `Switch` on a known value, including the tag id of a tag built in the same proc
+//! - removes `let`s of pure expressions whose symbol is never used
+//! - removes join points that are never jumped to
+//!
+//! Symbols are unique within a proc, so what is known about a symbol holds wherever it is in
+//! scope. The folding goes forward through a proc, and the dead code elimination backward.
+
+use crate::ir::{
+    BranchInfo, CallType, Expr, JoinPointId, ListLiteralElement, Literal, ModifyRc, Proc,
+    ProcLayout, Stmt,
+};
+use crate::layout::{Builtin, Layout, TagIdIntType};
+use bumpalo::collections::Vec;
+use bumpalo::Bump;
+use roc_builtins::bitcode::{FloatWidth, IntWidth};
+use roc_collections::all::{MutMap, MutSet};
+use roc_module::low_level::LowLevel;
+use roc_module::symbol::Symbol;
+use std::cmp::Ordering;
+
+pub fn simplify_procs<'a>(arena: &'a Bump, procs: &mut MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>) {
+    for proc in procs.values_mut() {
+        let mut env = Env {
+            arena,
+            known: MutMap::default(),
+        };
+        let folded = env.fold_stmt(&proc.body);
+
+        let mut used = Used::default();
+        proc.body = remove_dead_code(arena, &folded, &mut used);
+    }
+}
+
+/// A value that is known at compile time
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Known<'a> {
+    Literal(Literal<'a>),
+    /// A tag built in this proc. Only its tag id is used.
+    Tag(TagIdIntType),
+}
+
+struct Env<'a> {
+    arena: &'a Bump,
+    known: MutMap<Symbol, Known<'a>>,
+}
+
+impl<'a> Env<'a> {
+    fn fold_stmt(&mut self, stmt: &Stmt<'a>) -> Stmt<'a> {
+        use Stmt::*;
+
+        match stmt {
+            Let(symbol, expr, layout, cont) => {
+                let expr = match self.fold_expr(expr, *layout) {
+                    Some(literal) => Expr::Literal(literal),
+                    None => expr.clone(),
+                };
+
+                match &expr {
+                    Expr::Literal(literal) => {
+                        self.known.insert(*symbol, Known::Literal(*literal));
+                    }
+                    Expr::Tag { tag_id, .. } => {
+                        self.known.insert(*symbol, Known::Tag(*tag_id));
+                    }
+                    _ => {}
+                }
+
+                let cont = self.fold_stmt(cont);
+
+                Let(*symbol, expr, *layout, self.arena.alloc(cont))
+            }
+            Switch {
+                cond_symbol,
+                cond_layout,
+                branches,
+                default_branch,
+                ret_layout,
+            } => {
+                if let Some(value) = self.switch_value(*cond_symbol) {
+                    let taken = branches
+                        .iter()
+                        .find(|(label, _, _)| *label == value)
+                        .map(|(_, _, branch)| branch)
+                        .unwrap_or(default_branch.1);
+
+                    return self.fold_stmt(taken);
+                }
+
+                let mut new_branches = Vec::with_capacity_in(branches.len(), self.arena);
+                for (label, info, branch) in branches.iter() {
+                    new_branches.push((*label, info.clone(), self.fold_stmt(branch)));
+                }
+                let default_stmt = self.fold_stmt(default_branch.1);
+
+                Switch {
+                    cond_symbol: *cond_symbol,
+                    cond_layout: *cond_layout,
+                    branches: new_branches.into_bump_slice(),
+                    default_branch: (default_branch.0.clone(), self.arena.alloc(default_stmt)),
+                    ret_layout: *ret_layout,
+                }
+            }
+            Join {
+                id,
+                parameters,
+                body,
+                remainder,
+            } => {
+                let remainder = self.fold_stmt(remainder);
+
+                // A parameter is known if every jump passes the same known value. That only
+                // holds if the body does not jump back to the join point with other values.
+                if !jumps_to(body, *id) {
+                    let mut jumps = std::vec::Vec::new();
+                    jump_arguments(&remainder, *id, &mut jumps);
+
+                    for (index, param) in parameters.iter().enumerate() {
+                        if let Some(known) = self.known_for_all(&jumps, index) {
+                            self.known.insert(param.symbol, known);
+                        }
+                    }
+                }
+
+                let body = self.fold_stmt(body);
+
+                Join {
+                    id: *id,
+                    parameters,
+                    body: self.arena.alloc(body),
+                    remainder: self.arena.alloc(remainder),
+                }
+            }
+            Refcounting(modify, cont) => {
+                let cont = self.fold_stmt(cont);
+
+                Refcounting(*modify, self.arena.alloc(cont))
+            }
+            Expect {
+                condition,
+                region,
+                lookups,
+                layouts,
+                remainder,
+            } => {
+                let remainder = self.fold_stmt(remainder);
+
+                Expect {
+                    condition: *condition,
+                    region: *region,
+                    lookups,
+                    layouts,
+                    remainder: self.arena.alloc(remainder),
+                }
+            }
+            Dbg {
+                symbol,
+                region,
+                layout,
+                remainder,
+            } => {
+                let remainder = self.fold_stmt(remainder);
+
+                Dbg {
+                    symbol: *symbol,
+                    region: *region,
+                    layout: *layout,
+                    remainder: self.arena.alloc(remainder),
+                }
+            }
+            Ret(_) | Jump(_, _) | Crash(_) | RuntimeError(_) => stmt.clone(),
+        }
+    }
+
+    fn fold_expr(&self, expr: &Expr<'a>, layout: Layout<'a>) -> Option<Literal<'a>> {
+        match expr {
+            Expr::Call(crate::ir::Call {
+                call_type: CallType::LowLevel { op, .. },
+                arguments,
+            }) => {
+                let mut literals = std::vec::Vec::with_capacity(arguments.len());
+                for argument in arguments.iter() {
+                    match self.known.get(argument) {
+                        Some(Known::Literal(literal)) => literals.push(*literal),
+                        _ => return None,
+                    }
+                }
+
+                fold_lowlevel(*op, &literals, layout)
+            }
+            Expr::GetTagId { structure, .. } => match self.known.get(structure) {
+                Some(Known::Tag(tag_id)) => Some(Literal::Int((*tag_id as i128).to_ne_bytes())),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The value to compare with the labels of a `Switch`, computed like the decision tree does
+    fn switch_value(&self, cond_symbol: Symbol) -> Option<u64> {
+        match self.known.get(&cond_symbol)? {
+            Known::Literal(Literal::Int(bytes)) => Some(i128::from_ne_bytes(*bytes) as u64),
+            Known::Literal(Literal::Bool(b)) => Some(*b as u64),
+            Known::Literal(Literal::Byte(b)) => Some(*b as u64),
+            _ => None,
+        }
+    }
+
+    fn known_for_all(&self, jumps: &[&'a [Symbol]], index: usize) -> Option<Known<'a>> {
+        let (first, rest) = jumps.split_first()?;
+        let known = *self.known.get(&first[index])?;
+
+        if rest
+            .iter()
+            .all(|arguments| self.known.get(&arguments[index]) == Some(&known))
+        {
+            Some(known)
+        } else {
+            None
+        }
+    }
+}
+
+fn fold_lowlevel<'a>(
+    op: LowLevel,
+    args: &[Literal<'a>],
+    layout: Layout<'a>,
+) -> Option<Literal<'a>> {
+    use Literal::{Bool, Float, Int};
+    use LowLevel::*;
+
+    match (op, args) {
+        (Eq, [a, b]) if same_kind(a, b) => Some(Bool(a == b)),
+        (NotEq, [a, b]) if same_kind(a, b) => Some(Bool(a != b)),
+        (NumLt | NumLte | NumGt | NumGte, [a, b]) => {
+            let ordering = compare(a, b)?;
+            let result = match op {
+                NumLt => ordering.is_lt(),
+                NumLte => ordering.is_le(),
+                NumGt => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            };
+            Some(Bool(result))
+        }
+        (And, [Bool(a), Bool(b)]) => Some(Bool(*a && *b)),
+        (Or, [Bool(a), Bool(b)]) => Some(Bool(*a || *b)),
+        (Not, [Bool(a)]) => Some(Bool(!*a)),
+        (_, [Int(a), Int(b)]) => match layout {
+            Layout::Builtin(Builtin::Int(width)) => {
+                let a = i128::from_ne_bytes(*a);
+                let b = i128::from_ne_bytes(*b);
+                fold_int(op, a, b, width).map(|n| Int(n.to_ne_bytes()))
+            }
+            _ => None,
+        },
+        (_, [Float(a), Float(b)]) => match layout {
+            Layout::Builtin(Builtin::Float(width)) => fold_float(op, *a, *b, width).map(Float),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Only an overflow-free result is folded, so that `NumAdd` and friends still panic at runtime
+fn fold_int(op: LowLevel, a: i128, b: i128, width: IntWidth) -> Option<i128> {
+    use LowLevel::*;
+
+    // Literals that don't fit in an i128 are stored as `Literal::U128`
+    if width == IntWidth::U128 {
+        return None;
+    }
+
+    let result = match op {
+        NumAdd => a.checked_add(b)?,
+        NumSub => a.checked_sub(b)?,
+        NumMul => a.checked_mul(b)?,
+        NumAddWrap => return Some(wrap(a.wrapping_add(b), width)),
+        NumSubWrap => return Some(wrap(a.wrapping_sub(b), width)),
+        NumMulWrap => return Some(wrap(a.wrapping_mul(b), width)),
+        _ => return None,
+    };
+
+    let (min, max) = int_range(width);
+    if (min..=max).contains(&result) {
+        Some(result)
+    } else {
+        None
+    }
+}
+
+fn int_range(width: IntWidth) -> (i128, i128) {
+    let bits = width.stack_size() * 8;
+
+    match width {
+        IntWidth::I128 | IntWidth::U128 => (i128::MIN, i128::MAX),
+        _ if width.is_signed() => (-(1 << (bits - 1)), (1 << (bits - 1)) - 1),
+        _ => (0, (1 << bits) - 1),
+    }
+}
+
+fn wrap(n: i128, width: IntWidth) -> i128 {
+    let shift = 128 - width.stack_size() * 8;
+
+    if shift == 0 {
+        n
+    } else if width.is_signed() {
+        (n << shift) >> shift
+    } else {
+        n & ((1 << (128 - shift)) - 1)
+    }
+}
+
+fn fold_float(op: LowLevel, a: f64, b: f64, width: FloatWidth) -> Option<f64> {
+    use LowLevel::*;
+
+    let result = match op {
+        NumAdd => a + b,
+        NumSub => a - b,
+        NumMul => a * b,
+        NumDivUnchecked => a / b,
+        _ => return None,
+    };
+
+    match width {
+        // f64 has enough precision that rounding the f64 result gives the f32 result
+        FloatWidth::F32 => Some(result as f32 as f64),
+        FloatWidth::F64 => Some(result),
+        FloatWidth::F128 => None,
+    }
+}
+
+fn same_kind(a: &Literal, b: &Literal) -> bool {
+    std::mem::discriminant(a) == std::mem::discriminant(b)
+}
+
+fn compare(a: &Literal, b: &Literal) -> Option<Ordering> {
+    match (a, b) {
+        (Literal::Int(a), Literal::Int(b)) => {
+            Some(i128::from_ne_bytes(*a).cmp(&i128::from_ne_bytes(*b)))
+        }
+        (Literal::U128(a), Literal::U128(b)) => {
+            Some(u128::from_ne_bytes(*a).cmp(&u128::from_ne_bytes(*b)))
+        }
+        (Literal::Float(a), Literal::Float(b)) => a.partial_cmp(b),
+        _ => None,
+    }
+}
+
+fn jumps_to(stmt: &Stmt, id: JoinPointId) -> bool {
+    use Stmt::*;
+
+    match stmt {
+        Let(_, _, _, cont) | Refcounting(_, cont) => jumps_to(cont, id),
+        Switch {
+            branches,
+            default_branch,
+            ..
+        } => branches.iter().any(|(_, _, b)| jumps_to(b, id)) || jumps_to(default_branch.1, id),
+        Join {
+            body, remainder, ..
+        } => jumps_to(body, id) || jumps_to(remainder, id),
+        Expect { remainder, .. } | Dbg { remainder, .. } => jumps_to(remainder, id),
+        Jump(target, _) => *target == id,
+        Ret(_) | Crash(_) | RuntimeError(_) => false,
+    }
+}
+
+fn jump_arguments<'a>(stmt: &Stmt<'a>, id: JoinPointId, jumps: &mut std::vec::Vec<&'a [Symbol]>) {
+    use Stmt::*;
+
+    match stmt {
+        Let(_, _, _, cont) | Refcounting(_, cont) => jump_arguments(cont, id, jumps),
+        Switch {
+            branches,
+            default_branch,
+            ..
+        } => {
+            for (_, _, branch) in branches.iter() {
+                jump_arguments(branch, id, jumps);
+            }
+            jump_arguments(default_branch.1, id, jumps);
+        }
+        Join {
+            body, remainder, ..
+        } => {
+            jump_arguments(body, id, jumps);
+            jump_arguments(remainder, id, jumps);
+        }
+        Expect { remainder, .. } | Dbg { remainder, .. } => jump_arguments(remainder, id, jumps),
+        Jump(target, arguments) if *target == id => jumps.push(*arguments),
+        Jump(_, _) | Ret(_) | Crash(_) | RuntimeError(_) => {}
+    }
+}
+
+/// The symbols and join points used by the statements processed so far
+#[derive(Default)]
+struct Used {
+    symbols: MutSet<Symbol>,
+    join_points: MutSet<JoinPointId>,
+}
+
+impl Used {
+    fn extend(&mut self, symbols: &[Symbol]) {
+        self.symbols.extend(symbols.iter().copied());
+    }
+
+    fn add_branch_info(&mut self, info: &BranchInfo) {
+        if let BranchInfo::Constructor { scrutinee, .. } = info {
+            self.symbols.insert(*scrutinee);
+        }
+    }
+
+    fn add_expr(&mut self, expr: &Expr) {
+        use Expr::*;
+
+        match expr {
+            Literal(_) | EmptyArray | RuntimeErrorFunction(_) => {}
+            Call(call) => {
+                self.extend(call.arguments);
+                if let CallType::HigherOrder(higher_order) = &call.call_type {
+                    self.symbols
+                        .insert(higher_order.passed_function.captured_environment);
+                }
+            }
+            Tag { arguments, .. } | Struct(arguments) => self.extend(arguments),
+            StructAtIndex { structure, .. }
+            | GetTagId { structure, .. }
+            | UnionAtIndex { structure, .. } => {
+                self.symbols.insert(*structure);
+            }
+            SetUnionAtIndex {
+                structure, value, ..
+            } => {
+                self.symbols.insert(*structure);
+                self.symbols.insert(*value);
+            }
+            Array { elems, .. } => {
+                for elem in elems.iter() {
+                    if let ListLiteralElement::Symbol(symbol) = elem {
+                        self.symbols.insert(*symbol);
+                    }
+                }
+            }
+            ExprBox { symbol } | ExprUnbox { symbol } | Reset { symbol, .. } => {
+                self.symbols.insert(*symbol);
+            }
+            Reuse {
+                symbol, arguments, ..
+            } => {
+                self.symbols.insert(*symbol);
+                self.extend(arguments);
+            }
+        }
+    }
+}
+
+/// Goes backward, so a `let` is only kept if its symbol was used further down
+fn remove_dead_code<'a>(arena: &'a Bump, stmt: &Stmt<'a>, used: &mut Used) -> Stmt<'a> {
+    use Stmt::*;
+
+    match stmt {
+        Let(symbol, expr, layout, cont) => {
+            let cont = remove_dead_code(arena, cont, used);
+
+            if !used.symbols.contains(symbol) && is_pure(expr) {
+                return cont;
+            }
+
+            used.add_expr(expr);
+
+            Let(*symbol, expr.clone(), *layout, arena.alloc(cont))
+        }
+        Switch {
+            cond_symbol,
+            cond_layout,
+            branches,
+            default_branch,
+            ret_layout,
+        } => {
+            used.symbols.insert(*cond_symbol);
+
+            let mut new_branches = Vec::with_capacity_in(branches.len(), arena);
+            for (label, info, branch) in branches.iter() {
+                used.add_branch_info(info);
+                new_branches.push((*label, info.clone(), remove_dead_code(arena, branch, used)));
+            }
+
+            used.add_branch_info(&default_branch.0);
+            let default_stmt = remove_dead_code(arena, default_branch.1, used);
+
+            Switch {
+                cond_symbol: *cond_symbol,
+                cond_layout: *cond_layout,
+                branches: new_branches.into_bump_slice(),
+                default_branch: (default_branch.0.clone(), arena.alloc(default_stmt)),
+                ret_layout: *ret_layout,
+            }
+        }
+        Join {
+            id,
+            parameters,
+            body,
+            remainder,
+        } => {
+            let remainder = remove_dead_code(arena, remainder, used);
+
+            // The body can only be reached by a jump from the remainder
+            if !used.join_points.contains(id) {
+                return remainder;
+            }
+
+            let body = remove_dead_code(arena, body, used);
+
+            Join {
+                id: *id,
+                parameters,
+                body: arena.alloc(body),
+                remainder: arena.alloc(remainder),
+            }
+        }
+        Refcounting(modify, cont) => {
+            let symbol = match modify {
+                ModifyRc::Inc(symbol, _) | ModifyRc::Dec(symbol) | ModifyRc::DecRef(symbol) => {
+                    *symbol
+                }
+            };
+            used.symbols.insert(symbol);
+
+            let cont = remove_dead_code(arena, cont, used);
+
+            Refcounting(*modify, arena.alloc(cont))
+        }
+        Expect {
+            condition,
+            region,
+            lookups,
+            layouts,
+            remainder,
+        } => {
+            used.symbols.insert(*condition);
+            used.extend(lookups);
+
+            let remainder = remove_dead_code(arena, remainder, used);
+
+            Expect {
+                condition: *condition,
+                region: *region,
+                lookups,
+                layouts,
+                remainder: arena.alloc(remainder),
+            }
+        }
+        Dbg {
+            symbol,
+            region,
+            layout,
+            remainder,
+        } => {
+            used.symbols.insert(*symbol);
+
+            let remainder = remove_dead_code(arena, remainder, used);
+
+            Dbg {
+                symbol: *symbol,
+                region: *region,
+                layout: *layout,
+                remainder: arena.alloc(remainder),
+            }
+        }
+        Ret(symbol) | Crash(symbol) => {
+            used.symbols.insert(*symbol);
+            stmt.clone()
+        }
+        Jump(id, arguments) => {
+            used.join_points.insert(*id);
+            used.extend(arguments);
+            stmt.clone()
+        }
+        RuntimeError(_) => stmt.clone(),
+    }
+}
+
+/// Expressions that can be removed when their result is unused. Calls may not return, or
+/// panic, so only a few lowlevels qualify.
+fn is_pure(expr: &Expr) -> bool {
+    use Expr::*;
+
+    match expr {
+        Literal(_)
+        | Tag { .. }
+        | Struct(_)
+        | StructAtIndex { .. }
+        | GetTagId { .. }
+        | UnionAtIndex { .. }
+        | Array { .. }
+        | EmptyArray
+        | ExprBox { .. }
+        | ExprUnbox { .. } => true,
+        Call(crate::ir::Call {
+            call_type: CallType::LowLevel { op, .. },
+            ..
+        }) => matches!(
+            op,
+            LowLevel::Eq
+                | LowLevel::NotEq
+                | LowLevel::And
+                | LowLevel::Or
+                | LowLevel::Not
+                | LowLevel::NumLt
+                | LowLevel::NumLte
+                | LowLevel::NumGt
+                | LowLevel::NumGte
+                | LowLevel::NumAddWrap
+                | LowLevel::NumSubWrap
+                | LowLevel::NumMulWrap
+        ),
+        Call(_)
+        | Reuse { .. }
+        | Reset { .. }
+        | SetUnionAtIndex { .. }
+        | RuntimeErrorFunction(_) => false,
+    }
+}
@@ -1,7 +1,7 @@
 #![allow(clippy::manual_map)]
 
-use crate::ir::{CallType, Expr, JoinPointId, Param, Stmt};
-use crate::layout::{LambdaName, Layout};
+use crate::ir::{BranchInfo, Call, CallType, Env, Expr, JoinPointId, Param, Stmt};
+use crate::layout::{LambdaName, Layout, TagIdIntType, UnionLayout};
 use bumpalo::collections::Vec;
 use bumpalo::Bump;
 use roc_module::symbol::Symbol;
@@ -28,15 +28,64 @@ use roc_module::symbol::Symbol;
 ///
 /// This will effectively compile into a loop in llvm, and
 /// won't grow the call stack for each iteration
+///
+/// When the function returns a nullable recursive union, a self call whose result is
+/// immediately put into a constructor and returned is also turned into a jump (tail recursion
+/// modulo cons). The constructor is allocated before the call, with the null tag in the place
+/// of the recursive field, and the next iteration fills that hole instead of returning
+///
+/// > map f xs = when xs is Nil -> Nil; Cons x rest -> Cons (f x) (map f rest)
+///
+/// becomes
+///
+/// ```elm
+/// map f1 xs1 =
+///     let joinpoint j f xs first hole =
+///             when xs is
+///                 Nil ->
+///                     if hole is Nil then
+///                         Nil
+///                     else
+///                         hole.1 := Nil
+///                         first
+///                 Cons x rest ->
+///                     let cell = Cons (f x) Nil
+///                     if hole is Nil then
+///                         jump j f rest cell cell
+///                     else
+///                         hole.1 := cell
+///                         jump j f rest first cell
+///
+///     in
+///         jump j f1 xs1 Nil Nil
+/// ```
+///
+/// Here `first` is the cell that will be returned in the end, and `hole` is the last cell that
+/// was allocated, whose recursive field must still be written.
 pub fn make_tail_recursive<'a>(
-    arena: &'a Bump,
+    env: &mut Env<'a, '_>,
     id: JoinPointId,
-    needle: LambdaName,
+    needle: LambdaName<'a>,
     stmt: Stmt<'a>,
     args: &'a [(Layout<'a>, Symbol, Symbol)],
-    ret_layout: Layout,
+    ret_layout: Layout<'a>,
 ) -> Option<Stmt<'a>> {
-    let allocated = arena.alloc(stmt);
+    let arena = env.arena;
+    let allocated: &'a Stmt<'a> = arena.alloc(stmt);
+
+    if let Layout::Union(union_layout) = ret_layout {
+        let needle = Needle {
+            name: needle,
+            arguments: args,
+            result: ret_layout,
+        };
+
+        if let Some(stmt) =
+            make_tail_recursive_modulo_cons(env, id, needle, union_layout, allocated)
+        {
+            return Some(stmt);
+        }
+    }
 
     let new_stmt = insert_jumps(arena, allocated, id, needle, args, ret_layout)?;
 
@@ -273,8 +322,483 @@ fn insert_jumps<'a>(
             None => None,
         },
 
+        Dbg {
+            symbol,
+            region,
+            layout,
+            remainder,
+        } => match insert_jumps(
+            arena,
+            remainder,
+            goal_id,
+            needle,
+            needle_arguments,
+            needle_result,
+        ) {
+            Some(cont) => Some(arena.alloc(Dbg {
+                symbol: *symbol,
+                region: *region,
+                layout: *layout,
+                remainder: cont,
+            })),
+            None => None,
+        },
+
         Ret(_) => None,
         Jump(_, _) => None,
+        Crash(_) => None,
         RuntimeError(_) => None,
     }
 }
+
+#[derive(Clone, Copy)]
+struct Needle<'a> {
+    name: LambdaName<'a>,
+    arguments: &'a [(Layout<'a>, Symbol, Symbol)],
+    result: Layout<'a>,
+}
+
+impl<'a> Needle<'a> {
+    /// The arguments of `expr` if it is a call to the function itself, with the same layout
+    fn self_call_arguments(&self, expr: &Expr<'a>) -> Option<&'a [Symbol]> {
+        match expr {
+            Expr::Call(Call {
+                call_type:
+                    CallType::ByName {
+                        name,
+                        ret_layout,
+                        arg_layouts,
+                        ..
+                    },
+                arguments,
+            }) if *name == self.name
+                && self.arguments.iter().map(|t| &t.0).eq(arg_layouts.iter())
+                && **ret_layout == self.result =>
+            {
+                Some(arguments)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A self call whose result is put into a constructor that is then returned
+///
+/// > let result = f args
+/// > let cell = Tag tag_id tag_arguments   -- `result` occurs once, at `index`
+/// > ret cell
+struct ConsSite<'a> {
+    call_arguments: &'a [Symbol],
+    cell: Symbol,
+    tag_id: TagIdIntType,
+    index: usize,
+    tag_arguments: &'a [Symbol],
+}
+
+fn cons_site<'a>(
+    needle: Needle<'a>,
+    union_layout: UnionLayout<'a>,
+    stmt: &'a Stmt<'a>,
+) -> Option<ConsSite<'a>> {
+    match stmt {
+        Stmt::Let(
+            result,
+            call,
+            _,
+            Stmt::Let(
+                cell,
+                Expr::Tag {
+                    tag_layout,
+                    tag_id,
+                    arguments,
+                },
+                _,
+                Stmt::Ret(ret),
+            ),
+        ) if cell == ret && *tag_layout == union_layout => {
+            let call_arguments = needle.self_call_arguments(call)?;
+
+            let mut positions = arguments
+                .iter()
+                .enumerate()
+                .filter(|(_, symbol)| *symbol == result)
+                .map(|(index, _)| index);
+
+            match (positions.next(), positions.next()) {
+                (Some(index), None) => Some(ConsSite {
+                    call_arguments,
+                    cell: *cell,
+                    tag_id: *tag_id,
+                    index,
+                    tag_arguments: arguments,
+                }),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// The constructor and field that every cons site in `stmt` fills in. There must be at least one
+/// cons site, and they must all agree, because the hole is written without looking at its tag.
+fn hole_position<'a>(
+    needle: Needle<'a>,
+    union_layout: UnionLayout<'a>,
+    stmt: &'a Stmt<'a>,
+) -> Option<(TagIdIntType, usize)> {
+    use Stmt::*;
+
+    let mut position = None;
+    let mut stack = std::vec![stmt];
+
+    while let Some(stmt) = stack.pop() {
+        if let Some(site) = cons_site(needle, union_layout, stmt) {
+            match position {
+                None => position = Some((site.tag_id, site.index)),
+                Some(existing) if existing == (site.tag_id, site.index) => {}
+                Some(_) => return None,
+            }
+
+            continue;
+        }
+
+        match stmt {
+            Let(_, _, _, cont)
+            | Refcounting(_, cont)
+            | Expect {
+                remainder: cont, ..
+            }
+            | Dbg {
+                remainder: cont, ..
+            } => stack.push(cont),
+
+            Join {
+                body, remainder, ..
+            } => {
+                stack.push(body);
+                stack.push(remainder);
+            }
+
+            Switch {
+                branches,
+                default_branch,
+                ..
+            } => {
+                stack.extend(branches.iter().map(|(_, _, branch)| branch));
+                stack.push(default_branch.1);
+            }
+
+            Ret(_) | Jump(_, _) | Crash(_) | RuntimeError(_) => {}
+        }
+    }
+
+    position
+}
+
+fn make_tail_recursive_modulo_cons<'a>(
+    env: &mut Env<'a, '_>,
+    id: JoinPointId,
+    needle: Needle<'a>,
+    union_layout: UnionLayout<'a>,
+    stmt: &'a Stmt<'a>,
+) -> Option<Stmt<'a>> {
+    // The hole is filled with the null tag until the next iteration writes it. That value is not
+    // heap-allocated, so overwriting it does not leak, and it doubles as the initial value of
+    // `first` and `hole` that tells us no cell has been allocated yet.
+    let nullable_id = match union_layout {
+        UnionLayout::NullableWrapped { nullable_id, .. } => nullable_id,
+        UnionLayout::NullableUnwrapped { nullable_id, .. } => nullable_id as TagIdIntType,
+        UnionLayout::NonRecursive(_)
+        | UnionLayout::Recursive(_)
+        | UnionLayout::NonNullableUnwrapped(_) => return None,
+    };
+
+    let (tag_id, index) = hole_position(needle, union_layout, stmt)?;
+
+    let arena = env.arena;
+    let layout = needle.result;
+
+    let first = env.unique_symbol();
+    let hole = env.unique_symbol();
+
+    let mut state = ModuloCons {
+        env,
+        goal_id: id,
+        needle,
+        union_layout,
+        nullable_id,
+        tag_id,
+        index,
+        first,
+        hole,
+    };
+
+    let body = state.fill_holes(stmt);
+
+    let params = Vec::from_iter_in(
+        needle
+            .arguments
+            .iter()
+            .map(|(layout, symbol, _)| (*layout, *symbol))
+            .chain([(layout, first), (layout, hole)])
+            .map(|(layout, symbol)| Param {
+                symbol,
+                layout,
+                borrow: true,
+            }),
+        arena,
+    )
+    .into_bump_slice();
+
+    let initial_first = state.env.unique_symbol();
+    let initial_hole = state.env.unique_symbol();
+
+    let args = Vec::from_iter_in(
+        needle
+            .arguments
+            .iter()
+            .map(|t| t.2)
+            .chain([initial_first, initial_hole]),
+        arena,
+    )
+    .into_bump_slice();
+
+    let null = state.null_tag();
+    let jump = arena.alloc(Stmt::Jump(id, args));
+    let jump = arena.alloc(Stmt::Let(initial_hole, null.clone(), layout, jump));
+    let jump = arena.alloc(Stmt::Let(initial_first, null, layout, jump));
+
+    Some(Stmt::Join {
+        id,
+        remainder: jump,
+        parameters: params,
+        body,
+    })
+}
+
+struct ModuloCons<'a, 'r, 'i> {
+    env: &'r mut Env<'a, 'i>,
+    goal_id: JoinPointId,
+    needle: Needle<'a>,
+    union_layout: UnionLayout<'a>,
+    nullable_id: TagIdIntType,
+    tag_id: TagIdIntType,
+    index: usize,
+    /// The cell that is returned in the end, or the null tag if nothing was allocated yet
+    first: Symbol,
+    /// The most recently allocated cell, whose field at `index` is still the null tag
+    hole: Symbol,
+}
+
+impl<'a, 'r, 'i> ModuloCons<'a, 'r, 'i> {
+    fn fill_holes(&mut self, stmt: &'a Stmt<'a>) -> &'a Stmt<'a> {
+        use Stmt::*;
+
+        let arena = self.env.arena;
+
+        if let Some(site) = cons_site(self.needle, self.union_layout, stmt) {
+            return self.cons_site(site);
+        }
+
+        match stmt {
+            Let(symbol, expr, layout, cont) => {
+                match (cont, self.needle.self_call_arguments(expr)) {
+                    (Ret(rsym), Some(arguments)) if symbol == rsym => {
+                        // a plain tail call, the hole is passed on unchanged
+                        self.jump(arguments, self.first, self.hole)
+                    }
+                    _ => {
+                        let cont = self.fill_holes(cont);
+
+                        arena.alloc(Let(*symbol, expr.clone(), *layout, cont))
+                    }
+                }
+            }
+
+            Join {
+                id,
+                parameters,
+                remainder,
+                body,
+            } => {
+                let remainder = self.fill_holes(remainder);
+                let body = self.fill_holes(body);
+
+                arena.alloc(Join {
+                    id: *id,
+                    parameters,
+                    remainder,
+                    body,
+                })
+            }
+
+            Switch {
+                cond_symbol,
+                cond_layout,
+                branches,
+                default_branch,
+                ret_layout,
+            } => {
+                let branches = Vec::from_iter_in(
+                    branches.iter().map(|(label, info, branch)| {
+                        (*label, info.clone(), self.fill_holes(branch).clone())
+                    }),
+                    arena,
+                )
+                .into_bump_slice();
+
+                let default_branch = (default_branch.0.clone(), self.fill_holes(default_branch.1));
+
+                arena.alloc(Switch {
+                    cond_symbol: *cond_symbol,
+                    cond_layout: *cond_layout,
+                    branches,
+                    default_branch,
+                    ret_layout: *ret_layout,
+                })
+            }
+
+            Refcounting(modify, cont) => {
+                let cont = self.fill_holes(cont);
+
+                arena.alloc(Refcounting(*modify, cont))
+            }
+
+            Expect {
+                condition,
+                region,
+                lookups,
+                layouts,
+                remainder,
+            } => {
+                let remainder = self.fill_holes(remainder);
+
+                arena.alloc(Expect {
+                    condition: *condition,
+                    region: *region,
+                    lookups,
+                    layouts,
+                    remainder,
+                })
+            }
+
+            Dbg {
+                symbol,
+                region,
+                layout,
+                remainder,
+            } => {
+                let remainder = self.fill_holes(remainder);
+
+                arena.alloc(Dbg {
+                    symbol: *symbol,
+                    region: *region,
+                    layout: *layout,
+                    remainder,
+                })
+            }
+
+            Ret(symbol) => self.fill_and_return(*symbol),
+
+            Jump(_, _) | Crash(_) | RuntimeError(_) => stmt,
+        }
+    }
+
+    /// Allocate the cell with a hole in it, link it to the previous cell, and continue the loop
+    /// with the arguments of the recursive call
+    fn cons_site(&mut self, site: ConsSite<'a>) -> &'a Stmt<'a> {
+        let arena = self.env.arena;
+        let layout = self.needle.result;
+
+        let null = self.env.unique_symbol();
+
+        let mut tag_arguments = Vec::from_iter_in(site.tag_arguments.iter().copied(), arena);
+        tag_arguments[site.index] = null;
+
+        let cell = Expr::Tag {
+            tag_layout: self.union_layout,
+            tag_id: site.tag_id,
+            arguments: tag_arguments.into_bump_slice(),
+        };
+
+        // this is the first cell, it is also the result of the whole loop
+        let no_hole = self.jump(site.call_arguments, site.cell, site.cell);
+
+        let link = self.jump(site.call_arguments, self.first, site.cell);
+        let link = self.set_hole(site.cell, link);
+
+        let switch = self.switch_on_hole(no_hole, link);
+        let stmt = arena.alloc(Stmt::Let(site.cell, cell, layout, switch));
+
+        arena.alloc(Stmt::Let(null, self.null_tag(), layout, stmt))
+    }
+
+    /// Return `symbol` as the result of the whole loop
+    fn fill_and_return(&mut self, symbol: Symbol) -> &'a Stmt<'a> {
+        let arena = self.env.arena;
+
+        // no cells were allocated, `symbol` is the whole result
+        let no_hole = arena.alloc(Stmt::Ret(symbol));
+
+        let link = arena.alloc(Stmt::Ret(self.first));
+        let link = self.set_hole(symbol, link);
+
+        self.switch_on_hole(no_hole, link)
+    }
+
+    fn jump(&self, arguments: &'a [Symbol], first: Symbol, hole: Symbol) -> &'a Stmt<'a> {
+        let arena = self.env.arena;
+
+        let arguments = Vec::from_iter_in(arguments.iter().copied().chain([first, hole]), arena);
+
+        arena.alloc(Stmt::Jump(self.goal_id, arguments.into_bump_slice()))
+    }
+
+    fn set_hole(&mut self, value: Symbol, cont: &'a Stmt<'a>) -> &'a Stmt<'a> {
+        let unit = self.env.unique_symbol();
+
+        let expr = Expr::SetUnionAtIndex {
+            structure: self.hole,
+            tag_id: self.tag_id,
+            union_layout: self.union_layout,
+            index: self.index as u64,
+            value,
+        };
+
+        self.env
+            .arena
+            .alloc(Stmt::Let(unit, expr, Layout::UNIT, cont))
+    }
+
+    fn switch_on_hole(&mut self, no_hole: &'a Stmt<'a>, hole: &'a Stmt<'a>) -> &'a Stmt<'a> {
+        let arena = self.env.arena;
+
+        let tag_id_symbol = self.env.unique_symbol();
+        let tag_id_layout = self.union_layout.tag_id_layout();
+
+        let get_tag_id = Expr::GetTagId {
+            structure: self.hole,
+            union_layout: self.union_layout,
+        };
+
+        let branches = arena.alloc([(self.nullable_id as u64, BranchInfo::None, no_hole.clone())]);
+
+        let switch = arena.alloc(Stmt::Switch {
+            cond_symbol: tag_id_symbol,
+            cond_layout: tag_id_layout,
+            branches,
+            default_branch: (BranchInfo::None, hole),
+            ret_layout: self.needle.result,
+        });
+
+        arena.alloc(Stmt::Let(tag_id_symbol, get_tag_id, tag_id_layout, switch))
+    }
+
+    fn null_tag(&self) -> Expr<'a> {
+        Expr::Tag {
+            tag_layout: self.union_layout,
+            tag_id: self.nullable_id,
+            arguments: &[],
+        }
+    }
+}
@@ -5,7 +5,7 @@ use crate::ast::{
 use crate::blankspace::{
     space0_after_e, space0_around_ee, space0_before_e, space0_before_optional_after, space0_e,
 };
-use crate::ident::{lowercase_ident, parse_ident, Ident};
+use crate::ident::{is_tuple_index, lowercase_ident, parse_ident, tuple_index, Ident};
 use crate::keyword;
 use crate::parser::{
     self, backtrackable, optional, sep_by1, sep_by1_e, specialize, specialize_ref, then,
@@ -84,34 +84,88 @@ pub fn expr_help<'a>(min_indent: u32) -> impl Parser<'a, Expr<'a>, EExpr<'a>> {
 }
 
 fn loc_expr_in_parens_help<'a>(min_indent: u32) -> impl Parser<'a, Loc<Expr<'a>>, EInParens<'a>> {
-    move |arena, state| {
-        let (_, loc_expr, state) = loc_expr_in_parens_help_help(min_indent).parse(arena, state)?;
+    move |arena, state: State<'a>| {
+        let start = state.pos();
+        let (_, (first, rest), state) =
+            loc_expr_in_parens_help_help(min_indent).parse(arena, state)?;
 
-        Ok((
-            MadeProgress,
-            Loc {
-                region: loc_expr.region,
-                value: Expr::ParensAround(arena.alloc(loc_expr.value)),
-            },
-            state,
-        ))
+        // a tuple may have a trailing comma, e.g. `(foo, bar,)`
+        let (final_comments, state) = if rest.is_empty() {
+            (&[] as &[_], state)
+        } else {
+            match skip_first!(
+                word1(b',', EInParens::End),
+                space0_e(min_indent, EInParens::IndentEnd)
+            )
+            .parse(arena, state.clone())
+            {
+                Ok((_, spaces, state)) => (spaces, state),
+                Err((NoProgress, _, _)) => (&[] as &[_], state),
+                Err((MadeProgress, fail, state)) => return Err((MadeProgress, fail, state)),
+            }
+        };
+
+        let (_, _, state) = word1(b')', EInParens::End)
+            .parse(arena, state)
+            .map_err(|(_, fail, state)| (MadeProgress, fail, state))?;
+
+        let region = Region::new(start, state.pos());
+
+        if rest.is_empty() {
+            Ok((
+                MadeProgress,
+                Loc {
+                    region: first.region,
+                    value: Expr::ParensAround(arena.alloc(first.value)),
+                },
+                state,
+            ))
+        } else {
+            // this is a tuple, e.g. `(foo, bar)`
+            let mut elems = Vec::with_capacity_in(rest.len() + 1, arena);
+
+            elems.push(&*arena.alloc(first));
+            elems.extend(rest.into_iter().map(|elem| &*arena.alloc(elem)));
+
+            let tuple = Expr::Tuple(Collection::with_items_and_comments(
+                arena,
+                elems.into_bump_slice(),
+                final_comments,
+            ));
+
+            Ok((MadeProgress, Loc::at(region, tuple), state))
+        }
     }
 }
 
+#[allow(clippy::type_complexity)]
 fn loc_expr_in_parens_help_help<'a>(
     min_indent: u32,
-) -> impl Parser<'a, Loc<Expr<'a>>, EInParens<'a>> {
-    between!(
+) -> impl Parser<'a, (Loc<Expr<'a>>, Vec<'a, Loc<Expr<'a>>>), EInParens<'a>> {
+    skip_first!(
         word1(b'(', EInParens::Open),
-        space0_around_ee(
-            specialize_ref(EInParens::Expr, move |arena, state| parse_loc_expr(
-                min_indent, arena, state
-            )),
-            min_indent,
-            EInParens::IndentOpen,
-            EInParens::IndentEnd,
-        ),
-        word1(b')', EInParens::End)
+        and!(
+            space0_around_ee(
+                specialize_ref(EInParens::Expr, move |arena, state| {
+                    parse_loc_expr_no_multi_backpassing(min_indent, arena, state)
+                }),
+                min_indent,
+                EInParens::IndentOpen,
+                EInParens::IndentEnd,
+            ),
+            // any further comma-separated elements make this a tuple
+            zero_or_more!(backtrackable(skip_first!(
+                word1(b',', EInParens::End),
+                space0_around_ee(
+                    specialize_ref(EInParens::Expr, move |arena, state| {
+                        parse_loc_expr_no_multi_backpassing(min_indent, arena, state)
+                    }),
+                    min_indent,
+                    EInParens::IndentOpen,
+                    EInParens::IndentEnd,
+                )
+            )))
+        )
     )
 }
 
@@ -146,7 +200,7 @@ fn loc_expr_in_parens_etc_help<'a>(min_indent: u32) -> impl Parser<'a, Loc<Expr<
                 // Wrap the previous answer in the new one, so we end up
                 // with a nested Expr. That way, `foo.bar.baz` gets represented
                 // in the AST as if it had been written (foo.bar).baz all along.
-                value = Expr::Access(arena.alloc(value), field);
+                value = access_to_expr(arena, value, field);
             }
         }
 
@@ -183,10 +237,23 @@ fn record_field_access_chain<'a>() -> impl Parser<'a, Vec<'a, &'a str>, EExpr<'a
 fn record_field_access<'a>() -> impl Parser<'a, &'a str, EExpr<'a>> {
     skip_first!(
         word1(b'.', EExpr::Access),
-        specialize(|_, pos| EExpr::Access(pos), lowercase_ident())
+        specialize(
+            |_, pos| EExpr::Access(pos),
+            one_of!(lowercase_ident(), tuple_index())
+        )
     )
 }
 
+/// Wrap `value` in an access of `part`, which is a record field like the `bar` in `foo.bar`,
+/// or a tuple element index like the `1` in `foo.1`.
+fn access_to_expr<'a>(arena: &'a Bump, value: Expr<'a>, part: &'a str) -> Expr<'a> {
+    if is_tuple_index(part) {
+        Expr::TupleAccess(arena.alloc(value), part)
+    } else {
+        Expr::Access(arena.alloc(value), part)
+    }
+}
+
 /// In some contexts we want to parse the `_` as an expression, so it can then be turned into a
 /// pattern later
 fn parse_loc_term_or_underscore_or_conditional<'a>(
@@ -355,6 +422,8 @@ fn parse_expr_start<'a>(
             when::expr_help(min_indent, options)
         )),
         loc!(specialize(EExpr::Expect, expect_help(min_indent, options))),
+        loc!(specialize(EExpr::Dbg, dbg_help(options))),
+        loc!(specialize(EExpr::Crash, crash_help(options))),
         loc!(specialize(EExpr::Lambda, closure_help(min_indent, options))),
         loc!(move |a, s| parse_expr_operator_chain(min_indent, options, start_column, a, s)),
         fail_expr_start_e()
@@ -1832,6 +1901,16 @@ fn expr_to_pattern_help<'a>(arena: &'a Bump, expr: &Expr<'a>) -> Result<Pattern<
 
         Expr::ParensAround(sub_expr) => expr_to_pattern_help(arena, sub_expr),
 
+        Expr::Tuple(elems) => {
+            let patterns = elems.map_items_result(arena, |loc_elem| {
+                let region = loc_elem.region;
+                let value = expr_to_pattern_help(arena, &loc_elem.value)?;
+                Ok(Loc { region, value })
+            })?;
+
+            Ok(Pattern::Tuple(patterns))
+        }
+
         Expr::Record(fields) => {
             let patterns = fields.map_items_result(arena, |loc_assigned_field| {
                 let region = loc_assigned_field.region;
@@ -1856,6 +1935,8 @@ fn expr_to_pattern_help<'a>(arena: &'a Bump, expr: &Expr<'a>) -> Result<Pattern<
         // These would not have parsed as patterns
         Expr::AccessorFunction(_)
         | Expr::Access(_, _)
+        | Expr::TupleAccessorFunction(_)
+        | Expr::TupleAccess(_, _)
         | Expr::List { .. }
         | Expr::Closure(_, _)
         | Expr::Backpassing(_, _, _)
@@ -1864,6 +1945,8 @@ fn expr_to_pattern_help<'a>(arena: &'a Bump, expr: &Expr<'a>) -> Result<Pattern<
         | Expr::If(_, _)
         | Expr::When(_, _)
         | Expr::Expect(_, _)
+        | Expr::Dbg(_)
+        | Expr::Crash(_)
         | Expr::MalformedClosure
         | Expr::PrecedenceConflict { .. }
         | Expr::RecordUpdate { .. }
@@ -2361,6 +2444,52 @@ fn expect_help<'a>(
     }
 }
 
+fn dbg_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EExpect<'a>> {
+    move |arena: &'a Bump, state: State<'a>| {
+        let start_column = state.column();
+
+        let (_, _, state) = parser::keyword_e(keyword::DBG, EExpect::Dbg).parse(arena, state)?;
+
+        // unlike `expect`, there is no continuation: `dbg x` evaluates to `x`
+        let (_, message, state) = space0_before_e(
+            specialize_ref(EExpect::Condition, move |arena, state| {
+                parse_loc_expr_with_options(start_column + 1, options, arena, state)
+            }),
+            start_column + 1,
+            EExpect::IndentCondition,
+        )
+        .parse(arena, state)
+        .map_err(|(_, f, s)| (MadeProgress, f, s))?;
+
+        let expr = Expr::Dbg(arena.alloc(message));
+
+        Ok((MadeProgress, expr, state))
+    }
+}
+
+fn crash_help<'a>(options: ExprParseOptions) -> impl Parser<'a, Expr<'a>, EExpect<'a>> {
+    move |arena: &'a Bump, state: State<'a>| {
+        let start_column = state.column();
+
+        let (_, _, state) =
+            parser::keyword_e(keyword::CRASH, EExpect::Crash).parse(arena, state)?;
+
+        let (_, message, state) = space0_before_e(
+            specialize_ref(EExpect::Condition, move |arena, state| {
+                parse_loc_expr_with_options(start_column + 1, options, arena, state)
+            }),
+            start_column + 1,
+            EExpect::IndentCondition,
+        )
+        .parse(arena, state)
+        .map_err(|(_, f, s)| (MadeProgress, f, s))?;
+
+        let expr = Expr::Crash(arena.alloc(message));
+
+        Ok((MadeProgress, expr, state))
+    }
+}
+
 fn if_expr_help<'a>(
     min_indent: u32,
     options: ExprParseOptions,
@@ -2472,11 +2601,14 @@ fn ident_to_expr<'a>(arena: &'a Bump, src: Ident<'a>) -> Expr<'a> {
                 // Wrap the previous answer in the new one, so we end up
                 // with a nested Expr. That way, `foo.bar.baz` gets represented
                 // in the AST as if it had been written (foo.bar).baz all along.
-                answer = Expr::Access(arena.alloc(answer), field);
+                answer = access_to_expr(arena, answer, field);
             }
 
             answer
         }
+        Ident::AccessorFunction(string) if is_tuple_index(string) => {
+            Expr::TupleAccessorFunction(string)
+        }
         Ident::AccessorFunction(string) => Expr::AccessorFunction(string),
         Ident::Malformed(string, problem) => Expr::MalformedIdent(string, problem),
     }
@@ -2656,7 +2788,7 @@ fn record_literal_help<'a>(min_indent: u32) -> impl Parser<'a, Expr<'a>, EExpr<'
                     // Wrap the previous answer in the new one, so we end up
                     // with a nested Expr. That way, `foo.bar.baz` gets represented
                     // in the AST as if it had been written (foo.bar).baz all along.
-                    value = Expr::Access(arena.alloc(value), field);
+                    value = access_to_expr(arena, value, field);
                 }
             }
 
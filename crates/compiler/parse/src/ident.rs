@@ -43,7 +43,7 @@ pub enum Ident<'a> {
         module_name: &'a str,
         parts: &'a [&'a str],
     },
-    /// .foo { foo: 42 }
+    /// .foo { foo: 42 } or .0 ("a", "b")
     AccessorFunction(&'a str),
     /// .Foo or foo. or something like foo.Bar
     Malformed(&'a str, BadIdent),
@@ -97,6 +97,17 @@ pub fn lowercase_ident<'a>() -> impl Parser<'a, &'a str, ()> {
     }
 }
 
+/// A tuple element index, e.g. the `1` in `tup.1`
+pub fn tuple_index<'a>() -> impl Parser<'a, &'a str, ()> {
+    move |_, state: State<'a>| match chomp_tuple_index(state.bytes()) {
+        Err(progress) => Err((progress, (), state)),
+        Ok(index) => {
+            let width = index.len();
+            Ok((MadeProgress, index, state.advance(width)))
+        }
+    }
+}
+
 pub fn tag_name<'a>() -> impl Parser<'a, &'a str, ()> {
     move |arena, state: State<'a>| uppercase_ident().parse(arena, state)
 }
@@ -233,6 +244,29 @@ fn chomp_uppercase_part(buffer: &[u8]) -> Result<&str, Progress> {
     chomp_part(|c: char| c.is_uppercase(), buffer)
 }
 
+/// a tuple element index, e.g. the `1` in `tup.1`
+fn chomp_tuple_index(buffer: &[u8]) -> Result<&str, Progress> {
+    let chomped = buffer.iter().take_while(|b| b.is_ascii_digit()).count();
+
+    if chomped == 0 {
+        Err(NoProgress)
+    } else {
+        let index = unsafe { std::str::from_utf8_unchecked(&buffer[..chomped]) };
+
+        Ok(index)
+    }
+}
+
+/// the part after a `.` in an access chain; either a record field or a tuple element index
+fn chomp_access_part(buffer: &[u8]) -> Result<&str, Progress> {
+    chomp_lowercase_part(buffer).or_else(|_| chomp_tuple_index(buffer))
+}
+
+/// Whether an accessed part (e.g. the `1` in `tup.1`) refers to a tuple element
+pub fn is_tuple_index(part: &str) -> bool {
+    part.bytes().next().map_or(false, |b| b.is_ascii_digit())
+}
+
 #[inline(always)]
 fn chomp_part<F>(leading_is_good: F, buffer: &[u8]) -> Result<&str, Progress>
 where
@@ -268,12 +302,12 @@ where
     }
 }
 
-/// a `.foo` accessor function
+/// a `.foo` or `.0` accessor function
 fn chomp_accessor(buffer: &[u8], pos: Position) -> Result<&str, BadIdent> {
     // assumes the leading `.` has been chomped already
     use encode_unicode::CharExt;
 
-    match chomp_lowercase_part(buffer) {
+    match chomp_access_part(buffer) {
         Ok(name) => {
             let chomped = name.len();
 
@@ -504,7 +538,7 @@ fn chomp_access_chain<'a>(buffer: &'a [u8], parts: &mut Vec<'a, &'a str>) -> Res
 
     while let Some(b'.') = buffer.get(chomped) {
         match &buffer.get(chomped + 1..) {
-            Some(slice) => match chomp_lowercase_part(slice) {
+            Some(slice) => match chomp_access_part(slice) {
                 Ok(name) => {
                     let value = unsafe {
                         std::str::from_utf8_unchecked(
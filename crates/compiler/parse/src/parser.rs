@@ -107,7 +107,8 @@ impl_space_problem! {
     EWhen<'a>,
     EAbility<'a>,
     PInParens<'a>,
-    PRecord<'a>
+    PRecord<'a>,
+    PList<'a>
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -351,6 +352,8 @@ pub enum EExpr<'a> {
     If(EIf<'a>, Position),
 
     Expect(EExpect<'a>, Position),
+    Dbg(EExpect<'a>, Position),
+    Crash(EExpect<'a>, Position),
 
     Lambda(ELambda<'a>, Position),
     Underscore(Position),
@@ -509,6 +512,8 @@ pub enum EIf<'a> {
 pub enum EExpect<'a> {
     Space(BadInputError, Position),
     Expect(Position),
+    Dbg(Position),
+    Crash(Position),
     Condition(&'a EExpr<'a>, Position),
     Continuation(&'a EExpr<'a>, Position),
     IndentCondition(Position),
@@ -524,6 +529,7 @@ pub enum EPattern<'a> {
     Space(BadInputError, Position),
 
     PInParens(PInParens<'a>, Position),
+    List(PList<'a>, Position),
     NumLiteral(ENumber, Position),
 
     IndentStart(Position),
@@ -551,6 +557,19 @@ pub enum PRecord<'a> {
     IndentEnd(Position),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PList<'a> {
+    End(Position),
+    Open(Position),
+
+    Rest(Position),
+    Pattern(&'a EPattern<'a>, Position),
+
+    Space(BadInputError, Position),
+    IndentOpen(Position),
+    IndentEnd(Position),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PInParens<'a> {
     End(Position),
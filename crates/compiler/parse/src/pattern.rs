@@ -1,10 +1,10 @@
-use crate::ast::{Has, Pattern};
+use crate::ast::{Collection, Has, Pattern};
 use crate::blankspace::{space0_around_ee, space0_before_e, space0_e};
 use crate::ident::{lowercase_ident, parse_ident, Ident};
 use crate::parser::Progress::{self, *};
 use crate::parser::{
-    backtrackable, optional, specialize, specialize_ref, then, word1, EPattern, PInParens, PRecord,
-    ParseResult, Parser,
+    backtrackable, optional, specialize, specialize_ref, then, word1, word2, EPattern, PInParens,
+    PList, PRecord, ParseResult, Parser,
 };
 use crate::state::State;
 use bumpalo::collections::string::String;
@@ -59,6 +59,7 @@ pub fn loc_pattern_help<'a>(min_indent: u32) -> impl Parser<'a, Loc<Pattern<'a>>
             EPattern::Record,
             crate::pattern::record_pattern_help(min_indent)
         )),
+        loc!(specialize(EPattern::List, list_pattern_help(min_indent))),
         loc!(number_pattern_help()),
         loc!(string_pattern_help()),
         loc!(single_quote_pattern_help()),
@@ -139,6 +140,7 @@ fn loc_parse_tag_pattern_arg<'a>(
             EPattern::Record,
             crate::pattern::record_pattern_help(min_indent)
         )),
+        loc!(specialize(EPattern::List, list_pattern_help(min_indent))),
         loc!(string_pattern_help()),
         loc!(single_quote_pattern_help()),
         loc!(number_pattern_help())
@@ -146,19 +148,137 @@ fn loc_parse_tag_pattern_arg<'a>(
     .parse(arena, state)
 }
 
+fn list_pattern_help<'a>(min_indent: u32) -> impl Parser<'a, Pattern<'a>, PList<'a>> {
+    move |arena, state| {
+        let (_, pats, state) = collection_trailing_sep_e!(
+            word1(b'[', PList::Open),
+            list_element_pattern(min_indent),
+            word1(b',', PList::End),
+            word1(b']', PList::End),
+            min_indent,
+            PList::Open,
+            PList::IndentEnd,
+            Pattern::SpaceBefore
+        )
+        .parse(arena, state)?;
+
+        let result = Pattern::List(pats);
+
+        Ok((MadeProgress, result, state))
+    }
+}
+
+fn list_element_pattern<'a>(min_indent: u32) -> impl Parser<'a, Loc<Pattern<'a>>, PList<'a>> {
+    one_of!(
+        list_rest_pattern(),
+        specialize_ref(PList::Pattern, loc_pattern_help(min_indent)),
+    )
+}
+
+/// A rest pattern in a list, e.g. the `..` in `[x, .., y]` or the `..rest` in `[x, ..rest]`
+fn list_rest_pattern<'a>() -> impl Parser<'a, Loc<Pattern<'a>>, PList<'a>> {
+    move |arena: &'a Bump, state: State<'a>| {
+        let start = state.pos();
+
+        let (_, _, state) = word2(b'.', b'.', PList::Rest).parse(arena, state)?;
+
+        let pos = state.pos();
+        let (_, opt_name, state) =
+            optional(specialize(move |_, _| PList::Rest(pos), lowercase_ident()))
+                .parse(arena, state)?;
+
+        let region = Region::new(start, state.pos());
+
+        Ok((
+            MadeProgress,
+            Loc::at(region, Pattern::ListRest(opt_name)),
+            state,
+        ))
+    }
+}
+
 fn loc_pattern_in_parens_help<'a>(
     min_indent: u32,
 ) -> impl Parser<'a, Loc<Pattern<'a>>, PInParens<'a>> {
-    between!(
+    move |arena, state: State<'a>| {
+        let start = state.pos();
+        let (_, (first, rest), state) =
+            loc_pattern_in_parens_help_help(min_indent).parse(arena, state)?;
+
+        // a tuple may have a trailing comma, e.g. `(x, y,)`
+        let (final_comments, state) = if rest.is_empty() {
+            (&[] as &[_], state)
+        } else {
+            match skip_first!(
+                word1(b',', PInParens::End),
+                space0_e(min_indent, PInParens::IndentEnd)
+            )
+            .parse(arena, state.clone())
+            {
+                Ok((_, spaces, state)) => (spaces, state),
+                Err((NoProgress, _, _)) => (&[] as &[_], state),
+                Err((MadeProgress, fail, state)) => return Err((MadeProgress, fail, state)),
+            }
+        };
+
+        let (_, _, state) = word1(b')', PInParens::End)
+            .parse(arena, state)
+            .map_err(|(_, fail, state)| (MadeProgress, fail, state))?;
+
+        let region = Region::new(start, state.pos());
+
+        if rest.is_empty() {
+            Ok((MadeProgress, first, state))
+        } else {
+            // this is a tuple pattern, e.g. `(x, y)`
+            let mut elems = Vec::with_capacity_in(rest.len() + 1, arena);
+
+            elems.push(first);
+            elems.extend(rest);
+
+            let tuple = Pattern::Tuple(Collection::with_items_and_comments(
+                arena,
+                elems.into_bump_slice(),
+                final_comments,
+            ));
+
+            Ok((MadeProgress, Loc::at(region, tuple), state))
+        }
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn loc_pattern_in_parens_help_help<'a>(
+    min_indent: u32,
+) -> impl Parser<'a, (Loc<Pattern<'a>>, Vec<'a, Loc<Pattern<'a>>>), PInParens<'a>> {
+    skip_first!(
         word1(b'(', PInParens::Open),
-        space0_around_ee(
-            move |arena, state| specialize_ref(PInParens::Pattern, loc_pattern_help(min_indent))
+        and!(
+            space0_around_ee(
+                move |arena, state| specialize_ref(
+                    PInParens::Pattern,
+                    loc_pattern_help(min_indent)
+                )
                 .parse(arena, state),
-            min_indent,
-            PInParens::IndentOpen,
-            PInParens::IndentEnd,
-        ),
-        word1(b')', PInParens::End)
+                min_indent,
+                PInParens::IndentOpen,
+                PInParens::IndentEnd,
+            ),
+            // any further comma-separated patterns make this a tuple pattern
+            zero_or_more!(backtrackable(skip_first!(
+                word1(b',', PInParens::End),
+                space0_around_ee(
+                    move |arena, state| specialize_ref(
+                        PInParens::Pattern,
+                        loc_pattern_help(min_indent)
+                    )
+                    .parse(arena, state),
+                    min_indent,
+                    PInParens::IndentOpen,
+                    PInParens::IndentEnd,
+                )
+            )))
+        )
     )
 }
 
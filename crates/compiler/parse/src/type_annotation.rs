@@ -1,6 +1,6 @@
 use crate::ast::{
-    AssignedField, CommentOrNewline, HasAbilities, HasAbility, HasClause, HasImpls, Pattern,
-    Spaced, Tag, TypeAnnotation, TypeHeader,
+    AssignedField, Collection, CommentOrNewline, HasAbilities, HasAbility, HasClause, HasImpls,
+    Pattern, Spaced, Tag, TypeAnnotation, TypeHeader,
 };
 use crate::blankspace::{space0_around_ee, space0_before_e, space0_e};
 use crate::expr::record_value_field;
@@ -223,19 +223,100 @@ fn loc_applied_arg<'a>(
 fn loc_type_in_parens<'a>(
     min_indent: u32,
 ) -> impl Parser<'a, Loc<TypeAnnotation<'a>>, ETypeInParens<'a>> {
-    between!(
-        word1(b'(', ETypeInParens::Open),
-        space0_around_ee(
-            move |arena, state| specialize_ref(
-                ETypeInParens::Type,
-                expression(min_indent, true, false)
+    move |arena, state: State<'a>| {
+        let start = state.pos();
+        let (_, (first, rest), state) = loc_type_in_parens_help(min_indent).parse(arena, state)?;
+
+        // a tuple may have a trailing comma, e.g. `(Str, U64,)`
+        let (final_comments, state) = if rest.is_empty() {
+            (&[] as &[_], state)
+        } else {
+            match skip_first!(
+                word1(b',', ETypeInParens::End),
+                space0_e(min_indent, ETypeInParens::IndentEnd)
             )
-            .parse(arena, state),
-            min_indent,
-            ETypeInParens::IndentOpen,
-            ETypeInParens::IndentEnd,
-        ),
-        word1(b')', ETypeInParens::IndentEnd)
+            .parse(arena, state.clone())
+            {
+                Ok((_, spaces, state)) => (spaces, state),
+                Err((NoProgress, _, _)) => (&[] as &[_], state),
+                Err((MadeProgress, fail, state)) => return Err((MadeProgress, fail, state)),
+            }
+        };
+
+        let (_, _, state) = word1(b')', ETypeInParens::IndentEnd)
+            .parse(arena, state)
+            .map_err(|(_, fail, state)| (MadeProgress, fail, state))?;
+
+        let region = Region::new(start, state.pos());
+
+        if rest.is_empty() {
+            return Ok((MadeProgress, first, state));
+        }
+
+        // this is a tuple type, e.g. `(Str, U64)`
+        let mut elems = Vec::with_capacity_in(rest.len() + 1, arena);
+
+        elems.push(first);
+        elems.extend(rest);
+
+        // This could be an open tuple, e.g. `(Str, U64)a`
+        let (_, ext, state) = optional(allocated(specialize_ref(
+            ETypeInParens::Type,
+            term(min_indent, false),
+        )))
+        .parse(arena, state)?;
+
+        let region = match ext {
+            Some(ext) => Region::span_across(&region, &ext.region),
+            None => region,
+        };
+
+        let result = TypeAnnotation::Tuple {
+            elems: Collection::with_items_and_comments(
+                arena,
+                elems.into_bump_slice(),
+                final_comments,
+            ),
+            ext,
+        };
+
+        Ok((MadeProgress, Loc::at(region, result), state))
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn loc_type_in_parens_help<'a>(
+    min_indent: u32,
+) -> impl Parser<'a, (Loc<TypeAnnotation<'a>>, Vec<'a, Loc<TypeAnnotation<'a>>>), ETypeInParens<'a>>
+{
+    skip_first!(
+        word1(b'(', ETypeInParens::Open),
+        and!(
+            space0_around_ee(
+                move |arena, state| specialize_ref(
+                    ETypeInParens::Type,
+                    expression(min_indent, true, false)
+                )
+                .parse(arena, state),
+                min_indent,
+                ETypeInParens::IndentOpen,
+                ETypeInParens::IndentEnd,
+            ),
+            // any further comma-separated types make this a tuple type
+            zero_or_more!(backtrackable(skip_first!(
+                word1(b',', ETypeInParens::End),
+                space0_around_ee(
+                    move |arena, state| specialize_ref(
+                        ETypeInParens::Type,
+                        expression(min_indent, true, false)
+                    )
+                    .parse(arena, state),
+                    min_indent,
+                    ETypeInParens::IndentOpen,
+                    ETypeInParens::IndentEnd,
+                )
+            )))
+        )
     )
 }
 
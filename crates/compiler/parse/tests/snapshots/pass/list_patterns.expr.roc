@@ -0,0 +1,4 @@
+when [] is
+ [] -> 1
+ [x, .., y] -> 2
+ [head, ..rest] -> 3
@@ -0,0 +1,2 @@
+f : (Str, U8)
+42
\ No newline at end of file
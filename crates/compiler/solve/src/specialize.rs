@@ -17,8 +17,8 @@ use roc_error_macros::{internal_error, todo_abilities};
 use roc_module::symbol::{ModuleId, Symbol};
 use roc_types::{
     subs::{
-        get_member_lambda_sets_at_region, Content, Descriptor, GetSubsSlice, LambdaSet, Mark,
-        OptVariable, Rank, Subs, SubsSlice, UlsOfVar, Variable,
+        get_member_lambda_sets_at_region, instantiate_rigids, Content, Descriptor, GetSubsSlice,
+        LambdaSet, Mark, OptVariable, Rank, Subs, SubsSlice, UlsOfVar, Variable,
     },
     types::{AliasKind, MemberImpl, Uls},
 };
@@ -601,6 +601,7 @@ enum SpecializationTypeKey {
     Opaque(Symbol),
     Derived(DeriveKey),
     Immediate(Symbol),
+    SingleLambdaSetImmediate(Symbol),
 }
 
 enum SpecializeDecision {
@@ -666,6 +667,9 @@ fn make_specialization_decision<P: Phase>(
                     roc_derive_key::Derived::Immediate(imm) => {
                         SpecializeDecision::Specialize(Immediate(imm))
                     }
+                    roc_derive_key::Derived::SingleLambdaSetImmediate(imm) => {
+                        SpecializeDecision::Specialize(SingleLambdaSetImmediate(imm))
+                    }
                     roc_derive_key::Derived::Key(derive_key) => {
                         SpecializeDecision::Specialize(Derived(derive_key))
                     }
@@ -785,5 +789,28 @@ fn get_specialization_lambda_set_ambient_function<P: Phase>(
 
             Ok(immediate_lambda_set_at_region)
         }
+
+        SpecializationTypeKey::SingleLambdaSetImmediate(imm) => {
+            let module_id = imm.module_id();
+            debug_assert!(module_id.is_builtin());
+
+            let module_types = &derived_env
+                .exposed_types
+                .get(&module_id)
+                .unwrap()
+                .exposed_types_storage_subs;
+
+            // Since this immediate has only one lambda set, the region must be pointing to 1, and
+            // moreover the imported function type is the ambient function of the single lset.
+            debug_assert_eq!(lset_region, 1);
+            let storage_var = module_types.stored_vars_by_symbol.get(&imm).unwrap();
+            let imported = module_types
+                .storage_subs
+                .export_variable_to(subs, *storage_var);
+
+            instantiate_rigids(subs, imported.variable);
+
+            Ok(imported.variable)
+        }
     }
 }
@@ -1403,6 +1403,89 @@ mod solve_expr {
         );
     }
 
+    #[test]
+    fn when_with_list_patterns() {
+        infer_eq(
+            indoc!(
+                r#"
+                    \l ->
+                        when l is
+                            [] -> Nothing
+                            [x] -> One x
+                            [first, .., last] -> Two first last
+                "#
+            ),
+            "List a -> [Nothing, One a, Two a a]*",
+        );
+    }
+
+    #[test]
+    fn list_rest_pattern_binds_list() {
+        infer_eq(
+            indoc!(
+                r#"
+                    when ["a", "b"] is
+                        [_, ..rest] -> rest
+                        [] -> []
+                "#
+            ),
+            "List Str",
+        );
+    }
+
+    #[test]
+    fn dbg_evaluates_to_its_argument() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                    x = dbg "hello"
+
+                    x
+                "#
+            ),
+            "Str",
+        );
+    }
+
+    #[test]
+    fn dbg_in_function_body() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                    \n -> dbg n + 1
+                "#
+            ),
+            "Num a -> Num a",
+        );
+    }
+
+    #[test]
+    fn crash_unifies_with_other_branches() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                    \n -> if n > 0 then n else crash "not positive"
+                "#
+            ),
+            "Num a -> Num a",
+        );
+    }
+
+    #[test]
+    fn crash_takes_annotated_type() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                    x : Str
+                    x = crash "todo"
+
+                    x
+                "#
+            ),
+            "Str",
+        );
+    }
+
     // RECORDS
 
     #[test]
@@ -1425,6 +1508,30 @@ mod solve_expr {
         infer_eq("{ x: 5, y : 3.14 }.x", "Num *");
     }
 
+    #[test]
+    fn two_elem_tuple() {
+        infer_eq("(5, 3.14)", "( Num *, Float * )");
+    }
+
+    #[test]
+    fn tuple_literal_accessor() {
+        infer_eq("(5, \"foo\").1", "Str");
+    }
+
+    #[test]
+    fn tuple_destructure() {
+        infer_eq(
+            indoc!(
+                r#"
+                (x, y) = (5, "foo")
+
+                y
+                "#
+            ),
+            "Str",
+        );
+    }
+
     #[test]
     fn record_arg() {
         infer_eq("\\rec -> rec.x", "{ x : a }* -> a");
@@ -3463,7 +3570,7 @@ mod solve_expr {
                 Dict.insert
                 "#
             ),
-            "Dict k v, k, v -> Dict k v",
+            "Dict k v, k, v -> Dict k v | k has Hash",
         );
     }
 
@@ -3724,7 +3831,11 @@ mod solve_expr {
         infer_eq_without_problem(
             indoc!(
                 r#"
-                reconstructPath : Dict position position, position -> List position
+                app "test"
+                    imports [Hash.{ Hash }]
+                    provides [main] to "./platform"
+
+                reconstructPath : Dict position position, position -> List position | position has Hash
                 reconstructPath = \cameFrom, goal ->
                     when Dict.get cameFrom goal is
                         Err KeyNotFound ->
@@ -3733,10 +3844,10 @@ mod solve_expr {
                         Ok next ->
                             List.append (reconstructPath cameFrom next) goal
 
-                reconstructPath
+                main = reconstructPath
                 "#
             ),
-            "Dict position position, position -> List position",
+            "Dict position position, position -> List position | position has Hash",
         );
     }
 
@@ -6545,6 +6656,78 @@ mod solve_expr {
         )
     }
 
+    #[test]
+    fn hash_derived_structural_types() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                app "test"
+                    imports [Hash.{ hash }]
+                    provides [main] to "./platform"
+
+                main = \hasher -> hash hasher { a: "", b: [1u8, 2u8], c: -3i64 }
+                "#
+            ),
+            "a -> a | a has Hasher",
+        )
+    }
+
+    #[test]
+    fn hash_opaque_with_custom_impl() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                app "test"
+                    imports [Hash.{ Hash, hash, addU64 }]
+                    provides [main] to "./platform"
+
+                Id := U64 has [Hash {hash: hashId}]
+
+                hashId = \hasher, @Id n -> addU64 hasher n
+
+                main = \hasher -> hash hasher { id: @Id 1 }
+                "#
+            ),
+            "a -> a | a has Hasher",
+        )
+    }
+
+    #[test]
+    fn inspect_derived_structural_types() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                app "test"
+                    imports [Inspect.{ toDbgStr }]
+                    provides [main] to "./platform"
+
+                main = toDbgStr { a: "", b: [1u8, 2u8], c: [A -3i64, B] }
+                "#
+            ),
+            "Str",
+        )
+    }
+
+    #[test]
+    fn inspect_opaque_with_custom_impl() {
+        infer_eq_without_problem(
+            indoc!(
+                r#"
+                app "test"
+                    imports [Inspect.{ Inspect, toDbgStr }]
+                    provides [main] to "./platform"
+
+                Password := Str has [Inspect {toInspector: passwordToInspector}]
+
+                passwordToInspector = \@Password _ -> Inspect.opaque "Password" (Inspect.str "***")
+
+                main = toDbgStr { user: "rex", password: @Password "hunter2" }
+                "#
+            ),
+            "Str",
+        )
+    }
+
     #[test]
     fn resolve_lambda_set_generalized_ability_alias() {
         infer_queries!(
@@ -31,6 +31,32 @@ pub enum TypeError {
     },
 }
 
+impl TypeError {
+    /// The main region this error points at, if it has one.
+    pub fn region(&self) -> Option<Region> {
+        match self {
+            TypeError::BadExpr(region, ..)
+            | TypeError::BadPattern(region, ..)
+            | TypeError::CircularType(region, ..)
+            | TypeError::BadExprMissingAbility(region, ..)
+            | TypeError::BadPatternMissingAbility(region, ..)
+            | TypeError::StructuralSpecialization { region, .. }
+            | TypeError::WrongSpecialization { region, .. } => Some(*region),
+            TypeError::CircularDef(cycle) => cycle.first().map(|entry| entry.symbol_region),
+            TypeError::UnfulfilledAbility(Unfulfilled::OpaqueUnderivable {
+                derive_region, ..
+            }) => Some(*derive_region),
+            TypeError::UnfulfilledAbility(_) => None,
+            TypeError::Exhaustive(roc_exhaustive::Error::Incomplete(region, ..)) => Some(*region),
+            TypeError::Exhaustive(roc_exhaustive::Error::Redundant { branch_region, .. }) => {
+                Some(*branch_region)
+            }
+            TypeError::BadType(problem) => problem.region(),
+            TypeError::UnexposedLookup(_) => None,
+        }
+    }
+}
+
 #[derive(PartialEq, Debug, Clone)]
 pub enum Unfulfilled {
     /// No claimed implementation of an ability for an opaque type.
@@ -28,6 +28,20 @@ test_key_eq! {
     explicit_empty_record_and_implicit_empty_record:
         v!(EMPTY_RECORD), v!({})
 
+    same_tag_union:
+        v!([ A v!(U8) v!(STR), B v!(STR) ]), v!([ A v!(U8) v!(STR), B v!(STR) ])
+    same_tag_union_tags_diff_types:
+        v!([ A v!(U8) v!(U8), B v!(U8) ]), v!([ A v!(STR) v!(STR), B v!(STR) ])
+    same_tag_union_tags_any_order:
+        v!([ A v!(U8) v!(U8), B v!(U8), C ]), v!([ C, B v!(STR), A v!(STR) v!(STR) ])
+    explicit_empty_tag_union_and_implicit_empty_tag_union:
+        v!(EMPTY_TAG_UNION), v!([])
+
+    same_recursive_tag_union:
+        v!([ Nil, Cons v!(^lst)] as lst), v!([ Nil, Cons v!(^lst)] as lst)
+    same_tag_union_and_recursive_tag_union_fields:
+        v!([ Nil, Cons v!(STR)]), v!([ Nil, Cons v!(^lst)] as lst)
+
     list_list_diff_types:
         v!(Symbol::LIST_LIST v!(STR)), v!(Symbol::LIST_LIST v!(U8))
     str_str:
@@ -41,6 +55,15 @@ test_key_neq! {
         v!({ a: v!(U8), }), v!({ b: v!(U8), })
     record_empty_vs_nonempty:
         v!(EMPTY_RECORD), v!({ a: v!(U8), })
+
+    different_tag_union_tags:
+        v!([ A v!(U8) ]), v!([ B v!(U8) ])
+    different_tag_union_tag_arities:
+        v!([ A v!(U8) ]), v!([ A v!(U8) v!(U8) ])
+    tag_union_empty_vs_nonempty:
+        v!(EMPTY_TAG_UNION), v!([ B v!(U8) ])
+    different_recursive_tag_union_tags:
+        v!([ Nil, Cons v!(^lst) ] as lst), v!([ Nil, Next v!(^lst) ] as lst)
 }
 
 #[test]
@@ -146,3 +169,172 @@ fn record_2_fields() {
         )
     })
 }
+
+#[test]
+fn tag_two_labels_no_payloads() {
+    derive_test(Decoder, v!([A, B]), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [A, B]
+        # Decoder [A, B] fmt | fmt has DecoderFormatting
+        # List U8, fmt -[[custom(10)]]-> { rest : List U8, result : [Err [TooShort], Ok [A, B]] } | fmt has DecoderFormatting
+        # Specialization lambda sets:
+        #   @<1>: [[custom(10)]]
+        #Derived.decoder_[A 0,B 0] =
+          Decode.custom
+            \#Derived.bytes, #Derived.fmt ->
+              Decode.decodeWith
+                #Derived.bytes
+                (Decode.tag
+                  \#Derived.tagName ->
+                    when #Derived.tagName is
+                      "A" -> Keep A
+                      "B" -> Keep B
+                      _ -> Skip
+                  \#Derived.state, #Derived.index ->
+                    when #Derived.state is
+                      A -> TooLong
+                      B -> TooLong
+                  \#Derived.state2 ->
+                    when #Derived.state2 is
+                      A -> Ok A
+                      B -> Ok B)
+                #Derived.fmt
+        "###
+        )
+    })
+}
+
+#[test]
+fn tag_two_labels_with_payloads() {
+    derive_test(Decoder, v!([A v!(STR), B v!(U8)]), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [A Str, B U8]
+        # Decoder [A val, B val1] fmt | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # List U8, fmt -[[custom(26)]]-> { rest : List U8, result : [Err [TooShort], Ok [A val, B val1]] } | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # Specialization lambda sets:
+        #   @<1>: [[custom(26)]]
+        #Derived.decoder_[A 1,B 1] =
+          Decode.custom
+            \#Derived.bytes3, #Derived.fmt3 ->
+              Decode.decodeWith
+                #Derived.bytes3
+                (Decode.tag
+                  \#Derived.tagName ->
+                    when #Derived.tagName is
+                      "A" -> Keep (A (Err NoField))
+                      "B" -> Keep (B (Err NoField))
+                      _ -> Skip
+                  \#Derived.state, #Derived.index ->
+                    when #Derived.state is
+                      A #Derived.s0 ->
+                        when #Derived.index is
+                          0 ->
+                            Next (Decode.custom
+                              \#Derived.bytes, #Derived.fmt ->
+                                when Decode.decodeWith
+                                    #Derived.bytes
+                                    Decode.decoder
+                                    #Derived.fmt is
+                                  #Derived.rec ->
+                                    {
+                                      result: when #Derived.rec.result is
+                                          Ok #Derived.val -> Ok (A (Ok #Derived.val))
+                                          Err #Derived.err -> Err #Derived.err,
+                                      rest: #Derived.rec.rest
+                                    })
+                          _ -> TooLong
+                      B #Derived.s02 ->
+                        when #Derived.index is
+                          0 ->
+                            Next (Decode.custom
+                              \#Derived.bytes2, #Derived.fmt2 ->
+                                when Decode.decodeWith
+                                    #Derived.bytes2
+                                    Decode.decoder
+                                    #Derived.fmt2 is
+                                  #Derived.rec2 ->
+                                    {
+                                      result: when #Derived.rec2.result is
+                                          Ok #Derived.val2 -> Ok (B (Ok #Derived.val2))
+                                          Err #Derived.err2 -> Err #Derived.err2,
+                                      rest: #Derived.rec2.rest
+                                    })
+                          _ -> TooLong
+                  \#Derived.state2 ->
+                    when #Derived.state2 is
+                      A (Ok #Derived.p0) -> Ok (A #Derived.p0)
+                      B (Ok #Derived.p02) -> Ok (B #Derived.p02)
+                      _ -> Err TooShort)
+                #Derived.fmt3
+        "###
+        )
+    })
+}
+
+#[test]
+fn recursive_tag_union() {
+    derive_test(Decoder, v!([Nil, Cons v!(U8) v!(^lst)] as lst), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for [Cons U8 $rec, Nil] as $rec
+        # Decoder [Cons val val1, Nil] fmt | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # List U8, fmt -[[custom(26)]]-> { rest : List U8, result : [Err [TooShort], Ok [Cons val val1, Nil]] } | fmt has DecoderFormatting, val has Decoding, val1 has Decoding
+        # Specialization lambda sets:
+        #   @<1>: [[custom(26)]]
+        #Derived.decoder_[Cons 2,Nil 0] =
+          Decode.custom
+            \#Derived.bytes3, #Derived.fmt3 ->
+              Decode.decodeWith
+                #Derived.bytes3
+                (Decode.tag
+                  \#Derived.tagName ->
+                    when #Derived.tagName is
+                      "Cons" -> Keep (Cons (Err NoField) (Err NoField))
+                      "Nil" -> Keep Nil
+                      _ -> Skip
+                  \#Derived.state, #Derived.index ->
+                    when #Derived.state is
+                      Cons #Derived.s0 #Derived.s1 ->
+                        when #Derived.index is
+                          0 ->
+                            Next (Decode.custom
+                              \#Derived.bytes, #Derived.fmt ->
+                                when Decode.decodeWith
+                                    #Derived.bytes
+                                    Decode.decoder
+                                    #Derived.fmt is
+                                  #Derived.rec ->
+                                    {
+                                      result: when #Derived.rec.result is
+                                          Ok #Derived.val ->
+                                            Ok (Cons (Ok #Derived.val) #Derived.s1)
+                                          Err #Derived.err -> Err #Derived.err,
+                                      rest: #Derived.rec.rest
+                                    })
+                          1 ->
+                            Next (Decode.custom
+                              \#Derived.bytes2, #Derived.fmt2 ->
+                                when Decode.decodeWith
+                                    #Derived.bytes2
+                                    Decode.decoder
+                                    #Derived.fmt2 is
+                                  #Derived.rec2 ->
+                                    {
+                                      result: when #Derived.rec2.result is
+                                          Ok #Derived.val2 ->
+                                            Ok (Cons #Derived.s0 (Ok #Derived.val2))
+                                          Err #Derived.err2 -> Err #Derived.err2,
+                                      rest: #Derived.rec2.rest
+                                    })
+                          _ -> TooLong
+                      Nil -> TooLong
+                  \#Derived.state2 ->
+                    when #Derived.state2 is
+                      Cons (Ok #Derived.p0) (Ok #Derived.p1) ->
+                        Ok (Cons #Derived.p0 #Derived.p1)
+                      Nil -> Ok Nil
+                      _ -> Err TooShort)
+                #Derived.fmt3
+        "###
+        )
+    })
+}
@@ -0,0 +1,153 @@
+#![cfg(test)]
+// Even with #[allow(non_snake_case)] on individual idents, rust-analyzer issues diagnostics.
+// See https://github.com/rust-lang/rust-analyzer/issues/6541.
+// For the `v!` macro we use uppercase variables when constructing tag unions.
+#![allow(non_snake_case)]
+
+use crate::{
+    test_key_eq, test_key_neq,
+    util::{check_immediate, check_underivable, derive_test},
+    v,
+};
+use insta::assert_snapshot;
+use roc_module::symbol::Symbol;
+use roc_types::subs::Variable;
+
+use roc_derive_key::{DeriveBuiltin::ToInspector, DeriveError};
+
+test_key_eq! {
+    ToInspector,
+
+    same_record:
+        v!({ a: v!(U8), }), v!({ a: v!(U8), })
+    same_record_fields_diff_types:
+        v!({ a: v!(U8), }), v!({ a: v!(STR), })
+    same_record_fields_any_order:
+        v!({ a: v!(U8), b: v!(U8), c: v!(U8), }),
+        v!({ c: v!(U8), a: v!(U8), b: v!(U8), })
+    explicit_empty_record_and_implicit_empty_record:
+        v!(EMPTY_RECORD), v!({})
+
+    same_tag_union:
+        v!([ A v!(U8) v!(STR), B v!(STR) ]), v!([ A v!(U8) v!(STR), B v!(STR) ])
+    same_tag_union_tags_diff_types:
+        v!([ A v!(U8) v!(U8), B v!(U8) ]), v!([ A v!(STR) v!(STR), B v!(STR) ])
+    same_tag_union_tags_any_order:
+        v!([ A v!(U8) v!(U8), B v!(U8), C ]), v!([ C, B v!(STR), A v!(STR) v!(STR) ])
+    explicit_empty_tag_union_and_implicit_empty_tag_union:
+        v!(EMPTY_TAG_UNION), v!([])
+
+    same_recursive_tag_union:
+        v!([ Nil, Cons v!(^lst)] as lst), v!([ Nil, Cons v!(^lst)] as lst)
+    same_tag_union_and_recursive_tag_union_fields:
+        v!([ Nil, Cons v!(STR)]), v!([ Nil, Cons v!(^lst)] as lst)
+
+    list_list_diff_types:
+        v!(Symbol::LIST_LIST v!(STR)), v!(Symbol::LIST_LIST v!(U8))
+
+    alias_eq_real_type:
+        v!(Symbol::BOOL_BOOL => v!([ True, False ])), v!([False, True])
+    opaque_eq_real_type:
+        v!(@Symbol::BOOL_BOOL => v!([ True, False ])), v!([False, True])
+}
+
+test_key_neq! {
+    ToInspector,
+
+    different_record_fields:
+        v!({ a: v!(U8), }), v!({ b: v!(U8), })
+    record_empty_vs_nonempty:
+        v!(EMPTY_RECORD), v!({ a: v!(U8), })
+
+    different_tag_union_tags:
+        v!([ A v!(U8) ]), v!([ B v!(U8) ])
+    tag_union_empty_vs_nonempty:
+        v!(EMPTY_TAG_UNION), v!([ B v!(U8) ])
+    different_recursive_tag_union_tags:
+        v!([ Nil, Cons v!(^lst) ] as lst), v!([ Nil, Next v!(^lst) ] as lst)
+}
+
+#[test]
+fn immediates() {
+    check_immediate(ToInspector, v!(U8), Symbol::INSPECT_U8);
+    check_immediate(ToInspector, v!(U16), Symbol::INSPECT_U16);
+    check_immediate(ToInspector, v!(U32), Symbol::INSPECT_U32);
+    check_immediate(ToInspector, v!(U64), Symbol::INSPECT_U64);
+    check_immediate(ToInspector, v!(U128), Symbol::INSPECT_U128);
+    check_immediate(ToInspector, v!(I8), Symbol::INSPECT_I8);
+    check_immediate(ToInspector, v!(I16), Symbol::INSPECT_I16);
+    check_immediate(ToInspector, v!(I32), Symbol::INSPECT_I32);
+    check_immediate(ToInspector, v!(I64), Symbol::INSPECT_I64);
+    check_immediate(ToInspector, v!(I128), Symbol::INSPECT_I128);
+    check_immediate(ToInspector, v!(NAT), Symbol::INSPECT_NAT);
+    check_immediate(ToInspector, v!(DEC), Symbol::INSPECT_DEC);
+    check_immediate(ToInspector, v!(F32), Symbol::INSPECT_F32);
+    check_immediate(ToInspector, v!(F64), Symbol::INSPECT_F64);
+    check_immediate(ToInspector, v!(STR), Symbol::INSPECT_STR);
+}
+
+#[test]
+fn set_underivable() {
+    check_underivable(
+        ToInspector,
+        v!(Symbol::SET_SET v!(STR)),
+        DeriveError::Underivable,
+    );
+}
+
+#[test]
+fn one_field_record() {
+    derive_test(ToInspector, v!({ a: v!(U8), }), |golden| {
+        assert_snapshot!(golden, @r###"
+        # derived for { a : U8 }
+        # { a : val } -[[toInspector_{a}(0)]]-> Inspector f | f has InspectFormatter, val has Inspect
+        # { a : val } -[[toInspector_{a}(0)]]-> (f -[[custom(2) { a : val }]]-> f) | f has InspectFormatter, val has Inspect
+        # Specialization lambda sets:
+        #   @<1>: [[toInspector_{a}(0)]]
+        #   @<2>: [[custom(2) { a : val }]] | val has Inspect
+        #Derived.toInspector_{a} =
+          \#Derived.rcd ->
+            Inspect.custom
+              \#Derived.fmt ->
+                Inspect.apply
+                  (Inspect.record [{ value: Inspect.toInspector #Derived.rcd.a, key: "a" }])
+                  #Derived.fmt
+        "###
+        )
+    })
+}
+
+#[test]
+fn tag_two_labels() {
+    derive_test(
+        ToInspector,
+        v!([A v!(U8) v!(STR) v!(U16), B v!(STR)]),
+        |golden| {
+            assert_snapshot!(golden, @r###"
+        # derived for [A U8 Str U16, B Str]
+        # [A val val1 val1, B val1] -[[toInspector_[A 3,B 1](0)]]-> Inspector f | f has InspectFormatter, val has Inspect, val1 has Inspect
+        # [A val val1 val1, B val1] -[[toInspector_[A 3,B 1](0)]]-> (f -[[custom(6) [A val val1 val1, B val1]]]-> f) | f has InspectFormatter, val has Inspect, val1 has Inspect
+        # Specialization lambda sets:
+        #   @<1>: [[toInspector_[A 3,B 1](0)]]
+        #   @<2>: [[custom(6) [A val val1 val1, B val1]]] | val has Inspect, val1 has Inspect
+        #Derived.toInspector_[A 3,B 1] =
+          \#Derived.tag ->
+            Inspect.custom
+              \#Derived.fmt ->
+                Inspect.apply
+                  (when #Derived.tag is
+                    A #Derived.2 #Derived.3 #Derived.4 ->
+                      Inspect.tag
+                        "A"
+                        [
+                          Inspect.toInspector #Derived.2,
+                          Inspect.toInspector #Derived.3,
+                          Inspect.toInspector #Derived.4,
+                        ]
+                    B #Derived.5 -> Inspect.tag "B" [Inspect.toInspector #Derived.5])
+                  #Derived.fmt
+        "###
+            )
+        },
+    )
+}
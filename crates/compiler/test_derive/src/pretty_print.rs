@@ -2,8 +2,8 @@
 
 use roc_can::def::Def;
 use roc_can::expr::Expr::{self, *};
-use roc_can::expr::{ClosureData, OpaqueWrapFunctionData, WhenBranch};
-use roc_can::pattern::{Pattern, RecordDestruct};
+use roc_can::expr::{ClosureData, OpaqueWrapFunctionData, TupleAccessorData, WhenBranch};
+use roc_can::pattern::{ListPatterns, Pattern, RecordDestruct, TupleDestruct};
 
 use roc_module::symbol::Interns;
 use ven_pretty::{Arena, DocAllocator, DocBuilder};
@@ -206,6 +206,27 @@ fn expr<'a>(c: &Ctx, p: EPrec, f: &'a Arena<'a>, e: &'a Expr) -> DocBuilder<'a,
             f.text(format!("@{}", opaque_name.as_str(c.interns)))
         }
         Accessor(_) => todo!(),
+        Tuple { elems, .. } => f
+            .reflow("(")
+            .append(
+                f.intersperse(
+                    elems
+                        .iter()
+                        .map(|(_, elem)| f.line().append(expr(c, Free, f, &elem.value))),
+                    f.reflow(","),
+                )
+                .nest(2)
+                .group(),
+            )
+            .append(f.line())
+            .append(f.text(")"))
+            .group(),
+        TupleAccess {
+            loc_expr, index, ..
+        } => expr(c, AppArg, f, &loc_expr.value)
+            .append(f.text(format!(".{}", index)))
+            .group(),
+        TupleAccessor(TupleAccessorData { index, .. }) => f.text(format!(".{}", index)),
         Update {
             symbol, updates, ..
         } => f
@@ -257,6 +278,15 @@ fn expr<'a>(c: &Ctx, p: EPrec, f: &'a Arena<'a>, e: &'a Expr) -> DocBuilder<'a,
         ZeroArgumentTag { .. } => todo!(),
         OpaqueRef { .. } => todo!(),
         Expect { .. } => todo!(),
+        Dbg { loc_expr, .. } => maybe_paren!(
+            Free,
+            p,
+            f.text("dbg")
+                .append(f.space())
+                .append(expr(c, AppArg, f, &loc_expr.value))
+                .group()
+        ),
+        Crash { .. } => todo!(),
         TypedHole(_) => todo!(),
         RuntimeError(_) => todo!(),
     }
@@ -361,6 +391,40 @@ fn pattern<'a>(
             )
             .append(f.text("}"))
             .group(),
+        TupleDestructure { destructs, .. } => f
+            .text("(")
+            .append(
+                f.intersperse(
+                    destructs
+                        .iter()
+                        .map(|l| &l.value)
+                        .map(|TupleDestruct { typ: (_, p), .. }| pattern(c, Free, f, &p.value)),
+                    f.text(", "),
+                ),
+            )
+            .append(f.text(")"))
+            .group(),
+        List { patterns, .. } => {
+            let ListPatterns { patterns, opt_rest } = patterns;
+
+            let mut elems = patterns
+                .iter()
+                .map(|p| pattern(c, Free, f, &p.value))
+                .collect::<Vec<_>>();
+
+            if let Some((index, opt_name)) = opt_rest {
+                let rest = match opt_name {
+                    Some(name) => f.text(format!("..{}", name.value.as_str(c.interns))),
+                    None => f.text(".."),
+                };
+                elems.insert(*index, rest);
+            }
+
+            f.text("[")
+                .append(f.intersperse(elems, f.text(", ")))
+                .append(f.text("]"))
+                .group()
+        }
         NumLiteral(_, n, _, _) | IntLiteral(_, _, n, _, _) | FloatLiteral(_, _, n, _, _) => {
             f.text(&**n)
         }
@@ -2,6 +2,8 @@
 
 mod decoding;
 mod encoding;
+mod hash;
+mod inspect;
 
 mod pretty_print;
 mod util;
@@ -8,6 +8,7 @@ use std::process::Command;
 use wasi_libc_sys::{WASI_COMPILER_RT_PATH, WASI_LIBC_PATH};
 
 const PLATFORM_FILENAME: &str = "wasm_test_platform";
+const DEV_PLATFORM_FILENAME: &str = "dev_test_platform";
 
 fn main() {
     println!("cargo:rerun-if-changed=build.rs");
@@ -15,6 +16,9 @@ fn main() {
         build_wasm_test_host();
         build_wasm_linking_test_host();
     }
+    if feature_is_enabled("gen-dev") {
+        build_dev_test_host();
+    }
 }
 
 const fn object_file_extension() -> &'static str {
@@ -120,6 +124,30 @@ fn zig_executable() -> String {
     }
 }
 
+fn build_dev_test_host() {
+    let mut source_path = PathBuf::new()
+        .join("src")
+        .join("helpers")
+        .join(DEV_PLATFORM_FILENAME);
+    source_path.set_extension("c");
+    println!("cargo:rerun-if-changed={}", source_path.to_str().unwrap());
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let mut outfile = PathBuf::from(&out_dir).join(DEV_PLATFORM_FILENAME);
+    outfile.set_extension("o");
+
+    // The test dylibs are built from this, so it must be position independent.
+    run_zig(&[
+        "build-obj",
+        "-lc",
+        "-fPIC",
+        "-O",
+        "ReleaseSafe",
+        source_path.to_str().unwrap(),
+        &format!("-femit-bin={}", outfile.to_str().unwrap()),
+    ]);
+}
+
 fn build_wasm_platform(out_dir: &str, source_path: &str) -> PathBuf {
     let mut outfile = PathBuf::from(out_dir).join(PLATFORM_FILENAME);
     outfile.set_extension("o");
@@ -1010,3 +1010,445 @@ fn decode_empty_record() {
         RocStr
     )
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn decode_tag_with_payload() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            Pet : [Dog Str U8, Cat Str]
+
+            decodePet : List U8 -> Result Pet [Leftover (List U8), TooShort]
+            decodePet = \bytes -> Decode.fromBytes bytes Json.fromUtf8
+
+            main =
+                when Str.toUtf8 "{\"Dog\":[\"Rex\",3]}" |> decodePet is
+                    Ok (Dog name age) -> Str.concat name (Num.toStr age)
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("Rex3"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn decode_tag_without_payload() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            Color : [Red, Green, Blue]
+
+            decodeColor : List U8 -> Result Color [Leftover (List U8), TooShort]
+            decodeColor = \bytes -> Decode.fromBytes bytes Json.fromUtf8
+
+            main =
+                when Str.toUtf8 "{\"Green\":[]}" |> decodeColor is
+                    Ok Green -> "green"
+                    _ -> "something went wrong"
+            "#
+        ),
+        RocStr::from("green"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn decode_tag_unknown_name() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            Color : [Red, Green, Blue]
+
+            decodeColor : List U8 -> Result Color [Leftover (List U8), TooShort]
+            decodeColor = \bytes -> Decode.fromBytes bytes Json.fromUtf8
+
+            main =
+                when Str.toUtf8 "{\"Purple\":[]}" |> decodeColor is
+                    Ok _ -> "decoded an unknown tag"
+                    Err _ -> "rejected"
+            "#
+        ),
+        RocStr::from("rejected"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn decode_recursive_tag_union() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            LinkedList : [Nil, Cons U8 LinkedList]
+
+            decodeList : List U8 -> Result LinkedList [Leftover (List U8), TooShort]
+            decodeList = \bytes -> Decode.fromBytes bytes Json.fromUtf8
+
+            sum : LinkedList -> U8
+            sum = \list ->
+                when list is
+                    Nil -> 0
+                    Cons x rest -> x + sum rest
+
+            main =
+                when Str.toUtf8 "{\"Cons\":[1,{\"Cons\":[2,{\"Nil\":[]}]}]}" |> decodeList is
+                    Ok list -> sum list
+                    _ -> 0
+            "#
+        ),
+        3,
+        u8
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn encode_then_decode_tag_union() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Encode, Decode, Json] provides [main] to "./platform"
+
+            Shape : [Circle U8, Rect U8 U8]
+
+            decodeShape : List U8 -> Result Shape [Leftover (List U8), TooShort]
+            decodeShape = \bytes -> Decode.fromBytes bytes Json.fromUtf8
+
+            shape : Shape
+            shape = Rect 2 5
+
+            main =
+                when Encode.toBytes shape Json.toUtf8 |> decodeShape is
+                    Ok (Rect w h) -> w * h
+                    _ -> 0
+            "#
+        ),
+        10,
+        u8
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn inspect_record_with_list_and_tags() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Inspect] provides [main] to "./platform"
+
+            main = Inspect.toDbgStr { name: "Rex", age: 3u8, tags: [Good, Dog], owner: {} }
+            "#
+        ),
+        RocStr::from(r#"{ age: 3, name: "Rex", owner: {}, tags: [Good, Dog] }"#),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn inspect_nested_tags() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Inspect] provides [main] to "./platform"
+
+            LinkedList : [Nil, Cons I64 LinkedList]
+
+            list : LinkedList
+            list = Cons 1 (Cons -2 Nil)
+
+            main = Inspect.toDbgStr list
+            "#
+        ),
+        RocStr::from("Cons 1 (Cons -2 Nil)"),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn inspect_opaque_custom_impl() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Inspect.{ Inspect }] provides [main] to "./platform"
+
+            Password := Str has [Inspect {toInspector: passwordToInspector}]
+
+            passwordToInspector = \@Password _ -> Inspect.opaque "Password" (Inspect.str "***")
+
+            main = Inspect.toDbgStr { user: "rex", password: @Password "hunter2" }
+            "#
+        ),
+        RocStr::from(r#"{ password: @Password "***", user: "rex" }"#),
+        RocStr
+    )
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn inspect_custom_formatter() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" imports [Inspect.{ InspectFormatter }] provides [main] to "./platform"
+
+            # Renders values as JSON
+            JsonFormatter := Str has [
+                     InspectFormatter {
+                         init: jsonInit,
+                         u8: jsonU8,
+                         u16: jsonU16,
+                         u32: jsonU32,
+                         u64: jsonU64,
+                         u128: jsonU128,
+                         i8: jsonI8,
+                         i16: jsonI16,
+                         i32: jsonI32,
+                         i64: jsonI64,
+                         i128: jsonI128,
+                         nat: jsonNat,
+                         f32: jsonF32,
+                         f64: jsonF64,
+                         dec: jsonDec,
+                         str: jsonStr,
+                         list: jsonList,
+                         record: jsonRecord,
+                         tag: jsonTag,
+                         opaque: jsonOpaque,
+                     },
+                 ]
+
+            jsonInit = \{} -> @JsonFormatter ""
+
+            write = \@JsonFormatter data, added -> @JsonFormatter (Str.concat data added)
+
+            writeSeparated = \fmt, items, writeItem ->
+                walked = List.walk items { buffer: fmt, index: 0 } \{ buffer, index }, item ->
+                    withSeparator = if index > 0 then write buffer "," else buffer
+
+                    { buffer: writeItem withSeparator item, index: index + 1 }
+
+                walked.buffer
+
+            jsonU8 = \n -> Inspect.custom \fmt -> write fmt (Num.toStr n)
+            jsonU16 = \n -> Inspect.custom \fmt -> write fmt (Num.toStr n)
+            jsonU32 = \n -> Inspect.custom \fmt -> write fmt (Num.toStr n)
+            jsonU64 = \n -> Inspect.custom \fmt -> write fmt (Num.toStr n)
+            jsonU128 = \n -> Inspect.custom \fmt -> write fmt (Num.toStr n)
+            jsonI8 = \n -> Inspect.custom \fmt -> write fmt (Num.toStr n)
+            jsonI16 = \n -> Inspect.custom \fmt -> write fmt (Num.toStr n)
+            jsonI32 = \n -> Inspect.custom \fmt -> write fmt (Num.toStr n)
+            jsonI64 = \n -> Inspect.custom \fmt -> write fmt (Num.toStr n)
+            jsonI128 = \n -> Inspect.custom \fmt -> write fmt (Num.toStr n)
+            jsonNat = \n -> Inspect.custom \fmt -> write fmt (Num.toStr n)
+            jsonF32 = \n -> Inspect.custom \fmt -> write fmt (Num.toStr n)
+            jsonF64 = \n -> Inspect.custom \fmt -> write fmt (Num.toStr n)
+            jsonDec = \n -> Inspect.custom \fmt -> write fmt (Num.toStr n)
+
+            jsonStr = \s -> Inspect.custom \fmt -> write fmt "\"\(s)\""
+
+            jsonList = \content, toElemInspector ->
+                Inspect.custom \fmt ->
+                    write fmt "["
+                    |> writeSeparated content (\buffer, elem -> Inspect.apply (toElemInspector elem) buffer)
+                    |> write "]"
+
+            jsonRecord = \fields ->
+                Inspect.custom \fmt ->
+                    write fmt "{"
+                    |> writeSeparated fields (\buffer, { key, value } -> Inspect.apply value (write buffer "\"\(key)\":"))
+                    |> write "}"
+
+            jsonTag = \name, payload ->
+                Inspect.custom \fmt ->
+                    if List.isEmpty payload then
+                        write fmt "\"\(name)\""
+                    else
+                        write fmt "{\"\(name)\":["
+                        |> writeSeparated payload (\buffer, inspector -> Inspect.apply inspector buffer)
+                        |> write "]}"
+
+            jsonOpaque = \_name, inspector -> Inspect.custom \fmt -> Inspect.apply inspector fmt
+
+            jsonData = \@JsonFormatter data -> data
+
+            toJson = \val ->
+                fmt : JsonFormatter
+                fmt = Inspect.inspect val
+
+                jsonData fmt
+
+            main = toJson { name: "Rex", age: 3u8, tags: [Good, Dog], owner: Person "Alice" 30u8 }
+            "#
+        ),
+        RocStr::from(
+            r#"{"age":3,"name":"Rex","owner":{"Person":["Alice",30]},"tags":["Good","Dog"]}"#
+        ),
+        RocStr
+    )
+}
+
+#[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
+mod hash {
+    #[cfg(feature = "gen-llvm")]
+    use crate::helpers::llvm::assert_evals_to;
+
+    #[cfg(feature = "gen-wasm")]
+    use crate::helpers::wasm::assert_evals_to;
+
+    use indoc::{formatdoc, indoc};
+
+    // A small order-sensitive hasher, so that values which hash equal under it
+    // almost certainly fed the same sequence of integers to the hasher.
+    const TEST_HASHER: &str = indoc!(
+        r#"
+        THasher := U64 has [Hasher {
+            addBytes: tAddBytes,
+            addU8: tAddU8,
+            addU16: tAddU16,
+            addU32: tAddU32,
+            addU64: tAddU64,
+            addU128: tAddU128,
+            complete: tComplete,
+        }]
+
+        mix : THasher, U64 -> THasher
+        mix = \@THasher h, n -> @THasher (Num.addWrap (Num.mulWrap h 31) n)
+
+        tAddBytes = \h, bytes -> List.walk bytes h \acc, b -> mix acc (Num.toU64 b)
+        tAddU8 = \h, n -> mix h (Num.toU64 n)
+        tAddU16 = \h, n -> mix h (Num.toU64 n)
+        tAddU32 = \h, n -> mix h (Num.toU64 n)
+        tAddU64 = \h, n -> mix h n
+        tAddU128 = \h, n -> mix h (Num.toU64 n)
+        tComplete = \@THasher h -> h
+
+        hashOf = \value -> Hash.hash (@THasher 7) value |> Hash.complete
+        "#
+    );
+
+    fn build_test(input: &str) -> String {
+        formatdoc!(
+            r#"
+            app "test" imports [Hash.{{ Hasher }}] provides [main] to "./platform"
+
+            {}
+
+            {}
+            "#,
+            TEST_HASHER,
+            input
+        )
+    }
+
+    #[test]
+    fn derived_record() {
+        assert_evals_to!(
+            &build_test(indoc!(
+                r#"
+                main =
+                    r1 = { a: 1u8, b: "hello" }
+                    r2 = { a: 1u8, b: "hello" }
+                    r3 = { a: 1u8, b: "hellp" }
+
+                    hashOf r1 == hashOf r2 && hashOf r1 != hashOf r3
+                "#
+            )),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn derived_nested_record() {
+        assert_evals_to!(
+            &build_test(indoc!(
+                r#"
+                main =
+                    r1 = { outer: { inner: 3u16 }, xs: [1u32, 2] }
+                    r2 = { outer: { inner: 3u16 }, xs: [1u32, 2] }
+                    r3 = { outer: { inner: 3u16 }, xs: [2u32, 1] }
+
+                    hashOf r1 == hashOf r2 && hashOf r1 != hashOf r3
+                "#
+            )),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn derived_tag_union() {
+        assert_evals_to!(
+            &build_test(indoc!(
+                r#"
+                t : [A U8 Str, B Str] -> [A U8 Str, B Str]
+                t = \x -> x
+
+                main =
+                    a1 = t (A 1u8 "x")
+                    a2 = t (A 1u8 "x")
+                    b = t (B "x")
+
+                    hashOf a1 == hashOf a2 && hashOf a1 != hashOf b
+                "#
+            )),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn derived_tag_union_no_payloads() {
+        assert_evals_to!(
+            &build_test(indoc!(
+                r#"
+                t : [Red, Green, Blue] -> [Red, Green, Blue]
+                t = \x -> x
+
+                main =
+                    hashOf (t Green) == hashOf (t Green) && hashOf (t Green) != hashOf (t Blue)
+                "#
+            )),
+            true,
+            bool
+        )
+    }
+
+    #[test]
+    fn derived_recursive_tag_union() {
+        assert_evals_to!(
+            &build_test(indoc!(
+                r#"
+                LinkedList : [Nil, Cons U8 LinkedList]
+
+                l1 : LinkedList
+                l1 = Cons 1 (Cons 2 Nil)
+
+                l2 : LinkedList
+                l2 = Cons 1 (Cons 2 Nil)
+
+                l3 : LinkedList
+                l3 = Cons 1 (Cons 3 Nil)
+
+                main = hashOf l1 == hashOf l2 && hashOf l1 != hashOf l3
+                "#
+            )),
+            true,
+            bool
+        )
+    }
+}
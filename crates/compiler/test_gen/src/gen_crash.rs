@@ -0,0 +1,85 @@
+//! `crash` in every backend. A crash stops the dev backend's code with a trap,
+//! so the dev tests run each program in a child process.
+
+#[cfg(feature = "gen-llvm")]
+use crate::helpers::llvm::assert_evals_to;
+
+#[cfg(feature = "gen-dev")]
+use crate::helpers::dev::assert_evals_to;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::assert_evals_to;
+
+#[cfg(feature = "gen-llvm")]
+use crate::helpers::llvm::assert_evals_to as assert_crashes;
+
+#[cfg(feature = "gen-dev")]
+use crate::helpers::dev::assert_evals_to_in_child as assert_crashes;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::assert_evals_to as assert_crashes;
+
+use indoc::indoc;
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn crash_in_untaken_when_branch() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            f : [Ok I64, Err Str] -> I64
+            f = \result ->
+                when result is
+                    Ok value -> value + 1
+                    Err msg -> crash msg
+
+            f (Ok 41)
+            "#
+        ),
+        42,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+#[should_panic(expected = r#"Roc failed with message: "not ok""#)]
+fn crash_in_when_branch() {
+    assert_crashes!(
+        indoc!(
+            r#"
+            f : [Ok I64, Err Str] -> I64
+            f = \result ->
+                when result is
+                    Ok value -> value + 1
+                    Err _ -> crash "not ok"
+
+            f (Err "oops")
+            "#
+        ),
+        0,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+#[should_panic(expected = r#"Roc failed with message: "too big: 42""#)]
+fn crash_with_runtime_message() {
+    assert_crashes!(
+        indoc!(
+            r#"
+            check : I64 -> I64
+            check = \n ->
+                if n > 10 then
+                    crash (Str.concat "too big: " (Num.toStr n))
+                else
+                    n
+
+            check 42
+            "#
+        ),
+        0,
+        i64
+    );
+}
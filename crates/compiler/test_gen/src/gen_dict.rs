@@ -1,10 +1,10 @@
-#![cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#![cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 
 #[cfg(feature = "gen-llvm")]
 use crate::helpers::llvm::assert_evals_to;
 
-// #[cfg(feature = "gen-dev")]
-// use crate::helpers::dev::assert_evals_to;
+#[cfg(feature = "gen-dev")]
+use crate::helpers::dev::assert_evals_to;
 
 #[cfg(feature = "gen-wasm")]
 use crate::helpers::wasm::assert_evals_to;
@@ -13,7 +13,7 @@ use indoc::indoc;
 use roc_std::{RocList, RocStr};
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn dict_empty_len() {
     assert_evals_to!(
         indoc!(
@@ -27,7 +27,7 @@ fn dict_empty_len() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn dict_insert_empty() {
     assert_evals_to!(
         indoc!(
@@ -43,7 +43,7 @@ fn dict_insert_empty() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn dict_empty_contains() {
     assert_evals_to!(
         indoc!(
@@ -60,7 +60,7 @@ fn dict_empty_contains() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn dict_nonempty_contains() {
     assert_evals_to!(
         indoc!(
@@ -77,7 +77,7 @@ fn dict_nonempty_contains() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn dict_empty_remove() {
     assert_evals_to!(
         indoc!(
@@ -96,7 +96,7 @@ fn dict_empty_remove() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn dict_nonempty_remove() {
     assert_evals_to!(
         indoc!(
@@ -116,7 +116,7 @@ fn dict_nonempty_remove() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn dict_nonempty_get() {
     assert_evals_to!(
         indoc!(
@@ -159,7 +159,7 @@ fn dict_nonempty_get() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn keys() {
     assert_evals_to!(
         indoc!(
@@ -181,7 +181,7 @@ fn keys() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn values() {
     assert_evals_to!(
         indoc!(
@@ -203,7 +203,7 @@ fn values() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn from_list_with_fold_simple() {
     assert_evals_to!(
         indoc!(
@@ -222,7 +222,7 @@ fn from_list_with_fold_simple() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn from_list_with_fold_reallocates() {
     assert_evals_to!(
         indoc!(
@@ -252,7 +252,7 @@ fn from_list_with_fold_reallocates() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn small_str_keys() {
     assert_evals_to!(
         indoc!(
@@ -274,7 +274,7 @@ fn small_str_keys() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn big_str_keys() {
     assert_evals_to!(
         indoc!(
@@ -299,7 +299,7 @@ fn big_str_keys() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn big_str_values() {
     assert_evals_to!(
         indoc!(
@@ -324,7 +324,7 @@ fn big_str_values() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn unit_values() {
     assert_evals_to!(
         indoc!(
@@ -346,7 +346,7 @@ fn unit_values() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn single() {
     assert_evals_to!(
         indoc!(
@@ -364,7 +364,7 @@ fn single() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn insert_all() {
     assert_evals_to!(
         indoc!(
@@ -383,7 +383,7 @@ fn insert_all() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn insert_all_prefer_first() {
     assert_evals_to!(
         indoc!(
@@ -402,7 +402,7 @@ fn insert_all_prefer_first() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn keep_shared() {
     assert_evals_to!(
         indoc!(
@@ -434,7 +434,7 @@ fn keep_shared() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn keep_shared_prefer_first() {
     assert_evals_to!(
         indoc!(
@@ -465,7 +465,7 @@ fn keep_shared_prefer_first() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn remove_all() {
     assert_evals_to!(
         indoc!(
@@ -497,7 +497,7 @@ fn remove_all() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn remove_all_prefer_first() {
     assert_evals_to!(
         indoc!(
@@ -528,7 +528,7 @@ fn remove_all_prefer_first() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn walk_sum_keys() {
     assert_evals_to!(
         indoc!(
@@ -549,3 +549,244 @@ fn walk_sum_keys() {
         i64
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn large_dict_insert_get() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            keys : List I64
+            keys = List.range 0 5000
+
+            dict : Dict.Dict I64 I64
+            dict = List.walk keys Dict.empty \d, k -> Dict.insert d k (k * 2)
+
+            found = List.all keys \k -> Dict.get dict k == Ok (k * 2)
+            missing = Dict.get dict (-1) == Err KeyNotFound
+
+            found && missing && Dict.len dict == List.len keys
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn large_dict_overwrite() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            keys : List I64
+            keys = List.range 0 2000
+
+            zeroes : Dict.Dict I64 I64
+            zeroes = List.walk keys Dict.empty \d, k -> Dict.insert d k 0
+
+            dict = List.walk keys zeroes \d, k -> Dict.insert d k k
+
+            Dict.len dict == List.len keys && Dict.values dict == keys
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn large_dict_remove() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            keys : List I64
+            keys = List.range 0 3000
+
+            isEven = \k -> Num.isMultipleOf k 2
+
+            full : Dict.Dict I64 I64
+            full = List.walk keys Dict.empty \d, k -> Dict.insert d k k
+
+            odds = List.walk keys full \d, k -> if isEven k then Dict.remove d k else d
+
+            oddsPresent = List.all keys \k -> Dict.contains odds k != isEven k
+            oddsFound = List.all keys \k -> isEven k || Dict.get odds k == Ok k
+
+            oddsPresent && oddsFound && Dict.len odds == List.len (List.keepIf keys \k -> !(isEven k))
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn large_dict_str_keys() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            keys : List Str
+            keys = List.map (List.range 0 1000) Num.toStr
+
+            dict : Dict.Dict Str Nat
+            dict = List.walk keys Dict.empty \d, k -> Dict.insert d k (Str.countUtf8Bytes k)
+
+            List.all keys (\k -> Dict.get dict k == Ok (Str.countUtf8Bytes k))
+                && Dict.keys dict == keys
+                && !(Dict.contains dict "not a number")
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn dict_eq_with_capacity() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            empty : Dict.Dict I64 I64
+            empty = Dict.empty
+
+            Dict.withCapacity 10 == empty
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn dict_eq_after_remove() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            empty : Dict.Dict I64 I64
+            empty = Dict.empty
+
+            empty == (Dict.single 1 1 |> Dict.remove 1)
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn dict_eq_different_history() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            small : Dict.Dict I64 I64
+            small = Dict.empty |> Dict.insert 1 1 |> Dict.insert 2 2
+
+            # grows the hash table well past what two entries need, then shrinks it back
+            large : Dict.Dict I64 I64
+            large = List.walk (List.range 1 21) Dict.empty \d, k -> Dict.insert d k k
+
+            shrunk = List.walk (List.reverse (List.range 3 21)) large Dict.remove
+
+            small == shrunk && small != large
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn dict_eq_after_remove_from_middle() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            # the last entry moves into the removed entry's spot, so the entries are 1, 5, 3, 4
+            removed : Dict.Dict I64 I64
+            removed = List.walk (List.range 1 6) Dict.empty (\d, k -> Dict.insert d k k) |> Dict.remove 2
+
+            built : Dict.Dict I64 I64
+            built = List.walk [1, 5, 3, 4] Dict.empty \d, k -> Dict.insert d k k
+
+            removed == built
+            "#
+        ),
+        true,
+        bool
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn record_keys() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            dict : Dict.Dict { x : I64, y : I64 } Str
+            dict =
+                Dict.empty
+                |> Dict.insert { x: 1, y: 2 } "a"
+                |> Dict.insert { x: 2, y: 1 } "b"
+                |> Dict.insert { x: 1, y: 2 } "c"
+
+            when Dict.get dict { x: 1, y: 2 } is
+                Ok value if Dict.len dict == 2 -> value
+                _ -> "wrong"
+            "#
+        ),
+        RocStr::from("c"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn tag_union_keys() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            dict : Dict.Dict [Red, Green, Blue] I64
+            dict =
+                Dict.empty
+                |> Dict.insert Red 1
+                |> Dict.insert Green 2
+                |> Dict.insert Blue 3
+                |> Dict.remove Green
+
+            Dict.get dict Blue |> Result.withDefault 0
+            "#
+        ),
+        3,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn tag_union_keys_with_payloads() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            dict : Dict.Dict [Circle I64, Square I64 I64] Str
+            dict =
+                Dict.empty
+                |> Dict.insert (Circle 1) "small circle"
+                |> Dict.insert (Square 1 2) "rectangle"
+                |> Dict.insert (Circle 2) "big circle"
+
+            if Dict.contains dict (Square 2 1) then
+                "wrong"
+            else
+                Dict.get dict (Circle 2) |> Result.withDefault "wrong"
+            "#
+        ),
+        RocStr::from("big circle"),
+        RocStr
+    );
+}
@@ -0,0 +1,96 @@
+//! Mono IR in the textual syntax of `roc_mono::debug::parse_procs`, fed straight to each
+//! backend. These cover the helpers that reproduce a backend bug from hand-written IR.
+
+#[cfg(feature = "gen-llvm")]
+use crate::helpers::llvm::assert_ir_evals_to;
+
+#[cfg(feature = "gen-dev")]
+use crate::helpers::dev::assert_ir_evals_to;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::assert_ir_evals_to;
+
+use indoc::indoc;
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn lowlevel_add() {
+    assert_ir_evals_to!(
+        indoc!(
+            r#"
+            procedure Test.0 () -> I64 {
+                let Test.1 : I64 = int 40;
+                let Test.2 : I64 = int 2;
+                let Test.3 : I64 = lowlevel NumAdd (Test.1, Test.2) mode 0;
+                ret Test.3;
+            }
+            "#
+        ),
+        42,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn call_and_switch() {
+    assert_ir_evals_to!(
+        indoc!(
+            r#"
+            procedure Test.0 () -> I64 {
+                let Test.1 : I64 = int 10;
+                let Test.2 : I64 = call Test.3 (Test.1) : (I64) -> I64 spec 0;
+                ret Test.2;
+            }
+
+            procedure Test.3 (Test.4 : I64) -> I64 {
+                let Test.5 : I64 = int 0;
+                let Test.6 : Bool = lowlevel Eq (Test.4, Test.5) mode 0;
+                switch Test.6 : Bool -> I64 {
+                    1 => {
+                        let Test.7 : I64 = int -1;
+                        ret Test.7;
+                    }
+                    default => {
+                        let Test.8 : I64 = lowlevel NumMul (Test.4, Test.4) mode 0;
+                        ret Test.8;
+                    }
+                }
+            }
+            "#
+        ),
+        100,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn jump_to_join_point() {
+    assert_ir_evals_to!(
+        indoc!(
+            r#"
+            procedure Test.0 () -> I64 {
+                let Test.1 : Bool = bool true;
+                join Test.2 (Test.3 : I64) {
+                    let Test.4 : I64 = int 1;
+                    let Test.5 : I64 = lowlevel NumAdd (Test.3, Test.4) mode 0;
+                    ret Test.5;
+                }
+                switch Test.1 : Bool -> I64 {
+                    1 => {
+                        let Test.6 : I64 = int 41;
+                        jump Test.2 (Test.6);
+                    }
+                    default => {
+                        let Test.7 : I64 = int 0;
+                        jump Test.2 (Test.7);
+                    }
+                }
+            }
+            "#
+        ),
+        42,
+        i64
+    );
+}
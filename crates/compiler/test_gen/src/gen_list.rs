@@ -898,11 +898,31 @@ fn list_walk_with_str() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_walk_subtraction() {
     assert_evals_to!(r#"List.walk [1, 2] 1 Num.sub"#, (1 - 1) - 2, i64);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_walk_record_state_with_captured_closure() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            scale = 10
+
+            result =
+                List.walk [1, 2, 3, 4] { sum: 0, count: 0 } \state, elem ->
+                    { sum: state.sum + elem * scale, count: state.count + 1 }
+
+            result.sum + result.count
+            "#
+        ),
+        104,
+        i64
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn list_walk_until_sum() {
@@ -960,7 +980,7 @@ fn list_walk_until_even_prefix_sum() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_keep_if_empty_list_of_int() {
     assert_evals_to!(
         indoc!(
@@ -978,7 +998,7 @@ fn list_keep_if_empty_list_of_int() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_keep_if_empty_list() {
     assert_evals_to!(
         indoc!(
@@ -997,7 +1017,7 @@ fn list_keep_if_empty_list() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_keep_if_always_true_for_non_empty_list() {
     assert_evals_to!(
         indoc!(
@@ -1019,7 +1039,7 @@ fn list_keep_if_always_true_for_non_empty_list() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_keep_if_always_false_for_non_empty_list() {
     assert_evals_to!(
         indoc!(
@@ -1037,7 +1057,7 @@ fn list_keep_if_always_false_for_non_empty_list() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_keep_if_one() {
     assert_evals_to!(
         indoc!(
@@ -1069,7 +1089,7 @@ fn list_keep_if_str_is_hello() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_map_on_empty_list_with_int_layout() {
     assert_evals_to!(
         indoc!(
@@ -1087,7 +1107,7 @@ fn list_map_on_empty_list_with_int_layout() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_map_on_non_empty_list() {
     assert_evals_to!(
         indoc!(
@@ -1105,7 +1125,7 @@ fn list_map_on_non_empty_list() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_map_changes_input() {
     assert_evals_to!(
         indoc!(
@@ -1123,7 +1143,7 @@ fn list_map_changes_input() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_map_on_big_list() {
     assert_evals_to!(
         indoc!(
@@ -1143,7 +1163,7 @@ fn list_map_on_big_list() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_map_with_type_change() {
     assert_evals_to!(
         indoc!(
@@ -1162,7 +1182,7 @@ fn list_map_with_type_change() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_map_using_defined_function() {
     assert_evals_to!(
         indoc!(
@@ -1219,7 +1239,7 @@ fn list_map_closure() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_map4_group() {
     assert_evals_to!(
         indoc!(
@@ -1233,7 +1253,7 @@ fn list_map4_group() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_map4_different_length() {
     assert_evals_to!(
         indoc!(
@@ -1252,7 +1272,7 @@ fn list_map4_different_length() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_map3_group() {
     assert_evals_to!(
         indoc!(
@@ -1266,7 +1286,7 @@ fn list_map3_group() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_map3_different_length() {
     assert_evals_to!(
         indoc!(
@@ -1284,7 +1304,7 @@ fn list_map3_different_length() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_map2_pair() {
     assert_evals_to!(
         indoc!(
@@ -2729,7 +2749,7 @@ fn list_range() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
 fn list_sort_with() {
     assert_evals_to!(
         "List.sortWith [] Num.compare",
@@ -2748,6 +2768,24 @@ fn list_sort_with() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_sort_with_captured_comparator() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            pivot = 5
+
+            distance = \x -> Num.abs (x - pivot)
+
+            List.sortWith [9, 0, 4, 7, 5, 2] \a, b -> Num.compare (distance a) (distance b)
+            "#
+        ),
+        RocList::from_slice(&[5, 4, 7, 2, 9, 0]),
+        RocList<i64>
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn list_sort_asc() {
@@ -3389,3 +3427,173 @@ fn list_let_generalization() {
         usize
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_pattern_exact_length() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            describe : List I64 -> I64
+            describe = \list ->
+                when list is
+                    [] -> 0
+                    [x] -> x
+                    [x, y] -> x + y
+                    _ -> -1
+
+            describe [] * 1000000 + describe [7] * 10000 + describe [1, 2] * 100 + describe [1, 2, 3]
+            "#
+        ),
+        70299,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_pattern_first_and_last() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            firstAndLast : List I64 -> I64
+            firstAndLast = \list ->
+                when list is
+                    [first, .., last] -> first * 10 + last
+                    _ -> -1
+
+            firstAndLast [1, 2, 3, 4, 5] * 1000 + firstAndLast [6, 7] * 10 + firstAndLast [8]
+            "#
+        ),
+        15669,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_pattern_index_from_end() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            secondToLast : List I64 -> I64
+            secondToLast = \list ->
+                when list is
+                    [.., x, _] -> x
+                    _ -> -1
+
+            secondToLast [1, 2, 3, 4] * 10 + secondToLast [5, 6] + secondToLast [9]
+            "#
+        ),
+        34,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_pattern_rest_binding() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            when [1, 2, 3, 4, 5] is
+                [_, ..rest] -> rest
+                [] -> []
+            "#
+        ),
+        RocList::from_slice(&[2, 3, 4, 5]),
+        RocList<i64>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_pattern_rest_binding_in_the_middle() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            when [1, 2, 3, 4, 5] is
+                [_, ..middle, _] -> middle
+                _ -> []
+            "#
+        ),
+        RocList::from_slice(&[2, 3, 4]),
+        RocList<i64>
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_pattern_empty_rest_binding() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            when [1, 2] is
+                [_, ..middle, _] -> List.len middle
+                _ -> 42
+            "#
+        ),
+        0,
+        usize
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_pattern_overlapping_lengths() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            classify : List I64 -> I64
+            classify = \list ->
+                when list is
+                    [1, ..] -> 1
+                    [_, _] -> 2
+                    [_, ..] -> 3
+                    [] -> 4
+
+            classify [1, 2] * 1000 + classify [2, 2] * 100 + classify [2, 2, 2] * 10 + classify []
+            "#
+        ),
+        1234,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_pattern_overlapping_lengths_with_literals() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            classify : List I64 -> I64
+            classify = \list ->
+                when list is
+                    [_, _, 3] -> 1
+                    [.., 3] -> 2
+                    [_, _, _] -> 3
+                    _ -> 4
+
+            classify [1, 2, 3] * 1000 + classify [3] * 100 + classify [1, 2, 4] * 10 + classify [1, 2, 4, 5]
+            "#
+        ),
+        1234,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm", feature = "gen-dev"))]
+fn list_pattern_strings() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            when ["a", "b", "c"] is
+                [.., last] -> last
+                [] -> ""
+            "#
+        ),
+        RocStr::from("c"),
+        RocStr
+    );
+}
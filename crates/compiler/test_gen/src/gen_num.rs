@@ -631,6 +631,40 @@ fn abs_min_int_overflow() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn i128_abs() {
+    assert_evals_to!("Num.abs -6i128", 6, i128);
+    assert_evals_to!("Num.abs 7i128", 7, i128);
+    assert_evals_to!("Num.abs Num.maxI128", i128::MAX, i128);
+    assert_evals_to!("Num.abs (Num.minI128 + 1)", -(i128::MIN + 1), i128);
+    assert_evals_to!("Num.abs 7u128", 7, u128);
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[should_panic(
+    expected = r#"Roc failed with message: "integer absolute overflowed because its argument is the minimum value"#
+)]
+fn abs_min_i128_overflow() {
+    assert_evals_to!("Num.abs Num.minI128", 0, i128);
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn dec_abs() {
+    assert_evals_to!(
+        "Num.abs -4.7dec",
+        RocDec::from_str_to_i128_unsafe("4.7"),
+        i128
+    );
+    assert_evals_to!(
+        "Num.abs 5.8dec",
+        RocDec::from_str_to_i128_unsafe("5.8"),
+        i128
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn gen_if_fn() {
@@ -1142,6 +1176,14 @@ fn gen_div_i64() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn gen_div_i128() {
+    assert_evals_to!("1000i128 // 10", 100, i128);
+    assert_evals_to!("-7i128 // 2", -3, i128);
+    assert_evals_to!("Num.maxU128 // 2", u128::MAX / 2, u128);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn gen_div_checked_i64() {
@@ -1188,6 +1230,14 @@ fn gen_rem_i64() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn gen_rem_i128() {
+    assert_evals_to!("Num.rem 8i128 3", 2, i128);
+    assert_evals_to!("Num.rem -8i128 3", -2, i128);
+    assert_evals_to!("Num.rem Num.maxU128 10", u128::MAX % 10, u128);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn gen_rem_checked_div_by_zero_i64() {
@@ -1309,6 +1359,19 @@ fn bitwise_or() {
     assert_evals_to!("Num.bitwiseOr 1 2", 3, i64);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn bitwise_i128() {
+    assert_evals_to!("Num.bitwiseAnd 25i128 10", 8, i128);
+    assert_evals_to!("Num.bitwiseXor 15i128 14", 1, i128);
+    assert_evals_to!("Num.bitwiseOr 1i128 2", 3, i128);
+    assert_evals_to!(
+        "Num.bitwiseAnd Num.maxU128 0xFFFF_0000_0000_0000_0000u128",
+        0xFFFF_0000_0000_0000_0000,
+        u128
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn lt_u8() {
@@ -1461,6 +1524,38 @@ fn gte_f64() {
     assert_evals_to!("0.0 >= 0.0", true, bool);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn lt_i128() {
+    assert_evals_to!("1i128 < 2i128", true, bool);
+    assert_evals_to!("1i128 < 1i128", false, bool);
+    assert_evals_to!("-1i128 < 1i128", true, bool);
+    assert_evals_to!("Num.maxI128 < Num.minI128", false, bool);
+    assert_evals_to!("1i128 <= 1i128", true, bool);
+    assert_evals_to!("2i128 <= 1i128", false, bool);
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn gt_u128() {
+    assert_evals_to!("2u128 > 1u128", true, bool);
+    assert_evals_to!("2u128 > 2u128", false, bool);
+    assert_evals_to!("Num.maxU128 > 0u128", true, bool);
+    assert_evals_to!("1u128 >= 1u128", true, bool);
+    assert_evals_to!("1u128 >= 2u128", false, bool);
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn lt_gt_dec() {
+    assert_evals_to!("1.1dec < 1.2dec", true, bool);
+    assert_evals_to!("-1.2dec < -1.1dec", true, bool);
+    assert_evals_to!("1.1dec <= 1.1dec", true, bool);
+    assert_evals_to!("1.2dec > 1.1dec", true, bool);
+    assert_evals_to!("1.1dec > 1.1dec", false, bool);
+    assert_evals_to!("1.1dec >= 1.2dec", false, bool);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn gen_order_of_arithmetic_ops() {
@@ -1582,6 +1677,38 @@ fn neg_min_int_overflow() {
     );
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn i128_negate() {
+    assert_evals_to!("Num.neg 123i128", -123, i128);
+    assert_evals_to!("Num.neg -123i128", 123, i128);
+    assert_evals_to!("Num.neg Num.maxI128", -i128::MAX, i128);
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+#[should_panic(
+    expected = r#"Roc failed with message: "integer negation overflowed because its argument is the minimum value"#
+)]
+fn neg_min_i128_overflow() {
+    assert_evals_to!("Num.neg Num.minI128", 0, i128);
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn dec_negate() {
+    assert_evals_to!(
+        "Num.neg 1.5dec",
+        RocDec::from_str_to_i128_unsafe("-1.5"),
+        i128
+    );
+    assert_evals_to!(
+        "Num.neg -1.5dec",
+        RocDec::from_str_to_i128_unsafe("1.5"),
+        i128
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn gen_wrap_int_neg() {
@@ -1627,6 +1754,48 @@ fn num_to_frac() {
     assert_evals_to!("Num.toFrac 9", 9.0, f64);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn num_to_frac_i128() {
+    assert_evals_to!("Num.toFrac -9i128", -9.0, f64);
+    assert_evals_to!("Num.toFrac 9u128", 9.0, f64);
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn num_to_frac_dec() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            x : Dec
+            x = Num.toFrac 9i64
+
+            y : Dec
+            y = Num.toFrac -3i128
+
+            z : Dec
+            z = Num.toFrac 1.5f64
+
+            x + y + z
+            "#
+        ),
+        RocDec::from_str_to_i128_unsafe("7.5"),
+        i128
+    );
+    assert_evals_to!(
+        indoc!(
+            r#"
+            f : F64
+            f = Num.toFrac 2.25dec
+
+            f
+            "#
+        ),
+        2.25,
+        f64
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-dev"))]
 fn num_to_frac_f64_to_f32() {
@@ -1728,6 +1897,22 @@ fn float_compare() {
     assert_evals_to!("Num.compare 3.14 0.01", RocOrder::Gt, RocOrder);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn i128_compare() {
+    assert_evals_to!("Num.compare 0i128 1", RocOrder::Lt, RocOrder);
+    assert_evals_to!("Num.compare 1i128 1", RocOrder::Eq, RocOrder);
+    assert_evals_to!("Num.compare Num.maxU128 0", RocOrder::Gt, RocOrder);
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn dec_compare() {
+    assert_evals_to!("Num.compare 0.01dec 3.14dec", RocOrder::Lt, RocOrder);
+    assert_evals_to!("Num.compare 3.14dec 3.14dec", RocOrder::Eq, RocOrder);
+    assert_evals_to!("Num.compare 3.14dec -0.01dec", RocOrder::Gt, RocOrder);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn pow() {
@@ -1746,12 +1931,28 @@ fn floor() {
     assert_evals_to!("Num.floor 1.9", 1, i64);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn round_ceiling_floor_i128() {
+    assert_evals_to!("Num.round 3.6", 4, i128);
+    assert_evals_to!("Num.ceiling 1.1", 2, i128);
+    assert_evals_to!("Num.floor -1.1", -2, i128);
+    assert_evals_to!("Num.floor 1.9", 1, u128);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn pow_int() {
     assert_evals_to!("Num.powInt 2 3", 8, i64);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn pow_int_i128() {
+    assert_evals_to!("Num.powInt 2i128 100", 1 << 100, i128);
+    assert_evals_to!("Num.powInt 3u128 3", 27, u128);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
 fn atan() {
@@ -2160,6 +2361,21 @@ fn shift_right_zf_by() {
     assert_evals_to!("Num.shiftRightZfBy 12 0b1000_0000u8", 0b0000_0000u8, u8);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn shift_i128() {
+    assert_evals_to!("Num.shiftLeftBy 2 0b0000_0011i128", 0b0000_1100, i128);
+    assert_evals_to!("Num.shiftLeftBy 100 1i128", 1 << 100, i128);
+    assert_evals_to!("Num.shiftRightBy 1 -12i128", -6, i128);
+    assert_evals_to!("Num.shiftRightBy 127 Num.minI128", -1, i128);
+    assert_evals_to!("Num.shiftRightZfBy 127 Num.minI128", 1, i128);
+    assert_evals_to!(
+        "Num.shiftRightZfBy 100 (Num.shiftLeftBy 100 1u128)",
+        1,
+        u128
+    );
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn min_i128() {
@@ -2507,12 +2723,12 @@ num_conversion_tests! {
     "Num.toI64", i64, (
         to_i64_same_width, "15u64", 15, ["gen-wasm"]
         to_i64_extend, "15i8", 15, ["gen-wasm"]
-        to_i64_truncate, "115i128", 115
-        to_i64_truncate_wraps, "10_000_000_000_000_000_000i128", -8446744073709551616
+        to_i64_truncate, "115i128", 115, ["gen-wasm"]
+        to_i64_truncate_wraps, "10_000_000_000_000_000_000i128", -8446744073709551616, ["gen-wasm"]
     )
     "Num.toI128", i128, (
-        to_i128_same_width, "15u128", 15
-        to_i128_extend, "15i8", 15
+        to_i128_same_width, "15u128", 15, ["gen-wasm"]
+        to_i128_extend, "15i8", 15, ["gen-wasm"]
     )
     "Num.toU8", u8, (
         to_u8_same_width, "15i8", 15, ["gen-wasm"]
@@ -2534,29 +2750,29 @@ num_conversion_tests! {
     "Num.toU64", u64, (
         to_u64_same_width, "15i64", 15, ["gen-wasm"]
         to_u64_extend, "15i8", 15, ["gen-wasm"]
-        to_u64_truncate, "115i128", 115
-        to_u64_truncate_wraps, "10_000_000_000_000_000_000_000i128", 1864712049423024128
+        to_u64_truncate, "115i128", 115, ["gen-wasm"]
+        to_u64_truncate_wraps, "10_000_000_000_000_000_000_000i128", 1864712049423024128, ["gen-wasm"]
     )
     "Num.toU128", u128, (
-        to_u128_same_width, "15i128", 15
-        to_u128_extend, "15i8", 15
+        to_u128_same_width, "15i128", 15, ["gen-wasm"]
+        to_u128_extend, "15i8", 15, ["gen-wasm"]
     )
     "Num.toNat", usize, (
         to_nat_same_width, "15i64", 15, ["gen-wasm"]
         to_nat_extend, "15i8", 15, ["gen-wasm"]
-        to_nat_truncate, "115i128", 115
+        to_nat_truncate, "115i128", 115, ["gen-wasm"]
     )
     "Num.toF32", f32, (
         to_f32_from_i8, "15i8", 15.0
         to_f32_from_i16, "15i16", 15.0
         to_f32_from_i32, "15i32", 15.0
         to_f32_from_i64, "15i64", 15.0
-        to_f32_from_i128, "15i128", 15.0
+        to_f32_from_i128, "15i128", 15.0, ["gen-wasm"]
         to_f32_from_u8, "15u8", 15.0
         to_f32_from_u16, "15u16", 15.0
         to_f32_from_u32, "15u32", 15.0
         to_f32_from_u64, "15u64", 15.0
-        to_f32_from_u128, "15u128", 15.0
+        to_f32_from_u128, "15u128", 15.0, ["gen-wasm"]
         to_f32_from_nat, "15nat", 15.0
         to_f32_from_f32, "1.5f32", 1.5
         to_f32_from_f64, "1.5f64", 1.5
@@ -2566,12 +2782,12 @@ num_conversion_tests! {
         to_f64_from_i16, "15i16", 15.0
         to_f64_from_i32, "15i32", 15.0
         to_f64_from_i64, "15i64", 15.0
-        to_f64_from_i128, "15i128", 15.0
+        to_f64_from_i128, "15i128", 15.0, ["gen-wasm"]
         to_f64_from_u8, "15u8", 15.0
         to_f64_from_u16, "15u16", 15.0
         to_f64_from_u32, "15u32", 15.0
         to_f64_from_u64, "15u64", 15.0
-        to_f64_from_u128, "15u128", 15.0
+        to_f64_from_u128, "15u128", 15.0, ["gen-wasm"]
         to_f64_from_nat, "15nat", 15.0
         to_f64_from_f32, "1.5f32", 1.5
         to_f64_from_f64, "1.5f64", 1.5
@@ -2758,6 +2974,17 @@ fn is_multiple_of_unsigned() {
     assert_evals_to!("Num.isMultipleOf 0xFCu8 0xFE", false, bool);
 }
 
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn is_multiple_of_i128() {
+    assert_evals_to!("Num.isMultipleOf 10i128 5", true, bool);
+    assert_evals_to!("Num.isMultipleOf 0i128 0", true, bool);
+    assert_evals_to!("Num.isMultipleOf 5i128 0", false, bool);
+    assert_evals_to!("Num.isMultipleOf 5i128 2", false, bool);
+    assert_evals_to!("Num.isMultipleOf Num.minI128 -1", true, bool);
+    assert_evals_to!("Num.isMultipleOf Num.maxU128 5", true, bool);
+}
+
 #[test]
 #[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 fn bytes_to_u16_clearly_out_of_bounds() {
@@ -4040,3 +4040,214 @@ fn int_let_generalization() {
         RocStr
     );
 }
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn tail_recursion_modulo_cons_map() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            ConsList a : [Cons a (ConsList a), Nil]
+
+            range : I64, ConsList I64 -> ConsList I64
+            range = \n, accum ->
+                if n == 0 then
+                    accum
+                else
+                    range (n - 1) (Cons n accum)
+
+            map : ConsList a, (a -> b) -> ConsList b
+            map = \list, f ->
+                when list is
+                    Nil -> Nil
+                    Cons x rest -> Cons (f x) (map rest f)
+
+            sum : ConsList I64, I64 -> I64
+            sum = \list, accum ->
+                when list is
+                    Nil -> accum
+                    Cons x rest -> sum rest (accum + x)
+
+            main =
+                range 1_000_000 Nil
+                    |> map (\x -> 2 * x)
+                    |> sum 0
+            "#
+        ),
+        1_000_001_000_000,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn tail_recursion_modulo_cons_map_shared() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            ConsList a : [Cons a (ConsList a), Nil]
+
+            range : I64, ConsList I64 -> ConsList I64
+            range = \n, accum ->
+                if n == 0 then
+                    accum
+                else
+                    range (n - 1) (Cons n accum)
+
+            map : ConsList a, (a -> b) -> ConsList b
+            map = \list, f ->
+                when list is
+                    Nil -> Nil
+                    Cons x rest -> Cons (f x) (map rest f)
+
+            sum : ConsList I64, I64 -> I64
+            sum = \list, accum ->
+                when list is
+                    Nil -> accum
+                    Cons x rest -> sum rest (accum + x)
+
+            main =
+                numbers = range 1_000_000 Nil
+
+                # the first map can not reuse the cells of `numbers`, the second one can
+                doubled = map numbers (\x -> 2 * x)
+                negated = map numbers (\x -> -x)
+
+                sum doubled 0 + sum negated 0
+            "#
+        ),
+        500_000_500_000,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn tail_recursion_modulo_cons_append() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            ConsList a : [Cons a (ConsList a), Nil]
+
+            range : I64, ConsList I64 -> ConsList I64
+            range = \n, accum ->
+                if n == 0 then
+                    accum
+                else
+                    range (n - 1) (Cons n accum)
+
+            append : ConsList a, ConsList a -> ConsList a
+            append = \xs, ys ->
+                when xs is
+                    Nil -> ys
+                    Cons x rest -> Cons x (append rest ys)
+
+            length : ConsList a, I64 -> I64
+            length = \list, accum ->
+                when list is
+                    Nil -> accum
+                    Cons _ rest -> length rest (accum + 1)
+
+            main =
+                length (append (range 1_000_000 Nil) (range 1_000_000 Nil)) 0
+            "#
+        ),
+        2_000_000,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn tail_recursion_modulo_cons_mixed_with_tail_calls() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            ConsList a : [Cons a (ConsList a), Nil]
+
+            range : I64, ConsList I64 -> ConsList I64
+            range = \n, accum ->
+                if n == 0 then
+                    accum
+                else
+                    range (n - 1) (Cons n accum)
+
+            keepEvens : ConsList I64 -> ConsList I64
+            keepEvens = \list ->
+                when list is
+                    Nil -> Nil
+                    Cons x rest ->
+                        if Num.isEven x then
+                            Cons x (keepEvens rest)
+                        else
+                            keepEvens rest
+
+            sum : ConsList I64, I64 -> I64
+            sum = \list, accum ->
+                when list is
+                    Nil -> accum
+                    Cons x rest -> sum rest (accum + x)
+
+            main =
+                sum (keepEvens (range 1_000_000 Nil)) 0
+            "#
+        ),
+        250_000_500_000,
+        i64
+    );
+}
+
+#[test]
+#[cfg(feature = "gen-llvm")]
+fn llvm_debug_info_points_at_source() {
+    let ir = crate::helpers::llvm::llvm_ir_with_source_debug_info(indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        double : I64 -> I64
+        double = \n -> n * 2
+
+        main =
+            x = double 21
+            y = x + 1
+            y
+        "#
+    ));
+
+    let has_line = |parts: &[&str]| {
+        ir.lines()
+            .any(|line| parts.iter().all(|part| line.contains(part)))
+    };
+
+    assert!(has_line(&["DIFile(filename: \"Test.roc\""]), "{}", ir);
+    assert!(
+        has_line(&["DISubprogram(name: \"", ".double\"", "line: 4,"]),
+        "{}",
+        ir
+    );
+    assert!(
+        has_line(&["DILocalVariable(name: \"n\", arg: 1,", "line: 4,"]),
+        "{}",
+        ir
+    );
+    assert!(
+        has_line(&["DILocalVariable(name: \"x\",", "line: 7,"]),
+        "{}",
+        ir
+    );
+    assert!(
+        has_line(&["DILocalVariable(name: \"y\",", "line: 8,"]),
+        "{}",
+        ir
+    );
+    assert!(has_line(&["DILocation(line: 7, column: 5,"]), "{}", ir);
+    assert!(has_line(&["DILocation(line: 8, column: 5,"]), "{}", ir);
+}
@@ -1,6 +1,9 @@
 #[cfg(feature = "gen-wasm")]
 use crate::helpers::{wasm::assert_refcounts, RefCount::*};
 
+#[cfg(feature = "gen-dev")]
+use crate::helpers::{dev::assert_refcounts, RefCount::*};
+
 #[allow(unused_imports)]
 use indoc::indoc;
 
@@ -13,7 +16,7 @@ use roc_std::{RocList, RocStr};
 type Pointer = usize;
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn str_inc() {
     assert_refcounts!(
         indoc!(
@@ -32,7 +35,7 @@ fn str_inc() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn str_dealloc() {
     assert_refcounts!(
         indoc!(
@@ -48,7 +51,7 @@ fn str_dealloc() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn list_int_inc() {
     assert_refcounts!(
         indoc!(
@@ -66,7 +69,7 @@ fn list_int_inc() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn list_int_dealloc() {
     assert_refcounts!(
         indoc!(
@@ -84,7 +87,45 @@ fn list_int_dealloc() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
+fn dbg_str_inc() {
+    assert_refcounts!(
+        indoc!(
+            r#"
+                s = Str.concat "A long enough string " "to be heap-allocated"
+                t = dbg s
+
+                [s, t]
+            "#
+        ),
+        RocList<RocStr>,
+        &[
+            Live(2), // s
+            Live(1)  // result
+        ]
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
+fn dbg_list_dealloc() {
+    assert_refcounts!(
+        indoc!(
+            r#"
+                list = [0x111, 0x222, 0x333]
+
+                List.len (dbg list)
+            "#
+        ),
+        usize,
+        &[
+            Deallocated // list
+        ]
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn list_str_inc() {
     assert_refcounts!(
         indoc!(
@@ -104,7 +145,7 @@ fn list_str_inc() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn list_str_dealloc() {
     assert_refcounts!(
         indoc!(
@@ -124,7 +165,7 @@ fn list_str_dealloc() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn struct_inc() {
     assert_refcounts!(
         indoc!(
@@ -141,7 +182,7 @@ fn struct_inc() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn struct_dealloc() {
     assert_refcounts!(
         indoc!(
@@ -159,7 +200,7 @@ fn struct_dealloc() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn union_nonrecursive_inc() {
     type TwoStr = (RocStr, RocStr, i64);
 
@@ -185,7 +226,7 @@ fn union_nonrecursive_inc() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn union_nonrecursive_dec() {
     assert_refcounts!(
         indoc!(
@@ -208,7 +249,7 @@ fn union_nonrecursive_dec() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn union_recursive_inc() {
     assert_refcounts!(
         indoc!(
@@ -236,7 +277,7 @@ fn union_recursive_inc() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn union_recursive_dec() {
     assert_refcounts!(
         indoc!(
@@ -266,7 +307,7 @@ fn union_recursive_dec() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn refcount_different_rosetrees_inc() {
     // Requires two different Inc procedures for `List (Rose I64)` and `List (Rose Str)`
     // even though both appear in the mono Layout as `List(RecursivePointer)`
@@ -306,7 +347,7 @@ fn refcount_different_rosetrees_inc() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn refcount_different_rosetrees_dec() {
     // Requires two different Dec procedures for `List (Rose I64)` and `List (Rose Str)`
     // even though both appear in the mono Layout as `List(RecursivePointer)`
@@ -347,7 +388,7 @@ fn refcount_different_rosetrees_dec() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn union_linked_list_inc() {
     assert_refcounts!(
         indoc!(
@@ -373,7 +414,7 @@ fn union_linked_list_inc() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn union_linked_list_dec() {
     assert_refcounts!(
         indoc!(
@@ -401,7 +442,7 @@ fn union_linked_list_dec() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn union_linked_list_nil_dec() {
     let no_refcounts: &[crate::helpers::RefCount] = &[];
     assert_refcounts!(
@@ -423,7 +464,7 @@ fn union_linked_list_nil_dec() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn union_linked_list_long_dec() {
     assert_refcounts!(
         indoc!(
@@ -455,7 +496,7 @@ fn union_linked_list_long_dec() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn boxed_str_inc() {
     assert_refcounts!(
         indoc!(
@@ -475,7 +516,7 @@ fn boxed_str_inc() {
 }
 
 #[test]
-#[cfg(any(feature = "gen-wasm"))]
+#[cfg(any(feature = "gen-wasm", feature = "gen-dev"))]
 fn boxed_str_dec() {
     assert_refcounts!(
         indoc!(
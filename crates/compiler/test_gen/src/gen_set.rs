@@ -10,7 +10,7 @@ use crate::helpers::llvm::assert_evals_to;
 // use crate::helpers::wasm::assert_evals_to;
 
 use indoc::indoc;
-use roc_std::RocList;
+use roc_std::{RocList, RocStr};
 
 #[test]
 #[cfg(any(feature = "gen-llvm"))]
@@ -66,11 +66,11 @@ fn single_to_list() {
     assert_evals_to!(
         indoc!(
             r#"
-            Set.toList (Set.single 1.0)
+            Set.toList (Set.single "foo")
             "#
         ),
-        RocList::from_slice(&[1.0]),
-        RocList<f64>
+        RocList::from_slice(&[RocStr::from("foo")]),
+        RocList<RocStr>
     );
 }
 
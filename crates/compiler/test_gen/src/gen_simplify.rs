@@ -0,0 +1,136 @@
+//! The dev and wasm backends with constants folded and dead code removed from the mono IR.
+//! Every other dev and wasm test runs without this pass.
+
+#[cfg(feature = "gen-dev")]
+use crate::helpers::dev::assert_simplified_evals_to;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::assert_simplified_evals_to;
+
+use indoc::indoc;
+
+#[test]
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+fn fold_int_arithmetic() {
+    assert_simplified_evals_to!("(1 + 2) * 3 - 4", 5, i64);
+}
+
+#[test]
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+fn fold_float_arithmetic() {
+    assert_simplified_evals_to!("1.5 + 2.25", 3.75, f64);
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn fold_comparison() {
+    assert_simplified_evals_to!("1u8 < 2u8", true, bool);
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn fold_if_on_comparison() {
+    assert_simplified_evals_to!("if 3 > 2 then 10 else 20", 10, i64);
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn fold_add_wrap() {
+    assert_simplified_evals_to!("Num.addWrap 250u8 10u8", 4, u8);
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+#[should_panic(expected = r#"Roc failed with message: "integer addition overflowed!"#)]
+fn overflow_is_not_folded() {
+    assert_simplified_evals_to!("9_223_372_036_854_775_807 + 1", 0, i64);
+}
+
+#[test]
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+fn known_tag_switch() {
+    assert_simplified_evals_to!(
+        indoc!(
+            r#"
+            when These 1 2 is
+                This x -> x
+                That y -> y
+                These x _ -> x
+            "#
+        ),
+        1,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+fn known_guard() {
+    assert_simplified_evals_to!(
+        indoc!(
+            r#"
+            wrapper = \{} ->
+                when 2 is
+                    2 if True -> 42
+                    _ -> 0
+
+            wrapper {}
+            "#
+        ),
+        42,
+        i64
+    );
+}
+
+#[test]
+#[cfg(feature = "gen-wasm")]
+fn dead_branch_removed() {
+    assert_simplified_evals_to!(
+        indoc!(
+            r#"
+            x = 1 + 2
+
+            if x > 5 then x else 10
+            "#
+        ),
+        10,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+fn folded_constant_used_at_runtime() {
+    assert_simplified_evals_to!(
+        indoc!(
+            r#"
+            f : I64 -> I64
+            f = \n -> n + 2 * 3
+
+            f 4
+            "#
+        ),
+        10,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+fn unknown_switch_kept() {
+    assert_simplified_evals_to!(
+        indoc!(
+            r#"
+            pick : I64 -> I64
+            pick = \n ->
+                when n is
+                    1 -> 100
+                    _ -> 1 + 1
+
+            pick 1 + pick 5
+            "#
+        ),
+        102,
+        i64
+    );
+}
@@ -0,0 +1,170 @@
+#[cfg(feature = "gen-llvm")]
+use crate::helpers::llvm::assert_evals_to;
+
+#[cfg(feature = "gen-dev")]
+use crate::helpers::dev::assert_evals_to;
+
+#[cfg(feature = "gen-wasm")]
+use crate::helpers::wasm::assert_evals_to;
+
+// use crate::assert_wasm_evals_to as assert_evals_to;
+use indoc::indoc;
+
+#[cfg(all(test, any(feature = "gen-llvm", feature = "gen-wasm")))]
+use roc_std::{RocList, RocStr};
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn basic_tuple() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                    (15, 17, 19).0
+                "#
+        ),
+        15,
+        i64
+    );
+
+    assert_evals_to!(
+        indoc!(
+            r#"
+                    (15, 17, 19).1
+                "#
+        ),
+        17,
+        i64
+    );
+
+    assert_evals_to!(
+        indoc!(
+            r#"
+                    (15, 17, 19).2
+                "#
+        ),
+        19,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn f64_tuple() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                   tup = (17.2, 15.1, 19.3)
+
+                   tup.1
+                "#
+        ),
+        15.1,
+        f64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-dev", feature = "gen-wasm"))]
+fn destructure_tuple() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                   (x, y) = (3, 4)
+
+                   x * 10 + y
+                "#
+        ),
+        34,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn when_on_tuple() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                   when (1, 2) is
+                       (0, x) -> x
+                       (1, x) -> x + 40
+                       _ -> 0
+                "#
+        ),
+        42,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn tuple_function_argument() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                   swap : (I64, I64) -> (I64, I64)
+                   swap = \(a, b) -> (b, a)
+
+                   swap (1, 2)
+                "#
+        ),
+        (2, 1),
+        (i64, i64)
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn nested_tuple() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                   tup = ((1, 2), 3)
+                   inner = tup.0
+
+                   inner.1 + tup.1
+                "#
+        ),
+        5,
+        i64
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn alignment_in_tuple() {
+    // elements are stored by decreasing alignment, then by position
+    assert_evals_to!(
+        indoc!("(1 == 1, 32i64, 2u8)"),
+        (32i64, true, 2u8),
+        (i64, bool, u8)
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn tuple_with_string() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                   ("foo", 42).0
+                "#
+        ),
+        RocStr::from("foo"),
+        RocStr
+    );
+}
+
+#[test]
+#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
+fn tuple_accessor_function() {
+    assert_evals_to!(
+        indoc!(
+            r#"
+                   List.map [(1, 2), (3, 4)] .1
+                "#
+        ),
+        RocList::from_slice(&[2, 4]),
+        RocList<i64>
+    );
+}
@@ -1,3 +1,4 @@
+use super::RefCount;
 use libloading::Library;
 use roc_build::link::{link, LinkType};
 use roc_builtins::bitcode;
@@ -5,8 +6,10 @@ use roc_load::{EntryPoint, ExecutionMode, LoadConfig, Threading};
 use roc_region::all::LineInfo;
 use tempfile::tempdir;
 
-#[cfg(any(feature = "gen-llvm", feature = "gen-wasm"))]
 use roc_collections::all::MutMap;
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_mono::ir::{Proc, ProcLayout};
+use std::path::PathBuf;
 
 #[allow(unused_imports)]
 use roc_mono::ir::pretty_print_ir_symbols;
@@ -32,12 +35,29 @@ pub fn helper(
     _leak: bool,
     lazy_literals: bool,
 ) -> (String, Vec<roc_problem::can::Problem>, Library) {
-    use std::path::PathBuf;
+    build_library(arena, src, lazy_literals, false, false)
+}
 
-    let dir = tempdir().unwrap();
+/// Like `helper`, but folds constants and removes dead code before generating code
+#[allow(dead_code)]
+pub fn simplified_helper(
+    arena: &bumpalo::Bump,
+    src: &str,
+) -> (String, Vec<roc_problem::can::Problem>, Library) {
+    build_library(arena, src, false, false, true)
+}
+
+/// Compiles `src` into a dylib. Refcount tests link against `dev_test_platform.c`,
+/// which tracks allocations, instead of generating allocators.
+fn build_library(
+    arena: &bumpalo::Bump,
+    src: &str,
+    lazy_literals: bool,
+    refcount_test: bool,
+    simplify: bool,
+) -> (String, Vec<roc_problem::can::Problem>, Library) {
     let filename = PathBuf::from("Test.roc");
     let src_dir = PathBuf::from("fake/test/path");
-    let app_o_file = dir.path().join("app.o");
 
     let module_src;
     let temp;
@@ -50,11 +70,14 @@ pub fn helper(
         module_src = &temp;
     }
 
+    let target = target_lexicon::Triple::host();
     let load_config = LoadConfig {
-        target_info: roc_target::TargetInfo::default_x86_64(),
+        target_info: roc_target::TargetInfo::from(&target),
         render: roc_reporting::report::RenderTarget::ColorTerminal,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        type_cache_dir: None,
+        mono_passes: super::dev_mono_passes(simplify),
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -71,7 +94,7 @@ pub fn helper(
     let MonomorphizedModule {
         module_id,
         procedures,
-        mut interns,
+        interns,
         exposed_to_host,
         ..
     } = loaded;
@@ -108,23 +131,16 @@ pub fn helper(
             unreachable!()
         }
     };
-    let main_fn_symbol = entry_point.symbol;
-    let main_fn_layout = entry_point.layout;
-
-    let mut layout_ids = roc_mono::layout::LayoutIds::default();
-    let main_fn_name = layout_ids
-        .get_toplevel(main_fn_symbol, &main_fn_layout)
-        .to_exposed_symbol_string(main_fn_symbol, &interns);
 
     let mut lines = Vec::new();
     // errors whose reporting we delay (so we can see that code gen generates runtime errors)
     let mut delayed_errors = Vec::new();
 
-    for (home, (module_path, src)) in loaded.sources {
+    for (home, (module_path, src)) in loaded.sources.iter() {
         use roc_reporting::report::{can_problem, type_problem, RocDocAllocator, DEFAULT_PALETTE};
 
-        let can_problems = loaded.can_problems.remove(&home).unwrap_or_default();
-        let type_problems = loaded.type_problems.remove(&home).unwrap_or_default();
+        let can_problems = loaded.can_problems.remove(home).unwrap_or_default();
+        let type_problems = loaded.type_problems.remove(home).unwrap_or_default();
 
         let error_count = can_problems.len() + type_problems.len();
 
@@ -132,12 +148,12 @@ pub fn helper(
             continue;
         }
 
-        let line_info = LineInfo::new(&src);
+        let line_info = LineInfo::new(src);
         let src_lines: Vec<&str> = src.split('\n').collect();
         let palette = DEFAULT_PALETTE;
 
         // Report parsing and canonicalization problems
-        let alloc = RocDocAllocator::new(&src_lines, home, &interns);
+        let alloc = RocDocAllocator::new(&src_lines, *home, &interns);
 
         use roc_problem::can::Problem::*;
         for problem in can_problems.into_iter() {
@@ -174,15 +190,70 @@ pub fn helper(
         assert_eq!(0, 1, "Mistakes were made");
     }
 
+    let (main_fn_name, lib) = build_library_from_procedures(
+        arena,
+        module_id,
+        interns,
+        procedures,
+        entry_point,
+        &loaded.sources,
+        lazy_literals,
+        refcount_test,
+    );
+
+    (main_fn_name, delayed_errors, lib)
+}
+
+/// Compiles mono IR in the textual syntax of `roc_mono::debug::parse_procs` into a dylib,
+/// without going through the frontend. The first procedure is the entry point.
+#[allow(dead_code)]
+pub fn ir_helper(arena: &bumpalo::Bump, ir: &str) -> (String, Library) {
+    let parsed = super::parse_ir(arena, ir);
+
+    build_library_from_procedures(
+        arena,
+        parsed.home,
+        parsed.interns,
+        parsed.procedures,
+        parsed.entry_point,
+        &MutMap::default(),
+        false,
+        false,
+    )
+}
+
+/// Generates code for the procedures, and links it into a dylib together with the builtins.
+/// Returns the name of the entry point in the dylib.
+#[allow(clippy::too_many_arguments)]
+fn build_library_from_procedures<'a>(
+    arena: &'a bumpalo::Bump,
+    module_id: ModuleId,
+    mut interns: Interns,
+    procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    entry_point: roc_mono::ir::EntryPoint<'a>,
+    sources: &MutMap<ModuleId, (PathBuf, Box<str>)>,
+    lazy_literals: bool,
+    refcount_test: bool,
+) -> (String, Library) {
+    let dir = tempdir().unwrap();
+    let app_o_file = dir.path().join("app.o");
+    let target = target_lexicon::Triple::host();
+
+    let mut layout_ids = roc_mono::layout::LayoutIds::default();
+    let main_fn_name = layout_ids
+        .get_toplevel(entry_point.symbol, &entry_point.layout)
+        .to_exposed_symbol_string(entry_point.symbol, &interns);
+
     let env = roc_gen_dev::Env {
         arena,
         module_id,
-        exposed_to_host: exposed_to_host.values.keys().copied().collect(),
+        exposed_to_host: std::iter::once(entry_point.symbol).collect(),
         lazy_literals,
-        generate_allocators: true, // Needed for testing, since we don't have a platform
+        // Needed for testing, since we don't have a platform. Refcount tests link one instead.
+        generate_allocators: !refcount_test,
+        sources,
     };
 
-    let target = target_lexicon::Triple::host();
     let module_object = roc_gen_dev::build_module(&env, &mut interns, &target, procedures);
 
     let module_out = module_object
@@ -192,18 +263,16 @@ pub fn helper(
 
     // std::fs::copy(&app_o_file, "/tmp/app.o").unwrap();
 
-    let (mut child, dylib_path) = link(
-        &target,
-        app_o_file.clone(),
-        // Long term we probably want a smarter way to link in zig builtins.
-        // With the current method all methods are kept and it adds about 100k to all outputs.
-        &[
-            app_o_file.to_str().unwrap(),
-            &bitcode::get_builtins_host_obj_path(),
-        ],
-        LinkType::Dylib,
-    )
-    .expect("failed to link dynamic library");
+    let builtins_host_obj = bitcode::get_builtins_host_obj_path();
+    // Long term we probably want a smarter way to link in zig builtins.
+    // With the current method all methods are kept and it adds about 100k to all outputs.
+    let mut input_paths = vec![app_o_file.to_str().unwrap(), builtins_host_obj.as_str()];
+    if refcount_test {
+        input_paths.push(DEV_TEST_PLATFORM);
+    }
+
+    let (mut child, dylib_path) = link(&target, app_o_file.clone(), &input_paths, LinkType::Dylib)
+        .expect("failed to link dynamic library");
 
     child.wait().unwrap();
 
@@ -214,7 +283,67 @@ pub fn helper(
 
     let lib = unsafe { Library::new(path) }.expect("failed to load shared library");
 
-    (main_fn_name, delayed_errors, lib)
+    (main_fn_name, lib)
+}
+
+const DEV_TEST_PLATFORM: &str = concat!(env!("OUT_DIR"), "/dev_test_platform.o");
+
+#[allow(dead_code)]
+pub fn assert_dev_refcounts_help<T>(
+    src: &str,
+    num_refcounts: usize,
+) -> Result<Vec<RefCount>, String> {
+    let arena = bumpalo::Bump::new();
+    let (main_fn_name, errors, lib) = build_library(&arena, src, false, true, false);
+
+    if !errors.is_empty() {
+        return Err(format!("Encountered errors: {:?}", errors));
+    }
+
+    unsafe {
+        let init_refcount_test: libloading::Symbol<unsafe extern "C" fn(usize) -> *const usize> =
+            lib.get(b"init_refcount_test")
+                .map_err(|e| format!("Unable to find refcount test init function: {:?}", e))?;
+        let refcount_vector = init_refcount_test(num_refcounts);
+
+        // Run the test
+        let main: libloading::Symbol<unsafe extern "C" fn() -> T> = lib
+            .get(main_fn_name.as_bytes())
+            .map_err(|e| format!("Unable to JIT compile `{}`: {:?}", main_fn_name, e))?;
+
+        // Dropping the result would change the refcounts we are about to read.
+        std::mem::forget(main());
+
+        // Read the length of the vector in the C host
+        let actual_len = *refcount_vector;
+        if actual_len != num_refcounts {
+            return Err(format!(
+                "Expected {} refcounts but got {}",
+                num_refcounts, actual_len
+            ));
+        }
+
+        // Read the refcounts
+        let refcounts = (1..=num_refcounts)
+            .map(|i| {
+                let rc_ptr = *refcount_vector.add(i) as *const isize;
+                if rc_ptr.is_null() {
+                    RefCount::Deallocated
+                } else {
+                    // Dereference the RC pointer and decode its value from the negative number format
+                    let rc_encoded = *rc_ptr;
+                    if rc_encoded == 0 {
+                        RefCount::Constant
+                    } else {
+                        let rc = rc_encoded - isize::MIN + 1;
+                        RefCount::Live(rc as u32)
+                    }
+                }
+            })
+            .collect();
+
+        Ok(refcounts)
+    }
 }
 
 #[allow(unused_macros)]
@@ -254,5 +383,144 @@ macro_rules! assert_evals_to {
     };
 }
 
+/// Like `assert_evals_to`, with constants folded and dead code removed from the mono IR
+#[allow(unused_macros)]
+macro_rules! assert_simplified_evals_to {
+    ($src:expr, $expected:expr, $ty:ty) => {{
+        use bumpalo::Bump;
+        use roc_gen_dev::run_jit_function_raw;
+
+        let arena = Bump::new();
+        let (main_fn_name, errors, lib) = $crate::helpers::dev::simplified_helper(&arena, $src);
+
+        let transform = |success| {
+            let expected = $expected;
+            assert_eq!(&success, &expected);
+        };
+        run_jit_function_raw!(lib, main_fn_name, $ty, transform, errors)
+    }};
+}
+
+/// Like `assert_evals_to`, for mono IR in the textual syntax of `roc_mono::debug::parse_procs`
+#[allow(unused_macros)]
+macro_rules! assert_ir_evals_to {
+    ($ir:expr, $expected:expr, $ty:ty) => {{
+        use bumpalo::Bump;
+        use roc_gen_dev::run_jit_function_raw;
+
+        let arena = Bump::new();
+        let (main_fn_name, lib) = $crate::helpers::dev::ir_helper(&arena, $ir);
+
+        let transform = |success| {
+            let expected = $expected;
+            assert_eq!(&success, &expected);
+        };
+        run_jit_function_raw!(lib, main_fn_name, $ty, transform)
+    }};
+}
+
+/// Runs `run` in a forked process, because a `crash` stops the dev backend's code with a trap
+/// rather than unwinding. If the process does not exit cleanly, this panics with what it wrote
+/// to stderr, which includes the message of the crash.
+#[allow(dead_code)]
+pub(crate) fn run_in_child(run: impl FnOnce()) {
+    use std::io::{Read, Write};
+    use std::os::unix::io::FromRawFd;
+
+    unsafe {
+        let mut fds = [0; 2];
+        assert_eq!(libc::pipe(fds.as_mut_ptr()), 0);
+
+        match libc::fork() {
+            0 => {
+                libc::close(fds[0]);
+                libc::dup2(fds[1], libc::STDERR_FILENO);
+
+                // the test harness captures the panic message, so report it ourselves
+                let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(run));
+
+                let code = match result {
+                    Ok(()) => 0,
+                    Err(payload) => {
+                        let message = match payload.downcast_ref::<String>() {
+                            Some(message) => message.as_str(),
+                            None => payload.downcast_ref::<&str>().copied().unwrap_or(""),
+                        };
+
+                        let mut stderr = std::fs::File::from_raw_fd(libc::STDERR_FILENO);
+                        let _ = writeln!(stderr, "{}", message);
+
+                        1
+                    }
+                };
+
+                libc::_exit(code)
+            }
+            -1 => panic!("could not fork: {}", std::io::Error::last_os_error()),
+            pid => {
+                libc::close(fds[1]);
+
+                let mut stderr = String::new();
+                std::fs::File::from_raw_fd(fds[0])
+                    .read_to_string(&mut stderr)
+                    .unwrap();
+
+                let mut status = 0;
+                libc::waitpid(pid, &mut status, 0);
+
+                if !(libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 0) {
+                    panic!("{}", stderr);
+                }
+            }
+        }
+    }
+}
+
+/// Like `assert_evals_to`, but runs the compiled program in a child process, so that it can crash
+#[allow(unused_macros)]
+macro_rules! assert_evals_to_in_child {
+    ($src:expr, $expected:expr, $ty:ty) => {{
+        use bumpalo::Bump;
+        use roc_gen_dev::run_jit_function_raw;
+
+        let arena = Bump::new();
+        let (main_fn_name, errors, lib) = $crate::helpers::dev::helper(&arena, $src, true, false);
+
+        $crate::helpers::dev::run_in_child(|| {
+            let transform = |success| {
+                let expected = $expected;
+                assert_eq!(&success, &expected);
+            };
+            run_jit_function_raw!(lib, main_fn_name, $ty, transform, errors)
+        });
+    }};
+}
+
+#[allow(unused_macros)]
+macro_rules! assert_refcounts {
+    ($src: expr, $ty: ty, $expected_refcounts: expr) => {{
+        let num_refcounts = $expected_refcounts.len();
+        let result = $crate::helpers::dev::assert_dev_refcounts_help::<$ty>($src, num_refcounts);
+        match result {
+            Err(msg) => panic!("{:?}", msg),
+            Ok(actual_refcounts) => {
+                assert_eq!(&actual_refcounts, $expected_refcounts)
+            }
+        }
+    }};
+}
+
 #[allow(unused_imports)]
 pub(crate) use assert_evals_to;
+
+#[allow(unused_imports)]
+pub(crate) use assert_evals_to_in_child;
+
+#[allow(unused_imports)]
+pub(crate) use assert_ir_evals_to;
+
+#[allow(unused_imports)]
+pub(crate) use assert_refcounts;
+
+#[allow(unused_imports)]
+pub(crate) use assert_simplified_evals_to;
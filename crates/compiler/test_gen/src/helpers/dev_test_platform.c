@@ -0,0 +1,112 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// A native platform for dev backend refcount tests.
+// It tracks allocations the same way as wasm_test_platform.c.
+
+typedef struct
+{
+    size_t length;
+    size_t *elements[]; // flexible array member
+} Vector;
+
+// Globals for refcount testing
+Vector *rc_pointers;
+size_t rc_pointers_capacity;
+
+// The rust test passes us the max number of allocations it expects to make,
+// and we tell it where we're going to write the refcount pointers.
+// It won't actually read that memory until later, when the test is done.
+Vector *init_refcount_test(size_t capacity)
+{
+    rc_pointers_capacity = capacity;
+
+    rc_pointers = malloc((1 + capacity) * sizeof(size_t *));
+    rc_pointers->length = 0;
+    for (size_t i = 0; i < capacity; ++i)
+        rc_pointers->elements[i] = NULL;
+
+    return rc_pointers;
+}
+
+#define ASSERT(condition, format, ...)                               \
+    if (!(condition))                                                \
+    {                                                                \
+        fprintf(stderr, "ASSERT FAILED: " #format "\n", __VA_ARGS__); \
+        abort();                                                     \
+    }
+
+size_t *alloc_ptr_to_rc_ptr(void *ptr, unsigned int alignment)
+{
+    size_t alloc_addr = (size_t)ptr;
+    size_t rc_addr = alloc_addr + alignment - sizeof(size_t);
+    return (size_t *)rc_addr;
+}
+
+//--------------------------
+
+void *roc_alloc(size_t size, unsigned int alignment)
+{
+    void *allocated = malloc(size);
+
+    if (rc_pointers)
+    {
+        ASSERT(alignment >= sizeof(size_t), "alignment %u != %zu", alignment, sizeof(size_t));
+        size_t num_alloc = rc_pointers->length + 1;
+        ASSERT(num_alloc <= rc_pointers_capacity, "Too many allocations %zu > %zu", num_alloc, rc_pointers_capacity);
+
+        size_t *rc_ptr = alloc_ptr_to_rc_ptr(allocated, alignment);
+        rc_pointers->elements[rc_pointers->length] = rc_ptr;
+        rc_pointers->length++;
+    }
+
+    return allocated;
+}
+
+//--------------------------
+
+void *roc_realloc(void *ptr, size_t new_size, size_t old_size,
+                  unsigned int alignment)
+{
+    return realloc(ptr, new_size);
+}
+
+//--------------------------
+
+void roc_dealloc(void *ptr, unsigned int alignment)
+{
+    if (rc_pointers)
+    {
+        // Null out the entry in the test array to indicate that it was freed
+        // Then even if malloc reuses the space, everything still works
+        size_t *rc_ptr = alloc_ptr_to_rc_ptr(ptr, alignment);
+        size_t i = 0;
+        for (; i < rc_pointers->length; ++i)
+        {
+            if (rc_pointers->elements[i] == rc_ptr)
+            {
+                rc_pointers->elements[i] = NULL;
+                break;
+            }
+        }
+        int was_found = i < rc_pointers->length;
+        ASSERT(was_found, "RC pointer not found %p", (void *)rc_ptr);
+    }
+
+    free(ptr);
+}
+
+//--------------------------
+
+void roc_panic(char *msg, unsigned int tag_id)
+{
+    fprintf(stderr, "Roc hit a panic: %s\n", msg);
+    exit(101);
+}
+
+//--------------------------
+
+void roc_dbg(unsigned int module_id, unsigned int region_start, unsigned int region_end, void *value)
+{
+}
@@ -4,11 +4,13 @@ use inkwell::module::Module;
 use libloading::Library;
 use roc_build::link::llvm_module_to_dylib;
 use roc_build::program::FunctionIterator;
-use roc_collections::all::MutSet;
+use roc_collections::all::{MutMap, MutSet};
 use roc_gen_llvm::llvm::build::LlvmBackendMode;
+use roc_gen_llvm::llvm::debug_info::DebugSources;
 use roc_gen_llvm::llvm::externs::add_default_roc_externs;
-use roc_load::{EntryPoint, ExecutionMode, LoadConfig, Threading};
-use roc_mono::ir::OptLevel;
+use roc_load::{EntryPoint, ExecutionMode, LoadConfig, MonoPasses, Threading};
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_mono::ir::{OptLevel, Proc, ProcLayout};
 use roc_region::all::LineInfo;
 use roc_reporting::report::RenderTarget;
 use target_lexicon::Triple;
@@ -49,6 +51,7 @@ fn create_llvm_module<'a>(
     config: HelperConfig,
     context: &'a inkwell::context::Context,
     target: &Triple,
+    source_debug_info: bool,
 ) -> (&'static str, String, &'a Module<'a>) {
     let target_info = roc_target::TargetInfo::from(target);
 
@@ -71,6 +74,8 @@ fn create_llvm_module<'a>(
         render: RenderTarget::ColorTerminal,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        type_cache_dir: None,
+        mono_passes: MonoPasses::for_opt_level(config.opt_level),
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -98,6 +103,9 @@ fn create_llvm_module<'a>(
         ..
     } = loaded;
 
+    // the sources are consumed by the problem reporting below
+    let debug_sources = source_debug_info.then(|| loaded.sources.clone());
+
     let mut lines = Vec::new();
     // errors whose reporting we delay (so we can see that code gen generates runtime errors)
     let mut delayed_errors = Vec::new();
@@ -172,6 +180,50 @@ fn create_llvm_module<'a>(
         }
     }
 
+    let entry_point = match entry_point {
+        EntryPoint::Executable { symbol, layout, .. } => {
+            roc_mono::ir::EntryPoint { symbol, layout }
+        }
+        EntryPoint::Test => {
+            unreachable!()
+        }
+    };
+
+    let (main_fn_name, module) = llvm_module_from_procedures(
+        arena,
+        config,
+        context,
+        target,
+        interns,
+        procedures,
+        entry_point,
+        debug_sources,
+    );
+
+    let delayed_errors = if config.ignore_problems {
+        String::new()
+    } else {
+        delayed_errors.join("\n")
+    };
+    (main_fn_name, delayed_errors, module)
+}
+
+/// Generates code for the procedures, and returns the name of the entry point with the module.
+/// The sources are given when the module should have debug info that points at them.
+#[allow(clippy::too_many_arguments)]
+fn llvm_module_from_procedures<'a>(
+    arena: &'a bumpalo::Bump,
+    config: HelperConfig,
+    context: &'a inkwell::context::Context,
+    target: &Triple,
+    interns: Interns,
+    procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    entry_point: roc_mono::ir::EntryPoint<'a>,
+    debug_sources: Option<MutMap<ModuleId, (PathBuf, Box<str>)>>,
+) -> (&'static str, &'a Module<'a>) {
+    let target_info = roc_target::TargetInfo::from(target);
+    let source_debug_info = debug_sources.is_some();
+
     let builder = context.create_builder();
     let module = roc_gen_llvm::llvm::build::module_from_builtins(target, context, "app");
 
@@ -180,6 +232,8 @@ fn create_llvm_module<'a>(
         roc_gen_llvm::llvm::build::construct_optimization_passes(module, config.opt_level);
 
     let (dibuilder, compile_unit) = roc_gen_llvm::llvm::build::Env::new_debug_info(module);
+    let debug_sources =
+        debug_sources.map(|sources| DebugSources::new(&dibuilder, &sources, procedures.values()));
 
     // mark our zig-defined builtins as internal
     use inkwell::attributes::{Attribute, AttributeLoc};
@@ -221,6 +275,7 @@ fn create_llvm_module<'a>(
         mode: config.mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        debug_sources,
     };
 
     // strip Zig debug stuff
@@ -230,14 +285,6 @@ fn create_llvm_module<'a>(
     // platform to provide them.
     add_default_roc_externs(&env);
 
-    let entry_point = match entry_point {
-        EntryPoint::Executable { symbol, layout, .. } => {
-            roc_mono::ir::EntryPoint { symbol, layout }
-        }
-        EntryPoint::Test => {
-            unreachable!()
-        }
-    };
     let (main_fn_name, main_fn) = match config.mode {
         LlvmBackendMode::Binary => unreachable!(),
         LlvmBackendMode::CliTest => unreachable!(),
@@ -257,8 +304,10 @@ fn create_llvm_module<'a>(
 
     env.dibuilder.finalize();
 
-    // strip all debug info: we don't use it at the moment and causes weird validation issues
-    module.strip_debug_info();
+    if !source_debug_info {
+        // strip all debug info: we don't use it at the moment and causes weird validation issues
+        module.strip_debug_info();
+    }
 
     // Uncomment this to see the module's un-optimized LLVM instruction output:
     // env.module.print_to_stderr();
@@ -285,12 +334,7 @@ fn create_llvm_module<'a>(
     // Uncomment this to see the module's optimized LLVM instruction output:
     // env.module.print_to_stderr();
 
-    let delayed_errors = if config.ignore_problems {
-        String::new()
-    } else {
-        delayed_errors.join("\n")
-    };
-    (main_fn_name, delayed_errors, env.module)
+    (main_fn_name, env.module)
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -312,7 +356,7 @@ pub fn helper<'a>(
     let target = target_lexicon::Triple::host();
 
     let (main_fn_name, delayed_errors, module) =
-        create_llvm_module(arena, src, config, context, &target);
+        create_llvm_module(arena, src, config, context, &target, false);
 
     let res_lib = if config.add_debug_info {
         let module = annotate_with_debug_info(module, context);
@@ -326,6 +370,60 @@ pub fn helper<'a>(
     (main_fn_name, delayed_errors, lib)
 }
 
+/// Compiles mono IR in the textual syntax of `roc_mono::debug::parse_procs` into a dylib,
+/// without going through the frontend. The first procedure is the entry point.
+#[allow(dead_code)]
+pub fn ir_helper<'a>(
+    arena: &'a bumpalo::Bump,
+    ir: &str,
+    context: &'a inkwell::context::Context,
+) -> (&'static str, Library) {
+    let target = target_lexicon::Triple::host();
+    let config = HelperConfig {
+        mode: LlvmBackendMode::GenTest,
+        ignore_problems: false,
+        add_debug_info: false,
+        opt_level: OPT_LEVEL,
+    };
+
+    let parsed = crate::helpers::parse_ir(arena, ir);
+    let (main_fn_name, module) = llvm_module_from_procedures(
+        arena,
+        config,
+        context,
+        &target,
+        parsed.interns,
+        parsed.procedures,
+        parsed.entry_point,
+        None,
+    );
+
+    let lib = llvm_module_to_dylib(module, &target, config.opt_level)
+        .expect("Error loading compiled dylib for test");
+
+    (main_fn_name, lib)
+}
+
+/// Builds the LLVM module for the source with debug info that points at the source, and returns
+/// the module as text.
+#[allow(dead_code)]
+pub fn llvm_ir_with_source_debug_info(src: &str) -> String {
+    let arena = bumpalo::Bump::new();
+    let context = inkwell::context::Context::create();
+    let target = target_lexicon::Triple::host();
+    let config = HelperConfig {
+        mode: LlvmBackendMode::GenTest,
+        ignore_problems: false,
+        add_debug_info: false,
+        opt_level: OptLevel::Normal,
+    };
+
+    let (_main_fn_name, _delayed_errors, module) =
+        create_llvm_module(&arena, src, config, &context, &target, true);
+
+    module.print_to_string().to_string()
+}
+
 fn annotate_with_debug_info<'ctx>(
     module: &Module<'ctx>,
     context: &'ctx inkwell::context::Context,
@@ -402,7 +500,7 @@ fn compile_to_wasm_bytes<'a>(
     let target = wasm32_target_tripple();
 
     let (_main_fn_name, _delayed_errors, llvm_module) =
-        create_llvm_module(arena, src, config, context, &target);
+        create_llvm_module(arena, src, config, context, &target, false);
 
     let content_hash = crate::helpers::src_hash(src);
     let wasm_file = llvm_module_to_wasm_file(&TEMP_DIR, content_hash, llvm_module);
@@ -596,6 +694,31 @@ macro_rules! assert_llvm_evals_to {
     };
 }
 
+/// Like `assert_llvm_evals_to`, for mono IR in the textual syntax of `roc_mono::debug::parse_procs`
+#[allow(unused_macros)]
+macro_rules! assert_ir_evals_to {
+    ($ir:expr, $expected:expr, $ty:ty) => {{
+        use bumpalo::Bump;
+        use inkwell::context::Context;
+        use roc_gen_llvm::try_run_jit_function;
+
+        let arena = Bump::new();
+        let context = Context::create();
+
+        let (main_fn_name, lib) = $crate::helpers::llvm::ir_helper(&arena, $ir, &context);
+
+        let transform = |success| {
+            let expected = $expected;
+            assert_eq!(&success, &expected, "LLVM test failed");
+        };
+
+        match try_run_jit_function!(lib, main_fn_name, $ty, transform) {
+            Ok(raw) => transform(raw),
+            Err(msg) => panic!("Roc failed with message: \"{}\"", msg),
+        }
+    }};
+}
+
 #[allow(unused_macros)]
 macro_rules! assert_evals_to {
     ($src:expr, $expected:expr, $ty:ty) => {{
@@ -658,6 +781,8 @@ pub fn identity<T>(value: T) -> T {
 #[allow(unused_imports)]
 pub(crate) use assert_evals_to;
 #[allow(unused_imports)]
+pub(crate) use assert_ir_evals_to;
+#[allow(unused_imports)]
 pub(crate) use assert_llvm_evals_to;
 #[allow(unused_imports)]
 pub(crate) use assert_wasm_evals_to;
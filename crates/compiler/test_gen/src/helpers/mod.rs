@@ -1,5 +1,10 @@
 extern crate bumpalo;
 
+use roc_collections::all::MutMap;
+use roc_module::ident::ModuleName;
+use roc_module::symbol::{IdentIds, Interns, ModuleId, ModuleIds, Symbol};
+use roc_mono::ir::{EntryPoint, Proc, ProcLayout};
+
 pub mod platform_functions;
 
 #[cfg(feature = "gen-dev")]
@@ -18,6 +23,84 @@ pub fn zig_executable() -> String {
     }
 }
 
+/// The mono passes for the dev and wasm backend tests. Folded constants would leave the
+/// backend nothing to test, so only the tests of the simplify pass itself turn it on.
+#[allow(dead_code)]
+pub(crate) fn dev_mono_passes(simplify: bool) -> roc_load::MonoPasses {
+    roc_load::MonoPasses {
+        simplify,
+        ..roc_load::MonoPasses::for_opt_level(roc_mono::ir::OptLevel::Development)
+    }
+}
+
+/// Procedures read from mono IR in the textual syntax of `roc_mono::debug::parse_procs`, to
+/// feed a backend without going through the frontend
+#[allow(dead_code)]
+pub(crate) struct ParsedIr<'a> {
+    pub home: ModuleId,
+    pub interns: Interns,
+    pub procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+    pub entry_point: EntryPoint<'a>,
+}
+
+/// Parses mono IR for a backend test. The first procedure is the entry point, and its module
+/// is the home module that the backend adds its helper procedures to.
+#[allow(dead_code)]
+pub(crate) fn parse_ir<'a>(arena: &'a bumpalo::Bump, ir: &str) -> ParsedIr<'a> {
+    let mut interns = Interns {
+        module_ids: ModuleIds::default(),
+        all_ident_ids: IdentIds::exposed_builtins(1),
+    };
+
+    let procs = roc_mono::debug::parse_procs(arena, ir, &mut interns)
+        .unwrap_or_else(|error| panic!("Could not parse the mono IR: {}\n\n{}", error, ir));
+
+    let entry_point = match procs.first() {
+        Some(((symbol, layout), _)) => EntryPoint {
+            symbol: *symbol,
+            layout: *layout,
+        },
+        None => panic!("The mono IR has no procedures:\n\n{}", ir),
+    };
+    let home = entry_point.symbol.module_id();
+
+    name_idents(&mut interns, ir);
+    interns.all_ident_ids.get_or_insert(home);
+
+    ParsedIr {
+        home,
+        interns,
+        procedures: procs.into_iter().collect(),
+        entry_point,
+    }
+}
+
+/// The backends name functions after their symbols, but the textual IR only has ident ids,
+/// so every `Module.<id>` that is mentioned gets a name: the id itself.
+fn name_idents(interns: &mut Interns, ir: &str) {
+    let words = ir.split(|c: char| !(c.is_alphanumeric() || matches!(c, '.' | '#' | '_')));
+
+    for word in words {
+        let symbol = word.rsplit_once('.').and_then(|(module_name, ident_id)| {
+            let is_module_name = module_name.starts_with(|c: char| c.is_alphabetic() || c == '#');
+
+            match ident_id.parse::<usize>() {
+                Ok(ident_id) if is_module_name => Some((module_name, ident_id)),
+                _ => None,
+            }
+        });
+
+        if let Some((module_name, ident_id)) = symbol {
+            let module_id = interns.module_id(&ModuleName::from(module_name));
+            let ident_ids = interns.all_ident_ids.get_or_insert(module_id);
+
+            while ident_ids.len() <= ident_id {
+                ident_ids.gen_unique();
+            }
+        }
+    }
+}
+
 #[allow(dead_code)]
 pub(crate) fn src_hash(src: &str) -> u64 {
     use std::collections::hash_map::DefaultHasher;
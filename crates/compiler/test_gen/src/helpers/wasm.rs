@@ -1,10 +1,12 @@
 use super::RefCount;
 use crate::helpers::from_wasm32_memory::FromWasm32Memory;
-use roc_collections::all::MutSet;
+use roc_collections::all::{MutMap, MutSet};
 use roc_gen_wasm::wasm32_result::Wasm32Result;
 use roc_gen_wasm::wasm_module::{Export, ExportType};
-use roc_gen_wasm::DEBUG_SETTINGS;
-use roc_load::{ExecutionMode, LoadConfig, Threading};
+use roc_gen_wasm::{WasmFeatures, DEBUG_SETTINGS};
+use roc_load::{ExecutionMode, LoadConfig, MonoPasses, Threading};
+use roc_module::symbol::{Interns, ModuleId, Symbol};
+use roc_mono::ir::{Proc, ProcLayout};
 use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::rc::Rc;
@@ -48,13 +50,21 @@ fn write_final_wasm() -> bool {
 pub fn compile_to_wasm_bytes<'a, T: Wasm32Result>(
     arena: &'a bumpalo::Bump,
     src: &str,
+    features: WasmFeatures,
+    mono_passes: MonoPasses,
     test_wrapper_type_info: PhantomData<T>,
 ) -> Vec<u8> {
     let platform_bytes = include_bytes!(host_bytes_path!());
     println!("Loading test host {}", host_bytes_path!());
 
-    let compiled_bytes =
-        compile_roc_to_wasm_bytes(arena, platform_bytes, src, test_wrapper_type_info);
+    let compiled_bytes = compile_roc_to_wasm_bytes(
+        arena,
+        platform_bytes,
+        src,
+        features,
+        mono_passes,
+        test_wrapper_type_info,
+    );
 
     if write_final_wasm() {
         let build_dir_hash = crate::helpers::src_hash(src);
@@ -68,6 +78,8 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
     arena: &'a bumpalo::Bump,
     host_bytes: &[u8],
     src: &str,
+    features: WasmFeatures,
+    mono_passes: MonoPasses,
     _test_wrapper_type_info: PhantomData<T>,
 ) -> Vec<u8> {
     let filename = PathBuf::from("Test.roc");
@@ -89,6 +101,8 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
         render: roc_reporting::report::RenderTarget::ColorTerminal,
         threading: Threading::Single,
         exec_mode: ExecutionMode::Executable,
+        type_cache_dir: None,
+        mono_passes,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -105,7 +119,7 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
     let MonomorphizedModule {
         module_id,
         procedures,
-        mut interns,
+        interns,
         exposed_to_host,
         ..
     } = loaded;
@@ -118,11 +132,33 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
         .copied()
         .collect::<MutSet<_>>();
 
+    build_app_module_bytes::<T>(
+        arena,
+        host_bytes,
+        features,
+        module_id,
+        interns,
+        exposed_to_host,
+        procedures,
+    )
+}
+
+/// Generates code for the procedures and links it into the test host
+fn build_app_module_bytes<'a, T: Wasm32Result>(
+    arena: &'a bumpalo::Bump,
+    host_bytes: &[u8],
+    features: WasmFeatures,
+    module_id: ModuleId,
+    mut interns: Interns,
+    exposed_to_host: MutSet<Symbol>,
+    procedures: MutMap<(Symbol, ProcLayout<'a>), Proc<'a>>,
+) -> Vec<u8> {
     let env = roc_gen_wasm::Env {
         arena,
         module_id,
         exposed_to_host,
         stack_bytes: roc_gen_wasm::Env::DEFAULT_STACK_BYTES,
+        features,
     };
 
     let host_module = roc_gen_wasm::parse_host(env.arena, host_bytes).unwrap_or_else(|e| {
@@ -164,12 +200,73 @@ fn compile_roc_to_wasm_bytes<'a, T: Wasm32Result>(
 
 #[allow(dead_code)]
 pub fn assert_evals_to_help<T>(src: &str, phantom: PhantomData<T>) -> Result<T, String>
+where
+    T: FromWasm32Memory + Wasm32Result,
+{
+    assert_evals_to_with_features_help(src, WasmFeatures::default(), phantom)
+}
+
+/// Like `assert_evals_to_help`, but lets the generated code use WebAssembly proposals
+#[allow(dead_code)]
+pub fn assert_evals_to_with_features_help<T>(
+    src: &str,
+    features: WasmFeatures,
+    phantom: PhantomData<T>,
+) -> Result<T, String>
+where
+    T: FromWasm32Memory + Wasm32Result,
+{
+    let arena = bumpalo::Bump::new();
+
+    let mono_passes = crate::helpers::dev_mono_passes(false);
+    let wasm_bytes =
+        crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, features, mono_passes, phantom);
+
+    run_wasm_test_bytes::<T>(TEST_WRAPPER_NAME, wasm_bytes)
+}
+
+/// Like `assert_evals_to_help`, for mono IR in the textual syntax of `roc_mono::debug::parse_procs`
+#[allow(dead_code)]
+pub fn assert_ir_evals_to_help<T>(ir: &str) -> Result<T, String>
+where
+    T: FromWasm32Memory + Wasm32Result,
+{
+    let arena = bumpalo::Bump::new();
+    let parsed = crate::helpers::parse_ir(&arena, ir);
+
+    let wasm_bytes = build_app_module_bytes::<T>(
+        &arena,
+        include_bytes!(host_bytes_path!()),
+        WasmFeatures::default(),
+        parsed.home,
+        parsed.interns,
+        std::iter::once(parsed.entry_point.symbol).collect(),
+        parsed.procedures,
+    );
+
+    if write_final_wasm() {
+        crate::helpers::save_wasm_file(&wasm_bytes, crate::helpers::src_hash(ir))
+    };
+
+    run_wasm_test_bytes::<T>(TEST_WRAPPER_NAME, wasm_bytes)
+}
+
+/// Like `assert_evals_to_help`, with constants folded and dead code removed from the mono IR
+#[allow(dead_code)]
+pub fn assert_simplified_evals_to_help<T>(src: &str, phantom: PhantomData<T>) -> Result<T, String>
 where
     T: FromWasm32Memory + Wasm32Result,
 {
     let arena = bumpalo::Bump::new();
 
-    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, phantom);
+    let mono_passes = crate::helpers::dev_mono_passes(true);
+    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(
+        &arena,
+        src,
+        WasmFeatures::default(),
+        mono_passes,
+        phantom,
+    );
 
     run_wasm_test_bytes::<T>(TEST_WRAPPER_NAME, wasm_bytes)
 }
@@ -239,7 +336,14 @@ where
 {
     let arena = bumpalo::Bump::new();
 
-    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(&arena, src, phantom);
+    let mono_passes = crate::helpers::dev_mono_passes(false);
+    let wasm_bytes = crate::helpers::wasm::compile_to_wasm_bytes(
+        &arena,
+        src,
+        WasmFeatures::default(),
+        mono_passes,
+        phantom,
+    );
 
     let env = Environment::new().expect("Unable to create environment");
     let rt = env
@@ -385,6 +489,33 @@ macro_rules! assert_evals_to {
     }};
 }
 
+/// Like `assert_evals_to`, for mono IR in the textual syntax of `roc_mono::debug::parse_procs`
+#[allow(unused_macros)]
+macro_rules! assert_ir_evals_to {
+    ($ir:expr, $expected:expr, $ty:ty) => {{
+        match $crate::helpers::wasm::assert_ir_evals_to_help::<$ty>($ir) {
+            Err(msg) => panic!("{}", msg),
+            Ok(actual) => {
+                assert_eq!(actual, $expected)
+            }
+        }
+    }};
+}
+
+/// Like `assert_evals_to`, with constants folded and dead code removed from the mono IR
+#[allow(unused_macros)]
+macro_rules! assert_simplified_evals_to {
+    ($src:expr, $expected:expr, $ty:ty) => {{
+        let phantom = std::marker::PhantomData;
+        match $crate::helpers::wasm::assert_simplified_evals_to_help::<$ty>($src, phantom) {
+            Err(msg) => panic!("{}", msg),
+            Ok(actual) => {
+                assert_eq!(actual, $expected)
+            }
+        }
+    }};
+}
+
 #[allow(unused_macros)]
 macro_rules! expect_runtime_error_panic {
     ($src:expr) => {{
@@ -428,5 +559,11 @@ pub(crate) use assert_evals_to;
 #[allow(unused_imports)]
 pub(crate) use expect_runtime_error_panic;
 
+#[allow(unused_imports)]
+pub(crate) use assert_ir_evals_to;
+
 #[allow(unused_imports)]
 pub(crate) use assert_refcounts;
+
+#[allow(unused_imports)]
+pub(crate) use assert_simplified_evals_to;
@@ -6,7 +6,9 @@
 
 pub mod gen_abilities;
 pub mod gen_compare;
+pub mod gen_crash;
 pub mod gen_dict;
+pub mod gen_ir;
 pub mod gen_list;
 pub mod gen_num;
 pub mod gen_primitives;
@@ -14,12 +16,18 @@ pub mod gen_records;
 pub mod gen_refcount;
 pub mod gen_result;
 pub mod gen_set;
+#[cfg(any(feature = "gen-dev", feature = "gen-wasm"))]
+pub mod gen_simplify;
 pub mod gen_str;
 pub mod gen_tags;
+pub mod gen_tuples;
 mod helpers;
 pub mod wasm_str;
 
 #[cfg(feature = "gen-wasm")]
 pub mod wasm_linking;
 
+#[cfg(feature = "gen-wasm")]
+pub mod wasm_proposals;
+
 pub use helpers::platform_functions::*;
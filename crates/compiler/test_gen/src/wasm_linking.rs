@@ -1,7 +1,7 @@
 #![cfg(feature = "gen-wasm")]
 
 use bumpalo::Bump;
-use roc_gen_wasm::Env;
+use roc_gen_wasm::{Env, WasmFeatures};
 use std::fs;
 use std::process::Command;
 
@@ -19,6 +19,7 @@ use roc_mono::ir::{
     UpdateModeId,
 };
 use roc_mono::layout::{Builtin, CapturesNiche, LambdaName, Layout};
+use roc_region::all::Region;
 use wasm3::{Environment, Module};
 
 const LINKING_TEST_HOST_WASM: &str = "build/wasm_linking_test_host.wasm";
@@ -119,6 +120,8 @@ fn build_app_mono<'a>(
         is_self_recursive: SelfRecursive::NotSelfRecursive,
         must_own_arguments: false,
         host_exposed_layouts: HostExposedLayouts::NotHostExposed,
+        region: Region::zero(),
+        symbol_regions: &[],
     };
 
     let proc_layout = ProcLayout {
@@ -162,6 +165,7 @@ impl<'a> BackendInputs<'a> {
             module_id,
             exposed_to_host,
             stack_bytes: Env::DEFAULT_STACK_BYTES,
+            features: WasmFeatures::default(),
         };
 
         // Identifier stuff for the backend
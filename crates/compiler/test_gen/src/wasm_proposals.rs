@@ -0,0 +1,172 @@
+// Tests for the code that gen_wasm generates when WebAssembly proposals are enabled
+#![cfg(feature = "gen-wasm")]
+
+use crate::helpers::from_wasm32_memory::FromWasm32Memory;
+use crate::helpers::wasm::assert_evals_to_with_features_help;
+use indoc::indoc;
+use roc_gen_wasm::wasm32_result::Wasm32Result;
+use roc_gen_wasm::WasmFeatures;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+/// The result must be the same with and without the proposals
+fn assert_evals_to_with_proposals<T>(src: &str, expected: T)
+where
+    T: FromWasm32Memory + Wasm32Result + PartialEq + Debug,
+{
+    for features in [WasmFeatures::ALL, WasmFeatures::default()] {
+        match assert_evals_to_with_features_help::<T>(src, features, PhantomData) {
+            Err(msg) => panic!("{:?}: {}", features, msg),
+            Ok(actual) => assert_eq!(actual, expected, "{:?}", features),
+        }
+    }
+}
+
+#[test]
+fn deep_mutual_recursion() {
+    let src = indoc!(
+        r#"
+        app "test" provides [main] to "./platform"
+
+        ping : I64, I64 -> I64
+        ping = \n, acc ->
+            if n == 0 then
+                acc
+            else
+                pong (n - 1) (acc + 1)
+
+        pong : I64, I64 -> I64
+        pong = \n, acc ->
+            if n == 0 then
+                acc
+            else
+                ping (n - 1) (acc + 2)
+
+        main = ping 100_000 0
+        "#
+    );
+
+    let with_tail_calls =
+        assert_evals_to_with_features_help::<i64>(src, WasmFeatures::ALL, PhantomData);
+    assert_eq!(with_tail_calls, Ok(150_000));
+
+    // Every call gets a new frame on the VM stack, which is not big enough for all of them
+    let without_tail_calls =
+        assert_evals_to_with_features_help::<i64>(src, WasmFeatures::default(), PhantomData);
+    assert!(without_tail_calls.is_err(), "{:?}", without_tail_calls);
+}
+
+// The callees below are recursive, so that they are not inlined into `main`.
+// `main` is exposed to the host, so it returns its value through a pointer argument.
+
+#[test]
+fn return_4_byte_struct() {
+    assert_evals_to_with_proposals(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            make : I16, I16 -> { a : I16, b : I16 }
+            make = \n, x ->
+                if n == 0 then
+                    { a: x, b: x * 2 }
+                else
+                    make (n - 1) (x + 1)
+
+            main = make 3 18
+            "#
+        ),
+        (21i16, 42i16),
+    );
+}
+
+#[test]
+fn return_8_byte_struct() {
+    assert_evals_to_with_proposals(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            make : I32, I32 -> { a : I32, b : I32 }
+            make = \n, x ->
+                if n == 0 then
+                    { a: x, b: x * 2 }
+                else
+                    make (n - 1) (x + 1)
+
+            main = make 3 18
+            "#
+        ),
+        (21i32, 42i32),
+    );
+}
+
+#[test]
+fn return_12_byte_struct() {
+    assert_evals_to_with_proposals(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            make : I32, I32 -> { a : I32, b : I32, c : I32 }
+            make = \n, x ->
+                if n == 0 then
+                    { a: x, b: x * 2, c: x * 3 }
+                else
+                    make (n - 1) (x + 1)
+
+            main = make 3 18
+            "#
+        ),
+        (21i32, 42i32, 63i32),
+    );
+}
+
+#[test]
+fn return_16_byte_struct() {
+    assert_evals_to_with_proposals(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            make : I64, I64 -> { a : I64, b : I64 }
+            make = \n, x ->
+                if n == 0 then
+                    { a: x, b: x * 2 }
+                else
+                    make (n - 1) (x + 1)
+
+            main = make 3 18
+            "#
+        ),
+        (21i64, 42i64),
+    );
+}
+
+#[test]
+fn tail_call_returning_struct() {
+    assert_evals_to_with_proposals(
+        indoc!(
+            r#"
+            app "test" provides [main] to "./platform"
+
+            evens : I64, I64 -> { count : I64, last : I64 }
+            evens = \n, count ->
+                if n == 0 then
+                    { count, last: n }
+                else
+                    odds (n - 1) (count + 1)
+
+            odds : I64, I64 -> { count : I64, last : I64 }
+            odds = \n, count ->
+                if n == 0 then
+                    { count, last: n + 1 }
+                else
+                    evens (n - 1) count
+
+            main = evens 10_001 0
+            "#
+        ),
+        (5_001i64, 1i64),
+    );
+}
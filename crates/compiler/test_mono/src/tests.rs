@@ -19,7 +19,8 @@ use test_mono_macros::*;
 
 use roc_collections::all::MutMap;
 use roc_load::Threading;
-use roc_module::symbol::Symbol;
+use roc_module::symbol::{Interns, Symbol};
+use roc_mono::ir::MonoPasses;
 use roc_mono::ir::Proc;
 use roc_mono::ir::ProcLayout;
 
@@ -73,7 +74,7 @@ fn promote_expr_to_module(src: &str) -> String {
     buffer
 }
 
-fn compiles_to_ir(test_name: &str, src: &str) {
+fn compiles_to_ir(test_name: &str, src: &str, mono_passes: MonoPasses) {
     use bumpalo::Bump;
     use std::path::PathBuf;
 
@@ -93,11 +94,17 @@ fn compiles_to_ir(test_name: &str, src: &str) {
         module_src = &temp;
     }
 
+    // Check the mono IR after every pass, including the refcounts that inc_dec inserts.
+    // A pass that breaks the IR then fails here, rather than in one of the backends.
+    std::env::set_var("ROC_CHECK_MONO_IR", "1");
+
     let load_config = LoadConfig {
         target_info: TARGET_INFO,
         threading: Threading::Single,
         render: roc_reporting::report::RenderTarget::Generic,
         exec_mode: ExecutionMode::Executable,
+        type_cache_dir: None,
+        mono_passes,
     };
     let loaded = roc_load::load_and_monomorphize_from_str(
         arena,
@@ -122,6 +129,7 @@ fn compiles_to_ir(test_name: &str, src: &str) {
         module_id: home,
         procedures,
         exposed_to_host,
+        interns,
         ..
     } = loaded;
 
@@ -138,6 +146,7 @@ fn compiles_to_ir(test_name: &str, src: &str) {
 
     let main_fn_symbol = exposed_to_host.values.keys().copied().next().unwrap();
 
+    verify_text_round_trip(&procedures, &interns);
     verify_procedures(test_name, procedures, main_fn_symbol);
 }
 
@@ -197,6 +206,28 @@ fn verify_procedures(
     }
 }
 
+/// Print the procs in the textual mono IR, parse them back, and check that printing the parsed
+/// procs gives the same text. This makes sure the syntax covers everything the tests produce.
+fn verify_text_round_trip(
+    procedures: &MutMap<(Symbol, ProcLayout<'_>), Proc<'_>>,
+    interns: &Interns,
+) {
+    use roc_mono::debug::{parse_procs, procs_to_text};
+
+    let text = procs_to_text(procedures.values(), interns);
+
+    let arena = bumpalo::Bump::new();
+    let mut interns = interns.clone();
+    let parsed = match parse_procs(&arena, &text, &mut interns) {
+        Ok(parsed) => parsed,
+        Err(error) => panic!("Could not parse the textual mono IR: {}\n\n{}", error, text),
+    };
+
+    let reprinted = procs_to_text(parsed.iter().map(|(_, proc)| proc), &interns);
+
+    assert_eq!(text, reprinted);
+}
+
 // NOTE because the Show instance of module names is different in --release mode,
 // these tests would all fail. In the future, when we do interesting optimizations,
 // we'll likely want some tests for --release too.
@@ -461,6 +492,41 @@ fn let_with_record_pattern() {
     "#
 }
 
+#[mono_test]
+fn let_with_tuple_pattern() {
+    r#"
+    (x, _) = (0x2, 3.14)
+
+    x
+    "#
+}
+
+#[mono_test]
+fn ir_when_tuple() {
+    r#"
+    when (1, 3.14) is
+        (x, _) -> x
+    "#
+}
+
+#[mono_test]
+fn ir_when_list_first() {
+    r#"
+    when [1, 2] is
+        [x, ..] -> x
+        _ -> 0
+    "#
+}
+
+#[mono_test]
+fn ir_when_list_last() {
+    r#"
+    when [1, 2, 3] is
+        [.., x] -> x
+        _ -> 0
+    "#
+}
+
 #[mono_test]
 fn let_with_record_pattern_list() {
     r#"
@@ -717,6 +783,66 @@ fn factorial() {
     "#
 }
 
+#[mono_test(inline)]
+fn inline_num_add() {
+    r#"
+    1 + 2
+    "#
+}
+
+#[mono_test(inline)]
+fn inline_factorial() {
+    r#"
+    factorial = \n, accum ->
+        when n is
+            0 ->
+                accum
+
+            _ ->
+                factorial (n - 1) (n * accum)
+
+    factorial 10 1
+    "#
+}
+
+#[mono_test(inline, simplify)]
+fn simplify_num_add() {
+    r#"
+    1 + 2
+    "#
+}
+
+#[mono_test(simplify)]
+fn simplify_when_maybe() {
+    r#"
+    when Just 3 is
+        Just n -> n
+        Nothing -> 0
+    "#
+}
+
+#[mono_test(simplify)]
+fn simplify_when_these() {
+    r#"
+    when These 1 2 is
+        This x -> x
+        That y -> y
+        These x _ -> x
+    "#
+}
+
+#[mono_test(simplify)]
+fn simplify_guard_pattern_true() {
+    r#"
+    wrapper = \{} ->
+        when 2 is
+            2 if True -> 42
+            _ -> 0
+
+    wrapper {}
+    "#
+}
+
 #[mono_test]
 fn is_nil() {
     r#"
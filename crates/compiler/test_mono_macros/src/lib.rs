@@ -3,10 +3,19 @@ extern crate proc_macro;
 use proc_macro::TokenStream;
 use quote::quote;
 
+/// The arguments name optional mono IR passes to run, e.g. `#[mono_test(inline)]`
 #[proc_macro_attribute]
-pub fn mono_test(_args: TokenStream, item: TokenStream) -> TokenStream {
+pub fn mono_test(args: TokenStream, item: TokenStream) -> TokenStream {
+    let passes = syn::parse_macro_input!(args as syn::AttributeArgs);
     let task_fn = syn::parse_macro_input!(item as syn::ItemFn);
 
+    let passes = passes.into_iter().map(|arg| match arg {
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.get_ident().is_some() => {
+            path.get_ident().unwrap().clone()
+        }
+        _ => panic!("mono_test arguments must be names of mono IR passes"),
+    });
+
     let args = task_fn.sig.inputs.clone();
 
     let name = task_fn.sig.ident.clone();
@@ -20,7 +29,11 @@ pub fn mono_test(_args: TokenStream, item: TokenStream) -> TokenStream {
         #[test]
         #(#attributes)*
         #visibility fn #name(#args) {
-            compiles_to_ir(#name_str, #body);
+            #[allow(unused_mut)]
+            let mut mono_passes = MonoPasses::default();
+            #(mono_passes.#passes = true;)*
+
+            compiles_to_ir(#name_str, #body, mono_passes);
 
         }
     };
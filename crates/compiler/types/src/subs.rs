@@ -1,6 +1,7 @@
 #![deny(unsafe_op_in_unsafe_fn)]
 use crate::types::{
-    name_type_var, AliasKind, ErrorType, Problem, RecordField, RecordFieldsError, TypeExt, Uls,
+    name_type_var, AliasKind, ErrorType, Problem, RecordField, RecordFieldsError, TupleElemsError,
+    TypeExt, Uls,
 };
 use roc_collections::all::{ImMap, ImSet, MutSet, SendMap};
 use roc_collections::{VecMap, VecSet};
@@ -19,6 +20,7 @@ roc_error_macros::assert_sizeof_all!(Descriptor, 5 * 8 + 4);
 roc_error_macros::assert_sizeof_all!(FlatType, 3 * 8);
 roc_error_macros::assert_sizeof_all!(UnionTags, 12);
 roc_error_macros::assert_sizeof_all!(RecordFields, 2 * 8);
+roc_error_macros::assert_sizeof_all!(TupleElems, 12);
 
 roc_error_macros::assert_sizeof_aarch64!(Problem, 6 * 8);
 roc_error_macros::assert_sizeof_wasm!(Problem, 32);
@@ -75,6 +77,7 @@ struct SubsHeader {
     closure_names: u64,
     field_names: u64,
     record_fields: u64,
+    tuple_elem_indices: u64,
     variable_slices: u64,
     unspecialized_lambda_sets: u64,
     exposed_vars_by_symbol: u64,
@@ -93,6 +96,7 @@ impl SubsHeader {
             closure_names: subs.closure_names.len() as u64,
             field_names: subs.field_names.len() as u64,
             record_fields: subs.record_fields.len() as u64,
+            tuple_elem_indices: subs.tuple_elem_indices.len() as u64,
             variable_slices: subs.variable_slices.len() as u64,
             unspecialized_lambda_sets: subs.unspecialized_lambda_sets.len() as u64,
             exposed_vars_by_symbol: exposed_vars_by_symbol as u64,
@@ -142,6 +146,7 @@ impl Subs {
         written = Self::serialize_slice(&self.closure_names, writer, written)?;
         written = Self::serialize_field_names(&self.field_names, writer, written)?;
         written = Self::serialize_slice(&self.record_fields, writer, written)?;
+        written = Self::serialize_slice(&self.tuple_elem_indices, writer, written)?;
         written = Self::serialize_slice(&self.variable_slices, writer, written)?;
         written = Self::serialize_slice(&self.unspecialized_lambda_sets, writer, written)?;
         written = Self::serialize_slice(exposed_vars_by_symbol, writer, written)?;
@@ -209,30 +214,40 @@ impl Subs {
     }
 
     pub fn deserialize(bytes: &[u8]) -> (Self, &[(Symbol, Variable)]) {
+        Self::try_deserialize(bytes).expect("serialized Subs are malformed")
+    }
+
+    /// Like [`Self::deserialize`], but returns `None` instead of panicking when `bytes` are too
+    /// short for the sizes recorded in them.
+    pub fn try_deserialize(bytes: &[u8]) -> Option<(Self, &[(Symbol, Variable)])> {
         let mut offset = 0;
-        let header_slice = &bytes[..std::mem::size_of::<SubsHeader>()];
+        let header_slice = bytes.get(..std::mem::size_of::<SubsHeader>())?;
         offset += header_slice.len();
         let header = SubsHeader::from_array(header_slice.try_into().unwrap());
 
-        let (utable, offset) = UnificationTable::deserialize(bytes, header.utable as usize, offset);
+        let (utable, offset) =
+            UnificationTable::deserialize(bytes, header.utable as usize, offset)?;
 
-        let (variables, offset) = Self::deserialize_slice(bytes, header.variables as usize, offset);
+        let (variables, offset) =
+            Self::deserialize_slice(bytes, header.variables as usize, offset)?;
         let (tag_names, offset) =
-            Self::deserialize_tag_names(bytes, header.tag_names as usize, offset);
+            Self::deserialize_tag_names(bytes, header.tag_names as usize, offset)?;
         let (closure_names, offset) =
-            Self::deserialize_slice(bytes, header.closure_names as usize, offset);
+            Self::deserialize_slice(bytes, header.closure_names as usize, offset)?;
         let (field_names, offset) =
-            Self::deserialize_field_names(bytes, header.field_names as usize, offset);
+            Self::deserialize_field_names(bytes, header.field_names as usize, offset)?;
         let (record_fields, offset) =
-            Self::deserialize_slice(bytes, header.record_fields as usize, offset);
+            Self::deserialize_slice(bytes, header.record_fields as usize, offset)?;
+        let (tuple_elem_indices, offset) =
+            Self::deserialize_slice(bytes, header.tuple_elem_indices as usize, offset)?;
         let (variable_slices, offset) =
-            Self::deserialize_slice(bytes, header.variable_slices as usize, offset);
+            Self::deserialize_slice(bytes, header.variable_slices as usize, offset)?;
         let (unspecialized_lambda_sets, offset) =
-            Self::deserialize_slice(bytes, header.unspecialized_lambda_sets as usize, offset);
+            Self::deserialize_slice(bytes, header.unspecialized_lambda_sets as usize, offset)?;
         let (exposed_vars_by_symbol, _) =
-            Self::deserialize_slice(bytes, header.exposed_vars_by_symbol as usize, offset);
+            Self::deserialize_slice(bytes, header.exposed_vars_by_symbol as usize, offset)?;
 
-        (
+        Some((
             Self {
                 utable,
                 variables: variables.to_vec(),
@@ -240,6 +255,7 @@ impl Subs {
                 closure_names: closure_names.to_vec(),
                 field_names,
                 record_fields: record_fields.to_vec(),
+                tuple_elem_indices: tuple_elem_indices.to_vec(),
                 variable_slices: variable_slices.to_vec(),
                 unspecialized_lambda_sets: unspecialized_lambda_sets.to_vec(),
                 tag_name_cache: Default::default(),
@@ -247,66 +263,72 @@ impl Subs {
                 uls_of_var: Default::default(),
             },
             exposed_vars_by_symbol,
-        )
+        ))
     }
 
     fn deserialize_field_names(
         bytes: &[u8],
         length: usize,
         offset: usize,
-    ) -> (Vec<Lowercase>, usize) {
-        let (slices, mut offset) = Self::deserialize_slice::<SubsSlice<u8>>(bytes, length, offset);
+    ) -> Option<(Vec<Lowercase>, usize)> {
+        let (slices, mut offset) = Self::deserialize_slice::<SubsSlice<u8>>(bytes, length, offset)?;
 
-        let string_slice = &bytes[offset..];
+        let string_slice = bytes.get(offset..)?;
 
         let mut lowercases = Vec::with_capacity(length);
         for subs_slice in slices {
-            let bytes = &string_slice[subs_slice.indices()];
+            let bytes = string_slice.get(subs_slice.indices())?;
             offset += bytes.len();
-            let string = unsafe { std::str::from_utf8_unchecked(bytes) };
+            let string = std::str::from_utf8(bytes).ok()?;
 
             lowercases.push(string.into());
         }
 
-        (lowercases, offset)
+        Some((lowercases, offset))
     }
 
-    fn deserialize_tag_names(bytes: &[u8], length: usize, offset: usize) -> (Vec<TagName>, usize) {
+    fn deserialize_tag_names(
+        bytes: &[u8],
+        length: usize,
+        offset: usize,
+    ) -> Option<(Vec<TagName>, usize)> {
         let (slices, mut offset) =
-            Self::deserialize_slice::<SerializedTagName>(bytes, length, offset);
+            Self::deserialize_slice::<SerializedTagName>(bytes, length, offset)?;
 
-        let string_slice = &bytes[offset..];
+        let string_slice = bytes.get(offset..)?;
 
         let mut tag_names = Vec::with_capacity(length);
         for SerializedTagName(subs_slice) in slices {
-            let bytes = &string_slice[subs_slice.indices()];
+            let bytes = string_slice.get(subs_slice.indices())?;
             offset += bytes.len();
-            let string = unsafe { std::str::from_utf8_unchecked(bytes) };
+            let string = std::str::from_utf8(bytes).ok()?;
 
             let tag_name = TagName(string.into());
 
             tag_names.push(tag_name);
         }
 
-        (tag_names, offset)
+        Some((tag_names, offset))
     }
 
+    /// Reads `length` values of type `T` starting at the first suitably aligned position at or
+    /// after `offset`. Returns `None` if `bytes` are too short.
     pub(crate) fn deserialize_slice<T>(
         bytes: &[u8],
         length: usize,
         mut offset: usize,
-    ) -> (&[T], usize) {
+    ) -> Option<(&[T], usize)> {
         let alignment = std::mem::align_of::<T>();
         let size = std::mem::size_of::<T>();
 
         offset = round_to_multiple_of(offset, alignment);
 
-        let byte_length = length * size;
-        let byte_slice = &bytes[offset..][..byte_length];
+        let byte_length = length.checked_mul(size)?;
+        let byte_slice = bytes.get(offset..)?.get(..byte_length)?;
 
         let slice = unsafe { std::slice::from_raw_parts(byte_slice.as_ptr() as *const T, length) };
 
-        (slice, offset + byte_length)
+        Some((slice, offset + byte_length))
     }
 }
 
@@ -389,6 +411,7 @@ pub struct Subs {
     pub closure_names: Vec<Symbol>,
     pub field_names: Vec<Lowercase>,
     pub record_fields: Vec<RecordField<()>>,
+    pub tuple_elem_indices: Vec<usize>,
     pub variable_slices: Vec<VariableSubsSlice>,
     pub unspecialized_lambda_sets: Vec<Uls>,
     pub tag_name_cache: TagNameCache,
@@ -525,6 +548,20 @@ impl std::ops::IndexMut<SubsIndex<RecordField<()>>> for Subs {
     }
 }
 
+impl std::ops::Index<SubsIndex<usize>> for Subs {
+    type Output = usize;
+
+    fn index(&self, index: SubsIndex<usize>) -> &Self::Output {
+        &self.tuple_elem_indices[index.index as usize]
+    }
+}
+
+impl std::ops::IndexMut<SubsIndex<usize>> for Subs {
+    fn index_mut(&mut self, index: SubsIndex<usize>) -> &mut Self::Output {
+        &mut self.tuple_elem_indices[index.index as usize]
+    }
+}
+
 impl std::ops::Index<SubsIndex<VariableSubsSlice>> for Subs {
     type Output = VariableSubsSlice;
 
@@ -736,6 +773,12 @@ impl GetSubsSlice<RecordField<()>> for Subs {
     }
 }
 
+impl GetSubsSlice<usize> for Subs {
+    fn get_subs_slice(&self, subs_slice: SubsSlice<usize>) -> &[usize] {
+        subs_slice.get_slice(&self.tuple_elem_indices)
+    }
+}
+
 impl GetSubsSlice<Lowercase> for Subs {
     fn get_subs_slice(&self, subs_slice: SubsSlice<Lowercase>) -> &[Lowercase] {
         subs_slice.get_slice(&self.field_names)
@@ -943,6 +986,21 @@ fn subs_fmt_flat_type(this: &FlatType, subs: &Subs, f: &mut fmt::Formatter) -> f
 
             write!(f, "}}<{:?}>", new_ext)
         }
+        FlatType::Tuple(elems, ext) => {
+            write!(f, "( ")?;
+
+            let (it, new_ext) = elems.sorted_iterator_and_ext(subs, *ext);
+            for (index, var) in it {
+                write!(
+                    f,
+                    "{:?}: {:?}, ",
+                    index,
+                    SubsFmtContent(subs.get_content_without_compacting(var), subs)
+                )?;
+            }
+
+            write!(f, ")<{:?}>", new_ext)
+        }
         FlatType::TagUnion(tags, ext) => {
             write!(f, "[")?;
 
@@ -983,6 +1041,7 @@ fn subs_fmt_flat_type(this: &FlatType, subs: &Subs, f: &mut fmt::Formatter) -> f
         }
         FlatType::Erroneous(e) => write!(f, "Erroneous({:?})", e),
         FlatType::EmptyRecord => write!(f, "EmptyRecord"),
+        FlatType::EmptyTuple => write!(f, "EmptyTuple"),
         FlatType::EmptyTagUnion => write!(f, "EmptyTagUnion"),
     }
 }
@@ -1316,6 +1375,9 @@ define_const_var! {
     // The following are abound in derived abilities, so we cache them.
     :pub STR,
     :pub LIST_U8,
+
+    // Kept after the other reserved variables so that their indices stay stable.
+    :pub EMPTY_TUPLE,
 }
 
 impl Variable {
@@ -1691,6 +1753,7 @@ impl Subs {
             closure_names: Vec::new(),
             field_names: Vec::new(),
             record_fields: Vec::new(),
+            tuple_elem_indices: Vec::new(),
             // store an empty slice at the first position
             // used for "TagOrFunction"
             variable_slices: vec![VariableSubsSlice::default()],
@@ -1713,6 +1776,10 @@ impl Subs {
             Variable::EMPTY_TAG_UNION,
             Content::Structure(FlatType::EmptyTagUnion),
         );
+        subs.set_content(
+            Variable::EMPTY_TUPLE,
+            Content::Structure(FlatType::EmptyTuple),
+        );
 
         let bool_union_tags = UnionTags::insert_into_subs(
             &mut subs,
@@ -2403,11 +2470,13 @@ pub enum FlatType {
     Apply(Symbol, VariableSubsSlice),
     Func(VariableSubsSlice, Variable, Variable),
     Record(RecordFields, Variable),
+    Tuple(TupleElems, Variable),
     TagUnion(UnionTags, Variable),
     FunctionOrTagUnion(SubsIndex<TagName>, Symbol, Variable),
     RecursiveTagUnion(Variable, UnionTags, Variable),
     Erroneous(SubsIndex<Problem>),
     EmptyRecord,
+    EmptyTuple,
     EmptyTagUnion,
 }
 
@@ -3107,6 +3176,170 @@ fn is_empty_record(subs: &Subs, mut var: Variable) -> bool {
     }
 }
 
+/// The elements of a tuple type. Each element is stored with its position in the tuple, so that
+/// open tuples (e.g. the argument of the `.1` accessor) need not mention every element.
+#[derive(Clone, Copy, Debug)]
+pub struct TupleElems {
+    pub length: u16,
+    pub elem_index_start: u32,
+    pub variables_start: u32,
+}
+
+pub type SortedElemsIterator<'a> = Box<dyn Iterator<Item = (usize, Variable)> + 'a>;
+
+impl TupleElems {
+    pub const fn len(&self) -> usize {
+        self.length as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            length: 0,
+            elem_index_start: 0,
+            variables_start: 0,
+        }
+    }
+
+    pub const fn variables(&self) -> SubsSlice<Variable> {
+        SubsSlice::new(self.variables_start, self.length)
+    }
+
+    pub const fn elem_indices(&self) -> SubsSlice<usize> {
+        SubsSlice::new(self.elem_index_start, self.length)
+    }
+
+    pub fn iter_variables(&self) -> impl Iterator<Item = SubsIndex<Variable>> {
+        let slice = SubsSlice::new(self.variables_start, self.length);
+        slice.into_iter()
+    }
+
+    pub fn compare(x: &(usize, Variable), y: &(usize, Variable)) -> std::cmp::Ordering {
+        first(x, y)
+    }
+
+    pub fn insert_into_subs<I>(subs: &mut Subs, input: I) -> Self
+    where
+        I: IntoIterator<Item = (usize, Variable)>,
+    {
+        let elem_index_start = subs.tuple_elem_indices.len() as u32;
+        let variables_start = subs.variables.len() as u32;
+
+        let it = input.into_iter();
+        let size_hint = it.size_hint().0;
+
+        subs.tuple_elem_indices.reserve(size_hint);
+        subs.variables.reserve(size_hint);
+
+        let mut length = 0;
+        for (index, var) in it {
+            subs.tuple_elem_indices.push(index);
+            subs.variables.push(var);
+
+            length += 1;
+        }
+
+        TupleElems {
+            length,
+            elem_index_start,
+            variables_start,
+        }
+    }
+
+    #[inline(always)]
+    pub fn unsorted_iterator<'a>(
+        &'a self,
+        subs: &'a Subs,
+        ext: Variable,
+    ) -> Result<impl Iterator<Item = (usize, Variable)> + 'a, TupleElemsError> {
+        let (it, _) = crate::types::gather_tuple_elems_unsorted_iter(subs, *self, ext)?;
+
+        Ok(it)
+    }
+
+    #[inline(always)]
+    pub fn unsorted_iterator_and_ext<'a>(
+        &'a self,
+        subs: &'a Subs,
+        ext: Variable,
+    ) -> (impl Iterator<Item = (usize, Variable)> + 'a, Variable) {
+        let (it, ext) = crate::types::gather_tuple_elems_unsorted_iter(subs, *self, ext)
+            .expect("Something weird ended up in a tuple type");
+
+        (it, ext)
+    }
+
+    /// Get a sorted iterator over the elements of this tuple type
+    ///
+    /// Implementation: When the tuple has an `ext` variable that is the empty tuple, then
+    /// we read the (assumed sorted) elements directly from Subs. Otherwise we have to chase the
+    /// ext var, then sort the elements.
+    #[inline(always)]
+    pub fn sorted_iterator<'a>(&'_ self, subs: &'a Subs, ext: Variable) -> SortedElemsIterator<'a> {
+        self.sorted_iterator_and_ext(subs, ext).0
+    }
+
+    #[inline(always)]
+    pub fn sorted_iterator_and_ext<'a>(
+        &'_ self,
+        subs: &'a Subs,
+        ext: Variable,
+    ) -> (SortedElemsIterator<'a>, Variable) {
+        if is_empty_tuple(subs, ext) {
+            (
+                Box::new(self.iter_all().map(move |(i1, i2)| (subs[i1], subs[i2]))),
+                ext,
+            )
+        } else {
+            let tuple_structure = crate::types::gather_tuple_elems(subs, *self, ext)
+                .expect("Something ended up weird in this tuple type");
+
+            (
+                Box::new(tuple_structure.elems.into_iter()),
+                tuple_structure.ext,
+            )
+        }
+    }
+
+    pub fn iter_all(&self) -> impl Iterator<Item = (SubsIndex<usize>, SubsIndex<Variable>)> {
+        let helper = |start| start..(start + self.length as u32);
+
+        let range1 = helper(self.elem_index_start);
+        let range2 = helper(self.variables_start);
+
+        let it = range1.into_iter().zip(range2.into_iter());
+
+        it.map(|(i1, i2)| (SubsIndex::new(i1), SubsIndex::new(i2)))
+    }
+}
+
+fn is_empty_tuple(subs: &Subs, mut var: Variable) -> bool {
+    use crate::subs::Content::*;
+    use crate::subs::FlatType::*;
+
+    loop {
+        match subs.get_content_without_compacting(var) {
+            Structure(EmptyTuple) => return true,
+            Structure(Tuple(sub_elems, sub_ext)) => {
+                if !sub_elems.is_empty() {
+                    return false;
+                }
+
+                var = *sub_ext;
+            }
+
+            Alias(_, _, actual_var, _) => {
+                var = *actual_var;
+            }
+
+            _ => return false,
+        }
+    }
+}
+
 fn occurs(
     subs: &Subs,
     seen: &[Variable],
@@ -3149,6 +3382,11 @@ fn occurs(
                         let it = once(ext_var).chain(subs.get_subs_slice(slice).iter());
                         short_circuit(subs, root_var, &new_seen, it)
                     }
+                    Tuple(vars_by_elem, ext_var) => {
+                        let slice = vars_by_elem.variables();
+                        let it = once(ext_var).chain(subs.get_subs_slice(slice).iter());
+                        short_circuit(subs, root_var, &new_seen, it)
+                    }
                     TagUnion(tags, ext_var) => {
                         occurs_union(subs, root_var, &new_seen, tags)?;
 
@@ -3163,7 +3401,7 @@ fn occurs(
 
                         short_circuit_help(subs, root_var, &new_seen, *ext_var)
                     }
-                    EmptyRecord | EmptyTagUnion | Erroneous(_) => Ok(()),
+                    EmptyRecord | EmptyTuple | EmptyTagUnion | Erroneous(_) => Ok(()),
                 }
             }
             Alias(_, args, _, _) => {
@@ -3333,8 +3571,19 @@ fn explicit_substitute(
 
                         subs.set_content(in_var, Structure(Record(vars_by_field, new_ext_var)));
                     }
+                    Tuple(vars_by_elem, ext_var) => {
+                        let new_ext_var = explicit_substitute(subs, from, to, ext_var, seen);
+
+                        for index in vars_by_elem.iter_variables() {
+                            let var = subs[index];
+                            let new_var = explicit_substitute(subs, from, to, var, seen);
+                            subs[index] = new_var;
+                        }
 
-                    EmptyRecord | EmptyTagUnion | Erroneous(_) => {}
+                        subs.set_content(in_var, Structure(Tuple(vars_by_elem, new_ext_var)));
+                    }
+
+                    EmptyRecord | EmptyTuple | EmptyTagUnion | Erroneous(_) => {}
                 }
 
                 in_var
@@ -3517,9 +3766,10 @@ fn get_var_names(
                     accum
                 }
 
-                FlatType::EmptyRecord | FlatType::EmptyTagUnion | FlatType::Erroneous(_) => {
-                    taken_names
-                }
+                FlatType::EmptyRecord
+                | FlatType::EmptyTuple
+                | FlatType::EmptyTagUnion
+                | FlatType::Erroneous(_) => taken_names,
 
                 FlatType::Record(vars_by_field, ext_var) => {
                     let mut accum = get_var_names(subs, ext_var, taken_names);
@@ -3532,6 +3782,17 @@ fn get_var_names(
 
                     accum
                 }
+                FlatType::Tuple(vars_by_elem, ext_var) => {
+                    let mut accum = get_var_names(subs, ext_var, taken_names);
+
+                    for var_index in vars_by_elem.iter_variables() {
+                        let arg_var = subs[var_index];
+
+                        accum = get_var_names(subs, arg_var, accum)
+                    }
+
+                    accum
+                }
                 FlatType::TagUnion(tags, ext_var) => {
                     let taken_names = get_var_names(subs, ext_var, taken_names);
                     get_var_names_union(subs, tags, taken_names)
@@ -3796,6 +4057,7 @@ fn flat_type_to_err_type(
         }
 
         EmptyRecord => ErrorType::Record(SendMap::default(), TypeExt::Closed),
+        EmptyTuple => ErrorType::Tuple(Vec::new(), TypeExt::Closed),
         EmptyTagUnion => ErrorType::TagUnion(SendMap::default(), TypeExt::Closed),
 
         Record(vars_by_field, ext_var) => {
@@ -3837,6 +4099,41 @@ fn flat_type_to_err_type(
             }
         }
 
+        Tuple(elems, ext_var) => {
+            let mut err_elems = Vec::with_capacity(elems.len());
+
+            for (i1, i2) in elems.iter_all() {
+                let index = subs[i1];
+                let var = subs[i2];
+
+                let error_type = var_to_err_type(subs, state, var);
+
+                err_elems.push((index, error_type));
+            }
+
+            match var_to_err_type(subs, state, ext_var).unwrap_structural_alias() {
+                ErrorType::Tuple(mut sub_elems, sub_ext) => {
+                    sub_elems.extend(err_elems);
+                    sub_elems.sort_by_key(|(index, _)| *index);
+
+                    ErrorType::Tuple(sub_elems, sub_ext)
+                }
+
+                ErrorType::FlexVar(var) => {
+                    ErrorType::Tuple(err_elems, TypeExt::FlexOpen(var))
+                }
+
+                ErrorType::RigidVar(var) => {
+                    ErrorType::Tuple(err_elems, TypeExt::RigidOpen(var))
+                }
+
+                ErrorType::Error => ErrorType::Error,
+
+                other =>
+                    panic!("Tried to convert a tuple extension to an error, but the tuple extension had the ErrorType of {:?}", other)
+            }
+        }
+
         TagUnion(tags, ext_var) => {
             let err_tags = union_tags_to_err_tags(subs, state, tags);
 
@@ -3994,6 +4291,7 @@ struct StorageSubsOffsets {
     closure_names: u32,
     field_names: u32,
     record_fields: u32,
+    tuple_elem_indices: u32,
     variable_slices: u32,
     unspecialized_lambda_sets: u32,
     problems: u32,
@@ -4054,6 +4352,7 @@ impl StorageSubs {
             closure_names: self.subs.closure_names.len() as u32,
             field_names: self.subs.field_names.len() as u32,
             record_fields: self.subs.record_fields.len() as u32,
+            tuple_elem_indices: self.subs.tuple_elem_indices.len() as u32,
             variable_slices: self.subs.variable_slices.len() as u32,
             unspecialized_lambda_sets: self.subs.unspecialized_lambda_sets.len() as u32,
             problems: self.subs.problems.len() as u32,
@@ -4066,6 +4365,7 @@ impl StorageSubs {
             closure_names: target.closure_names.len() as u32,
             field_names: target.field_names.len() as u32,
             record_fields: target.record_fields.len() as u32,
+            tuple_elem_indices: target.tuple_elem_indices.len() as u32,
             variable_slices: target.variable_slices.len() as u32,
             unspecialized_lambda_sets: target.unspecialized_lambda_sets.len() as u32,
             problems: target.problems.len() as u32,
@@ -4114,6 +4414,9 @@ impl StorageSubs {
         target.closure_names.extend(self.subs.closure_names);
         target.field_names.extend(self.subs.field_names);
         target.record_fields.extend(self.subs.record_fields);
+        target
+            .tuple_elem_indices
+            .extend(self.subs.tuple_elem_indices);
         target
             .unspecialized_lambda_sets
             .extend(self.subs.unspecialized_lambda_sets);
@@ -4154,6 +4457,10 @@ impl StorageSubs {
                 Self::offset_record_fields(offsets, *record_fields),
                 Self::offset_variable(offsets, *ext),
             ),
+            FlatType::Tuple(tuple_elems, ext) => FlatType::Tuple(
+                Self::offset_tuple_elems(offsets, *tuple_elems),
+                Self::offset_variable(offsets, *ext),
+            ),
             FlatType::TagUnion(union_tags, ext) => FlatType::TagUnion(
                 Self::offset_tag_union(offsets, *union_tags),
                 Self::offset_variable(offsets, *ext),
@@ -4172,6 +4479,7 @@ impl StorageSubs {
                 FlatType::Erroneous(Self::offset_problem(offsets, *problem))
             }
             FlatType::EmptyRecord => FlatType::EmptyRecord,
+            FlatType::EmptyTuple => FlatType::EmptyTuple,
             FlatType::EmptyTagUnion => FlatType::EmptyTagUnion,
         }
     }
@@ -4251,6 +4559,13 @@ impl StorageSubs {
         record_fields
     }
 
+    fn offset_tuple_elems(offsets: &StorageSubsOffsets, mut tuple_elems: TupleElems) -> TupleElems {
+        tuple_elems.elem_index_start += offsets.tuple_elem_indices;
+        tuple_elems.variables_start += offsets.variables;
+
+        tuple_elems
+    }
+
     fn offset_tag_name_index(
         offsets: &StorageSubsOffsets,
         mut tag_name: SubsIndex<TagName>,
@@ -4467,7 +4782,10 @@ fn storage_copy_var_to_help(env: &mut StorageCopyVarToEnv<'_>, var: Variable) ->
                     Func(new_arguments, new_closure_var, new_ret_var)
                 }
 
-                same @ EmptyRecord | same @ EmptyTagUnion | same @ Erroneous(_) => same,
+                same @ EmptyRecord
+                | same @ EmptyTuple
+                | same @ EmptyTagUnion
+                | same @ Erroneous(_) => same,
 
                 Record(fields, ext_var) => {
                     let record_fields = {
@@ -4504,6 +4822,36 @@ fn storage_copy_var_to_help(env: &mut StorageCopyVarToEnv<'_>, var: Variable) ->
                     Record(record_fields, storage_copy_var_to_help(env, ext_var))
                 }
 
+                Tuple(elems, ext_var) => {
+                    let tuple_elems = {
+                        let new_variables =
+                            VariableSubsSlice::reserve_into_subs(env.target, elems.len());
+
+                        let it = (new_variables.indices()).zip(elems.iter_variables());
+                        for (target_index, var_index) in it {
+                            let var = env.source[var_index];
+                            let copy_var = storage_copy_var_to_help(env, var);
+                            env.target.variables[target_index] = copy_var;
+                        }
+
+                        let elem_index_start = env.target.tuple_elem_indices.len() as u32;
+
+                        let elem_indices =
+                            &env.source.tuple_elem_indices[elems.elem_indices().indices()];
+                        env.target
+                            .tuple_elem_indices
+                            .extend(elem_indices.iter().copied());
+
+                        TupleElems {
+                            length: elems.len() as _,
+                            elem_index_start,
+                            variables_start: new_variables.start,
+                        }
+                    };
+
+                    Tuple(tuple_elems, storage_copy_var_to_help(env, ext_var))
+                }
+
                 TagUnion(tags, ext_var) => {
                     let new_ext = storage_copy_var_to_help(env, ext_var);
                     let union_tags = storage_copy_union(env, tags);
@@ -4767,7 +5115,7 @@ pub fn copy_import_to(
 
 /// is this content registered (in the current pool) by type_to_variable?
 /// TypeToVar skips registering for flex and rigid variables, and
-/// also for the empty records and tag unions (they used the Variable::EMPTY_RECORD/...)
+/// also for the empty records, tuples and tag unions (they used the Variable::EMPTY_RECORD/...)
 /// standard variables
 fn is_registered(content: &Content) -> bool {
     match content {
@@ -4775,7 +5123,9 @@ fn is_registered(content: &Content) -> bool {
         | Content::RigidVar(_)
         | Content::FlexAbleVar(..)
         | Content::RigidAbleVar(..) => false,
-        Content::Structure(FlatType::EmptyRecord | FlatType::EmptyTagUnion) => false,
+        Content::Structure(
+            FlatType::EmptyRecord | FlatType::EmptyTuple | FlatType::EmptyTagUnion,
+        ) => false,
 
         Content::Structure(_)
         | Content::RecursionVar { .. }
@@ -4905,7 +5255,7 @@ fn copy_import_to_help(env: &mut CopyImportEnv<'_>, max_rank: Rank, var: Variabl
 
                 Erroneous(_) => internal_error!("I thought this was handled above"),
 
-                same @ EmptyRecord | same @ EmptyTagUnion => same,
+                same @ EmptyRecord | same @ EmptyTuple | same @ EmptyTagUnion => same,
 
                 Record(fields, ext_var) => {
                     let record_fields = {
@@ -4942,6 +5292,36 @@ fn copy_import_to_help(env: &mut CopyImportEnv<'_>, max_rank: Rank, var: Variabl
                     Record(record_fields, copy_import_to_help(env, max_rank, ext_var))
                 }
 
+                Tuple(elems, ext_var) => {
+                    let tuple_elems = {
+                        let new_variables =
+                            VariableSubsSlice::reserve_into_subs(env.target, elems.len());
+
+                        let it = (new_variables.indices()).zip(elems.iter_variables());
+                        for (target_index, var_index) in it {
+                            let var = env.source[var_index];
+                            let copy_var = copy_import_to_help(env, max_rank, var);
+                            env.target.variables[target_index] = copy_var;
+                        }
+
+                        let elem_index_start = env.target.tuple_elem_indices.len() as u32;
+
+                        let elem_indices =
+                            &env.source.tuple_elem_indices[elems.elem_indices().indices()];
+                        env.target
+                            .tuple_elem_indices
+                            .extend(elem_indices.iter().copied());
+
+                        TupleElems {
+                            length: elems.len() as _,
+                            elem_index_start,
+                            variables_start: new_variables.start,
+                        }
+                    };
+
+                    Tuple(tuple_elems, copy_import_to_help(env, max_rank, ext_var))
+                }
+
                 TagUnion(tags, ext_var) => {
                     let new_ext = copy_import_to_help(env, max_rank, ext_var);
 
@@ -5219,6 +5599,7 @@ fn instantiate_rigids_help(subs: &mut Subs, max_rank: Rank, initial: Variable) {
                 }
 
                 EmptyRecord => (),
+                EmptyTuple => (),
                 EmptyTagUnion => (),
 
                 Record(fields, ext_var) => {
@@ -5228,6 +5609,13 @@ fn instantiate_rigids_help(subs: &mut Subs, max_rank: Rank, initial: Variable) {
 
                     stack.push(ext_var);
                 }
+                Tuple(elems, ext_var) => {
+                    let elems = *elems;
+                    let ext_var = *ext_var;
+                    stack.extend(var_slice!(elems.variables()));
+
+                    stack.push(ext_var);
+                }
                 TagUnion(tags, ext_var) => {
                     let tags = *tags;
                     let ext_var = *ext_var;
@@ -5340,6 +5728,10 @@ pub fn get_member_lambda_sets_at_region(subs: &Subs, var: Variable, target_regio
                     stack.extend(subs.get_subs_slice(fields.variables()));
                     stack.push(*ext);
                 }
+                FlatType::Tuple(elems, ext) => {
+                    stack.extend(subs.get_subs_slice(elems.variables()));
+                    stack.push(*ext);
+                }
                 FlatType::TagUnion(tags, ext) => {
                     stack.extend(
                         subs.get_subs_slice(tags.variables())
@@ -5360,7 +5752,10 @@ pub fn get_member_lambda_sets_at_region(subs: &Subs, var: Variable, target_regio
                     );
                     stack.push(*ext);
                 }
-                FlatType::Erroneous(_) | FlatType::EmptyRecord | FlatType::EmptyTagUnion => {}
+                FlatType::Erroneous(_)
+                | FlatType::EmptyRecord
+                | FlatType::EmptyTuple
+                | FlatType::EmptyTagUnion => {}
             },
             Content::Alias(_, _, real_var, _) => {
                 stack.push(*real_var);
@@ -414,14 +414,14 @@ impl UnificationTable {
         Ok(written)
     }
 
-    pub(crate) fn deserialize(bytes: &[u8], length: usize, offset: usize) -> (Self, usize) {
+    pub(crate) fn deserialize(bytes: &[u8], length: usize, offset: usize) -> Option<(Self, usize)> {
         use crate::subs::Subs;
 
-        let (contents, offset) = Subs::deserialize_slice::<Content>(bytes, length, offset);
-        let (ranks, offset) = Subs::deserialize_slice::<Rank>(bytes, length, offset);
-        let (marks, offset) = Subs::deserialize_slice::<Mark>(bytes, length, offset);
-        let (copies, offset) = Subs::deserialize_slice::<OptVariable>(bytes, length, offset);
-        let (redirects, offset) = Subs::deserialize_slice::<OptVariable>(bytes, length, offset);
+        let (contents, offset) = Subs::deserialize_slice::<Content>(bytes, length, offset)?;
+        let (ranks, offset) = Subs::deserialize_slice::<Rank>(bytes, length, offset)?;
+        let (marks, offset) = Subs::deserialize_slice::<Mark>(bytes, length, offset)?;
+        let (copies, offset) = Subs::deserialize_slice::<OptVariable>(bytes, length, offset)?;
+        let (redirects, offset) = Subs::deserialize_slice::<OptVariable>(bytes, length, offset)?;
 
         let mut metadata = Vec::with_capacity(ranks.len());
 
@@ -448,6 +448,6 @@ impl UnificationTable {
             metadata,
         };
 
-        (this, offset)
+        Some((this, offset))
     }
 }
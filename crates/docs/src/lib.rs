@@ -10,7 +10,7 @@ use roc_highlight::highlight_parser::{highlight_defs, highlight_expr};
 use roc_load::docs::DocEntry::DocDef;
 use roc_load::docs::{DocEntry, TypeAnnotation};
 use roc_load::docs::{ModuleDocumentation, RecordField};
-use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
+use roc_load::{ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, MonoPasses, Threading};
 use roc_module::symbol::{IdentIdsByModule, Interns, ModuleId};
 use roc_parse::ident::{parse_ident, Ident};
 use roc_parse::state::State;
@@ -437,6 +437,8 @@ pub fn load_modules_for_files(filenames: Vec<PathBuf>) -> Vec<LoadedModule> {
             render: roc_reporting::report::RenderTarget::ColorTerminal,
             threading: Threading::AllAvailable,
             exec_mode: ExecutionMode::Check,
+            type_cache_dir: None,
+            mono_passes: MonoPasses::default(),
         };
         match roc_load::load_and_typecheck(&arena, filename, Default::default(), load_config) {
             Ok(loaded) => modules.push(loaded),
@@ -603,6 +605,22 @@ fn type_annotation_to_html(indent_level: usize, buf: &mut String, type_ann: &Typ
 
             type_annotation_to_html(indent_level, buf, extension);
         }
+        TypeAnnotation::Tuple { elems, extension } => {
+            buf.push('(');
+
+            let mut peekable_elems = elems.iter().peekable();
+            while let Some(elem) = peekable_elems.next() {
+                type_annotation_to_html(indent_level, buf, elem);
+
+                if peekable_elems.peek().is_some() {
+                    buf.push_str(", ");
+                }
+            }
+
+            buf.push(')');
+
+            type_annotation_to_html(indent_level, buf, extension);
+        }
         TypeAnnotation::Function { args, output } => {
             let mut peekable_args = args.iter().peekable();
             while let Some(arg) = peekable_args.next() {
@@ -714,6 +732,9 @@ fn should_be_multiline(type_ann: &TypeAnnotation) -> bool {
 
             is_multiline
         }
+        TypeAnnotation::Tuple { elems, extension } => {
+            should_be_multiline(extension) || elems.iter().any(should_be_multiline)
+        }
         TypeAnnotation::Ability { .. } => true,
         TypeAnnotation::Wildcard => false,
         TypeAnnotation::NoTypeAnn => false,
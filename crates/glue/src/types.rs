@@ -717,6 +717,26 @@ fn add_type_help<'a>(
                 RocType::Struct { name, fields }
             })
         }
+        Content::Structure(FlatType::Tuple(elems, ext)) => {
+            let it = elems
+                .unsorted_iterator(subs, *ext)
+                .expect("something weird in content");
+
+            let name = match opt_name {
+                Some(sym) => sym.as_str(env.interns).to_string(),
+                None => env.struct_names.get_name(var),
+            };
+
+            // Tuple elements become struct fields named after their position, e.g. `f0`, `f1`.
+            add_struct(env, name, it, types, layout, |name, elems| {
+                let fields = elems
+                    .into_iter()
+                    .map(|(index, type_id)| (format!("f{}", index), type_id))
+                    .collect();
+
+                RocType::Struct { name, fields }
+            })
+        }
         Content::Structure(FlatType::TagUnion(tags, ext_var)) => {
             debug_assert!(ext_var_is_empty_tag_union(subs, *ext_var));
 
@@ -795,6 +815,7 @@ fn add_type_help<'a>(
         }
         Content::Structure(FlatType::Erroneous(_)) => todo!(),
         Content::Structure(FlatType::EmptyRecord) => types.add_anonymous(RocType::Unit, layout),
+        Content::Structure(FlatType::EmptyTuple) => types.add_anonymous(RocType::Unit, layout),
         Content::Structure(FlatType::EmptyTagUnion) => {
             types.add_anonymous(RocType::EmptyTagUnion, layout)
         }
@@ -0,0 +1,290 @@
+//! Type checks open documents and answers questions about them.
+
+use crate::convert::{path_to_uri, LineIndex};
+use bumpalo::Bump;
+use roc_can::expr::{Declarations, Expr};
+use roc_can::pattern::Pattern;
+use roc_can::traverse::{walk_expr, walk_pattern, Visitor};
+use roc_load::{LoadedModule, LoadingProblem};
+use roc_module::symbol::Symbol;
+use roc_region::all::{LineInfo, Position, Region};
+use roc_reporting::report::{
+    can_problem, type_problem, CiWrite, RenderTarget, Report, RocDocAllocator, Severity,
+};
+use roc_types::pretty_print::{name_and_print_var, DebugPrint};
+use roc_types::subs::Variable;
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
+
+/// LSP `DiagnosticSeverity` values.
+const SEVERITY_ERROR: u32 = 1;
+const SEVERITY_WARNING: u32 = 2;
+
+/// The result of type checking a document.
+pub struct Analysis {
+    /// `None` if the document could not be loaded at all, e.g. because it does not parse.
+    module: Option<LoadedModule>,
+    pub diagnostics: Vec<Value>,
+}
+
+impl Analysis {
+    pub fn new(path: &Path, text: &str) -> Self {
+        let arena = Bump::new();
+        let src_dir = path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+
+        let loaded = roc_load::load_and_typecheck_str(
+            &arena,
+            path.to_path_buf(),
+            text,
+            src_dir,
+            Default::default(),
+            roc_target::TargetInfo::default_x86_64(),
+            RenderTarget::Generic,
+        );
+
+        match loaded {
+            Ok(mut module) => {
+                let diagnostics = diagnostics(&mut module, path, text);
+
+                Analysis {
+                    module: Some(module),
+                    diagnostics,
+                }
+            }
+            Err(problem) => {
+                let message = match problem {
+                    LoadingProblem::FormattedReport(report) => report,
+                    LoadingProblem::FileProblem { filename, error } => {
+                        format!("Could not read {}: {:?}", filename.display(), error)
+                    }
+                    other => format!("{:?}", other),
+                };
+
+                // Loading problems don't carry a region, so point at the start of the document
+                Analysis {
+                    module: None,
+                    diagnostics: vec![diagnostic(
+                        LineIndex::new(text).range_to_json(0, 0),
+                        SEVERITY_ERROR,
+                        message,
+                    )],
+                }
+            }
+        }
+    }
+
+    /// The inferred type of the innermost expression or pattern at `position`.
+    pub fn type_at(&mut self, position: Position) -> Option<(Region, String)> {
+        let module = self.module.as_mut()?;
+        let home = module.module_id;
+        let decls = module.declarations_by_id.get(&home)?;
+
+        let mut visitor = InnermostTyped {
+            position,
+            found: None,
+        };
+        visitor.visit_decls(decls);
+        let (region, var) = visitor.found?;
+
+        let debug_print = DebugPrint {
+            print_lambda_sets: false,
+            print_only_under_alias: false,
+        };
+        let typ = name_and_print_var(
+            var,
+            module.solved.inner_mut(),
+            home,
+            &module.interns,
+            debug_print,
+        );
+
+        Some((region, typ))
+    }
+
+    /// The `Location` where the symbol at `position` is defined, if it is defined in a user module.
+    pub fn definition_at(&self, position: Position) -> Option<Value> {
+        let module = self.module.as_ref()?;
+        let home = module.module_id;
+        let decls = module.declarations_by_id.get(&home)?;
+
+        let mut visitor = InnermostSymbol {
+            position,
+            found: None,
+        };
+        visitor.visit_decls(decls);
+        let symbol = visitor.found?;
+
+        if symbol.is_builtin() {
+            return None;
+        }
+
+        let defining_module = symbol.module_id();
+        let region = definition_region(module.declarations_by_id.get(&defining_module)?, symbol)?;
+        let (path, src) = module.sources.get(&defining_module)?;
+
+        Some(json!({
+            "uri": path_to_uri(path),
+            "range": LineIndex::new(src).region_to_json(region),
+        }))
+    }
+}
+
+fn diagnostic(range: Value, severity: u32, message: String) -> Value {
+    json!({
+        "range": range,
+        "severity": severity,
+        "source": "roc",
+        "message": message,
+    })
+}
+
+fn diagnostics(module: &mut LoadedModule, path: &Path, text: &str) -> Vec<Value> {
+    let home = module.module_id;
+    let src_lines: Vec<&str> = text.split('\n').collect();
+    let lines = LineInfo::new(text);
+    let line_index = LineIndex::new(text);
+    let alloc = RocDocAllocator::new(&src_lines, home, &module.interns);
+
+    let mut diagnostics = Vec::new();
+    let mut push_report = |report: Report, region: Option<Region>| {
+        let severity = match report.severity {
+            Severity::Warning => SEVERITY_WARNING,
+            Severity::RuntimeError => SEVERITY_ERROR,
+        };
+        let range = line_index.region_to_json(region.unwrap_or_else(Region::zero));
+
+        let mut buf = String::new();
+        report
+            .doc
+            .1
+            .render_raw(70, &mut CiWrite::new(&mut buf))
+            .expect("reports are valid utf-8");
+
+        let message = format!("{}\n\n{}", report.title, buf.trim_end());
+        diagnostics.push(diagnostic(range, severity, message));
+    };
+
+    for problem in module.can_problems.remove(&home).unwrap_or_default() {
+        let region = problem.region();
+        push_report(
+            can_problem(&alloc, &lines, path.to_path_buf(), problem),
+            region,
+        );
+    }
+
+    for problem in module.type_problems.remove(&home).unwrap_or_default() {
+        let region = problem.region();
+        if let Some(report) = type_problem(&alloc, &lines, path.to_path_buf(), problem) {
+            push_report(report, region);
+        }
+    }
+
+    diagnostics
+}
+
+fn region_contains(region: Region, position: Position) -> bool {
+    region.start() <= position && position <= region.end()
+}
+
+/// Finds the innermost expression or pattern with a type that surrounds a position.
+struct InnermostTyped {
+    position: Position,
+    found: Option<(Region, Variable)>,
+}
+
+impl Visitor for InnermostTyped {
+    fn should_visit(&mut self, region: Region) -> bool {
+        region_contains(region, self.position)
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        if region_contains(region, self.position) {
+            self.found = Some((region, var));
+            walk_expr(self, expr, var);
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern, region: Region, opt_var: Option<Variable>) {
+        if region_contains(region, self.position) {
+            if let Some(var) = opt_var {
+                self.found = Some((region, var));
+            }
+            walk_pattern(self, pattern);
+        }
+    }
+}
+
+/// Finds the symbol referenced or introduced at a position.
+struct InnermostSymbol {
+    position: Position,
+    found: Option<Symbol>,
+}
+
+impl Visitor for InnermostSymbol {
+    fn should_visit(&mut self, region: Region) -> bool {
+        region_contains(region, self.position)
+    }
+
+    fn visit_expr(&mut self, expr: &Expr, region: Region, var: Variable) {
+        if region_contains(region, self.position) {
+            match expr {
+                Expr::Var(symbol) | Expr::AbilityMember(symbol, _, _) => {
+                    self.found = Some(*symbol);
+                }
+                _ => walk_expr(self, expr, var),
+            }
+        }
+    }
+
+    fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+        if region_contains(region, self.position) {
+            match pattern {
+                Pattern::Identifier(symbol)
+                | Pattern::AbilityMemberSpecialization { ident: symbol, .. } => {
+                    self.found = Some(*symbol);
+                }
+                _ => walk_pattern(self, pattern),
+            }
+        }
+    }
+}
+
+/// Finds the region of the pattern that introduces `symbol` in a module.
+fn definition_region(decls: &Declarations, symbol: Symbol) -> Option<Region> {
+    let mut visitor = DefinitionOf {
+        symbol,
+        found: None,
+    };
+    visitor.visit_decls(decls);
+    return visitor.found;
+
+    struct DefinitionOf {
+        symbol: Symbol,
+        found: Option<Region>,
+    }
+
+    impl Visitor for DefinitionOf {
+        fn should_visit(&mut self, _region: Region) -> bool {
+            self.found.is_none()
+        }
+
+        fn visit_pattern(&mut self, pattern: &Pattern, region: Region, _opt_var: Option<Variable>) {
+            match pattern {
+                Pattern::Identifier(symbol)
+                | Pattern::AbilityMemberSpecialization { ident: symbol, .. }
+                    if *symbol == self.symbol =>
+                {
+                    self.found = Some(region);
+                }
+                _ => {
+                    if self.should_visit(region) {
+                        walk_pattern(self, pattern);
+                    }
+                }
+            }
+        }
+    }
+}
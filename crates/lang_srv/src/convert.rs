@@ -0,0 +1,121 @@
+//! Conversions between compiler regions, which count bytes, and LSP positions, which count
+//! lines and UTF-16 code units.
+
+use roc_region::all::{Position, Region};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+/// Maps byte offsets in a document to LSP positions and back.
+pub struct LineIndex<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(offset, _)| offset + 1));
+
+        Self { text, line_starts }
+    }
+
+    /// Returns the zero-based line and UTF-16 column of a byte offset.
+    pub fn line_col(&self, offset: usize) -> (u32, u32) {
+        let offset = offset.min(self.text.len());
+        let line = match self.line_starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next_line) => next_line - 1,
+        };
+        let line_text = &self.text[self.line_starts[line]..offset];
+        let column = line_text.chars().map(char::len_utf16).sum::<usize>();
+
+        (line as u32, column as u32)
+    }
+
+    /// Returns the byte offset of a zero-based line and UTF-16 column, clamped to the document.
+    pub fn offset(&self, line: u32, column: u32) -> usize {
+        let line_start = match self.line_starts.get(line as usize) {
+            Some(start) => *start,
+            None => return self.text.len(),
+        };
+        let line_end = self
+            .line_starts
+            .get(line as usize + 1)
+            .map(|next_start| next_start - 1)
+            .unwrap_or_else(|| self.text.len());
+
+        let mut utf16_column = 0;
+        for (byte_offset, c) in self.text[line_start..line_end].char_indices() {
+            if utf16_column >= column as usize {
+                return line_start + byte_offset;
+            }
+            utf16_column += c.len_utf16();
+        }
+
+        line_end
+    }
+
+    pub fn position_to_json(&self, offset: usize) -> Value {
+        let (line, character) = self.line_col(offset);
+
+        json!({ "line": line, "character": character })
+    }
+
+    pub fn range_to_json(&self, start: usize, end: usize) -> Value {
+        json!({
+            "start": self.position_to_json(start),
+            "end": self.position_to_json(end),
+        })
+    }
+
+    pub fn region_to_json(&self, region: Region) -> Value {
+        self.range_to_json(region.start().offset as usize, region.end().offset as usize)
+    }
+
+    /// Reads an LSP `Position` into a compiler [Position].
+    pub fn position_from_json(&self, position: &Value) -> Option<Position> {
+        let line = position.get("line")?.as_u64()?;
+        let character = position.get("character")?.as_u64()?;
+
+        Some(Position::new(
+            self.offset(line as u32, character as u32) as u32
+        ))
+    }
+}
+
+/// Turns a `file://` URI into a path. Other schemes are not supported.
+pub fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?;
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    Some(PathBuf::from(String::from_utf8(decoded).ok()?))
+}
+
+/// Turns an absolute path into a `file://` URI.
+pub fn path_to_uri(path: &std::path::Path) -> String {
+    let mut uri = String::from("file://");
+
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    uri
+}
@@ -0,0 +1,43 @@
+//! Formats whole documents with the same formatter as `roc format`.
+
+use crate::convert::LineIndex;
+use bumpalo::Bump;
+use roc_fmt::def::fmt_defs;
+use roc_fmt::module::fmt_module;
+use roc_fmt::{Ast, Buf};
+use roc_parse::module::{self, module_defs};
+use roc_parse::parser::{Parser, SyntaxError};
+use roc_parse::state::State;
+use serde_json::{json, Value};
+
+/// The edits that format a document: none if it is already formatted, otherwise a single edit
+/// that replaces the whole document. Returns `None` if the document does not parse.
+pub fn format_edits(text: &str) -> Option<Value> {
+    let arena = Bump::new();
+    let ast = parse_all(&arena, text).ok()?;
+
+    let mut buf = Buf::new_in(&arena);
+    fmt_module(&mut buf, &ast.module);
+    fmt_defs(&mut buf, &ast.defs, 0);
+    buf.fmt_end_of_file();
+
+    let formatted = buf.as_str();
+
+    if formatted == text {
+        return Some(json!([]));
+    }
+
+    Some(json!([{
+        "range": LineIndex::new(text).range_to_json(0, text.len()),
+        "newText": formatted,
+    }]))
+}
+
+fn parse_all<'a>(arena: &'a Bump, src: &'a str) -> Result<Ast<'a>, SyntaxError<'a>> {
+    let (module, state) = module::parse_header(arena, State::new(src.as_bytes()))
+        .map_err(|e| SyntaxError::Header(e.problem))?;
+
+    let (_, defs, _) = module_defs().parse(arena, state).map_err(|(_, e, _)| e)?;
+
+    Ok(Ast { module, defs })
+}
@@ -0,0 +1,22 @@
+//! A language server for Roc, started with `roc lsp`.
+//!
+//! The server speaks the Language Server Protocol over stdin and stdout. Each time a document is
+//! opened or changed it is type checked from scratch, and its problems are published as
+//! diagnostics. The server also answers hover (the inferred type under the cursor),
+//! go-to-definition, whole-document formatting and semantic token requests.
+#![warn(clippy::dbg_macro)]
+
+mod analysis;
+mod convert;
+mod format;
+mod rpc;
+mod server;
+mod tokens;
+
+use std::io::{self, BufRead, Write};
+
+/// Serves LSP messages read from `input`, writing responses and notifications to `output`,
+/// until the client sends `exit` or closes the input.
+pub fn run(input: impl BufRead, output: impl Write) -> io::Result<()> {
+    server::Server::new(output).run(input)
+}
@@ -0,0 +1,169 @@
+//! JSON-RPC messages, framed with `Content-Length` headers as the LSP base protocol requires.
+
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+
+/// The error codes defined by JSON-RPC and LSP that the server can respond with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCode {
+    ParseError = -32700,
+    InvalidRequest = -32600,
+    MethodNotFound = -32601,
+    InvalidParams = -32602,
+    ServerNotInitialized = -32002,
+}
+
+/// An incoming message from the client.
+#[derive(Debug)]
+pub enum Message {
+    Request {
+        id: Value,
+        method: String,
+        params: Value,
+    },
+    Notification {
+        method: String,
+        params: Value,
+    },
+    /// A response to a request the server sent. We never send requests, so these are ignored.
+    Response,
+}
+
+impl Message {
+    fn from_json(value: Value) -> Result<Self, ErrorCode> {
+        let mut value = match value {
+            Value::Object(object) => object,
+            _ => return Err(ErrorCode::InvalidRequest),
+        };
+
+        let params = value.remove("params").unwrap_or(Value::Null);
+        let id = value.remove("id");
+
+        match (value.remove("method"), id) {
+            (Some(Value::String(method)), Some(id)) => Ok(Message::Request { id, method, params }),
+            (Some(Value::String(method)), None) => Ok(Message::Notification { method, params }),
+            (None, Some(_)) => Ok(Message::Response),
+            _ => Err(ErrorCode::InvalidRequest),
+        }
+    }
+}
+
+/// Reads the next message, or returns `None` once the input is closed.
+///
+/// A message without a `Content-Length` header is logged and skipped. Its content cannot be
+/// delimited, so reading resumes at the next `Content-Length` header.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Result<Message, ErrorCode>>> {
+    let content_length = loop {
+        let (content_length, headers) = match read_headers(reader)? {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+
+        match content_length {
+            Some(length) => break length,
+            None if headers.is_empty() => {}
+            None => eprintln!(
+                "roc_lang_srv: skipping a message without a Content-Length header: {:?}",
+                headers
+            ),
+        }
+    };
+
+    let mut content = vec![0; content_length];
+    reader.read_exact(&mut content)?;
+
+    let message = match serde_json::from_slice(&content) {
+        Ok(value) => Message::from_json(value),
+        Err(_) => Err(ErrorCode::ParseError),
+    };
+
+    Ok(Some(message))
+}
+
+/// Reads a block of headers up to the blank line that ends it. Returns the `Content-Length`, if
+/// there was a valid one, and every header line, or `None` if the input is closed.
+fn read_headers(reader: &mut impl BufRead) -> io::Result<Option<(Option<usize>, Vec<String>)>> {
+    let mut content_length = None;
+    let mut headers = Vec::new();
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let mut header = line.trim_end_matches(&['\r', '\n'][..]);
+
+        if header.is_empty() {
+            return Ok(Some((content_length, headers)));
+        }
+
+        // The content of a skipped message runs into the headers of the next one.
+        if let Some(start) = header.find("Content-Length:") {
+            header = &header[start..];
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse::<usize>().ok();
+            }
+        }
+
+        headers.push(header.to_string());
+    }
+}
+
+fn write_message(writer: &mut impl Write, message: Value) -> io::Result<()> {
+    let content = message.to_string();
+
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    writer.flush()
+}
+
+pub fn write_response(writer: &mut impl Write, id: Value, result: Value) -> io::Result<()> {
+    write_message(
+        writer,
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "result": result,
+        }),
+    )
+}
+
+pub fn write_error(
+    writer: &mut impl Write,
+    id: Value,
+    code: ErrorCode,
+    message: &str,
+) -> io::Result<()> {
+    write_message(
+        writer,
+        json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": {
+                "code": code as i32,
+                "message": message,
+            },
+        }),
+    )
+}
+
+pub fn write_notification(writer: &mut impl Write, method: &str, params: Value) -> io::Result<()> {
+    write_message(
+        writer,
+        json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+        }),
+    )
+}
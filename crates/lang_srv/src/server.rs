@@ -0,0 +1,237 @@
+//! Dispatches LSP requests and notifications, and keeps track of open documents.
+
+use crate::analysis::Analysis;
+use crate::convert::{uri_to_path, LineIndex};
+use crate::format::format_edits;
+use crate::rpc::{self, ErrorCode, Message};
+use crate::tokens::{semantic_tokens, TOKEN_TYPES};
+use roc_collections::MutMap;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+/// LSP `TextDocumentSyncKind.Full`: clients send the whole document on every change.
+const TEXT_DOCUMENT_SYNC_FULL: u32 = 1;
+
+struct Document {
+    text: String,
+    analysis: Analysis,
+}
+
+impl Document {
+    fn new(path: PathBuf, text: String) -> Self {
+        let analysis = Analysis::new(&path, &text);
+
+        Self { text, analysis }
+    }
+}
+
+/// The outcome of handling a request.
+enum Reply {
+    Result(Value),
+    Error(ErrorCode, &'static str),
+}
+
+pub struct Server<W: Write> {
+    output: W,
+    documents: MutMap<String, Document>,
+    initialized: bool,
+    shutdown_requested: bool,
+}
+
+impl<W: Write> Server<W> {
+    pub fn new(output: W) -> Self {
+        Self {
+            output,
+            documents: MutMap::default(),
+            initialized: false,
+            shutdown_requested: false,
+        }
+    }
+
+    /// Handles messages until the client sends `exit` or closes the input.
+    pub fn run(&mut self, mut input: impl BufRead) -> io::Result<()> {
+        while let Some(message) = rpc::read_message(&mut input)? {
+            match message {
+                Ok(Message::Request { id, method, params }) => {
+                    match self.handle_request(&method, params) {
+                        Reply::Result(result) => rpc::write_response(&mut self.output, id, result)?,
+                        Reply::Error(code, message) => {
+                            rpc::write_error(&mut self.output, id, code, message)?
+                        }
+                    }
+                }
+                Ok(Message::Notification { method, params }) => {
+                    if method == "exit" {
+                        return Ok(());
+                    }
+
+                    self.handle_notification(&method, params)?;
+                }
+                Ok(Message::Response) => {}
+                Err(code) => {
+                    rpc::write_error(&mut self.output, Value::Null, code, "invalid message")?
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn handle_request(&mut self, method: &str, params: Value) -> Reply {
+        if method == "initialize" {
+            self.initialized = true;
+
+            return Reply::Result(json!({
+                "capabilities": {
+                    "textDocumentSync": TEXT_DOCUMENT_SYNC_FULL,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "documentFormattingProvider": true,
+                    "semanticTokensProvider": {
+                        "legend": {
+                            "tokenTypes": TOKEN_TYPES,
+                            "tokenModifiers": [],
+                        },
+                        "full": true,
+                    },
+                },
+                "serverInfo": {
+                    "name": "roc_lang_srv",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            }));
+        }
+
+        if !self.initialized {
+            return Reply::Error(
+                ErrorCode::ServerNotInitialized,
+                "the server has not been initialized",
+            );
+        }
+
+        if self.shutdown_requested {
+            return Reply::Error(ErrorCode::InvalidRequest, "the server is shutting down");
+        }
+
+        let result = match method {
+            "shutdown" => {
+                self.shutdown_requested = true;
+
+                Some(Value::Null)
+            }
+            "textDocument/hover" => self.hover(&params),
+            "textDocument/definition" => self.definition(&params),
+            "textDocument/formatting" => self.formatting(&params),
+            "textDocument/semanticTokens/full" => self.semantic_tokens(&params),
+            _ => return Reply::Error(ErrorCode::MethodNotFound, "unsupported method"),
+        };
+
+        // Requests about positions with nothing to report, or documents that aren't open,
+        // succeed with a null result.
+        Reply::Result(result.unwrap_or(Value::Null))
+    }
+
+    fn handle_notification(&mut self, method: &str, params: Value) -> io::Result<()> {
+        match method {
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+
+                if let (Some(uri), Some(text)) =
+                    (document["uri"].as_str(), document["text"].as_str())
+                {
+                    self.update(uri, text.to_string())?;
+                }
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str();
+
+                // With full sync, the last change holds the whole new document
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+
+                if let (Some(uri), Some(text)) = (uri, text) {
+                    self.update(uri, text.to_string())?;
+                }
+            }
+            "textDocument/didClose" => {
+                if let Some(uri) = params["textDocument"]["uri"].as_str() {
+                    self.documents.remove(uri);
+                    self.publish_diagnostics(uri, Vec::new())?;
+                }
+            }
+            _ => {
+                // `initialized`, `$/cancelRequest` and friends need no action
+            }
+        }
+
+        Ok(())
+    }
+
+    fn update(&mut self, uri: &str, text: String) -> io::Result<()> {
+        let path = match uri_to_path(uri) {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+
+        let document = Document::new(path, text);
+        let diagnostics = document.analysis.diagnostics.clone();
+        self.documents.insert(uri.to_string(), document);
+
+        self.publish_diagnostics(uri, diagnostics)
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str, diagnostics: Vec<Value>) -> io::Result<()> {
+        rpc::write_notification(
+            &mut self.output,
+            "textDocument/publishDiagnostics",
+            json!({
+                "uri": uri,
+                "diagnostics": diagnostics,
+            }),
+        )
+    }
+
+    fn document(&mut self, params: &Value) -> Option<&mut Document> {
+        let uri = params["textDocument"]["uri"].as_str()?;
+
+        self.documents.get_mut(uri)
+    }
+
+    fn hover(&mut self, params: &Value) -> Option<Value> {
+        let document = self.document(params)?;
+        let line_index = LineIndex::new(&document.text);
+        let position = line_index.position_from_json(&params["position"])?;
+
+        let (region, typ) = document.analysis.type_at(position)?;
+
+        Some(json!({
+            "contents": {
+                "kind": "markdown",
+                "value": format!("```roc\n{}\n```", typ),
+            },
+            "range": line_index.region_to_json(region),
+        }))
+    }
+
+    fn definition(&mut self, params: &Value) -> Option<Value> {
+        let document = self.document(params)?;
+        let position = LineIndex::new(&document.text).position_from_json(&params["position"])?;
+
+        document.analysis.definition_at(position)
+    }
+
+    fn formatting(&mut self, params: &Value) -> Option<Value> {
+        let document = self.document(params)?;
+
+        format_edits(&document.text)
+    }
+
+    fn semantic_tokens(&mut self, params: &Value) -> Option<Value> {
+        let document = self.document(params)?;
+
+        Some(semantic_tokens(&document.text))
+    }
+}
@@ -0,0 +1,87 @@
+//! Semantic tokens, so editors can highlight Roc without a grammar of their own.
+
+use crate::convert::LineIndex;
+use roc_highlight::tokenizer::{full_tokenize, Token};
+use serde_json::{json, Value};
+
+/// The token types the server reports, in the order of the legend sent during initialization.
+pub const TOKEN_TYPES: &[&str] = &[
+    "keyword", "variable", "type", "string", "number", "operator",
+];
+
+const KEYWORD: u32 = 0;
+const VARIABLE: u32 = 1;
+const TYPE: u32 = 2;
+const STRING: u32 = 3;
+const NUMBER: u32 = 4;
+const OPERATOR: u32 = 5;
+
+fn token_type(token: Token) -> Option<u32> {
+    use Token::*;
+
+    let token_type = match token {
+        LowercaseIdent => VARIABLE,
+        UppercaseIdent => TYPE,
+
+        KeywordIf | KeywordThen | KeywordElse | KeywordWhen | KeywordAs | KeywordIs
+        | KeywordExpect | KeywordApp | KeywordInterface | KeywordPackages | KeywordImports
+        | KeywordProvides | KeywordTo | KeywordExposes | KeywordEffects | KeywordPlatform
+        | KeywordRequires => KEYWORD,
+
+        OpPlus | OpMinus | OpSlash | OpPercent | OpCaret | OpGreaterThan | OpLessThan
+        | OpAssignment | OpPizza | OpEquals | OpNotEquals | OpGreaterThanOrEq | OpLessThanOrEq
+        | OpAnd | OpOr | OpDoubleSlash | OpBackpassing | LambdaStart | Arrow | FatArrow => OPERATOR,
+
+        String => STRING,
+        Number | NumberBase => NUMBER,
+
+        _ => return None,
+    };
+
+    Some(token_type)
+}
+
+/// The `data` of a `SemanticTokens` response: five integers per token, with each token's line
+/// and start column relative to the previous token.
+pub fn semantic_tokens(text: &str) -> Value {
+    let line_index = LineIndex::new(text);
+    let table = full_tokenize(text);
+
+    let mut data = Vec::with_capacity(table.tokens.len() * 5);
+    let (mut prev_line, mut prev_start) = (0, 0);
+
+    for ((token, offset), length) in table
+        .tokens
+        .iter()
+        .zip(table.offsets.iter())
+        .zip(table.lengths.iter())
+    {
+        let token_type = match token_type(*token) {
+            Some(token_type) => token_type,
+            None => continue,
+        };
+
+        let (line, start) = line_index.line_col(*offset);
+        let (end_line, end) = line_index.line_col(offset + length);
+
+        // Tokens may not span lines, e.g. multiline strings; highlight their first line only
+        let length = if end_line == line {
+            end - start
+        } else {
+            let (_, line_end) = line_index.line_col(line_index.offset(line + 1, 0) - 1);
+            line_end - start
+        };
+
+        let delta_start = if line == prev_line {
+            start - prev_start
+        } else {
+            start
+        };
+
+        data.extend([line - prev_line, delta_start, length, token_type, 0]);
+        prev_line = line;
+        prev_start = start;
+    }
+
+    json!({ "data": data })
+}
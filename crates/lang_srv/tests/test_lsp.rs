@@ -0,0 +1,478 @@
+#[macro_use]
+extern crate indoc;
+#[macro_use]
+extern crate pretty_assertions;
+
+#[cfg(test)]
+mod test_lsp {
+    use serde_json::{json, Value};
+    use std::io::{BufRead, Cursor, Read};
+
+    const URI: &str = "file:///roc-lsp-test/Test.roc";
+
+    /// Frames messages the way an LSP client would.
+    fn frame(messages: &[Value]) -> Vec<u8> {
+        let mut input = Vec::new();
+
+        for message in messages {
+            let content = message.to_string();
+            input.extend(format!("Content-Length: {}\r\n\r\n", content.len()).bytes());
+            input.extend(content.bytes());
+        }
+
+        input
+    }
+
+    /// Reads back every message the server wrote.
+    fn unframe(output: Vec<u8>) -> Vec<Value> {
+        let mut reader = Cursor::new(output);
+        let mut messages = Vec::new();
+        let mut line = String::new();
+
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).unwrap() == 0 {
+                return messages;
+            }
+
+            let length: usize = line
+                .trim_end()
+                .strip_prefix("Content-Length: ")
+                .expect("every message starts with a Content-Length header")
+                .parse()
+                .unwrap();
+
+            line.clear();
+            reader.read_line(&mut line).unwrap();
+            assert_eq!(line, "\r\n");
+
+            let mut content = vec![0; length];
+            reader.read_exact(&mut content).unwrap();
+            messages.push(serde_json::from_slice(&content).unwrap());
+        }
+    }
+
+    /// Opens a document containing `src`, sends the given requests about it, and returns the
+    /// diagnostics published for it along with the result of each request.
+    fn run_session(src: &str, requests: &[(&str, Value)]) -> (Value, Vec<Value>) {
+        run_session_at(URI, src, requests)
+    }
+
+    /// Like `run_session`, for a document at `uri`.
+    fn run_session_at(uri: &str, src: &str, requests: &[(&str, Value)]) -> (Value, Vec<Value>) {
+        let mut messages = vec![
+            json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/didOpen",
+                "params": {
+                    "textDocument": {
+                        "uri": uri,
+                        "languageId": "roc",
+                        "version": 1,
+                        "text": src,
+                    },
+                },
+            }),
+        ];
+
+        for (i, (method, params)) in requests.iter().enumerate() {
+            let mut params = params.clone();
+            params["textDocument"] = json!({ "uri": uri });
+
+            messages.push(json!({
+                "jsonrpc": "2.0",
+                "id": i + 1,
+                "method": method,
+                "params": params,
+            }));
+        }
+
+        messages.push(json!({ "jsonrpc": "2.0", "id": requests.len() + 1, "method": "shutdown" }));
+        messages.push(json!({ "jsonrpc": "2.0", "method": "exit" }));
+
+        let mut output = Vec::new();
+        roc_lang_srv::run(Cursor::new(frame(&messages)), &mut output).unwrap();
+
+        let mut diagnostics = Value::Null;
+        let mut results = Vec::new();
+
+        for message in unframe(output) {
+            if message["method"] == "textDocument/publishDiagnostics" {
+                assert_eq!(message["params"]["uri"], uri);
+                diagnostics = message["params"]["diagnostics"].clone();
+            } else {
+                let id = message["id"].as_u64().unwrap() as usize;
+                if id >= 1 && id <= requests.len() {
+                    results.push(message["result"].clone());
+                }
+            }
+        }
+
+        (diagnostics, results)
+    }
+
+    fn position(line: u32, character: u32) -> Value {
+        json!({ "position": { "line": line, "character": character } })
+    }
+
+    const MODULE: &str = indoc!(
+        r#"
+        interface Test
+            exposes [main]
+            imports []
+
+        double = \n -> n * 2
+
+        main = double 21
+        "#
+    );
+
+    #[test]
+    fn initialize_advertises_capabilities() {
+        let messages = frame(&[
+            json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "exit" }),
+        ]);
+
+        let mut output = Vec::new();
+        roc_lang_srv::run(Cursor::new(messages), &mut output).unwrap();
+
+        let responses = unframe(output);
+        let capabilities = &responses[0]["result"]["capabilities"];
+
+        assert_eq!(capabilities["textDocumentSync"], 1);
+        assert_eq!(capabilities["hoverProvider"], true);
+        assert_eq!(capabilities["definitionProvider"], true);
+        assert_eq!(capabilities["documentFormattingProvider"], true);
+        assert_eq!(capabilities["semanticTokensProvider"]["full"], true);
+    }
+
+    #[test]
+    fn requests_before_initialize_are_rejected() {
+        let messages = frame(&[
+            json!({ "jsonrpc": "2.0", "id": 7, "method": "textDocument/hover", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "exit" }),
+        ]);
+
+        let mut output = Vec::new();
+        roc_lang_srv::run(Cursor::new(messages), &mut output).unwrap();
+
+        let responses = unframe(output);
+        assert_eq!(responses[0]["id"], 7);
+        assert_eq!(responses[0]["error"]["code"], -32002);
+    }
+
+    #[test]
+    fn message_without_content_length_is_skipped() {
+        let mut messages =
+            b"Content-Type: application/vscode-jsonrpc; charset=utf-8\r\n\r\n".to_vec();
+        messages.extend(
+            json!({ "jsonrpc": "2.0", "id": 9, "method": "initialize", "params": {} })
+                .to_string()
+                .bytes(),
+        );
+        messages.extend(frame(&[
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "exit" }),
+        ]));
+
+        let mut output = Vec::new();
+        roc_lang_srv::run(Cursor::new(messages), &mut output).unwrap();
+
+        let responses = unframe(output);
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0]["id"], 1);
+    }
+
+    #[test]
+    fn no_diagnostics_for_valid_module() {
+        let (diagnostics, _) = run_session(MODULE, &[]);
+
+        assert_eq!(diagnostics, json!([]));
+    }
+
+    #[test]
+    fn type_mismatch_diagnostic() {
+        let src = indoc!(
+            r#"
+            interface Test
+                exposes [main]
+                imports []
+
+            main : Str
+            main = 1 + 1
+            "#
+        );
+
+        let (diagnostics, _) = run_session(src, &[]);
+        let diagnostics = diagnostics.as_array().unwrap();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["severity"], 1);
+        assert_eq!(diagnostics[0]["source"], "roc");
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 5);
+        assert!(diagnostics[0]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("TYPE MISMATCH"));
+    }
+
+    #[test]
+    fn unused_definition_is_a_warning() {
+        let src = indoc!(
+            r#"
+            interface Test
+                exposes [main]
+                imports []
+
+            main =
+                unused = 1
+
+                2
+            "#
+        );
+
+        let (diagnostics, _) = run_session(src, &[]);
+        let diagnostics = diagnostics.as_array().unwrap();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["severity"], 2);
+        assert_eq!(
+            diagnostics[0]["range"],
+            json!({
+                "start": { "line": 5, "character": 4 },
+                "end": { "line": 5, "character": 10 },
+            })
+        );
+    }
+
+    #[test]
+    fn did_change_updates_diagnostics() {
+        let did_open = json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": {
+                "textDocument": { "uri": URI, "languageId": "roc", "version": 1, "text": MODULE },
+            },
+        });
+        let did_change = |version: u32, text: &str| {
+            json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/didChange",
+                "params": {
+                    "textDocument": { "uri": URI, "version": version },
+                    "contentChanges": [{ "text": text }],
+                },
+            })
+        };
+        let broken = MODULE.replace("double 21", "double \"21\"");
+
+        let messages = frame(&[
+            json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {} }),
+            did_open,
+            did_change(2, &broken),
+            did_change(3, MODULE),
+            json!({ "jsonrpc": "2.0", "method": "exit" }),
+        ]);
+
+        let mut output = Vec::new();
+        roc_lang_srv::run(Cursor::new(messages), &mut output).unwrap();
+
+        let published: Vec<Value> = unframe(output)
+            .into_iter()
+            .filter(|message| message["method"] == "textDocument/publishDiagnostics")
+            .map(|message| message["params"]["diagnostics"].clone())
+            .collect();
+
+        // One set of diagnostics for opening the document, and one for each change
+        assert_eq!(published.len(), 3);
+        assert_eq!(published[0], json!([]));
+        assert_eq!(published[2], json!([]));
+
+        let diagnostics = published[1].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["severity"], 1);
+        assert_eq!(diagnostics[0]["range"]["start"]["line"], 6);
+        assert!(diagnostics[0]["message"]
+            .as_str()
+            .unwrap()
+            .starts_with("TYPE MISMATCH"));
+    }
+
+    #[test]
+    fn parse_error_diagnostic() {
+        let (diagnostics, _) = run_session("interface Test exposes", &[]);
+        let diagnostics = diagnostics.as_array().unwrap();
+
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["severity"], 1);
+    }
+
+    #[test]
+    fn hover_shows_type() {
+        let (_, results) = run_session(MODULE, &[("textDocument/hover", position(4, 2))]);
+
+        assert_eq!(
+            results[0],
+            json!({
+                "contents": {
+                    "kind": "markdown",
+                    "value": "```roc\nNum a -> Num a\n```",
+                },
+                "range": {
+                    "start": { "line": 4, "character": 0 },
+                    "end": { "line": 4, "character": 6 },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn hover_on_nothing() {
+        let (_, results) = run_session(MODULE, &[("textDocument/hover", position(3, 0))]);
+
+        assert_eq!(results[0], Value::Null);
+    }
+
+    #[test]
+    fn definition_of_top_level_value() {
+        let (_, results) = run_session(MODULE, &[("textDocument/definition", position(6, 9))]);
+
+        assert_eq!(
+            results[0],
+            json!({
+                "uri": URI,
+                "range": {
+                    "start": { "line": 4, "character": 0 },
+                    "end": { "line": 4, "character": 6 },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn definition_of_argument() {
+        let (_, results) = run_session(MODULE, &[("textDocument/definition", position(4, 15))]);
+
+        assert_eq!(
+            results[0],
+            json!({
+                "uri": URI,
+                "range": {
+                    "start": { "line": 4, "character": 10 },
+                    "end": { "line": 4, "character": 11 },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn definition_in_imported_module() {
+        // Imported modules are loaded from the document's directory, so they need to be on disk
+        let dir = std::env::temp_dir().join(format!("roc-lsp-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dep_path = dir.join("Dep.roc");
+        std::fs::write(
+            &dep_path,
+            indoc!(
+                r#"
+                interface Dep
+                    exposes [double]
+                    imports []
+
+                double = \n -> n * 2
+                "#
+            ),
+        )
+        .unwrap();
+
+        let src = indoc!(
+            r#"
+            interface Test
+                exposes [main]
+                imports [Dep]
+
+            main = Dep.double 21
+            "#
+        );
+        let uri = format!("file://{}", dir.join("Test.roc").display());
+        let (diagnostics, results) =
+            run_session_at(&uri, src, &[("textDocument/definition", position(4, 12))]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(diagnostics, json!([]));
+        assert_eq!(
+            results[0],
+            json!({
+                "uri": format!("file://{}", dep_path.display()),
+                "range": {
+                    "start": { "line": 4, "character": 0 },
+                    "end": { "line": 4, "character": 6 },
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn formatting_replaces_document() {
+        let header = indoc!(
+            r#"
+            interface Test
+                exposes [main]
+                imports []
+
+            "#
+        );
+        let src = format!("{}main=1\n", header);
+
+        let (_, results) = run_session(&src, &[("textDocument/formatting", json!({}))]);
+
+        assert_eq!(
+            results[0],
+            json!([{
+                "range": {
+                    "start": { "line": 0, "character": 0 },
+                    "end": { "line": 5, "character": 0 },
+                },
+                "newText": format!("{}main = 1\n", header),
+            }])
+        );
+    }
+
+    #[test]
+    fn formatting_formatted_document() {
+        let (_, results) = run_session(MODULE, &[("textDocument/formatting", json!({}))]);
+
+        assert_eq!(results[0], json!([]));
+    }
+
+    #[test]
+    fn semantic_tokens() {
+        let (_, results) = run_session(
+            "interface Test exposes [main] imports []\n\nmain = 1\n",
+            &[("textDocument/semanticTokens/full", json!({}))],
+        );
+
+        let data: Vec<u64> = results[0]["data"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|n| n.as_u64().unwrap())
+            .collect();
+
+        let tokens: Vec<&[u64]> = data.chunks(5).collect();
+
+        // `interface`
+        assert_eq!(tokens[0], [0, 0, 9, 0, 0]);
+        // `Test`
+        assert_eq!(tokens[1], [0, 10, 4, 2, 0]);
+        // `main = 1`
+        assert_eq!(
+            tokens[tokens.len() - 3..].to_vec(),
+            [[2, 0, 4, 1, 0], [0, 5, 1, 5, 0], [0, 2, 1, 4, 0]]
+        );
+    }
+}
@@ -10,13 +10,16 @@ use roc_module::ident::TagName;
 use roc_module::symbol::{Interns, ModuleId, Symbol};
 use roc_mono::ir::ProcLayout;
 use roc_mono::layout::{
-    union_sorted_tags_help, Builtin, Layout, LayoutCache, UnionLayout, UnionVariant, WrappedVariant,
+    cmp_fields, union_sorted_tags_help, Builtin, Layout, LayoutCache, UnionLayout, UnionVariant,
+    WrappedVariant,
 };
 use roc_parse::ast::{AssignedField, Collection, Expr, StrLiteral};
 use roc_region::all::{Loc, Region};
 use roc_std::RocDec;
 use roc_target::TargetInfo;
-use roc_types::subs::{Content, FlatType, GetSubsSlice, RecordFields, Subs, UnionTags, Variable};
+use roc_types::subs::{
+    Content, FlatType, GetSubsSlice, RecordFields, Subs, TupleElems, UnionTags, Variable,
+};
 
 use crate::{ReplApp, ReplAppMemory};
 
@@ -360,6 +363,12 @@ fn jit_to_ast_help<'a, A: ReplApp<'a>>(
                 Content::Structure(FlatType::EmptyRecord) => {
                     Ok(struct_to_ast(env, mem, addr, RecordFields::empty()))
                 }
+                Content::Structure(FlatType::Tuple(elems, _)) => {
+                    Ok(tuple_to_ast(env, mem, addr, *elems))
+                }
+                Content::Structure(FlatType::EmptyTuple) => {
+                    Ok(tuple_to_ast(env, mem, addr, TupleElems::empty()))
+                }
                 Content::Structure(FlatType::TagUnion(tags, _)) => {
                     debug_assert_eq!(tags.len(), 1);
 
@@ -567,6 +576,10 @@ fn addr_to_ast<'a, M: ReplAppMemory>(
             Content::Structure(FlatType::EmptyRecord) => {
                 struct_to_ast(env, mem, addr, RecordFields::empty())
             }
+            Content::Structure(FlatType::Tuple(elems, _)) => tuple_to_ast(env, mem, addr, *elems),
+            Content::Structure(FlatType::EmptyTuple) => {
+                tuple_to_ast(env, mem, addr, TupleElems::empty())
+            }
             other => {
                 unreachable!(
                     "Something had a Struct layout, but instead of a Record type, it had: {:?}",
@@ -1037,6 +1050,78 @@ fn struct_to_ast<'a, 'env, M: ReplAppMemory>(
     }
 }
 
+fn tuple_to_ast<'a, 'env, M: ReplAppMemory>(
+    env: &Env<'a, 'env>,
+    mem: &'a M,
+    addr: usize,
+    tuple_elems: TupleElems,
+) -> Expr<'a> {
+    let arena = env.arena;
+    let subs = env.subs;
+    let mut layout_cache = LayoutCache::new(env.target_info);
+
+    let mut elems = Vec::with_capacity_in(tuple_elems.len(), arena);
+
+    for (index, elem_var) in tuple_elems.sorted_iterator(subs, Variable::EMPTY_TUPLE) {
+        let elem_layout = layout_cache.from_var(arena, elem_var, subs).unwrap();
+
+        elems.push((index, elem_var, elem_layout));
+    }
+
+    if let [(_, elem_var, elem_layout)] = elems.as_slice() {
+        // a single-element tuple is unwrapped in its layout
+        let loc_expr = &*arena.alloc(Loc {
+            value: addr_to_ast(
+                env,
+                mem,
+                addr,
+                elem_layout,
+                WhenRecursive::Unreachable,
+                subs.get_content_without_compacting(*elem_var),
+            ),
+            region: Region::zero(),
+        });
+
+        return Expr::Tuple(Collection::with_items(arena.alloc([loc_expr])));
+    }
+
+    // The elements are laid out in memory sorted by descending alignment, and then by index, but
+    // we want to render them in the order of the type.
+    elems.sort_by(|(index1, _, layout1), (index2, _, layout2)| {
+        cmp_fields(index1, layout1, index2, layout2, env.target_info)
+    });
+
+    let mut output = Vec::with_capacity_in(elems.len(), arena);
+
+    // We'll advance this as we iterate through the elements
+    let mut elem_addr = addr;
+
+    for (index, elem_var, elem_layout) in elems {
+        let loc_expr = &*arena.alloc(Loc {
+            value: addr_to_ast(
+                env,
+                mem,
+                elem_addr,
+                &elem_layout,
+                WhenRecursive::Unreachable,
+                subs.get_content_without_compacting(elem_var),
+            ),
+            region: Region::zero(),
+        });
+
+        output.push((index, loc_expr));
+
+        // Advance the element pointer to the next element.
+        elem_addr += elem_layout.stack_size(env.target_info) as usize;
+    }
+
+    output.sort_by_key(|(index, _)| *index);
+
+    let output = Vec::from_iter_in(output.into_iter().map(|(_, loc_expr)| loc_expr), arena);
+
+    Expr::Tuple(Collection::with_items(output.into_bump_slice()))
+}
+
 fn unpack_single_element_tag_union(subs: &Subs, tags: UnionTags) -> (&TagName, &[Variable]) {
     let (tag_name_index, payload_vars_index) = tags.iter_all().next().unwrap();
 
@@ -1,5 +1,5 @@
 use bumpalo::Bump;
-use roc_load::{ExecutionMode, LoadConfig, Threading};
+use roc_load::{ExecutionMode, LoadConfig, MonoPasses, Threading};
 use roc_reporting::report::Palette;
 use std::path::PathBuf;
 
@@ -65,6 +65,8 @@ pub fn compile_to_mono<'a>(
             render: roc_reporting::report::RenderTarget::ColorTerminal,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Executable,
+            type_cache_dir: None,
+            mono_passes: MonoPasses::default(),
         },
     );
 
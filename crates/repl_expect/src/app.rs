@@ -1,3 +1,4 @@
+use bumpalo::Bump;
 use roc_parse::ast::Expr;
 use roc_repl_eval::{ReplApp, ReplAppMemory};
 use roc_std::RocStr;
@@ -72,13 +73,127 @@ impl ReplAppMemory for ExpectMemory {
     }
 }
 
-pub(crate) struct ExpectReplApp<'a> {
-    pub(crate) memory: &'a ExpectMemory,
+/// Memory that whole values can be read out of, not just numbers and strings
+pub(crate) trait ReadValue: ReplAppMemory {
+    fn read_value<T>(&self, addr: usize) -> T;
+}
+
+impl ReadValue for ExpectMemory {
+    fn read_value<T>(&self, addr: usize) -> T {
+        unsafe {
+            let ptr = self.start.add(addr);
+            let ptr: *const T = std::mem::transmute(ptr);
+            ptr.read()
+        }
+    }
+}
+
+/// The memory of the process that a `roc dev` app runs in. A `dbg`'d value is not copied out
+/// of the app; it is read through `/proc/<pid>/mem` while the app waits.
+pub(crate) struct ChildMemory<'a> {
+    pub(crate) arena: &'a Bump,
+    pub(crate) mem: std::fs::File,
+}
+
+impl<'a> ChildMemory<'a> {
+    fn read_bytes(&self, addr: usize, bytes: &mut [u8]) {
+        use std::os::unix::fs::FileExt;
+
+        if let Err(e) = self.mem.read_exact_at(bytes, addr as u64) {
+            panic!("could not read the memory of the app at {:#x}: {}", addr, e);
+        }
+    }
+}
+
+impl<'a> ReadValue for ChildMemory<'a> {
+    fn read_value<T>(&self, addr: usize) -> T {
+        let mut value = std::mem::MaybeUninit::<T>::uninit();
+
+        unsafe {
+            let bytes = std::slice::from_raw_parts_mut(
+                value.as_mut_ptr().cast::<u8>(),
+                std::mem::size_of::<T>(),
+            );
+
+            self.read_bytes(addr, bytes);
+
+            value.assume_init()
+        }
+    }
+}
+
+macro_rules! deref_child_number {
+    ($name: ident, $t: ty) => {
+        fn $name(&self, addr: usize) -> $t {
+            self.read_value(addr)
+        }
+    };
+}
+
+impl<'a> ReplAppMemory for ChildMemory<'a> {
+    fn deref_bool(&self, addr: usize) -> bool {
+        self.deref_u8(addr) != 0
+    }
+
+    deref_child_number!(deref_u8, u8);
+    deref_child_number!(deref_u16, u16);
+    deref_child_number!(deref_u32, u32);
+    deref_child_number!(deref_u64, u64);
+    deref_child_number!(deref_u128, u128);
+    deref_child_number!(deref_usize, usize);
+
+    deref_child_number!(deref_i8, i8);
+    deref_child_number!(deref_i16, i16);
+    deref_child_number!(deref_i32, i32);
+    deref_child_number!(deref_i64, i64);
+    deref_child_number!(deref_i128, i128);
+    deref_child_number!(deref_isize, isize);
+
+    deref_child_number!(deref_f32, f32);
+    deref_child_number!(deref_f64, f64);
+
+    fn deref_str(&self, addr: usize) -> &str {
+        const WIDTH: usize = 3 * std::mem::size_of::<usize>();
+
+        let mut roc_str = [0u8; WIDTH];
+        self.read_bytes(addr, &mut roc_str);
+
+        let last_byte = roc_str[WIDTH - 1] as i8;
+
+        let bytes = if last_byte < 0 {
+            let length = (last_byte as u8 & 0b0111_1111) as usize;
+
+            self.arena.alloc_slice_copy(&roc_str[..length])
+        } else {
+            let ptr = self.deref_usize(addr);
+            let length = self.deref_usize(addr + std::mem::size_of::<usize>());
+
+            let bytes = self.arena.alloc_slice_fill_copy(length, 0u8);
+            self.read_bytes(ptr, bytes);
+
+            bytes
+        };
+
+        std::str::from_utf8(bytes).unwrap()
+    }
+
+    fn deref_pointer_with_tag_id(&self, addr: usize) -> (u16, u64) {
+        let addr_with_id = self.deref_usize(addr);
+        let tag_id_mask = 0b111;
+
+        let tag_id = addr_with_id & tag_id_mask;
+        let data_addr = addr_with_id & !tag_id_mask;
+        (tag_id as _, data_addr as _)
+    }
+}
+
+pub(crate) struct ExpectReplApp<'a, M> {
+    pub(crate) memory: &'a M,
     pub(crate) offset: usize,
 }
 
-impl<'a> ReplApp<'a> for ExpectReplApp<'a> {
-    type Memory = ExpectMemory;
+impl<'a, M: ReadValue + 'a> ReplApp<'a> for ExpectReplApp<'a, M> {
+    type Memory = M;
 
     /// Run user code that returns a type with a `Builtin` layout
     /// Size of the return value is statically determined from its Rust type
@@ -89,11 +204,7 @@ impl<'a> ReplApp<'a> for ExpectReplApp<'a> {
         F: Fn(&'a Self::Memory, Return) -> Expr<'a>,
         Self::Memory: 'a,
     {
-        let result: Return = unsafe {
-            let ptr = self.memory.start.add(self.offset);
-            let ptr: *const Return = std::mem::transmute(ptr);
-            ptr.read()
-        };
+        let result: Return = self.memory.read_value(self.offset);
 
         transform(self.memory, result)
     }
@@ -14,7 +14,7 @@ use roc_types::subs::{Subs, Variable};
 mod app;
 pub mod run;
 
-use app::{ExpectMemory, ExpectReplApp};
+use app::{ChildMemory, ExpectMemory, ExpectReplApp, ReadValue};
 
 #[allow(clippy::too_many_arguments)]
 pub fn get_values<'a>(
@@ -41,38 +41,71 @@ pub fn get_values<'a>(
         let start = app.memory.deref_usize(start_offset + i * 8);
         app.offset = start;
 
-        let expr = {
-            let variable = *variable;
+        let expr = value_to_ast(target_info, arena, subs, interns, app, *variable)?;
 
-            let content = subs.get_content_without_compacting(variable);
+        result.push(expr);
+    }
 
-            let mut layout_cache = LayoutCache::new(target_info);
-            let layout = layout_cache.from_var(arena, variable, subs).unwrap();
+    Ok((app.offset, result))
+}
 
-            let proc_layout = ProcLayout {
-                arguments: &[],
-                result: layout,
-                captures_niche: CapturesNiche::no_niche(),
-            };
+/// Reads a value that is still in the memory of a running `roc dev` app. The app must not
+/// continue until this returns, because the value may change or be freed after that.
+pub fn get_value_from_process<'a>(
+    target_info: TargetInfo,
+    arena: &'a bumpalo::Bump,
+    subs: &Subs,
+    interns: &'a Interns,
+    mem: std::fs::File,
+    addr: usize,
+    variable: Variable,
+) -> Result<Expr<'a>, ToAstProblem> {
+    let memory = ChildMemory { arena, mem };
 
-            let element = jit_to_ast(
-                arena,
-                app,
-                "expect_repl_main_fn",
-                proc_layout,
-                content,
-                subs,
-                interns,
-                target_info,
-            )?;
+    let app = ExpectReplApp {
+        memory: arena.alloc(memory),
+        offset: addr,
+    };
 
-            element
-        };
+    value_to_ast(
+        target_info,
+        arena,
+        subs,
+        interns,
+        arena.alloc(app),
+        variable,
+    )
+}
 
-        result.push(expr);
-    }
+fn value_to_ast<'a, M: ReadValue + 'a>(
+    target_info: TargetInfo,
+    arena: &'a bumpalo::Bump,
+    subs: &Subs,
+    interns: &'a Interns,
+    app: &mut ExpectReplApp<'a, M>,
+    variable: Variable,
+) -> Result<Expr<'a>, ToAstProblem> {
+    let content = subs.get_content_without_compacting(variable);
+
+    let mut layout_cache = LayoutCache::new(target_info);
+    let layout = layout_cache.from_var(arena, variable, subs).unwrap();
+
+    let proc_layout = ProcLayout {
+        arguments: &[],
+        result: layout,
+        captures_niche: CapturesNiche::no_niche(),
+    };
 
-    Ok((app.offset, result))
+    jit_to_ast(
+        arena,
+        app,
+        "expect_repl_main_fn",
+        proc_layout,
+        content,
+        subs,
+        interns,
+        target_info,
+    )
 }
 
 #[cfg(test)]
@@ -109,6 +142,8 @@ mod test {
             render: RenderTarget::ColorTerminal,
             threading: Threading::Single,
             exec_mode: ExecutionMode::Test,
+            type_cache_dir: None,
+            mono_passes: roc_load::MonoPasses::for_opt_level(opt_level),
         };
         let loaded = roc_load::load_and_monomorphize_from_str(
             arena,
@@ -66,7 +66,7 @@ fn run_expect<W: std::io::Write>(
 
     let shared_memory_ptr: *const u8 = shared_ptr.cast();
 
-    if result.is_err() || sequence.count_failures() > 0 {
+    if result.is_err() || sequence.count_frames() > 0 {
         let module_id = expect.symbol.module_id();
         let data = expectations.get_mut(&module_id).unwrap();
 
@@ -76,66 +76,176 @@ fn run_expect<W: std::io::Write>(
 
         let renderer = Renderer::new(arena, interns, render_target, module_id, filename, &source);
 
+        // the frames are rendered even if the expect panicked, so that `dbg`s show up
+        let mut failed = false;
+        let mut offset = ExpectSequence::START_OFFSET;
+
+        for _ in 0..sequence.count_frames() {
+            let frame = ExpectFrame::at_offset(shared_memory_ptr, offset);
+
+            failed |= render_frame(
+                writer,
+                &renderer,
+                arena,
+                Some(expect),
+                expectations,
+                interns,
+                shared_memory_ptr,
+                &frame,
+            )?;
+
+            offset = frame.end_offset;
+        }
+
         if let Err(roc_panic_message) = result {
             renderer.render_panic(writer, &roc_panic_message, expect.region)?;
-        } else {
-            let mut offset = ExpectSequence::START_OFFSET;
-
-            for _ in 0..sequence.count_failures() {
-                offset += render_expect_failure(
-                    writer,
-                    &renderer,
-                    arena,
-                    Some(expect),
-                    expectations,
-                    interns,
-                    shared_memory_ptr,
-                    offset,
-                )?;
-            }
+
+            failed = true;
         }
 
-        writeln!(writer)?;
+        if failed {
+            writeln!(writer)?;
+        }
 
-        Ok(false)
+        Ok(!failed)
     } else {
         Ok(true)
     }
 }
 
+/// Empties the buffer that a `roc dev` app writes its frames to.
+pub fn init_expect_buffer(shared_ptr: *mut u8) {
+    ExpectSequence::new(shared_ptr);
+}
+
+/// Renders every frame that the `roc dev` app with the given `pid` wrote since the last call,
+/// then empties the buffer, which tells the app that it can continue.
+/// Returns the number of frames that were rendered.
 pub fn roc_dev_expect(
     writer: &mut impl std::io::Write,
     arena: &Bump,
     expectations: &mut VecMap<ModuleId, Expectations>,
     interns: &Interns,
     shared_ptr: *mut u8,
+    pid: u32,
 ) -> std::io::Result<usize> {
-    let frame = ExpectFrame::at_offset(shared_ptr, ExpectSequence::START_OFFSET);
-    let module_id = frame.module_id;
+    // we always run programs as the host
+    let target_info = (&target_lexicon::Triple::host()).into();
 
-    let data = expectations.get_mut(&module_id).unwrap();
-    let filename = data.path.to_owned();
-    let source = std::fs::read_to_string(&data.path).unwrap();
+    let sequence = ExpectSequence {
+        ptr: shared_ptr as *const u8,
+    };
 
-    let renderer = Renderer::new(
-        arena,
-        interns,
-        RenderTarget::ColorTerminal,
-        module_id,
-        filename,
-        &source,
-    );
+    let count = sequence.count_frames();
+    let mut offset = ExpectSequence::START_OFFSET;
 
-    render_expect_failure(
-        writer,
-        &renderer,
+    for _ in 0..count {
+        let frame = ExpectFrame::at_offset(shared_ptr, offset);
+        let module_id = frame.module_id;
+
+        let data = expectations.get_mut(&module_id).unwrap();
+        let filename = data.path.to_owned();
+        let source = std::fs::read_to_string(&data.path).unwrap();
+
+        let renderer = Renderer::new(
+            arena,
+            interns,
+            RenderTarget::ColorTerminal,
+            module_id,
+            filename,
+            &source,
+        );
+
+        // the dev backend only reports `dbg`s; the frame holds the address of the value in the app
+        let variable = match data.dbgs.get(&frame.region) {
+            Some(variable) => *variable,
+            None => panic!("region not in list of dbgs"),
+        };
+
+        let addr: usize =
+            unsafe { std::ptr::read_unaligned(shared_ptr.add(frame.start_offset) as *const usize) };
+
+        let mem = std::fs::File::open(format!("/proc/{}/mem", pid))?;
+        let subs = arena.alloc(&mut data.subs);
+
+        let expression =
+            crate::get_value_from_process(target_info, arena, subs, interns, mem, addr, variable)
+                .unwrap();
+
+        renderer.render_dbg(writer, subs, variable, &expression, frame.region)?;
+
+        offset = frame.end_offset;
+    }
+
+    sequence.acknowledge();
+
+    Ok(count)
+}
+
+/// Renders a frame that was written to the shared memory by either a failed `expect` or a `dbg`.
+/// Returns whether the frame was an expect failure.
+#[allow(clippy::too_many_arguments)]
+fn render_frame<'a>(
+    writer: &mut impl std::io::Write,
+    renderer: &Renderer,
+    arena: &'a Bump,
+    expect: Option<ToplevelExpect>,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    interns: &'a Interns,
+    start: *const u8,
+    frame: &ExpectFrame,
+) -> std::io::Result<bool> {
+    let data = expectations.get(&frame.module_id).unwrap();
+
+    if data.dbgs.contains_key(&frame.region) {
+        render_dbg(writer, renderer, arena, expectations, interns, start, frame)?;
+
+        Ok(false)
+    } else {
+        render_expect_failure(
+            writer,
+            renderer,
+            arena,
+            expect,
+            expectations,
+            interns,
+            start,
+            frame,
+        )?;
+
+        Ok(true)
+    }
+}
+
+fn render_dbg<'a>(
+    writer: &mut impl std::io::Write,
+    renderer: &Renderer,
+    arena: &'a Bump,
+    expectations: &mut VecMap<ModuleId, Expectations>,
+    interns: &'a Interns,
+    start: *const u8,
+    frame: &ExpectFrame,
+) -> std::io::Result<()> {
+    // we always run programs as the host
+    let target_info = (&target_lexicon::Triple::host()).into();
+
+    let data = expectations.get_mut(&frame.module_id).unwrap();
+
+    let variable = *data.dbgs.get(&frame.region).unwrap();
+    let subs = arena.alloc(&mut data.subs);
+
+    let (_, expressions) = crate::get_values(
+        target_info,
         arena,
-        None,
-        expectations,
+        subs,
         interns,
-        shared_ptr,
-        ExpectSequence::START_OFFSET,
+        start,
+        frame.start_offset,
+        &[variable],
     )
+    .unwrap();
+
+    renderer.render_dbg(writer, subs, variable, &expressions[0], frame.region)
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -147,12 +257,11 @@ fn render_expect_failure<'a>(
     expectations: &mut VecMap<ModuleId, Expectations>,
     interns: &'a Interns,
     start: *const u8,
-    offset: usize,
-) -> std::io::Result<usize> {
+    frame: &ExpectFrame,
+) -> std::io::Result<()> {
     // we always run programs as the host
     let target_info = (&target_lexicon::Triple::host()).into();
 
-    let frame = ExpectFrame::at_offset(start, offset);
     let module_id = frame.module_id;
 
     let failure_region = frame.region;
@@ -168,7 +277,7 @@ fn render_expect_failure<'a>(
 
     let (symbols, variables): (Vec<_>, Vec<_>) = current.iter().map(|(a, b)| (*a, *b)).unzip();
 
-    let (offset, expressions) = crate::get_values(
+    let (_, expressions) = crate::get_values(
         target_info,
         arena,
         subs,
@@ -187,9 +296,7 @@ fn render_expect_failure<'a>(
         &expressions,
         expect_region,
         failure_region,
-    )?;
-
-    Ok(offset)
+    )
 }
 
 struct ExpectSequence {
@@ -214,15 +321,29 @@ impl ExpectSequence {
         }
     }
 
-    fn count_failures(&self) -> usize {
+    fn count_frames(&self) -> usize {
         unsafe { *(self.ptr as *const usize).add(Self::COUNT_INDEX) }
     }
+
+    /// Empties the buffer. A `roc dev` app waits for the count to drop to zero after each frame,
+    /// so the offset must be reset first.
+    fn acknowledge(&self) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        unsafe {
+            let header = self.ptr as *const AtomicUsize;
+
+            (*header.add(Self::OFFSET_INDEX)).store(Self::START_OFFSET, Ordering::SeqCst);
+            (*header.add(Self::COUNT_INDEX)).store(0, Ordering::SeqCst);
+        }
+    }
 }
 
 struct ExpectFrame {
     region: Region,
     module_id: ModuleId,
     start_offset: usize,
+    end_offset: usize,
 }
 
 impl ExpectFrame {
@@ -233,13 +354,17 @@ impl ExpectFrame {
         let module_id_bytes: [u8; 4] = unsafe { *(start.add(offset + 8).cast()) };
         let module_id: ModuleId = unsafe { std::mem::transmute(module_id_bytes) };
 
-        // skip to frame, 8 bytes for region, 4 for module id
-        let start_offset = offset + 12;
+        let end_offset: usize =
+            unsafe { std::ptr::read_unaligned(start.add(offset + 12) as *const usize) };
+
+        // skip to frame, 8 bytes for region, 4 for module id, 8 for the end offset
+        let start_offset = offset + 20;
 
         Self {
             region,
             module_id,
             start_offset,
+            end_offset,
         }
     }
 }
@@ -293,6 +418,7 @@ pub fn expect_mono_module_to_dylib<'a>(
         mode,
         // important! we don't want any procedures to get the C calling convention
         exposed_to_host: MutSet::default(),
+        debug_sources: None,
     };
 
     // Add roc_alloc, roc_realloc, and roc_dealloc, since the repl has no
@@ -162,6 +162,57 @@ impl<'a> Renderer<'a> {
         write!(writer, "{}", buf)
     }
 
+    /// Renders the value that a `dbg` reported, along with its type
+    pub fn render_dbg<W>(
+        &self,
+        writer: &mut W,
+        subs: &mut Subs,
+        variable: Variable,
+        expr: &Expr<'_>,
+        dbg_region: Region,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+    {
+        use crate::error::r#type::error_type_to_doc;
+        use crate::report::Report;
+        use roc_fmt::annotation::Formattable;
+        use ven_pretty::DocAllocator;
+
+        let line_col_region = self.line_info.convert_region(dbg_region);
+
+        let (error_type, _) = subs.var_to_error_type(variable);
+
+        let mut buf = roc_fmt::Buf::new_in(self.arena);
+        expr.format(&mut buf, 0);
+
+        let doc = self.alloc.stack([
+            self.alloc.region(line_col_region),
+            self.alloc
+                .text(buf.into_bump_str())
+                .append(" : ")
+                .append(error_type_to_doc(&self.alloc, error_type)),
+        ]);
+
+        let report = Report {
+            title: "DBG".into(),
+            doc,
+            filename: self.filename.clone(),
+            severity: crate::report::Severity::Warning,
+        };
+
+        let mut buf = String::new();
+
+        report.render(
+            self.render_target,
+            &mut buf,
+            &self.alloc,
+            &crate::report::DEFAULT_PALETTE,
+        );
+
+        write!(writer, "{}", buf)
+    }
+
     pub fn render_panic<W>(
         &self,
         writer: &mut W,
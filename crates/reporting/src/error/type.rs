@@ -893,6 +893,33 @@ fn to_expr_report<'b>(
                     ]),
                 )
             }
+            Reason::CrashArg => {
+                let problem = alloc.concat([
+                    alloc.text("This value passed to "),
+                    alloc.keyword("crash"),
+                    alloc.text(" is not a string:"),
+                ]);
+
+                report_bad_type(
+                    alloc,
+                    lines,
+                    filename,
+                    &category,
+                    found,
+                    expected_type,
+                    region,
+                    Some(expr_region),
+                    problem,
+                    alloc.text("The value is"),
+                    alloc.concat([
+                        alloc.reflow("But I can only "),
+                        alloc.keyword("crash"),
+                        alloc.reflow(" with messages of type "),
+                        alloc.type_str("Str"),
+                        alloc.reflow("."),
+                    ]),
+                )
+            }
             Reason::IfBranch {
                 index,
                 total_branches,
@@ -1679,6 +1706,25 @@ fn format_category<'b>(
             ]),
             alloc.text(" is a:"),
         ),
+        Tuple => (
+            alloc.concat([this_is, alloc.text(" a tuple")]),
+            alloc.text(" of type:"),
+        ),
+        TupleAccessor(index) => (
+            alloc.concat([
+                alloc.text(format!("{}his ", t)),
+                alloc.tuple_elem(*index),
+                alloc.text(" value"),
+            ]),
+            alloc.text(" is a:"),
+        ),
+        TupleAccess(index) => (
+            alloc.concat([
+                alloc.text(format!("{}he value at ", t)),
+                alloc.tuple_elem(*index),
+            ]),
+            alloc.text(" is a:"),
+        ),
         CallResult(
             Some(_),
             CalledVia::BinOp(
@@ -1904,6 +1950,29 @@ fn to_pattern_report<'b>(
                     severity: Severity::RuntimeError,
                 }
             }
+            PReason::ListElem => {
+                let doc = alloc.stack([
+                    alloc.reflow(
+                        "This list element doesn't match the types of other elements in the pattern:",
+                    ),
+                    alloc.region(lines.convert_region(region)),
+                    pattern_type_comparison(
+                        alloc,
+                        found,
+                        expected_type,
+                        add_pattern_category(alloc, alloc.text("It matches"), &category),
+                        alloc.text("But the other elements in this list pattern match:"),
+                        vec![],
+                    ),
+                ]);
+
+                Report {
+                    filename,
+                    title: "TYPE MISMATCH".to_string(),
+                    doc,
+                    severity: Severity::RuntimeError,
+                }
+            }
             PReason::TagArg { .. } | PReason::PatternGuard => {
                 unreachable!("I didn't think this could trigger. Please tell Folkert about it!")
             }
@@ -1948,6 +2017,8 @@ fn add_pattern_category<'b>(
     let rest = match category {
         Record => alloc.reflow(" record values of type:"),
         EmptyRecord => alloc.reflow(" an empty record:"),
+        Tuple => alloc.reflow(" tuple values of type:"),
+        List => alloc.reflow(" lists of type:"),
         PatternGuard => alloc.reflow(" a pattern guard of type:"),
         PatternDefault => alloc.reflow(" an optional field of type:"),
         Set => alloc.reflow(" sets of type:"),
@@ -2295,6 +2366,19 @@ fn to_doc_help<'b>(
             )
         }
 
+        Tuple(mut elems, ext) => {
+            elems.sort_by_key(|(index, _)| *index);
+
+            report_text::tuple(
+                alloc,
+                elems
+                    .into_iter()
+                    .map(|(_, elem)| to_doc_help(ctx, alloc, Parens::Unnecessary, elem))
+                    .collect(),
+                ext_to_doc(alloc, ext),
+            )
+        }
+
         TagUnion(tags_map, ext) => {
             let mut tags = tags_map
                 .into_iter()
@@ -2594,6 +2678,31 @@ fn to_diff<'b>(
             diff_tag_union(alloc, &tags1, ext1, &tags2, ext2)
         }
 
+        (Tuple(mut elems1, ext1), Tuple(mut elems2, ext2))
+            if elems1.len() == elems2.len() =>
+        {
+            elems1.sort_by_key(|(index, _)| *index);
+            elems2.sort_by_key(|(index, _)| *index);
+
+            let elems_diff = traverse(
+                alloc,
+                Parens::Unnecessary,
+                elems1.into_iter().map(|(_, elem)| elem).collect::<Vec<_>>(),
+                elems2.into_iter().map(|(_, elem)| elem).collect::<Vec<_>>(),
+            );
+
+            let left = report_text::tuple(alloc, elems_diff.left, ext_to_doc(alloc, ext1));
+            let right = report_text::tuple(alloc, elems_diff.right, ext_to_doc(alloc, ext2));
+
+            Diff {
+                left,
+                right,
+                status: elems_diff.status,
+                left_able: elems_diff.left_able,
+                right_able: elems_diff.right_able,
+            }
+        }
+
         (RecursiveTagUnion(_rec1, _tags1, _ext1), RecursiveTagUnion(_rec2, _tags2, _ext2)) => {
             // TODO do a better job here
             let (left, left_able) = to_doc(alloc, Parens::Unnecessary, type1);
@@ -3223,6 +3332,34 @@ mod report_text {
         }
     }
 
+    pub fn tuple<'b>(
+        alloc: &'b RocDocAllocator<'b>,
+        elems: Vec<RocDocBuilder<'b>>,
+        opt_ext: Option<RocDocBuilder<'b>>,
+    ) -> RocDocBuilder<'b> {
+        let ext_doc = if let Some(t) = opt_ext {
+            t
+        } else {
+            alloc.nil()
+        };
+
+        if elems.is_empty() {
+            alloc.text("()").append(ext_doc)
+        } else {
+            let starts =
+                std::iter::once(alloc.reflow("( ")).chain(std::iter::repeat(alloc.reflow(", ")));
+
+            let elems_doc = alloc.concat(
+                elems
+                    .into_iter()
+                    .zip(starts)
+                    .map(|(elem, start)| start.append(elem)),
+            );
+
+            elems_doc.append(alloc.reflow(" )")).append(ext_doc)
+        }
+    }
+
     pub fn to_suggestion_record<'b>(
         alloc: &'b RocDocAllocator<'b>,
         f: (Lowercase, RecordField<ErrorType>),
@@ -3568,6 +3705,7 @@ fn type_problem_to_pretty<'b>(
                 RigidVar(y) | RigidAbleVar(y, _) => bad_double_rigid(x, y),
                 Function(_, _, _) => bad_rigid_var(x, alloc.reflow("a function value")),
                 Record(_, _) => bad_rigid_var(x, alloc.reflow("a record value")),
+                Tuple(_, _) => bad_rigid_var(x, alloc.reflow("a tuple value")),
                 TagUnion(_, _) | RecursiveTagUnion(_, _, _) => {
                     bad_rigid_var(x, alloc.reflow("a tag value"))
                 }
@@ -3921,7 +4059,7 @@ fn pattern_to_doc_help<'b>(
     use roc_can::exhaustive::{GUARD_CTOR, NONEXHAUSIVE_CTOR};
     use roc_exhaustive::Literal::*;
     use roc_exhaustive::Pattern::*;
-    use roc_exhaustive::RenderAs;
+    use roc_exhaustive::{ListArity, RenderAs};
 
     match pattern {
         Anything => alloc.text("_"),
@@ -3960,7 +4098,7 @@ fn pattern_to_doc_help<'b>(
                             Anything => {
                                 arg_docs.push(alloc.text(label.to_string()));
                             }
-                            Literal(_) | Ctor(_, _, _) => {
+                            Literal(_) | Ctor(_, _, _) | List(_, _) => {
                                 arg_docs.push(
                                     alloc
                                         .text(label.to_string())
@@ -3976,6 +4114,16 @@ fn pattern_to_doc_help<'b>(
                         .append(alloc.intersperse(arg_docs, alloc.reflow(", ")))
                         .append(" }")
                 }
+                RenderAs::Tuple => {
+                    let arg_docs = args
+                        .into_iter()
+                        .map(|v| pattern_to_doc_help(alloc, v, false));
+
+                    alloc
+                        .text("( ")
+                        .append(alloc.intersperse(arg_docs, alloc.reflow(", ")))
+                        .append(" )")
+                }
                 RenderAs::Tag | RenderAs::Opaque => {
                     let ctor = &union.alternatives[tag_id.0 as usize];
                     match &ctor.name {
@@ -4018,5 +4166,20 @@ fn pattern_to_doc_help<'b>(
                 }
             }
         }
+        List(arity, elems) => {
+            let mut elem_docs: Vec<_> = elems
+                .into_iter()
+                .map(|v| pattern_to_doc_help(alloc, v, false))
+                .collect();
+
+            if let ListArity::Slice(before, _) = arity {
+                elem_docs.insert(before, alloc.text(".."));
+            }
+
+            alloc
+                .text("[")
+                .append(alloc.intersperse(elem_docs, alloc.reflow(", ")))
+                .append("]")
+        }
     }
 }
@@ -13,7 +13,9 @@ mod test_reporting {
     use indoc::indoc;
     use roc_can::abilities::AbilitiesStore;
     use roc_can::expr::PendingDerives;
-    use roc_load::{self, ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, Threading};
+    use roc_load::{
+        self, ExecutionMode, LoadConfig, LoadedModule, LoadingProblem, MonoPasses, Threading,
+    };
     use roc_module::symbol::{Interns, ModuleId};
     use roc_region::all::LineInfo;
     use roc_reporting::report::{
@@ -88,6 +90,8 @@ mod test_reporting {
                 render: RenderTarget::Generic,
                 threading: Threading::Single,
                 exec_mode: ExecutionMode::Check,
+                type_cache_dir: None,
+                mono_passes: MonoPasses::default(),
             };
             let result =
                 roc_load::load_and_typecheck(arena, full_file_path, exposed_types, load_config);
@@ -791,6 +795,29 @@ mod test_reporting {
     "###
     );
 
+    test_report!(
+        crash_message_not_str,
+        indoc!(
+            r#"
+            crash 1
+            "#
+        ),
+        @r###"
+    ── TYPE MISMATCH ───────────────────────────────────────── /code/proj/Main.roc ─
+
+    This value passed to `crash` is not a string:
+
+    4│      crash 1
+                  ^
+
+    The value is a number of type:
+
+        Num a
+
+    But I can only `crash` with messages of type Str.
+    "###
+    );
+
     test_report!(
         when_if_guard,
         indoc!(
@@ -2745,6 +2772,61 @@ mod test_reporting {
     "###
     );
 
+    test_report!(
+        patterns_list_not_exhaustive,
+        indoc!(
+            r#"
+            l : List [A, B]
+            l = []
+
+            when l is
+                [A, ..] -> ""
+                [] -> ""
+            "#
+        ),
+        @r###"
+    ── UNSAFE PATTERN ──────────────────────────────────────── /code/proj/Main.roc ─
+
+    This `when` does not cover all the possibilities:
+
+    7│>      when l is
+    8│>          [A, ..] -> ""
+    9│>          [] -> ""
+
+    Other possibilities include:
+
+        [B]
+        [B, _, ..]
+
+    I would have to crash if I saw one of those! Add branches for them!
+    "###
+    );
+
+    test_report!(
+        patterns_list_redundant,
+        indoc!(
+            r#"
+            when [] is
+                [] -> ""
+                [_, ..] -> ""
+                [_] -> ""
+            "#
+        ),
+        @r###"
+    ── REDUNDANT PATTERN ───────────────────────────────────── /code/proj/Main.roc ─
+
+    The 3rd pattern is redundant:
+
+    4│       when [] is
+    5│           [] -> ""
+    6│           [_, ..] -> ""
+    7│>          [_] -> ""
+
+    Any value of this shape will be handled by a previous pattern, so this
+    one should be removed.
+    "###
+    );
+
     test_report!(
         unify_alias_other,
         indoc!(